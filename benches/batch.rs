@@ -0,0 +1,72 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The `_f32` buffer conversions of `batch` against converting one color at
+//! a time.
+//!
+//! Run with `cargo bench --bench batch` for the scalar buffer conversions,
+//! and with `--features simd` for the vector ones.
+
+extern crate color;
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use color::{Rgb, Srgb, Hsv, ToRgb, ToSrgb, ToHsv};
+use color::angle::Deg;
+use color::batch::{rgb_to_hsv_f32, hsv_to_rgb_f32, srgb_to_linear_f32, linear_to_srgb_f32};
+
+const ROUNDS: usize = 200;
+
+/// The nanoseconds per color of `f`, run over `len` colors, at best over the
+/// rounds.
+fn time<F: FnMut()>(len: usize, mut f: F) -> f64 {
+    (0..ROUNDS).map(|_| {
+        let start = Instant::now();
+        f();
+        start.elapsed().as_secs_f64() * 1e9 / len as f64
+    }).fold(f64::INFINITY, f64::min)
+}
+
+/// Prints the times of the conversions one at a time and of the buffer ones.
+fn report(name: &str, single: f64, batch: f64) {
+    println!("{:<16} single {:>6.2} ns   batch {:>6.2} ns   {:>5.1}x", name, single, batch, single / batch);
+}
+
+fn main() {
+    let srgb: Vec<Srgb<f32>> = (0..4096u32).map(|i| {
+        let i = i.wrapping_mul(2654435761);
+        Srgb::new(i as u8 as f32 / 255.0, (i >> 8) as u8 as f32 / 255.0, (i >> 16) as u8 as f32 / 255.0)
+    }).collect();
+    let rgb: Vec<Rgb<f32>> = srgb.iter().map(|c| c.to_rgb()).collect();
+    let hsv: Vec<Hsv<f32>> = rgb.iter().map(|c| c.to_hsv()).collect();
+    let mut rgb_out = vec![Rgb::new(0.0, 0.0, 0.0); srgb.len()];
+    let mut srgb_out = vec![Srgb::new(0.0, 0.0, 0.0); srgb.len()];
+    let mut hsv_out = vec![Hsv::new(Deg(0.0), 0.0, 0.0); srgb.len()];
+
+    report("rgb to hsv",
+           time(rgb.len(), || for (d, c) in hsv_out.iter_mut().zip(&rgb) { *d = black_box(c).to_hsv(); }),
+           time(rgb.len(), || rgb_to_hsv_f32(black_box(&rgb), &mut hsv_out)));
+    report("hsv to rgb",
+           time(hsv.len(), || for (d, c) in rgb_out.iter_mut().zip(&hsv) { *d = black_box(c).to_rgb(); }),
+           time(hsv.len(), || hsv_to_rgb_f32(black_box(&hsv), &mut rgb_out)));
+    report("srgb to linear",
+           time(srgb.len(), || for (d, c) in rgb_out.iter_mut().zip(&srgb) { *d = black_box(c).to_rgb(); }),
+           time(srgb.len(), || srgb_to_linear_f32(black_box(&srgb), &mut rgb_out)));
+    report("linear to srgb",
+           time(rgb.len(), || for (d, c) in srgb_out.iter_mut().zip(&rgb) { *d = black_box(c).to_srgb(); }),
+           time(rgb.len(), || linear_to_srgb_f32(black_box(&rgb), &mut srgb_out)));
+    black_box((&rgb_out, &srgb_out, &hsv_out));
+}
@@ -0,0 +1,69 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! sRGB decoding and encoding by table against the transfer function.
+//!
+//! Run with `cargo bench --bench srgb`, and with `--features srgb_lut16` for
+//! the `u16` table and the encoding search. The analytic runs convert the same values held as
+//! `f32`, which always go through the transfer function. Integer channels
+//! without a table go through it too.
+
+extern crate color;
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use color::{Rgb, Srgb, ToRgb, ToSrgb};
+
+const ROUNDS: usize = 200;
+
+/// The nanoseconds per color of `f` over `input`, at best over the rounds.
+fn time<T, F: FnMut(&T)>(input: &[T], mut f: F) -> f64 {
+    (0..ROUNDS).map(|_| {
+        let start = Instant::now();
+        for c in input {
+            f(c);
+        }
+        start.elapsed().as_secs_f64() * 1e9 / input.len() as f64
+    }).fold(f64::INFINITY, f64::min)
+}
+
+/// Prints the times of the integer channels, which may use the tables, and
+/// of the `f32` ones.
+fn report(name: &str, integer: f64, float: f64) {
+    println!("{:<16} integer {:>6.2} ns   f32 {:>6.2} ns   {:>5.1}x", name, integer, float, float / integer);
+}
+
+fn main() {
+    let u8s: Vec<Srgb<u8>> = (0..4096u32).map(|i| {
+        let i = i.wrapping_mul(2654435761);
+        Srgb::new(i as u8, (i >> 8) as u8, (i >> 16) as u8)
+    }).collect();
+    let f32s: Vec<Srgb<f32>> = u8s.iter().map(|c| c.to_srgb()).collect();
+    report("decode u8",
+           time(&u8s, |c| { black_box(black_box(c).to_rgb::<f32>()); }),
+           time(&f32s, |c| { black_box(black_box(c).to_rgb::<f32>()); }));
+
+    let u16s: Vec<Srgb<u16>> = u8s.iter().map(|c| Srgb::new(c.r as u16 * 251, c.g as u16 * 257, c.b as u16 * 253)).collect();
+    let f32s: Vec<Srgb<f32>> = u16s.iter().map(|c| c.to_srgb()).collect();
+    report("decode u16",
+           time(&u16s, |c| { black_box(black_box(c).to_rgb::<f32>()); }),
+           time(&f32s, |c| { black_box(black_box(c).to_rgb::<f32>()); }));
+
+    let linear: Vec<Rgb<f32>> = f32s.iter().map(|c| c.to_rgb()).collect();
+    report("encode to u8",
+           time(&linear, |c| { black_box(black_box(c).to_srgb::<u8>()); }),
+           time(&linear, |c| { black_box(black_box(c).to_srgb::<f32>().to_srgb::<u8>()); }));
+}
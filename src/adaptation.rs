@@ -0,0 +1,257 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Chromatic adaptation: expressing a color seen under one illuminant as the
+//! corresponding color under another.
+//!
+//! All transforms are von Kries-style: XYZ is taken into a cone-like
+//! response space, each response is scaled by the ratio of the two whites,
+//! and the result is taken back to XYZ. The methods differ only in the
+//! response space. Adaptation is always complete.
+//!
+//! See http://www.brucelindbloom.com/index.html?Eqn_ChromAdapt.html
+//!
+//! White balancing is adaptation to D65, the white of sRGB, from the
+//! illuminant a photo was taken under. The illuminant can be given as a
+//! standard white point, a correlated color temperature and tint, or the
+//! color of something known to be neutral.
+
+use {Channel, FloatChannel};
+use {Rgb, ToRgb, Xyz, ToXyz, WhitePoint, Chromaticity, ColorError};
+use matrix::{self, Matrix3};
+
+const BRADFORD: Matrix3 = [[ 0.8951,  0.2664, -0.1614],
+                           [-0.7502,  1.7135,  0.0367],
+                           [ 0.0389, -0.0685,  1.0296]];
+const VON_KRIES: Matrix3 = [[ 0.40024, 0.70760, -0.08081],
+                            [-0.22630, 1.16532,  0.04570],
+                            [ 0.0,     0.0,      0.91822]];
+const CAT02: Matrix3 = [[ 0.7328,  0.4296, -0.1624],
+                        [-0.7036,  1.6975,  0.0061],
+                        [ 0.0030,  0.0136,  0.9834]];
+const CAT16: Matrix3 = [[ 0.401288, 0.650173, -0.051461],
+                        [-0.250268, 1.204414,  0.045854],
+                        [-0.002079, 0.048952,  0.953127]];
+const IDENTITY: Matrix3 = [[1.0, 0.0, 0.0],
+                           [0.0, 1.0, 0.0],
+                           [0.0, 0.0, 1.0]];
+
+const BRADFORD_INVERSE: Matrix3 = invert(BRADFORD);
+const VON_KRIES_INVERSE: Matrix3 = invert(VON_KRIES);
+const CAT02_INVERSE: Matrix3 = invert(CAT02);
+const CAT16_INVERSE: Matrix3 = invert(CAT16);
+
+/// Inverts a cone response matrix when the crate is compiled.
+const fn invert(m: Matrix3) -> Matrix3 {
+    match matrix::inverse(&m) {
+        Some(i) => i,
+        None => panic!("singular cone response matrix"),
+    }
+}
+
+/// The cone response space a chromatic adaptation scales in.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Adaptation {
+    /// Bradford, as used by ICC profiles and CSS.
+    Bradford,
+    /// Von Kries, with the Hunt-Pointer-Estevez cone fundamentals.
+    VonKries,
+    /// The transform of CIECAM02.
+    Cat02,
+    /// The transform of CAM16.
+    Cat16,
+    /// Scales XYZ directly. Simple, but the worst of the lot.
+    XyzScaling,
+}
+
+impl Adaptation {
+    /// The matrix taking XYZ to the cone response space.
+    pub fn cone_matrix(self) -> [[f64; 3]; 3] {
+        match self {
+            Adaptation::Bradford => BRADFORD,
+            Adaptation::VonKries => VON_KRIES,
+            Adaptation::Cat02 => CAT02,
+            Adaptation::Cat16 => CAT16,
+            Adaptation::XyzScaling => IDENTITY,
+        }
+    }
+
+    /// The inverse of `cone_matrix`.
+    fn cone_inverse(self) -> Matrix3 {
+        match self {
+            Adaptation::Bradford => BRADFORD_INVERSE,
+            Adaptation::VonKries => VON_KRIES_INVERSE,
+            Adaptation::Cat02 => CAT02_INVERSE,
+            Adaptation::Cat16 => CAT16_INVERSE,
+            Adaptation::XyzScaling => IDENTITY,
+        }
+    }
+
+    /// The matrix adapting XYZ values from `from` to `to`. Fails if either
+    /// white is degenerate.
+    pub fn matrix(self, from: WhitePoint, to: WhitePoint) -> Result<[[f64; 3]; 3], ColorError> {
+        from.check()?;
+        to.check()?;
+        let m = self.matrix_unchecked(from, to);
+        if m.iter().flatten().all(|v| v.is_finite()) { Ok(m) } else { Err(ColorError::Degenerate) }
+    }
+
+    /// `matrix` for the crate's own white points.
+    pub(crate) fn matrix_unchecked(self, from: WhitePoint, to: WhitePoint) -> Matrix3 {
+        let m: Matrix3 = self.cone_matrix();
+        let src = matrix::mul_vec(&m, from.to_array());
+        let dst = matrix::mul_vec(&m, to.to_array());
+        let scale = [[dst[0] / src[0], 0.0, 0.0],
+                     [0.0, dst[1] / src[1], 0.0],
+                     [0.0, 0.0, dst[2] / src[2]]];
+        matrix::mul(&self.cone_inverse(), &matrix::mul(&scale, &m))
+    }
+}
+
+/// Adapts `color` from the illuminant `from` to the illuminant `to`. Fails
+/// if either white is degenerate.
+pub fn adapt<C: ToXyz, U: FloatChannel>(color: &C, from: WhitePoint, to: WhitePoint,
+                                        method: Adaptation) -> Result<Xyz<U>, ColorError> {
+    let m = method.matrix(from, to)?;
+    Ok(Xyz::from_array(matrix::mul_vec(&m, color.to_xyz::<f64>().to_array())))
+}
+
+impl<T: FloatChannel> Xyz<T> {
+    /// Adapts the color from the illuminant `from` to the illuminant `to`.
+    /// Fails if either white is degenerate.
+    #[inline]
+    pub fn adapt(&self, from: WhitePoint, to: WhitePoint, method: Adaptation) -> Result<Xyz<T>, ColorError> {
+        adapt(self, from, to, method)
+    }
+
+    /// `adapt` for the crate's own white points.
+    pub(crate) fn adapt_unchecked(&self, from: WhitePoint, to: WhitePoint, method: Adaptation) -> Xyz<T> {
+        if from == to {
+            return *self;
+        }
+        Xyz::from_array(matrix::mul_vec(&method.matrix_unchecked(from, to), self.to_array()))
+    }
+}
+
+impl WhitePoint {
+    /// The white point a color would have if it were neutral, e.g. a gray
+    /// card sampled from a photo. `None` for black, and for colors that
+    /// would give a degenerate white.
+    pub fn from_neutral<C: ToXyz>(color: &C) -> Option<WhitePoint> {
+        let xyz = color.to_xyz::<f64>();
+        if xyz.y <= 0.0 {
+            return None;
+        }
+        WhitePoint::new(xyz.x / xyz.y, 1.0, xyz.z / xyz.y).ok()
+    }
+}
+
+impl<T: Channel> Rgb<T> {
+    /// Corrects a linear color seen under `illuminant` to how it would look
+    /// under D65. Fails for a degenerate illuminant.
+    pub fn white_balance(&self, illuminant: WhitePoint, method: Adaptation) -> Result<Rgb<T>, ColorError> {
+        Ok(adapt::<_, f64>(self, illuminant, WhitePoint::D65, method)?.to_rgb())
+    }
+
+    /// Corrects a linear color seen under a light of `kelvin` and `tint`
+    /// (Duv) to how it would look under D65, with Bradford adaptation. These
+    /// are the temperature and tint sliders of a photo editor: a higher
+    /// temperature warms the result towards amber, and a higher tint moves
+    /// it towards magenta.
+    pub fn white_balance_temperature(&self, kelvin: f64, tint: f64) -> Rgb<T> {
+        let illuminant = Chromaticity::from_temperature_tint(kelvin, tint).to_white_point();
+        self.to_xyz::<f64>().adapt_unchecked(illuminant, WhitePoint::D65, Adaptation::Bradford).to_rgb()
+    }
+
+    /// Corrects a linear color so that `neutral`, a color sampled from the
+    /// same scene that should be gray, becomes gray. Neutrals that give no
+    /// white point, such as black, leave the color unchanged.
+    pub fn white_balance_neutral<C: ToXyz>(&self, neutral: &C, method: Adaptation) -> Rgb<T> {
+        match WhitePoint::from_neutral(neutral) {
+            Some(illuminant) => self.white_balance(illuminant, method).unwrap_or(*self),
+            None => *self,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{adapt, Adaptation};
+    use {Rgb, ToRgb, Xyz, WhitePoint, Chromaticity, ColorError};
+
+    #[test]
+    fn test_bradford_matrix() {
+        let expected = [[ 1.0478112,  0.0228866, -0.0501270],
+                        [ 0.0295424,  0.9904844, -0.0170491],
+                        [-0.0092345,  0.0150436,  0.7521316]];
+        let m = Adaptation::Bradford.matrix(WhitePoint::D65, WhitePoint::D50).unwrap();
+        for i in 0..3 {
+            for j in 0..3 {
+                assert!((m[i][j] - expected[i][j]).abs() < 1e-5, "{:?}", m);
+            }
+        }
+    }
+
+    #[test]
+    fn test_adapt_white() {
+        for &method in &[Adaptation::Bradford, Adaptation::VonKries, Adaptation::Cat02,
+                         Adaptation::Cat16, Adaptation::XyzScaling] {
+            let w: Xyz<f64> = adapt(&WhitePoint::D65.to_xyz::<f64>(), WhitePoint::D65, WhitePoint::A, method).unwrap();
+            let a = WhitePoint::A;
+            assert!((w.x - a.x).abs() < 1e-9 && (w.y - a.y).abs() < 1e-9 && (w.z - a.z).abs() < 1e-9,
+                    "{:?}: {:?}", method, w);
+            let c = Xyz::<f64>::new(0.3, 0.4, 0.5);
+            let back = c.adapt(WhitePoint::D65, WhitePoint::D50, method).and_then(|c| c.adapt(WhitePoint::D50, WhitePoint::D65, method)).unwrap();
+            assert!((back.x - c.x).abs() < 1e-9 && (back.y - c.y).abs() < 1e-9 && (back.z - c.z).abs() < 1e-9);
+        }
+        let zero = WhitePoint { x: 0.0, y: 0.0, z: 0.0 };
+        assert_eq!(Adaptation::Bradford.matrix(zero, WhitePoint::D65), Err(ColorError::Degenerate));
+        assert_eq!(Xyz::<f64>::new(0.3, 0.4, 0.5).adapt(WhitePoint::D65, zero, Adaptation::Cat16), Err(ColorError::Degenerate));
+    }
+
+    #[test]
+    fn test_white_balance() {
+        // a gray card under a warm light comes out neutral, keeping its
+        // luminance
+        let card = Rgb::<f64>::new(0.5, 0.4, 0.25);
+        let balanced = card.white_balance_neutral(&card, Adaptation::Bradford);
+        assert!((balanced.r - balanced.g).abs() < 1e-9 && (balanced.g - balanced.b).abs() < 1e-9,
+                "{:?}", balanced);
+        let y = |c: Rgb<f64>| 0.2126 * c.r + 0.7152 * c.g + 0.0722 * c.b;
+        assert!((y(balanced) - y(card)).abs() < 1e-4);
+
+        let tungsten = Chromaticity::from_temperature(2856.0).to_white_point();
+        let white: Rgb<f64> = Xyz::new(tungsten.x, tungsten.y, tungsten.z).to_rgb();
+        let corrected = white.white_balance(tungsten, Adaptation::Cat16).unwrap();
+        assert!((corrected.r - 1.0).abs() < 1e-3 && (corrected.g - 1.0).abs() < 1e-3 && (corrected.b - 1.0).abs() < 1e-3,
+                "{:?}", corrected);
+        assert_eq!(WhitePoint::from_neutral(&Rgb::<f64>::new(0.0, 0.0, 0.0)), None);
+
+        // D65's own temperature and tint change nothing
+        let (t, tint) = Chromaticity::D65.temperature_tint();
+        let c = Rgb::<f64>::new(0.2, 0.5, 0.8);
+        let same = c.white_balance_temperature(t, tint);
+        assert!((same.r - c.r).abs() < 1e-3 && (same.g - c.g).abs() < 1e-3 && (same.b - c.b).abs() < 1e-3,
+                "{:?}", same);
+        // higher temperatures warm, higher tints add magenta
+        let gray = Rgb::<f64>::new(0.5, 0.5, 0.5);
+        let (cool, warm) = (gray.white_balance_temperature(4000.0, tint), gray.white_balance_temperature(9000.0, tint));
+        assert!(cool.b > cool.r && warm.r > warm.b, "{:?} {:?}", cool, warm);
+        let magenta = gray.white_balance_temperature(t, tint + 0.02);
+        assert!(magenta.g < magenta.r && magenta.g < magenta.b, "{:?}", magenta);
+        assert_eq!(Rgb::<f64>::new(0.1, 0.2, 0.3).white_balance_neutral(&Rgb::<f64>::new(0.0, 0.0, 0.0), Adaptation::Bradford),
+                   Rgb::new(0.1, 0.2, 0.3));
+    }
+}
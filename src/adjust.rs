@@ -0,0 +1,216 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Everyday lightness, saturation and hue adjustments.
+//!
+//! All adjustments are made in Oklch, so lightening keeps the hue and
+//! saturating keeps the perceived lightness. RGB results that leave the
+//! gamut are brought back by reducing chroma, as CSS does.
+
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use math::*;
+
+use angle::Deg;
+
+use {Channel, FloatChannel};
+use {AlphaColor, Rgb, Srgb, Oklch, ToOklch};
+use gamut::{map_to_srgb, GamutMapping};
+
+/// Lightness, saturation and hue adjustments.
+pub trait Adjust: Sized {
+    /// Adds `amount` to the Oklch lightness, which runs from `0` (black) to
+    /// `1` (white).
+    fn lighten(self, amount: f64) -> Self;
+
+    /// Subtracts `amount` from the Oklch lightness.
+    #[inline]
+    fn darken(self, amount: f64) -> Self {
+        self.lighten(-amount)
+    }
+
+    /// Scales chroma up by a fraction of itself: `0.5` makes the color half
+    /// as colorful again.
+    fn saturate_by(self, amount: f64) -> Self;
+
+    /// Scales chroma down by a fraction of itself: `1` removes it entirely.
+    #[inline]
+    fn desaturate_by(self, amount: f64) -> Self {
+        self.saturate_by(-amount)
+    }
+
+    /// Rotates the hue.
+    fn shift_hue(self, degrees: Deg<f64>) -> Self;
+
+    /// The gray of the same perceived lightness.
+    #[inline]
+    fn grayscale(self) -> Self {
+        self.desaturate_by(1.0)
+    }
+}
+
+fn adjust_lch<F: Fn(Oklch<f64>) -> Oklch<f64>>(lch: Oklch<f64>, f: F) -> Oklch<f64> {
+    let mut lch = f(lch);
+    lch.l = lch.l.clamp(0.0, 1.0);
+    lch.c = lch.c.max(0.0);
+    lch.h = Deg(lch.h.0.rem_euclid(360.0));
+    lch
+}
+
+fn lighten(amount: f64) -> impl Fn(Oklch<f64>) -> Oklch<f64> {
+    move |c| Oklch::new(c.l + amount, c.c, c.h)
+}
+
+fn saturate(amount: f64) -> impl Fn(Oklch<f64>) -> Oklch<f64> {
+    move |c| Oklch::new(c.l, c.c * (1.0 + amount), c.h)
+}
+
+fn shift_hue(degrees: Deg<f64>) -> impl Fn(Oklch<f64>) -> Oklch<f64> {
+    move |c| Oklch::new(c.l, c.c, Deg(c.h.0 + degrees.0))
+}
+
+impl<T: FloatChannel> Oklch<T> {
+    fn adjusted<F: Fn(Oklch<f64>) -> Oklch<f64>>(&self, f: F) -> Oklch<T> {
+        adjust_lch(self.to_oklch(), f).to_oklch()
+    }
+}
+
+impl<T: FloatChannel> Adjust for Oklch<T> {
+    #[inline]
+    fn lighten(self, amount: f64) -> Oklch<T> {
+        self.adjusted(lighten(amount))
+    }
+
+    #[inline]
+    fn saturate_by(self, amount: f64) -> Oklch<T> {
+        self.adjusted(saturate(amount))
+    }
+
+    #[inline]
+    fn shift_hue(self, degrees: Deg<f64>) -> Oklch<T> {
+        self.adjusted(shift_hue(degrees))
+    }
+}
+
+impl<T: Channel> Srgb<T> {
+    fn adjusted<F: Fn(Oklch<f64>) -> Oklch<f64>>(&self, f: F) -> Srgb<T> {
+        map_to_srgb(&adjust_lch(self.to_oklch(), f), GamutMapping::Css)
+    }
+}
+
+impl<T: Channel> Adjust for Srgb<T> {
+    #[inline]
+    fn lighten(self, amount: f64) -> Srgb<T> {
+        self.adjusted(lighten(amount))
+    }
+
+    #[inline]
+    fn saturate_by(self, amount: f64) -> Srgb<T> {
+        self.adjusted(saturate(amount))
+    }
+
+    #[inline]
+    fn shift_hue(self, degrees: Deg<f64>) -> Srgb<T> {
+        self.adjusted(shift_hue(degrees))
+    }
+}
+
+impl<T: Channel> Rgb<T> {
+    fn adjusted<F: Fn(Oklch<f64>) -> Oklch<f64>>(&self, f: F) -> Rgb<T> {
+        map_to_srgb::<_, f64>(&adjust_lch(self.to_oklch(), f), GamutMapping::Css).to_linear()
+    }
+}
+
+impl<T: Channel> Adjust for Rgb<T> {
+    /// `Rgb` is taken to be linear light, as for the other conversions.
+    #[inline]
+    fn lighten(self, amount: f64) -> Rgb<T> {
+        self.adjusted(lighten(amount))
+    }
+
+    #[inline]
+    fn saturate_by(self, amount: f64) -> Rgb<T> {
+        self.adjusted(saturate(amount))
+    }
+
+    #[inline]
+    fn shift_hue(self, degrees: Deg<f64>) -> Rgb<T> {
+        self.adjusted(shift_hue(degrees))
+    }
+}
+
+impl<T, C: Adjust> Adjust for AlphaColor<T, C> {
+    /// Adjusts the color, leaving the alpha component untouched.
+    #[inline]
+    fn lighten(self, amount: f64) -> AlphaColor<T, C> {
+        AlphaColor { c: self.c.lighten(amount), a: self.a }
+    }
+
+    #[inline]
+    fn saturate_by(self, amount: f64) -> AlphaColor<T, C> {
+        AlphaColor { c: self.c.saturate_by(amount), a: self.a }
+    }
+
+    #[inline]
+    fn shift_hue(self, degrees: Deg<f64>) -> AlphaColor<T, C> {
+        AlphaColor { c: self.c.shift_hue(degrees), a: self.a }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use angle::Deg;
+
+    use {Adjust, AlphaColor, Oklch, Rgb, Srgb, ToOklch};
+
+    #[test]
+    fn test_oklch() {
+        let c = Oklch::<f64>::new(0.5, 0.1, Deg(30.0));
+        assert!((c.lighten(0.2).l - 0.7).abs() < 1e-9);
+        assert_eq!(c.darken(0.8).l, 0.0);
+        assert!((c.saturate_by(0.5).c - 0.15).abs() < 1e-9);
+        assert!((c.desaturate_by(0.5).c - 0.05).abs() < 1e-9);
+        assert_eq!(c.grayscale().c, 0.0);
+        assert!((c.shift_hue(Deg(-60.0)).h.0 - 330.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_srgb() {
+        let c = Srgb::<f64>::new(0.2, 0.4, 0.6);
+        let lch = c.to_oklch::<f64>();
+        let lighter = c.lighten(0.1).to_oklch::<f64>();
+        assert!((lighter.l - lch.l - 0.1).abs() < 1e-3);
+        assert!((lighter.h.0 - lch.h.0).abs() < 1.0);
+        let gray = c.grayscale();
+        assert!((gray.r - gray.g).abs() < 1e-6 && (gray.g - gray.b).abs() < 1e-6);
+        assert!((gray.to_oklch::<f64>().l - lch.l).abs() < 1e-6);
+        // saturating a strong color stays in gamut
+        let red = Srgb::<f64>::new(1.0, 0.0, 0.0).saturate_by(1.0);
+        assert!(red.is_in_gamut());
+        let white = Srgb::<f64>::new(0.0, 0.0, 0.0).lighten(1.0);
+        assert!(white.r > 0.999 && white.g > 0.999 && white.b > 0.999, "{:?}", white);
+    }
+
+    #[test]
+    fn test_rgb_and_alpha() {
+        let c = Rgb::<f64>::new(0.5, 0.2, 0.1);
+        let shifted = c.shift_hue(Deg(40.0)).to_oklch::<f64>();
+        let lch = c.to_oklch::<f64>();
+        assert!((shifted.h.0 - lch.h.0 - 40.0).abs() < 1.0);
+        let a = AlphaColor::from_color(c, 0.25).darken(0.1);
+        assert_eq!(a.a, 0.25);
+        assert!((a.c.to_oklch::<f64>().l - lch.l + 0.1).abs() < 1e-3);
+    }
+}
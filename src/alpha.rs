@@ -13,17 +13,21 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::ops::{Mul, Div, Add, Sub, Index, IndexMut};
-use std::slice;
+use core::ops::{Mul, Div, Add, Sub, Neg, AddAssign, SubAssign, MulAssign, DivAssign, Index, IndexMut};
+use core::slice;
 use num::Saturating;
 use {Color, Channel, FloatChannel};
-use {Rgb, Rg, ToRgb, Hsv, Srgb, YCbCr};
+use {Rgb, Rg, ToRgb, Hsv, Hsl, Hwb, Srgb, YCbCr};
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[repr(C)]
 pub struct AlphaColor<T, C> { pub c: C, pub a: T }
 
 pub type Rgba<T> = AlphaColor<T, Rgb<T>>;
 pub type Hsva<T> = AlphaColor<T, Hsv<T>>;
+pub type Hsla<T> = AlphaColor<T, Hsl<T>>;
+pub type Hwba<T> = AlphaColor<T, Hwb<T>>;
 pub type Srgba<T> = AlphaColor<T, Srgb<T>>;
 pub type YCbCra<T> = AlphaColor<T, YCbCr<T>>;
 
@@ -46,12 +50,12 @@ impl<T: Channel, C: Color<T>> Color<T> for AlphaColor<T, C> {
         }
     }
 
-    /// Inverts the color.
+    /// Inverts the color, leaving the alpha component untouched.
     #[inline]
     fn inverse(self) -> AlphaColor<T, C> {
         AlphaColor {
             c: self.c.inverse(),
-            a: self.a.invert_channel(),
+            a: self.a,
         }
     }
     
@@ -64,11 +68,30 @@ impl<T: Channel, C: Color<T>> Color<T> for AlphaColor<T, C> {
     }
 }
 
+impl<T, C> AlphaColor<T, C> {
+    #[inline]
+    pub const fn from_color(c: C, a: T) -> AlphaColor<T, C> {
+        AlphaColor { c, a }
+    }
+}
+
+/// Builds an `Srgba<u8>` from a `0xRRGGBBAA` literal, usable in `const` and
+/// `static` items.
+#[macro_export]
+macro_rules! const_srgba {
+    ( $v: expr ) => {
+        $crate::AlphaColor {
+            c: $crate::Srgb::new((($v >> 24) & 0xFF) as u8, (($v >> 16) & 0xFF) as u8, (($v >> 8) & 0xFF) as u8),
+            a: ($v & 0xFF) as u8,
+        }
+    };
+}
+
 #[macro_export]
 macro_rules! rgba{
     ( $r: expr, $g: expr, $b: expr, $a: expr ) => ({
         use $crate::{Rgba,Rgb};
-        Rgba{ c: Rgb{ r: $r, g: $g, b: $b }, a: $a } 
+        Rgba{ c: Rgb::<_>::new($r, $g, $b), a: $a } 
     });
     ( $to_rgb: expr, $a: expr ) => ({
         use $crate::{Rgba,ToRgb};
@@ -77,6 +100,17 @@ macro_rules! rgba{
 }
 
 impl<T:Channel> Rgba<T> {
+    #[inline]
+    pub const fn new(r: T, g: T, b: T, a: T) -> Rgba<T> {
+        AlphaColor { c: Rgb::new(r, g, b), a }
+    }
+
+    /// Packs the color into a `0xRRGGBBAA` value.
+    #[inline]
+    pub fn to_u32(&self) -> u32 {
+        (self.c.to_u32() << 8) | self.a.to_channel_u8() as u32
+    }
+
     #[inline]
     pub fn rg(&self) -> Rg<T> {
         self.c.rg()
@@ -390,6 +424,14 @@ impl<T: Channel, C: ToRgb> ToRgba for AlphaColor<T,C>{
     }
 }
 
+impl ToRgba for u32 {
+    /// Converts a packed `0xRRGGBBAA` value.
+    #[inline]
+    fn to_rgba<U: Channel>(&self) -> Rgba<U>{
+        Rgba{c: (*self >> 8).to_rgb(), a: ((*self & 0xff) as u8).to_channel()}
+    }
+}
+
 impl<T: Channel> ToRgba for Rgb<T> {
     #[inline]
     fn to_rgba<U: Channel>(&self) -> Rgba<U>{
@@ -404,6 +446,20 @@ impl<T:Clone + FloatChannel> ToRgba for Hsv<T> {
     }
 }
 
+impl<T:Clone + FloatChannel> ToRgba for Hsl<T> {
+    #[inline]
+    fn to_rgba<U: Channel>(&self) -> Rgba<U>{
+        Rgba{c: self.to_rgb(), a: 1.0f32.to_channel()}
+    }
+}
+
+impl<T:Clone + FloatChannel> ToRgba for Hwb<T> {
+    #[inline]
+    fn to_rgba<U: Channel>(&self) -> Rgba<U>{
+        Rgba{c: self.to_rgb(), a: 1.0f32.to_channel()}
+    }
+}
+
 impl<T:Channel, C: Mul<Output=C>> Mul for AlphaColor<T,C> {
     type Output = AlphaColor<T,C>;
 
@@ -466,6 +522,106 @@ impl<T:Channel + Sub<T,Output=T>, C: Sub<Output=C>> Sub for AlphaColor<T,C>{
     }
 }
 
+impl<T:Channel + Neg<Output=T>, C: Neg<Output=C>> Neg for AlphaColor<T,C> {
+    type Output = AlphaColor<T,C>;
+
+    #[inline]
+    fn neg(self) -> AlphaColor<T,C> {
+        AlphaColor{ c: -self.c, a: -self.a }
+    }
+}
+
+impl<T:Channel + Add<T,Output=T>, C: Add<Output=C> + Copy> AddAssign for AlphaColor<T,C> {
+    #[inline]
+    fn add_assign(&mut self, rhs: AlphaColor<T,C>) {
+        *self = *self + rhs;
+    }
+}
+
+impl<T:Channel + Sub<T,Output=T>, C: Sub<Output=C> + Copy> SubAssign for AlphaColor<T,C> {
+    #[inline]
+    fn sub_assign(&mut self, rhs: AlphaColor<T,C>) {
+        *self = *self - rhs;
+    }
+}
+
+impl<T:Channel, C: Mul<Output=C> + Copy> MulAssign for AlphaColor<T,C> {
+    #[inline]
+    fn mul_assign(&mut self, rhs: AlphaColor<T,C>) {
+        *self = *self * rhs;
+    }
+}
+
+impl<T:Channel + Mul<T,Output=T>, C: Mul<T,Output=C> + Copy> MulAssign<T> for AlphaColor<T,C> {
+    #[inline]
+    fn mul_assign(&mut self, rhs: T) {
+        *self = *self * rhs;
+    }
+}
+
+impl<T:Channel, C: Div<Output=C> + Copy> DivAssign for AlphaColor<T,C> {
+    #[inline]
+    fn div_assign(&mut self, rhs: AlphaColor<T,C>) {
+        *self = *self / rhs;
+    }
+}
+
+impl<T:Channel + Div<T,Output=T>, C: Div<T,Output=C> + Copy> DivAssign<T> for AlphaColor<T,C> {
+    #[inline]
+    fn div_assign(&mut self, rhs: T) {
+        *self = *self / rhs;
+    }
+}
+
+impl Mul<Rgba<f32>> for f32 {
+    type Output = Rgba<f32>;
+
+    #[inline]
+    fn mul(self, rhs: Rgba<f32>) -> Rgba<f32> {
+        rhs * self
+    }
+}
+
+impl Mul<Rgba<f64>> for f64 {
+    type Output = Rgba<f64>;
+
+    #[inline]
+    fn mul(self, rhs: Rgba<f64>) -> Rgba<f64> {
+        rhs * self
+    }
+}
+
+macro_rules! int_rgba_impls {
+    ($($t:ident),*) => {
+        $(
+            impl Rgba<$t> {
+                /// Adds the color channels, wrapping around on overflow, and
+                /// leaves alpha alone.
+                #[inline]
+                pub fn wrapping_add(self, rhs: Rgba<$t>) -> Rgba<$t> {
+                    AlphaColor { c: self.c.wrapping_add(rhs.c), a: self.a }
+                }
+
+                /// Subtracts the color channels, wrapping around on
+                /// underflow, and leaves alpha alone.
+                #[inline]
+                pub fn wrapping_sub(self, rhs: Rgba<$t>) -> Rgba<$t> {
+                    AlphaColor { c: self.c.wrapping_sub(rhs.c), a: self.a }
+                }
+
+                /// Scales the color channels by `factor`, rounding to nearest
+                /// and clamping, and leaves alpha alone.
+                #[inline]
+                pub fn scale(self, factor: f32) -> Rgba<$t> {
+                    AlphaColor { c: self.c.scale(factor), a: self.a }
+                }
+            }
+        )*
+    }
+}
+
+int_rgba_impls!(u8, u16);
+
 impl<T:Channel + Saturating, C: Saturating> Saturating for AlphaColor<T,C>{
     fn saturating_add(self, v: AlphaColor<T,C>) -> AlphaColor<T,C> {
         AlphaColor{ c: self.c.saturating_add(v.c),
@@ -480,13 +636,13 @@ impl<T:Channel + Saturating, C: Saturating> Saturating for AlphaColor<T,C>{
 
 impl<T, C: AsRef<[T]>> Index<usize> for AlphaColor<T,C> {
     type Output = T;
-    fn index<'a>(&'a self, index: usize) -> &'a T {
+    fn index(&self, index: usize) -> &T {
         self.as_ref().index(index)
     }
 }
 
 impl<T, C: AsRef<[T]> + AsMut<[T]>> IndexMut<usize> for AlphaColor<T,C> {
-    fn index_mut<'a>(&'a mut self, index: usize) -> &'a mut T {
+    fn index_mut(&mut self, index: usize) -> &mut T {
         self.as_mut().index_mut(index)
     }
 }
@@ -502,3 +658,76 @@ impl<T, C: AsMut<[T]>> AsMut<[T]> for AlphaColor<T,C> {
         unsafe{ slice::from_raw_parts_mut(&mut self.c.as_mut()[0], 4) }
     }
 }
+
+impl<T, C: AsRef<[T]>> AlphaColor<T, C> {
+    /// Iterates over the channels, alpha last.
+    #[inline]
+    pub fn iter(&self) -> slice::Iter<'_, T> {
+        self.as_ref().iter()
+    }
+}
+
+impl<T, C: AsMut<[T]>> AlphaColor<T, C> {
+    #[inline]
+    pub fn iter_mut(&mut self) -> slice::IterMut<'_, T> {
+        self.as_mut().iter_mut()
+    }
+}
+
+impl<'a, T, C: AsRef<[T]>> IntoIterator for &'a AlphaColor<T, C> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+    #[inline]
+    fn into_iter(self) -> slice::Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T, C: AsMut<[T]>> IntoIterator for &'a mut AlphaColor<T, C> {
+    type Item = &'a mut T;
+    type IntoIter = slice::IterMut<'a, T>;
+    #[inline]
+    fn into_iter(self) -> slice::IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num::Saturating;
+
+    use {Rgb, Rgba, Srgb, Srgba, ToRgb, ToRgba, Color};
+
+    #[test]
+    fn test_rgba_int_ops() {
+        let a = Rgba::<u8>::new(200, 100, 10, 255);
+        assert_eq!(a.saturating_add(Rgba::new(100, 0, 0, 1)), Rgba::new(255, 100, 10, 255));
+        assert_eq!(a.wrapping_add(Rgba::new(100, 0, 0, 1)), Rgba::new(44, 100, 10, 255));
+        assert_eq!(a.saturating_sub(Rgba::new(0, 150, 0, 0)), Rgba::new(200, 0, 10, 255));
+        assert_eq!(a.wrapping_sub(Rgba::new(0, 0, 11, 9)), Rgba::new(200, 100, 255, 255));
+        assert_eq!(a.scale(0.25), Rgba::new(50, 25, 3, 255));
+    }
+
+    #[test]
+    fn test_rgba_packed() {
+        assert_eq!(0x10203040_u32.to_rgba::<u8>(), Rgba::<u8>::new(0x10, 0x20, 0x30, 0x40));
+        const TRANSLUCENT: Srgba<u8> = const_srgba!(0x10203040);
+        assert_eq!(TRANSLUCENT, Srgba { c: Srgb::new(0x10, 0x20, 0x30), a: 0x40 });
+        assert_eq!(Rgba::<u8>::new(0x10, 0x20, 0x30, 0x40).to_u32(), 0x10203040);
+        assert_eq!(0xFF0000FF_u32.to_rgba::<f32>(), Rgba::<f32>::new(1.0, 0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn test_rgba_to_rgb() {
+        assert_eq!(Rgba::<u8>::new(0x10, 0x20, 0x30, 0x40).to_rgb::<u8>(), Rgb::<u8>::new(0x10, 0x20, 0x30));
+        assert_eq!(Rgb::<u8>::new(0x10, 0x20, 0x30).to_rgba::<u8>(), Rgba::<u8>::new(0x10, 0x20, 0x30, 0xFF));
+    }
+
+    #[test]
+    fn test_rgba_color_ops() {
+        assert_eq!(Rgba::<u8>::new(0x00, 0x66, 0xFF, 0x80).inverse(), Rgba::<u8>::new(0xFF, 0x99, 0x00, 0x80));
+        assert_eq!(Rgba::<f32>::new(0.0, 0.0, 0.0, 0.0).mix(Rgba::new(1.0, 1.0, 1.0, 1.0), 0.5),
+                   Rgba::<f32>::new(0.5, 0.5, 0.5, 0.5));
+        assert_eq!(Rgba::<f32>::new(-1.0, 0.5, 2.0, 3.0).clamp_s(0.0, 1.0), Rgba::<f32>::new(0.0, 0.5, 1.0, 1.0));
+    }
+}
@@ -0,0 +1,490 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Angles in degrees and radians.
+//!
+//! This is the `angle` 0.1 crate by Arturo Castro, which the hues of this
+//! crate have always used, brought in so that it builds without `std`. The
+//! API is unchanged: `Deg` and `Rad` compare after wrapping into one turn,
+//! and the `Angle` trait converts between them.
+
+use core::f64;
+use core::ops::{Add, Mul, Div, Sub, Neg};
+use core::cmp::Ordering;
+use core::fmt;
+
+use num::{Zero, Float, Num, NumCast, FromPrimitive, ToPrimitive, zero};
+use num::cast;
+
+pub trait Angle<N> {
+    fn pi() -> Self;
+    fn two_pi() -> Self;
+    fn half_pi() -> Self;
+    fn to_rad(self) -> Rad<N>;
+    fn to_deg(self) -> Deg<N>;
+    fn wrap(self) -> Self;
+
+    fn max(self, other: Self) -> Self;
+    fn min(self, other: Self) -> Self;
+    fn value(self) -> N;
+
+    fn sin(self) -> N where N: Float;
+    fn cos(self) -> N where N: Float;
+    fn tan(self) -> N where N: Float;
+    fn sin_cos(self) -> (N, N) where N: Float;
+    fn abs(self) -> Self;
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Deg<N>(pub N);
+#[derive(Clone, Copy, Debug)]
+pub struct Rad<N>(pub N);
+
+impl<N: Num + NumCast + Clone> Angle<N> for Deg<N> {
+    #[inline]
+    fn to_rad(self) -> Rad<N> {
+        Rad(cast::<f64, N>(cast::<N, f64>(self.value()).unwrap() * f64::consts::PI / 180.0).unwrap())
+    }
+
+    #[inline]
+    fn to_deg(self) -> Deg<N> {
+        self
+    }
+
+    #[inline]
+    fn pi() -> Deg<N> {
+        Deg(cast(180.0).unwrap())
+    }
+
+    #[inline]
+    fn two_pi() -> Deg<N> {
+        Deg(cast(360.0).unwrap())
+    }
+
+    #[inline]
+    fn half_pi() -> Deg<N> {
+        Deg(cast(90.0).unwrap())
+    }
+
+    #[inline]
+    fn wrap(self) -> Deg<N> {
+        // algorithm from http://stackoverflow.com/a/5852628/599884
+        self.clone() - Deg::two_pi() * Deg(cast(cast::<N, i64>((self / Deg::two_pi()).value()).unwrap()).unwrap())
+    }
+
+    #[inline]
+    fn max(self, other: Deg<N>) -> Deg<N> {
+        Deg(cast::<f64, N>(cast::<N, f64>(self.wrap().value()).unwrap().max(cast(other.wrap().value()).unwrap())).unwrap())
+    }
+
+    #[inline]
+    fn min(self, other: Deg<N>) -> Deg<N> {
+        Deg(cast::<f64, N>(cast::<N, f64>(self.wrap().value()).unwrap().min(cast(other.wrap().value()).unwrap())).unwrap())
+    }
+
+    #[inline]
+    fn value(self) -> N {
+        self.0
+    }
+
+    #[inline]
+    fn sin(self) -> N where N: Float {
+        self.to_rad().value().sin()
+    }
+
+    #[inline]
+    fn cos(self) -> N where N: Float {
+        self.to_rad().value().cos()
+    }
+
+    #[inline]
+    fn tan(self) -> N where N: Float {
+        self.to_rad().value().tan()
+    }
+
+    #[inline]
+    fn sin_cos(self) -> (N, N) where N: Float {
+        self.to_rad().value().sin_cos()
+    }
+
+    #[inline]
+    fn abs(self) -> Deg<N> {
+        Deg(cast::<f64, N>(Float::abs(cast::<N, f64>(self.0).unwrap())).unwrap())
+    }
+}
+
+impl<N: Float> Angle<N> for Rad<N> {
+    #[inline]
+    fn to_rad(self) -> Rad<N> {
+        self
+    }
+
+    #[inline]
+    fn to_deg(self) -> Deg<N> {
+        Deg(self.0 * cast(180.0 / f64::consts::PI).unwrap())
+    }
+
+    #[inline]
+    fn pi() -> Rad<N> {
+        Rad(cast(f64::consts::PI).unwrap())
+    }
+
+    #[inline]
+    fn two_pi() -> Rad<N> {
+        Rad(cast(f64::consts::PI * 2.0).unwrap())
+    }
+
+    #[inline]
+    fn half_pi() -> Rad<N> {
+        Rad(cast(f64::consts::PI * 0.5).unwrap())
+    }
+
+    #[inline]
+    fn wrap(self) -> Rad<N> {
+        // algorithm from http://stackoverflow.com/a/5852628/599884
+        self - Rad::two_pi() * Rad((self / Rad::two_pi()).value().floor())
+    }
+
+    #[inline]
+    fn max(self, other: Rad<N>) -> Rad<N> {
+        Rad(self.wrap().value().max(other.wrap().value()))
+    }
+
+    #[inline]
+    fn min(self, other: Rad<N>) -> Rad<N> {
+        Rad(self.wrap().value().min(other.wrap().value()))
+    }
+
+    #[inline]
+    fn value(self) -> N {
+        self.0
+    }
+
+    #[inline]
+    fn sin(self) -> N {
+        self.value().sin()
+    }
+
+    #[inline]
+    fn cos(self) -> N {
+        self.value().cos()
+    }
+
+    #[inline]
+    fn tan(self) -> N {
+        self.value().tan()
+    }
+
+    #[inline]
+    fn sin_cos(self) -> (N, N) {
+        self.value().sin_cos()
+    }
+
+    #[inline]
+    fn abs(self) -> Rad<N> {
+        Rad(cast::<f64, N>(Float::abs(cast::<N, f64>(self.0).unwrap())).unwrap())
+    }
+}
+
+impl<N: Num + NumCast + Clone + PartialOrd> Zero for Deg<N> {
+    /// Returns the additive identity.
+    #[inline]
+    fn zero() -> Deg<N> {
+        Deg(zero())
+    }
+
+    #[inline]
+    fn is_zero(&self) -> bool {
+        *self == Deg::zero()
+    }
+}
+
+impl<N: Float> Zero for Rad<N> {
+    /// Returns the additive identity.
+    #[inline]
+    fn zero() -> Rad<N> {
+        Rad(zero())
+    }
+
+    #[inline]
+    fn is_zero(&self) -> bool {
+        *self == Rad::zero()
+    }
+}
+
+impl<N: Num> Add for Deg<N> {
+    type Output = Deg<N>;
+
+    #[inline]
+    fn add(self, other: Deg<N>) -> Deg<N> {
+        Deg(self.0 + other.0)
+    }
+}
+
+impl<N: Float> Add for Rad<N> {
+    type Output = Rad<N>;
+
+    #[inline]
+    fn add(self, other: Rad<N>) -> Rad<N> {
+        Rad(self.0 + other.0)
+    }
+}
+
+impl<N: Num> Sub for Deg<N> {
+    type Output = Deg<N>;
+
+    #[inline]
+    fn sub(self, other: Deg<N>) -> Deg<N> {
+        Deg(self.0 - other.0)
+    }
+}
+
+impl<N: Float> Sub for Rad<N> {
+    type Output = Rad<N>;
+
+    #[inline]
+    fn sub(self, other: Rad<N>) -> Rad<N> {
+        Rad(self.0 - other.0)
+    }
+}
+
+impl<N: Num> Mul for Deg<N> {
+    type Output = Deg<N>;
+
+    #[inline]
+    fn mul(self, other: Deg<N>) -> Deg<N> {
+        Deg(self.0 * other.0)
+    }
+}
+
+impl<N: Float> Mul for Rad<N> {
+    type Output = Rad<N>;
+
+    #[inline]
+    fn mul(self, other: Rad<N>) -> Rad<N> {
+        Rad(self.0 * other.0)
+    }
+}
+
+impl<N: Float> Mul<N> for Deg<N> {
+    type Output = Deg<N>;
+
+    #[inline]
+    fn mul(self, other: N) -> Deg<N> {
+        Deg(self.0 * other)
+    }
+}
+
+impl<N: Float> Mul<N> for Rad<N> {
+    type Output = Rad<N>;
+
+    #[inline]
+    fn mul(self, other: N) -> Rad<N> {
+        Rad(self.0 * other)
+    }
+}
+
+impl<N: Num> Div for Deg<N> {
+    type Output = Deg<N>;
+
+    #[inline]
+    fn div(self, other: Deg<N>) -> Deg<N> {
+        Deg(self.0 / other.0)
+    }
+}
+
+impl<N: Float> Div for Rad<N> {
+    type Output = Rad<N>;
+
+    #[inline]
+    fn div(self, other: Rad<N>) -> Rad<N> {
+        Rad(self.0 / other.0)
+    }
+}
+
+impl<N: Num> Div<N> for Deg<N> {
+    type Output = Deg<N>;
+
+    #[inline]
+    fn div(self, other: N) -> Deg<N> {
+        Deg(self.0 / other)
+    }
+}
+
+impl<N: Float> Div<N> for Rad<N> {
+    type Output = Rad<N>;
+
+    #[inline]
+    fn div(self, other: N) -> Rad<N> {
+        Rad(self.0 / other)
+    }
+}
+
+impl<N: Num + Neg<Output = N>> Neg for Deg<N> {
+    type Output = Deg<N>;
+
+    #[inline]
+    fn neg(self) -> Deg<N> {
+        Deg(-self.0)
+    }
+}
+
+impl<N: Float> Neg for Rad<N> {
+    type Output = Rad<N>;
+
+    #[inline]
+    fn neg(self) -> Rad<N> {
+        Rad(-self.0)
+    }
+}
+
+impl<N: Num + NumCast + Clone + PartialOrd> PartialEq for Deg<N> {
+    #[inline]
+    fn eq(&self, other: &Deg<N>) -> bool {
+        self.clone().wrap().0.eq(&other.clone().wrap().0)
+    }
+}
+
+impl<N: Float> PartialEq for Rad<N> {
+    #[inline]
+    fn eq(&self, other: &Rad<N>) -> bool {
+        self.wrap().0.eq(&other.wrap().0)
+    }
+}
+
+impl<N: Num + NumCast + Clone + PartialOrd> PartialOrd for Deg<N> {
+    #[inline]
+    fn partial_cmp(&self, other: &Deg<N>) -> Option<Ordering> {
+        self.clone().wrap().0.partial_cmp(&other.clone().wrap().0)
+    }
+}
+
+impl<N: Float> PartialOrd for Rad<N> {
+    #[inline]
+    fn partial_cmp(&self, other: &Rad<N>) -> Option<Ordering> {
+        self.wrap().0.partial_cmp(&other.wrap().0)
+    }
+}
+
+pub trait Cast<T> {
+    fn from(t: T) -> Self;
+}
+
+impl<N1: NumCast, N2: NumCast> Cast<Deg<N2>> for Deg<N1> {
+    fn from(t: Deg<N2>) -> Deg<N1> {
+        Deg(cast(t.0).unwrap())
+    }
+}
+
+impl<N1: NumCast, N2: NumCast> Cast<Rad<N2>> for Rad<N1> {
+    fn from(t: Rad<N2>) -> Rad<N1> {
+        Rad(cast(t.0).unwrap())
+    }
+}
+
+impl<N1: NumCast, N2: Float> Cast<Rad<N2>> for Deg<N1> {
+    fn from(t: Rad<N2>) -> Deg<N1> {
+        Deg(cast(t.to_deg().0).unwrap())
+    }
+}
+
+impl<N1: NumCast, N2: Num + NumCast + Clone> Cast<Deg<N2>> for Rad<N1> {
+    fn from(t: Deg<N2>) -> Rad<N1> {
+        Rad(cast(t.to_rad().0).unwrap())
+    }
+}
+
+impl<N: FromPrimitive> FromPrimitive for Deg<N> {
+    fn from_i64(n: i64) -> Option<Deg<N>> {
+        N::from_i64(n).map(Deg)
+    }
+
+    fn from_u64(n: u64) -> Option<Deg<N>> {
+        N::from_u64(n).map(Deg)
+    }
+}
+
+impl<N: FromPrimitive> FromPrimitive for Rad<N> {
+    fn from_i64(n: i64) -> Option<Rad<N>> {
+        N::from_i64(n).map(Rad)
+    }
+
+    fn from_u64(n: u64) -> Option<Rad<N>> {
+        N::from_u64(n).map(Rad)
+    }
+}
+
+impl<N: ToPrimitive> ToPrimitive for Deg<N> {
+    fn to_i64(&self) -> Option<i64> {
+        self.0.to_i64()
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        self.0.to_u64()
+    }
+}
+
+impl<N: ToPrimitive> ToPrimitive for Rad<N> {
+    fn to_i64(&self) -> Option<i64> {
+        self.0.to_i64()
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        self.0.to_u64()
+    }
+}
+
+impl<N: fmt::Display> fmt::Display for Deg<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} deg", self.0)
+    }
+}
+
+impl<N: fmt::Display> fmt::Display for Rad<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} rad", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Angle, Deg, Rad};
+
+    #[test]
+    fn test_wrap() {
+        let pi: Rad<f64> = Rad::pi();
+        assert_eq!(pi, (pi + Rad::two_pi()).wrap());
+        // in degrees, as an f32 pi is not precise enough
+        let pi: Deg<f32> = Deg::pi();
+        assert_eq!(pi, (pi + Deg::two_pi()).wrap());
+    }
+
+    #[test]
+    fn test_deg_rad() {
+        let pi: Rad<f64> = Rad::pi();
+        assert_eq!(Deg(180.0f64), pi.to_deg());
+        assert_eq!(Deg(180.0f64), Rad(Deg(180.0f64).to_rad().value()).to_deg());
+        assert_eq!(Deg(180.0f32), Rad::<f32>::pi().to_deg());
+    }
+
+    #[test]
+    fn test_cmp() {
+        let (a, b) = (Deg(20f32), Deg(370f32));
+        assert!(a > b);
+        assert_eq!(b.partial_cmp(&a), Some(::core::cmp::Ordering::Less));
+        assert_eq!(a.max(b), a);
+        assert_eq!(a.min(b), b);
+    }
+}
@@ -0,0 +1,147 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `approx` impls for the float color types. Colors compare equal when every
+//! component does; hues are compared as plain numbers, so `0` and `360` are
+//! not considered close.
+
+use approx::{AbsDiffEq, RelativeEq, UlpsEq};
+
+use {FloatChannel, AlphaColor};
+use {Rgb, Srgb, Luma, Hsv, Hsl, Hwb, Hsi, Hsp, Ryb, Cmyk, YCbCr, Xyz, XyY, Lab, Lch, Luv, Lchuv, Oklab, Oklch, Hsluv, Hpluv, Cam16, Cam16Ucs, Jzazbz, ICtCp};
+
+macro_rules! approx_impls {
+    ($($ty:ident, |$c:ident| [$($x:expr),*];)*) => {
+        $(
+            impl<T: FloatChannel + AbsDiffEq<Epsilon = T>> AbsDiffEq for $ty<T> {
+                type Epsilon = T;
+
+                #[inline]
+                fn default_epsilon() -> T {
+                    T::default_epsilon()
+                }
+
+                fn abs_diff_eq(&self, other: &Self, epsilon: T) -> bool {
+                    let (a, b) = ({ let $c = self; [$($x),*] }, { let $c = other; [$($x),*] });
+                    a.iter().zip(b.iter()).all(|(x, y)| x.abs_diff_eq(y, epsilon))
+                }
+            }
+
+            impl<T: FloatChannel + RelativeEq<Epsilon = T>> RelativeEq for $ty<T> {
+                #[inline]
+                fn default_max_relative() -> T {
+                    T::default_max_relative()
+                }
+
+                fn relative_eq(&self, other: &Self, epsilon: T, max_relative: T) -> bool {
+                    let (a, b) = ({ let $c = self; [$($x),*] }, { let $c = other; [$($x),*] });
+                    a.iter().zip(b.iter()).all(|(x, y)| x.relative_eq(y, epsilon, max_relative))
+                }
+            }
+
+            impl<T: FloatChannel + UlpsEq<Epsilon = T>> UlpsEq for $ty<T> {
+                #[inline]
+                fn default_max_ulps() -> u32 {
+                    T::default_max_ulps()
+                }
+
+                fn ulps_eq(&self, other: &Self, epsilon: T, max_ulps: u32) -> bool {
+                    let (a, b) = ({ let $c = self; [$($x),*] }, { let $c = other; [$($x),*] });
+                    a.iter().zip(b.iter()).all(|(x, y)| x.ulps_eq(y, epsilon, max_ulps))
+                }
+            }
+        )*
+    }
+}
+
+approx_impls! {
+    Rgb,   |c| [c.r, c.g, c.b];
+    Srgb,  |c| [c.r, c.g, c.b];
+    Luma,  |c| [c.l];
+    Hsv,   |c| [c.h.0, c.s, c.v];
+    Hsl,   |c| [c.h.0, c.s, c.l];
+    Hwb,   |c| [c.h.0, c.w, c.b];
+    Hsi,   |c| [c.h.0, c.s, c.i];
+    Hsp,   |c| [c.h.0, c.s, c.p];
+    Ryb,   |c| [c.r, c.y, c.b];
+    Cmyk,  |c| [c.c, c.m, c.y, c.k];
+    YCbCr, |c| [c.y, c.cb, c.cr];
+    Xyz,   |c| [c.x, c.y, c.z];
+    XyY,   |c| [c.x, c.y, c.luminance];
+    Lab,   |c| [c.l, c.a, c.b];
+    Lch,   |c| [c.l, c.c, c.h.0];
+    Luv,   |c| [c.l, c.u, c.v];
+    Lchuv, |c| [c.l, c.c, c.h.0];
+    Oklab, |c| [c.l, c.a, c.b];
+    Oklch, |c| [c.l, c.c, c.h.0];
+    Hsluv, |c| [c.h.0, c.s, c.l];
+    Hpluv, |c| [c.h.0, c.p, c.l];
+    Cam16, |c| [c.j, c.c, c.h.0, c.m, c.s, c.q];
+    Cam16Ucs, |c| [c.j, c.a, c.b];
+    Jzazbz, |c| [c.jz, c.az, c.bz];
+    ICtCp, |c| [c.i, c.ct, c.cp];
+}
+
+impl<T: FloatChannel + AbsDiffEq<Epsilon = T>, C: AbsDiffEq<Epsilon = T>> AbsDiffEq for AlphaColor<T, C> {
+    type Epsilon = T;
+
+    #[inline]
+    fn default_epsilon() -> T {
+        T::default_epsilon()
+    }
+
+    #[inline]
+    fn abs_diff_eq(&self, other: &Self, epsilon: T) -> bool {
+        self.c.abs_diff_eq(&other.c, epsilon) && self.a.abs_diff_eq(&other.a, epsilon)
+    }
+}
+
+impl<T: FloatChannel + RelativeEq<Epsilon = T>, C: RelativeEq<Epsilon = T>> RelativeEq for AlphaColor<T, C> {
+    #[inline]
+    fn default_max_relative() -> T {
+        T::default_max_relative()
+    }
+
+    #[inline]
+    fn relative_eq(&self, other: &Self, epsilon: T, max_relative: T) -> bool {
+        self.c.relative_eq(&other.c, epsilon, max_relative) && self.a.relative_eq(&other.a, epsilon, max_relative)
+    }
+}
+
+impl<T: FloatChannel + UlpsEq<Epsilon = T>, C: UlpsEq<Epsilon = T>> UlpsEq for AlphaColor<T, C> {
+    #[inline]
+    fn default_max_ulps() -> u32 {
+        T::default_max_ulps()
+    }
+
+    #[inline]
+    fn ulps_eq(&self, other: &Self, epsilon: T, max_ulps: u32) -> bool {
+        self.c.ulps_eq(&other.c, epsilon, max_ulps) && self.a.ulps_eq(&other.a, epsilon, max_ulps)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {Rgb, ToRgb, Rgba, Hsv, Srgb, Lab, ToLab};
+    use angle::*;
+
+    #[test]
+    fn test_approx() {
+        assert_relative_eq!(Hsv::<f64>::new(Deg(120.0), 1.0, 1.0).to_rgb::<f64>(), Rgb::new(0.0, 1.0, 1e-17));
+        assert_abs_diff_eq!(Srgb::<f64>::new(1.0, 1.0, 1.0).to_lab::<f64>(), Lab::new(100.0, 0.0, 0.0), epsilon = 1e-3);
+        assert_ulps_eq!(Rgba::<f32>::new(0.1 + 0.2, 0.0, 0.0, 1.0), Rgba::new(0.3, 0.0, 0.0, 1.0));
+        assert_relative_ne!(Rgb::<f32>::new(0.5, 0.5, 0.5), Rgb::new(0.5, 0.5, 0.51));
+    }
+}
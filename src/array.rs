@@ -0,0 +1,292 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Conversions between the `#[repr(C)]` color types and arrays and tuples
+//! of their channels, in field order, and indexing and iteration over the
+//! channels in the same order. The alpha versions append alpha as the last
+//! element.
+
+use core::array;
+use core::marker::PhantomData;
+use core::ops::{Index, IndexMut};
+use core::slice;
+
+use AlphaColor;
+use {Rgb, Rg, Srgb, Luma, Ryb, Cmyk, YCbCr, Xyz, XyY, Lab, Luv, Oklab, Cam16Ucs, Jzazbz, ICtCp};
+
+macro_rules! channel_type {
+    ($_field:ident) => { T }
+}
+
+macro_rules! array_impls {
+    ($($ty:ident { $($field:ident),+ $(; $phantom:ident)? } [$n:expr, $na:expr];)*) => {
+        $(
+            impl<T> From<[T; $n]> for $ty<T> {
+                #[inline]
+                fn from(v: [T; $n]) -> $ty<T> {
+                    let [$($field),+] = v;
+                    $ty { $($field,)+ $($phantom: PhantomData)? }
+                }
+            }
+
+            impl<T> From<$ty<T>> for [T; $n] {
+                #[inline]
+                fn from(c: $ty<T>) -> [T; $n] {
+                    [$(c.$field),+]
+                }
+            }
+
+            impl<T> From<($(channel_type!($field),)+)> for $ty<T> {
+                #[inline]
+                fn from(v: ($(channel_type!($field),)+)) -> $ty<T> {
+                    let ($($field,)+) = v;
+                    $ty { $($field,)+ $($phantom: PhantomData)? }
+                }
+            }
+
+            impl<T> From<$ty<T>> for ($(channel_type!($field),)+) {
+                #[inline]
+                fn from(c: $ty<T>) -> ($(channel_type!($field),)+) {
+                    ($(c.$field,)+)
+                }
+            }
+
+            // the struct is `#[repr(C)]` with only fields of type `T`, besides the
+            // zero-sized encoding marker of `Rgb`
+            impl<T> AsRef<[T]> for $ty<T> {
+                #[inline]
+                fn as_ref(&self) -> &[T] {
+                    unsafe { slice::from_raw_parts(self as *const $ty<T> as *const T, $n) }
+                }
+            }
+
+            impl<T> AsMut<[T]> for $ty<T> {
+                #[inline]
+                fn as_mut(&mut self) -> &mut [T] {
+                    unsafe { slice::from_raw_parts_mut(self as *mut $ty<T> as *mut T, $n) }
+                }
+            }
+
+            /// Panics if `index` is not less than the number of channels.
+            impl<T> Index<usize> for $ty<T> {
+                type Output = T;
+                #[inline]
+                fn index(&self, index: usize) -> &T {
+                    &self.as_ref()[index]
+                }
+            }
+
+            impl<T> IndexMut<usize> for $ty<T> {
+                #[inline]
+                fn index_mut(&mut self, index: usize) -> &mut T {
+                    &mut self.as_mut()[index]
+                }
+            }
+
+            impl<T> $ty<T> {
+                /// Iterates over the channels in field order.
+                #[inline]
+                pub fn iter(&self) -> slice::Iter<'_, T> {
+                    self.as_ref().iter()
+                }
+
+                #[inline]
+                pub fn iter_mut(&mut self) -> slice::IterMut<'_, T> {
+                    self.as_mut().iter_mut()
+                }
+
+                /// Applies `f` to each channel.
+                #[inline]
+                pub fn map<U, F: FnMut(T) -> U>(self, mut f: F) -> $ty<U> {
+                    $ty { $($field: f(self.$field),)+ $($phantom: PhantomData)? }
+                }
+
+                /// Combines the channels of two colors pairwise.
+                #[inline]
+                pub fn zip_with<U, V, F: FnMut(T, U) -> V>(self, other: $ty<U>, mut f: F) -> $ty<V> {
+                    $ty { $($field: f(self.$field, other.$field),)+ $($phantom: PhantomData)? }
+                }
+            }
+
+            impl<T> AlphaColor<T, $ty<T>> {
+                /// Applies `f` to each channel, alpha included.
+                #[inline]
+                pub fn map<U, F: FnMut(T) -> U>(self, mut f: F) -> AlphaColor<U, $ty<U>> {
+                    AlphaColor { c: $ty { $($field: f(self.c.$field),)+ $($phantom: PhantomData)? }, a: f(self.a) }
+                }
+
+                /// Combines the channels of two colors pairwise, alpha included.
+                #[inline]
+                pub fn zip_with<U, V, F: FnMut(T, U) -> V>(self, other: AlphaColor<U, $ty<U>>, mut f: F)
+                        -> AlphaColor<V, $ty<V>> {
+                    AlphaColor { c: $ty { $($field: f(self.c.$field, other.c.$field),)+ $($phantom: PhantomData)? }, a: f(self.a, other.a) }
+                }
+            }
+
+            impl<T> IntoIterator for $ty<T> {
+                type Item = T;
+                type IntoIter = array::IntoIter<T, $n>;
+                #[inline]
+                fn into_iter(self) -> array::IntoIter<T, $n> {
+                    IntoIterator::into_iter(<[T; $n]>::from(self))
+                }
+            }
+
+            impl<'a, T> IntoIterator for &'a $ty<T> {
+                type Item = &'a T;
+                type IntoIter = slice::Iter<'a, T>;
+                #[inline]
+                fn into_iter(self) -> slice::Iter<'a, T> {
+                    self.iter()
+                }
+            }
+
+            impl<'a, T> IntoIterator for &'a mut $ty<T> {
+                type Item = &'a mut T;
+                type IntoIter = slice::IterMut<'a, T>;
+                #[inline]
+                fn into_iter(self) -> slice::IterMut<'a, T> {
+                    self.iter_mut()
+                }
+            }
+
+            impl<T> IntoIterator for AlphaColor<T, $ty<T>> {
+                type Item = T;
+                type IntoIter = array::IntoIter<T, $na>;
+                #[inline]
+                fn into_iter(self) -> array::IntoIter<T, $na> {
+                    IntoIterator::into_iter(<[T; $na]>::from(self))
+                }
+            }
+
+            impl<T> From<[T; $na]> for AlphaColor<T, $ty<T>> {
+                #[inline]
+                fn from(v: [T; $na]) -> AlphaColor<T, $ty<T>> {
+                    let [$($field,)+ alpha] = v;
+                    AlphaColor { c: $ty { $($field,)+ $($phantom: PhantomData)? }, a: alpha }
+                }
+            }
+
+            impl<T> From<AlphaColor<T, $ty<T>>> for [T; $na] {
+                #[inline]
+                fn from(c: AlphaColor<T, $ty<T>>) -> [T; $na] {
+                    [$(c.c.$field,)+ c.a]
+                }
+            }
+
+            impl<T> From<($(channel_type!($field),)+ T)> for AlphaColor<T, $ty<T>> {
+                #[inline]
+                fn from(v: ($(channel_type!($field),)+ T)) -> AlphaColor<T, $ty<T>> {
+                    let ($($field,)+ alpha) = v;
+                    AlphaColor { c: $ty { $($field,)+ $($phantom: PhantomData)? }, a: alpha }
+                }
+            }
+
+            impl<T> From<AlphaColor<T, $ty<T>>> for ($(channel_type!($field),)+ T) {
+                #[inline]
+                fn from(c: AlphaColor<T, $ty<T>>) -> ($(channel_type!($field),)+ T) {
+                    ($(c.c.$field,)+ c.a)
+                }
+            }
+        )*
+    }
+}
+
+array_impls! {
+    Rgb { r, g, b; encoding } [3, 4];
+    Rg { r, g } [2, 3];
+    Srgb { r, g, b; encoding } [3, 4];
+    Luma { l } [1, 2];
+    Ryb { r, y, b } [3, 4];
+    Cmyk { c, m, y, k } [4, 5];
+    YCbCr { y, cb, cr } [3, 4];
+    Xyz { x, y, z } [3, 4];
+    XyY { x, y, luminance } [3, 4];
+    Lab { l, a, b } [3, 4];
+    Luv { l, u, v } [3, 4];
+    Oklab { l, a, b } [3, 4];
+    Cam16Ucs { j, a, b } [3, 4];
+    Jzazbz { jz, az, bz } [3, 4];
+    ICtCp { i, ct, cp } [3, 4];
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use {Rgb, Rgba, Srgb, Xyz, Lab, Luma, Cmyk};
+
+    #[test]
+    fn test_array() {
+        assert_eq!(Rgb::<u8>::from([1, 2, 3]), Rgb::new(1, 2, 3));
+        assert_eq!(<[u8; 3]>::from(Rgb::<u8>::new(1, 2, 3)), [1, 2, 3]);
+        let arr: [f32; 4] = Rgba::new(0.1f32, 0.2, 0.3, 0.4).into();
+        assert_eq!(arr, [0.1, 0.2, 0.3, 0.4]);
+        assert_eq!(Rgba::from([1u8, 2, 3, 4]), Rgba::new(1, 2, 3, 4));
+        assert_eq!(Cmyk::from([0.1, 0.2, 0.3, 0.4]), Cmyk::new(0.1, 0.2, 0.3, 0.4));
+        assert_eq!(Luma::from([7u8]), Luma::new(7));
+    }
+
+    #[test]
+    fn test_tuple() {
+        assert_eq!(Srgb::from((1u8, 2, 3)), Srgb::new(1, 2, 3));
+        assert_eq!(<(f64, f64, f64)>::from(Lab::new(50.0, 10.0, -10.0)), (50.0, 10.0, -10.0));
+        assert_eq!(Rgba::from((1u8, 2, 3, 4)), Rgba::new(1, 2, 3, 4));
+        let (r, g, b, a) = Rgba::new(1u8, 2, 3, 4).into();
+        assert_eq!((r, g, b, a), (1, 2, 3, 4));
+    }
+
+    #[test]
+    fn test_slices() {
+        let mut lab = Lab::new(50.0, 10.0, -10.0);
+        assert_eq!(lab.as_ref(), &[50.0, 10.0, -10.0]);
+        lab.as_mut()[1] = 20.0;
+        assert_eq!(lab.a, 20.0);
+        let mut rgba = Rgba::new(1u8, 2, 3, 4);
+        rgba.as_mut()[3] = 5;
+        assert_eq!(rgba.as_ref(), &[1, 2, 3, 5]);
+    }
+
+    #[test]
+    fn test_index_iter() {
+        let mut xyz = Xyz::new(0.1, 0.2, 0.3);
+        assert_eq!(xyz[2], 0.3);
+        xyz[0] = 0.5;
+        assert_eq!(xyz.x, 0.5);
+        for c in xyz.iter_mut() {
+            *c *= 2.0;
+        }
+        assert_eq!(xyz.iter().cloned().collect::<Vec<_>>(), vec![1.0, 0.4, 0.6]);
+        assert_eq!(xyz.into_iter().sum::<f64>(), 2.0);
+
+        let mut rgba = Rgba::new(1u8, 2, 3, 4);
+        assert_eq!(rgba[3], 4);
+        for c in &mut rgba {
+            *c += 1;
+        }
+        assert_eq!((&rgba).into_iter().max(), Some(&5));
+        assert_eq!(rgba.into_iter().collect::<Vec<_>>(), vec![2, 3, 4, 5]);
+        assert_eq!(Rgb::<u8>::new(1, 2, 3).into_iter().rev().collect::<Vec<_>>(), vec![3, 2, 1]);
+    }
+
+    #[test]
+    fn test_map_zip() {
+        assert_eq!(Rgb::<u8>::new(1, 2, 3).map(|c| c as f32 * 0.5), Rgb::new(0.5, 1.0, 1.5));
+        assert_eq!(Lab::new(50.0, 10.0, -10.0).zip_with(Lab::new(1.0, 2.0, 3.0), |a, b| a * b),
+                   Lab::new(50.0, 20.0, -30.0));
+        let levels = |c: u8| c.saturating_mul(2);
+        assert_eq!(Rgba::new(10u8, 100, 200, 255).map(levels), Rgba::new(20, 200, 255, 255));
+        assert_eq!(Rgba::new(1u8, 2, 3, 4).zip_with(Rgba::new(4u8, 3, 2, 1), u8::max), Rgba::new(4, 3, 3, 4));
+    }
+}
@@ -0,0 +1,125 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Averages of many colors.
+//!
+//! Averaging the bytes of encoded sRGB colors gives results that are too
+//! dark: the mean of black and white bytes is `#808080`, which emits about a
+//! fifth of the light of white rather than half. The functions here
+//! accumulate in linear light by default, so `Srgb` inputs are decoded
+//! first, and convert the mean to whichever type is asked for.
+
+use FromColor;
+use {Rgb, ToRgb, Lab, ToLab, Oklab, ToOklab};
+
+/// The space colors are averaged in.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum AverageSpace {
+    /// Linear light, which is physically correct: the average of two lights
+    /// is what you see when both are shown half the time.
+    #[default]
+    Linear,
+    /// CIE Lab, perceptually more even.
+    Lab,
+    /// Oklab, perceptually more even, with better behaved blues.
+    Oklab,
+}
+
+/// The mean of `colors` in linear light, or `None` if there are none.
+#[inline]
+pub fn average<C: ToRgb, D, I: IntoIterator<Item = C>>(colors: I) -> Option<D>
+    where D: FromColor<Rgb<f64>> + FromColor<Lab<f64>> + FromColor<Oklab<f64>>
+{
+    average_in(colors, AverageSpace::Linear)
+}
+
+/// The mean of `colors` in `space`, or `None` if there are none.
+#[inline]
+pub fn average_in<C: ToRgb, D, I: IntoIterator<Item = C>>(colors: I, space: AverageSpace) -> Option<D>
+    where D: FromColor<Rgb<f64>> + FromColor<Lab<f64>> + FromColor<Oklab<f64>>
+{
+    weighted_average_in(colors.into_iter().map(|c| (c, 1.0)), space)
+}
+
+/// The mean of the `(color, weight)` pairs in linear light, or `None` if
+/// the weights add up to zero. Negative weights are treated as zero.
+#[inline]
+pub fn weighted_average<C: ToRgb, D, I: IntoIterator<Item = (C, f64)>>(colors: I) -> Option<D>
+    where D: FromColor<Rgb<f64>> + FromColor<Lab<f64>> + FromColor<Oklab<f64>>
+{
+    weighted_average_in(colors, AverageSpace::Linear)
+}
+
+/// The mean of the `(color, weight)` pairs in `space`, or `None` if the
+/// weights add up to zero. Negative weights are treated as zero.
+pub fn weighted_average_in<C: ToRgb, D, I: IntoIterator<Item = (C, f64)>>(colors: I, space: AverageSpace) -> Option<D>
+    where D: FromColor<Rgb<f64>> + FromColor<Lab<f64>> + FromColor<Oklab<f64>>
+{
+    let mut sum = [0.0; 3];
+    let mut total = 0.0;
+    for (c, w) in colors {
+        let w = w.max(0.0);
+        let rgb = c.to_rgb::<f64>();
+        let v = match space {
+            AverageSpace::Linear => [rgb.r, rgb.g, rgb.b],
+            AverageSpace::Lab => { let lab: Lab<f64> = rgb.to_lab(); [lab.l, lab.a, lab.b] }
+            AverageSpace::Oklab => { let lab: Oklab<f64> = rgb.to_oklab(); [lab.l, lab.a, lab.b] }
+        };
+        for (s, x) in sum.iter_mut().zip(v.iter()) {
+            *s += x * w;
+        }
+        total += w;
+    }
+    if total <= 0.0 {
+        return None;
+    }
+    let [a, b, c] = sum.map(|s| s / total);
+    Some(match space {
+        AverageSpace::Linear => D::from_color(&Rgb::new(a, b, c)),
+        AverageSpace::Lab => D::from_color(&Lab::new(a, b, c)),
+        AverageSpace::Oklab => D::from_color(&Oklab::new(a, b, c)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{average, average_in, weighted_average, AverageSpace};
+    use {Rgb, Srgb};
+
+    #[test]
+    fn test_average() {
+        let colors = [Srgb::<u8>::new(0, 0, 0), Srgb::new(255, 255, 255)];
+        let mean: Srgb<u8> = average(colors.iter().cloned()).unwrap();
+        assert_eq!(mean, Srgb::new(187, 187, 187));
+        let mean: Rgb<f64> = average(colors.iter().cloned()).unwrap();
+        assert!((mean.r - 0.5).abs() < 1e-12);
+
+        // the perceptual spaces average lightness, which is darker
+        let mean: Srgb<f64> = average_in(colors.iter().cloned(), AverageSpace::Lab).unwrap();
+        assert!((mean.r - 0.466).abs() < 1e-3);
+        let mean: Srgb<f64> = average_in(colors.iter().cloned(), AverageSpace::Oklab).unwrap();
+        assert!((mean.r - 0.389).abs() < 1e-3);
+
+        assert_eq!(average::<Srgb<u8>, Srgb<u8>, _>(vec![]), None);
+    }
+
+    #[test]
+    fn test_weighted_average() {
+        let colors = vec![(Rgb::<f64>::new(1.0, 0.0, 0.0), 3.0), (Rgb::new(0.0, 0.0, 1.0), 1.0), (Rgb::new(0.0, 1.0, 0.0), -1.0)];
+        let mean: Rgb<f64> = weighted_average(colors).unwrap();
+        assert_eq!(mean, Rgb::new(0.75, 0.0, 0.25));
+        assert_eq!(weighted_average::<Rgb<f64>, Rgb<f64>, _>(vec![(Rgb::new(1.0, 1.0, 1.0), 0.0)]), None);
+    }
+}
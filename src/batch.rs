@@ -0,0 +1,468 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Conversions over whole buffers of colors.
+//!
+//! `convert_slice` works for any pair of types. The `_f32` functions are
+//! specialized for the hot paths of image pipelines: they process pixels in
+//! groups of eight, with the channels split into separate arrays.
+//!
+//! With the `simd` feature, on `x86_64` and `aarch64`, the groups are
+//! converted with SSE2 or NEON. There are no vector instructions for `powf`,
+//! so the sRGB transfer functions then use polynomial approximations of
+//! `log2`/`exp2`, accurate to about `1e-6`. Otherwise the conversions are
+//! scalar and use `powf`. `cargo bench --bench batch`, with and without the
+//! feature, compares them with converting one color at a time.
+//!
+//! With the `rayon` feature, the `par_` functions split the work across
+//! threads.
+
+use alloc::vec::Vec;
+
+use angle::Deg;
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use FromColor;
+use {Rgb, Srgb, Hsv};
+use blend::{Blend, BlendMode};
+
+/// The smallest number of pixels a parallel task is given.
+#[cfg(feature = "rayon")]
+const MIN_PAR_LEN: usize = 4096;
+
+/// Converts every color in `src` into the matching slot of `dst`.
+///
+/// # Panics
+///
+/// Panics if the slices differ in length.
+pub fn convert_slice<S, D: FromColor<S>>(src: &[S], dst: &mut [D]) {
+    assert_eq!(src.len(), dst.len(), "source and destination lengths differ");
+    for (d, s) in dst.iter_mut().zip(src) {
+        *d = D::from_color(s);
+    }
+}
+
+/// Converts every color in `src` into a new vector.
+pub fn convert_vec<S, D: FromColor<S>>(src: &[S]) -> Vec<D> {
+    src.iter().map(D::from_color).collect()
+}
+
+/// Blends each color of `source` on top of the matching color of
+/// `backdrop`, in place.
+///
+/// # Panics
+///
+/// Panics if the slices differ in length.
+pub fn blend<C: Blend>(backdrop: &mut [C], source: &[C], mode: BlendMode) {
+    assert_eq!(backdrop.len(), source.len(), "backdrop and source lengths differ");
+    for (b, &s) in backdrop.iter_mut().zip(source) {
+        *b = b.blend(s, mode);
+    }
+}
+
+/// The parallel version of `convert_slice`.
+///
+/// # Panics
+///
+/// Panics if the slices differ in length.
+#[cfg(feature = "rayon")]
+pub fn par_convert_slice<S: Sync, D: FromColor<S> + Send>(src: &[S], dst: &mut [D]) {
+    assert_eq!(src.len(), dst.len(), "source and destination lengths differ");
+    dst.par_iter_mut().with_min_len(MIN_PAR_LEN).zip(src.par_iter())
+        .for_each(|(d, s)| *d = D::from_color(s));
+}
+
+/// The parallel version of `blend`.
+///
+/// # Panics
+///
+/// Panics if the slices differ in length.
+#[cfg(feature = "rayon")]
+pub fn par_blend<C: Blend + Send + Sync>(backdrop: &mut [C], source: &[C], mode: BlendMode) {
+    assert_eq!(backdrop.len(), source.len(), "backdrop and source lengths differ");
+    backdrop.par_iter_mut().with_min_len(MIN_PAR_LEN).zip(source.par_iter())
+        .for_each(|(b, &s)| *b = b.blend(s, mode));
+}
+
+const LANES: usize = 8;
+
+type Lane = [f32; LANES];
+
+/// Runs `f` over `src` in chunks of `LANES`, with the channels of each chunk
+/// split into separate arrays, and writes the results to `dst`. The tail is
+/// padded with zeros.
+#[inline(always)]
+fn lanes<S, D, L, F, W>(src: &[S], dst: &mut [D], load: L, f: F, store: W)
+    where L: Fn(&S) -> [f32; 3], F: Fn(&mut Lane, &mut Lane, &mut Lane), W: Fn(f32, f32, f32) -> D
+{
+    assert_eq!(src.len(), dst.len(), "source and destination lengths differ");
+    for (s, d) in src.chunks(LANES).zip(dst.chunks_mut(LANES)) {
+        let (mut a, mut b, mut c) = ([0.0; LANES], [0.0; LANES], [0.0; LANES]);
+        for (i, px) in s.iter().enumerate() {
+            let v = load(px);
+            a[i] = v[0];
+            b[i] = v[1];
+            c[i] = v[2];
+        }
+        f(&mut a, &mut b, &mut c);
+        for (i, px) in d.iter_mut().enumerate() {
+            *px = store(a[i], b[i], c[i]);
+        }
+    }
+}
+
+/// Converts RGB to HSV, with hues in `[0, 360)` and gray given a hue of `0`.
+///
+/// # Panics
+///
+/// Panics if the slices differ in length.
+pub fn rgb_to_hsv_f32(src: &[Rgb<f32>], dst: &mut [Hsv<f32>]) {
+    lanes(src, dst, |c| [c.r, c.g, c.b], kernels::rgb_to_hsv, |h, s, v| Hsv::new(Deg(h), s, v))
+}
+
+/// Converts HSV to RGB. Hues outside `[0, 360)` are wrapped.
+///
+/// # Panics
+///
+/// Panics if the slices differ in length.
+pub fn hsv_to_rgb_f32(src: &[Hsv<f32>], dst: &mut [Rgb<f32>]) {
+    lanes(src, dst, |c| [c.h.0, c.s, c.v], kernels::hsv_to_rgb, Rgb::new)
+}
+
+/// Decodes sRGB to linear light.
+///
+/// # Panics
+///
+/// Panics if the slices differ in length.
+pub fn srgb_to_linear_f32(src: &[Srgb<f32>], dst: &mut [Rgb<f32>]) {
+    lanes(src, dst, |c| [c.r, c.g, c.b], kernels::srgb_to_linear, Rgb::new)
+}
+
+/// Encodes linear light as sRGB.
+///
+/// # Panics
+///
+/// Panics if the slices differ in length.
+pub fn linear_to_srgb_f32(src: &[Rgb<f32>], dst: &mut [Srgb<f32>]) {
+    lanes(src, dst, |c| [c.r, c.g, c.b], kernels::linear_to_srgb, Srgb::new)
+}
+
+#[cfg(all(feature = "simd", any(target_arch = "x86_64", target_arch = "aarch64")))]
+use self::vector as kernels;
+#[cfg(not(all(feature = "simd", any(target_arch = "x86_64", target_arch = "aarch64"))))]
+use self::scalar as kernels;
+
+/// The conversions of a group, one pixel at a time.
+#[cfg(any(test, not(all(feature = "simd", any(target_arch = "x86_64", target_arch = "aarch64")))))]
+mod scalar {
+    #[cfg(not(feature = "std"))]
+    #[allow(unused_imports)]
+    use math::*;
+
+    use super::{Lane, LANES};
+
+    #[inline(always)]
+    pub fn rgb_to_hsv(r: &mut Lane, g: &mut Lane, b: &mut Lane) {
+        for i in 0..LANES {
+            let (cr, cg, cb) = (r[i], g[i], b[i]);
+            let max = cr.max(cg).max(cb);
+            let min = cr.min(cg).min(cb);
+            let chroma = max - min;
+            let inv = if chroma > 0.0 { 1.0 / chroma } else { 0.0 };
+            let h = if max == cr { (cg - cb) * inv } else if max == cg { (cb - cr) * inv + 2.0 } else { (cr - cg) * inv + 4.0 };
+            let h = h * 60.0;
+            r[i] = if h < 0.0 { h + 360.0 } else { h };
+            g[i] = if max > 0.0 { chroma / max } else { 0.0 };
+            b[i] = max;
+        }
+    }
+
+    #[inline(always)]
+    pub fn hsv_to_rgb(h: &mut Lane, s: &mut Lane, v: &mut Lane) {
+        for i in 0..LANES {
+            let h6 = h[i] / 60.0;
+            let (sv, val) = (s[i] * v[i], v[i]);
+            // https://en.wikipedia.org/wiki/HSL_and_HSV#HSV_to_RGB_alternative
+            let channel = |n: f32| {
+                let k = n + h6;
+                let k = k - 6.0 * (k / 6.0).floor();
+                val - sv * k.min(4.0 - k).clamp(0.0, 1.0)
+            };
+            let (r, g, b) = (channel(5.0), channel(3.0), channel(1.0));
+            h[i] = r;
+            s[i] = g;
+            v[i] = b;
+        }
+    }
+
+    #[inline(always)]
+    pub fn srgb_to_linear(r: &mut Lane, g: &mut Lane, b: &mut Lane) {
+        for lane in [r, g, b].iter_mut() {
+            for v in lane.iter_mut() {
+                let x = *v;
+                *v = if x <= 0.04045 { x * (1.0 / 12.92) } else { ((x + 0.055) * (1.0 / 1.055)).powf(2.4) };
+            }
+        }
+    }
+
+    #[inline(always)]
+    pub fn linear_to_srgb(r: &mut Lane, g: &mut Lane, b: &mut Lane) {
+        for lane in [r, g, b].iter_mut() {
+            for v in lane.iter_mut() {
+                let x = *v;
+                *v = if x <= 0.0031308 { x * 12.92 } else { 1.055 * x.powf(1.0 / 2.4) - 0.055 };
+            }
+        }
+    }
+}
+
+/// The conversions of a group, four pixels at a time.
+#[cfg(all(feature = "simd", any(target_arch = "x86_64", target_arch = "aarch64")))]
+mod vector {
+    use core::convert::TryInto;
+    use core::f32::consts::{LN_2, LOG2_E, SQRT_2};
+
+    use simd::F32x4;
+    use super::Lane;
+
+    /// Runs `f` over the lanes four at a time. Both halves of the group are
+    /// loaded before either is stored, so their chains of dependent
+    /// instructions can overlap.
+    #[inline(always)]
+    fn quads<F>(a: &mut Lane, b: &mut Lane, c: &mut Lane, f: F)
+        where F: Fn(F32x4, F32x4, F32x4) -> (F32x4, F32x4, F32x4)
+    {
+        let halves = |lane: &mut Lane| -> (F32x4, F32x4) {
+            let (low, high) = lane.split_at(4);
+            (F32x4::load(low.try_into().unwrap()), F32x4::load(high.try_into().unwrap()))
+        };
+        let ((a0, a1), (b0, b1), (c0, c1)) = (halves(a), halves(b), halves(c));
+        let (x0, y0, z0) = f(a0, b0, c0);
+        let (x1, y1, z1) = f(a1, b1, c1);
+        for (lane, (low, high)) in [a, b, c].iter_mut().zip([(x0, x1), (y0, y1), (z0, z1)].iter()) {
+            let (l, h) = lane.split_at_mut(4);
+            low.store(l.try_into().unwrap());
+            high.store(h.try_into().unwrap());
+        }
+    }
+
+    #[inline(always)]
+    fn splat(v: f32) -> F32x4 {
+        F32x4::splat(v)
+    }
+
+    #[inline(always)]
+    pub fn rgb_to_hsv(r: &mut Lane, g: &mut Lane, b: &mut Lane) {
+        quads(r, g, b, |r, g, b| {
+            let zero = splat(0.0);
+            let max = r.max(g).max(b);
+            let chroma = max - r.min(g).min(b);
+            let inv = zero.lt(chroma).select(splat(1.0) / chroma, zero);
+            let h = max.eq(g).select((b - r) * inv + splat(2.0), (r - g) * inv + splat(4.0));
+            let h = max.eq(r).select((g - b) * inv, h) * splat(60.0);
+            let h = h.lt(zero).select(h + splat(360.0), h);
+            (h, zero.lt(max).select(chroma / max, zero), max)
+        })
+    }
+
+    #[inline(always)]
+    pub fn hsv_to_rgb(h: &mut Lane, s: &mut Lane, v: &mut Lane) {
+        quads(h, s, v, |h, s, v| {
+            let h6 = h / splat(60.0);
+            let sv = s * v;
+            let channel = |n: f32| {
+                let k = splat(n) + h6;
+                let k = k - splat(6.0) * (k / splat(6.0)).floor();
+                v - sv * k.min(splat(4.0) - k).max(splat(0.0)).min(splat(1.0))
+            };
+            (channel(5.0), channel(3.0), channel(1.0))
+        })
+    }
+
+    #[inline(always)]
+    pub fn srgb_to_linear(r: &mut Lane, g: &mut Lane, b: &mut Lane) {
+        let decode = |x: F32x4| {
+            let curve = pow((x + splat(0.055)) * splat(1.0 / 1.055), 2.4);
+            x.le(splat(0.04045)).select(x * splat(1.0 / 12.92), curve)
+        };
+        quads(r, g, b, |r, g, b| (decode(r), decode(g), decode(b)))
+    }
+
+    #[inline(always)]
+    pub fn linear_to_srgb(r: &mut Lane, g: &mut Lane, b: &mut Lane) {
+        let encode = |x: F32x4| {
+            let curve = splat(1.055) * pow(x, 1.0 / 2.4) - splat(0.055);
+            x.le(splat(0.0031308)).select(x * splat(12.92), curve)
+        };
+        quads(r, g, b, |r, g, b| (encode(r), encode(g), encode(b)))
+    }
+
+    /// `c[0] + x * (c[1] + x * (c[2] + ...))`.
+    #[inline(always)]
+    fn horner(x: F32x4, c: &[f32]) -> F32x4 {
+        c[..c.len() - 1].iter().rev().fold(splat(c[c.len() - 1]), |acc, &k| acc * x + splat(k))
+    }
+
+    /// `x.powf(y)` for positive `x`, as `exp2(y * log2(x))`. Other lanes
+    /// give unspecified results, which callers discard.
+    #[inline(always)]
+    fn pow(x: F32x4, y: f32) -> F32x4 {
+        exp2(splat(y) * log2(x))
+    }
+
+    #[inline(always)]
+    fn log2(x: F32x4) -> F32x4 {
+        // split into exponent and a mantissa in [sqrt(1/2), sqrt(2))
+        let (e, m) = x.split_exponent();
+        let big = splat(SQRT_2).lt(m);
+        let m = big.select(m * splat(0.5), m);
+        let e = e + big.select(splat(1.0), splat(0.0));
+        // ln(m) = 2 atanh(t), with |t| < 0.172
+        let t = (m - splat(1.0)) / (m + splat(1.0));
+        let ln = splat(2.0) * t * horner(t * t, &[1.0, 1.0 / 3.0, 1.0 / 5.0, 1.0 / 7.0, 1.0 / 9.0]);
+        e + ln * splat(LOG2_E)
+    }
+
+    #[inline(always)]
+    fn exp2(y: F32x4) -> F32x4 {
+        let y = y.max(splat(-126.0)).min(splat(127.0));
+        let n = y.round();
+        // e^f for |f| <= ln(2) / 2
+        let f = (y - n) * splat(LN_2);
+        horner(f, &[1.0, 1.0, 1.0 / 2.0, 1.0 / 6.0, 1.0 / 24.0, 1.0 / 120.0, 1.0 / 720.0]) * n.exp2_int()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use {Rgb, ToRgb, Srgb, ToSrgb, Hsv, ToHsv, Lab};
+
+    #[test]
+    fn test_convert_slice() {
+        let src = [Hsv::<f32>::new(Deg(0.0), 1.0, 1.0), Hsv::new(Deg(120.0), 1.0, 1.0), Hsv::new(Deg(0.0), 0.0, 0.0)];
+        let mut dst = [Rgb::<u8>::new(0, 0, 0); 3];
+        convert_slice(&src, &mut dst);
+        assert_eq!(dst, [Rgb::new(0xFF, 0x00, 0x00), Rgb::new(0x00, 0xFF, 0x00), Rgb::new(0x00, 0x00, 0x00)]);
+        let labs: Vec<Lab<f64>> = convert_vec(&dst);
+        assert_eq!(labs.len(), 3);
+        assert!((labs[2].l).abs() < 1e-9);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_convert_slice_length() {
+        let mut dst = [Rgb::<u8>::new(0, 0, 0); 2];
+        convert_slice(&[0xFF0000_u32], &mut dst);
+    }
+
+    #[test]
+    fn test_blend() {
+        let mut backdrop = vec![Rgb::<f32>::new(0.5, 0.5, 0.5); 3];
+        let source = [Rgb::new(1.0, 0.0, 0.5), Rgb::new(0.0, 0.0, 0.0), Rgb::new(1.0, 1.0, 1.0)];
+        blend(&mut backdrop, &source, BlendMode::Multiply);
+        assert_eq!(backdrop, vec![Rgb::new(0.5, 0.0, 0.25), Rgb::new(0.0, 0.0, 0.0), Rgb::new(0.5, 0.5, 0.5)]);
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn test_par() {
+        let src: Vec<u32> = (0..20000).map(|i| i * 0x301).collect();
+        let mut serial = vec![Rgb::<u8>::new(0, 0, 0); src.len()];
+        let mut parallel = serial.clone();
+        convert_slice(&src, &mut serial);
+        par_convert_slice(&src, &mut parallel);
+        assert_eq!(serial, parallel);
+
+        let source = vec![Rgb::<u8>::new(0x80, 0x40, 0x20); src.len()];
+        blend(&mut serial, &source, BlendMode::Screen);
+        par_blend(&mut parallel, &source, BlendMode::Screen);
+        assert_eq!(serial, parallel);
+    }
+
+    fn samples() -> Vec<Rgb<f32>> {
+        let steps = [0.0, 0.001, 0.02, 0.25, 0.5, 0.77, 0.999, 1.0];
+        let mut v = Vec::new();
+        for &r in &steps { for &g in &steps { for &b in &steps { v.push(Rgb::new(r, g, b)); } } }
+        v
+    }
+
+    #[test]
+    fn test_hsv_f32() {
+        let src = samples();
+        let mut hsv = vec![Hsv::new(Deg(0.0), 0.0, 0.0); src.len()];
+        let mut rgb = vec![Rgb::new(0.0, 0.0, 0.0); src.len()];
+        rgb_to_hsv_f32(&src, &mut hsv);
+        hsv_to_rgb_f32(&hsv, &mut rgb);
+        for ((c, h), back) in src.iter().zip(&hsv).zip(&rgb) {
+            let expected = c.to_hsv::<f32>();
+            let hue = if expected.h.0 < 0.0 { expected.h.0 + 360.0 } else { expected.h.0 };
+            assert!((h.h.0 - hue).abs() < 1e-3 && (h.s - expected.s).abs() < 1e-6 && h.v == expected.v,
+                    "{:?}: {:?} != {:?}", c, h, expected);
+            assert!((back.r - c.r).abs() < 1e-5 && (back.g - c.g).abs() < 1e-5 && (back.b - c.b).abs() < 1e-5,
+                    "{:?} != {:?}", back, c);
+            let scalar = h.to_rgb::<f32>();
+            assert!((back.r - scalar.r).abs() < 1e-5 && (back.g - scalar.g).abs() < 1e-5 && (back.b - scalar.b).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_srgb_f32() {
+        let src: Vec<Srgb<f32>> = samples().iter().map(|c| Srgb::new(c.r, c.g, c.b)).collect();
+        let mut linear = vec![Rgb::new(0.0, 0.0, 0.0); src.len()];
+        let mut encoded = vec![Srgb::new(0.0, 0.0, 0.0); src.len()];
+        srgb_to_linear_f32(&src, &mut linear);
+        linear_to_srgb_f32(&linear, &mut encoded);
+        for ((c, l), e) in src.iter().zip(&linear).zip(&encoded) {
+            let expected = c.to_linear::<f32>();
+            assert!((l.r - expected.r).abs() < 2e-6 && (l.g - expected.g).abs() < 2e-6 && (l.b - expected.b).abs() < 2e-6,
+                    "{:?} != {:?}", l, expected);
+            let expected = l.to_srgb::<f32>();
+            assert!((e.r - expected.r).abs() < 2e-6 && (e.g - expected.g).abs() < 2e-6 && (e.b - expected.b).abs() < 2e-6,
+                    "{:?} != {:?}", e, expected);
+        }
+    }
+
+    /// Runs a kernel over `values` as the first channel, with the others
+    /// fixed, and returns the three channels of the results.
+    #[cfg(all(feature = "simd", any(target_arch = "x86_64", target_arch = "aarch64")))]
+    fn run(kernel: fn(&mut Lane, &mut Lane, &mut Lane), values: &Lane, g: f32, b: f32) -> [Lane; 3] {
+        let (mut a, mut g, mut b) = (*values, [g; LANES], [b; LANES]);
+        kernel(&mut a, &mut g, &mut b);
+        [a, g, b]
+    }
+
+    #[test]
+    #[cfg(all(feature = "simd", any(target_arch = "x86_64", target_arch = "aarch64")))]
+    fn test_vector_kernels() {
+        // The vector kernels agree with the scalar ones, out of range too.
+        let close = |a: [Lane; 3], b: [Lane; 3], tolerance: f32| {
+            for (a, b) in a.iter().flatten().zip(b.iter().flatten()) {
+                assert!((a - b).abs() <= tolerance * b.abs().max(1.0), "{} != {}", a, b);
+            }
+        };
+        for i in 0..200 {
+            let values: Lane = core::array::from_fn(|j| (i * LANES + j) as f32 / 1000.0 - 0.2);
+            for &(g, b) in &[(0.0, 0.0), (0.3, 0.9), (1.0, 0.5), (-0.1, 1.2)] {
+                close(run(vector::rgb_to_hsv, &values, g, b), run(scalar::rgb_to_hsv, &values, g, b), 1e-6);
+                close(run(vector::hsv_to_rgb, &values.map(|v| v * 900.0), g, b), run(scalar::hsv_to_rgb, &values.map(|v| v * 900.0), g, b), 1e-5);
+                close(run(vector::srgb_to_linear, &values, g, b), run(scalar::srgb_to_linear, &values, g, b), 2e-6);
+                close(run(vector::linear_to_srgb, &values, g, b), run(scalar::linear_to_srgb, &values, g, b), 2e-6);
+            }
+        }
+    }
+}
@@ -0,0 +1,261 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Blend modes, as specified by W3C Compositing and Blending Level 1.
+//!
+//! https://www.w3.org/TR/compositing-1/#blending
+//!
+//! The formulas are applied to the components as given, so blending `Srgb`
+//! values gives the results browsers and image editors produce, while
+//! blending `Rgb` operates on linear light.
+
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use math::*;
+
+use {AlphaColor, Channel, Rgb, Srgb};
+
+/// A blend mode, combining a backdrop color with a source color.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BlendMode {
+    Normal,
+    Multiply,
+    Screen,
+    Overlay,
+    Darken,
+    Lighten,
+    ColorDodge,
+    ColorBurn,
+    HardLight,
+    SoftLight,
+    Difference,
+    Exclusion,
+    Hue,
+    Saturation,
+    Color,
+    Luminosity,
+}
+
+impl BlendMode {
+    /// Whether the mode is applied to each component independently.
+    pub fn is_separable(self) -> bool {
+        !matches!(self, BlendMode::Hue | BlendMode::Saturation | BlendMode::Color | BlendMode::Luminosity)
+    }
+
+    /// Blends opaque components in `[0, 1]`.
+    pub(crate) fn apply(self, b: [f64; 3], s: [f64; 3]) -> [f64; 3] {
+        match self {
+            BlendMode::Hue => set_lum(set_sat(s, sat(b)), lum(b)),
+            BlendMode::Saturation => set_lum(set_sat(b, sat(s)), lum(b)),
+            BlendMode::Color => set_lum(s, lum(b)),
+            BlendMode::Luminosity => set_lum(b, lum(s)),
+            _ => [self.separable(b[0], s[0]), self.separable(b[1], s[1]), self.separable(b[2], s[2])],
+        }
+    }
+
+    fn separable(self, b: f64, s: f64) -> f64 {
+        match self {
+            BlendMode::Normal => s,
+            BlendMode::Multiply => b * s,
+            BlendMode::Screen => b + s - b * s,
+            BlendMode::Overlay => BlendMode::HardLight.separable(s, b),
+            BlendMode::Darken => b.min(s),
+            BlendMode::Lighten => b.max(s),
+            BlendMode::ColorDodge => {
+                if b == 0.0 { 0.0 } else if s >= 1.0 { 1.0 } else { (b / (1.0 - s)).min(1.0) }
+            }
+            BlendMode::ColorBurn => {
+                if b >= 1.0 { 1.0 } else if s <= 0.0 { 0.0 } else { 1.0 - ((1.0 - b) / s).min(1.0) }
+            }
+            BlendMode::HardLight => {
+                if s <= 0.5 { b * 2.0 * s } else { BlendMode::Screen.separable(b, 2.0 * s - 1.0) }
+            }
+            BlendMode::SoftLight => {
+                if s <= 0.5 {
+                    b - (1.0 - 2.0 * s) * b * (1.0 - b)
+                } else {
+                    let d = if b <= 0.25 { ((16.0 * b - 12.0) * b + 4.0) * b } else { b.sqrt() };
+                    b + (2.0 * s - 1.0) * (d - b)
+                }
+            }
+            BlendMode::Difference => (b - s).abs(),
+            BlendMode::Exclusion => b + s - 2.0 * b * s,
+            BlendMode::Hue | BlendMode::Saturation | BlendMode::Color | BlendMode::Luminosity => {
+                unreachable!()
+            }
+        }
+    }
+}
+
+#[inline]
+fn lum(c: [f64; 3]) -> f64 {
+    0.3 * c[0] + 0.59 * c[1] + 0.11 * c[2]
+}
+
+fn clip_color(c: [f64; 3]) -> [f64; 3] {
+    let l = lum(c);
+    let n = c[0].min(c[1]).min(c[2]);
+    let x = c[0].max(c[1]).max(c[2]);
+    let mut c = c;
+    if n < 0.0 {
+        for v in c.iter_mut() { *v = l + (*v - l) * l / (l - n); }
+    }
+    if x > 1.0 {
+        for v in c.iter_mut() { *v = l + (*v - l) * (1.0 - l) / (x - l); }
+    }
+    c
+}
+
+#[inline]
+fn set_lum(c: [f64; 3], l: f64) -> [f64; 3] {
+    let d = l - lum(c);
+    clip_color([c[0] + d, c[1] + d, c[2] + d])
+}
+
+#[inline]
+fn sat(c: [f64; 3]) -> f64 {
+    c[0].max(c[1]).max(c[2]) - c[0].min(c[1]).min(c[2])
+}
+
+fn set_sat(c: [f64; 3], s: f64) -> [f64; 3] {
+    let mut idx = [0, 1, 2];
+    idx.sort_by(|&i, &j| c[i].total_cmp(&c[j]));
+    let (min, mid, max) = (idx[0], idx[1], idx[2]);
+    let mut out = [0.0; 3];
+    if c[max] > c[min] {
+        out[mid] = (c[mid] - c[min]) * s / (c[max] - c[min]);
+        out[max] = s;
+    }
+    out
+}
+
+/// Colors that can be blended with a blend mode.
+pub trait Blend: Copy {
+    #[doc(hidden)]
+    fn to_components(&self) -> [f64; 3];
+
+    #[doc(hidden)]
+    fn from_components(c: [f64; 3]) -> Self;
+
+    /// Blends `source` on top of `self` with the given mode.
+    #[inline]
+    fn blend(self, source: Self, mode: BlendMode) -> Self {
+        Self::from_components(mode.apply(self.to_components(), source.to_components()))
+    }
+}
+
+impl<T: Channel> Blend for Rgb<T> {
+    #[inline]
+    fn to_components(&self) -> [f64; 3] {
+        [self.r.to_channel_f64(), self.g.to_channel_f64(), self.b.to_channel_f64()]
+    }
+
+    #[inline]
+    fn from_components(c: [f64; 3]) -> Rgb<T> {
+        Rgb::new(Channel::from(c[0]), Channel::from(c[1]), Channel::from(c[2]))
+    }
+}
+
+impl<T: Channel> Blend for Srgb<T> {
+    #[inline]
+    fn to_components(&self) -> [f64; 3] {
+        [self.r.to_channel_f64(), self.g.to_channel_f64(), self.b.to_channel_f64()]
+    }
+
+    #[inline]
+    fn from_components(c: [f64; 3]) -> Srgb<T> {
+        Srgb::new(Channel::from(c[0]), Channel::from(c[1]), Channel::from(c[2]))
+    }
+}
+
+impl<T: Channel, C: Blend> AlphaColor<T, C> {
+    /// Blends `source` on top of `self` with the given mode and composites
+    /// the result source-over. Where only one of the colors is opaque the
+    /// blend mode has no effect.
+    pub fn blend(self, source: AlphaColor<T, C>, mode: BlendMode) -> AlphaColor<T, C> {
+        let ab = self.a.to_channel_f64();
+        let as_ = source.a.to_channel_f64();
+        let ao = as_ + ab * (1.0 - as_);
+        if ao == 0.0 {
+            return AlphaColor { c: C::from_components([0.0; 3]), a: Channel::from(0.0f64) };
+        }
+        let (cb, cs) = (self.c.to_components(), source.c.to_components());
+        let mixed = mode.apply(cb, cs);
+        let mut c = [0.0; 3];
+        for i in 0..3 {
+            c[i] = ((1.0 - as_) * ab * cb[i] + as_ * (1.0 - ab) * cs[i] + as_ * ab * mixed[i]) / ao;
+        }
+        AlphaColor { c: C::from_components(c), a: Channel::from(ao) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Blend, BlendMode};
+    use {Rgb, Rgba, Srgb};
+
+    fn assert_rgb_eq(a: Rgb<f64>, b: Rgb<f64>) {
+        assert!((a.r - b.r).abs() < 1e-6 && (a.g - b.g).abs() < 1e-6 && (a.b - b.b).abs() < 1e-6,
+                "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn test_separable() {
+        let b = Rgb::<f64>::new(0.2, 0.5, 0.8);
+        let s = Rgb::<f64>::new(0.6, 0.6, 0.1);
+        assert_rgb_eq(b.blend(s, BlendMode::Normal),     s);
+        assert_rgb_eq(b.blend(s, BlendMode::Multiply),   Rgb::new(0.12, 0.3, 0.08));
+        assert_rgb_eq(b.blend(s, BlendMode::Screen),     Rgb::new(0.68, 0.8, 0.82));
+        assert_rgb_eq(b.blend(s, BlendMode::Overlay),    Rgb::new(0.24, 0.6, 0.64));
+        assert_rgb_eq(b.blend(s, BlendMode::Darken),     Rgb::new(0.2, 0.5, 0.1));
+        assert_rgb_eq(b.blend(s, BlendMode::Lighten),    Rgb::new(0.6, 0.6, 0.8));
+        assert_rgb_eq(b.blend(s, BlendMode::ColorDodge), Rgb::new(0.5, 1.0, 0.8 / 0.9));
+        assert_rgb_eq(b.blend(s, BlendMode::ColorBurn),  Rgb::new(0.0, 1.0 / 6.0, 0.0));
+        assert_rgb_eq(b.blend(s, BlendMode::HardLight),  Rgb::new(0.36, 0.6, 0.16));
+        assert_rgb_eq(b.blend(s, BlendMode::SoftLight),  Rgb::new(0.2 + 0.2 * (0.2 * (-8.8) + 4.0) * 0.2 - 0.04,
+                                                                  0.5 + 0.2 * (0.5f64.sqrt() - 0.5),
+                                                                  0.8 - 0.8 * 0.8 * 0.2));
+        assert_rgb_eq(b.blend(s, BlendMode::Difference), Rgb::new(0.4, 0.1, 0.7));
+        assert_rgb_eq(b.blend(s, BlendMode::Exclusion),  Rgb::new(0.56, 0.5, 0.74));
+    }
+
+    #[test]
+    fn test_non_separable() {
+        let red = Rgb::<f64>::new(1.0, 0.0, 0.0);
+        let gray = Rgb::<f64>::new(0.5, 0.5, 0.5);
+        assert_rgb_eq(gray.blend(red, BlendMode::Luminosity), Rgb::new(0.3, 0.3, 0.3));
+        assert_rgb_eq(red.blend(gray, BlendMode::Saturation), Rgb::new(0.3, 0.3, 0.3));
+        assert_rgb_eq(gray.blend(red, BlendMode::Hue),        gray);
+        // red at the luminance of a 0.5 gray, clipped into gamut
+        let c = gray.blend(red, BlendMode::Color);
+        assert!((0.3 * c.r + 0.59 * c.g + 0.11 * c.b - 0.5).abs() < 1e-6);
+        assert!(c.r > c.g && c.g == c.b);
+        assert!(!BlendMode::Color.is_separable() && BlendMode::Screen.is_separable());
+    }
+
+    #[test]
+    fn test_alpha() {
+        let b = Rgba::<f64>::new(0.2, 0.5, 0.8, 1.0);
+        let s = Rgba::<f64>::new(0.6, 0.6, 0.1, 0.5);
+        let c = b.blend(s, BlendMode::Multiply);
+        assert_eq!(c.a, 1.0);
+        assert_rgb_eq(c.c, Rgb::new(0.16, 0.4, 0.44));
+        let c = Rgba::<f64>::new(0.0, 0.0, 0.0, 0.0).blend(s, BlendMode::Multiply);
+        assert_rgb_eq(c.c, s.c);
+        assert_eq!(c.a, 0.5);
+        assert_eq!(Srgb::<u8>::new(0xFF, 0x80, 0x00).blend(Srgb::new(0xFF, 0xFF, 0xFF), BlendMode::Multiply),
+                   Srgb::new(0xFF, 0x80, 0x00));
+    }
+}
@@ -0,0 +1,409 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The CAM16 color appearance model and the CAM16-UCS uniform color space.
+//!
+//! Li et al., "Comprehensive color solutions: CAM16, CAT16, and CAM16-UCS"
+//! (2017).
+//!
+//! Unlike Lab, CAM16 accounts for the viewing conditions: how bright the
+//! surroundings are, what the eye has adapted to, and the background the
+//! color is seen against. The `ToCam16` and `ToCam16Ucs` conversions use
+//! `ViewingConditions::default()`; use `Cam16::from_xyz_with` for others.
+
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use math::*;
+
+use angle::*;
+
+use {Channel, FloatChannel};
+use {Rgb, ToRgb, Srgb, Xyz, ToXyz, WhitePoint, ColorError};
+use adaptation::Adaptation;
+use matrix::{self, Matrix3};
+
+/// The surroundings of the viewing field.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Surround {
+    /// Viewing surface colors, or a display in a lit room.
+    Average,
+    /// Television or a display in a dim room.
+    Dim,
+    /// A projector in a dark room.
+    Dark,
+}
+
+/// The conditions a color is viewed under, with the values derived from
+/// them precomputed.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ViewingConditions {
+    white: [f64; 3],
+    n: f64,
+    z: f64,
+    nbb: f64,
+    c: f64,
+    nc: f64,
+    fl: f64,
+    d_rgb: [f64; 3],
+    aw: f64,
+}
+
+impl Default for ViewingConditions {
+    /// An sRGB display under a D65 white, in an average surround of 64 lux
+    /// against a 20% gray background.
+    fn default() -> ViewingConditions {
+        ViewingConditions::new_unchecked(WhitePoint::D65, 64.0 / ::core::f64::consts::PI * 0.2, 20.0, Surround::Average, false)
+    }
+}
+
+impl ViewingConditions {
+    /// `adapting_luminance` is in cd/m², usually a fifth of the luminance
+    /// of a white object; `background_luminance` is relative to the white,
+    /// on a scale of `0` to `100`. When `discounting` is set the observer is
+    /// taken to be fully adapted to the illuminant. Fails for a degenerate
+    /// white.
+    pub fn new(white: WhitePoint, adapting_luminance: f64, background_luminance: f64,
+               surround: Surround, discounting: bool) -> Result<ViewingConditions, ColorError> {
+        white.check()?;
+        Ok(ViewingConditions::new_unchecked(white, adapting_luminance, background_luminance, surround, discounting))
+    }
+
+    fn new_unchecked(white: WhitePoint, adapting_luminance: f64, background_luminance: f64,
+                     surround: Surround, discounting: bool) -> ViewingConditions {
+        let (f, c, nc) = match surround {
+            Surround::Average => (1.0, 0.69, 1.0),
+            Surround::Dim => (0.9, 0.59, 0.9),
+            Surround::Dark => (0.8, 0.525, 0.8),
+        };
+        let la = adapting_luminance;
+        let white = [white.x * 100.0 / white.y, 100.0, white.z * 100.0 / white.y];
+        let k = 1.0 / (5.0 * la + 1.0);
+        let k4 = k.powi(4);
+        let fl = 0.2 * k4 * (5.0 * la) + 0.1 * (1.0 - k4).powi(2) * (5.0 * la).cbrt();
+        let n = background_luminance / white[1];
+        let z = 1.48 + n.sqrt();
+        let nbb = 0.725 * n.powf(-0.2);
+        let d = if discounting { 1.0 } else {
+            (f * (1.0 - (1.0 / 3.6) * ((-la - 42.0) / 92.0).exp())).clamp(0.0, 1.0)
+        };
+        let rgb_w = matrix::mul_vec(&m16(), white);
+        let d_rgb = [d * white[1] / rgb_w[0] + 1.0 - d,
+                     d * white[1] / rgb_w[1] + 1.0 - d,
+                     d * white[1] / rgb_w[2] + 1.0 - d];
+        let a = [compress(fl, d_rgb[0] * rgb_w[0]), compress(fl, d_rgb[1] * rgb_w[1]), compress(fl, d_rgb[2] * rgb_w[2])];
+        let aw = (2.0 * a[0] + a[1] + a[2] / 20.0 - 0.305) * nbb;
+        ViewingConditions { white, n, z, nbb, c, nc, fl, d_rgb, aw }
+    }
+}
+
+/// The CAT16 cone response matrix.
+#[inline]
+fn m16() -> Matrix3 {
+    Adaptation::Cat16.cone_matrix()
+}
+
+#[inline]
+fn compress(fl: f64, v: f64) -> f64 {
+    let p = (fl * v.abs() / 100.0).powf(0.42);
+    400.0 * v.signum() * p / (p + 27.13) + 0.1
+}
+
+#[inline]
+fn decompress(fl: f64, v: f64) -> f64 {
+    let v = v - 0.1;
+    v.signum() * 100.0 / fl * (27.13 * v.abs() / (400.0 - v.abs())).powf(1.0 / 0.42)
+}
+
+/// The CAM16 appearance correlates of a color.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Cam16<T: Channel> {
+    /// Lightness.
+    pub j: T,
+    /// Chroma.
+    pub c: T,
+    /// Hue angle.
+    #[cfg_attr(feature = "serde", serde(with = "::serialize::deg"))]
+    pub h: Deg<T>,
+    /// Colorfulness.
+    pub m: T,
+    /// Saturation.
+    pub s: T,
+    /// Brightness.
+    pub q: T,
+}
+
+impl<T: FloatChannel> Cam16<T> {
+    /// Computes the correlates of a color viewed under `vc`.
+    pub fn from_xyz_with<C: ToXyz>(color: &C, vc: &ViewingConditions) -> Cam16<T> {
+        let xyz = color.to_xyz::<f64>();
+        let rgb = matrix::mul_vec(&m16(), [xyz.x * 100.0, xyz.y * 100.0, xyz.z * 100.0]);
+        let ra = compress(vc.fl, vc.d_rgb[0] * rgb[0]);
+        let ga = compress(vc.fl, vc.d_rgb[1] * rgb[1]);
+        let ba = compress(vc.fl, vc.d_rgb[2] * rgb[2]);
+
+        let a = ra - 12.0 * ga / 11.0 + ba / 11.0;
+        let b = (ra + ga - 2.0 * ba) / 9.0;
+        let h = b.atan2(a).to_degrees().rem_euclid(360.0);
+        let et = 0.25 * ((h.to_radians() + 2.0).cos() + 3.8);
+        let achromatic = (2.0 * ra + ga + ba / 20.0 - 0.305) * vc.nbb;
+        let j = 100.0 * (achromatic / vc.aw).max(0.0).powf(vc.c * vc.z);
+        let q = 4.0 / vc.c * (j / 100.0).sqrt() * (vc.aw + 4.0) * vc.fl.powf(0.25);
+        let t = (50000.0 / 13.0 * vc.nc * vc.nbb * et * a.hypot(b)) / (ra + ga + 21.0 / 20.0 * ba);
+        let c = t.powf(0.9) * (j / 100.0).sqrt() * (1.64 - 0.29f64.powf(vc.n)).powf(0.73);
+        let m = c * vc.fl.powf(0.25);
+        let s = if q == 0.0 { 0.0 } else { 100.0 * (m / q).sqrt() };
+        let f = Channel::from;
+        Cam16 { j: f(j), c: f(c), h: Deg(f(h)), m: f(m), s: f(s), q: f(q) }
+    }
+
+    /// Recovers the color from its lightness, chroma and hue, as viewed
+    /// under `vc`. The other correlates are ignored.
+    pub fn to_xyz_with<U: FloatChannel>(&self, vc: &ViewingConditions) -> Xyz<U> {
+        let (j, c) = (self.j.to_channel_f64(), self.c.to_channel_f64());
+        let h = self.h.0.to_channel_f64().to_radians();
+        if j <= 0.0 {
+            return Xyz::new(Channel::from(0.0f64), Channel::from(0.0f64), Channel::from(0.0f64));
+        }
+        let t = (c / ((j / 100.0).sqrt() * (1.64 - 0.29f64.powf(vc.n)).powf(0.73))).powf(1.0 / 0.9);
+        let et = 0.25 * ((h + 2.0).cos() + 3.8);
+        let achromatic = vc.aw * (j / 100.0).powf(1.0 / (vc.c * vc.z));
+        let p2 = achromatic / vc.nbb + 0.305;
+        let p3 = 21.0 / 20.0;
+        let (sin, cos) = h.sin_cos();
+        let (a, b) = if t == 0.0 {
+            (0.0, 0.0)
+        } else {
+            let p1 = 50000.0 / 13.0 * vc.nc * vc.nbb * et / t;
+            let n = p2 * (2.0 + p3) * (460.0 / 1403.0);
+            if sin.abs() >= cos.abs() {
+                let b = n / (p1 / sin + (2.0 + p3) * (220.0 / 1403.0) * (cos / sin) - 27.0 / 1403.0
+                             + p3 * (6300.0 / 1403.0));
+                (b * cos / sin, b)
+            } else {
+                let a = n / (p1 / cos + (2.0 + p3) * (220.0 / 1403.0)
+                             - (27.0 / 1403.0 - p3 * (6300.0 / 1403.0)) * (sin / cos));
+                (a, a * sin / cos)
+            }
+        };
+        let ra = (460.0 * p2 + 451.0 * a + 288.0 * b) / 1403.0;
+        let ga = (460.0 * p2 - 891.0 * a - 261.0 * b) / 1403.0;
+        let ba = (460.0 * p2 - 220.0 * a - 6300.0 * b) / 1403.0;
+        let rgb = [decompress(vc.fl, ra) / vc.d_rgb[0],
+                   decompress(vc.fl, ga) / vc.d_rgb[1],
+                   decompress(vc.fl, ba) / vc.d_rgb[2]];
+        let xyz = matrix::mul_vec(&matrix::inverse(&m16()).unwrap(), rgb);
+        Xyz::from_array([xyz[0] / 100.0, xyz[1] / 100.0, xyz[2] / 100.0])
+    }
+}
+
+/// CAM16-UCS, a space in which Euclidean distance tracks perceived
+/// difference better than in CIELAB.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[repr(C)]
+pub struct Cam16Ucs<T> { pub j: T, pub a: T, pub b: T }
+
+impl<T> Cam16Ucs<T> {
+    #[inline]
+    pub const fn new(j: T, a: T, b: T) -> Cam16Ucs<T> {
+        Cam16Ucs { j, a, b }
+    }
+}
+
+impl<T: FloatChannel> Cam16Ucs<T> {
+    /// Builds the uniform coordinates from the CAM16 lightness,
+    /// colorfulness and hue.
+    pub fn from_cam16<U: FloatChannel>(cam: &Cam16<U>) -> Cam16Ucs<T> {
+        let (j, m) = (cam.j.to_channel_f64(), cam.m.to_channel_f64());
+        let h = cam.h.0.to_channel_f64().to_radians();
+        let jp = 1.7 * j / (1.0 + 0.007 * j);
+        let mp = (1.0 + 0.0228 * m).ln() / 0.0228;
+        Cam16Ucs::new(Channel::from(jp), Channel::from(mp * h.cos()), Channel::from(mp * h.sin()))
+    }
+
+    /// Recovers the CAM16 lightness, chroma and hue under `vc`, computing
+    /// the remaining correlates from them.
+    pub fn to_cam16_with<U: FloatChannel>(&self, vc: &ViewingConditions) -> Cam16<U> {
+        let (jp, a, b) = (self.j.to_channel_f64(), self.a.to_channel_f64(), self.b.to_channel_f64());
+        let j = jp / (1.7 - 0.007 * jp);
+        let m = ((0.0228 * a.hypot(b)).exp() - 1.0) / 0.0228;
+        let h = b.atan2(a).to_degrees().rem_euclid(360.0);
+        let c = m / vc.fl.powf(0.25);
+        let q = 4.0 / vc.c * (j / 100.0).sqrt() * (vc.aw + 4.0) * vc.fl.powf(0.25);
+        let s = if q == 0.0 { 0.0 } else { 100.0 * (m / q).sqrt() };
+        let f = Channel::from;
+        Cam16 { j: f(j), c: f(c), h: Deg(f(h)), m: f(m), s: f(s), q: f(q) }
+    }
+}
+
+pub trait ToCam16 {
+    fn to_cam16<U:FloatChannel>(&self) -> Cam16<U>;
+}
+
+impl<T:FloatChannel> ToCam16 for Cam16<T> {
+    #[inline]
+    fn to_cam16<U:FloatChannel>(&self) -> Cam16<U> {
+        Cam16 {
+            j: self.j.to_channel(), c: self.c.to_channel(), h: Deg(self.h.0.to_channel()),
+            m: self.m.to_channel(), s: self.s.to_channel(), q: self.q.to_channel(),
+        }
+    }
+}
+
+impl<T:FloatChannel> ToCam16 for Cam16Ucs<T> {
+    #[inline]
+    fn to_cam16<U:FloatChannel>(&self) -> Cam16<U> {
+        self.to_cam16_with(&ViewingConditions::default())
+    }
+}
+
+impl<T:FloatChannel> ToCam16 for Xyz<T> {
+    #[inline]
+    fn to_cam16<U:FloatChannel>(&self) -> Cam16<U> {
+        Cam16::from_xyz_with(self, &ViewingConditions::default())
+    }
+}
+
+impl<T:Channel> ToCam16 for Rgb<T> {
+    #[inline]
+    fn to_cam16<U:FloatChannel>(&self) -> Cam16<U> {
+        Cam16::from_xyz_with(self, &ViewingConditions::default())
+    }
+}
+
+impl<T:Channel> ToCam16 for Srgb<T> {
+    #[inline]
+    fn to_cam16<U:FloatChannel>(&self) -> Cam16<U> {
+        Cam16::from_xyz_with(self, &ViewingConditions::default())
+    }
+}
+
+pub trait ToCam16Ucs {
+    fn to_cam16_ucs<U:FloatChannel>(&self) -> Cam16Ucs<U>;
+}
+
+impl<T:FloatChannel> ToCam16Ucs for Cam16Ucs<T> {
+    #[inline]
+    fn to_cam16_ucs<U:FloatChannel>(&self) -> Cam16Ucs<U> {
+        Cam16Ucs::new(self.j.to_channel(), self.a.to_channel(), self.b.to_channel())
+    }
+}
+
+impl<T:FloatChannel> ToCam16Ucs for Cam16<T> {
+    #[inline]
+    fn to_cam16_ucs<U:FloatChannel>(&self) -> Cam16Ucs<U> {
+        Cam16Ucs::from_cam16(self)
+    }
+}
+
+impl<T:FloatChannel> ToCam16Ucs for Xyz<T> {
+    #[inline]
+    fn to_cam16_ucs<U:FloatChannel>(&self) -> Cam16Ucs<U> {
+        Cam16Ucs::from_cam16(&self.to_cam16::<f64>())
+    }
+}
+
+impl<T:Channel> ToCam16Ucs for Rgb<T> {
+    #[inline]
+    fn to_cam16_ucs<U:FloatChannel>(&self) -> Cam16Ucs<U> {
+        Cam16Ucs::from_cam16(&self.to_cam16::<f64>())
+    }
+}
+
+impl<T:Channel> ToCam16Ucs for Srgb<T> {
+    #[inline]
+    fn to_cam16_ucs<U:FloatChannel>(&self) -> Cam16Ucs<U> {
+        Cam16Ucs::from_cam16(&self.to_cam16::<f64>())
+    }
+}
+
+impl<T:FloatChannel> ToXyz for Cam16<T> {
+    #[inline]
+    fn to_xyz<U:FloatChannel>(&self) -> Xyz<U> {
+        self.to_xyz_with(&ViewingConditions::default())
+    }
+}
+
+impl<T:FloatChannel> ToXyz for Cam16Ucs<T> {
+    #[inline]
+    fn to_xyz<U:FloatChannel>(&self) -> Xyz<U> {
+        self.to_cam16::<f64>().to_xyz()
+    }
+}
+
+impl<T:FloatChannel> ToRgb for Cam16<T> {
+    #[inline]
+    fn to_rgb<U:Channel>(&self) -> Rgb<U> {
+        self.to_xyz::<f64>().to_rgb()
+    }
+}
+
+impl<T:FloatChannel> ToRgb for Cam16Ucs<T> {
+    #[inline]
+    fn to_rgb<U:Channel>(&self) -> Rgb<U> {
+        self.to_xyz::<f64>().to_rgb()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use {Xyz, Srgb, WhitePoint};
+
+    fn assert_close(a: f64, b: f64, eps: f64) {
+        assert!((a - b).abs() < eps, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn test_reference() {
+        // from the colour-science documentation
+        let white = WhitePoint { x: 0.9505, y: 1.0, z: 1.0888 };
+        let vc = ViewingConditions::new(white, 318.31, 20.0, Surround::Average, false).unwrap();
+        let cam: Cam16<f64> = Cam16::from_xyz_with(&Xyz::new(0.1901, 0.2000, 0.2178), &vc);
+        assert_close(cam.j, 41.73120791, 1e-6);
+        assert_close(cam.c, 0.10335574, 1e-6);
+        assert_close(cam.h.0, 217.06795977, 1e-4);
+        assert_close(cam.q, 195.37170899, 1e-6);
+        // at this luminance F_L is almost exactly 0.1 * (5 L_A)^(1/3)
+        assert_close(cam.m, cam.c * (0.1 * (5.0f64 * 318.31).cbrt()).powf(0.25), 1e-6);
+        assert_close(cam.s, 100.0 * (cam.m / cam.q).sqrt(), 1e-9);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for &c in [(0.8, 0.3, 0.1), (0.1, 0.6, 0.9), (0.5, 0.5, 0.5), (1.0, 1.0, 1.0)].iter() {
+            let srgb = Srgb::<f64>::new(c.0, c.1, c.2);
+            let xyz: Xyz<f64> = srgb.to_xyz();
+            let back: Xyz<f64> = srgb.to_cam16::<f64>().to_xyz();
+            assert_close(back.x, xyz.x, 1e-9);
+            assert_close(back.y, xyz.y, 1e-9);
+            assert_close(back.z, xyz.z, 1e-9);
+            let back: Xyz<f64> = srgb.to_cam16_ucs::<f64>().to_xyz();
+            assert_close(back.x, xyz.x, 1e-9);
+            assert_close(back.y, xyz.y, 1e-9);
+            assert_close(back.z, xyz.z, 1e-9);
+        }
+        // the adopted white is achromatic at J = 100 once fully adapted to
+        let white: Cam16<f64> = Srgb::<f64>::new(1.0, 1.0, 1.0).to_cam16();
+        assert_close(white.j, 100.0, 1e-9);
+        let vc = ViewingConditions::new(WhitePoint::D65, 64.0, 20.0, Surround::Dim, true).unwrap();
+        let white: Cam16<f64> = Cam16::from_xyz_with(&WhitePoint::D65.to_xyz::<f64>(), &vc);
+        assert_close(white.j, 100.0, 1e-9);
+        assert_close(white.c, 0.0, 1e-6);
+        let black: Xyz<f64> = Cam16::<f64> { j: 0.0, c: 0.0, h: Deg(0.0), m: 0.0, s: 0.0, q: 0.0 }.to_xyz();
+        assert_eq!(black, Xyz::new(0.0, 0.0, 0.0));
+    }
+}
@@ -0,0 +1,68 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Interoperability with `cgmath` vectors.
+//!
+//! The components of the vectors are taken as linear RGB, in the order of
+//! the fields, with alpha last in `Vector4`. Gamma-encoded colors have no
+//! conversions: decode an `Srgb` with `to_rgb` first, so shading code
+//! always works in linear light.
+
+use cgmath::{Vector3, Vector4};
+
+use {Channel, Rgb, Rgba};
+
+impl<T: Channel> From<Vector3<T>> for Rgb<T> {
+    #[inline]
+    fn from(v: Vector3<T>) -> Rgb<T> {
+        Rgb::new(v.x, v.y, v.z)
+    }
+}
+
+impl<T: Channel> From<Rgb<T>> for Vector3<T> {
+    #[inline]
+    fn from(c: Rgb<T>) -> Vector3<T> {
+        Vector3::new(c.r, c.g, c.b)
+    }
+}
+
+impl<T: Channel> From<Vector4<T>> for Rgba<T> {
+    #[inline]
+    fn from(v: Vector4<T>) -> Rgba<T> {
+        Rgba::new(v.x, v.y, v.z, v.w)
+    }
+}
+
+impl<T: Channel> From<Rgba<T>> for Vector4<T> {
+    #[inline]
+    fn from(c: Rgba<T>) -> Vector4<T> {
+        Vector4::new(c.c.r, c.c.g, c.c.b, c.a)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::{Vector3, Vector4};
+
+    use {Rgb, Rgba};
+
+    #[test]
+    fn test_vectors() {
+        assert_eq!(Vector3::from(Rgb::new(0.25f32, 0.5, 1.0)), Vector3::new(0.25, 0.5, 1.0));
+        assert_eq!(Rgb::from(Vector3::new(0.25f64, 0.5, 1.0) * 2.0), Rgb::new(0.5, 1.0, 2.0));
+        assert_eq!(Vector4::from(Rgba::new(0.1f32, 0.2, 0.3, 0.4)), Vector4::new(0.1, 0.2, 0.3, 0.4));
+        assert_eq!(Rgba::from(Vector4::new(0x10u8, 0x20, 0x30, 0x40)), Rgba::new(0x10, 0x20, 0x30, 0x40));
+    }
+}
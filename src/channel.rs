@@ -14,11 +14,46 @@
 // limitations under the License.
 
 //! Color channel conversions and utility methods
+//!
+//! Unsigned integer channels are unsigned normalized (UNORM): `0` is `0.0`
+//! and the maximum is `1.0`. The signed `i8` and `i16` are signed
+//! normalized (SNORM) as GPUs define them: the maximum is `1.0`, its
+//! negation is `-1.0`, and the one value below that, such as `-128i8`, is
+//! also `-1.0`. Converting to a signed channel rounds to nearest and clamps
+//! to `[-1, 1]`, and converting a negative value to an unsigned channel
+//! gives `0`. With the `half` feature, `half::f16` is a float channel, and
+//! `Fixed` is a fixed-point one for targets without a floating point unit.
+//!
+//! sRGB decoding of `u8` channels looks the linear values up in a table of
+//! 256. The `srgb_lut16` feature adds a table of 65536 for decoding `u16`
+//! channels, which takes 256 KiB once used, and encodes to `u8` by a search
+//! of the 255 linear values at which the result steps up. Both give the
+//! results of the transfer function.
+
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use math::*;
+
+use num::{self, Bounded, Float, NumCast, Num, Zero, zero, one};
+#[cfg(feature = "half")]
+use half::f16;
+
+use srgb;
+
+/// Casts between numeric types without panicking. Values the target type
+/// cannot represent saturate to its bounds, so a hue of `300` in a `u8` is
+/// `255`, and NaN becomes zero.
+#[inline]
+pub(crate) fn cast<T: NumCast, U: NumCast + Zero + Bounded>(n: T) -> U {
+    let f = n.to_f64();
+    num::cast(n).unwrap_or_else(|| match f {
+        Some(f) if f > 0.0 => U::max_value(),
+        Some(f) if f < 0.0 => U::min_value(),
+        _ => U::zero(),
+    })
+}
 
-use num::{Float, NumCast, Num, zero, one};
-use std::{u8, u16};
-
-pub trait Channel: Copy + Sized + Clone + PartialOrd<Self> + Num + NumCast {
+pub trait Channel: Copy + Sized + Clone + PartialOrd<Self> + Num + NumCast + Bounded {
     fn from<T:Channel>(chan: T) -> Self;
     fn to_channel<T:Channel>(self) -> T { Channel::from(self) }
     fn to_channel_u8(self)  -> u8;
@@ -52,7 +87,31 @@ pub trait Channel: Copy + Sized + Clone + PartialOrd<Self> + Num + NumCast {
     
     #[inline]
     fn mix(self, rhs: Self, value: Self) -> Self {
-        (self + (rhs - self).normalized_mul(value))
+        self + (rhs - self).normalized_mul(value)
+    }
+
+    /// Splits a hue in degrees into its sextant of the color wheel, from
+    /// `0` to `5`, and the position within the sextant, from `0` to
+    /// `max()`. Hues outside `[0, 360)` wrap, and NaN is `0`.
+    #[inline]
+    fn hue_sextant(self) -> (usize, Self) {
+        let hue: f64 = cast(self);
+        let hue = if hue.is_finite() { hue.rem_euclid(360.0) } else { 0.0 };
+        let hue_six = hue / 360.0 * 6.0;
+        let max: f64 = cast(Self::max());
+        (cast(hue_six), cast(hue_six.fract() * max))
+    }
+
+    /// Decodes `self` as an sRGB encoded value to linear light.
+    #[inline]
+    fn srgb_to_linear(self) -> f64 {
+        srgb::decode(self.to_channel_f64())
+    }
+
+    /// Encodes the linear light `v` as an sRGB value.
+    #[inline]
+    fn srgb_from_linear(v: f64) -> Self {
+        Channel::from(srgb::encode(v))
     }
 }
 
@@ -64,26 +123,63 @@ impl Channel for u8 {
     #[inline] fn to_channel_f64(self) -> f64 { (self as f64) / (0xFF_u8 as f64) }
 
     #[inline] fn invert_channel(self) -> u8 { !self }
+
+    #[inline]
+    fn normalized_mul(self, rhs: Self) -> Self {
+        (self as u32 * rhs as u32 / 0xFF) as u8
+    }
     
     #[inline] fn max() -> u8{ u8::MAX }
+
+    #[inline]
+    fn hue_sextant(self) -> (usize, u8) {
+        ((self / 60) as usize, (self as u16 % 60 * 0xFF / 60) as u8)
+    }
+
+    #[inline] fn srgb_to_linear(self) -> f64 { srgb::decode_u8(self) }
+
+    #[cfg(feature = "srgb_lut16")]
+    #[inline] fn srgb_from_linear(v: f64) -> u8 { srgb::encode_u8(v) }
 }
 
 impl Channel for u16 {
     #[inline] fn from<T:Channel>(chan: T) -> u16 { chan.to_channel_u16() }
     #[inline] fn to_channel_u8(self)  -> u8  { (self >> 8) as u8 }
     #[inline] fn to_channel_u16(self) -> u16 { self }
-    #[inline] fn to_channel_f32(self) -> f32 { (self / 0xFFFF) as f32 }
-    #[inline] fn to_channel_f64(self) -> f64 { (self / 0xFFFF) as f64 }
+    #[inline] fn to_channel_f32(self) -> f32 { (self as f32) / (0xFFFF_u16 as f32) }
+    #[inline] fn to_channel_f64(self) -> f64 { (self as f64) / (0xFFFF_u16 as f64) }
 
     #[inline] fn invert_channel(self) -> u16 { !self }
+
+    #[inline]
+    fn normalized_mul(self, rhs: Self) -> Self {
+        (self as u32 * rhs as u32 / 0xFFFF) as u16
+    }
     
     #[inline] fn max() -> u16{ u16::MAX }
+
+    #[inline]
+    fn hue_sextant(self) -> (usize, u16) {
+        let hue = self % 360;
+        ((hue / 60) as usize, (hue as u32 % 60 * 0xFFFF / 60) as u16)
+    }
+
+    #[cfg(feature = "srgb_lut16")]
+    #[inline] fn srgb_to_linear(self) -> f64 { srgb::decode_u16(self) }
 }
 
 impl Channel for f32 {
     #[inline] fn from<T:Channel>(chan: T) -> f32 { chan.to_channel_f32() }
-    #[inline] fn to_channel_u8(self)  -> u8  { (self * (0xFF_u8 as f32)) as u8 }
-    #[inline] fn to_channel_u16(self) -> u16 { (self * (0xFFFF_u16 as f32)) as u16 }
+    #[inline]
+    fn to_channel_u8(self) -> u8 {
+        debug_assert!(!self.is_nan(), "NaN channel cast to u8");
+        (self * (0xFF_u8 as f32)) as u8
+    }
+    #[inline]
+    fn to_channel_u16(self) -> u16 {
+        debug_assert!(!self.is_nan(), "NaN channel cast to u16");
+        (self * (0xFFFF_u16 as f32)) as u16
+    }
     #[inline] fn to_channel_f32(self) -> f32 { self }
     #[inline] fn to_channel_f64(self) -> f64 { self as f64 }
 
@@ -104,8 +200,16 @@ impl Channel for f32 {
 
 impl Channel for f64 {
     #[inline] fn from<T:Channel>(chan: T) -> f64 { chan.to_channel_f64() }
-    #[inline] fn to_channel_u8(self)  -> u8  { (self * (0xFF_u8 as f64)) as u8 }
-    #[inline] fn to_channel_u16(self) -> u16 { (self * (0xFFFF_u16 as f64)) as u16 }
+    #[inline]
+    fn to_channel_u8(self) -> u8 {
+        debug_assert!(!self.is_nan(), "NaN channel cast to u8");
+        (self * (0xFF_u8 as f64)) as u8
+    }
+    #[inline]
+    fn to_channel_u16(self) -> u16 {
+        debug_assert!(!self.is_nan(), "NaN channel cast to u16");
+        (self * (0xFFFF_u16 as f64)) as u16
+    }
     #[inline] fn to_channel_f32(self) -> f32 { self as f32 }
     #[inline] fn to_channel_f64(self) -> f64 { self }
 
@@ -124,15 +228,86 @@ impl Channel for f64 {
     #[inline] fn max() -> f64{ 1.0 }
 }
 
+macro_rules! snorm_impls {
+    ($($T:ident, $wide:ident, $max:expr;)*) => {
+        $(
+            impl Channel for $T {
+                #[inline]
+                fn from<T:Channel>(chan: T) -> $T {
+                    let x = chan.to_channel_f64();
+                    debug_assert!(!x.is_nan(), "NaN channel cast to a signed channel");
+                    (x.max(-1.0).min(1.0) * $max as f64).round() as $T
+                }
+                #[inline]
+                fn to_channel_u8(self) -> u8 {
+                    ((self.max(0) as $wide * 0xFF + $max / 2) / $max) as u8
+                }
+                #[inline]
+                fn to_channel_u16(self) -> u16 {
+                    ((self.max(0) as i64 * 0xFFFF + $max / 2) / $max) as u16
+                }
+                #[inline] fn to_channel_f32(self) -> f32 { (self as f32 / $max as f32).max(-1.0) }
+                #[inline] fn to_channel_f64(self) -> f64 { (self as f64 / $max as f64).max(-1.0) }
+
+                /// `1 - x`, which saturates at `1` for negative values.
+                #[inline]
+                fn invert_channel(self) -> $T {
+                    ($max - self.max(-$max) as $wide).min($max) as $T
+                }
+
+                #[inline] fn max() -> $T { $max as $T }
+
+                /// Mixes through `f64`, as the difference of two channels
+                /// may not fit.
+                #[inline]
+                fn mix(self, rhs: Self, value: Self) -> Self {
+                    let (a, b) = (self.to_channel_f64(), rhs.to_channel_f64());
+                    Channel::from(a + (b - a) * value.to_channel_f64())
+                }
+            }
+        )*
+    }
+}
+
+snorm_impls! {
+    i8,  i32, 0x7F;
+    i16, i32, 0x7FFF;
+}
+
+#[cfg(feature = "half")]
+impl Channel for f16 {
+    #[inline] fn from<T:Channel>(chan: T) -> f16 { f16::from_f32(chan.to_channel_f32()) }
+    #[inline] fn to_channel_u8(self) -> u8 { self.to_f32().to_channel_u8() }
+    #[inline] fn to_channel_u16(self) -> u16 { self.to_f32().to_channel_u16() }
+    #[inline] fn to_channel_f32(self) -> f32 { self.to_f32() }
+    #[inline] fn to_channel_f64(self) -> f64 { self.to_f64() }
+
+    #[inline] fn invert_channel(self) -> f16 { f16::ONE - self }
+
+    #[inline]
+    fn normalized_mul(self, rhs: Self) -> Self {
+        self * rhs
+    }
+
+    #[inline]
+    fn normalized_div(self, rhs: Self) -> Self {
+        self / rhs
+    }
+
+    #[inline] fn max() -> f16 { f16::ONE }
+}
+
 pub trait FloatChannel: Float + Channel {
     #[inline]
     fn saturate(self) -> Self {
-        self.clamp(zero(),one())
+        Channel::clamp(self, zero(), one())
     }
 }
 
 impl FloatChannel for f32 {}
 impl FloatChannel for f64 {}
+#[cfg(feature = "half")]
+impl FloatChannel for f16 {}
 
 #[cfg(test)]
 mod tests {
@@ -181,9 +356,11 @@ mod tests {
         assert_eq!(0xFFFF_u16.to_channel_u16(), 0xFFFF_u16);
 
         assert_eq!(0x0000_u16.to_channel_f32(), 0f32);
+        assert_eq!(0x9999_u16.to_channel_f32(), 0.6f32);
         assert_eq!(0xFFFF_u16.to_channel_f32(), 1f32);
 
         assert_eq!(0x0000_u16.to_channel_f64(), 0f64);
+        assert_eq!(0x9999_u16.to_channel_f64(), 0.6f64);
         assert_eq!(0xFFFF_u16.to_channel_f64(), 1f64);
     }
 
@@ -249,4 +426,43 @@ mod tests {
         assert_eq!(0.50f64.invert_channel(), 0.50f64);
         assert_eq!(1.00f64.invert_channel(), 0.00f64);
     }
+
+    #[test]
+    fn test_snorm() {
+        assert_eq!(127i8.to_channel_f32(), 1.0);
+        assert_eq!((-127i8).to_channel_f32(), -1.0);
+        assert_eq!((-128i8).to_channel_f64(), -1.0);
+        assert_eq!(0i8.to_channel_f32(), 0.0);
+        assert_eq!(<i8 as Channel>::from(0.5f32), 64);
+        assert_eq!(<i8 as Channel>::from(-2.0f64), -127);
+        assert_eq!(<i16 as Channel>::from(1.0f32), 0x7FFF);
+        assert_eq!(<i16 as Channel>::from(-0.5f64), -16384);
+
+        assert_eq!(127i8.to_channel_u8(), 0xFF);
+        assert_eq!(64i8.to_channel_u8(), 129);
+        assert_eq!((-5i8).to_channel_u8(), 0);
+        assert_eq!(0x7FFFi16.to_channel_u16(), 0xFFFF);
+        assert_eq!(0x7FFFi16.to_channel_u8(), 0xFF);
+        assert_eq!(<i8 as Channel>::from(0xFFu8), 127);
+        assert_eq!(<i16 as Channel>::from(127i8), 0x7FFF);
+        assert_eq!(<i8 as Channel>::from(-0x7FFFi16), -127);
+
+        assert_eq!(27i8.invert_channel(), 100);
+        assert_eq!((-100i8).invert_channel(), 127);
+        assert_eq!((-128i8).mix(127, 127), 127);
+        assert_eq!((-100i8).mix(100, 0), -100);
+    }
+
+    #[cfg(feature = "half")]
+    #[test]
+    fn test_f16() {
+        use half::f16;
+        use ToHsv;
+        let half = f16::from_f32(0.5);
+        assert_eq!(half.to_channel_u8(), 0x7F);
+        assert_eq!(half.to_channel_f64(), 0.5);
+        assert_eq!(<f16 as Channel>::from(0xFFu8), f16::ONE);
+        assert_eq!(half.invert_channel(), half);
+        assert_eq!(::Rgb::<f16>::new(f16::ONE, half, f16::ZERO).to_hsv::<f32>().h.0, 30.0);
+    }
 }
@@ -0,0 +1,163 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! http://en.wikipedia.org/wiki/CMYK_color_model
+//!
+//! `ToCmyk` and `ToRgb` use the naive device conversion, which ignores inks,
+//! paper and dot gain and is only good for previews. A real, usually
+//! ICC-based, transform can be supplied by implementing `CmykTransform`.
+
+use {Channel, Rgb, ToRgb, Hsv, Hsl};
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[repr(C)]
+pub struct Cmyk<T> { pub c: T, pub m: T, pub y: T, pub k: T }
+
+impl<T> Cmyk<T> {
+    #[inline]
+    pub const fn new(c: T, m: T, y: T, k: T) -> Cmyk<T> {
+        Cmyk { c, m, y, k }
+    }
+}
+
+impl<T: Channel> Cmyk<T> {
+    /// Converts a color to CMYK with the given transform.
+    #[inline]
+    pub fn from_rgb_with<C: ToRgb, X: CmykTransform + ?Sized>(color: &C, transform: &X) -> Cmyk<T> {
+        let c = transform.rgb_to_cmyk(color.to_rgb());
+        Cmyk::new(Channel::from(c.c), Channel::from(c.m), Channel::from(c.y), Channel::from(c.k))
+    }
+
+    /// Converts the color to RGB with the given transform.
+    #[inline]
+    pub fn to_rgb_with<U: Channel, X: CmykTransform + ?Sized>(&self, transform: &X) -> Rgb<U> {
+        let cmyk = Cmyk::new(self.c.to_channel_f64(), self.m.to_channel_f64(),
+                             self.y.to_channel_f64(), self.k.to_channel_f64());
+        transform.cmyk_to_rgb(cmyk).to_rgb()
+    }
+}
+
+/// A conversion between device RGB and CMYK, such as one backed by a pair
+/// of ICC profiles.
+pub trait CmykTransform {
+    fn rgb_to_cmyk(&self, rgb: Rgb<f64>) -> Cmyk<f64>;
+    fn cmyk_to_rgb(&self, cmyk: Cmyk<f64>) -> Rgb<f64>;
+}
+
+/// The naive conversion, with full black generation and no under color
+/// removal limits.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct NaiveCmyk;
+
+impl CmykTransform for NaiveCmyk {
+    fn rgb_to_cmyk(&self, rgb: Rgb<f64>) -> Cmyk<f64> {
+        let k = 1.0 - rgb.r.max(rgb.g).max(rgb.b);
+        if k >= 1.0 {
+            return Cmyk::new(0.0, 0.0, 0.0, 1.0);
+        }
+        Cmyk::new((1.0 - rgb.r - k) / (1.0 - k),
+                  (1.0 - rgb.g - k) / (1.0 - k),
+                  (1.0 - rgb.b - k) / (1.0 - k),
+                  k)
+    }
+
+    fn cmyk_to_rgb(&self, cmyk: Cmyk<f64>) -> Rgb<f64> {
+        Rgb::new((1.0 - cmyk.c) * (1.0 - cmyk.k),
+                 (1.0 - cmyk.m) * (1.0 - cmyk.k),
+                 (1.0 - cmyk.y) * (1.0 - cmyk.k))
+    }
+}
+
+pub trait ToCmyk {
+    fn to_cmyk<U:Channel>(&self) -> Cmyk<U>;
+}
+
+impl<T:Channel> ToCmyk for Cmyk<T> {
+    #[inline]
+    fn to_cmyk<U:Channel>(&self) -> Cmyk<U> {
+        Cmyk::new(self.c.to_channel(), self.m.to_channel(), self.y.to_channel(), self.k.to_channel())
+    }
+}
+
+impl<T:Channel> ToCmyk for Rgb<T> {
+    #[inline]
+    fn to_cmyk<U:Channel>(&self) -> Cmyk<U> {
+        Cmyk::from_rgb_with(self, &NaiveCmyk)
+    }
+}
+
+impl<T:Channel> ToCmyk for Hsv<T> {
+    #[inline]
+    fn to_cmyk<U:Channel>(&self) -> Cmyk<U> {
+        Cmyk::from_rgb_with(self, &NaiveCmyk)
+    }
+}
+
+impl<T:Channel> ToCmyk for Hsl<T> {
+    #[inline]
+    fn to_cmyk<U:Channel>(&self) -> Cmyk<U> {
+        Cmyk::from_rgb_with(self, &NaiveCmyk)
+    }
+}
+
+impl<T:Channel> ToRgb for Cmyk<T> {
+    #[inline]
+    fn to_rgb<U:Channel>(&self) -> Rgb<U> {
+        self.to_rgb_with(&NaiveCmyk)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {Cmyk, ToCmyk, Rgb, ToRgb, Hsv};
+    use cmyk::{CmykTransform, NaiveCmyk};
+    use angle::Deg;
+
+    #[test]
+    fn test_rgb_to_cmyk() {
+        assert_eq!(Rgb::<u8>::new(0xFF, 0xFF, 0xFF).to_cmyk::<f32>(), Cmyk::new(0.0, 0.0, 0.0, 0.0));
+        assert_eq!(Rgb::<u8>::new(0x00, 0x00, 0x00).to_cmyk::<f32>(), Cmyk::new(0.0, 0.0, 0.0, 1.0));
+        assert_eq!(Rgb::<f32>::new(1.0, 0.0, 0.0).to_cmyk::<f32>(),   Cmyk::new(0.0, 1.0, 1.0, 0.0));
+        assert_eq!(Rgb::<f32>::new(0.0, 0.5, 0.5).to_cmyk::<f32>(),   Cmyk::new(1.0, 0.0, 0.0, 0.5));
+        assert_eq!(Hsv::<f32>::new(Deg(240.0), 1.0, 1.0).to_cmyk::<u8>(), Cmyk::new(0xFF, 0xFF, 0x00, 0x00));
+    }
+
+    #[test]
+    fn test_cmyk_to_rgb() {
+        assert_eq!(Cmyk::<f32>::new(0.0, 1.0, 1.0, 0.0).to_rgb::<u8>(), Rgb::new(0xFF, 0x00, 0x00));
+        assert_eq!(Cmyk::<f32>::new(1.0, 0.0, 0.0, 0.5).to_rgb::<f32>(), Rgb::new(0.0, 0.5, 0.5));
+        assert_eq!(Cmyk::<u8>::new(0x00, 0x00, 0x00, 0xFF).to_rgb::<u8>(), Rgb::new(0x00, 0x00, 0x00));
+    }
+
+    #[test]
+    fn test_custom_transform() {
+        /// Black only, as a grayscale printer would.
+        struct Gray;
+
+        impl CmykTransform for Gray {
+            fn rgb_to_cmyk(&self, rgb: Rgb<f64>) -> Cmyk<f64> {
+                Cmyk::new(0.0, 0.0, 0.0, 1.0 - (rgb.r + rgb.g + rgb.b) / 3.0)
+            }
+            fn cmyk_to_rgb(&self, cmyk: Cmyk<f64>) -> Rgb<f64> {
+                NaiveCmyk.cmyk_to_rgb(Cmyk::new(0.0, 0.0, 0.0, cmyk.k))
+            }
+        }
+
+        let c = Cmyk::<f32>::from_rgb_with(&Rgb::<f32>::new(1.0, 0.5, 0.0), &Gray);
+        assert_eq!(c, Cmyk::new(0.0, 0.0, 0.0, 0.5));
+        assert_eq!(c.to_rgb_with::<f32, _>(&Gray), Rgb::new(0.5, 0.5, 0.5));
+    }
+}
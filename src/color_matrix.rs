@@ -0,0 +1,267 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Linear color transforms as matrices, composable into a single pass.
+//!
+//! The constructors follow the CSS and SVG filter functions.
+//! https://www.w3.org/TR/filter-effects-1/#FilterPrimitiveRepresentation
+//!
+//! Like blending, matrices apply to the components as given: applying them
+//! to `Srgb` matches what browsers do for CSS filters. Results are not
+//! clamped, so that composed matrices give the same result as applying
+//! each in turn.
+
+use core::ops::Mul;
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use math::*;
+
+use Channel;
+use AlphaColor;
+use blend::Blend;
+use matrix::{self, Matrix3};
+
+/// A 3x3 matrix transforming RGB components, in row-major order.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ColorMatrix3(pub [[f64; 3]; 3]);
+
+impl ColorMatrix3 {
+    pub const IDENTITY: ColorMatrix3 = ColorMatrix3([[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]);
+
+    /// Scales every component, as CSS `brightness()`.
+    pub fn brightness(amount: f64) -> ColorMatrix3 {
+        ColorMatrix3([[amount, 0.0, 0.0], [0.0, amount, 0.0], [0.0, 0.0, amount]])
+    }
+
+    /// CSS `saturate()`: `0` is fully desaturated, `1` leaves the color
+    /// unchanged and larger values oversaturate.
+    pub fn saturate(s: f64) -> ColorMatrix3 {
+        ColorMatrix3([[0.213 + 0.787 * s, 0.715 - 0.715 * s, 0.072 - 0.072 * s],
+                      [0.213 - 0.213 * s, 0.715 + 0.285 * s, 0.072 - 0.072 * s],
+                      [0.213 - 0.213 * s, 0.715 - 0.715 * s, 0.072 + 0.928 * s]])
+    }
+
+    /// CSS `hue-rotate()`, by `degrees`.
+    pub fn hue_rotate(degrees: f64) -> ColorMatrix3 {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        ColorMatrix3([[0.213 + cos * 0.787 - sin * 0.213, 0.715 - cos * 0.715 - sin * 0.715, 0.072 - cos * 0.072 + sin * 0.928],
+                      [0.213 - cos * 0.213 + sin * 0.143, 0.715 + cos * 0.285 + sin * 0.140, 0.072 - cos * 0.072 - sin * 0.283],
+                      [0.213 - cos * 0.213 - sin * 0.787, 0.715 - cos * 0.715 + sin * 0.715, 0.072 + cos * 0.928 + sin * 0.072]])
+    }
+
+    /// CSS `sepia()`, with `amount` from `0` (unchanged) to `1`.
+    pub fn sepia(amount: f64) -> ColorMatrix3 {
+        let a = 1.0 - amount.clamp(0.0, 1.0);
+        ColorMatrix3([[0.393 + 0.607 * a, 0.769 - 0.769 * a, 0.189 - 0.189 * a],
+                      [0.349 - 0.349 * a, 0.686 + 0.314 * a, 0.168 - 0.168 * a],
+                      [0.272 - 0.272 * a, 0.534 - 0.534 * a, 0.131 + 0.869 * a]])
+    }
+
+    /// CSS `grayscale()`, with `amount` from `0` (unchanged) to `1`.
+    pub fn grayscale(amount: f64) -> ColorMatrix3 {
+        let a = 1.0 - amount.clamp(0.0, 1.0);
+        ColorMatrix3([[0.2126 + 0.7874 * a, 0.7152 - 0.7152 * a, 0.0722 - 0.0722 * a],
+                      [0.2126 - 0.2126 * a, 0.7152 + 0.2848 * a, 0.0722 - 0.0722 * a],
+                      [0.2126 - 0.2126 * a, 0.7152 - 0.7152 * a, 0.0722 + 0.9278 * a]])
+    }
+
+    /// The transform applying `self` and then `next`.
+    #[inline]
+    pub fn then(self, next: ColorMatrix3) -> ColorMatrix3 {
+        next * self
+    }
+
+    /// The inverse transform, or `None` if the matrix is singular.
+    #[inline]
+    pub fn inverse(&self) -> Option<ColorMatrix3> {
+        matrix::inverse(&self.0).map(ColorMatrix3)
+    }
+
+    #[inline]
+    pub fn apply<C: Blend>(&self, color: &C) -> C {
+        C::from_components(matrix::mul_vec(&self.0, color.to_components()))
+    }
+
+    /// Applies the matrix to the color of an `AlphaColor`, leaving alpha
+    /// untouched.
+    #[inline]
+    pub fn apply_alpha<T: Copy, C: Blend>(&self, color: &AlphaColor<T, C>) -> AlphaColor<T, C> {
+        AlphaColor { c: self.apply(&color.c), a: color.a }
+    }
+
+    pub fn apply_slice<C: Blend>(&self, colors: &mut [C]) {
+        for c in colors.iter_mut() {
+            *c = self.apply(c);
+        }
+    }
+}
+
+impl Mul for ColorMatrix3 {
+    type Output = ColorMatrix3;
+
+    /// The transform applying `rhs` and then `self`.
+    #[inline]
+    fn mul(self, rhs: ColorMatrix3) -> ColorMatrix3 {
+        ColorMatrix3(matrix::mul(&self.0, &rhs.0))
+    }
+}
+
+/// A 4x4 matrix transforming RGBA components, in row-major order.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ColorMatrix4(pub [[f64; 4]; 4]);
+
+impl ColorMatrix4 {
+    pub const IDENTITY: ColorMatrix4 = ColorMatrix4([[1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0],
+                                                     [0.0, 0.0, 1.0, 0.0], [0.0, 0.0, 0.0, 1.0]]);
+
+    /// CSS `opacity()`: scales alpha only.
+    pub fn opacity(amount: f64) -> ColorMatrix4 {
+        let mut m = ColorMatrix4::IDENTITY;
+        m.0[3][3] = amount;
+        m
+    }
+
+    /// The transform applying `self` and then `next`.
+    #[inline]
+    pub fn then(self, next: ColorMatrix4) -> ColorMatrix4 {
+        next * self
+    }
+
+    /// The inverse transform, or `None` if the matrix is singular.
+    pub fn inverse(&self) -> Option<ColorMatrix4> {
+        // Gauss-Jordan elimination with partial pivoting
+        let mut a = self.0;
+        let mut inv = ColorMatrix4::IDENTITY.0;
+        for col in 0..4 {
+            let pivot = (col..4).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs())).unwrap();
+            if a[pivot][col] == 0.0 {
+                return None;
+            }
+            a.swap(col, pivot);
+            inv.swap(col, pivot);
+            let p = a[col][col];
+            for j in 0..4 {
+                a[col][j] /= p;
+                inv[col][j] /= p;
+            }
+            for i in 0..4 {
+                if i != col {
+                    let f = a[i][col];
+                    for j in 0..4 {
+                        a[i][j] -= f * a[col][j];
+                        inv[i][j] -= f * inv[col][j];
+                    }
+                }
+            }
+        }
+        Some(ColorMatrix4(inv))
+    }
+
+    pub fn apply<T: Channel, C: Blend>(&self, color: &AlphaColor<T, C>) -> AlphaColor<T, C> {
+        let c = color.c.to_components();
+        let v = [c[0], c[1], c[2], color.a.to_channel_f64()];
+        let m = &self.0;
+        let row = |i: usize| m[i][0] * v[0] + m[i][1] * v[1] + m[i][2] * v[2] + m[i][3] * v[3];
+        AlphaColor { c: C::from_components([row(0), row(1), row(2)]), a: Channel::from(row(3)) }
+    }
+
+    pub fn apply_slice<T: Channel, C: Blend>(&self, colors: &mut [AlphaColor<T, C>]) {
+        for c in colors.iter_mut() {
+            *c = self.apply(c);
+        }
+    }
+}
+
+impl Mul for ColorMatrix4 {
+    type Output = ColorMatrix4;
+
+    /// The transform applying `rhs` and then `self`.
+    fn mul(self, rhs: ColorMatrix4) -> ColorMatrix4 {
+        let mut m = [[0.0; 4]; 4];
+        for (i, row) in m.iter_mut().enumerate() {
+            for (j, v) in row.iter_mut().enumerate() {
+                *v = (0..4).map(|k| self.0[i][k] * rhs.0[k][j]).sum();
+            }
+        }
+        ColorMatrix4(m)
+    }
+}
+
+impl From<ColorMatrix3> for ColorMatrix4 {
+    /// Embeds an RGB transform, passing alpha through.
+    fn from(m: ColorMatrix3) -> ColorMatrix4 {
+        let m: Matrix3 = m.0;
+        ColorMatrix4([[m[0][0], m[0][1], m[0][2], 0.0],
+                      [m[1][0], m[1][1], m[1][2], 0.0],
+                      [m[2][0], m[2][1], m[2][2], 0.0],
+                      [0.0, 0.0, 0.0, 1.0]])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use {Rgb, Rgba, Srgb};
+
+    fn assert_rgb_eq(a: Rgb<f64>, b: Rgb<f64>) {
+        assert!((a.r - b.r).abs() < 1e-6 && (a.g - b.g).abs() < 1e-6 && (a.b - b.b).abs() < 1e-6,
+                "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn test_filters() {
+        let c = Rgb::<f64>::new(0.2, 0.5, 0.8);
+        assert_rgb_eq(ColorMatrix3::saturate(1.0).apply(&c), c);
+        assert_rgb_eq(ColorMatrix3::hue_rotate(0.0).apply(&c), c);
+        assert_rgb_eq(ColorMatrix3::hue_rotate(360.0).apply(&c), c);
+        assert_rgb_eq(ColorMatrix3::sepia(0.0).apply(&c), c);
+        assert_rgb_eq(ColorMatrix3::brightness(0.5).apply(&c), Rgb::new(0.1, 0.25, 0.4));
+        let gray = ColorMatrix3::saturate(0.0).apply(&c);
+        assert!((gray.r - gray.g).abs() < 1e-9 && (gray.g - gray.b).abs() < 1e-9);
+        let gray = ColorMatrix3::grayscale(1.0).apply(&c);
+        assert!((gray.r - (0.2126 * 0.2 + 0.7152 * 0.5 + 0.0722 * 0.8)).abs() < 1e-9);
+        // white maps to the sepia tone
+        let s = ColorMatrix3::sepia(1.0).apply(&Srgb::<f64>::new(1.0, 1.0, 1.0));
+        assert!((s.r - 1.351).abs() < 1e-9 && (s.g - 1.203).abs() < 1e-9 && (s.b - 0.937).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_compose() {
+        let c = Rgb::<f64>::new(0.2, 0.5, 0.8);
+        let a = ColorMatrix3::hue_rotate(90.0);
+        let b = ColorMatrix3::saturate(1.5);
+        assert_rgb_eq(a.then(b).apply(&c), b.apply(&a.apply(&c)));
+        assert_rgb_eq((b * a).apply(&c), b.apply(&a.apply(&c)));
+        assert_rgb_eq(a.inverse().unwrap().apply(&a.apply(&c)), c);
+        assert!(ColorMatrix3::saturate(0.0).then(ColorMatrix3::brightness(0.0)).inverse().is_none());
+        let mut colors = [c, c];
+        a.apply_slice(&mut colors);
+        assert_eq!(colors[1], a.apply(&c));
+    }
+
+    #[test]
+    fn test_rgba() {
+        let c = Rgba::<f64>::new(0.2, 0.5, 0.8, 0.5);
+        let m = ColorMatrix4::from(ColorMatrix3::hue_rotate(45.0)).then(ColorMatrix4::opacity(0.5));
+        let out = m.apply(&c);
+        assert_rgb_eq(out.c, ColorMatrix3::hue_rotate(45.0).apply(&c.c));
+        assert_eq!(out.a, 0.25);
+        let back = m.inverse().unwrap().apply(&out);
+        assert_rgb_eq(back.c, c.c);
+        assert!((back.a - 0.5).abs() < 1e-12);
+        assert_eq!(ColorMatrix3::brightness(2.0).apply_alpha(&c).a, 0.5);
+        assert!(ColorMatrix4::opacity(0.0).inverse().is_none());
+    }
+}
@@ -0,0 +1,406 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Colormaps for data visualization.
+//!
+//! The built-in maps are the matplotlib perceptually uniform maps, sampled
+//! at ten points, and a selection of ColorBrewer schemes
+//! (http://colorbrewer2.org) and Tableau 10. Colors are gamma-encoded sRGB,
+//! and sequential and diverging maps interpolate between them in sRGB like
+//! the plotting libraries they come from. Custom maps made with
+//! `from_stops` interpolate in Oklab by default, and `lightness_profile`
+//! shows whether a map's lightness changes steadily.
+
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use Channel;
+use {Srgb, ToSrgb, ToCam16Ucs};
+use gradient::Interpolation;
+
+/// How a colormap is meant to be used.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ColormapKind {
+    /// Ordered data, from low to high.
+    Sequential,
+    /// Data with a meaningful midpoint, which sits at `0.5`.
+    Diverging,
+    /// Unordered categories. Sampling picks a color without blending.
+    Qualitative,
+}
+
+/// A list of colors, evenly spaced unless built with `from_stops`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Colormap {
+    name: Cow<'static, str>,
+    kind: ColormapKind,
+    colors: Cow<'static, [Srgb<u8>]>,
+    /// The position of each color in `[0, 1]`, or `None` if evenly spaced.
+    positions: Option<Vec<f64>>,
+    interpolation: Interpolation,
+}
+
+impl Colormap {
+    /// # Panics
+    ///
+    /// If `colors` is empty. In a `static` this is a compile error.
+    pub const fn new(name: &'static str, kind: ColormapKind, colors: &'static [Srgb<u8>]) -> Colormap {
+        assert!(!colors.is_empty(), "a colormap needs at least one color");
+        Colormap {
+            name: Cow::Borrowed(name),
+            kind,
+            colors: Cow::Borrowed(colors),
+            positions: None,
+            interpolation: Interpolation::Rgb,
+        }
+    }
+
+    /// A sequential colormap through `(position, color)` stops, interpolating
+    /// in Oklab. Sequential and diverging maps sample the same
+    /// way, so the kind only matters as a label. The stops are sorted by position, and positions are clamped
+    /// to `[0, 1]`; below the first stop and above the last, the map is
+    /// flat.
+    ///
+    /// # Panics
+    ///
+    /// If `stops` is empty.
+    pub fn from_stops<S: Into<String>>(name: S, mut stops: Vec<(f64, Srgb<u8>)>) -> Colormap {
+        assert!(!stops.is_empty(), "a colormap needs at least one color");
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Colormap {
+            name: Cow::Owned(name.into()),
+            kind: ColormapKind::Sequential,
+            colors: Cow::Owned(stops.iter().map(|s| s.1).collect()),
+            positions: Some(stops.iter().map(|s| s.0.clamp(0.0, 1.0)).collect()),
+            interpolation: Interpolation::Oklab,
+        }
+    }
+
+    /// Sets the color space `sample` interpolates in.
+    pub fn interpolation(mut self, interpolation: Interpolation) -> Colormap {
+        self.interpolation = interpolation;
+        self
+    }
+
+    /// Looks up a built-in colormap, ignoring case.
+    pub fn by_name(name: &str) -> Option<&'static Colormap> {
+        COLORMAPS.iter().cloned().find(|c| c.name.eq_ignore_ascii_case(name))
+    }
+
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[inline]
+    pub fn kind(&self) -> ColormapKind {
+        self.kind
+    }
+
+    #[inline]
+    pub fn colors(&self) -> &[Srgb<u8>] {
+        &self.colors
+    }
+
+    /// The position of color `i`.
+    #[inline]
+    fn position(&self, i: usize) -> f64 {
+        match self.positions {
+            Some(ref p) => p[i],
+            None if self.colors.len() == 1 => 0.0,
+            None => i as f64 / (self.colors.len() - 1) as f64,
+        }
+    }
+
+    /// The color at `t`, which is clamped to `[0, 1]`. Qualitative maps
+    /// split the range into one equal part per color.
+    #[inline]
+    pub fn sample<U: Channel>(&self, t: f64) -> Srgb<U> {
+        self.sample_with(t, self.interpolation)
+    }
+
+    /// The color at `t`, interpolating in the given space. Qualitative maps
+    /// ignore `interpolation`.
+    pub fn sample_with<U: Channel>(&self, t: f64, interpolation: Interpolation) -> Srgb<U> {
+        let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+        let n = self.colors.len();
+        if self.kind == ColormapKind::Qualitative {
+            return self.colors[((t * n as f64) as usize).min(n - 1)].to_srgb();
+        }
+        let i = match (0..n).rev().find(|&i| self.position(i) <= t) {
+            Some(i) if i + 1 < n => i,
+            Some(i) => return self.colors[i].to_srgb(),
+            None => return self.colors[0].to_srgb(),
+        };
+        let (p0, p1) = (self.position(i), self.position(i + 1));
+        let local = if p1 > p0 { (t - p0) / (p1 - p0) } else { 0.0 };
+        if local == 0.0 {
+            return self.colors[i].to_srgb();
+        }
+        interpolation.mix(self.colors[i].to_srgb(), self.colors[i + 1].to_srgb(), local).to_srgb()
+    }
+
+    /// `n` evenly spaced samples, endpoints included. For a qualitative map
+    /// these are its first `n` colors, repeating once they run out.
+    pub fn samples<U: Channel>(&self, n: usize) -> Vec<Srgb<U>> {
+        if self.kind == ColormapKind::Qualitative {
+            return self.colors.iter().cycle().take(n).map(|c| c.to_srgb()).collect();
+        }
+        (0..n).map(|i| self.sample(if n == 1 { 0.0 } else { i as f64 / (n - 1) as f64 })).collect()
+    }
+
+    /// The CAM16-UCS lightness `J'`, from `0` to `100`, of `n` evenly spaced
+    /// samples. A map for ordered data should have a profile that rises or
+    /// falls steadily, with no plateaus or reversals.
+    pub fn lightness_profile(&self, n: usize) -> Vec<f64> {
+        self.samples::<f64>(n).into_iter()
+            .map(|c| c.to_cam16_ucs::<f64>().j)
+            .collect()
+    }
+
+    /// Whether the lightness only rises or only falls over 256 samples,
+    /// allowing reversals of up to `tolerance` in `J'`.
+    pub fn is_lightness_monotonic(&self, tolerance: f64) -> bool {
+        let profile = self.lightness_profile(256);
+        let reverses = |sign: f64| {
+            let mut peak = f64::NEG_INFINITY;
+            profile.iter().any(|&j| {
+                peak = peak.max(j * sign);
+                j * sign < peak - tolerance
+            })
+        };
+        !reverses(1.0) || !reverses(-1.0)
+    }
+
+    /// The map running the other way.
+    pub fn reversed(&self) -> Colormap {
+        let mut colors = self.colors.to_vec();
+        colors.reverse();
+        Colormap {
+            name: Cow::Owned(format!("{}_r", self.name)),
+            kind: self.kind,
+            colors: Cow::Owned(colors),
+            positions: self.positions.as_ref().map(|p| p.iter().rev().map(|x| 1.0 - x).collect()),
+            interpolation: self.interpolation,
+        }
+    }
+}
+
+/// Perceptually uniform, from dark blue through green to yellow, and
+/// readable with every common color vision deficiency.
+pub static VIRIDIS: Colormap = Colormap::new("viridis", ColormapKind::Sequential, &[
+    const_srgb!(0x440154), const_srgb!(0x482878), const_srgb!(0x3e4989), const_srgb!(0x31688e),
+    const_srgb!(0x26828e), const_srgb!(0x1f9e89), const_srgb!(0x35b779), const_srgb!(0x6ece58),
+    const_srgb!(0xb5de2b), const_srgb!(0xfde725),
+]);
+
+/// Perceptually uniform, from black through purple and orange to pale yellow.
+pub static MAGMA: Colormap = Colormap::new("magma", ColormapKind::Sequential, &[
+    const_srgb!(0x000004), const_srgb!(0x180f3d), const_srgb!(0x440f76), const_srgb!(0x721f81),
+    const_srgb!(0x9e2f7f), const_srgb!(0xcd4071), const_srgb!(0xf1605d), const_srgb!(0xfd9668),
+    const_srgb!(0xfeca8d), const_srgb!(0xfcfdbf),
+]);
+
+/// Perceptually uniform, from black through red and orange to pale yellow.
+pub static INFERNO: Colormap = Colormap::new("inferno", ColormapKind::Sequential, &[
+    const_srgb!(0x000004), const_srgb!(0x1b0c41), const_srgb!(0x4a0c6b), const_srgb!(0x781c6d),
+    const_srgb!(0xa52c60), const_srgb!(0xcf4446), const_srgb!(0xed6925), const_srgb!(0xfb9b06),
+    const_srgb!(0xf7d13d), const_srgb!(0xfcffa4),
+]);
+
+/// Perceptually uniform, from blue through magenta to yellow.
+pub static PLASMA: Colormap = Colormap::new("plasma", ColormapKind::Sequential, &[
+    const_srgb!(0x0d0887), const_srgb!(0x46039f), const_srgb!(0x7201a8), const_srgb!(0x9c179e),
+    const_srgb!(0xbd3786), const_srgb!(0xd8576b), const_srgb!(0xed7953), const_srgb!(0xfb9f3a),
+    const_srgb!(0xfdca26), const_srgb!(0xf0f921),
+]);
+
+/// From blue to yellow, designed to look the same to viewers with red-green
+/// color vision deficiency.
+pub static CIVIDIS: Colormap = Colormap::new("cividis", ColormapKind::Sequential, &[
+    const_srgb!(0x00224e), const_srgb!(0x123570), const_srgb!(0x3b496c), const_srgb!(0x575d6d),
+    const_srgb!(0x707173), const_srgb!(0x8a8779), const_srgb!(0xa69d75), const_srgb!(0xc4b56c),
+    const_srgb!(0xe4cf5b), const_srgb!(0xfee838),
+]);
+
+/// ColorBrewer single hue blues.
+pub static BLUES: Colormap = Colormap::new("Blues", ColormapKind::Sequential, &[
+    const_srgb!(0xf7fbff), const_srgb!(0xdeebf7), const_srgb!(0xc6dbef), const_srgb!(0x9ecae1),
+    const_srgb!(0x6baed6), const_srgb!(0x4292c6), const_srgb!(0x2171b5), const_srgb!(0x08519c),
+    const_srgb!(0x08306b),
+]);
+
+/// ColorBrewer single hue greens.
+pub static GREENS: Colormap = Colormap::new("Greens", ColormapKind::Sequential, &[
+    const_srgb!(0xf7fcf5), const_srgb!(0xe5f5e0), const_srgb!(0xc7e9c0), const_srgb!(0xa1d99b),
+    const_srgb!(0x74c476), const_srgb!(0x41ab5d), const_srgb!(0x238b45), const_srgb!(0x006d2c),
+    const_srgb!(0x00441b),
+]);
+
+/// ColorBrewer single hue reds.
+pub static REDS: Colormap = Colormap::new("Reds", ColormapKind::Sequential, &[
+    const_srgb!(0xfff5f0), const_srgb!(0xfee0d2), const_srgb!(0xfcbba1), const_srgb!(0xfc9272),
+    const_srgb!(0xfb6a4a), const_srgb!(0xef3b2c), const_srgb!(0xcb181d), const_srgb!(0xa50f15),
+    const_srgb!(0x67000d),
+]);
+
+/// ColorBrewer red to blue through white.
+pub static RD_BU: Colormap = Colormap::new("RdBu", ColormapKind::Diverging, &[
+    const_srgb!(0x67001f), const_srgb!(0xb2182b), const_srgb!(0xd6604d), const_srgb!(0xf4a582),
+    const_srgb!(0xfddbc7), const_srgb!(0xf7f7f7), const_srgb!(0xd1e5f0), const_srgb!(0x92c5de),
+    const_srgb!(0x4393c3), const_srgb!(0x2166ac), const_srgb!(0x053061),
+]);
+
+/// ColorBrewer red to blue through pale yellow.
+pub static RD_YL_BU: Colormap = Colormap::new("RdYlBu", ColormapKind::Diverging, &[
+    const_srgb!(0xa50026), const_srgb!(0xd73027), const_srgb!(0xf46d43), const_srgb!(0xfdae61),
+    const_srgb!(0xfee090), const_srgb!(0xffffbf), const_srgb!(0xe0f3f8), const_srgb!(0xabd9e9),
+    const_srgb!(0x74add1), const_srgb!(0x4575b4), const_srgb!(0x313695),
+]);
+
+/// ColorBrewer red through yellow to purple.
+pub static SPECTRAL: Colormap = Colormap::new("Spectral", ColormapKind::Diverging, &[
+    const_srgb!(0x9e0142), const_srgb!(0xd53e4f), const_srgb!(0xf46d43), const_srgb!(0xfdae61),
+    const_srgb!(0xfee08b), const_srgb!(0xffffbf), const_srgb!(0xe6f598), const_srgb!(0xabdda4),
+    const_srgb!(0x66c2a5), const_srgb!(0x3288bd), const_srgb!(0x5e4fa2),
+]);
+
+/// ColorBrewer bold colors.
+pub static SET1: Colormap = Colormap::new("Set1", ColormapKind::Qualitative, &[
+    const_srgb!(0xe41a1c), const_srgb!(0x377eb8), const_srgb!(0x4daf4a), const_srgb!(0x984ea3),
+    const_srgb!(0xff7f00), const_srgb!(0xffff33), const_srgb!(0xa65628), const_srgb!(0xf781bf),
+    const_srgb!(0x999999),
+]);
+
+/// ColorBrewer soft colors.
+pub static SET2: Colormap = Colormap::new("Set2", ColormapKind::Qualitative, &[
+    const_srgb!(0x66c2a5), const_srgb!(0xfc8d62), const_srgb!(0x8da0cb), const_srgb!(0xe78ac3),
+    const_srgb!(0xa6d854), const_srgb!(0xffd92f), const_srgb!(0xe5c494), const_srgb!(0xb3b3b3),
+]);
+
+/// ColorBrewer dark colors, readable on white.
+pub static DARK2: Colormap = Colormap::new("Dark2", ColormapKind::Qualitative, &[
+    const_srgb!(0x1b9e77), const_srgb!(0xd95f02), const_srgb!(0x7570b3), const_srgb!(0xe7298a),
+    const_srgb!(0x66a61e), const_srgb!(0xe6ab02), const_srgb!(0xa6761d), const_srgb!(0x666666),
+]);
+
+/// ColorBrewer light and dark pairs of six hues.
+pub static PAIRED: Colormap = Colormap::new("Paired", ColormapKind::Qualitative, &[
+    const_srgb!(0xa6cee3), const_srgb!(0x1f78b4), const_srgb!(0xb2df8a), const_srgb!(0x33a02c),
+    const_srgb!(0xfb9a99), const_srgb!(0xe31a1c), const_srgb!(0xfdbf6f), const_srgb!(0xff7f00),
+    const_srgb!(0xcab2d6), const_srgb!(0x6a3d9a), const_srgb!(0xffff99), const_srgb!(0xb15928),
+]);
+
+/// The Tableau 10 palette.
+pub static TABLEAU10: Colormap = Colormap::new("Tableau10", ColormapKind::Qualitative, &[
+    const_srgb!(0x4e79a7), const_srgb!(0xf28e2c), const_srgb!(0xe15759), const_srgb!(0x76b7b2),
+    const_srgb!(0x59a14f), const_srgb!(0xedc949), const_srgb!(0xaf7aa1), const_srgb!(0xff9da7),
+    const_srgb!(0x9c755f), const_srgb!(0xbab0ab),
+]);
+
+/// Every built-in colormap.
+pub static COLORMAPS: [&Colormap; 16] = [
+    &VIRIDIS, &MAGMA, &INFERNO, &PLASMA, &CIVIDIS,
+    &BLUES, &GREENS, &REDS,
+    &RD_BU, &RD_YL_BU, &SPECTRAL,
+    &SET1, &SET2, &DARK2, &PAIRED, &TABLEAU10,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gradient::Interpolation;
+    use Srgb;
+
+    #[test]
+    fn test_sample() {
+        assert_eq!(VIRIDIS.sample::<u8>(0.0), Srgb::new(0x44, 0x01, 0x54));
+        assert_eq!(VIRIDIS.sample::<u8>(1.0), Srgb::new(0xfd, 0xe7, 0x25));
+        assert_eq!(VIRIDIS.sample::<u8>(2.0), VIRIDIS.sample::<u8>(1.0));
+        assert_eq!(VIRIDIS.sample::<u8>(-1.0), VIRIDIS.sample::<u8>(0.0));
+        assert_eq!(VIRIDIS.sample::<u8>(f64::NAN), VIRIDIS.sample::<u8>(0.0));
+        // halfway between the fifth and sixth stop
+        let mid: Srgb<f64> = VIRIDIS.sample(0.5);
+        assert!((mid.r * 255.0 - (0x26 + 0x1f) as f64 / 2.0).abs() < 1e-9);
+        let _: Srgb<u8> = RD_BU.sample_with(0.25, Interpolation::Oklab);
+        assert_eq!(RD_BU.sample::<u8>(0.5), Srgb::new(0xf7, 0xf7, 0xf7));
+    }
+
+    #[test]
+    fn test_qualitative() {
+        assert_eq!(SET1.sample::<u8>(0.0), Srgb::new(0xe4, 0x1a, 0x1c));
+        assert_eq!(SET1.sample::<u8>(0.12), Srgb::new(0x37, 0x7e, 0xb8));
+        assert_eq!(SET1.sample::<u8>(1.0), Srgb::new(0x99, 0x99, 0x99));
+        let s: Vec<Srgb<u8>> = DARK2.samples(10);
+        assert_eq!(s.len(), 10);
+        assert_eq!(s[8], s[0]);
+        assert_eq!(&s[..8], DARK2.colors());
+    }
+
+    #[test]
+    fn test_colormaps() {
+        for map in COLORMAPS.iter() {
+            assert_eq!(Colormap::by_name(map.name()), Some(*map));
+            let s: Vec<Srgb<u8>> = map.samples(5);
+            assert_eq!(s.len(), 5);
+            let r = map.reversed();
+            assert_eq!(r.colors()[0], *map.colors().last().unwrap());
+            if map.kind() != ColormapKind::Qualitative {
+                assert_eq!(s[0], map.colors()[0]);
+                assert_eq!(s[4], r.colors()[0]);
+            }
+        }
+        assert_eq!(Colormap::by_name("VIRIDIS"), Some(&VIRIDIS));
+        assert_eq!(VIRIDIS.reversed().name(), "viridis_r");
+        assert!(Colormap::by_name("jet").is_none());
+    }
+
+    #[test]
+    fn test_from_stops() {
+        let map = Colormap::from_stops("custom", vec![
+            (1.0, Srgb::new(255, 255, 255)), (0.0, Srgb::new(0, 0, 0)), (0.25, Srgb::new(0, 0, 128)),
+        ]);
+        assert_eq!(map.sample::<u8>(0.0), Srgb::new(0, 0, 0));
+        assert_eq!(map.sample::<u8>(0.25), Srgb::new(0, 0, 128));
+        assert_eq!(map.sample::<u8>(1.0), Srgb::new(255, 255, 255));
+        // perceptual mid gray is a little darker than the byte midpoint
+        let gray: Srgb<u8> = Colormap::from_stops("gray", vec![(0.0, Srgb::new(0, 0, 0)), (1.0, Srgb::new(255, 255, 255))])
+            .interpolation(Interpolation::Cam16Ucs)
+            .sample(0.5);
+        assert!(gray.r > 100 && gray.r < 128, "{:?}", gray);
+        let r = map.reversed();
+        assert_eq!(r.sample::<u8>(0.75), Srgb::new(0, 0, 128));
+
+        let flat = Colormap::from_stops("one", vec![(0.5, Srgb::new(1, 2, 3))]);
+        assert_eq!(flat.sample::<u8>(0.0), Srgb::new(1, 2, 3));
+        assert_eq!(flat.sample::<u8>(1.0), Srgb::new(1, 2, 3));
+    }
+
+    #[test]
+    fn test_lightness_profile() {
+        for map in &[&VIRIDIS, &MAGMA, &INFERNO, &PLASMA, &CIVIDIS, &BLUES] {
+            assert!(map.is_lightness_monotonic(0.5), "{}", map.name());
+        }
+        assert!(!RD_BU.is_lightness_monotonic(0.5));
+        let profile = VIRIDIS.lightness_profile(3);
+        assert!(profile[0] < profile[1] && profile[1] < profile[2]);
+        // a rainbow through yellow is not
+        let rainbow = Colormap::from_stops("rainbow", vec![
+            (0.0, Srgb::new(0, 0, 255)), (0.5, Srgb::new(255, 255, 0)), (1.0, Srgb::new(255, 0, 0)),
+        ]);
+        assert!(!rainbow.is_lightness_monotonic(0.5));
+    }
+}
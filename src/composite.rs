@@ -0,0 +1,120 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Porter-Duff alpha compositing.
+//!
+//! https://www.w3.org/TR/compositing-1/#advancedcompositing
+
+use {AlphaColor, Channel};
+use blend::Blend;
+
+/// A Porter-Duff operator, combining a source color with a destination
+/// (backdrop) color.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Operator {
+    Clear,
+    Source,
+    Destination,
+    SourceOver,
+    DestinationOver,
+    SourceIn,
+    DestinationIn,
+    SourceOut,
+    DestinationOut,
+    SourceAtop,
+    DestinationAtop,
+    Xor,
+}
+
+impl Operator {
+    /// The fractions of the source and of the destination that make it into
+    /// the result, given their alphas.
+    #[inline]
+    fn factors(self, a_s: f64, a_d: f64) -> (f64, f64) {
+        match self {
+            Operator::Clear           => (0.0, 0.0),
+            Operator::Source          => (1.0, 0.0),
+            Operator::Destination     => (0.0, 1.0),
+            Operator::SourceOver      => (1.0, 1.0 - a_s),
+            Operator::DestinationOver => (1.0 - a_d, 1.0),
+            Operator::SourceIn        => (a_d, 0.0),
+            Operator::DestinationIn   => (0.0, a_s),
+            Operator::SourceOut       => (1.0 - a_d, 0.0),
+            Operator::DestinationOut  => (0.0, 1.0 - a_s),
+            Operator::SourceAtop      => (a_d, 1.0 - a_s),
+            Operator::DestinationAtop => (1.0 - a_d, a_s),
+            Operator::Xor             => (1.0 - a_d, 1.0 - a_s),
+        }
+    }
+
+    /// Composites premultiplied components and alphas.
+    pub(crate) fn apply(self, src: [f64; 3], a_s: f64, dst: [f64; 3], a_d: f64) -> ([f64; 3], f64) {
+        let (fa, fb) = self.factors(a_s, a_d);
+        ([src[0] * fa + dst[0] * fb, src[1] * fa + dst[1] * fb, src[2] * fa + dst[2] * fb],
+         a_s * fa + a_d * fb)
+    }
+
+    /// Composites two colors with straight (unassociated) alpha. Use
+    /// `Premultiplied::composite` for premultiplied colors.
+    pub fn composite<T: Channel, C: Blend>(self, src: AlphaColor<T, C>, dst: AlphaColor<T, C>) -> AlphaColor<T, C> {
+        let (a_s, a_d) = (src.a.to_channel_f64(), dst.a.to_channel_f64());
+        let (s, d) = (src.c.to_components(), dst.c.to_components());
+        let (c, a) = self.apply([s[0] * a_s, s[1] * a_s, s[2] * a_s], a_s,
+                                [d[0] * a_d, d[1] * a_d, d[2] * a_d], a_d);
+        let c = if a == 0.0 { [0.0; 3] } else { [c[0] / a, c[1] / a, c[2] / a] };
+        AlphaColor { c: C::from_components(c), a: Channel::from(a) }
+    }
+}
+
+impl<T: Channel, C: Blend> AlphaColor<T, C> {
+    /// Composites `self` over `dst`, the most common operator, with straight
+    /// alpha.
+    #[inline]
+    pub fn over(self, dst: AlphaColor<T, C>) -> AlphaColor<T, C> {
+        Operator::SourceOver.composite(self, dst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Operator;
+    use Rgba;
+
+    fn assert_rgba_eq(a: Rgba<f64>, b: Rgba<f64>) {
+        assert!((a.c.r - b.c.r).abs() < 1e-9 && (a.c.g - b.c.g).abs() < 1e-9 &&
+                (a.c.b - b.c.b).abs() < 1e-9 && (a.a - b.a).abs() < 1e-9,
+                "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn test_straight() {
+        let src = Rgba::<f64>::new(1.0, 0.0, 0.0, 0.5);
+        let dst = Rgba::<f64>::new(0.0, 0.0, 1.0, 1.0);
+        let clear = Rgba::<f64>::new(0.0, 0.0, 0.0, 0.0);
+        assert_rgba_eq(src.over(dst),                                     Rgba::new(0.5, 0.0, 0.5, 1.0));
+        assert_rgba_eq(Operator::Clear.composite(src, dst),               clear);
+        assert_rgba_eq(Operator::Source.composite(src, dst),              src);
+        assert_rgba_eq(Operator::Destination.composite(src, dst),         dst);
+        assert_rgba_eq(Operator::DestinationOver.composite(src, dst),     dst);
+        assert_rgba_eq(Operator::SourceIn.composite(src, dst),            src);
+        assert_rgba_eq(Operator::DestinationIn.composite(src, dst),       Rgba::new(0.0, 0.0, 1.0, 0.5));
+        assert_rgba_eq(Operator::SourceOut.composite(src, dst),           clear);
+        assert_rgba_eq(Operator::DestinationOut.composite(src, dst),      Rgba::new(0.0, 0.0, 1.0, 0.5));
+        assert_rgba_eq(Operator::SourceAtop.composite(src, dst),          Rgba::new(0.5, 0.0, 0.5, 1.0));
+        assert_rgba_eq(Operator::DestinationAtop.composite(src, dst),     Rgba::new(0.0, 0.0, 1.0, 0.5));
+        assert_rgba_eq(Operator::Xor.composite(src, dst),                 Rgba::new(0.0, 0.0, 1.0, 0.5));
+        assert_rgba_eq(Operator::SourceOver.composite(clear, clear),      clear);
+    }
+}
@@ -0,0 +1,371 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `Default` for the color types, and associated constants for black, white
+//! and the primaries.
+//!
+//! Every color defaults to black, and colors with alpha to transparent
+//! black. The constants are defined for `u8`, `u16`, `f32` and `f64`
+//! channels, except those of types with a hue, which are only defined for
+//! floats, and those of the perceptual spaces, whose components are not
+//! channel values. The channel type has to be spelled out, as in
+//! `Rgb::<u8>::RED`.
+//!
+//! `Xyz` and `XyY` white is D65, matching `Rgb`, and `Lab` and `Luv` white
+//! is relative to it. YCbCr is full range. Absolute spaces such as
+//! `Jzazbz`, and `Cam16`, which depends on the viewing conditions, only have
+//! `BLACK`.
+
+use angle::Deg;
+
+use num::{zero, Zero};
+
+use AlphaColor;
+use encoding::Encoding;
+use {Channel, Rgb, Rg, Srgb, Luma, Hsv, Hsl, Hwb, Hsi, Hsp, Ryb, Cmyk, YCbCr};
+use {Xyz, XyY, Lab, Lch, Luv, Lchuv, Oklab, Oklch, Hsluv, Hpluv, Cam16, Cam16Ucs, Jzazbz, ICtCp};
+
+macro_rules! zero_defaults {
+    ($($name:ident { $($field:ident),* };)*) => {
+        $(
+            impl<T: Channel> Default for $name<T> {
+                #[inline]
+                fn default() -> $name<T> {
+                    $name { $($field: zero()),* }
+                }
+            }
+        )*
+    }
+}
+
+impl<T: Channel, E: Encoding> Default for Rgb<T, E> {
+    #[inline]
+    fn default() -> Rgb<T, E> {
+        Rgb::new(zero(), zero(), zero())
+    }
+}
+
+zero_defaults! {
+    Rg { r, g };
+    Luma { l };
+    Xyz { x, y, z };
+    XyY { x, y, luminance };
+    Lab { l, a, b };
+    Luv { l, u, v };
+    Oklab { l, a, b };
+    Cam16Ucs { j, a, b };
+    Jzazbz { jz, az, bz };
+    ICtCp { i, ct, cp };
+}
+
+macro_rules! hue_defaults {
+    ($($name:ident { $($field:ident),* };)*) => {
+        $(
+            impl<T: Channel> Default for $name<T> {
+                #[inline]
+                fn default() -> $name<T> {
+                    $name { h: Deg(zero()), $($field: zero()),* }
+                }
+            }
+        )*
+    }
+}
+
+hue_defaults! {
+    Hsv { s, v };
+    Hsl { s, l };
+    Hsi { s, i };
+    Hsp { s, p };
+    Lch { l, c };
+    Lchuv { l, c };
+    Oklch { l, c };
+    Hsluv { s, l };
+    Hpluv { p, l };
+    Cam16 { j, c, m, s, q };
+}
+
+impl<T: Channel> Default for Hwb<T> {
+    #[inline]
+    fn default() -> Hwb<T> {
+        Hwb { h: Deg(zero()), w: zero(), b: T::max() }
+    }
+}
+
+impl<T: Channel> Default for Cmyk<T> {
+    #[inline]
+    fn default() -> Cmyk<T> {
+        Cmyk { c: zero(), m: zero(), y: zero(), k: T::max() }
+    }
+}
+
+impl<T: Channel> Default for Ryb<T> {
+    #[inline]
+    fn default() -> Ryb<T> {
+        Ryb { r: T::max(), y: T::max(), b: T::max() }
+    }
+}
+
+impl<T: Channel> Default for YCbCr<T> {
+    #[inline]
+    fn default() -> YCbCr<T> {
+        YCbCr { y: zero(), cb: 128u8.to_channel(), cr: 128u8.to_channel() }
+    }
+}
+
+impl<T: Zero, C: Default> Default for AlphaColor<T, C> {
+    /// Transparent black.
+    #[inline]
+    fn default() -> AlphaColor<T, C> {
+        AlphaColor { c: C::default(), a: zero() }
+    }
+}
+
+macro_rules! rgb_consts {
+    ($T:ty, $name:ident, $zero:expr, $max:expr) => {
+        pub const BLACK: $name<$T> = $name::new($zero, $zero, $zero);
+        pub const WHITE: $name<$T> = $name::new($max, $max, $max);
+        pub const RED: $name<$T> = $name::new($max, $zero, $zero);
+        pub const GREEN: $name<$T> = $name::new($zero, $max, $zero);
+        pub const BLUE: $name<$T> = $name::new($zero, $zero, $max);
+        pub const CYAN: $name<$T> = $name::new($zero, $max, $max);
+        pub const MAGENTA: $name<$T> = $name::new($max, $zero, $max);
+        pub const YELLOW: $name<$T> = $name::new($max, $max, $zero);
+    }
+}
+
+macro_rules! rgba_consts {
+    ($T:ty, $name:ident, $zero:expr, $max:expr) => {
+        pub const BLACK: AlphaColor<$T, $name<$T>> = AlphaColor { c: $name::<$T>::BLACK, a: $max };
+        pub const WHITE: AlphaColor<$T, $name<$T>> = AlphaColor { c: $name::<$T>::WHITE, a: $max };
+        pub const RED: AlphaColor<$T, $name<$T>> = AlphaColor { c: $name::<$T>::RED, a: $max };
+        pub const GREEN: AlphaColor<$T, $name<$T>> = AlphaColor { c: $name::<$T>::GREEN, a: $max };
+        pub const BLUE: AlphaColor<$T, $name<$T>> = AlphaColor { c: $name::<$T>::BLUE, a: $max };
+        pub const CYAN: AlphaColor<$T, $name<$T>> = AlphaColor { c: $name::<$T>::CYAN, a: $max };
+        pub const MAGENTA: AlphaColor<$T, $name<$T>> = AlphaColor { c: $name::<$T>::MAGENTA, a: $max };
+        pub const YELLOW: AlphaColor<$T, $name<$T>> = AlphaColor { c: $name::<$T>::YELLOW, a: $max };
+        pub const TRANSPARENT: AlphaColor<$T, $name<$T>> = AlphaColor { c: $name::<$T>::BLACK, a: $zero };
+    }
+}
+
+macro_rules! channel_consts {
+    ($($T:ident, $zero:expr, $half:expr, $max:expr;)*) => {
+        $(
+            impl Rgb<$T> {
+                rgb_consts!($T, Rgb, $zero, $max);
+            }
+
+            impl Srgb<$T> {
+                rgb_consts!($T, Srgb, $zero, $max);
+            }
+
+            impl AlphaColor<$T, Rgb<$T>> {
+                rgba_consts!($T, Rgb, $zero, $max);
+            }
+
+            impl AlphaColor<$T, Srgb<$T>> {
+                rgba_consts!($T, Srgb, $zero, $max);
+            }
+
+            impl Luma<$T> {
+                pub const BLACK: Luma<$T> = Luma { l: $zero };
+                pub const WHITE: Luma<$T> = Luma { l: $max };
+            }
+
+            impl Cmyk<$T> {
+                pub const BLACK: Cmyk<$T> = Cmyk { c: $zero, m: $zero, y: $zero, k: $max };
+                pub const WHITE: Cmyk<$T> = Cmyk { c: $zero, m: $zero, y: $zero, k: $zero };
+                pub const CYAN: Cmyk<$T> = Cmyk { c: $max, m: $zero, y: $zero, k: $zero };
+                pub const MAGENTA: Cmyk<$T> = Cmyk { c: $zero, m: $max, y: $zero, k: $zero };
+                pub const YELLOW: Cmyk<$T> = Cmyk { c: $zero, m: $zero, y: $max, k: $zero };
+            }
+
+            impl Ryb<$T> {
+                pub const BLACK: Ryb<$T> = Ryb { r: $max, y: $max, b: $max };
+                pub const WHITE: Ryb<$T> = Ryb { r: $zero, y: $zero, b: $zero };
+                pub const RED: Ryb<$T> = Ryb { r: $max, y: $zero, b: $zero };
+                pub const YELLOW: Ryb<$T> = Ryb { r: $zero, y: $max, b: $zero };
+                pub const BLUE: Ryb<$T> = Ryb { r: $zero, y: $zero, b: $max };
+                pub const ORANGE: Ryb<$T> = Ryb { r: $max, y: $max, b: $zero };
+                pub const GREEN: Ryb<$T> = Ryb { r: $zero, y: $max, b: $max };
+                pub const PURPLE: Ryb<$T> = Ryb { r: $max, y: $zero, b: $max };
+            }
+
+            impl YCbCr<$T> {
+                pub const BLACK: YCbCr<$T> = YCbCr { y: $zero, cb: $half, cr: $half };
+                pub const WHITE: YCbCr<$T> = YCbCr { y: $max, cb: $half, cr: $half };
+            }
+        )*
+    }
+}
+
+channel_consts! {
+    u8,  0,   0x80,            0xFF;
+    u16, 0,   0x8080,          0xFFFF;
+    f32, 0.0, 128.0 / 255.0,   1.0;
+    f64, 0.0, 128.0 / 255.0,   1.0;
+}
+
+macro_rules! hue_consts {
+    ($T:ty, $name:ident { $a:ident, $b:ident }, black: $black:expr, white: $white:expr, hue: $hue:expr) => {
+        pub const BLACK: $name<$T> = $name { h: Deg(0.0), $a: $black.0, $b: $black.1 };
+        pub const WHITE: $name<$T> = $name { h: Deg(0.0), $a: $white.0, $b: $white.1 };
+        pub const RED: $name<$T> = $name { h: Deg(0.0), $a: $hue.0, $b: $hue.1 };
+        pub const GREEN: $name<$T> = $name { h: Deg(120.0), $a: $hue.0, $b: $hue.1 };
+        pub const BLUE: $name<$T> = $name { h: Deg(240.0), $a: $hue.0, $b: $hue.1 };
+    }
+}
+
+macro_rules! float_consts {
+    ($($T:ident;)*) => {
+        $(
+            impl Hsv<$T> {
+                hue_consts!($T, Hsv { s, v }, black: (0.0, 0.0), white: (0.0, 1.0), hue: (1.0, 1.0));
+            }
+
+            impl Hsl<$T> {
+                hue_consts!($T, Hsl { s, l }, black: (0.0, 0.0), white: (0.0, 1.0), hue: (1.0, 0.5));
+            }
+
+            impl Hwb<$T> {
+                hue_consts!($T, Hwb { w, b }, black: (0.0, 1.0), white: (1.0, 0.0), hue: (0.0, 0.0));
+            }
+
+            impl Hsi<$T> {
+                pub const BLACK: Hsi<$T> = Hsi { h: Deg(0.0), s: 0.0, i: 0.0 };
+                pub const WHITE: Hsi<$T> = Hsi { h: Deg(0.0), s: 0.0, i: 1.0 };
+            }
+
+            impl Hsp<$T> {
+                pub const BLACK: Hsp<$T> = Hsp { h: Deg(0.0), s: 0.0, p: 0.0 };
+                pub const WHITE: Hsp<$T> = Hsp { h: Deg(0.0), s: 0.0, p: 1.0 };
+            }
+
+            impl Hsluv<$T> {
+                pub const BLACK: Hsluv<$T> = Hsluv { h: Deg(0.0), s: 0.0, l: 0.0 };
+                pub const WHITE: Hsluv<$T> = Hsluv { h: Deg(0.0), s: 0.0, l: 100.0 };
+            }
+
+            impl Hpluv<$T> {
+                pub const BLACK: Hpluv<$T> = Hpluv { h: Deg(0.0), p: 0.0, l: 0.0 };
+                pub const WHITE: Hpluv<$T> = Hpluv { h: Deg(0.0), p: 0.0, l: 100.0 };
+            }
+
+            impl Xyz<$T> {
+                pub const BLACK: Xyz<$T> = Xyz { x: 0.0, y: 0.0, z: 0.0 };
+                pub const WHITE: Xyz<$T> = Xyz { x: 0.95047, y: 1.0, z: 1.08883 };
+            }
+
+            impl XyY<$T> {
+                pub const BLACK: XyY<$T> = XyY { x: 0.31271, y: 0.32902, luminance: 0.0 };
+                pub const WHITE: XyY<$T> = XyY { x: 0.31271, y: 0.32902, luminance: 1.0 };
+            }
+
+            impl Lab<$T> {
+                pub const BLACK: Lab<$T> = Lab { l: 0.0, a: 0.0, b: 0.0 };
+                pub const WHITE: Lab<$T> = Lab { l: 100.0, a: 0.0, b: 0.0 };
+            }
+
+            impl Lch<$T> {
+                pub const BLACK: Lch<$T> = Lch { l: 0.0, c: 0.0, h: Deg(0.0) };
+                pub const WHITE: Lch<$T> = Lch { l: 100.0, c: 0.0, h: Deg(0.0) };
+            }
+
+            impl Luv<$T> {
+                pub const BLACK: Luv<$T> = Luv { l: 0.0, u: 0.0, v: 0.0 };
+                pub const WHITE: Luv<$T> = Luv { l: 100.0, u: 0.0, v: 0.0 };
+            }
+
+            impl Lchuv<$T> {
+                pub const BLACK: Lchuv<$T> = Lchuv { l: 0.0, c: 0.0, h: Deg(0.0) };
+                pub const WHITE: Lchuv<$T> = Lchuv { l: 100.0, c: 0.0, h: Deg(0.0) };
+            }
+
+            impl Oklab<$T> {
+                pub const BLACK: Oklab<$T> = Oklab { l: 0.0, a: 0.0, b: 0.0 };
+                pub const WHITE: Oklab<$T> = Oklab { l: 1.0, a: 0.0, b: 0.0 };
+            }
+
+            impl Oklch<$T> {
+                pub const BLACK: Oklch<$T> = Oklch { l: 0.0, c: 0.0, h: Deg(0.0) };
+                pub const WHITE: Oklch<$T> = Oklch { l: 1.0, c: 0.0, h: Deg(0.0) };
+            }
+
+            impl Cam16<$T> {
+                pub const BLACK: Cam16<$T> = Cam16 { j: 0.0, c: 0.0, h: Deg(0.0), m: 0.0, s: 0.0, q: 0.0 };
+            }
+
+            impl Cam16Ucs<$T> {
+                pub const BLACK: Cam16Ucs<$T> = Cam16Ucs { j: 0.0, a: 0.0, b: 0.0 };
+            }
+
+            impl Jzazbz<$T> {
+                pub const BLACK: Jzazbz<$T> = Jzazbz { jz: 0.0, az: 0.0, bz: 0.0 };
+            }
+
+            impl ICtCp<$T> {
+                pub const BLACK: ICtCp<$T> = ICtCp { i: 0.0, ct: 0.0, cp: 0.0 };
+            }
+        )*
+    }
+}
+
+float_consts! {
+    f32;
+    f64;
+}
+
+#[cfg(test)]
+mod tests {
+    use {Rgb, Rgba, Srgb, Srgba, ToRgb, Hsv, Hsl, Hwb, Ryb, Cmyk, YCbCr, Lab, ToLab, Oklab, ToOklab, Xyz, ToXyz};
+    use srgb::consts;
+
+    #[test]
+    fn test_defaults() {
+        assert_eq!(Rgb::<u8>::default(), Rgb::<u8>::BLACK);
+        assert_eq!(Rgba::<u8>::default(), Rgba::<u8>::TRANSPARENT);
+        assert_eq!(Srgba::<f32>::default(), Srgba::<f32>::TRANSPARENT);
+        for black in [Hsv::<f64>::default().to_rgb::<u8>(), Hwb::<f64>::default().to_rgb(), Ryb::<f64>::default().to_rgb(),
+                      Cmyk::<f64>::default().to_rgb(), YCbCr::<u8>::default().to_rgb()].iter() {
+            assert_eq!(*black, Rgb::<u8>::BLACK);
+        }
+    }
+
+    #[test]
+    fn test_consts() {
+        assert_eq!(Srgb::<u8>::RED, consts::RED);
+        assert_eq!(Rgb::<u16>::YELLOW, Rgb::<u8>::YELLOW.to_rgb());
+        assert_eq!(Rgba::<u8>::BLUE, Rgba::new(0, 0, 0xFF, 0xFF));
+        let cases = [(Hsv::<f64>::RED, Hsl::<f64>::RED, Hwb::<f64>::RED, Rgb::<f64>::RED),
+                     (Hsv::<f64>::GREEN, Hsl::<f64>::GREEN, Hwb::<f64>::GREEN, Rgb::<f64>::GREEN),
+                     (Hsv::<f64>::BLUE, Hsl::<f64>::BLUE, Hwb::<f64>::BLUE, Rgb::<f64>::BLUE),
+                     (Hsv::<f64>::WHITE, Hsl::<f64>::WHITE, Hwb::<f64>::WHITE, Rgb::<f64>::WHITE),
+                     (Hsv::<f64>::BLACK, Hsl::<f64>::BLACK, Hwb::<f64>::BLACK, Rgb::<f64>::BLACK)];
+        for &(hsv, hsl, hwb, rgb) in cases.iter() {
+            assert_eq!(hsv.to_rgb::<f64>(), rgb);
+            assert_eq!(hsl.to_rgb::<f64>(), rgb);
+            assert_eq!(hwb.to_rgb::<f64>(), rgb);
+        }
+        assert_eq!(Ryb::<f64>::GREEN.to_rgb::<f64>(), Rgb::<f64>::GREEN);
+        assert_eq!(Cmyk::<u8>::CYAN.to_rgb::<u8>(), Rgb::<u8>::CYAN);
+        assert_eq!(YCbCr::<u8>::WHITE.to_rgb::<u8>(), Rgb::<u8>::WHITE);
+        let xyz: Xyz<f64> = Rgb::<f64>::WHITE.to_xyz();
+        let white = Xyz::<f64>::WHITE;
+        assert!((xyz.x - white.x).abs() < 1e-9 && (xyz.y - white.y).abs() < 1e-9 && (xyz.z - white.z).abs() < 1e-9);
+        let lab: Lab<f64> = Srgb::<f64>::WHITE.to_rgb::<f64>().to_lab();
+        assert!((lab.l - Lab::<f64>::WHITE.l).abs() < 1e-9 && lab.a.abs() < 1e-9 && lab.b.abs() < 1e-9);
+        let oklab: Oklab<f64> = Rgb::<f64>::WHITE.to_oklab();
+        assert!((oklab.l - Oklab::<f64>::WHITE.l).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,245 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Contrast metrics for accessibility checks.
+//!
+//! https://www.w3.org/TR/WCAG21/#dfn-contrast-ratio
+//! https://github.com/Myndex/SAPC-APCA
+//!
+//! Colors are taken through `to_rgb`, which is linear light. Wrap
+//! gamma-encoded values such as hex codes in `Srgb` so they are decoded first.
+//!
+//! `adjust_for_contrast` goes the other way and finds the nearest color that
+//! meets a WCAG or APCA target.
+
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use math::*;
+
+use {Channel, Rgb, ToRgb, Srgb, ToSrgb, ToLuma, ToOklch};
+use gamut::{map_to_srgb, GamutMapping};
+
+impl<T: Channel> Rgb<T> {
+    /// The relative luminance of the color, from `0` for black to `1` for
+    /// white, as defined by WCAG 2.1.
+    #[inline]
+    pub fn relative_luminance(&self) -> f64 {
+        self.to_luma::<f64>().l
+    }
+}
+
+impl<T: Channel> Srgb<T> {
+    /// The relative luminance of the color, decoding it first.
+    #[inline]
+    pub fn relative_luminance(&self) -> f64 {
+        self.to_linear::<f64>().relative_luminance()
+    }
+}
+
+/// The WCAG 2.1 contrast ratio between two colors, from `1` for identical
+/// luminances to `21` for black on white. The order of the colors does not
+/// matter.
+pub fn contrast_ratio<A: ToRgb, B: ToRgb>(a: &A, b: &B) -> f64 {
+    let la = a.to_rgb::<f64>().relative_luminance();
+    let lb = b.to_rgb::<f64>().relative_luminance();
+    let (hi, lo) = if la > lb { (la, lb) } else { (lb, la) };
+    (hi + 0.05) / (lo + 0.05)
+}
+
+/// Whether normal text meets WCAG level AA, a contrast ratio of 4.5:1.
+#[inline]
+pub fn is_aa<A: ToRgb, B: ToRgb>(a: &A, b: &B) -> bool {
+    contrast_ratio(a, b) >= 4.5
+}
+
+/// Whether large text meets WCAG level AA, a contrast ratio of 3:1.
+#[inline]
+pub fn is_aa_large<A: ToRgb, B: ToRgb>(a: &A, b: &B) -> bool {
+    contrast_ratio(a, b) >= 3.0
+}
+
+/// Whether normal text meets WCAG level AAA, a contrast ratio of 7:1.
+#[inline]
+pub fn is_aaa<A: ToRgb, B: ToRgb>(a: &A, b: &B) -> bool {
+    contrast_ratio(a, b) >= 7.0
+}
+
+/// Whether large text meets WCAG level AAA, a contrast ratio of 4.5:1.
+#[inline]
+pub fn is_aaa_large<A: ToRgb, B: ToRgb>(a: &A, b: &B) -> bool {
+    contrast_ratio(a, b) >= 4.5
+}
+
+/// The APCA (0.0.98G) lightness contrast `Lc` of `text` drawn on
+/// `background`. It ranges from about `106` for black on white to about `-108`
+/// for white on black; the sign gives the polarity and the magnitude is
+/// compared against the APCA thresholds, e.g. `75` for body text.
+pub fn apca_contrast<A: ToSrgb, B: ToSrgb>(text: &A, background: &B) -> f64 {
+    // APCA estimates screen luminance with a plain 2.4 exponent
+    fn luminance(c: Srgb<f64>) -> f64 {
+        let y = 0.2126729 * c.r.powf(2.4) + 0.7151522 * c.g.powf(2.4) + 0.0721750 * c.b.powf(2.4);
+        // soft clamp near black to model flare
+        if y < 0.022 { y + (0.022 - y).powf(1.414) } else { y }
+    }
+
+    let txt = luminance(text.to_srgb());
+    let bg = luminance(background.to_srgb());
+    if (bg - txt).abs() < 0.0005 {
+        return 0.0;
+    }
+    let lc = if bg > txt {
+        let sapc = (bg.powf(0.56) - txt.powf(0.57)) * 1.14;
+        if sapc < 0.1 { 0.0 } else { sapc - 0.027 }
+    } else {
+        let sapc = (bg.powf(0.65) - txt.powf(0.62)) * 1.14;
+        if sapc > -0.1 { 0.0 } else { sapc + 0.027 }
+    };
+    lc * 100.0
+}
+
+/// A contrast requirement for `adjust_for_contrast`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ContrastTarget {
+    /// A minimum WCAG 2.1 contrast ratio, such as `4.5` for AA.
+    Wcag(f64),
+    /// A minimum APCA `Lc` magnitude, such as `75` for body text.
+    Apca(f64),
+}
+
+impl ContrastTarget {
+    /// Whether `fg` on `bg` meets the target.
+    pub fn is_met<A: ToSrgb, B: ToSrgb>(self, fg: &A, bg: &B) -> bool {
+        match self {
+            ContrastTarget::Wcag(ratio) => contrast_ratio(&fg.to_srgb::<f64>(), &bg.to_srgb::<f64>()) >= ratio,
+            ContrastTarget::Apca(lc) => apca_contrast(fg, bg).abs() >= lc,
+        }
+    }
+}
+
+/// The color closest to `fg` in Oklch lightness that meets `target` on
+/// `bg`, keeping the hue and as much of the chroma as the sRGB gamut
+/// allows. `fg` comes back unchanged if it already meets the target, and
+/// `None` if neither lightening nor darkening it can.
+pub fn adjust_for_contrast<A: ToSrgb, B: ToSrgb, U: Channel>(fg: &A, bg: &B, target: ContrastTarget) -> Option<Srgb<U>> {
+    let bg = bg.to_srgb::<f64>();
+    let fg = fg.to_srgb::<f64>();
+    if target.is_met(&fg, &bg) {
+        return Some(fg.to_srgb());
+    }
+    let lch = fg.to_oklch::<f64>();
+    let at = |l: f64| -> Srgb<f64> {
+        let mut c = lch;
+        c.l = l;
+        map_to_srgb(&c, GamutMapping::Css)
+    };
+
+    // Walks from the current lightness towards `end` until the target is
+    // met, then bisects the last step.
+    let search = |end: f64| -> Option<f64> {
+        const STEPS: usize = 100;
+        let mut prev = lch.l;
+        for i in 1..=STEPS {
+            let l = lch.l + (end - lch.l) * i as f64 / STEPS as f64;
+            if target.is_met(&at(l), &bg) {
+                let (mut fail, mut pass) = (prev, l);
+                for _ in 0..24 {
+                    let mid = (fail + pass) / 2.0;
+                    if target.is_met(&at(mid), &bg) { pass = mid } else { fail = mid }
+                }
+                return Some(pass);
+            }
+            prev = l;
+        }
+        None
+    };
+
+    let l = match (search(1.0), search(0.0)) {
+        (Some(up), Some(down)) => if up - lch.l <= lch.l - down { up } else { down },
+        (Some(l), None) | (None, Some(l)) => l,
+        (None, None) => return None,
+    };
+    Some(at(l).to_srgb())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use {Rgb, Srgb};
+
+    #[test]
+    fn test_relative_luminance() {
+        assert_eq!(Rgb::<u8>::new(0xFF, 0xFF, 0xFF).relative_luminance(), 1.0);
+        assert_eq!(Rgb::<u8>::new(0x00, 0x00, 0x00).relative_luminance(), 0.0);
+        assert!((Srgb::<u8>::new(0x77, 0x77, 0x77).relative_luminance() - 0.1845).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_contrast_ratio() {
+        let white = Srgb::<u8>::new(0xFF, 0xFF, 0xFF);
+        let black = Srgb::<u8>::new(0x00, 0x00, 0x00);
+        assert!((contrast_ratio(&black, &white) - 21.0).abs() < 1e-9);
+        assert_eq!(contrast_ratio(&white, &black), contrast_ratio(&black, &white));
+        assert_eq!(contrast_ratio(&white, &white), 1.0);
+
+        // #777 on white is the classic just-below-AA gray
+        let gray = Srgb::<u8>::new(0x77, 0x77, 0x77);
+        assert!((contrast_ratio(&gray, &white) - 4.48).abs() < 1e-2);
+        assert!(!is_aa(&gray, &white) && is_aa_large(&gray, &white));
+        let gray = Srgb::<u8>::new(0x76, 0x76, 0x76);
+        assert!(is_aa(&gray, &white) && is_aaa_large(&gray, &white) && !is_aaa(&gray, &white));
+        assert!(is_aaa(&black, &white));
+    }
+
+    #[test]
+    fn test_apca_contrast() {
+        let white = Srgb::<u8>::new(0xFF, 0xFF, 0xFF);
+        let black = Srgb::<u8>::new(0x00, 0x00, 0x00);
+        let gray = Srgb::<u8>::new(0x88, 0x88, 0x88);
+        assert!((apca_contrast(&black, &white) - 106.04).abs() < 0.01);
+        assert!((apca_contrast(&white, &black) + 107.88).abs() < 0.01);
+        assert!((apca_contrast(&gray, &white) - 63.06).abs() < 0.01);
+        assert!((apca_contrast(&white, &gray) + 68.54).abs() < 0.01);
+        assert_eq!(apca_contrast(&gray, &gray), 0.0);
+    }
+
+    #[test]
+    fn test_adjust_for_contrast() {
+        let white = Srgb::<u8>::new(0xFF, 0xFF, 0xFF);
+        let black = Srgb::<u8>::new(0x00, 0x00, 0x00);
+        let gray = Srgb::<u8>::new(0x77, 0x77, 0x77);
+
+        let fixed: Srgb<u8> = adjust_for_contrast(&gray, &white, ContrastTarget::Wcag(4.5)).unwrap();
+        assert!(is_aa(&fixed, &white));
+        // the smallest change: one step darker than #777
+        assert_eq!(fixed, Srgb::new(0x76, 0x76, 0x76));
+
+        // already fine
+        assert_eq!(adjust_for_contrast::<_, _, u8>(&black, &white, ContrastTarget::Wcag(7.0)), Some(black));
+
+        // a light blue on a dark background is lightened, keeping its hue
+        let navy = Srgb::<u8>::new(0x10, 0x18, 0x40);
+        let blue = Srgb::<u8>::new(0x30, 0x50, 0xC0);
+        let fixed: Srgb<f64> = adjust_for_contrast(&blue, &navy, ContrastTarget::Wcag(7.0)).unwrap();
+        assert!(ContrastTarget::Wcag(7.0).is_met(&fixed, &navy));
+        assert!(fixed.to_oklch::<f64>().l > blue.to_oklch::<f64>().l);
+        assert!((fixed.to_oklch::<f64>().h.0 - blue.to_oklch::<f64>().h.0).abs() < 2.0);
+
+        let fixed: Srgb<f64> = adjust_for_contrast(&gray, &white, ContrastTarget::Apca(75.0)).unwrap();
+        assert!(apca_contrast(&fixed, &white) >= 75.0);
+
+        // nothing reaches 22:1
+        assert_eq!(adjust_for_contrast::<_, _, u8>(&gray, &white, ContrastTarget::Wcag(22.0)), None);
+    }
+}
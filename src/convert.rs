@@ -0,0 +1,71 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use {Channel, FloatChannel};
+use {Rgb, ToRgb, Rgba, ToRgba, Srgb, ToSrgb, Hsv, ToHsv, Hsl, ToHsl, Hwb, ToHwb, Cmyk, ToCmyk};
+use {Hsi, ToHsi, Hsp, ToHsp, Ryb, ToRyb, Luma, ToLuma};
+use {YCbCr, ToYCbCr, Xyz, ToXyz, XyY, ToXyY, Lab, ToLab, Lch, ToLch, Luv, ToLuv, Lchuv, ToLchuv};
+use {Oklab, ToOklab, Oklch, ToOklch, Hsluv, ToHsluv, Hpluv, ToHpluv, Cam16, ToCam16, Cam16Ucs, ToCam16Ucs};
+use {Jzazbz, ToJzazbz, ICtCp, ToICtCp};
+
+/// Conversion into a color type, named by the destination rather than the
+/// source. `Rgb<u8>: FromColor<C>` holds for every `C: ToRgb`, and so on for
+/// each of the `ToX` traits, which lets generic code take the destination
+/// type as a parameter.
+pub trait FromColor<C> {
+    fn from_color(color: &C) -> Self;
+}
+
+macro_rules! from_color_impls {
+    ($($ty:ident<U: $bound:ident> => $to:ident :: $method:ident;)*) => {
+        $(
+            impl<U: $bound, C: $to> FromColor<C> for $ty<U> {
+                #[inline]
+                fn from_color(color: &C) -> $ty<U> {
+                    color.$method()
+                }
+            }
+        )*
+    }
+}
+
+from_color_impls! {
+    Rgb<U: Channel> => ToRgb::to_rgb;
+    Rgba<U: Channel> => ToRgba::to_rgba;
+    Srgb<U: Channel> => ToSrgb::to_srgb;
+    Hsv<U: Channel> => ToHsv::to_hsv;
+    Hsl<U: Channel> => ToHsl::to_hsl;
+    Hwb<U: Channel> => ToHwb::to_hwb;
+    Hsi<U: Channel> => ToHsi::to_hsi;
+    Hsp<U: Channel> => ToHsp::to_hsp;
+    Ryb<U: Channel> => ToRyb::to_ryb;
+    Cmyk<U: Channel> => ToCmyk::to_cmyk;
+    Luma<U: Channel> => ToLuma::to_luma;
+    YCbCr<U: Channel> => ToYCbCr::to_ycbcr;
+    Xyz<U: FloatChannel> => ToXyz::to_xyz;
+    XyY<U: FloatChannel> => ToXyY::to_xyy;
+    Lab<U: FloatChannel> => ToLab::to_lab;
+    Lch<U: FloatChannel> => ToLch::to_lch;
+    Luv<U: FloatChannel> => ToLuv::to_luv;
+    Lchuv<U: FloatChannel> => ToLchuv::to_lchuv;
+    Oklab<U: FloatChannel> => ToOklab::to_oklab;
+    Oklch<U: FloatChannel> => ToOklch::to_oklch;
+    Hsluv<U: FloatChannel> => ToHsluv::to_hsluv;
+    Hpluv<U: FloatChannel> => ToHpluv::to_hpluv;
+    Cam16<U: FloatChannel> => ToCam16::to_cam16;
+    Cam16Ucs<U: FloatChannel> => ToCam16Ucs::to_cam16_ucs;
+    Jzazbz<U: FloatChannel> => ToJzazbz::to_jzazbz;
+    ICtCp<U: FloatChannel> => ToICtCp::to_ictcp;
+}
@@ -0,0 +1,769 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Parsing and formatting of CSS Color Module Level 4 syntax:
+//! https://www.w3.org/TR/css-color-4/
+
+use core::error::Error;
+use core::fmt;
+use core::str::FromStr;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use math::*;
+
+use angle::*;
+use num;
+
+use {AlphaColor, Rgba, Hsva, Hsla, Hwba, Srgba};
+use {Rgb, ToRgb, Srgb, ToSrgb, Hsv, Hsl, Hwb, Xyz, ToXyz, Lab, ToLab, Lch, ToLch, Oklab, Oklch, WhitePoint};
+use {Channel, FloatChannel};
+use channel::cast;
+use adaptation::Adaptation;
+use rgb_space::RgbSpace;
+
+/// A color parsed from CSS, kept in the color space it was written in.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum CssColor {
+    /// Hex notation, named colors, `rgb()` and `color(srgb ...)`.
+    Srgb(Srgba<f32>),
+    /// `color(srgb-linear ...)`.
+    LinearRgb(Rgba<f32>),
+    /// `hsl()` and `hsla()`.
+    Hsl(Hsla<f32>),
+    /// `hwb()`.
+    Hwb(Hwba<f32>),
+    /// `lab()`, relative to D50 as CSS specifies.
+    Lab(AlphaColor<f32, Lab<f32>>),
+    /// `lch()`, relative to D50 as CSS specifies.
+    Lch(AlphaColor<f32, Lch<f32>>),
+    /// `oklab()`.
+    Oklab(AlphaColor<f32, Oklab<f32>>),
+    /// `oklch()`.
+    Oklch(AlphaColor<f32, Oklch<f32>>),
+    /// `color(xyz ...)` and `color(xyz-d65 ...)`.
+    Xyz(AlphaColor<f32, Xyz<f32>>),
+    /// `color(xyz-d50 ...)`.
+    XyzD50(AlphaColor<f32, Xyz<f32>>),
+    /// `color()` in one of the other predefined RGB spaces, with the values
+    /// encoded by that space's transfer function.
+    Predefined(PredefinedRgb, Rgba<f32>),
+}
+
+/// The RGB spaces of `color()` besides sRGB and linear sRGB.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PredefinedRgb {
+    /// `display-p3`.
+    DisplayP3,
+    /// `rec2020`.
+    Rec2020,
+    /// `a98-rgb`, Adobe RGB (1998).
+    A98Rgb,
+    /// `prophoto-rgb`, relative to D50.
+    ProphotoRgb,
+}
+
+impl PredefinedRgb {
+    /// The name `color()` takes.
+    pub fn name(self) -> &'static str {
+        match self {
+            PredefinedRgb::DisplayP3 => "display-p3",
+            PredefinedRgb::Rec2020 => "rec2020",
+            PredefinedRgb::A98Rgb => "a98-rgb",
+            PredefinedRgb::ProphotoRgb => "prophoto-rgb",
+        }
+    }
+
+    /// The space the values are in.
+    pub fn space(self) -> RgbSpace {
+        match self {
+            PredefinedRgb::DisplayP3 => RgbSpace::DISPLAY_P3,
+            PredefinedRgb::Rec2020 => RgbSpace::REC2020,
+            PredefinedRgb::A98Rgb => RgbSpace::ADOBE_RGB,
+            PredefinedRgb::ProphotoRgb => RgbSpace::PROPHOTO,
+        }
+    }
+
+    fn from_name(name: &str) -> Option<PredefinedRgb> {
+        [PredefinedRgb::DisplayP3, PredefinedRgb::Rec2020, PredefinedRgb::A98Rgb, PredefinedRgb::ProphotoRgb]
+            .iter().cloned().find(|space| space.name() == name)
+    }
+}
+
+impl CssColor {
+    /// The alpha component of the color.
+    pub fn alpha(&self) -> f32 {
+        match *self {
+            CssColor::Srgb(c) => c.a,
+            CssColor::LinearRgb(c) => c.a,
+            CssColor::Hsl(c) => c.a,
+            CssColor::Hwb(c) => c.a,
+            CssColor::Lab(c) => c.a,
+            CssColor::Lch(c) => c.a,
+            CssColor::Oklab(c) => c.a,
+            CssColor::Oklch(c) => c.a,
+            CssColor::Xyz(c) => c.a,
+            CssColor::XyzD50(c) => c.a,
+            CssColor::Predefined(_, c) => c.a,
+        }
+    }
+
+    /// Converts the color to gamma-encoded sRGB. Out of gamut colors are not
+    /// clamped.
+    pub fn to_srgba(&self) -> Srgba<f32> {
+        let encode = |rgb: Rgb<f64>| rgb.to_srgb();
+        let c = match *self {
+            CssColor::Srgb(c) => c.c,
+            CssColor::LinearRgb(c) => encode(c.c.to_rgb()),
+            CssColor::Hsl(c) => { let rgb = c.c.to_rgb::<f32>(); Srgb::new(rgb.r, rgb.g, rgb.b) }
+            CssColor::Hwb(c) => { let rgb = c.c.to_rgb::<f32>(); Srgb::new(rgb.r, rgb.g, rgb.b) }
+            CssColor::Lab(c) => encode(from_d50(c.c.to_xyz_unchecked(WhitePoint::D50))),
+            CssColor::Lch(c) => encode(from_d50(c.c.to_lab::<f64>().to_xyz_unchecked(WhitePoint::D50))),
+            CssColor::Oklab(c) => c.c.to_srgb(),
+            CssColor::Oklch(c) => c.c.to_srgb(),
+            CssColor::Xyz(c) => encode(c.c.to_rgb()),
+            CssColor::XyzD50(c) => encode(from_d50(c.c.to_xyz())),
+            CssColor::Predefined(space, c) => encode(space.space().to_xyz::<f32, f64>(&c.c).to_rgb()),
+        };
+        AlphaColor { c, a: self.alpha() }
+    }
+}
+
+/// Takes D50-relative XYZ, as CSS Lab, LCH and `xyz-d50` use, to linear
+/// sRGB.
+fn from_d50(xyz: Xyz<f64>) -> Rgb<f64> {
+    xyz.adapt_unchecked(WhitePoint::D50, WhitePoint::D65, Adaptation::Bradford).to_rgb()
+}
+
+impl FromStr for CssColor {
+    type Err = ParseError;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<CssColor, ParseError> {
+        parse(s)
+    }
+}
+
+impl fmt::Display for CssColor {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.format_css())
+    }
+}
+
+/// Formatting as CSS Color 4 syntax that `parse` and browsers read back.
+///
+/// Each type is written in its own CSS function, so nothing is lost to
+/// gamut clipping: `Srgb` as `rgb()`, linear `Rgb` as
+/// `color(srgb-linear ...)`, `Xyz` as `color(xyz-d65 ...)`, and `Hsl`,
+/// `Hwb`, `Lab`, `Lch`, `Oklab` and `Oklch` as the functions of the same
+/// name. `Lab` and `Lch` are adapted to the D50 white CSS uses. Alpha is
+/// written only when the color is not opaque. Other types can be converted
+/// to one of these first.
+pub trait FormatCss {
+    fn format_css(&self) -> String;
+}
+
+/// Writes `x` with at most four decimals.
+fn number(x: f64) -> String {
+    let s = format!("{:.4}", x);
+    let s = s.trim_end_matches('0').trim_end_matches('.');
+    if s == "-0" { "0".to_string() } else { s.to_string() }
+}
+
+#[inline]
+fn percent<T: Channel>(x: T) -> String {
+    format!("{}%", number(x.to_channel_f64() * 100.0))
+}
+
+#[inline]
+fn hue<T: Channel>(h: Deg<T>) -> String {
+    number(cast(h.0))
+}
+
+#[inline]
+fn function(name: &str, components: [String; 3]) -> String {
+    format!("{}({} {} {})", name, components[0], components[1], components[2])
+}
+
+/// `color()` in the named space.
+#[inline]
+fn color_function(space: &str, [x, y, z]: [f64; 3]) -> String {
+    format!("color({} {} {} {})", space, number(x), number(y), number(z))
+}
+
+/// Adds the alpha `a` to the function `css` unless it is opaque.
+fn with_alpha(css: String, a: f64) -> String {
+    if a >= 1.0 {
+        return css;
+    }
+    // every color function ends with its closing parenthesis
+    format!("{} / {})", &css[..css.len() - 1], number(a))
+}
+
+/// `lab()` of a color relative to D50.
+#[inline]
+fn lab_d50(lab: Lab<f64>) -> String {
+    function("lab", [number(lab.l), number(lab.a), number(lab.b)])
+}
+
+/// `lch()` of a color relative to D50.
+#[inline]
+fn lch_d50(lch: Lch<f64>) -> String {
+    function("lch", [number(lch.l), number(lch.c), hue(lch.h)])
+}
+
+/// Takes a color relative to D65, as the crate's `Lab` is, to D50 for CSS.
+#[inline]
+fn to_d50<C: ToXyz>(color: &C) -> Lab<f64> {
+    let xyz: Xyz<f64> = color.to_xyz::<f64>().adapt_unchecked(WhitePoint::D65, WhitePoint::D50, Adaptation::Bradford);
+    Lab::from_xyz_unchecked(&xyz, WhitePoint::D50)
+}
+
+impl<T: Channel> FormatCss for Srgb<T> {
+    fn format_css(&self) -> String {
+        let c = |x: T| number(x.to_channel_f64() * 255.0);
+        function("rgb", [c(self.r), c(self.g), c(self.b)])
+    }
+}
+
+impl<T: Channel> FormatCss for Rgb<T> {
+    fn format_css(&self) -> String {
+        color_function("srgb-linear", [self.r.to_channel_f64(), self.g.to_channel_f64(), self.b.to_channel_f64()])
+    }
+}
+
+impl<T: Channel> FormatCss for Hsl<T> {
+    #[inline]
+    fn format_css(&self) -> String {
+        function("hsl", [hue(self.h), percent(self.s), percent(self.l)])
+    }
+}
+
+impl<T: Channel> FormatCss for Hwb<T> {
+    #[inline]
+    fn format_css(&self) -> String {
+        function("hwb", [hue(self.h), percent(self.w), percent(self.b)])
+    }
+}
+
+impl<T: FloatChannel> FormatCss for Lab<T> {
+    #[inline]
+    fn format_css(&self) -> String {
+        lab_d50(to_d50(self))
+    }
+}
+
+impl<T: FloatChannel> FormatCss for Lch<T> {
+    #[inline]
+    fn format_css(&self) -> String {
+        lch_d50(to_d50(&self.to_lab::<f64>()).to_lch())
+    }
+}
+
+impl<T: Channel> FormatCss for Oklab<T> {
+    #[inline]
+    fn format_css(&self) -> String {
+        function("oklab", [number(cast(self.l)), number(cast(self.a)), number(cast(self.b))])
+    }
+}
+
+impl<T: Channel> FormatCss for Oklch<T> {
+    #[inline]
+    fn format_css(&self) -> String {
+        function("oklch", [number(cast(self.l)), number(cast(self.c)), hue(self.h)])
+    }
+}
+
+impl<T: Channel> FormatCss for Xyz<T> {
+    fn format_css(&self) -> String {
+        color_function("xyz-d65", [cast(self.x), cast(self.y), cast(self.z)])
+    }
+}
+
+impl<T: Channel, C: FormatCss> FormatCss for AlphaColor<T, C> {
+    #[inline]
+    fn format_css(&self) -> String {
+        with_alpha(self.c.format_css(), self.a.to_channel_f64())
+    }
+}
+
+impl FormatCss for CssColor {
+    /// Writes the color in the function it was parsed from, or in `rgb()`
+    /// for hex notation and names.
+    fn format_css(&self) -> String {
+        match *self {
+            CssColor::Srgb(c) => c.format_css(),
+            CssColor::LinearRgb(c) => c.format_css(),
+            CssColor::Hsl(c) => c.format_css(),
+            CssColor::Hwb(c) => c.format_css(),
+            CssColor::Lab(c) => with_alpha(lab_d50(c.c.to_lab()), cast(c.a)),
+            CssColor::Lch(c) => with_alpha(lch_d50(c.c.to_lch()), cast(c.a)),
+            CssColor::Oklab(c) => c.format_css(),
+            CssColor::Oklch(c) => c.format_css(),
+            CssColor::Xyz(c) => c.format_css(),
+            CssColor::XyzD50(c) => with_alpha(color_function("xyz-d50", c.c.to_array()), cast(c.a)),
+            CssColor::Predefined(space, c) => {
+                with_alpha(color_function(space.name(), [cast(c.c.r), cast(c.c.g), cast(c.c.b)]), cast(c.a))
+            }
+        }
+    }
+}
+
+/// The ways in which parsing a CSS color can fail.
+#[derive(Clone, PartialEq, Debug)]
+pub enum ParseError {
+    /// The input contained nothing but whitespace.
+    Empty,
+    /// A `#` color did not have 3, 4, 6 or 8 hexadecimal digits.
+    InvalidHex(String),
+    /// The input was neither a function nor a known color keyword.
+    UnknownName(String),
+    /// The function name is not a CSS color function.
+    UnknownFunction(String),
+    /// The color space given to `color()` is not supported.
+    UnknownColorSpace(String),
+    /// A function was missing its closing parenthesis.
+    UnclosedFunction,
+    /// The function was given the wrong number of components.
+    ArgumentCount { expected: usize, found: usize },
+    /// A component could not be parsed, or had the wrong unit.
+    InvalidComponent(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ParseError::Empty => write!(f, "empty color string"),
+            ParseError::InvalidHex(ref s) => write!(f, "invalid hex color `{}`", s),
+            ParseError::UnknownName(ref s) => write!(f, "unknown color name `{}`", s),
+            ParseError::UnknownFunction(ref s) => write!(f, "unknown color function `{}`", s),
+            ParseError::UnknownColorSpace(ref s) => write!(f, "unsupported color space `{}`", s),
+            ParseError::UnclosedFunction => write!(f, "missing closing parenthesis"),
+            ParseError::ArgumentCount { expected, found } =>
+                write!(f, "expected {} color components, found {}", expected, found),
+            ParseError::InvalidComponent(ref s) => write!(f, "invalid color component `{}`", s),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// A single component of a color function, with the text it was parsed
+/// from for errors.
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct Component<'a> {
+    value: Value,
+    source: &'a str,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Value {
+    Number(f32),
+    Percentage(f32),
+    /// An angle with an explicit unit, in degrees.
+    Angle(f32),
+    None,
+}
+
+impl<'a> Component<'a> {
+    fn parse(s: &'a str) -> Result<Component<'a>, ParseError> {
+        let component = |value| Ok(Component { value, source: s });
+        if s == "none" {
+            return component(Value::None);
+        }
+        // `f32` parsing takes `nan` and `inf`, which CSS does not, and
+        // overflows to infinity
+        let number = |n: &str| n.parse::<f32>().ok().filter(|n| n.is_finite())
+                                .ok_or_else(|| ParseError::InvalidComponent(s.to_string()));
+        if let Some(n) = s.strip_suffix('%') {
+            return component(Value::Percentage(number(n)?));
+        }
+        // `grad` must be tested before `rad`
+        let units: [(&str, f32); 4] = [("deg", 1.0), ("grad", 0.9), ("rad", 180.0 / ::core::f32::consts::PI), ("turn", 360.0)];
+        for &(unit, scale) in units.iter() {
+            if let Some(n) = s.strip_suffix(unit) {
+                return component(Value::Angle(number(n)? * scale));
+            }
+        }
+        component(Value::Number(number(s)?))
+    }
+
+    /// Resolves a number or percentage, where `100%` maps to `full`.
+    fn number(self, full: f32) -> Result<f32, ParseError> {
+        match self.value {
+            Value::Number(n) => Ok(n),
+            Value::Percentage(p) => Ok(p / 100.0 * full),
+            Value::None => Ok(0.0),
+            Value::Angle(_) => Err(ParseError::InvalidComponent(self.source.to_string())),
+        }
+    }
+
+    /// Resolves a hue, where plain numbers are degrees.
+    fn hue(self) -> Result<f32, ParseError> {
+        match self.value {
+            Value::Number(n) | Value::Angle(n) => Ok(n),
+            Value::None => Ok(0.0),
+            Value::Percentage(_) => Err(ParseError::InvalidComponent(self.source.to_string())),
+        }
+    }
+}
+
+/// Parses a CSS color value such as `#ff8000`, `rebeccapurple`,
+/// `rgb(255 128 0 / 50%)` or `oklch(70% 0.1 200)`.
+pub fn parse(s: &str) -> Result<CssColor, ParseError> {
+    let s = s.trim().to_ascii_lowercase();
+    if s.is_empty() {
+        return Err(ParseError::Empty);
+    }
+    if let Some(hex) = s.strip_prefix('#') {
+        return parse_hex(hex).map(CssColor::Srgb);
+    }
+    let open = match s.find('(') {
+        Some(open) => open,
+        None => return parse_name(&s).map(CssColor::Srgb),
+    };
+    let name = s[..open].trim();
+    let body = s[open + 1..].strip_suffix(')').ok_or(ParseError::UnclosedFunction)?;
+
+    if name == "color" {
+        let body = body.trim_start();
+        let end = body.find(char::is_whitespace).unwrap_or(body.len());
+        let (c, a) = components(&body[end..], 3)?;
+        let (x, y, z) = (c[0].number(1.0)?, c[1].number(1.0)?, c[2].number(1.0)?);
+        return match &body[..end] {
+            "srgb" => Ok(CssColor::Srgb(AlphaColor { c: Srgb::new(x, y, z), a })),
+            "srgb-linear" => Ok(CssColor::LinearRgb(AlphaColor { c: Rgb::new(x, y, z), a })),
+            "xyz" | "xyz-d65" => Ok(CssColor::Xyz(AlphaColor { c: Xyz::new(x, y, z), a })),
+            "xyz-d50" => Ok(CssColor::XyzD50(AlphaColor { c: Xyz::new(x, y, z), a })),
+            space => match PredefinedRgb::from_name(space) {
+                Some(space) => Ok(CssColor::Predefined(space, AlphaColor { c: Rgb::new(x, y, z), a })),
+                None => Err(ParseError::UnknownColorSpace(space.to_string())),
+            },
+        };
+    }
+
+    let (c, a) = components(body, 3)?;
+    match name {
+        "rgb" | "rgba" => {
+            let chan = |c: Component| c.number(255.0).map(|n| n / 255.0);
+            Ok(CssColor::Srgb(AlphaColor { c: Srgb::new(chan(c[0])?, chan(c[1])?, chan(c[2])?), a }))
+        }
+        "hsl" | "hsla" => {
+            let hsl = Hsl::new(Deg(c[0].hue()?), c[1].number(100.0)? / 100.0, c[2].number(100.0)? / 100.0);
+            Ok(CssColor::Hsl(AlphaColor { c: hsl, a }))
+        }
+        "hwb" => {
+            let hwb = Hwb::new(Deg(c[0].hue()?), c[1].number(100.0)? / 100.0, c[2].number(100.0)? / 100.0);
+            Ok(CssColor::Hwb(AlphaColor { c: hwb, a }))
+        }
+        "lab" => {
+            let lab = Lab::new(c[0].number(100.0)?, c[1].number(125.0)?, c[2].number(125.0)?);
+            Ok(CssColor::Lab(AlphaColor { c: lab, a }))
+        }
+        "lch" => {
+            let lch = Lch::new(c[0].number(100.0)?, c[1].number(150.0)?, Deg(c[2].hue()?));
+            Ok(CssColor::Lch(AlphaColor { c: lch, a }))
+        }
+        "oklab" => {
+            let lab = Oklab::new(c[0].number(1.0)?, c[1].number(0.4)?, c[2].number(0.4)?);
+            Ok(CssColor::Oklab(AlphaColor { c: lab, a }))
+        }
+        "oklch" => {
+            let lch = Oklch::new(c[0].number(1.0)?, c[1].number(0.4)?, Deg(c[2].hue()?));
+            Ok(CssColor::Oklch(AlphaColor { c: lch, a }))
+        }
+        _ => Err(ParseError::UnknownFunction(name.to_string())),
+    }
+}
+
+/// Parses `names(h s l)` with optional alpha, as `hsl()` is parsed, into
+/// a hue in `[0, 360)`, the other two components in `[0, 1]` and alpha.
+fn parse_hue_function(s: &str, names: &[&str]) -> Result<(f32, f32, f32, f32), ParseError> {
+    let s = s.trim().to_ascii_lowercase();
+    if s.is_empty() {
+        return Err(ParseError::Empty);
+    }
+    let open = s.find('(').ok_or_else(|| ParseError::UnknownName(s.clone()))?;
+    let name = s[..open].trim();
+    if !names.contains(&name) {
+        return Err(ParseError::UnknownFunction(name.to_string()));
+    }
+    let body = s[open + 1..].strip_suffix(')').ok_or(ParseError::UnclosedFunction)?;
+    let (c, a) = components(body, 3)?;
+    Ok((c[0].hue()?.rem_euclid(360.0), c[1].number(100.0)? / 100.0, c[2].number(100.0)? / 100.0, a))
+}
+
+/// A hue in degrees in the channel type, failing if the channel cannot hold
+/// it, as a `u8` cannot hold hues above `255`.
+#[inline]
+fn hue_channel<T: Channel>(h: f32) -> Result<T, ParseError> {
+    num::cast(h).ok_or_else(|| ParseError::InvalidComponent(format!("hue {}", h)))
+}
+
+/// Fails if a color without alpha was given a translucent one.
+#[inline]
+fn opaque(a: f32) -> Result<(), ParseError> {
+    if a < 1.0 { Err(ParseError::InvalidComponent(format!("alpha {}", a))) } else { Ok(()) }
+}
+
+impl<T: Channel> FromStr for Hsl<T> {
+    type Err = ParseError;
+
+    /// Parses `hsl()` or `hsla()` as CSS does, with the hue in degrees
+    /// unless given a `deg`, `grad`, `rad` or `turn` unit, and saturation
+    /// and lightness as percentages. Hues outside `[0, 360)` are wrapped.
+    /// An alpha other than `1`, or a hue the channel type cannot hold, is
+    /// an error.
+    fn from_str(s: &str) -> Result<Hsl<T>, ParseError> {
+        let (h, s, l, a) = parse_hue_function(s, &["hsl", "hsla"])?;
+        opaque(a)?;
+        Ok(Hsl::new(Deg(hue_channel(h)?), Channel::from(s), Channel::from(l)))
+    }
+}
+
+impl<T: Channel> FromStr for Hsla<T> {
+    type Err = ParseError;
+
+    /// Parses `hsl()` or `hsla()` as `Hsl` does, keeping the alpha.
+    fn from_str(s: &str) -> Result<Hsla<T>, ParseError> {
+        let (h, s, l, a) = parse_hue_function(s, &["hsl", "hsla"])?;
+        Ok(AlphaColor { c: Hsl::new(Deg(hue_channel(h)?), Channel::from(s), Channel::from(l)), a: Channel::from(a) })
+    }
+}
+
+impl<T: Channel> FromStr for Hsv<T> {
+    type Err = ParseError;
+
+    /// Parses `hsv()` or `hsva()`, which CSS lacks, with the same grammar
+    /// as `hsl()`: `hsv(210 50% 40%)`, `hsv(0.25turn, 100%, 80%)`.
+    fn from_str(s: &str) -> Result<Hsv<T>, ParseError> {
+        let (h, s, v, a) = parse_hue_function(s, &["hsv", "hsva"])?;
+        opaque(a)?;
+        Ok(Hsv::new(Deg(hue_channel(h)?), Channel::from(s), Channel::from(v)))
+    }
+}
+
+impl<T: Channel> FromStr for Hsva<T> {
+    type Err = ParseError;
+
+    /// Parses `hsv()` or `hsva()` as `Hsv` does, keeping the alpha.
+    fn from_str(s: &str) -> Result<Hsva<T>, ParseError> {
+        let (h, s, v, a) = parse_hue_function(s, &["hsv", "hsva"])?;
+        Ok(AlphaColor { c: Hsv::new(Deg(hue_channel(h)?), Channel::from(s), Channel::from(v)), a: Channel::from(a) })
+    }
+}
+
+/// Splits the body of a color function into its components and alpha,
+/// accepting both the modern space separated and the legacy comma separated
+/// syntax.
+fn components<'a>(body: &'a str, count: usize) -> Result<(Vec<Component<'a>>, f32), ParseError> {
+    let (parts, alpha): (Vec<&str>, Option<&str>) = if body.contains(',') {
+        let mut parts: Vec<&str> = body.split(',').map(str::trim).collect();
+        let alpha = if parts.len() == count + 1 { parts.pop() } else { None };
+        (parts, alpha)
+    } else {
+        let mut halves = body.splitn(2, '/');
+        let parts = halves.next().unwrap_or("").split_whitespace().collect();
+        (parts, halves.next().map(str::trim))
+    };
+    if parts.len() != count {
+        return Err(ParseError::ArgumentCount { expected: count, found: parts.len() });
+    }
+    let parts = parts.into_iter().map(Component::parse).collect::<Result<Vec<_>, _>>()?;
+    let alpha = match alpha {
+        Some(a) => Component::parse(a)?.number(1.0)?.clamp(0.0, 1.0),
+        None => 1.0,
+    };
+    Ok((parts, alpha))
+}
+
+fn parse_hex(hex: &str) -> Result<Srgba<f32>, ParseError> {
+    let invalid = || ParseError::InvalidHex(format!("#{}", hex));
+    let digits = hex.chars().map(|c| c.to_digit(16).map(|d| d as u8)).collect::<Option<Vec<u8>>>()
+                    .ok_or_else(invalid)?;
+    let bytes: Vec<u8> = match digits.len() {
+        3 | 4 => digits.iter().map(|&d| d << 4 | d).collect(),
+        6 | 8 => digits.chunks(2).map(|d| d[0] << 4 | d[1]).collect(),
+        _ => return Err(invalid()),
+    };
+    let a = bytes.get(3).cloned().unwrap_or(0xFF);
+    Ok(AlphaColor { c: Srgb::new(bytes[0].to_channel_f32(), bytes[1].to_channel_f32(), bytes[2].to_channel_f32()),
+                    a: a.to_channel_f32() })
+}
+
+fn parse_name(name: &str) -> Result<Srgba<f32>, ParseError> {
+    if name == "transparent" {
+        return Ok(AlphaColor { c: Srgb::new(0.0, 0.0, 0.0), a: 0.0 });
+    }
+    let srgb = Srgb::from_name(name).ok_or_else(|| ParseError::UnknownName(name.to_string()))?;
+    Ok(AlphaColor { c: srgb.to_srgb(), a: 1.0 })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+    use super::{parse, CssColor, ParseError, FormatCss};
+    use {AlphaColor, Rgba, Srgb, Hsv, Hsva, Hsl, Hsla, Hwb, Lab, ToLab, Oklch, Xyz, WhitePoint};
+    use angle::*;
+
+    fn srgba(r: f32, g: f32, b: f32, a: f32) -> CssColor {
+        CssColor::Srgb(AlphaColor { c: Srgb::new(r, g, b), a })
+    }
+
+    fn assert_srgba_eq(css: &str, r: f32, g: f32, b: f32) {
+        let c = parse(css).unwrap().to_srgba();
+        assert!((c.c.r - r).abs() < 2e-3 && (c.c.g - g).abs() < 2e-3 && (c.c.b - b).abs() < 2e-3,
+                "{}: {:?}", css, c);
+    }
+
+    #[test]
+    fn test_parse_hex() {
+        assert_eq!(parse("#ff0000"), Ok(srgba(1.0, 0.0, 0.0, 1.0)));
+        assert_eq!(parse("#F00"), Ok(srgba(1.0, 0.0, 0.0, 1.0)));
+        assert_eq!(parse("#ff000000"), Ok(srgba(1.0, 0.0, 0.0, 0.0)));
+        assert_eq!(parse("#f00f"), Ok(srgba(1.0, 0.0, 0.0, 1.0)));
+        assert_eq!(parse("#ff00"), Ok(srgba(1.0, 1.0, 0.0, 0.0)));
+        assert_eq!(parse("#ff000"), Err(ParseError::InvalidHex("#ff000".to_string())));
+        assert_eq!(parse("#gg0000"), Err(ParseError::InvalidHex("#gg0000".to_string())));
+    }
+
+    #[test]
+    fn test_parse_name() {
+        assert_eq!(parse("Red"), Ok(srgba(1.0, 0.0, 0.0, 1.0)));
+        assert_eq!(parse(" white "), Ok(srgba(1.0, 1.0, 1.0, 1.0)));
+        assert_eq!(parse("transparent"), Ok(srgba(0.0, 0.0, 0.0, 0.0)));
+        assert_eq!(parse("rebeccapurple"), parse("#663399"));
+        assert_eq!(parse("notacolor"), Err(ParseError::UnknownName("notacolor".to_string())));
+        assert_eq!(parse(""), Err(ParseError::Empty));
+    }
+
+    #[test]
+    fn test_parse_rgb() {
+        assert_eq!(parse("rgb(255 0 0)"), Ok(srgba(1.0, 0.0, 0.0, 1.0)));
+        assert_eq!(parse("rgb(255, 0, 0)"), Ok(srgba(1.0, 0.0, 0.0, 1.0)));
+        assert_eq!(parse("rgba(255, 0, 0, 0.5)"), Ok(srgba(1.0, 0.0, 0.0, 0.5)));
+        assert_eq!(parse("rgb(100% 0% none / 25%)"), Ok(srgba(1.0, 0.0, 0.0, 0.25)));
+        assert_eq!(parse("rgb(255 0)"), Err(ParseError::ArgumentCount { expected: 3, found: 2 }));
+        assert_eq!(parse("rgb(255 0 0"), Err(ParseError::UnclosedFunction));
+        assert_eq!(parse("rgb(red 0 0)"), Err(ParseError::InvalidComponent("red".to_string())));
+        assert_eq!(parse("rgb(90deg 0 0)"), Err(ParseError::InvalidComponent("90deg".to_string())));
+    }
+
+    #[test]
+    fn test_parse_non_finite() {
+        assert_eq!(parse("rgb(nan 0 0)"), Err(ParseError::InvalidComponent("nan".to_string())));
+        assert_eq!(parse("rgb(0 inf 0)"), Err(ParseError::InvalidComponent("inf".to_string())));
+        assert_eq!(parse("rgb(0 0 -infinity%)"), Err(ParseError::InvalidComponent("-infinity%".to_string())));
+        assert_eq!(parse("hsl(NaNdeg 100% 50%)"), Err(ParseError::InvalidComponent("nandeg".to_string())));
+        assert_eq!(parse("rgb(0 0 0 / nan)"), Err(ParseError::InvalidComponent("nan".to_string())));
+        assert_eq!(parse("lab(1e39 0 0)"), Err(ParseError::InvalidComponent("1e39".to_string())));
+        assert_eq!("hsv(inf 100% 100%)".parse::<Hsv<f32>>(), Err(ParseError::InvalidComponent("inf".to_string())));
+    }
+
+    #[test]
+    fn test_parse_hsl_hwb() {
+        assert_eq!(parse("hsl(120 100% 50%)"),
+                   Ok(CssColor::Hsl(AlphaColor { c: Hsl::new(Deg(120.0), 1.0, 0.5), a: 1.0 })));
+        assert_eq!(parse("hsla(0.5turn, 100%, 50%, 0.5)"),
+                   Ok(CssColor::Hsl(AlphaColor { c: Hsl::new(Deg(180.0), 1.0, 0.5), a: 0.5 })));
+        assert_eq!(parse("hwb(240deg 0% 0%)"),
+                   Ok(CssColor::Hwb(AlphaColor { c: Hwb::new(Deg(240.0), 0.0, 0.0), a: 1.0 })));
+        assert_srgba_eq("hsl(120 100% 50%)", 0.0, 1.0, 0.0);
+        assert_srgba_eq("hwb(0 50% 50%)", 0.5, 0.5, 0.5);
+        assert_srgba_eq("hwb(0 100% 100%)", 0.5, 0.5, 0.5);
+        assert_eq!(parse("hsl(10% 100% 50%)"), Err(ParseError::InvalidComponent("10%".to_string())));
+    }
+
+    #[test]
+    fn test_parse_lab_oklch() {
+        assert_eq!(parse("lab(50% 40 -20)"),
+                   Ok(CssColor::Lab(AlphaColor { c: Lab::new(50.0, 40.0, -20.0), a: 1.0 })));
+        assert_eq!(parse("oklch(70% 0.1 200 / 0.5)"),
+                   Ok(CssColor::Oklch(AlphaColor { c: Oklch::new(0.7, 0.1, Deg(200.0)), a: 0.5 })));
+        assert_srgba_eq("lab(100 0 0)", 1.0, 1.0, 1.0);
+        assert_srgba_eq("lch(0 0 0)", 0.0, 0.0, 0.0);
+        assert_srgba_eq("lab(54.29 80.82 69.91)", 1.0, 0.0, 0.0);
+        assert_srgba_eq("oklab(1 0 0)", 1.0, 1.0, 1.0);
+        assert_srgba_eq("oklch(62.8% 0.2577 29.23)", 1.0, 0.0, 0.0);
+    }
+
+    #[test]
+    fn test_parse_color_function() {
+        assert_eq!(parse("color(srgb 1 0.5 0)"), Ok(srgba(1.0, 0.5, 0.0, 1.0)));
+        assert_srgba_eq("color(srgb-linear 0.214 0.214 0.214)", 0.5, 0.5, 0.5);
+        assert_srgba_eq("color(xyz-d65 0.95047 1 1.08883)", 1.0, 1.0, 1.0);
+        assert_srgba_eq("color(xyz-d50 0.96422 1 0.82521)", 1.0, 1.0, 1.0);
+        for space in &["display-p3", "rec2020", "a98-rgb", "prophoto-rgb"] {
+            assert_srgba_eq(&format!("color({} 1 1 1)", space), 1.0, 1.0, 1.0);
+            assert_srgba_eq(&format!("color({} 0 0 0)", space), 0.0, 0.0, 0.0);
+        }
+        // sRGB red in the wider spaces, from the CSS Color 4 sample code
+        assert_srgba_eq("color(display-p3 0.9175 0.2003 0.1386)", 1.0, 0.0, 0.0);
+        assert_srgba_eq("color(a98-rgb 0.8587 0 0)", 1.0, 0.0, 0.0);
+        assert_eq!(parse("color(display-p3 1 0 0 / 0.5)").map(|c| c.alpha()), Ok(0.5));
+        assert_eq!(parse("color(unknown 1 0 0)"), Err(ParseError::UnknownColorSpace("unknown".to_string())));
+        assert_eq!(parse("foo(1 2 3)"), Err(ParseError::UnknownFunction("foo".to_string())));
+    }
+
+    #[test]
+    fn test_format_css() {
+        assert_eq!(Srgb::<u8>::new(0x33, 0x66, 0x99).format_css(), "rgb(51 102 153)");
+        assert_eq!(Rgba::<f64>::new(0.5, 0.0, 1.0, 0.25).format_css(), "color(srgb-linear 0.5 0 1 / 0.25)");
+        assert_eq!(Hsl::<f64>::new(Deg(210.0), 0.5, 0.4).format_css(), "hsl(210 50% 40%)");
+        assert_eq!(Oklch::<f64>::new(0.628, 0.2577, Deg(29.23)).format_css(), "oklch(0.628 0.2577 29.23)");
+        assert_eq!(Xyz::<f64>::new(0.95047, 1.0, 1.08883).format_css(), "color(xyz-d65 0.9505 1 1.0888)");
+        // D65 white is D50 white in CSS
+        assert_eq!(WhitePoint::D65.to_xyz::<f64>().to_lab::<f64>().format_css(), "lab(100 0 0)");
+
+        for css in &["rgb(255 0 128 / 0.5)", "hwb(120 10% 20%)", "lab(50 40 -20)", "lch(50 40 200 / 0.1)",
+                     "oklab(0.5 0.1 -0.1)", "color(srgb-linear 0.2 0.4 0.6)", "color(xyz-d50 0.3 0.4 0.5)",
+                     "color(display-p3 1 0.5 0)", "color(rec2020 0.1 0.2 0.3 / 0.5)", "color(a98-rgb 0 0 1)",
+                     "color(prophoto-rgb 0.5 0.5 0.5)"] {
+            assert_eq!(parse(css).unwrap().format_css(), *css);
+            assert_eq!(parse(css).unwrap().to_string(), *css);
+        }
+        let red = parse(&Lab::<f64>::new(53.24, 80.09, 67.2).format_css()).unwrap().to_srgba();
+        assert!((red.c.r - 1.0).abs() < 2e-3 && red.c.g.abs() < 2e-3 && red.c.b.abs() < 2e-3, "{:?}", red);
+    }
+
+    #[test]
+    fn test_from_str_hsv_hsl() {
+        assert_eq!("hsv(210 50% 40%)".parse(), Ok(Hsv::<f32>::new(Deg(210.0), 0.5, 0.4)));
+        assert_eq!("HSV(0.5turn, 100%, 20)".parse(), Ok(Hsv::<f32>::new(Deg(180.0), 1.0, 0.2)));
+        assert_eq!("hsl(-90deg 100% 50%)".parse(), Ok(Hsl::<f32>::new(Deg(270.0), 1.0, 0.5)));
+        assert_eq!("hsl(200grad none 50%)".parse(), Ok(Hsl::<f32>::new(Deg(180.0), 0.0, 0.5)));
+        let hsl: Hsl<f64> = "hsl(3.14159265rad 100% 50%)".parse().unwrap();
+        assert!((hsl.h.0 - 180.0).abs() < 1e-3);
+        assert_eq!("hsl(120, 100%, 50%)".parse(), Ok(Hsl::<u16>::new(Deg(120), 0xFFFF, 0x7FFF)));
+        assert_eq!("hsla(120 100% 50% / 50%)".parse(),
+                   Ok(Hsla::<f32> { c: Hsl::new(Deg(120.0), 1.0, 0.5), a: 0.5 }));
+        assert_eq!("hsva(120 100% 50% / 1)".parse(),
+                   Ok(Hsva::<f32> { c: Hsv::new(Deg(120.0), 1.0, 0.5), a: 1.0 }));
+
+        assert_eq!("hsl(120 100% 50% / 0.5)".parse::<Hsl<f32>>(), Err(ParseError::InvalidComponent("alpha 0.5".to_string())));
+        assert_eq!("hsl(120 100% 50%)".parse::<Hsv<f32>>(), Err(ParseError::UnknownFunction("hsl".to_string())));
+        assert_eq!("red".parse::<Hsv<f32>>(), Err(ParseError::UnknownName("red".to_string())));
+        assert_eq!("hsv(1 2)".parse::<Hsv<f32>>(), Err(ParseError::ArgumentCount { expected: 3, found: 2 }));
+        assert_eq!("hsv(10% 2 3)".parse::<Hsv<f32>>(), Err(ParseError::InvalidComponent("10%".to_string())));
+
+        // a u8 holds hues up to 255
+        assert_eq!("hsv(200 100% 100%)".parse::<Hsv<u8>>().map(|c| c.h.0), Ok(200));
+        assert_eq!("hsv(300 100% 100%)".parse::<Hsv<u8>>(), Err(ParseError::InvalidComponent("hue 300".to_string())));
+        assert_eq!("hsla(-60 100% 50% / 1)".parse::<Hsla<u8>>(), Err(ParseError::InvalidComponent("hue 300".to_string())));
+        assert_eq!("hsl(300 100% 50%)".parse::<Hsl<u16>>().map(|c| c.h.0), Ok(300));
+    }
+}
@@ -0,0 +1,272 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Simulation of color vision deficiencies.
+//!
+//! Simulations work on linear light: any `ToRgb` color is accepted, so
+//! gamma-encoded values should be passed as `Srgb` and the result encoded
+//! again with `Srgb::from_linear`. Results are clamped to `[0, 1]`.
+//!
+//! A `severity` of `1` simulates the dichromacy (protanopia, deuteranopia or
+//! tritanopia); values in between simulate the anomalous trichromacies
+//! (protanomaly, deuteranomaly, tritanomaly) by interpolating towards the
+//! unaltered color.
+//!
+//! `daltonize` goes the other way, shifting colors so that people with a
+//! deficiency can tell them apart better.
+
+use alloc::vec::Vec;
+
+use Channel;
+use {Rgb, ToRgb};
+use matrix::{self, Matrix3};
+use difference::ciede2000;
+
+/// The cone type that is missing or anomalous.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Deficiency {
+    /// Long-wavelength (red) cones.
+    Protan,
+    /// Medium-wavelength (green) cones.
+    Deutan,
+    /// Short-wavelength (blue) cones.
+    Tritan,
+}
+
+/// The simulation model.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CvdModel {
+    /// Machado, Oliveira and Fernandes (2009). A single matrix per
+    /// deficiency; accurate for protans and deutans.
+    Machado,
+    /// Brettel, Viénot and Mollon (1997). Projects onto two half-planes and
+    /// is the better choice for tritans.
+    Brettel,
+}
+
+const MACHADO_PROTAN: Matrix3 = [[ 0.152286,  1.052583, -0.204868],
+                                 [ 0.114503,  0.786281,  0.099216],
+                                 [-0.003882, -0.048116,  1.051998]];
+const MACHADO_DEUTAN: Matrix3 = [[ 0.367322,  0.860646, -0.227968],
+                                 [ 0.280085,  0.672501,  0.047413],
+                                 [-0.011820,  0.042940,  0.968881]];
+const MACHADO_TRITAN: Matrix3 = [[ 1.255528, -0.076749, -0.178779],
+                                 [-0.078411,  0.930809,  0.147602],
+                                 [ 0.004733,  0.691367,  0.303900]];
+
+/// The two projection matrices for the Brettel model, and the normal of the
+/// plane through the neutral axis that picks between them.
+struct Brettel {
+    positive: Matrix3,
+    negative: Matrix3,
+    normal: [f64; 3],
+}
+
+// Precomputed for sRGB primaries, as in libDaltonLens.
+const BRETTEL_PROTAN: Brettel = Brettel {
+    positive: [[0.14510, 1.20165, -0.34675], [0.10447, 0.85316, 0.04237], [0.00429, -0.00603, 1.00174]],
+    negative: [[0.14115, 1.16782, -0.30897], [0.10495, 0.85730, 0.03776], [0.00431, -0.00586, 1.00155]],
+    normal: [0.00048, 0.00416, -0.00464],
+};
+const BRETTEL_DEUTAN: Brettel = Brettel {
+    positive: [[0.36198, 0.86755, -0.22953], [0.26099, 0.64512, 0.09389], [-0.01975, 0.02686, 0.99289]],
+    negative: [[0.37009, 0.88540, -0.25549], [0.25767, 0.63782, 0.10451], [-0.01950, 0.02741, 0.99209]],
+    normal: [-0.00293, -0.00645, 0.00938],
+};
+const BRETTEL_TRITAN: Brettel = Brettel {
+    positive: [[1.01354, 0.14268, -0.15622], [-0.01181, 0.87561, 0.13619], [0.07707, 0.81208, 0.11085]],
+    negative: [[0.93337, 0.19999, -0.13336], [0.05809, 0.82565, 0.11626], [-0.37923, 1.13825, 0.24098]],
+    normal: [0.03960, -0.02831, -0.01129],
+};
+
+impl Deficiency {
+    fn dichromat(self, model: CvdModel, c: [f64; 3]) -> [f64; 3] {
+        match model {
+            CvdModel::Machado => {
+                let m = match self {
+                    Deficiency::Protan => &MACHADO_PROTAN,
+                    Deficiency::Deutan => &MACHADO_DEUTAN,
+                    Deficiency::Tritan => &MACHADO_TRITAN,
+                };
+                matrix::mul_vec(m, c)
+            }
+            CvdModel::Brettel => {
+                let b = match self {
+                    Deficiency::Protan => &BRETTEL_PROTAN,
+                    Deficiency::Deutan => &BRETTEL_DEUTAN,
+                    Deficiency::Tritan => &BRETTEL_TRITAN,
+                };
+                let side = b.normal[0] * c[0] + b.normal[1] * c[1] + b.normal[2] * c[2];
+                matrix::mul_vec(if side >= 0.0 { &b.positive } else { &b.negative }, c)
+            }
+        }
+    }
+}
+
+/// Simulates how `color` appears with the given deficiency, using the
+/// Machado model.
+#[inline]
+pub fn simulate<C: ToRgb, U: Channel>(color: &C, deficiency: Deficiency, severity: f64) -> Rgb<U> {
+    simulate_with(color, deficiency, severity, CvdModel::Machado)
+}
+
+/// Simulates how `color` appears with the given deficiency and model.
+pub fn simulate_with<C: ToRgb, U: Channel>(color: &C, deficiency: Deficiency, severity: f64,
+                                           model: CvdModel) -> Rgb<U> {
+    let rgb = color.to_rgb::<f64>();
+    let c = [rgb.r, rgb.g, rgb.b];
+    let d = deficiency.dichromat(model, c);
+    let t = severity.clamp(0.0, 1.0);
+    let mix = |i: usize| Channel::from((c[i] + (d[i] - c[i]) * t).clamp(0.0, 1.0));
+    Rgb::new(mix(0), mix(1), mix(2))
+}
+
+/// Shifts `color` so that the detail lost to the deficiency shows up in
+/// the channels that are still seen, after Fidaner, Lin and Ozguven (2005).
+/// A `strength` of `1` applies the full correction. Neutral colors are left
+/// unchanged.
+pub fn daltonize<C: ToRgb, U: Channel>(color: &C, deficiency: Deficiency, strength: f64) -> Rgb<U> {
+    let rgb = color.to_rgb::<f64>();
+    let c = [rgb.r, rgb.g, rgb.b];
+    let d = deficiency.dichromat(CvdModel::Machado, c);
+    let e = [c[0] - d[0], c[1] - d[1], c[2] - d[2]];
+    let shift = match deficiency {
+        Deficiency::Protan | Deficiency::Deutan => [0.0, 0.7 * e[0] + e[1], 0.7 * e[0] + e[2]],
+        Deficiency::Tritan => [e[0] + 0.7 * e[2], e[1] + 0.7 * e[2], 0.0],
+    };
+    let out = |i: usize| Channel::from((c[i] + shift[i] * strength).clamp(0.0, 1.0));
+    Rgb::new(out(0), out(1), out(2))
+}
+
+/// The CIEDE2000 difference between two palette entries as seen with a
+/// deficiency, before and after correction.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct PairDifference {
+    pub first: usize,
+    pub second: usize,
+    pub before: f64,
+    pub after: f64,
+}
+
+/// A palette corrected by `daltonize_palette`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct DaltonizedPalette<U> {
+    pub colors: Vec<Rgb<U>>,
+    /// Every pair of entries, in the order `(0, 1), (0, 2), ..., (1, 2), ...`.
+    pub differences: Vec<PairDifference>,
+}
+
+impl<U> DaltonizedPalette<U> {
+    /// The smallest difference between any two entries before and after
+    /// correction, or `None` for palettes of fewer than two colors.
+    pub fn min_difference(&self) -> Option<(f64, f64)> {
+        self.differences.iter().fold(None, |acc, d| match acc {
+            None => Some((d.before, d.after)),
+            Some((b, a)) => Some((b.min(d.before), a.min(d.after))),
+        })
+    }
+}
+
+/// Daltonizes every color of `palette` and reports how far apart each pair
+/// of entries looks to someone with the deficiency, before and after.
+pub fn daltonize_palette<C: ToRgb, U: Channel>(palette: &[C], deficiency: Deficiency,
+                                               strength: f64) -> DaltonizedPalette<U> {
+    let corrected: Vec<Rgb<f64>> = palette.iter().map(|c| daltonize(c, deficiency, strength)).collect();
+    let seen_before: Vec<Rgb<f64>> = palette.iter().map(|c| simulate(c, deficiency, 1.0)).collect();
+    let seen_after: Vec<Rgb<f64>> = corrected.iter().map(|c| simulate(c, deficiency, 1.0)).collect();
+    let mut differences = Vec::new();
+    for i in 0..palette.len() {
+        for j in i + 1..palette.len() {
+            differences.push(PairDifference {
+                first: i,
+                second: j,
+                before: ciede2000(&seen_before[i], &seen_before[j]),
+                after: ciede2000(&seen_after[i], &seen_after[j]),
+            });
+        }
+    }
+    DaltonizedPalette {
+        colors: corrected.iter().map(|c| c.to_rgb()).collect(),
+        differences,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use {Rgb, Srgb};
+
+    fn assert_rgb_eq(a: Rgb<f64>, b: Rgb<f64>, eps: f64) {
+        assert!((a.r - b.r).abs() < eps && (a.g - b.g).abs() < eps && (a.b - b.b).abs() < eps,
+                "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn test_neutrals_unchanged() {
+        let deficiencies = [Deficiency::Protan, Deficiency::Deutan, Deficiency::Tritan];
+        for &d in deficiencies.iter() {
+            for &m in [CvdModel::Machado, CvdModel::Brettel].iter() {
+                for &v in [0.0, 0.2, 0.7, 1.0].iter() {
+                    let gray = Rgb::new(v, v, v);
+                    assert_rgb_eq(simulate_with(&gray, d, 1.0, m), gray, 1e-4);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_confusion() {
+        // red and green are hard to tell apart for protans and deutans, but
+        // not for tritans
+        let red = Srgb::<f64>::new(0.8, 0.3, 0.1);
+        let green = Srgb::<f64>::new(0.45, 0.45, 0.1);
+        let dist = |a: Rgb<f64>, b: Rgb<f64>| (a.r - b.r).abs() + (a.g - b.g).abs() + (a.b - b.b).abs();
+        let normal = dist(red.to_linear(), green.to_linear());
+        for &d in [Deficiency::Protan, Deficiency::Deutan].iter() {
+            for &m in [CvdModel::Machado, CvdModel::Brettel].iter() {
+                let sim = dist(simulate_with(&red, d, 1.0, m), simulate_with(&green, d, 1.0, m));
+                assert!(sim < normal / 2.0, "{:?} {:?}: {} vs {}", d, m, sim, normal);
+            }
+        }
+        let tritan = dist(simulate(&red, Deficiency::Tritan, 1.0), simulate(&green, Deficiency::Tritan, 1.0));
+        assert!(tritan > normal / 2.0);
+    }
+
+    #[test]
+    fn test_severity() {
+        let c = Rgb::<f64>::new(0.6, 0.2, 0.4);
+        assert_rgb_eq(simulate(&c, Deficiency::Deutan, 0.0), c, 1e-12);
+        let full: Rgb<f64> = simulate(&c, Deficiency::Deutan, 1.0);
+        let half: Rgb<f64> = simulate(&c, Deficiency::Deutan, 0.5);
+        assert_rgb_eq(half, Rgb::new((c.r + full.r) / 2.0, (c.g + full.g) / 2.0, (c.b + full.b) / 2.0), 1e-12);
+    }
+
+    #[test]
+    fn test_daltonize() {
+        let gray = Rgb::<f64>::new(0.4, 0.4, 0.4);
+        assert_rgb_eq(daltonize(&gray, Deficiency::Deutan, 1.0), gray, 1e-4);
+        let c = Rgb::<f64>::new(0.6, 0.2, 0.4);
+        assert_rgb_eq(daltonize(&c, Deficiency::Protan, 0.0), c, 1e-12);
+
+        let palette = [Srgb::<f64>::new(0.8, 0.3, 0.1), Srgb::new(0.45, 0.45, 0.1), Srgb::new(0.2, 0.2, 0.8)];
+        let result = daltonize_palette::<_, f64>(&palette, Deficiency::Deutan, 1.0);
+        assert_eq!(result.colors.len(), 3);
+        assert_eq!(result.differences.len(), 3);
+        assert_eq!((result.differences[2].first, result.differences[2].second), (1, 2));
+        let (before, after) = result.min_difference().unwrap();
+        assert!(after > before, "{} -> {}", before, after);
+        assert!(daltonize_palette::<_, f64>(&palette[..1], Deficiency::Deutan, 1.0).min_difference().is_none());
+    }
+}
@@ -0,0 +1,197 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Color difference (ΔE) metrics.
+//!
+//! http://en.wikipedia.org/wiki/Color_difference
+//!
+//! All metrics but `delta_e_ok` work on CIELAB. Any `ToLab` color can be
+//! passed directly;
+//! note that `Rgb` is converted as linear light, so gamma-encoded values
+//! should be wrapped in `Srgb` first. Roughly, a ΔE of `1` is the smallest
+//! difference a trained observer can notice.
+
+use core::f64::consts::PI;
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use math::*;
+
+use {Lab, ToLab, Oklab, ToOklab};
+
+/// The weighting used by CIE94.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Cie94Application {
+    GraphicArts,
+    Textiles,
+}
+
+#[inline]
+fn lab<C: ToLab>(c: &C) -> Lab<f64> {
+    c.to_lab()
+}
+
+/// CIE76: the Euclidean distance in Lab.
+pub fn cie76<A: ToLab, B: ToLab>(a: &A, b: &B) -> f64 {
+    let (a, b) = (lab(a), lab(b));
+    ((a.l - b.l).powi(2) + (a.a - b.a).powi(2) + (a.b - b.b).powi(2)).sqrt()
+}
+
+/// CIE94. The metric is not symmetric: `reference` is the standard the
+/// `sample` is compared against.
+pub fn cie94<A: ToLab, B: ToLab>(reference: &A, sample: &B, application: Cie94Application) -> f64 {
+    let (a, b) = (lab(reference), lab(sample));
+    let (kl, k1, k2) = match application {
+        Cie94Application::GraphicArts => (1.0, 0.045, 0.015),
+        Cie94Application::Textiles => (2.0, 0.048, 0.014),
+    };
+    let (c1, c2) = (a.chroma(), b.chroma());
+    let dl = a.l - b.l;
+    let dc = c1 - c2;
+    let dh2 = ((a.a - b.a).powi(2) + (a.b - b.b).powi(2) - dc * dc).max(0.0);
+    let sc = 1.0 + k1 * c1;
+    let sh = 1.0 + k2 * c1;
+    ((dl / kl).powi(2) + (dc / sc).powi(2) + dh2 / (sh * sh)).sqrt()
+}
+
+/// CIEDE2000, following Sharma, Wu and Dalal (2005).
+pub fn ciede2000<A: ToLab, B: ToLab>(a: &A, b: &B) -> f64 {
+    let (x, y) = (lab(a), lab(b));
+
+    let c_bar = (x.chroma() + y.chroma()) / 2.0;
+    let c_bar7 = c_bar.powi(7);
+    let g = 0.5 * (1.0 - (c_bar7 / (c_bar7 + 25f64.powi(7))).sqrt());
+    let (a1, a2) = ((1.0 + g) * x.a, (1.0 + g) * y.a);
+    let (c1, c2) = (a1.hypot(x.b), a2.hypot(y.b));
+    let hue = |b: f64, a: f64| if b == 0.0 && a == 0.0 { 0.0 } else {
+        let h = b.atan2(a).to_degrees();
+        if h < 0.0 { h + 360.0 } else { h }
+    };
+    let (h1, h2) = (hue(x.b, a1), hue(y.b, a2));
+
+    let dl = y.l - x.l;
+    let dc = c2 - c1;
+    let dh = if c1 * c2 == 0.0 { 0.0 }
+             else if (h2 - h1).abs() <= 180.0 { h2 - h1 }
+             else if h2 - h1 > 180.0 { h2 - h1 - 360.0 }
+             else { h2 - h1 + 360.0 };
+    let dh = 2.0 * (c1 * c2).sqrt() * (dh.to_radians() / 2.0).sin();
+
+    let l_bar = (x.l + y.l) / 2.0;
+    let c_bar = (c1 + c2) / 2.0;
+    let h_bar = if c1 * c2 == 0.0 { h1 + h2 }
+                else if (h1 - h2).abs() <= 180.0 { (h1 + h2) / 2.0 }
+                else if h1 + h2 < 360.0 { (h1 + h2 + 360.0) / 2.0 }
+                else { (h1 + h2 - 360.0) / 2.0 };
+
+    let t = 1.0 - 0.17 * (h_bar - 30.0).to_radians().cos()
+                + 0.24 * (2.0 * h_bar).to_radians().cos()
+                + 0.32 * (3.0 * h_bar + 6.0).to_radians().cos()
+                - 0.20 * (4.0 * h_bar - 63.0).to_radians().cos();
+    let d_theta = 30.0 * (-((h_bar - 275.0) / 25.0).powi(2)).exp();
+    let c_bar7 = c_bar.powi(7);
+    let rc = 2.0 * (c_bar7 / (c_bar7 + 25f64.powi(7))).sqrt();
+    let sl = 1.0 + 0.015 * (l_bar - 50.0).powi(2) / (20.0 + (l_bar - 50.0).powi(2)).sqrt();
+    let sc = 1.0 + 0.045 * c_bar;
+    let sh = 1.0 + 0.015 * c_bar * t;
+    let rt = -(2.0 * d_theta * PI / 180.0).sin() * rc;
+
+    ((dl / sl).powi(2) + (dc / sc).powi(2) + (dh / sh).powi(2) + rt * (dc / sc) * (dh / sh)).sqrt()
+}
+
+/// CMC l:c, commonly used with `2:1` for acceptability and `1:1` for
+/// perceptibility. Like CIE94 it is not symmetric.
+pub fn cmc<A: ToLab, B: ToLab>(reference: &A, sample: &B, l: f64, c: f64) -> f64 {
+    let (a, b) = (lab(reference), lab(sample));
+    let (c1, c2) = (a.chroma(), b.chroma());
+    let dl = a.l - b.l;
+    let dc = c1 - c2;
+    let dh2 = ((a.a - b.a).powi(2) + (a.b - b.b).powi(2) - dc * dc).max(0.0);
+    let h1 = a.hue().0;
+
+    let sl = if a.l < 16.0 { 0.511 } else { 0.040975 * a.l / (1.0 + 0.01765 * a.l) };
+    let sc = 0.0638 * c1 / (1.0 + 0.0131 * c1) + 0.638;
+    let f = (c1.powi(4) / (c1.powi(4) + 1900.0)).sqrt();
+    let t = if (164.0..=345.0).contains(&h1) {
+        0.56 + (0.2 * (h1 + 168.0).to_radians().cos()).abs()
+    } else {
+        0.36 + (0.4 * (h1 + 35.0).to_radians().cos()).abs()
+    };
+    let sh = sc * (f * t + 1.0 - f);
+    ((dl / (l * sl)).powi(2) + (dc / (c * sc)).powi(2) + dh2 / (sh * sh)).sqrt()
+}
+
+/// ΔEOK: the Euclidean distance in Oklab, as used by CSS gamut mapping.
+/// The values are on Oklab's scale, so roughly `0.02` is just noticeable.
+pub fn delta_e_ok<A: ToOklab, B: ToOklab>(a: &A, b: &B) -> f64 {
+    let (a, b): (Oklab<f64>, Oklab<f64>) = (a.to_oklab(), b.to_oklab());
+    ((a.l - b.l).powi(2) + (a.a - b.a).powi(2) + (a.b - b.b).powi(2)).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use {Lab, Oklab, Rgb, Srgb};
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-4, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn test_cie76() {
+        assert_eq!(cie76(&Lab::new(50.0, 0.0, 0.0), &Lab::new(53.0, 4.0, 0.0)), 5.0);
+        assert_eq!(cie76(&Rgb::<u8>::new(0x12, 0x34, 0x56), &Rgb::<u8>::new(0x12, 0x34, 0x56)), 0.0);
+        assert_close(cie76(&Srgb::<u8>::new(0x00, 0x00, 0x00), &Srgb::<u8>::new(0xFF, 0xFF, 0xFF)), 100.0);
+    }
+
+    #[test]
+    fn test_ciede2000() {
+        // test data from Sharma, Wu and Dalal
+        let pairs = [
+            ((50.0, 2.6772, -79.7751), (50.0, 0.0, -82.7485), 2.0425),
+            ((50.0, 0.0, 0.0), (50.0, -1.0, 2.0), 2.3669),
+            ((50.0, 2.49, -0.001), (50.0, -2.49, 0.0011), 7.2195),
+            ((50.0, 2.5, 0.0), (73.0, 25.0, -18.0), 27.1492),
+            ((60.2574, -34.0099, 36.2677), (60.4626, -34.1751, 39.4387), 1.2644),
+            ((22.7233, 20.0904, -46.694), (23.0331, 14.973, -42.5619), 2.0373),
+            ((90.9257, -0.5406, -0.9208), (88.6381, -0.8985, -0.7239), 1.5381),
+        ];
+        for &((l1, a1, b1), (l2, a2, b2), de) in pairs.iter() {
+            let (x, y) = (Lab::new(l1, a1, b1), Lab::new(l2, a2, b2));
+            assert_close(ciede2000(&x, &y), de);
+            assert_close(ciede2000(&y, &x), de);
+        }
+    }
+
+    #[test]
+    fn test_cie94_cmc() {
+        let x = Lab::<f64>::new(50.0, 2.5, 0.0);
+        let y = Lab::<f64>::new(73.0, 25.0, -18.0);
+        assert_close(cie94(&x, &y, Cie94Application::GraphicArts), 34.6892);
+        assert_close(cie94(&x, &y, Cie94Application::Textiles), 28.2503);
+        assert_close(cmc(&x, &y, 2.0, 1.0), 37.9233);
+        assert_close(cmc(&x, &y, 1.0, 1.0), 42.1088);
+
+        let x = Lab::<f64>::new(60.2574, -34.0099, 36.2677);
+        let y = Lab::<f64>::new(60.4626, -34.1751, 39.4387);
+        assert_close(cie94(&x, &y, Cie94Application::GraphicArts), 1.3910);
+        assert_close(cmc(&x, &y, 2.0, 1.0), 1.4205);
+    }
+
+    #[test]
+    fn test_delta_e_ok() {
+        assert_eq!(delta_e_ok(&Oklab::new(0.5, 0.0, 0.0), &Oklab::new(0.5, 0.03, 0.04)), 0.05);
+        assert_close(delta_e_ok(&Srgb::<u8>::new(0x00, 0x00, 0x00), &Srgb::<u8>::new(0xFF, 0xFF, 0xFF)), 1.0);
+    }
+}
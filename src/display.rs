@@ -0,0 +1,195 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `Display` for the color types, for logs and messages.
+//!
+//! `Rgb` and `Srgb` print as `#rrggbb`, quantized to 8 bits as `to_rgb::<u8>()`
+//! does. The other types print as a function of their components, such as
+//! `hsv(210°, 50%, 40%)`: components in `[0, 1]` as percentages, hues in
+//! degrees, and everything else as is. Whole numbers are printed without
+//! decimals and others with a few, and the precision of the formatter, as in
+//! `{:.3}`, overrides both. Colors with alpha append it as a percentage, as
+//! in `#336699 / 50%`.
+//!
+//! For output that CSS can read back, see `css::FormatCss`.
+
+use core::fmt;
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use math::*;
+
+use angle::Deg;
+
+use AlphaColor;
+use {Channel, Rgb, Rg, Srgb, Luma, Hsv, Hsl, Hwb, Hsi, Hsp, Ryb, Cmyk, YCbCr};
+use {Xyz, XyY, Lab, Lch, Luv, Lchuv, Oklab, Oklch, Hsluv, Hpluv, Cam16, Cam16Ucs, Jzazbz, ICtCp};
+use channel::cast;
+
+enum Part {
+    Number(f64),
+    Percent(f64),
+    Hue(f64),
+}
+
+#[inline]
+fn number<T: Channel>(x: T) -> Part {
+    Part::Number(cast(x))
+}
+
+/// A component in `[0, 1]`, as a percentage.
+#[inline]
+fn percent<T: Channel>(x: T) -> Part {
+    Part::Percent(x.to_channel_f64() * 100.0)
+}
+
+/// A component that is a percentage already.
+#[inline]
+fn raw_percent<T: Channel>(x: T) -> Part {
+    Part::Percent(cast(x))
+}
+
+#[inline]
+fn hue<T: Channel>(h: Deg<T>) -> Part {
+    Part::Hue(cast(h.0))
+}
+
+/// Writes `x` with the formatter's precision, or else without decimals if
+/// it is whole and with `decimals` if not.
+fn write_number(f: &mut fmt::Formatter, x: f64, decimals: usize) -> fmt::Result {
+    // avoid printing `-0`
+    let x = if x == 0.0 { 0.0 } else { x };
+    match f.precision() {
+        Some(p) => write!(f, "{:.*}", p, x),
+        None if x.fract() == 0.0 => write!(f, "{}", x),
+        None => write!(f, "{:.*}", decimals, x),
+    }
+}
+
+/// Writes `name(a, b, c)`. Plain numbers default to `decimals`, and
+/// percentages and hues to none.
+fn write_parts(f: &mut fmt::Formatter, name: &str, parts: &[Part], decimals: usize) -> fmt::Result {
+    write!(f, "{}(", name)?;
+    for (i, part) in parts.iter().enumerate() {
+        if i > 0 {
+            f.write_str(", ")?;
+        }
+        match *part {
+            Part::Number(x) => write_number(f, x, decimals)?,
+            Part::Percent(x) => { write_number(f, x, 0)?; f.write_str("%")?; }
+            Part::Hue(x) => { write_number(f, x, 0)?; f.write_str("°")?; }
+        }
+    }
+    f.write_str(")")
+}
+
+#[inline]
+fn write_hex<T: Channel>(f: &mut fmt::Formatter, r: T, g: T, b: T) -> fmt::Result {
+    write!(f, "#{:02x}{:02x}{:02x}", r.to_channel_u8(), g.to_channel_u8(), b.to_channel_u8())
+}
+
+impl<T: Channel> fmt::Display for Rgb<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_hex(f, self.r, self.g, self.b)
+    }
+}
+
+impl<T: Channel> fmt::Display for Srgb<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_hex(f, self.r, self.g, self.b)
+    }
+}
+
+impl<T: Channel, C: fmt::Display> fmt::Display for AlphaColor<T, C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.c, f)?;
+        f.write_str(" / ")?;
+        write_number(f, self.a.to_channel_f64() * 100.0, 0)?;
+        f.write_str("%")
+    }
+}
+
+macro_rules! display_impls {
+    ($($name:ident, $label:expr, $decimals:expr, |$c:ident| $parts:expr;)*) => {
+        $(
+            impl<T: Channel> fmt::Display for $name<T> {
+                #[inline]
+                fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    let $c = self;
+                    write_parts(f, $label, &$parts, $decimals)
+                }
+            }
+        )*
+    }
+}
+
+display_impls! {
+    Rg,       "rg",       0, |c| [percent(c.r), percent(c.g)];
+    Luma,     "luma",     0, |c| [percent(c.l)];
+    Hsv,      "hsv",      0, |c| [hue(c.h), percent(c.s), percent(c.v)];
+    Hsl,      "hsl",      0, |c| [hue(c.h), percent(c.s), percent(c.l)];
+    Hwb,      "hwb",      0, |c| [hue(c.h), percent(c.w), percent(c.b)];
+    Hsi,      "hsi",      0, |c| [hue(c.h), percent(c.s), percent(c.i)];
+    Hsp,      "hsp",      0, |c| [hue(c.h), percent(c.s), percent(c.p)];
+    Ryb,      "ryb",      0, |c| [percent(c.r), percent(c.y), percent(c.b)];
+    Cmyk,     "cmyk",     0, |c| [percent(c.c), percent(c.m), percent(c.y), percent(c.k)];
+    YCbCr,    "ycbcr",    3, |c| [number(c.y), number(c.cb), number(c.cr)];
+    Xyz,      "xyz",      4, |c| [number(c.x), number(c.y), number(c.z)];
+    XyY,      "xyY",      4, |c| [number(c.x), number(c.y), number(c.luminance)];
+    Lab,      "lab",      2, |c| [number(c.l), number(c.a), number(c.b)];
+    Lch,      "lch",      2, |c| [number(c.l), number(c.c), hue(c.h)];
+    Luv,      "luv",      2, |c| [number(c.l), number(c.u), number(c.v)];
+    Lchuv,    "lchuv",    2, |c| [number(c.l), number(c.c), hue(c.h)];
+    Oklab,    "oklab",    4, |c| [number(c.l), number(c.a), number(c.b)];
+    Oklch,    "oklch",    4, |c| [number(c.l), number(c.c), hue(c.h)];
+    Hsluv,    "hsluv",    0, |c| [hue(c.h), raw_percent(c.s), raw_percent(c.l)];
+    Hpluv,    "hpluv",    0, |c| [hue(c.h), raw_percent(c.p), raw_percent(c.l)];
+    Cam16,    "cam16",    2, |c| [number(c.j), number(c.c), hue(c.h)];
+    Cam16Ucs, "cam16ucs", 2, |c| [number(c.j), number(c.a), number(c.b)];
+    Jzazbz,   "jzazbz",   4, |c| [number(c.jz), number(c.az), number(c.bz)];
+    ICtCp,    "ictcp",    4, |c| [number(c.i), number(c.ct), number(c.cp)];
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+    use angle::Deg;
+
+    use {Rgb, Rgba, Srgb, Hsv, Hsva, Cmyk, Lab, Lch, Hsluv, Xyz};
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Rgb::<u8>::new(0x33, 0x66, 0x99).to_string(), "#336699");
+        assert_eq!(Srgb::<f64>::new(1.0, 0.5, 0.0).to_string(), "#ff7f00");
+        assert_eq!(Hsv::<f64>::new(Deg(210.0), 0.5, 0.4).to_string(), "hsv(210°, 50%, 40%)");
+        assert_eq!(Hsv::<u8>::new(Deg(210), 0x80, 0x66).to_string(), "hsv(210°, 50%, 40%)");
+        assert_eq!(Cmyk::<f64>::new(0.0, 0.5, 1.0, 0.1).to_string(), "cmyk(0%, 50%, 100%, 10%)");
+        assert_eq!(Lab::<f64>::new(53.2329, 80.1093, 67.2201).to_string(), "lab(53.23, 80.11, 67.22)");
+        assert_eq!(Lch::<f64>::new(50.0, -0.0, Deg(40.0)).to_string(), "lch(50, 0, 40°)");
+        assert_eq!(Hsluv::<f64>::new(Deg(12.4), 90.0, 60.0).to_string(), "hsluv(12°, 90%, 60%)");
+        assert_eq!(Xyz::<f64>::new(0.95047, 1.0, 1.08883).to_string(), "xyz(0.9505, 1, 1.0888)");
+    }
+
+    #[test]
+    fn test_display_options() {
+        assert_eq!(format!("{:.1}", Hsv::<f64>::new(Deg(210.0), 0.5, 0.4)), "hsv(210.0°, 50.0%, 40.0%)");
+        assert_eq!(format!("{:.1}", Lab::<f64>::new(50.0, 0.26, -10.0)), "lab(50.0, 0.3, -10.0)");
+        // hex ignores the precision
+        assert_eq!(format!("{:.3}", Rgb::<u8>::new(1, 2, 3)), "#010203");
+        assert_eq!(Rgba::<u8>::new(0x33, 0x66, 0x99, 0x80).to_string(), "#336699 / 50%");
+        assert_eq!(Hsva { c: Hsv::<f64>::new(Deg(0.0), 1.0, 1.0), a: 0.25 }.to_string(), "hsv(0°, 100%, 100%) / 25%");
+    }
+}
@@ -0,0 +1,214 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Dithering images down to a fixed palette or a lower bit depth.
+//!
+//! Images are row-major slices of `width` pixels per row. Errors are
+//! measured on the encoded 8-bit components, so images are dithered in
+//! their encoded space, as most tools do.
+
+use alloc::vec::Vec;
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use math::*;
+
+use {Rgb, Srgb, ToRgb};
+use packed::Rgb565;
+use palette::{Metric, Palette};
+
+/// A dithering method.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Dither {
+    /// Plain nearest color, without dithering.
+    None,
+    /// Error diffusion to four neighbours, keeping all of the error.
+    FloydSteinberg,
+    /// Error diffusion to six neighbours, dropping a quarter of the error.
+    /// Gives crisper, higher contrast results.
+    Atkinson,
+    /// Ordered dithering with a Bayer matrix of the given size, which must
+    /// be a power of two from 2 to 16. Stable under animation and trivially
+    /// parallel, but with a visible pattern.
+    Bayer(usize),
+}
+
+/// `(dx, dy, weight)`, with the weights divided by the second element.
+type Kernel = (&'static [(isize, usize, f64)], f64);
+
+const FLOYD_STEINBERG: Kernel = (&[(1, 0, 7.0), (-1, 1, 3.0), (0, 1, 5.0), (1, 1, 1.0)], 16.0);
+const ATKINSON: Kernel = (&[(1, 0, 1.0), (2, 0, 1.0), (-1, 1, 1.0), (0, 1, 1.0), (1, 1, 1.0), (0, 2, 1.0)], 8.0);
+
+/// The Bayer threshold matrix of size `n`, normalized to `(-0.5, 0.5)`.
+fn bayer(n: usize) -> Vec<f64> {
+    assert!(n.is_power_of_two() && (2..=16).contains(&n), "invalid Bayer matrix size {}", n);
+    let mut m = vec![0usize];
+    let mut size = 1;
+    while size < n {
+        let next = size * 2;
+        let mut out = vec![0; next * next];
+        for y in 0..size {
+            for x in 0..size {
+                let v = 4 * m[y * size + x];
+                out[y * next + x] = v;
+                out[y * next + x + size] = v + 2;
+                out[(y + size) * next + x] = v + 3;
+                out[(y + size) * next + x + size] = v + 1;
+            }
+        }
+        m = out;
+        size = next;
+    }
+    m.iter().map(|&v| (v as f64 + 0.5) / (n * n) as f64 - 0.5).collect()
+}
+
+/// Runs `method` over the image. `pick` maps a target color to the color
+/// actually output and its representation; `spread` is the per-channel step
+/// between output levels, used to scale ordered dithering.
+fn dither<R, F>(pixels: &[Srgb<u8>], width: usize, method: Dither, spread: [f64; 3], mut pick: F) -> Vec<R>
+    where F: FnMut([f64; 3]) -> ([f64; 3], R)
+{
+    assert!(width > 0 && pixels.len().is_multiple_of(width), "{} pixels do not fill rows of {}", pixels.len(), width);
+    let value = |p: &Srgb<u8>| [p.r as f64, p.g as f64, p.b as f64];
+    let kernel = match method {
+        Dither::None => return pixels.iter().map(|p| pick(value(p)).1).collect(),
+        Dither::Bayer(n) => {
+            let m = bayer(n);
+            return pixels.iter().enumerate().map(|(i, p)| {
+                let t = m[(i / width % n) * n + i % width % n];
+                let c = value(p);
+                pick([c[0] + t * spread[0], c[1] + t * spread[1], c[2] + t * spread[2]]).1
+            }).collect();
+        }
+        Dither::FloydSteinberg => FLOYD_STEINBERG,
+        Dither::Atkinson => ATKINSON,
+    };
+
+    let height = pixels.len() / width;
+    let mut work: Vec<[f64; 3]> = pixels.iter().map(value).collect();
+    let mut out = Vec::with_capacity(pixels.len());
+    for y in 0..height {
+        for x in 0..width {
+            let c = work[y * width + x];
+            let (chosen, r) = pick(c);
+            out.push(r);
+            let err = [c[0] - chosen[0], c[1] - chosen[1], c[2] - chosen[2]];
+            for &(dx, dy, w) in kernel.0 {
+                let (nx, ny) = (x as isize + dx, y + dy);
+                if nx < 0 || nx as usize >= width || ny >= height {
+                    continue;
+                }
+                let n = &mut work[ny * width + nx as usize];
+                for i in 0..3 {
+                    n[i] += err[i] * w / kernel.1;
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Dithers an image to `palette`, returning the palette index of each
+/// pixel. Ordered dithering assumes the palette is spread roughly evenly,
+/// with about the cube root of its size levels per channel.
+///
+/// # Panics
+///
+/// If the palette is empty or has more than 256 entries, or the image does
+/// not fill whole rows.
+pub fn dither_to_palette(pixels: &[Srgb<u8>], width: usize, palette: &[Srgb<u8>], method: Dither) -> Vec<u8> {
+    assert!(!palette.is_empty() && palette.len() <= 256, "invalid palette size {}", palette.len());
+    let step = 255.0 / ((palette.len() as f64).cbrt() - 1.0).max(1.0);
+    let palette = Palette::with_metric(palette.to_vec(), Metric::RgbEuclidean);
+    dither(pixels, width, method, [step; 3], |c| {
+        let i = palette.nearest_point(c).unwrap();
+        let p = palette.colors()[i];
+        ([p.r as f64, p.g as f64, p.b as f64], i as u8)
+    })
+}
+
+/// Dithers an image down to RGB565.
+///
+/// # Panics
+///
+/// If the image does not fill whole rows.
+pub fn dither_to_rgb565(pixels: &[Srgb<u8>], width: usize, method: Dither) -> Vec<Rgb565> {
+    dither(pixels, width, method, [255.0 / 31.0, 255.0 / 63.0, 255.0 / 31.0], |c| {
+        // the display takes the encoded values as they are
+        let packed = Rgb565::from_rgb(&Rgb::<f64>::new(c[0] / 255.0, c[1] / 255.0, c[2] / 255.0));
+        let out: Rgb<u8> = packed.to_rgb();
+        ([out.r as f64, out.g as f64, out.b as f64], packed)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ToRgb;
+
+    const BLACK_WHITE: [Srgb<u8>; 2] = [Srgb::new(0, 0, 0), Srgb::new(255, 255, 255)];
+
+    fn white_fraction(indices: &[u8]) -> f64 {
+        indices.iter().filter(|&&i| i == 1).count() as f64 / indices.len() as f64
+    }
+
+    #[test]
+    fn test_gray_to_black_and_white() {
+        let gray = vec![Srgb::new(64u8, 64, 64); 16 * 16];
+        assert_eq!(white_fraction(&dither_to_palette(&gray, 16, &BLACK_WHITE, Dither::None)), 0.0);
+        let fs = white_fraction(&dither_to_palette(&gray, 16, &BLACK_WHITE, Dither::FloydSteinberg));
+        assert!((fs - 0.25).abs() < 0.02, "{}", fs);
+        let bayer = white_fraction(&dither_to_palette(&gray, 16, &BLACK_WHITE, Dither::Bayer(4)));
+        assert_eq!(bayer, 0.25);
+        // Atkinson drops part of the error, so mid tones come out darker
+        let atkinson = white_fraction(&dither_to_palette(&gray, 16, &BLACK_WHITE, Dither::Atkinson));
+        assert!(atkinson > 0.0 && atkinson <= 0.25, "{}", atkinson);
+    }
+
+    #[test]
+    fn test_exact_colors_kept() {
+        let pixels = [Srgb::new(0u8, 0, 0), Srgb::new(255, 255, 255), Srgb::new(255, 255, 255), Srgb::new(0, 0, 0)];
+        for &method in [Dither::FloydSteinberg, Dither::Atkinson].iter() {
+            assert_eq!(dither_to_palette(&pixels, 2, &BLACK_WHITE, method), vec![0, 1, 1, 0]);
+        }
+    }
+
+    #[test]
+    fn test_bayer_matrix() {
+        let m = bayer(2);
+        assert_eq!(m, vec![-0.375, 0.125, 0.375, -0.125]);
+        let mut sorted = bayer(8);
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert!(sorted.windows(2).all(|w| (w[1] - w[0] - 1.0 / 64.0).abs() < 1e-12));
+    }
+
+    #[test]
+    fn test_rgb565() {
+        // a level between two representable reds averages out to it
+        let pixels = vec![Srgb::new(4u8, 0, 0); 8 * 8];
+        let plain = dither_to_rgb565(&pixels, 8, Dither::None);
+        assert!(plain.iter().all(|&p| p == plain[0]));
+        for &method in [Dither::FloydSteinberg, Dither::Bayer(8)].iter() {
+            let out = dither_to_rgb565(&pixels, 8, method);
+            let mean = out.iter().map(|p| p.to_rgb::<u8>().r as f64).sum::<f64>() / out.len() as f64;
+            assert!((mean - 4.0).abs() < 0.5, "{:?}: {}", method, mean);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_partial_rows() {
+        dither_to_palette(&[Srgb::new(0, 0, 0); 5], 2, &BLACK_WHITE, Dither::None);
+    }
+}
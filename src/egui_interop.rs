@@ -0,0 +1,99 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Interoperability with `egui` colors.
+//!
+//! `egui::Color32` and `egui::Rgba` come from the `ecolor` crate, which
+//! `egui` re-exports, so this feature only depends on that. `Color32` holds
+//! gamma-encoded sRGB bytes and `Rgba` linear floats, both with
+//! premultiplied alpha, so they convert to and from `Srgba<u8>` and
+//! `Rgba<f32>`, multiplying and dividing the alpha on the way. Dividing it
+//! out of a nearly transparent `Color32` loses precision, as it does in
+//! `egui` itself.
+
+use ecolor::{self, Color32};
+
+use {Rgb, Rgba, Srgb, Srgba};
+
+impl From<Color32> for Srgba<u8> {
+    #[inline]
+    fn from(c: Color32) -> Srgba<u8> {
+        let [r, g, b, a] = c.to_srgba_unmultiplied();
+        Srgba { c: Srgb::new(r, g, b), a }
+    }
+}
+
+impl From<Srgba<u8>> for Color32 {
+    #[inline]
+    fn from(c: Srgba<u8>) -> Color32 {
+        Color32::from_rgba_unmultiplied(c.c.r, c.c.g, c.c.b, c.a)
+    }
+}
+
+impl From<Srgb<u8>> for Color32 {
+    #[inline]
+    fn from(c: Srgb<u8>) -> Color32 {
+        Color32::from_rgb(c.r, c.g, c.b)
+    }
+}
+
+impl From<ecolor::Rgba> for Rgba<f32> {
+    #[inline]
+    fn from(c: ecolor::Rgba) -> Rgba<f32> {
+        let [r, g, b, a] = c.to_rgba_unmultiplied();
+        Rgba::new(r, g, b, a)
+    }
+}
+
+impl From<Rgba<f32>> for ecolor::Rgba {
+    #[inline]
+    fn from(c: Rgba<f32>) -> ecolor::Rgba {
+        ecolor::Rgba::from_rgba_unmultiplied(c.c.r, c.c.g, c.c.b, c.a)
+    }
+}
+
+impl From<Rgb<f32>> for ecolor::Rgba {
+    #[inline]
+    fn from(c: Rgb<f32>) -> ecolor::Rgba {
+        ecolor::Rgba::from_rgb(c.r, c.g, c.b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ecolor::{self, Color32};
+
+    use {Rgb, Rgba, Srgb, Srgba};
+
+    #[test]
+    fn test_color32() {
+        assert_eq!(Color32::from(Srgb::new(0xFFu8, 0x80, 0x00)), Color32::from_rgb(0xFF, 0x80, 0x00));
+        let c = Srgba::from_color(Srgb::new(0x10u8, 0x20, 0x30), 0xFF);
+        assert_eq!(Srgba::from(Color32::from(c)), c);
+        // egui stores the alpha multiplied in
+        let c = Color32::from(Srgba::from_color(Srgb::new(0xFFu8, 0x00, 0x00), 0x80));
+        assert_eq!(c.to_array(), [0x80, 0x00, 0x00, 0x80]);
+        assert_eq!(Srgba::from(c), Srgba::from_color(Srgb::new(0xFF, 0x00, 0x00), 0x80));
+    }
+
+    #[test]
+    fn test_rgba() {
+        let c = Rgba::new(1.0f32, 0.5, 0.25, 0.5);
+        let e = ecolor::Rgba::from(c);
+        assert_eq!(e.to_array(), [0.5, 0.25, 0.125, 0.5]);
+        assert_eq!(Rgba::from(e), c);
+        assert_eq!(ecolor::Rgba::from(Rgb::new(0.1f32, 0.2, 0.3)), ecolor::Rgba::from_rgb(0.1, 0.2, 0.3));
+    }
+}
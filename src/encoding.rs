@@ -0,0 +1,86 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The transfer encodings `Rgb` is tagged with.
+//!
+//! `Rgb<T>` is `Rgb<T, Linear>`, linear light, and `Srgb<T>` is
+//! `Rgb<T, encoding::Srgb>`, gamma-encoded as stored in images and written
+//! in CSS. The two are distinct types, so a function taking one does not
+//! accept the other, and moving between them goes through `to_linear`,
+//! `to_srgb` or `ToRgb`, which decode and encode.
+
+use core::fmt::Debug;
+use core::hash::Hash;
+
+use Channel;
+
+/// A transfer encoding of `Rgb`. The markers carry no data.
+pub trait Encoding: Copy + Eq + Ord + Hash + Default + Debug + 'static {
+    /// The name of the color type, for `Debug`.
+    const NAME: &'static str;
+
+    /// Decodes a channel in `[0, 1]` to linear light.
+    fn decode(v: f64) -> f64;
+
+    /// Encodes a channel in linear light.
+    fn encode(v: f64) -> f64;
+}
+
+/// Linear light, the encoding of `Rgb<T>`.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+pub struct Linear;
+
+/// The sRGB transfer function, the encoding of `Srgb<T>`.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+pub struct Srgb;
+
+impl Encoding for Linear {
+    const NAME: &'static str = "Rgb";
+
+    #[inline]
+    fn decode(v: f64) -> f64 { v }
+
+    #[inline]
+    fn encode(v: f64) -> f64 { v }
+}
+
+impl Encoding for Srgb {
+    const NAME: &'static str = "Srgb";
+
+    #[inline]
+    fn decode(v: f64) -> f64 { v.srgb_to_linear() }
+
+    #[inline]
+    fn encode(v: f64) -> f64 { <f64 as Channel>::srgb_from_linear(v) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Encoding, Linear, Srgb};
+
+    #[test]
+    fn test_transfer() {
+        assert_eq!(Linear::decode(0.25), 0.25);
+        assert_eq!(Srgb::decode(1.0), 1.0);
+        assert!((Srgb::decode(0.5) - 0.214041).abs() < 1e-6);
+        assert!((Srgb::decode(Srgb::encode(0.3)) - 0.3).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_debug() {
+        assert_eq!(format!("{:?}", ::Rgb::<u8>::new(1, 2, 3)), "Rgb { r: 1, g: 2, b: 3 }");
+        assert_eq!(format!("{:?}", ::Srgb::<u8>::new(1, 2, 3)), "Srgb { r: 1, g: 2, b: 3 }");
+    }
+}
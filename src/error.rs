@@ -0,0 +1,178 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Fallible conversions for untrusted input.
+//!
+//! The `ToX` conversions never fail: out of range values pass through or
+//! are clamped by the channel cast. The `TryFrom` impls here instead
+//! reject floating point colors that do not fit an integer channel type,
+//! and strings that are not CSS colors.
+
+use core::convert::TryFrom;
+use core::error::Error;
+use core::fmt;
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use math::*;
+
+use AlphaColor;
+use {Rgb, Rgba, Srgb, Srgba, ToRgb, ToSrgb};
+use css::{self, ParseError};
+
+/// The ways a fallible color conversion can fail.
+#[derive(Clone, PartialEq, Debug)]
+pub enum ColorError {
+    /// A channel lies outside `[0, 1]`, e.g. an out of gamut color.
+    OutOfRange,
+    /// A channel is NaN or infinite.
+    NotFinite,
+    /// A string could not be parsed as a color.
+    Parse(ParseError),
+    /// A white point or a set of primaries that spans no color space, such
+    /// as a white with a zero or non-finite component.
+    Degenerate,
+}
+
+impl fmt::Display for ColorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ColorError::OutOfRange => write!(f, "color channel out of range"),
+            ColorError::NotFinite => write!(f, "color channel is not finite"),
+            ColorError::Parse(ref e) => write!(f, "{}", e),
+            ColorError::Degenerate => write!(f, "degenerate white point or primaries"),
+        }
+    }
+}
+
+impl Error for ColorError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            ColorError::Parse(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<ParseError> for ColorError {
+    #[inline]
+    fn from(e: ParseError) -> ColorError {
+        ColorError::Parse(e)
+    }
+}
+
+/// Checks a normalized channel and scales it to `max`, rounding to nearest.
+#[inline]
+fn quantize(v: f64, max: f64) -> Result<f64, ColorError> {
+    if !v.is_finite() {
+        Err(ColorError::NotFinite)
+    } else if !(0.0..=1.0).contains(&v) {
+        Err(ColorError::OutOfRange)
+    } else {
+        Ok((v * max).round())
+    }
+}
+
+macro_rules! try_from_impls {
+    ($($ty:ident, $alpha:ident, $to:ident;)*) => {
+        $(
+            try_from_impls!(@float $ty, $alpha, f32, u8);
+            try_from_impls!(@float $ty, $alpha, f32, u16);
+            try_from_impls!(@float $ty, $alpha, f64, u8);
+            try_from_impls!(@float $ty, $alpha, f64, u16);
+
+            impl<'a> TryFrom<&'a str> for $ty<u8> {
+                type Error = ColorError;
+
+                /// Parses a CSS color, which must be opaque and inside the
+                /// sRGB gamut. `Rgb` colors are decoded to linear light.
+                fn try_from(s: &'a str) -> Result<$ty<u8>, ColorError> {
+                    let c = $alpha::<u8>::try_from(s)?;
+                    if c.a == 0xFF { Ok(c.c) } else { Err(ColorError::OutOfRange) }
+                }
+            }
+
+            impl<'a> TryFrom<&'a str> for $alpha<u8> {
+                type Error = ColorError;
+
+                /// Parses a CSS color, which must be inside the sRGB gamut.
+                /// `Rgba` colors are decoded to linear light.
+                fn try_from(s: &'a str) -> Result<$alpha<u8>, ColorError> {
+                    let c = css::parse(s)?.to_srgba();
+                    let c = AlphaColor { c: c.c.$to::<f32>(), a: c.a };
+                    $alpha::<u8>::try_from(c)
+                }
+            }
+        )*
+    };
+    (@float $ty:ident, $alpha:ident, $f:ident, $i:ident) => {
+        impl TryFrom<$ty<$f>> for $ty<$i> {
+            type Error = ColorError;
+
+            fn try_from(c: $ty<$f>) -> Result<$ty<$i>, ColorError> {
+                let max = $i::MAX as f64;
+                Ok($ty::new(quantize(c.r as f64, max)? as $i,
+                            quantize(c.g as f64, max)? as $i,
+                            quantize(c.b as f64, max)? as $i))
+            }
+        }
+
+        impl TryFrom<$alpha<$f>> for $alpha<$i> {
+            type Error = ColorError;
+
+            fn try_from(c: $alpha<$f>) -> Result<$alpha<$i>, ColorError> {
+                let max = $i::MAX as f64;
+                Ok(AlphaColor { c: $ty::<$i>::try_from(c.c)?, a: quantize(c.a as f64, max)? as $i })
+            }
+        }
+    };
+}
+
+try_from_impls! {
+    Rgb, Rgba, to_rgb;
+    Srgb, Srgba, to_srgb;
+}
+
+#[cfg(test)]
+mod tests {
+    use core::convert::TryFrom;
+    use core::f32;
+    use alloc::string::ToString;
+    use super::ColorError;
+    use css::ParseError;
+    use {Rgb, Rgba, Srgb, Srgba};
+
+    #[test]
+    fn test_try_from_float() {
+        assert_eq!(Rgb::<u8>::try_from(Rgb::new(1.0f32, 0.5, 0.0)), Ok(Rgb::new(255, 128, 0)));
+        assert_eq!(Rgb::<u16>::try_from(Rgb::new(1.0f64, 0.5, 0.0)), Ok(Rgb::new(0xFFFF, 0x8000, 0)));
+        assert_eq!(Rgb::<u8>::try_from(Rgb::new(1.1f32, 0.5, 0.0)), Err(ColorError::OutOfRange));
+        assert_eq!(Srgb::<u8>::try_from(Srgb::new(-0.1f64, 0.5, 0.0)), Err(ColorError::OutOfRange));
+        assert_eq!(Rgb::<u8>::try_from(Rgb::new(f32::NAN, 0.5, 0.0)), Err(ColorError::NotFinite));
+        assert_eq!(Rgba::<u8>::try_from(Rgba::new(0.0f32, 0.0, 0.0, f32::INFINITY)), Err(ColorError::NotFinite));
+        assert_eq!(Srgba::<u8>::try_from(Srgba { c: Srgb::new(0.0f32, 0.0, 1.0), a: 0.5 }),
+                   Ok(Srgba { c: Srgb::new(0, 0, 255), a: 128 }));
+    }
+
+    #[test]
+    fn test_try_from_str() {
+        assert_eq!(Srgb::<u8>::try_from("#ff8000"), Ok(Srgb::new(0xFF, 0x80, 0x00)));
+        assert_eq!(Rgba::<u8>::try_from("rgb(0 0 255 / 50%)"), Ok(Rgba::new(0, 0, 255, 128)));
+        assert_eq!(Rgb::<u8>::try_from("rgb(0 0 255 / 50%)"), Err(ColorError::OutOfRange));
+        assert_eq!(Srgb::<u8>::try_from("#808080"), Ok(Srgb::new(0x80, 0x80, 0x80)));
+        assert_eq!(Rgb::<u8>::try_from("#808080"), Ok(Rgb::new(0x37, 0x37, 0x37)));
+        assert_eq!(Srgb::<u8>::try_from("oklch(90% 0.4 140)"), Err(ColorError::OutOfRange));
+        assert_eq!(Rgb::<u8>::try_from("nope"), Err(ColorError::Parse(ParseError::UnknownName("nope".to_string()))));
+    }
+}
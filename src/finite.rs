@@ -0,0 +1,157 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Checks for NaN and infinite components in float colors.
+//!
+//! Arithmetic on colors follows IEEE rules, so a NaN that gets into a color
+//! survives `mix`, clamping and conversions and ends up as an arbitrary
+//! value once cast to an integer channel. `Finite::sanitize` replaces such
+//! components with a fixed value:
+//!
+//! - NaN becomes `0`.
+//! - Infinities become the largest finite value of the same sign, so a
+//!   following `saturate` takes them to `0` or `1`.
+//!
+//! In debug builds, casting a NaN channel to `u8` or `u16` fails an
+//! assertion.
+
+use num::Float;
+
+use {FloatChannel, AlphaColor};
+use {Rgb, Srgb, Luma, Hsv, Hsl, Hwb, Hsi, Hsp, Ryb, Cmyk, YCbCr, Xyz, XyY, Lab, Lch, Luv, Lchuv, Oklab, Oklch, Hsluv, Hpluv, Cam16, Cam16Ucs, Jzazbz, ICtCp};
+
+pub trait Finite {
+    /// Whether every component is neither NaN nor infinite.
+    fn is_finite(&self) -> bool;
+    /// Whether any component is NaN.
+    fn has_nan(&self) -> bool;
+    /// Replaces NaN components with zero and infinite ones with the largest
+    /// finite value of the same sign.
+    fn sanitize(self) -> Self;
+}
+
+#[inline]
+fn sanitize<T: FloatChannel>(x: T) -> T {
+    if x.is_nan() {
+        T::zero()
+    } else if x.is_infinite() {
+        if x > T::zero() { Float::max_value() } else { Float::min_value() }
+    } else {
+        x
+    }
+}
+
+macro_rules! finite_impls {
+    ($($ty:ident, |$c:ident| [$($x:expr),*];)*) => {
+        $(
+            impl<T: FloatChannel> Finite for $ty<T> {
+                #[inline]
+                fn is_finite(&self) -> bool {
+                    let $c = self;
+                    $($x.is_finite())&&*
+                }
+
+                #[inline]
+                fn has_nan(&self) -> bool {
+                    let $c = self;
+                    $($x.is_nan())||*
+                }
+
+                #[inline]
+                fn sanitize(mut self) -> Self {
+                    {
+                        let $c = &mut self;
+                        $($x = sanitize($x);)*
+                    }
+                    self
+                }
+            }
+        )*
+    }
+}
+
+finite_impls! {
+    Rgb,   |c| [c.r, c.g, c.b];
+    Srgb,  |c| [c.r, c.g, c.b];
+    Luma,  |c| [c.l];
+    Hsv,   |c| [c.h.0, c.s, c.v];
+    Hsl,   |c| [c.h.0, c.s, c.l];
+    Hwb,   |c| [c.h.0, c.w, c.b];
+    Hsi,   |c| [c.h.0, c.s, c.i];
+    Hsp,   |c| [c.h.0, c.s, c.p];
+    Ryb,   |c| [c.r, c.y, c.b];
+    Cmyk,  |c| [c.c, c.m, c.y, c.k];
+    YCbCr, |c| [c.y, c.cb, c.cr];
+    Xyz,   |c| [c.x, c.y, c.z];
+    XyY,   |c| [c.x, c.y, c.luminance];
+    Lab,   |c| [c.l, c.a, c.b];
+    Lch,   |c| [c.l, c.c, c.h.0];
+    Luv,   |c| [c.l, c.u, c.v];
+    Lchuv, |c| [c.l, c.c, c.h.0];
+    Oklab, |c| [c.l, c.a, c.b];
+    Oklch, |c| [c.l, c.c, c.h.0];
+    Hsluv, |c| [c.h.0, c.s, c.l];
+    Hpluv, |c| [c.h.0, c.p, c.l];
+    Cam16, |c| [c.j, c.c, c.h.0, c.m, c.s, c.q];
+    Cam16Ucs, |c| [c.j, c.a, c.b];
+    Jzazbz, |c| [c.jz, c.az, c.bz];
+    ICtCp, |c| [c.i, c.ct, c.cp];
+}
+
+impl<T: FloatChannel, C: Finite> Finite for AlphaColor<T, C> {
+    #[inline]
+    fn is_finite(&self) -> bool {
+        self.c.is_finite() && self.a.is_finite()
+    }
+
+    #[inline]
+    fn has_nan(&self) -> bool {
+        self.c.has_nan() || self.a.is_nan()
+    }
+
+    #[inline]
+    fn sanitize(self) -> Self {
+        AlphaColor { c: self.c.sanitize(), a: sanitize(self.a) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {Color, FloatColor, Finite, Rgb, Rgba, Hsv, ToRgb};
+    use angle::*;
+
+    #[test]
+    fn test_finite() {
+        let c = Rgb::<f32>::new(0.5, f32::NAN, f32::INFINITY);
+        assert!(!c.is_finite());
+        assert!(c.has_nan());
+        assert_eq!(c.sanitize(), Rgb::new(0.5, 0.0, f32::MAX));
+        assert_eq!(c.sanitize().saturate(), Rgb::new(0.5, 0.0, 1.0));
+        assert!(c.sanitize().is_finite());
+
+        let c = Rgb::<f64>::new(0.2, 0.4, 0.6);
+        assert!(c.is_finite() && !c.has_nan());
+        assert_eq!(c.sanitize(), c);
+        assert!(c.mix(Rgb::new(f64::NAN, 0.0, 0.0), 0.5).has_nan());
+
+        let a = Rgba::<f32>::new(1.0, 1.0, 1.0, f32::NEG_INFINITY);
+        assert!(!a.is_finite() && !a.has_nan());
+        assert_eq!(a.sanitize(), Rgba::new(1.0, 1.0, 1.0, f32::MIN));
+
+        let h = Hsv::<f64>::new(Deg(f64::NAN), 1.0, 1.0);
+        assert!(h.has_nan());
+        assert_eq!(h.sanitize().to_rgb::<u8>(), Rgb::new(0xFF, 0x00, 0x00));
+    }
+}
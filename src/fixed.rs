@@ -0,0 +1,352 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A fixed-point channel, for targets without a floating point unit.
+//!
+//! `Fixed` stands in for `f32` where floats are emulated in software, as on
+//! Cortex-M0 and M3. Its arithmetic, its conversions to `u8` and `u16`, and
+//! conversion from `Hsv<Fixed>` to `Rgb<u8>` or `Rgb<u16>` use integers
+//! only. Converting any channel type to it goes through `f64`, as do the
+//! color spaces that need powers or roots.
+
+use core::fmt;
+use core::ops::{Add, Sub, Mul, Div, Rem, Neg};
+use core::str::FromStr;
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use math::*;
+
+use num::{Bounded, Num, NumCast, ToPrimitive, Zero, One};
+use num::{ParseFloatError, FloatErrorKind};
+
+use Channel;
+
+/// A signed fixed-point number in an `i32`, with `FRAC` bits after the
+/// point, as a channel from `0` to `1` like a float.
+///
+/// `FRAC` can be at most `30`. `Fixed16`, with sixteen, holds values from
+/// `-32768` to just under `32768` in steps of `1 / 65536`, which is enough
+/// for hues in degrees and for 16-bit channels. Values past the range
+/// overflow as integers do. Conversions to `u8` and `u16` clamp to `[0, 1]`
+/// and round to nearest, so that those channels round-trip through it.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[repr(transparent)]
+pub struct Fixed<const FRAC: u32>(i32);
+
+/// A fixed-point channel with sixteen fractional bits, Q15.16.
+pub type Fixed16 = Fixed<16>;
+
+impl<const FRAC: u32> Fixed<FRAC> {
+    const ONE_BITS: i32 = {
+        assert!(FRAC <= 30, "a Fixed can have at most 30 fractional bits");
+        1 << FRAC
+    };
+
+    pub const ZERO: Fixed<FRAC> = Fixed(0);
+    pub const ONE: Fixed<FRAC> = Fixed(Self::ONE_BITS);
+
+    /// The number with the bits `bits`, that is `bits / 2^FRAC`.
+    #[inline]
+    pub const fn from_bits(bits: i32) -> Fixed<FRAC> {
+        Fixed(bits)
+    }
+
+    #[inline]
+    pub const fn to_bits(self) -> i32 {
+        self.0
+    }
+
+    #[inline]
+    pub const fn from_int(n: i32) -> Fixed<FRAC> {
+        Fixed(n << FRAC)
+    }
+
+    /// The nearest number to `x`, saturating at the ends of the range. NaN
+    /// is `0`.
+    #[inline]
+    pub fn from_f64(x: f64) -> Fixed<FRAC> {
+        Fixed((x * Self::ONE_BITS as f64).round() as i32)
+    }
+
+    #[inline]
+    pub fn from_f32(x: f32) -> Fixed<FRAC> {
+        Fixed::from_f64(x as f64)
+    }
+
+    #[inline]
+    pub fn to_f64(self) -> f64 {
+        self.0 as f64 / Self::ONE_BITS as f64
+    }
+
+    #[inline]
+    pub fn to_f32(self) -> f32 {
+        self.0 as f32 / Self::ONE_BITS as f32
+    }
+
+    /// `self` clamped to `[0, 1]` and scaled to `max`, rounded to nearest.
+    #[inline]
+    fn scale(self, max: i64) -> i64 {
+        (self.0.clamp(0, Self::ONE_BITS) as i64 * max + (Self::ONE_BITS as i64 >> 1)) >> FRAC
+    }
+}
+
+impl<const FRAC: u32> Add for Fixed<FRAC> {
+    type Output = Fixed<FRAC>;
+
+    #[inline]
+    fn add(self, rhs: Fixed<FRAC>) -> Fixed<FRAC> {
+        Fixed(self.0 + rhs.0)
+    }
+}
+
+impl<const FRAC: u32> Sub for Fixed<FRAC> {
+    type Output = Fixed<FRAC>;
+
+    #[inline]
+    fn sub(self, rhs: Fixed<FRAC>) -> Fixed<FRAC> {
+        Fixed(self.0 - rhs.0)
+    }
+}
+
+impl<const FRAC: u32> Mul for Fixed<FRAC> {
+    type Output = Fixed<FRAC>;
+
+    /// Multiplies in an `i64`, rounding towards negative infinity.
+    #[inline]
+    fn mul(self, rhs: Fixed<FRAC>) -> Fixed<FRAC> {
+        Fixed(((self.0 as i64 * rhs.0 as i64) >> FRAC) as i32)
+    }
+}
+
+impl<const FRAC: u32> Div for Fixed<FRAC> {
+    type Output = Fixed<FRAC>;
+
+    /// Divides in an `i64`, rounding towards zero. Panics if `rhs` is zero,
+    /// as integer division does.
+    #[inline]
+    fn div(self, rhs: Fixed<FRAC>) -> Fixed<FRAC> {
+        Fixed((((self.0 as i64) << FRAC) / rhs.0 as i64) as i32)
+    }
+}
+
+impl<const FRAC: u32> Rem for Fixed<FRAC> {
+    type Output = Fixed<FRAC>;
+
+    #[inline]
+    fn rem(self, rhs: Fixed<FRAC>) -> Fixed<FRAC> {
+        Fixed(self.0 % rhs.0)
+    }
+}
+
+impl<const FRAC: u32> Neg for Fixed<FRAC> {
+    type Output = Fixed<FRAC>;
+
+    #[inline]
+    fn neg(self) -> Fixed<FRAC> {
+        Fixed(-self.0)
+    }
+}
+
+impl<const FRAC: u32> Zero for Fixed<FRAC> {
+    #[inline] fn zero() -> Fixed<FRAC> { Fixed::ZERO }
+    #[inline] fn is_zero(&self) -> bool { self.0 == 0 }
+}
+
+impl<const FRAC: u32> One for Fixed<FRAC> {
+    #[inline] fn one() -> Fixed<FRAC> { Fixed::ONE }
+}
+
+impl<const FRAC: u32> Bounded for Fixed<FRAC> {
+    #[inline] fn min_value() -> Fixed<FRAC> { Fixed(i32::MIN) }
+    #[inline] fn max_value() -> Fixed<FRAC> { Fixed(i32::MAX) }
+}
+
+impl<const FRAC: u32> Num for Fixed<FRAC> {
+    type FromStrRadixErr = ParseFloatError;
+
+    /// Parses a number with an optional sign and fraction, such as `-1.25`,
+    /// in `radix`, rounding the fraction towards zero.
+    fn from_str_radix(s: &str, radix: u32) -> Result<Fixed<FRAC>, ParseFloatError> {
+        let invalid = || ParseFloatError { kind: FloatErrorKind::Invalid };
+        let (negative, s) = match s.as_bytes().first() {
+            None => return Err(ParseFloatError { kind: FloatErrorKind::Empty }),
+            Some(&b'-') => (true, &s[1..]),
+            Some(&b'+') => (false, &s[1..]),
+            Some(_) => (false, s),
+        };
+        let (int, frac) = match s.find('.') {
+            Some(i) => (&s[..i], &s[i + 1..]),
+            None => (s, ""),
+        };
+        if int.is_empty() && frac.is_empty() {
+            return Err(invalid());
+        }
+        let mut bits: i64 = 0;
+        for c in int.chars() {
+            let d = c.to_digit(radix).ok_or_else(invalid)?;
+            bits = bits * radix as i64 + d as i64;
+            if bits > i32::MAX as i64 {
+                return Err(invalid());
+            }
+        }
+        bits <<= FRAC;
+        // the fraction from its last digit, a digit at a time
+        let mut fraction: i64 = 0;
+        for c in frac.chars().rev() {
+            let d = c.to_digit(radix).ok_or_else(invalid)?;
+            fraction = (fraction + ((d as i64) << FRAC)) / radix as i64;
+        }
+        bits += fraction;
+        let bits = if negative { -bits } else { bits };
+        if bits < i32::MIN as i64 || bits > i32::MAX as i64 {
+            return Err(invalid());
+        }
+        Ok(Fixed(bits as i32))
+    }
+}
+
+impl<const FRAC: u32> FromStr for Fixed<FRAC> {
+    type Err = ParseFloatError;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Fixed<FRAC>, ParseFloatError> {
+        Fixed::from_str_radix(s, 10)
+    }
+}
+
+impl<const FRAC: u32> fmt::Display for Fixed<FRAC> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&Fixed::to_f64(*self), f)
+    }
+}
+
+impl<const FRAC: u32> ToPrimitive for Fixed<FRAC> {
+    /// The whole part, rounding towards zero as floats do.
+    #[inline]
+    fn to_i64(&self) -> Option<i64> {
+        Some((self.0 / Self::ONE_BITS) as i64)
+    }
+
+    #[inline]
+    fn to_u64(&self) -> Option<u64> {
+        let n = self.0 / Self::ONE_BITS;
+        if n >= 0 { Some(n as u64) } else { None }
+    }
+
+    #[inline]
+    fn to_f32(&self) -> Option<f32> {
+        Some(Fixed::to_f32(*self))
+    }
+
+    #[inline]
+    fn to_f64(&self) -> Option<f64> {
+        Some(Fixed::to_f64(*self))
+    }
+}
+
+impl<const FRAC: u32> NumCast for Fixed<FRAC> {
+    /// The nearest number to `n`, or `None` if it is NaN or out of range.
+    #[inline]
+    fn from<N: ToPrimitive>(n: N) -> Option<Fixed<FRAC>> {
+        let x = n.to_f64()? * Self::ONE_BITS as f64;
+        if x.is_nan() || x < i32::MIN as f64 || x > i32::MAX as f64 {
+            None
+        } else {
+            Some(Fixed(x.round() as i32))
+        }
+    }
+}
+
+impl<const FRAC: u32> Channel for Fixed<FRAC> {
+    #[inline] fn from<T:Channel>(chan: T) -> Fixed<FRAC> { Fixed::from_f64(chan.to_channel_f64()) }
+    #[inline] fn to_channel_u8(self) -> u8 { self.scale(0xFF) as u8 }
+    #[inline] fn to_channel_u16(self) -> u16 { self.scale(0xFFFF) as u16 }
+    #[inline] fn to_channel_f32(self) -> f32 { self.to_f32() }
+    #[inline] fn to_channel_f64(self) -> f64 { self.to_f64() }
+
+    #[inline] fn invert_channel(self) -> Fixed<FRAC> { Fixed::ONE - self }
+
+    #[inline]
+    fn normalized_mul(self, rhs: Self) -> Self {
+        self * rhs
+    }
+
+    #[inline]
+    fn normalized_div(self, rhs: Self) -> Self {
+        self / rhs
+    }
+
+    #[inline] fn max() -> Fixed<FRAC> { Fixed::ONE }
+
+    fn hue_sextant(self) -> (usize, Fixed<FRAC>) {
+        let sixth = 60 * Self::ONE_BITS as i64;
+        let hue = (self.0 as i64).rem_euclid(6 * sixth);
+        ((hue / sixth) as usize, Fixed((hue % sixth / 60) as i32))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use angle::Deg;
+
+    use super::{Fixed, Fixed16};
+    use {Channel, Hsv, Rgb, ToRgb};
+
+    #[test]
+    fn test_arithmetic() {
+        let (half, quarter) = (Fixed16::from_f64(0.5), Fixed16::from_f64(0.25));
+        assert_eq!(half * half, quarter);
+        assert_eq!(quarter / half, half);
+        assert_eq!(half + quarter - Fixed16::ONE, -quarter);
+        assert_eq!(Fixed16::from_int(360).to_f64(), 360.0);
+        assert_eq!("-1.25".parse::<Fixed16>().ok(), Some(Fixed16::from_f64(-1.25)));
+        assert_eq!(".5".parse::<Fixed<8>>().ok(), Some(Fixed::from_bits(0x80)));
+        assert!("1.2x".parse::<Fixed16>().is_err() && "".parse::<Fixed16>().is_err());
+    }
+
+    #[test]
+    fn test_channel() {
+        assert_eq!(Fixed16::ONE.to_channel_u8(), 0xFF);
+        assert_eq!(Fixed16::ONE.to_channel_u16(), 0xFFFF);
+        assert_eq!(Fixed16::from_f64(0.5).to_channel_u8(), 0x80);
+        assert_eq!(Fixed16::from_f64(2.0).to_channel_u8(), 0xFF);
+        assert_eq!(Fixed16::from_f64(-0.5).to_channel_u16(), 0);
+        for x in 0..=0xFFu8 {
+            assert_eq!(x.to_channel::<Fixed16>().to_channel_u8(), x);
+        }
+        for &x in &[0u16, 0x3039, 0x8000, 0xFFFE, 0xFFFF] {
+            assert_eq!(x.to_channel::<Fixed16>().to_channel_u16(), x);
+        }
+        assert_eq!(Fixed16::from_f64(0.25).invert_channel(), Fixed16::from_f64(0.75));
+        assert_eq!(Fixed16::from_int(420).hue_sextant(), (1, Fixed16::ZERO));
+        assert_eq!(Fixed16::from_int(-30).hue_sextant(), (5, Fixed16::from_f64(0.5)));
+    }
+
+    #[test]
+    fn test_hsv_to_rgb() {
+        for &(h, s, v) in &[(0.0, 1.0, 1.0), (30.0, 0.8, 0.6), (200.0, 0.5, 0.9), (300.0, 0.25, 0.4),
+                            (359.0, 1.0, 0.2), (120.0, 0.0, 0.7), (75.5, 0.9, 1.0)] {
+            let float = Hsv::<f32>::new(Deg(h as f32), s as f32, v as f32).to_rgb::<f32>();
+            let fixed = Hsv::new(Deg(Fixed16::from_f64(h)), Fixed16::from_f64(s), Fixed16::from_f64(v)).to_rgb::<u16>();
+            let close = |a: f32, b: u16| (a * 65535.0 - b as f32).abs() <= 2.0;
+            assert!(close(float.r, fixed.r) && close(float.g, fixed.g) && close(float.b, fixed.b),
+                    "{:?} {:?}", float, fixed);
+        }
+        let red = Hsv::new(Deg(Fixed16::ZERO), Fixed16::ONE, Fixed16::ONE);
+        assert_eq!(red.to_rgb::<u8>(), Rgb::new(0xFF, 0, 0));
+    }
+}
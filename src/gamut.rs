@@ -0,0 +1,204 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Detecting and mapping colors that fall outside the sRGB gamut.
+//!
+//! Clamping each channel separately keeps the result in gamut but can shift
+//! the hue noticeably. The other strategies keep lightness and hue and give
+//! up chroma instead.
+
+use Channel;
+use {Rgb, Srgb, ToSrgb, Oklab, ToOklab, Oklch, ToOklch};
+use difference::{ciede2000, delta_e_ok};
+
+/// How much a component may stray outside `[0, 1]` and still count as in
+/// gamut, to absorb rounding in conversions.
+const TOLERANCE: f64 = 1e-6;
+
+/// A strategy for bringing out of gamut colors into sRGB.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GamutMapping {
+    /// Clamps each channel to `[0, 1]`.
+    Clip,
+    /// Reduces chroma in Oklch until clipping changes the color by less
+    /// than a just noticeable difference, as specified by CSS Color 4.
+    Css,
+    /// Picks whichever of clipping and the in-gamut chroma reduction in
+    /// Oklch is closer to the original by CIEDE2000.
+    MinDeltaE,
+}
+
+impl<T: Channel> Rgb<T> {
+    /// Whether every component lies within `[0, 1]`.
+    #[inline]
+    pub fn is_in_gamut(&self) -> bool {
+        in_unit_range([self.r.to_channel_f64(), self.g.to_channel_f64(), self.b.to_channel_f64()])
+    }
+}
+
+impl<T: Channel> Srgb<T> {
+    /// Whether every component lies within `[0, 1]`.
+    #[inline]
+    pub fn is_in_gamut(&self) -> bool {
+        in_unit_range([self.r.to_channel_f64(), self.g.to_channel_f64(), self.b.to_channel_f64()])
+    }
+}
+
+#[inline]
+fn in_unit_range(c: [f64; 3]) -> bool {
+    c.iter().all(|&v| (-TOLERANCE..=1.0 + TOLERANCE).contains(&v))
+}
+
+#[inline]
+fn clip(c: Srgb<f64>) -> Srgb<f64> {
+    let f = |v: f64| v.clamp(0.0, 1.0);
+    Srgb::new(f(c.r), f(c.g), f(c.b))
+}
+
+/// Whether the color can be shown in sRGB without clipping.
+#[inline]
+pub fn is_in_gamut<C: ToSrgb>(color: &C) -> bool {
+    color.to_srgb::<f64>().is_in_gamut()
+}
+
+/// Converts the color to sRGB, mapping it into gamut with `method`.
+pub fn map_to_srgb<C: ToOklab, U: Channel>(color: &C, method: GamutMapping) -> Srgb<U> {
+    let origin = color.to_oklab::<f64>();
+    let srgb = origin.to_srgb::<f64>();
+    let mapped = if srgb.is_in_gamut() {
+        srgb
+    } else {
+        match method {
+            GamutMapping::Clip => clip(srgb),
+            GamutMapping::Css => css_map(origin),
+            GamutMapping::MinDeltaE => {
+                let clipped = clip(srgb);
+                let reduced = reduce_chroma(origin);
+                if ciede2000(&clipped, &srgb) <= ciede2000(&reduced, &srgb) { clipped } else { reduced }
+            }
+        }
+    };
+    let mapped = clip(mapped);
+    Srgb::new(Channel::from(mapped.r), Channel::from(mapped.g), Channel::from(mapped.b))
+}
+
+/// Pins the lightness extremes to white and black, which no amount of
+/// chroma reduction brings into gamut.
+#[inline]
+fn lightness_extreme(lch: &Oklch<f64>) -> Option<Srgb<f64>> {
+    if lch.l >= 1.0 {
+        Some(Srgb::new(1.0, 1.0, 1.0))
+    } else if lch.l <= 0.0 {
+        Some(Srgb::new(0.0, 0.0, 0.0))
+    } else {
+        None
+    }
+}
+
+/// The CSS Color 4 gamut mapping algorithm.
+///
+/// https://www.w3.org/TR/css-color-4/#binsearch
+fn css_map(origin: Oklab<f64>) -> Srgb<f64> {
+    const JND: f64 = 0.02;
+    const EPSILON: f64 = 0.0001;
+
+    let mut current = origin.to_oklch::<f64>();
+    if let Some(c) = lightness_extreme(&current) {
+        return c;
+    }
+    let mut clipped = clip(origin.to_srgb());
+    if delta_e_ok(&clipped, &origin) < JND {
+        return clipped;
+    }
+    let (mut min, mut max) = (0.0, current.c);
+    let mut min_in_gamut = true;
+    while max - min > EPSILON {
+        current.c = (min + max) / 2.0;
+        let srgb = current.to_srgb::<f64>();
+        if min_in_gamut && srgb.is_in_gamut() {
+            min = current.c;
+            continue;
+        }
+        clipped = clip(srgb);
+        let e = delta_e_ok(&clipped, &current);
+        if e < JND {
+            if JND - e < EPSILON {
+                return clipped;
+            }
+            min_in_gamut = false;
+            min = current.c;
+        } else {
+            max = current.c;
+        }
+    }
+    clipped
+}
+
+/// The most saturated in-gamut color with the lightness and hue of
+/// `origin`, found by bisecting chroma in Oklch.
+fn reduce_chroma(origin: Oklab<f64>) -> Srgb<f64> {
+    let mut current = origin.to_oklch::<f64>();
+    if let Some(c) = lightness_extreme(&current) {
+        return c;
+    }
+    let (mut min, mut max) = (0.0, current.c);
+    while max - min > 1e-6 {
+        current.c = (min + max) / 2.0;
+        if current.to_srgb::<f64>().is_in_gamut() { min = current.c } else { max = current.c }
+    }
+    current.c = min;
+    current.to_srgb()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_in_gamut, map_to_srgb, GamutMapping};
+    use {Rgb, Srgb, ToOklch, Oklch, Xyz};
+    use rgb_space::RgbSpace;
+    use angle::*;
+
+    #[test]
+    fn test_is_in_gamut() {
+        assert!(Srgb::<f64>::new(0.0, 0.5, 1.0).is_in_gamut());
+        assert!(!Rgb::<f64>::new(1.1, 0.5, 0.0).is_in_gamut());
+        assert!(is_in_gamut(&Oklch::<f64>::new(0.6, 0.05, Deg(30.0))));
+        assert!(!is_in_gamut(&Oklch::<f64>::new(0.6, 0.4, Deg(30.0))));
+    }
+
+    #[test]
+    fn test_map_to_srgb() {
+        let p3_red: Xyz<f64> = RgbSpace::DISPLAY_P3.to_xyz(&Rgb::new(1.0, 0.0, 0.0));
+        let hue = p3_red.to_oklch::<f64>().h.value();
+        for &method in &[GamutMapping::Clip, GamutMapping::Css, GamutMapping::MinDeltaE] {
+            let c: Srgb<f64> = map_to_srgb(&p3_red, method);
+            assert!(c.is_in_gamut(), "{:?}: {:?}", method, c);
+        }
+        let css: Srgb<f64> = map_to_srgb(&p3_red, GamutMapping::Css);
+        assert!((css.to_oklch::<f64>().h.value() - hue).abs() < 2.0, "{:?}", css);
+
+        // a saturated green far outside sRGB: clipping shifts the hue,
+        // chroma reduction does not
+        let green = Oklch::<f64>::new(0.7, 0.35, Deg(150.0));
+        let clipped: Srgb<f64> = map_to_srgb(&green, GamutMapping::Clip);
+        let mapped: Srgb<f64> = map_to_srgb(&green, GamutMapping::Css);
+        let hue_error = |c: Srgb<f64>| (c.to_oklch::<f64>().h.value() - 150.0).abs();
+        assert!(hue_error(mapped) < hue_error(clipped), "{:?} {:?}", mapped, clipped);
+
+        let inside = Srgb::<f64>::new(0.2, 0.4, 0.6);
+        let c: Srgb<f64> = map_to_srgb(&inside, GamutMapping::Css);
+        assert!((c.r - 0.2).abs() < 1e-6 && (c.g - 0.4).abs() < 1e-6 && (c.b - 0.6).abs() < 1e-6);
+        assert_eq!(map_to_srgb::<_, f64>(&Oklch::<f64>::new(1.2, 0.1, Deg(0.0)), GamutMapping::Css), Srgb::new(1.0, 1.0, 1.0));
+    }
+}
@@ -0,0 +1,93 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Interoperability with `glam` vectors.
+//!
+//! The components of the vectors are taken as linear RGB, in the order of
+//! the fields, with alpha last in the four-component vectors. Gamma-encoded
+//! colors have no conversions: decode an `Srgb` with `to_rgb` first, so
+//! shading code always works in linear light.
+
+use glam::{Vec3, Vec3A, Vec4, DVec3, DVec4};
+
+use {Rgb, Rgba};
+
+macro_rules! vector_impls {
+    ($t:ty, $vec3:ty, $vec4:ty) => {
+        impl From<$vec3> for Rgb<$t> {
+            #[inline]
+            fn from(v: $vec3) -> Rgb<$t> {
+                Rgb::new(v.x, v.y, v.z)
+            }
+        }
+
+        impl From<Rgb<$t>> for $vec3 {
+            #[inline]
+            fn from(c: Rgb<$t>) -> $vec3 {
+                <$vec3>::new(c.r, c.g, c.b)
+            }
+        }
+
+        impl From<$vec4> for Rgba<$t> {
+            #[inline]
+            fn from(v: $vec4) -> Rgba<$t> {
+                Rgba::new(v.x, v.y, v.z, v.w)
+            }
+        }
+
+        impl From<Rgba<$t>> for $vec4 {
+            #[inline]
+            fn from(c: Rgba<$t>) -> $vec4 {
+                <$vec4>::new(c.c.r, c.c.g, c.c.b, c.a)
+            }
+        }
+    }
+}
+
+vector_impls!(f32, Vec3, Vec4);
+vector_impls!(f64, DVec3, DVec4);
+
+impl From<Vec3A> for Rgb<f32> {
+    #[inline]
+    fn from(v: Vec3A) -> Rgb<f32> {
+        Rgb::new(v.x, v.y, v.z)
+    }
+}
+
+impl From<Rgb<f32>> for Vec3A {
+    #[inline]
+    fn from(c: Rgb<f32>) -> Vec3A {
+        Vec3A::new(c.r, c.g, c.b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{Vec3, Vec3A, Vec4, DVec4};
+
+    use {Rgb, Rgba, Srgb, ToRgb};
+
+    #[test]
+    fn test_vectors() {
+        assert_eq!(Vec3::from(Rgb::new(0.25f32, 0.5, 1.0)), Vec3::new(0.25, 0.5, 1.0));
+        assert_eq!(Rgb::from(Vec3::new(0.25, 0.5, 1.0) * 2.0), Rgb::new(0.5f32, 1.0, 2.0));
+        assert_eq!(Rgb::from(Vec3A::from(Rgb::new(0.1f32, 0.2, 0.3))), Rgb::new(0.1, 0.2, 0.3));
+        assert_eq!(Vec4::from(Rgba::new(0.1f32, 0.2, 0.3, 0.4)), Vec4::new(0.1, 0.2, 0.3, 0.4));
+        assert_eq!(Rgba::from(DVec4::new(0.1, 0.2, 0.3, 0.4)), Rgba::new(0.1f64, 0.2, 0.3, 0.4));
+        // sRGB is decoded on the way in
+        let v = Vec3::from(Srgb::<u8>::new(0xFF, 0x80, 0x00).to_rgb::<f32>());
+        assert!(v.x == 1.0 && (v.y - 0.2158).abs() < 1e-3 && v.z == 0.0);
+    }
+}
@@ -0,0 +1,148 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Colors laid out for GPU uniform and storage buffers.
+//!
+//! In the std140 and std430 layouts of GLSL, and in WGSL, a `vec4<f32>` and
+//! a `vec3<f32>` are both aligned to 16 bytes, and an array of either has a
+//! stride of 16. `Rgb<f32>` is 12 bytes aligned to 4, so it lands in the
+//! wrong place after any member that does not end on a multiple of 16, and
+//! an array of it has the wrong stride. The types here are aligned to 16 and
+//! are 16 bytes long, which is right for both layouts, as members and as
+//! array elements. The two layouts only differ for scalars, 2-vectors and
+//! structs, so the `Std430` names are the same types.
+//!
+//! `RgbF32Std140` ends in four bytes of padding. A shader member that
+//! follows a `vec3` can start in those bytes, as `float` does in `vec3 c;
+//! float x;`, but the Rust member after an `RgbF32Std140` starts after them.
+//! Declare the color as a `vec4`, or put something in the padding, in that
+//! case.
+//!
+//! The conversions go through `ToRgb` and `ToRgba`, so `Srgb` colors are
+//! decoded to linear light, as shaders expect. With the `bytemuck` feature
+//! the types are `Pod` and can be written into buffers as bytes.
+
+use Channel;
+use {Rgb, ToRgb, Rgba, ToRgba};
+
+/// An RGB color as a `vec3<f32>` in std140 or std430: 16 bytes, aligned to
+/// 16, with the last four padding.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[repr(C, align(16))]
+pub struct RgbF32Std140 {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    /// Always zero, so the padding is initialized.
+    _pad: f32,
+}
+
+/// An RGBA color as a `vec4<f32>` in std140 or std430: 16 bytes, aligned to
+/// 16.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[repr(C, align(16))]
+pub struct RgbaF32Std140 {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub a: f32,
+}
+
+/// std430 lays out a `vec3<f32>` as std140 does.
+pub type RgbF32Std430 = RgbF32Std140;
+
+/// std430 lays out a `vec4<f32>` as std140 does.
+pub type RgbaF32Std430 = RgbaF32Std140;
+
+impl RgbF32Std140 {
+    #[inline]
+    pub const fn new(r: f32, g: f32, b: f32) -> RgbF32Std140 {
+        RgbF32Std140 { r, g, b, _pad: 0.0 }
+    }
+
+    #[inline]
+    pub fn from_rgb<C: ToRgb>(color: &C) -> RgbF32Std140 {
+        let c = color.to_rgb::<f32>();
+        RgbF32Std140::new(c.r, c.g, c.b)
+    }
+}
+
+impl ToRgb for RgbF32Std140 {
+    #[inline]
+    fn to_rgb<U: Channel>(&self) -> Rgb<U> {
+        Rgb::new(Channel::from(self.r), Channel::from(self.g), Channel::from(self.b))
+    }
+}
+
+impl RgbaF32Std140 {
+    #[inline]
+    pub const fn new(r: f32, g: f32, b: f32, a: f32) -> RgbaF32Std140 {
+        RgbaF32Std140 { r, g, b, a }
+    }
+
+    #[inline]
+    pub fn from_rgba<C: ToRgba>(color: &C) -> RgbaF32Std140 {
+        let c = color.to_rgba::<f32>();
+        RgbaF32Std140::new(c.c.r, c.c.g, c.c.b, c.a)
+    }
+}
+
+impl ToRgba for RgbaF32Std140 {
+    #[inline]
+    fn to_rgba<U: Channel>(&self) -> Rgba<U> {
+        Rgba::new(Channel::from(self.r), Channel::from(self.g), Channel::from(self.b), Channel::from(self.a))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::mem::{align_of, size_of};
+
+    use super::{RgbF32Std140, RgbaF32Std140};
+    use {Rgb, Rgba, Srgb, Srgba, ToRgb, ToRgba};
+
+    /// A uniform block of `float x; vec3 c; vec4 d[2];`.
+    #[repr(C)]
+    struct Block {
+        x: f32,
+        c: RgbF32Std140,
+        d: [RgbaF32Std140; 2],
+    }
+
+    #[test]
+    fn test_layout() {
+        assert_eq!((size_of::<RgbF32Std140>(), align_of::<RgbF32Std140>()), (16, 16));
+        assert_eq!((size_of::<RgbaF32Std140>(), align_of::<RgbaF32Std140>()), (16, 16));
+        assert_eq!(size_of::<[RgbF32Std140; 3]>(), 48);
+        let block = Block { x: 0.0, c: RgbF32Std140::default(), d: [RgbaF32Std140::default(); 2] };
+        let base = &block as *const Block as usize;
+        assert_eq!(&block.x as *const f32 as usize - base, 0);
+        assert_eq!(&block.c as *const RgbF32Std140 as usize - base, 16);
+        assert_eq!(&block.d[1] as *const RgbaF32Std140 as usize - base, 48);
+    }
+
+    #[test]
+    fn test_conversions() {
+        let c = RgbF32Std140::from_rgb(&Srgb::<u8>::new(0xFF, 0x00, 0x00));
+        assert_eq!(c, RgbF32Std140::new(1.0, 0.0, 0.0));
+        assert_eq!(c.to_rgb::<u8>(), Rgb::new(0xFF, 0, 0));
+        let c = RgbaF32Std140::from_rgba(&Rgba::<f32>::new(0.25, 0.5, 0.75, 0.5));
+        assert_eq!(c, RgbaF32Std140::new(0.25, 0.5, 0.75, 0.5));
+        assert_eq!(c.to_rgba::<f32>(), Rgba::new(0.25, 0.5, 0.75, 0.5));
+        // sRGB is decoded
+        let c = RgbaF32Std140::from_rgba(&Srgba::from_color(Srgb::<f64>::new(0.5, 0.5, 0.5), 1.0));
+        assert!((c.r - 0.214).abs() < 1e-3 && c.a == 1.0);
+    }
+}
@@ -0,0 +1,420 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Multi-stop color gradients.
+//!
+//! The stops are converted to gamma-encoded sRGB with `to_srgb()`, so
+//! linear `Rgb`, `Srgb` and device independent colors such as `Oklab` all
+//! land where they should, and the gradient's samples are `Srgb`.
+//!
+//! Between the stops a gradient follows a `Curve`: straight lines by default,
+//! or a spline through the components of the interpolation space, which
+//! avoids the visible kinks at each stop of a piecewise linear ramp.
+
+use core::marker::PhantomData;
+use alloc::vec::Vec;
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use math::*;
+
+use {Color, FloatColor};
+use Channel;
+use angle::Deg;
+
+use {Rgb, ToRgb, Srgb, ToSrgb, Hsv, ToHsv, Oklab, ToOklab, Cam16Ucs, ToCam16Ucs};
+
+/// The color space in which a gradient blends between neighbouring stops.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Interpolation {
+    /// Component-wise in gamma-encoded sRGB, as most software does.
+    Rgb,
+    /// Component-wise in linear light, which is physically correct for
+    /// blending light but looks uneven to the eye.
+    LinearRgb,
+    /// In HSV of the encoded components, taking the shorter way around the
+    /// hue circle.
+    Hsv,
+    /// In Oklab, which keeps lightness and chroma perceptually even.
+    Oklab,
+    /// In CAM16-UCS under the default viewing conditions. More uniform
+    /// than Oklab for lightness, and much slower.
+    Cam16Ucs,
+}
+
+/// HSV, like most software, works on the encoded components.
+#[inline]
+fn encoded_hsv(c: Srgb<f64>) -> Hsv<f64> {
+    Rgb::new(c.r, c.g, c.b).to_hsv()
+}
+
+impl Interpolation {
+    /// Interpolates between two colors.
+    pub(crate) fn mix(self, a: Srgb<f64>, b: Srgb<f64>, t: f64) -> Srgb<f64> {
+        match self {
+            Interpolation::Rgb => {
+                let lerp = |x: f64, y: f64| x + (y - x) * t;
+                Srgb::new(lerp(a.r, b.r), lerp(a.g, b.g), lerp(a.b, b.b))
+            }
+            // Srgb mixes in linear light
+            Interpolation::LinearRgb => a.mix(b, t),
+            Interpolation::Hsv => {
+                let c = encoded_hsv(a).mix(encoded_hsv(b), t).to_rgb::<f64>();
+                Srgb::new(c.r, c.g, c.b)
+            }
+            Interpolation::Oklab => a.to_oklab::<f64>().mix(b.to_oklab(), t).to_srgb(),
+            Interpolation::Cam16Ucs => {
+                let (a, b) = (a.to_cam16_ucs::<f64>(), b.to_cam16_ucs::<f64>());
+                let lerp = |x: f64, y: f64| x + (y - x) * t;
+                Srgb::from_linear(&Cam16Ucs::new(lerp(a.j, b.j), lerp(a.a, b.a), lerp(a.b, b.b)))
+            }
+        }
+    }
+}
+
+impl Interpolation {
+    /// The components of a color in this space. Hues come first and are in
+    /// degrees.
+    fn coordinates(self, c: Srgb<f64>) -> [f64; 3] {
+        match self {
+            Interpolation::Rgb => [c.r, c.g, c.b],
+            Interpolation::LinearRgb => { let l = c.to_linear::<f64>(); [l.r, l.g, l.b] }
+            Interpolation::Hsv => { let h = encoded_hsv(c); [h.h.0, h.s, h.v] }
+            Interpolation::Oklab => { let l = c.to_oklab::<f64>(); [l.l, l.a, l.b] }
+            Interpolation::Cam16Ucs => { let u = c.to_cam16_ucs::<f64>(); [u.j, u.a, u.b] }
+        }
+    }
+
+    /// The color with the given components, clamped to `[0, 1]`.
+    fn color_at(self, c: [f64; 3]) -> Srgb<f64> {
+        let srgb = match self {
+            Interpolation::Rgb => Srgb::new(c[0], c[1], c[2]),
+            Interpolation::LinearRgb => Srgb::from_linear(&Rgb::<_>::new(c[0], c[1], c[2])),
+            Interpolation::Hsv => {
+                let rgb = Hsv::new(Deg(c[0].rem_euclid(360.0)), c[1].clamp(0.0, 1.0), c[2].clamp(0.0, 1.0))
+                    .to_rgb::<f64>();
+                Srgb::new(rgb.r, rgb.g, rgb.b)
+            }
+            Interpolation::Oklab => Oklab::new(c[0], c[1], c[2]).to_srgb(),
+            Interpolation::Cam16Ucs => Srgb::from_linear(&Cam16Ucs::new(c[0], c[1], c[2])),
+        };
+        let rgb = Rgb::new(srgb.r, srgb.g, srgb.b).saturate();
+        Srgb::new(rgb.r, rgb.g, rgb.b)
+    }
+}
+
+/// The path a gradient takes through its stops.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Curve {
+    /// Straight segments between neighbouring stops.
+    #[default]
+    Linear,
+    /// A Catmull-Rom spline, which passes through every stop with a smooth
+    /// tangent. It can overshoot a little between stops that change
+    /// direction; colors that leave the gamut are clamped.
+    CatmullRom,
+    /// A single Bézier curve using the stops as control points. It starts
+    /// and ends at the first and last stops but only passes near the
+    /// others, and ignores the positions of the inner stops.
+    Bezier,
+    /// A clamped uniform cubic B-spline using the stops as control points.
+    /// Smoother than Catmull-Rom and never overshoots, but like `Bezier` it
+    /// only touches the end stops and ignores inner positions.
+    BSpline,
+}
+
+#[inline]
+fn lerp(a: [f64; 3], b: [f64; 3], t: f64) -> [f64; 3] {
+    [a[0] + (b[0] - a[0]) * t, a[1] + (b[1] - a[1]) * t, a[2] + (b[2] - a[2]) * t]
+}
+
+/// Uniform Catmull-Rom between `p1` and `p2`.
+fn catmull_rom(p0: [f64; 3], p1: [f64; 3], p2: [f64; 3], p3: [f64; 3], t: f64) -> [f64; 3] {
+    let (t2, t3) = (t * t, t * t * t);
+    let mut out = [0.0; 3];
+    for (i, o) in out.iter_mut().enumerate() {
+        *o = 0.5 * (2.0 * p1[i]
+                    + (p2[i] - p0[i]) * t
+                    + (2.0 * p0[i] - 5.0 * p1[i] + 4.0 * p2[i] - p3[i]) * t2
+                    + (3.0 * p1[i] - p0[i] - 3.0 * p2[i] + p3[i]) * t3);
+    }
+    out
+}
+
+/// De Casteljau's algorithm.
+fn bezier(points: &[[f64; 3]], t: f64) -> [f64; 3] {
+    let mut p = points.to_vec();
+    for n in (1..p.len()).rev() {
+        for i in 0..n {
+            p[i] = lerp(p[i], p[i + 1], t);
+        }
+    }
+    p[0]
+}
+
+/// De Boor's algorithm for a clamped uniform B-spline of degree up to 3.
+fn bspline(points: &[[f64; 3]], t: f64) -> [f64; 3] {
+    let n = points.len();
+    let p = (n - 1).min(3);
+    let spans = n - p;
+    let knot = |i: usize| -> f64 {
+        if i <= p { 0.0 } else if i >= n { 1.0 } else { (i - p) as f64 / spans as f64 }
+    };
+    let k = ((t * spans as f64) as usize).min(spans - 1) + p;
+    let mut d: Vec<[f64; 3]> = points[k - p..=k].to_vec();
+    for r in 1..=p {
+        for j in (r..=p).rev() {
+            let i = j + k - p;
+            let span = knot(i + p + 1 - r) - knot(i);
+            let alpha = if span > 0.0 { (t - knot(i)) / span } else { 0.0 };
+            d[j] = lerp(d[j - 1], d[j], alpha);
+        }
+    }
+    d[p]
+}
+
+/// A color ramp through an ordered list of `(position, color)` stops.
+#[derive(Clone, Debug)]
+pub struct Gradient<C> {
+    stops: Vec<(f32, C)>,
+    interpolation: Interpolation,
+    curve: Curve,
+}
+
+impl<C: ToSrgb> Gradient<C> {
+    /// Creates a gradient interpolating in sRGB. The stops are sorted by
+    /// position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `stops` is empty.
+    pub fn new(mut stops: Vec<(f32, C)>) -> Gradient<C> {
+        assert!(!stops.is_empty(), "a gradient needs at least one stop");
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Gradient { stops, interpolation: Interpolation::Rgb, curve: Curve::Linear }
+    }
+
+    /// Sets the color space the gradient interpolates in.
+    pub fn interpolation(mut self, interpolation: Interpolation) -> Gradient<C> {
+        self.interpolation = interpolation;
+        self
+    }
+
+    /// Sets the path the gradient takes through its stops, in the components
+    /// of its interpolation space.
+    pub fn curve(mut self, curve: Curve) -> Gradient<C> {
+        self.curve = curve;
+        self
+    }
+
+    #[inline]
+    pub fn stops(&self) -> &[(f32, C)] {
+        &self.stops
+    }
+
+    /// The position of the first and the last stop.
+    #[inline]
+    pub fn domain(&self) -> (f32, f32) {
+        (self.stops[0].0, self.stops[self.stops.len() - 1].0)
+    }
+
+    /// Samples the gradient at `t`, clamped to the gradient's domain.
+    pub fn get<U: Channel>(&self, t: f32) -> Srgb<U> {
+        let (lo, hi) = self.domain();
+        let t = if t < lo { lo } else if t > hi { hi } else { t };
+        let i = self.stops.iter().rposition(|s| s.0 <= t).unwrap_or(0);
+        let (p0, ref c0) = self.stops[i];
+        if i + 1 == self.stops.len() {
+            return c0.to_srgb();
+        }
+        let (p1, ref c1) = self.stops[i + 1];
+        let local = if p1 > p0 { (t - p0) / (p1 - p0) } else { 0.0 };
+        if self.curve == Curve::Linear {
+            return self.interpolation.mix(c0.to_srgb(), c1.to_srgb(), local as f64).to_srgb();
+        }
+
+        let points = self.coordinates();
+        let global = if hi > lo { ((t - lo) / (hi - lo)) as f64 } else { 0.0 };
+        let c = match self.curve {
+            Curve::Linear => unreachable!(),
+            Curve::CatmullRom => {
+                // the end stops are extended by reflection, so the curve
+                // leaves them heading for their neighbours
+                let n = points.len();
+                let p0 = if i > 0 { points[i - 1] } else { lerp(points[1], points[0], 2.0) };
+                let p3 = if i + 2 < n { points[i + 2] } else { lerp(points[n - 2], points[n - 1], 2.0) };
+                catmull_rom(p0, points[i], points[i + 1], p3, local as f64)
+            }
+            Curve::Bezier => bezier(&points, global),
+            Curve::BSpline => bspline(&points, global),
+        };
+        self.interpolation.color_at(c).to_srgb()
+    }
+
+    /// The stops in the components of the interpolation space, with hues
+    /// unwrapped so that each is within half a turn of the one before.
+    fn coordinates(&self) -> Vec<[f64; 3]> {
+        let mut points: Vec<[f64; 3]> = self.stops.iter()
+            .map(|s| self.interpolation.coordinates(s.1.to_srgb()))
+            .collect();
+        if self.interpolation == Interpolation::Hsv {
+            for i in 1..points.len() {
+                let prev = points[i - 1][0];
+                points[i][0] = prev + (points[i][0] - prev + 180.0).rem_euclid(360.0) - 180.0;
+            }
+        }
+        points
+    }
+
+    /// Returns an iterator over `n` evenly spaced samples spanning the whole
+    /// gradient, endpoints included.
+    pub fn take<U: Channel>(&self, n: usize) -> Samples<'_, C, U> {
+        Samples { gradient: self, index: 0, count: n, _channel: PhantomData }
+    }
+}
+
+/// An iterator over evenly spaced samples of a `Gradient`.
+pub struct Samples<'a, C: 'a, U> {
+    gradient: &'a Gradient<C>,
+    index: usize,
+    count: usize,
+    _channel: PhantomData<U>,
+}
+
+impl<'a, C: ToSrgb, U: Channel> Iterator for Samples<'a, C, U> {
+    type Item = Srgb<U>;
+
+    fn next(&mut self) -> Option<Srgb<U>> {
+        if self.index >= self.count {
+            return None;
+        }
+        let (lo, hi) = self.gradient.domain();
+        let t = if self.count == 1 { 0.0 } else { self.index as f32 / (self.count - 1) as f32 };
+        self.index += 1;
+        Some(self.gradient.get(lo + (hi - lo) * t))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.count - self.index;
+        (n, Some(n))
+    }
+}
+
+impl<'a, C: ToSrgb, U: Channel> ExactSizeIterator for Samples<'a, C, U> {}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use super::{Gradient, Interpolation, Curve};
+    use {Rgb, Srgb, ToSrgb, Oklab, ToOklab};
+
+    /// An encoded color from `0xRRGGBB`.
+    fn hex(c: u32) -> Srgb<u8> {
+        Srgb::new((c >> 16) as u8, (c >> 8) as u8, c as u8)
+    }
+
+    fn assert_rgb_eq(a: Srgb<f32>, b: Srgb<f32>) {
+        assert!((a.r - b.r).abs() < 1e-3 && (a.g - b.g).abs() < 1e-3 && (a.b - b.b).abs() < 1e-3,
+                "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn test_gradient_get() {
+        let g = Gradient::new(vec![(1.0, hex(0x0000FF)), (0.0, hex(0xFF0000)), (0.5, hex(0x00FF00))]);
+        assert_eq!(g.domain(), (0.0, 1.0));
+        assert_eq!(g.get::<u8>(-1.0), Srgb::new(0xFF, 0x00, 0x00));
+        assert_eq!(g.get::<u8>(0.5), Srgb::new(0x00, 0xFF, 0x00));
+        assert_eq!(g.get::<u8>(2.0), Srgb::new(0x00, 0x00, 0xFF));
+        assert_rgb_eq(g.get(0.25), Srgb::new(0.5, 0.5, 0.0));
+        assert_rgb_eq(g.get(0.75), Srgb::new(0.0, 0.5, 0.5));
+    }
+
+    #[test]
+    fn test_gradient_spaces() {
+        let bw = Gradient::new(vec![(0.0, hex(0x000000)), (1.0, hex(0xFFFFFF))]);
+        assert_rgb_eq(bw.get(0.5), Srgb::new(0.5, 0.5, 0.5));
+        assert_rgb_eq(bw.clone().interpolation(Interpolation::LinearRgb).get(0.5), Srgb::new(0.7354, 0.7354, 0.7354));
+        assert_rgb_eq(bw.clone().interpolation(Interpolation::Oklab).get(0.5), Srgb::new(0.3885, 0.3885, 0.3885));
+
+        let rb = Gradient::new(vec![(0.0, hex(0xFF0000)), (1.0, hex(0x0000FF))]).interpolation(Interpolation::Hsv);
+        assert_rgb_eq(rb.get(0.5), Srgb::new(1.0, 0.0, 1.0));
+        let gray = Gradient::new(vec![(0.0, hex(0x808080)), (1.0, hex(0x00FF00))]).interpolation(Interpolation::Hsv);
+        assert_rgb_eq(gray.get(0.5), Srgb::new(0.3755, 0.7510, 0.3755));
+    }
+
+    #[test]
+    fn test_gradient_encoding() {
+        // encoded stops are interpolated as they are
+        let g = Gradient::new(vec![(0.0, Srgb::new(0u8, 0, 0)), (0.5, Srgb::new(128, 128, 128)), (1.0, Srgb::new(255, 255, 255))]);
+        assert_eq!(g.get::<u8>(0.5), Srgb::new(128, 128, 128));
+        assert_eq!(g.get::<u8>(0.25), Srgb::new(64, 64, 64));
+        // linear stops are encoded first
+        let g = Gradient::new(vec![(0.0, Rgb::<f64>::new(0.0, 0.0, 0.0)), (1.0, Rgb::new(0.5, 0.5, 0.5))]);
+        assert_rgb_eq(g.get(1.0), Rgb::<f32>::new(0.5, 0.5, 0.5).to_srgb());
+        // and so are device independent ones
+        let (a, b) = (hex(0xFF0000).to_oklab::<f64>(), hex(0x0000FF).to_oklab::<f64>());
+        let g = Gradient::new(vec![(0.0, a), (1.0, b)]);
+        assert_rgb_eq(g.get(0.0), Srgb::new(1.0, 0.0, 0.0));
+        assert_rgb_eq(g.get(1.0), Srgb::new(0.0, 0.0, 1.0));
+        let srgb = Gradient::new(vec![(0.0, hex(0xFF0000)), (1.0, hex(0x0000FF))]);
+        let mid: Srgb<f32> = g.interpolation(Interpolation::Oklab).get(0.5);
+        assert_rgb_eq(mid, srgb.interpolation(Interpolation::Oklab).get(0.5));
+        let mid: Oklab<f64> = mid.to_oklab();
+        assert!((mid.l - (a.l + b.l) / 2.0).abs() < 1e-3, "{:?}", mid);
+    }
+
+    #[test]
+    fn test_gradient_take() {
+        let g = Gradient::new(vec![(0.0, hex(0x000000)), (1.0, hex(0xFFFFFF))]);
+        let samples: Vec<Srgb<u8>> = g.take(3).collect();
+        assert_eq!(samples, vec![Srgb::new(0x00, 0x00, 0x00), Srgb::new(0x7F, 0x7F, 0x7F), Srgb::new(0xFF, 0xFF, 0xFF)]);
+        assert_eq!(g.take::<u8>(5).len(), 5);
+        assert_eq!(g.take::<u8>(1).collect::<Vec<_>>(), vec![Srgb::new(0x00, 0x00, 0x00)]);
+        assert_eq!(g.take::<u8>(0).count(), 0);
+    }
+
+    #[test]
+    fn test_gradient_curves() {
+        let stops = vec![(0.0, hex(0x000000)), (0.25, hex(0xFF0000)), (0.5, hex(0xFFFF00)), (1.0, hex(0xFFFFFF))];
+        let linear = Gradient::new(stops.clone());
+        for &curve in &[Curve::CatmullRom, Curve::Bezier, Curve::BSpline] {
+            let g = Gradient::new(stops.clone()).curve(curve);
+            assert_eq!(g.get::<u8>(0.0), Srgb::new(0x00, 0x00, 0x00), "{:?}", curve);
+            assert_eq!(g.get::<u8>(1.0), Srgb::new(0xFF, 0xFF, 0xFF), "{:?}", curve);
+            for i in 0..=20 {
+                let c: Srgb<f32> = g.get(i as f32 / 20.0);
+                assert!([c.r, c.g, c.b].iter().all(|&v| (0.0..=1.0).contains(&v)), "{:?} {:?}", curve, c);
+            }
+        }
+        // Catmull-Rom passes through every stop
+        let cr = Gradient::new(stops.clone()).curve(Curve::CatmullRom);
+        assert_rgb_eq(cr.get(0.25), Srgb::new(1.0, 0.0, 0.0));
+        assert_rgb_eq(cr.get(0.5), Srgb::new(1.0, 1.0, 0.0));
+        assert_eq!(cr.get::<u8>(0.5), linear.get::<u8>(0.5));
+        // with two stops every curve is a straight line
+        let two = vec![(0.0, hex(0x000000)), (1.0, hex(0xFFFFFF))];
+        for &curve in &[Curve::CatmullRom, Curve::Bezier, Curve::BSpline] {
+            assert_rgb_eq(Gradient::new(two.clone()).curve(curve).get(0.25), Srgb::new(0.25, 0.25, 0.25));
+        }
+        // a quadratic Bézier through the middle control point's pull
+        let b = Gradient::new(vec![(0.0, hex(0x000000)), (0.5, hex(0xFF0000)), (1.0, hex(0x000000))]).curve(Curve::Bezier);
+        assert_rgb_eq(b.get(0.5), Srgb::new(0.5, 0.0, 0.0));
+        // hues take the short way round
+        let hsv = Gradient::new(vec![(0.0, hex(0xFF0000)), (0.5, hex(0xFF00FF)), (1.0, hex(0x0000FF))])
+            .interpolation(Interpolation::Hsv)
+            .curve(Curve::CatmullRom);
+        assert_rgb_eq(hsv.get(0.5), Srgb::new(1.0, 0.0, 1.0));
+        let _: Srgb<u8> = Gradient::new(stops).interpolation(Interpolation::Oklab).curve(Curve::BSpline).get(0.3);
+    }
+}
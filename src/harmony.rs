@@ -0,0 +1,262 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Color schemes built by rotating the hue of a base color.
+//!
+//! The colors passed in are encoded to sRGB with `to_srgb()`, and the
+//! results are `Srgb`. Hues are rotated in HSV and RYB as CSS does, on the
+//! encoded values. Every scheme starts with the base color itself.
+//!
+//! `distinct_color` and `Srgb::from_hash` pick colors for categorical data,
+//! such as chart series or log sources, in HSLuv so that they share a
+//! lightness and read equally well on the same background.
+
+use core::hash::{Hash, Hasher};
+use alloc::vec::Vec;
+
+use angle::Deg;
+
+use {Channel, Hue};
+use {Rgb, ToRgb, Srgb, ToSrgb, ToHsv, Hsluv, ToHsluv, ToOklch, ToRyb};
+use gamut::{map_to_srgb, GamutMapping};
+
+/// The color space in which hues are rotated.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HarmonySpace {
+    /// Cheap, but hues are unevenly spaced to the eye and lightness varies
+    /// wildly between them.
+    Hsv,
+    /// Keeps lightness and relative saturation while staying in gamut.
+    Hsluv,
+    /// Keeps lightness and chroma perceptually even; colors that leave the
+    /// sRGB gamut are mapped back by reducing chroma.
+    Oklch,
+    /// The painters' wheel of red, yellow and blue, on which the complement
+    /// of red is green rather than cyan. Like `Hsv`, it ignores lightness.
+    Ryb,
+}
+
+impl HarmonySpace {
+    /// Rotates the hue of `color` by `degrees`.
+    pub fn rotate<C: ToSrgb, U: Channel>(self, color: &C, degrees: f64) -> Srgb<U> {
+        let srgb = color.to_srgb::<f64>();
+        let rgb = Rgb::<f64>::new(srgb.r, srgb.g, srgb.b);
+        let out: Srgb<f64> = match self {
+            HarmonySpace::Hsv => {
+                let mut hsv = rgb.to_hsv::<f64>();
+                hsv.h = (Hue::from(hsv.h) + Deg(degrees)).to_deg();
+                let c = hsv.to_rgb::<f64>();
+                Srgb::new(c.r, c.g, c.b)
+            }
+            HarmonySpace::Hsluv => {
+                let mut hsluv = srgb.to_hsluv::<f64>();
+                hsluv.h = (Hue::from(hsluv.h) + Deg(degrees)).to_deg();
+                hsluv.to_srgb()
+            }
+            HarmonySpace::Oklch => {
+                let mut lch = srgb.to_oklch::<f64>();
+                lch.h = (Hue::from(lch.h) + Deg(degrees)).to_deg();
+                map_to_srgb(&lch, GamutMapping::Css)
+            }
+            HarmonySpace::Ryb => {
+                let c = rgb.to_ryb::<f64>().rotate_hue(degrees).to_rgb::<f64>();
+                Srgb::new(c.r, c.g, c.b)
+            }
+        };
+        out.to_srgb()
+    }
+
+    /// The color and the hue opposite it.
+    pub fn complementary<C: ToSrgb, U: Channel>(self, color: &C) -> [Srgb<U>; 2] {
+        [color.to_srgb(), self.rotate(color, 180.0)]
+    }
+
+    /// The color and the two hues `spacing` degrees either side of its
+    /// complement. A spacing of `30` is customary.
+    pub fn split_complementary<C: ToSrgb, U: Channel>(self, color: &C, spacing: f64) -> [Srgb<U>; 3] {
+        [color.to_srgb(), self.rotate(color, 180.0 - spacing), self.rotate(color, 180.0 + spacing)]
+    }
+
+    /// Three hues evenly spaced around the circle.
+    pub fn triadic<C: ToSrgb, U: Channel>(self, color: &C) -> [Srgb<U>; 3] {
+        [color.to_srgb(), self.rotate(color, 120.0), self.rotate(color, 240.0)]
+    }
+
+    /// Two complementary pairs `spacing` degrees apart, forming a rectangle
+    /// on the hue circle. A spacing of `90` gives a square.
+    pub fn tetradic<C: ToSrgb, U: Channel>(self, color: &C, spacing: f64) -> [Srgb<U>; 4] {
+        [color.to_srgb(), self.rotate(color, spacing), self.rotate(color, 180.0), self.rotate(color, 180.0 + spacing)]
+    }
+
+    /// `count` neighbouring hues `spacing` degrees apart, alternating
+    /// either side of the color.
+    pub fn analogous<C: ToSrgb, U: Channel>(self, color: &C, count: usize, spacing: f64) -> Vec<Srgb<U>> {
+        (0..count).map(|i| {
+            if i == 0 {
+                return color.to_srgb();
+            }
+            let step = i.div_ceil(2) as f64 * spacing;
+            self.rotate(color, if i % 2 == 1 { step } else { -step })
+        }).collect()
+    }
+}
+
+/// The fraction of a turn between consecutive `distinct_color` hues. Stepping
+/// by the golden ratio keeps every prefix of the sequence well spread.
+const GOLDEN_TURN: f64 = 0.618033988749895;
+
+/// HSLuv lightnesses `distinct_color` cycles through once hue alone no
+/// longer separates the colors.
+const LIGHTNESSES: [f64; 3] = [60.0, 45.0, 75.0];
+
+/// The `index`th of `total` colors for categorical data. Hues step around
+/// the circle by the golden ratio from red, at a fixed lightness for up to
+/// ten colors; beyond that the lightness cycles through three levels as
+/// well. The sequence does not depend on `total` up to ten, so colors stay
+/// put as series are added.
+pub fn distinct_color<U: Channel>(index: usize, total: usize) -> Srgb<U> {
+    let hue = (12.0 + index as f64 * GOLDEN_TURN * 360.0) % 360.0;
+    let l = if total > 10 { LIGHTNESSES[index % 3] } else { LIGHTNESSES[0] };
+    Hsluv::new(Deg(hue), 90.0, l).to_srgb()
+}
+
+/// 64-bit FNV-1a, used instead of `DefaultHasher` because its output is
+/// fixed across Rust releases and platforms.
+struct Fnv(u64);
+
+impl Hasher for Fnv {
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 = (self.0 ^ b as u64).wrapping_mul(0x100000001b3);
+        }
+    }
+}
+
+impl<T: Channel> Srgb<T> {
+    /// A color picked from the hash of `value`, always the same for equal
+    /// values. The hue can be anything, while lightness and saturation are
+    /// kept in a narrow band so that every color is readable on the same
+    /// background. Integers hash through their native byte order, so they
+    /// can give different colors on machines of different endianness.
+    pub fn from_hash<H: Hash + ?Sized>(value: &H) -> Srgb<T> {
+        let mut hasher = Fnv(0xcbf29ce484222325);
+        value.hash(&mut hasher);
+        let h = hasher.finish();
+        let hue = (h & 0xFFFF) as f64 / 65536.0 * 360.0;
+        let s = 75.0 + ((h >> 16) & 0xFF) as f64 / 255.0 * 25.0;
+        let l = 55.0 + ((h >> 24) & 0xFF) as f64 / 255.0 * 15.0;
+        Hsluv::new(Deg(hue), s, l).to_srgb()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use super::{HarmonySpace, distinct_color};
+    use {Rgb, Srgb, ToSrgb, ToOklch, ToHsluv};
+
+    fn assert_rgb_eq(a: Srgb<f64>, b: Srgb<f64>) {
+        assert!((a.r - b.r).abs() < 1e-3 && (a.g - b.g).abs() < 1e-3 && (a.b - b.b).abs() < 1e-3,
+                "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn test_hsv_schemes() {
+        let red = Srgb::<f64>::new(1.0, 0.0, 0.0);
+        let [a, b] = HarmonySpace::Hsv.complementary::<_, f64>(&red);
+        assert_rgb_eq(a, red);
+        assert_rgb_eq(b, Srgb::new(0.0, 1.0, 1.0));
+        let t: [Srgb<f64>; 3] = HarmonySpace::Hsv.triadic(&red);
+        assert_rgb_eq(t[1], Srgb::new(0.0, 1.0, 0.0));
+        assert_rgb_eq(t[2], Srgb::new(0.0, 0.0, 1.0));
+        let s: [Srgb<f64>; 3] = HarmonySpace::Hsv.split_complementary(&red, 60.0);
+        assert_rgb_eq(s[1], Srgb::new(0.0, 1.0, 0.0));
+        assert_rgb_eq(s[2], Srgb::new(0.0, 0.0, 1.0));
+        let q: [Srgb<f64>; 4] = HarmonySpace::Hsv.tetradic(&red, 60.0);
+        assert_rgb_eq(q[1], Srgb::new(1.0, 1.0, 0.0));
+        assert_rgb_eq(q[3], Srgb::new(0.0, 0.0, 1.0));
+        let an: Vec<Srgb<f64>> = HarmonySpace::Hsv.analogous(&red, 3, 60.0);
+        assert_eq!(an.len(), 3);
+        assert_rgb_eq(an[1], Srgb::new(1.0, 1.0, 0.0));
+        assert_rgb_eq(an[2], Srgb::new(1.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn test_linear_input() {
+        // linear light is encoded first, so the hue rotated is the one of
+        // the sRGB color
+        let linear = Rgb::<f64>::new(0.2, 0.4, 0.6);
+        let [a, b] = HarmonySpace::Hsv.complementary::<_, f64>(&linear);
+        assert_rgb_eq(a, linear.to_srgb());
+        assert_rgb_eq(b, HarmonySpace::Hsv.rotate(&linear.to_srgb::<f64>(), 180.0));
+    }
+
+    #[test]
+    fn test_perceptual_schemes() {
+        let base = Srgb::<f64>::new(0.2, 0.4, 0.6);
+        let lch = base.to_oklch::<f64>();
+        let t: [Srgb<f64>; 3] = HarmonySpace::Oklch.triadic(&base);
+        for (i, c) in t.iter().enumerate() {
+            let out = c.to_oklch::<f64>();
+            let expected = (lch.h.0 + 120.0 * i as f64) % 360.0;
+            assert!((out.l - lch.l).abs() < 0.02, "{:?}", out);
+            assert!((out.h.0 - expected).abs() < 2.0, "{:?}", out);
+        }
+        let hsluv = base.to_hsluv::<f64>();
+        let [_, c] = HarmonySpace::Hsluv.complementary::<_, f64>(&base);
+        let out = c.to_hsluv::<f64>();
+        assert!((out.l - hsluv.l).abs() < 1e-6 && (out.s - hsluv.s).abs() < 1e-6);
+        assert!((out.h.0 - (hsluv.h.0 + 180.0) % 360.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_ryb_schemes() {
+        let red = Srgb::<f64>::new(1.0, 0.0, 0.0);
+        let [_, green] = HarmonySpace::Ryb.complementary::<_, f64>(&red);
+        assert_rgb_eq(green, Srgb::new(0.0, 1.0, 0.0));
+        // the primaries of the painters' wheel
+        let t: [Srgb<f64>; 3] = HarmonySpace::Ryb.triadic(&red);
+        assert_rgb_eq(t[1], Srgb::new(1.0, 1.0, 0.0));
+        assert_rgb_eq(t[2], Srgb::new(0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn test_distinct_color() {
+        let colors: Vec<Srgb<u8>> = (0..8).map(|i| distinct_color(i, 8)).collect();
+        for (i, a) in colors.iter().enumerate() {
+            for b in &colors[i + 1..] {
+                assert_ne!(a, b);
+            }
+        }
+        // adding series keeps the colors of existing ones
+        assert_eq!(distinct_color::<u8>(3, 4), colors[3]);
+        assert!((distinct_color::<f64>(5, 8).to_hsluv::<f64>().l - 60.0).abs() < 0.1);
+        assert!((distinct_color::<f64>(1, 20).to_hsluv::<f64>().l - 45.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_from_hash() {
+        assert_eq!(Srgb::<u8>::from_hash("server-1"), Srgb::<u8>::from_hash("server-1"));
+        assert_ne!(Srgb::<u8>::from_hash("server-1"), Srgb::<u8>::from_hash("server-2"));
+        let l = Srgb::<f64>::from_hash(&42u32).to_hsluv::<f64>().l;
+        assert!((55.0..=70.1).contains(&l), "{}", l);
+    }
+}
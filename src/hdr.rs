@@ -0,0 +1,82 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The SMPTE ST 2084 perceptual quantizer (PQ) used by HDR10 and BT.2100.
+//!
+//! PQ encodes absolute luminance up to 10000 cd/m². The rest of the crate
+//! works with relative values, so `Jzazbz`, `ICtCp` and
+//! `TransferFunction::Pq` place a relative luminance of `1` at
+//! `REFERENCE_WHITE`.
+
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use math::*;
+
+/// The luminance of diffuse white in HDR content, in cd/m², per ITU-R
+/// BT.2408.
+pub const REFERENCE_WHITE: f64 = 203.0;
+
+/// The luminance PQ encodes as `1`, in cd/m².
+pub const PEAK_LUMINANCE: f64 = 10000.0;
+
+const M1: f64 = 2610.0 / 16384.0;
+pub(crate) const M2: f64 = 2523.0 / 4096.0 * 128.0;
+const C1: f64 = 3424.0 / 4096.0;
+const C2: f64 = 2413.0 / 4096.0 * 32.0;
+const C3: f64 = 2392.0 / 4096.0 * 32.0;
+
+/// PQ with exponent `m2`, taking `y` relative to the peak luminance.
+/// Extended to negative values by symmetry.
+#[inline]
+pub(crate) fn encode_with(y: f64, m2: f64) -> f64 {
+    let p = y.abs().powf(M1);
+    ((C1 + C2 * p) / (1.0 + C3 * p)).powf(m2).copysign(y)
+}
+
+#[inline]
+pub(crate) fn decode_with(v: f64, m2: f64) -> f64 {
+    let p = v.abs().powf(1.0 / m2);
+    (((p - C1).max(0.0)) / (C2 - C3 * p)).powf(1.0 / M1).copysign(v)
+}
+
+/// Encodes a luminance in cd/m² into a PQ signal in `[0, 1]`.
+#[inline]
+pub fn pq_encode(luminance: f64) -> f64 {
+    encode_with(luminance / PEAK_LUMINANCE, M2)
+}
+
+/// Decodes a PQ signal into a luminance in cd/m².
+#[inline]
+pub fn pq_decode(signal: f64) -> f64 {
+    decode_with(signal, M2) * PEAK_LUMINANCE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pq() {
+        assert!(pq_encode(0.0) < 1e-6);
+        assert_eq!(pq_decode(0.0), 0.0);
+        assert!((pq_encode(PEAK_LUMINANCE) - 1.0).abs() < 1e-12);
+        assert!((pq_encode(REFERENCE_WHITE) - 0.5806).abs() < 1e-4);
+        assert!((pq_encode(100.0) - 0.5081).abs() < 1e-4);
+        assert!((pq_decode(0.5) - 92.24).abs() < 0.01);
+        for &l in [0.01, 1.0, 203.0, 1000.0, 4000.0].iter() {
+            assert!((pq_decode(pq_encode(l)) - l).abs() / l < 1e-9);
+        }
+    }
+}
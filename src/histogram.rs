@@ -0,0 +1,250 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Histograms of 8-bit pixels.
+//!
+//! The `0..=255` range of a component is split into `bins` equal bins, so
+//! with 256 bins every value has its own. Pixels can be given as a slice or
+//! as any iterator of `Rgb<u8>` or `&Rgb<u8>`.
+
+use core::borrow::Borrow;
+use alloc::vec::Vec;
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use math::*;
+
+use Rgb;
+
+#[inline]
+fn check_bins(bins: usize) {
+    assert!((1..=256).contains(&bins), "bin count must be between 1 and 256, got {}", bins);
+}
+
+#[inline]
+fn bin_of(value: u8, bins: usize) -> usize {
+    value as usize * bins / 256
+}
+
+/// Counts of the values of a single component.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Histogram {
+    pub counts: Vec<u64>,
+}
+
+impl Histogram {
+    /// Counts `values` into `bins` bins.
+    ///
+    /// # Panics
+    ///
+    /// If `bins` is not in `1..=256`.
+    pub fn new<I: IntoIterator<Item = u8>>(values: I, bins: usize) -> Histogram {
+        check_bins(bins);
+        let mut counts = vec![0; bins];
+        for v in values {
+            counts[bin_of(v, bins)] += 1;
+        }
+        Histogram { counts }
+    }
+
+    #[inline]
+    pub fn bins(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// The bin holding `value`.
+    #[inline]
+    pub fn bin(&self, value: u8) -> usize {
+        bin_of(value, self.bins())
+    }
+
+    /// The number of values counted.
+    #[inline]
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    /// The running totals of the counts: entry `i` is the number of values in
+    /// bins `0..=i`.
+    pub fn cumulative(&self) -> Vec<u64> {
+        self.counts.iter().scan(0, |sum, &c| { *sum += c; Some(*sum) }).collect()
+    }
+
+    /// The cumulative distribution function, as fractions of the total. All
+    /// zero for an empty histogram.
+    pub fn cdf(&self) -> Vec<f64> {
+        let total = self.total();
+        self.cumulative().into_iter()
+            .map(|c| if total == 0 { 0.0 } else { c as f64 / total as f64 })
+            .collect()
+    }
+
+    /// The first bin at which the cumulative fraction of values reaches `p`,
+    /// which is clamped to `[0, 1]`. `None` for an empty histogram.
+    pub fn percentile(&self, p: f64) -> Option<usize> {
+        let total = self.total();
+        if total == 0 {
+            return None;
+        }
+        let target = (p.clamp(0.0, 1.0) * total as f64).ceil().max(1.0) as u64;
+        self.cumulative().iter().position(|&c| c >= target)
+    }
+}
+
+/// Separate histograms of the red, green and blue components.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct RgbHistogram {
+    pub r: Histogram,
+    pub g: Histogram,
+    pub b: Histogram,
+}
+
+impl RgbHistogram {
+    /// # Panics
+    ///
+    /// If `bins` is not in `1..=256`.
+    pub fn new<C: Borrow<Rgb<u8>>, I: IntoIterator<Item = C>>(pixels: I, bins: usize) -> RgbHistogram {
+        check_bins(bins);
+        let (mut r, mut g, mut b) = (vec![0; bins], vec![0; bins], vec![0; bins]);
+        for p in pixels {
+            let p = p.borrow();
+            r[bin_of(p.r, bins)] += 1;
+            g[bin_of(p.g, bins)] += 1;
+            b[bin_of(p.b, bins)] += 1;
+        }
+        RgbHistogram {
+            r: Histogram { counts: r },
+            g: Histogram { counts: g },
+            b: Histogram { counts: b },
+        }
+    }
+}
+
+/// A joint histogram over the RGB cube, with `bins` bins along each axis.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Histogram3d {
+    bins: usize,
+    counts: Vec<u64>,
+}
+
+impl Histogram3d {
+    /// # Panics
+    ///
+    /// If `bins` is not in `1..=256`.
+    pub fn new<C: Borrow<Rgb<u8>>, I: IntoIterator<Item = C>>(pixels: I, bins: usize) -> Histogram3d {
+        check_bins(bins);
+        let mut h = Histogram3d { bins, counts: vec![0; bins * bins * bins] };
+        for p in pixels {
+            let i = h.index(h.bin(p.borrow()));
+            h.counts[i] += 1;
+        }
+        h
+    }
+
+    #[inline]
+    pub fn bins(&self) -> usize {
+        self.bins
+    }
+
+    /// The `[r, g, b]` bin holding `color`.
+    #[inline]
+    pub fn bin(&self, color: &Rgb<u8>) -> [usize; 3] {
+        [bin_of(color.r, self.bins), bin_of(color.g, self.bins), bin_of(color.b, self.bins)]
+    }
+
+    #[inline]
+    fn index(&self, [r, g, b]: [usize; 3]) -> usize {
+        (r * self.bins + g) * self.bins + b
+    }
+
+    /// The count of bin `[r, g, b]`.
+    ///
+    /// # Panics
+    ///
+    /// If a bin index is out of range.
+    #[inline]
+    pub fn get(&self, bin: [usize; 3]) -> u64 {
+        assert!(bin.iter().all(|&i| i < self.bins), "bin {:?} out of range", bin);
+        self.counts[self.index(bin)]
+    }
+
+    #[inline]
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    /// The color at the center of bin `[r, g, b]`.
+    pub fn center(&self, bin: [usize; 3]) -> Rgb<u8> {
+        let c = |i: usize| ((2 * i + 1) * 256 / (2 * self.bins)) as u8;
+        Rgb::new(c(bin[0]), c(bin[1]), c(bin[2]))
+    }
+
+    /// The non-empty bins and their counts, in index order.
+    pub fn iter(&self) -> impl Iterator<Item = ([usize; 3], u64)> + '_ {
+        let n = self.bins;
+        self.counts.iter().enumerate()
+            .filter(|&(_, &c)| c > 0)
+            .map(move |(i, &c)| ([i / (n * n), i / n % n, i % n], c))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use super::{Histogram, RgbHistogram, Histogram3d};
+    use Rgb;
+
+    #[test]
+    fn test_histogram() {
+        let h = Histogram::new(vec![0, 1, 127, 128, 255, 255], 2);
+        assert_eq!(h.counts, vec![3, 3]);
+        let h = Histogram::new(vec![0, 10, 10, 200], 256);
+        assert_eq!(h.bins(), 256);
+        assert_eq!(h.total(), 4);
+        assert_eq!(h.counts[10], 2);
+        assert_eq!(h.cumulative()[10], 3);
+        assert_eq!(h.cdf()[255], 1.0);
+        assert_eq!(h.cdf()[0], 0.25);
+        assert_eq!(h.percentile(0.0), Some(0));
+        assert_eq!(h.percentile(0.5), Some(10));
+        assert_eq!(h.percentile(1.0), Some(200));
+        assert_eq!(Histogram::new(vec![], 16).percentile(0.5), None);
+    }
+
+    #[test]
+    fn test_rgb_histogram() {
+        let pixels = [Rgb::new(0, 128, 255), Rgb::new(255, 128, 0)];
+        let h = RgbHistogram::new(pixels, 4);
+        assert_eq!(h.r.counts, vec![1, 0, 0, 1]);
+        assert_eq!(h.g.counts, vec![0, 0, 2, 0]);
+        assert_eq!(h, RgbHistogram::new(pixels.iter(), 4));
+    }
+
+    #[test]
+    fn test_histogram_3d() {
+        let pixels = [Rgb::new(0, 0, 0), Rgb::new(10, 20, 30), Rgb::new(255, 0, 255)];
+        let h = Histogram3d::new(pixels, 8);
+        assert_eq!(h.total(), 3);
+        assert_eq!(h.get([0, 0, 0]), 2);
+        assert_eq!(h.get([7, 0, 7]), 1);
+        assert_eq!(h.iter().collect::<Vec<_>>(), vec![([0, 0, 0], 2), ([7, 0, 7], 1)]);
+        assert_eq!(h.center([0, 0, 7]), Rgb::new(16, 16, 240));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_bins_out_of_range() {
+        Histogram::new(vec![0], 0);
+    }
+}
@@ -0,0 +1,179 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The HSI model of Gonzalez and Woods, where intensity is the mean of the
+//! three channels and hue is the angle around the gray axis.
+//!
+//! Not every HSI triple is a displayable color: high saturations at high
+//! intensities give RGB channels above one, which are not clipped.
+//!
+//! http://en.wikipedia.org/wiki/HSL_and_HSV#Lightness
+
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use math::*;
+
+use angle::*;
+
+use {Color, FloatColor};
+use {Channel, FloatChannel};
+use channel::cast;
+use {Rgb, ToRgb};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Hsi<T: Channel> {
+    #[cfg_attr(feature = "serde", serde(with = "::serialize::deg"))]
+    pub h: Deg<T>,
+    pub s: T,
+    pub i: T,
+}
+
+impl<T: Channel> Hsi<T> {
+    pub const fn new(h: Deg<T>, s: T, i: T) -> Hsi<T> {
+        Hsi { h, s, i }
+    }
+}
+
+impl<T: Channel> Color<T> for Hsi<T> {
+    /// Clamps the components of the color to the range `(lo,hi)`.
+    #[inline]
+    fn clamp_s(self, lo: T, hi: T) -> Hsi<T> {
+        Hsi::new(self.h,
+                 self.s.clamp(lo, hi),
+                 self.i.clamp(lo, hi))
+    }
+
+    /// Clamps the components of the color component-wise between `lo` and `hi`.
+    #[inline]
+    fn clamp_c(self, lo: Hsi<T>, hi: Hsi<T>) -> Hsi<T> {
+        Hsi::new(self.h,
+                 self.s.clamp(lo.s, hi.s),
+                 self.i.clamp(lo.i, hi.i))
+    }
+
+    /// Inverts the color through RGB.
+    #[inline]
+    fn inverse(self) -> Hsi<T> {
+        self.to_rgb::<f64>().inverse().to_hsi()
+    }
+
+    #[inline]
+    fn mix(self, other: Self, value: T) -> Self {
+        self.to_rgb().mix(other.to_rgb(), value).to_hsi()
+    }
+}
+
+impl<T: FloatChannel> FloatColor<T> for Hsi<T> {
+    /// Normalizes the components of the color. Modulo `360` is applied to the
+    /// `h` component, and `s` and `i` are clamped to the range `(0,1)`.
+    #[inline]
+    fn saturate(self) -> Hsi<T> {
+        Hsi::new(self.h.wrap(),
+                 self.s.saturate(),
+                 self.i.saturate())
+    }
+}
+
+pub trait ToHsi {
+    fn to_hsi<U:Channel>(&self) -> Hsi<U>;
+}
+
+impl<T:Channel> ToHsi for Hsi<T> {
+    #[inline]
+    fn to_hsi<U:Channel>(&self) -> Hsi<U> {
+        Hsi::new(Deg(cast(self.h.value())),
+                 self.s.to_channel(),
+                 self.i.to_channel())
+    }
+}
+
+impl<T:Channel> ToHsi for Rgb<T> {
+    fn to_hsi<U:Channel>(&self) -> Hsi<U> {
+        let r = self.r.to_channel_f64();
+        let g = self.g.to_channel_f64();
+        let b = self.b.to_channel_f64();
+
+        let i = (r + g + b) / 3.0;
+        let mn = r.min(g).min(b);
+        let s = if i == 0.0 { 0.0 } else { 1.0 - mn / i };
+
+        let num = 0.5 * ((r - g) + (r - b));
+        let den = ((r - g) * (r - g) + (r - b) * (g - b)).sqrt();
+        let h = if den == 0.0 {
+            0.0
+        } else {
+            let theta = (num / den).clamp(-1.0, 1.0).acos().to_degrees();
+            if b <= g { theta } else { 360.0 - theta }
+        };
+
+        Hsi::new(Deg(cast(h)), Channel::from(s), Channel::from(i))
+    }
+}
+
+impl<T:Channel> ToRgb for Hsi<T> {
+    fn to_rgb<U:Channel>(&self) -> Rgb<U> {
+        let h: f64 = cast(self.h.value());
+        let h = h.rem_euclid(360.0);
+        let s = self.s.to_channel_f64();
+        let i = self.i.to_channel_f64();
+
+        // the lowest channel sits at i * (1 - s), the sector's leading
+        // channel is found from the hue and the third makes up the sum
+        let sector = (h / 120.0) as usize % 3;
+        let h = (h - 120.0 * sector as f64).to_radians();
+        let low = i * (1.0 - s);
+        let lead = i * (1.0 + s * h.cos() / (60.0f64.to_radians() - h).cos());
+        let rest = 3.0 * i - (low + lead);
+
+        let (r, g, b) = match sector {
+            0 => (lead, rest, low),
+            1 => (low, lead, rest),
+            _ => (rest, low, lead),
+        };
+
+        Rgb::new(Channel::from(r),
+                 Channel::from(g),
+                 Channel::from(b))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {Hsi, ToHsi};
+    use {Rgb, ToRgb};
+    use angle::*;
+
+    #[test]
+    fn test_rgb_to_hsi() {
+        assert_eq!(Rgb::<u8>::new(0xFF, 0xFF, 0xFF).to_hsi::<f32>(), Hsi::<f32>::new(Deg(0.0), 0.0, 1.0));
+        assert_eq!(Rgb::<u8>::new(0x00, 0x00, 0x00).to_hsi::<f32>(), Hsi::<f32>::new(Deg(0.0), 0.0, 0.0));
+        let red = Rgb::<f64>::new(1.0, 0.0, 0.0).to_hsi::<f64>();
+        assert!(red.h.0.abs() < 1e-9 && (red.s - 1.0).abs() < 1e-9 && (red.i - 1.0 / 3.0).abs() < 1e-9);
+        let blue = Rgb::<f64>::new(0.0, 0.0, 1.0).to_hsi::<f64>();
+        assert!((blue.h.0 - 240.0).abs() < 1e-9);
+        let yellow = Rgb::<f64>::new(1.0, 1.0, 0.0).to_hsi::<f64>();
+        assert!((yellow.h.0 - 60.0).abs() < 1e-9 && (yellow.i - 2.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_hsi_roundtrip() {
+        for &(r, g, b) in &[(0.9, 0.2, 0.1), (0.1, 0.8, 0.3), (0.2, 0.3, 0.9), (0.7, 0.1, 0.6), (0.5, 0.5, 0.5)] {
+            let back: Rgb<f64> = Rgb::<f64>::new(r, g, b).to_hsi::<f64>().to_rgb();
+            assert!((back.r - r).abs() < 1e-9 && (back.g - g).abs() < 1e-9 && (back.b - b).abs() < 1e-9,
+                    "{:?}", back);
+        }
+    }
+}
@@ -0,0 +1,216 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! http://en.wikipedia.org/wiki/HSL_and_HSV
+
+use angle::*;
+
+use {Color, FloatColor};
+use {Channel, FloatChannel};
+use channel::cast;
+use hue::wrap_hue;
+use {Rgb, ToRgb, Hsv, ToHsv};
+
+/// Hue, saturation and lightness. The hue is in degrees in the channel
+/// type, so an `Hsl<u8>` only holds hues up to `255`: larger ones saturate.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Hsl<T: Channel> {
+    #[cfg_attr(feature = "serde", serde(with = "::serialize::deg"))]
+    pub h: Deg<T>,
+    pub s: T,
+    pub l: T,
+}
+
+impl<T: Channel> Hsl<T> {
+    pub const fn new(h: Deg<T>, s: T, l: T) -> Hsl<T> {
+        Hsl { h, s, l }
+    }
+}
+
+impl<T: Channel> Color<T> for Hsl<T> {
+    /// Clamps the components of the color to the range `(lo,hi)`.
+    #[inline]
+    fn clamp_s(self, lo: T, hi: T) -> Hsl<T> {
+        Hsl::new(self.h,
+                 self.s.clamp(lo, hi),
+                 self.l.clamp(lo, hi))
+    }
+
+    /// Clamps the components of the color component-wise between `lo` and `hi`.
+    #[inline]
+    fn clamp_c(self, lo: Hsl<T>, hi: Hsl<T>) -> Hsl<T> {
+        Hsl::new(self.h,
+                 self.s.clamp(lo.s, hi.s),
+                 self.l.clamp(lo.l, hi.l))
+    }
+
+    /// Inverts the color. The hue is rotated by 180 degrees and the lightness
+    /// is inverted, which matches the inverse of the equivalent RGB color.
+    #[inline]
+    fn inverse(self) -> Hsl<T> {
+        Hsl::new((self.h + Deg(cast(180))).wrap(),
+                 self.s,
+                 self.l.invert_channel())
+    }
+
+    #[inline]
+    fn mix(self, other: Self, value: T) -> Self {
+        self.to_rgb().mix(other.to_rgb(), value).to_hsl()
+    }
+}
+
+impl<T: FloatChannel> FloatColor<T> for Hsl<T> {
+    /// Normalizes the components of the color. Modulo `360` is applied to the
+    /// `h` component, and `s` and `l` are clamped to the range `(0,1)`.
+    #[inline]
+    fn saturate(self) -> Hsl<T> {
+        Hsl::new(self.h.wrap(),
+                 self.s.saturate(),
+                 self.l.saturate())
+    }
+}
+
+pub trait ToHsl {
+    fn to_hsl<U:Channel>(&self) -> Hsl<U>;
+}
+
+impl<T:Channel> ToHsl for Hsl<T> {
+    #[inline]
+    fn to_hsl<U:Channel>(&self) -> Hsl<U> {
+        Hsl::new(Deg(cast(self.h.value())),
+                 self.s.to_channel(),
+                 self.l.to_channel())
+    }
+}
+
+impl<T:Channel> ToHsl for Rgb<T> {
+    fn to_hsl<U:Channel>(&self) -> Hsl<U> {
+        // Algorithm taken from the Wikipedia article on HSL and HSV:
+        // http://en.wikipedia.org/wiki/HSL_and_HSV#General_approach
+
+        let r = self.r.to_channel_f64();
+        let g = self.g.to_channel_f64();
+        let b = self.b.to_channel_f64();
+
+        let mx = r.max(g).max(b);
+        let mn = r.min(g).min(b);
+        let chr = mx - mn;
+        let l = (mx + mn) / 2.0;
+
+        if chr == 0.0 {
+            return Hsl::new(Deg(cast(0)), Channel::from(0.0f64), Channel::from(l));
+        }
+
+        let h = if      r == mx       { ((g - b) / chr) % 6.0 }
+                else if g == mx       { ((b - r) / chr) + 2.0 }
+                else    /* b == mx */ { ((r - g) / chr) + 4.0 }
+            * 60.0;
+        let s = chr / (1.0 - (2.0 * l - 1.0).abs());
+
+        Hsl::new(Deg(cast(wrap_hue(h))), Channel::from(s), Channel::from(l))
+    }
+}
+
+impl<T:Channel> ToHsl for Hsv<T> {
+    fn to_hsl<U:Channel>(&self) -> Hsl<U> {
+        let s = self.s.to_channel_f64();
+        let v = self.v.to_channel_f64();
+        let l = v * (1.0 - s / 2.0);
+        let sl = if l == 0.0 || l == 1.0 { 0.0 } else { (v - l) / l.min(1.0 - l) };
+        Hsl::new(Deg(cast(self.h.value())), Channel::from(sl), Channel::from(l))
+    }
+}
+
+impl<T:Channel> ToHsv for Hsl<T> {
+    fn to_hsv<U:Channel>(&self) -> Hsv<U> {
+        let s = self.s.to_channel_f64();
+        let l = self.l.to_channel_f64();
+        let v = l + s * l.min(1.0 - l);
+        let sv = if v == 0.0 { 0.0 } else { 2.0 * (1.0 - l / v) };
+        Hsv::new(Deg(cast(self.h.value())), Channel::from(sv), Channel::from(v))
+    }
+}
+
+impl<T:Channel> ToRgb for Hsl<T> {
+    fn to_rgb<U:Channel>(&self) -> Rgb<U> {
+        let h = wrap_hue(cast(self.h.value())) / 60.0;
+        let s = self.s.to_channel_f64();
+        let l = self.l.to_channel_f64();
+
+        let chr = (1.0 - (2.0 * l - 1.0).abs()) * s;
+        let x = chr * (1.0 - (h % 2.0 - 1.0).abs());
+        let m = l - chr / 2.0;
+
+        let (r, g, b) = match h as usize {
+            1 =>     (x, chr, 0.0),
+            2 =>     (0.0, chr, x),
+            3 =>     (0.0, x, chr),
+            4 =>     (x, 0.0, chr),
+            5 =>     (chr, 0.0, x),
+            // 0, 6 for a hue that rounded up to 360, and NaN
+            _ =>     (chr, x, 0.0),
+        };
+
+        Rgb::new(Channel::from(r + m),
+                 Channel::from(g + m),
+                 Channel::from(b + m))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {Hsl, ToHsl};
+    use {Hsv, ToHsv};
+    use {Rgb, ToRgb};
+    use angle::*;
+
+    #[test]
+    fn test_hsl_to_hsl() {
+        assert_eq!(Hsl::<f64>::new(Deg(0.0), 0.0, 1.0).to_hsl::<f32>(),   Hsl::<f32>::new(Deg(0.0), 0.0, 1.0));
+        assert_eq!(Hsl::<f64>::new(Deg(120.0), 1.0, 0.5).to_hsl::<f32>(), Hsl::<f32>::new(Deg(120.0), 1.0, 0.5));
+    }
+
+    #[test]
+    fn test_rgb_to_hsl() {
+        assert_eq!(Rgb::<u8>::new(0xFF, 0xFF, 0xFF).to_hsl::<f32>(), Hsl::<f32>::new(Deg(0.0), 0.0, 1.0));
+        assert_eq!(Rgb::<u8>::new(0x00, 0x00, 0x00).to_hsl::<f32>(), Hsl::<f32>::new(Deg(0.0), 0.0, 0.0));
+        assert_eq!(Rgb::<f32>::new(1.0, 0.0, 0.0).to_hsl::<f32>(),   Hsl::<f32>::new(Deg(0.0), 1.0, 0.5));
+        assert_eq!(Rgb::<f32>::new(0.0, 1.0, 0.0).to_hsl::<f32>(),   Hsl::<f32>::new(Deg(120.0), 1.0, 0.5));
+        assert_eq!(Rgb::<f32>::new(0.0, 0.0, 1.0).to_hsl::<f32>(),   Hsl::<f32>::new(Deg(240.0), 1.0, 0.5));
+        assert_eq!(Rgb::<f32>::new(1.0, 0.0, 1.0).to_hsl::<f32>(),   Hsl::<f32>::new(Deg(300.0), 1.0, 0.5));
+        assert_eq!(Rgb::<f32>::new(0.5, 1.0, 1.0).to_hsl::<f32>(),   Hsl::<f32>::new(Deg(180.0), 1.0, 0.75));
+    }
+
+    #[test]
+    fn test_hsl_to_rgb() {
+        assert_eq!(Hsl::<f32>::new(Deg(0.0), 0.0, 1.0).to_rgb::<u8>(),   Rgb::<u8>::new(0xFF, 0xFF, 0xFF));
+        assert_eq!(Hsl::<f32>::new(Deg(0.0), 1.0, 0.5).to_rgb::<u8>(),   Rgb::<u8>::new(0xFF, 0x00, 0x00));
+        assert_eq!(Hsl::<f32>::new(Deg(120.0), 1.0, 0.5).to_rgb::<u8>(), Rgb::<u8>::new(0x00, 0xFF, 0x00));
+        assert_eq!(Hsl::<f32>::new(Deg(240.0), 1.0, 0.5).to_rgb::<u8>(), Rgb::<u8>::new(0x00, 0x00, 0xFF));
+        assert_eq!(Hsl::<f32>::new(Deg(180.0), 1.0, 0.75).to_rgb::<f32>(), Rgb::<f32>::new(0.5, 1.0, 1.0));
+        assert_eq!(Hsl::<f32>::new(Deg(-60.0), 1.0, 0.5).to_rgb::<f32>(), Rgb::<f32>::new(1.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn test_hsl_hsv_roundtrip() {
+        assert_eq!(Hsv::<f64>::new(Deg(0.0), 1.0, 1.0).to_hsl::<f64>(),   Hsl::<f64>::new(Deg(0.0), 1.0, 0.5));
+        assert_eq!(Hsl::<f64>::new(Deg(0.0), 1.0, 0.5).to_hsv::<f64>(),   Hsv::<f64>::new(Deg(0.0), 1.0, 1.0));
+        assert_eq!(Hsv::<f64>::new(Deg(90.0), 0.0, 0.0).to_hsl::<f64>(),  Hsl::<f64>::new(Deg(90.0), 0.0, 0.0));
+        assert_eq!(Hsl::<f64>::new(Deg(90.0), 0.0, 0.0).to_hsv::<f64>(),  Hsv::<f64>::new(Deg(90.0), 0.0, 0.0));
+        assert_eq!(Hsl::<f64>::new(Deg(200.0), 0.5, 0.25).to_hsv::<f64>().to_hsl::<f64>(),
+                   Hsl::<f64>::new(Deg(200.0), 0.5, 0.25));
+    }
+}
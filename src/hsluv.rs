@@ -0,0 +1,279 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! HSLuv and HPLuv, https://www.hsluv.org
+//!
+//! Both are LCh(uv) with the chroma rescaled against the sRGB gamut, so the
+//! saturation and lightness run from `0` to `100`. HSLuv scales by the
+//! largest chroma available at each hue, so every saturation is in gamut but
+//! equal saturations can differ in chroma. HPLuv scales by the largest chroma
+//! available at *any* hue for the lightness, keeping chroma uniform at the
+//! cost of only covering pastels; its `p` may exceed `100` for colors outside
+//! that range.
+//!
+//! As with `Xyz`, `Rgb` is linear light; use `Srgb` for encoded values.
+
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use math::*;
+
+use angle::*;
+
+use {Channel, FloatChannel};
+use {Rgb, ToRgb, Srgb, Xyz, ToXyz, WhitePoint, Lchuv, ToLchuv};
+use luv::{EPSILON, KAPPA, uv_prime};
+use xyz::XYZ_TO_SRGB;
+
+/// The chromaticity `(u', v')` of the reference white.
+fn white_uv() -> (f64, f64) {
+    let w = WhitePoint::D65;
+    uv_prime(w.x, w.y, w.z)
+}
+
+/// The six lines `(slope, intercept)` in the uv plane bounding the sRGB
+/// gamut at lightness `l`, where one of the RGB channels is `0` or `1`.
+fn gamut_bounds(l: f64) -> [(f64, f64); 6] {
+    let (un, vn) = white_uv();
+    let y = if l > KAPPA * EPSILON { ((l + 16.0) / 116.0).powi(3) } else { l / KAPPA };
+    let mut bounds = [(0.0, 0.0); 6];
+    for (i, row) in XYZ_TO_SRGB.iter().enumerate() {
+        let (m1, m2, m3) = (row[0], row[1], row[2]);
+        for (j, &t) in [0.0, 1.0].iter().enumerate() {
+            // substituting X and Z from u and v into `m1 X + m2 Y + m3 Z = t`
+            // leaves a line in the (u, v) plane
+            let d = 4.0 * t - y * (4.0 * m2 - 20.0 * m3);
+            let slope = y * (9.0 * m1 - 3.0 * m3) / d;
+            let intercept = 13.0 * l * (y * ((9.0 * m1 - 3.0 * m3) * un + (4.0 * m2 - 20.0 * m3) * vn + 12.0 * m3)
+                                        - 4.0 * t * vn) / d;
+            bounds[i * 2 + j] = (slope, intercept);
+        }
+    }
+    bounds
+}
+
+/// The largest in-gamut chroma at lightness `l` and hue `h`.
+fn max_chroma(l: f64, h: f64) -> f64 {
+    let (sin, cos) = h.to_radians().sin_cos();
+    gamut_bounds(l).iter()
+        .map(|&(slope, intercept)| intercept / (sin - slope * cos))
+        .filter(|&len| len >= 0.0)
+        .fold(f64::INFINITY, f64::min)
+}
+
+/// The largest chroma that is in gamut for every hue at lightness `l`.
+fn max_safe_chroma(l: f64) -> f64 {
+    gamut_bounds(l).iter()
+        .map(|&(slope, intercept)| intercept.abs() / (slope * slope + 1.0).sqrt())
+        .fold(f64::INFINITY, f64::min)
+}
+
+/// Rescales chroma to a percentage of `max`, pinning white and black.
+#[inline]
+fn to_percent(l: f64, c: f64, max: f64) -> f64 {
+    if (1e-8..=99.9999999).contains(&l) { c / max * 100.0 } else { 0.0 }
+}
+
+#[inline]
+fn from_percent(l: f64, s: f64, max: f64) -> f64 {
+    if (1e-8..=99.9999999).contains(&l) { max / 100.0 * s } else { 0.0 }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Hsluv<T: Channel> {
+    #[cfg_attr(feature = "serde", serde(with = "::serialize::deg"))]
+    pub h: Deg<T>,
+    pub s: T,
+    pub l: T,
+}
+
+impl<T: Channel> Hsluv<T> {
+    #[inline]
+    pub const fn new(h: Deg<T>, s: T, l: T) -> Hsluv<T> {
+        Hsluv { h, s, l }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Hpluv<T: Channel> {
+    #[cfg_attr(feature = "serde", serde(with = "::serialize::deg"))]
+    pub h: Deg<T>,
+    pub p: T,
+    pub l: T,
+}
+
+impl<T: Channel> Hpluv<T> {
+    #[inline]
+    pub const fn new(h: Deg<T>, p: T, l: T) -> Hpluv<T> {
+        Hpluv { h, p, l }
+    }
+}
+
+pub trait ToHsluv {
+    fn to_hsluv<U:FloatChannel>(&self) -> Hsluv<U>;
+}
+
+pub trait ToHpluv {
+    fn to_hpluv<U:FloatChannel>(&self) -> Hpluv<U>;
+}
+
+impl<T:FloatChannel> ToHsluv for Hsluv<T> {
+    #[inline]
+    fn to_hsluv<U:FloatChannel>(&self) -> Hsluv<U> {
+        Hsluv::new(Deg(self.h.value().to_channel()), self.s.to_channel(), self.l.to_channel())
+    }
+}
+
+impl<T:FloatChannel> ToHsluv for Xyz<T> {
+    fn to_hsluv<U:FloatChannel>(&self) -> Hsluv<U> {
+        let lch = self.to_lchuv::<f64>();
+        let (l, c, h) = (lch.l, lch.c, lch.h.value());
+        Hsluv::new(Deg(Channel::from(h)), Channel::from(to_percent(l, c, max_chroma(l, h))), Channel::from(l))
+    }
+}
+
+impl<T:Channel> ToHsluv for Rgb<T> {
+    #[inline]
+    fn to_hsluv<U:FloatChannel>(&self) -> Hsluv<U> {
+        self.to_xyz::<f64>().to_hsluv()
+    }
+}
+
+impl<T:Channel> ToHsluv for Srgb<T> {
+    #[inline]
+    fn to_hsluv<U:FloatChannel>(&self) -> Hsluv<U> {
+        self.to_xyz::<f64>().to_hsluv()
+    }
+}
+
+impl<T:FloatChannel> ToHsluv for Hpluv<T> {
+    #[inline]
+    fn to_hsluv<U:FloatChannel>(&self) -> Hsluv<U> {
+        self.to_xyz::<f64>().to_hsluv()
+    }
+}
+
+impl<T:FloatChannel> ToHpluv for Hpluv<T> {
+    #[inline]
+    fn to_hpluv<U:FloatChannel>(&self) -> Hpluv<U> {
+        Hpluv::new(Deg(self.h.value().to_channel()), self.p.to_channel(), self.l.to_channel())
+    }
+}
+
+impl<T:FloatChannel> ToHpluv for Xyz<T> {
+    fn to_hpluv<U:FloatChannel>(&self) -> Hpluv<U> {
+        let lch = self.to_lchuv::<f64>();
+        let (l, c, h) = (lch.l, lch.c, lch.h.value());
+        Hpluv::new(Deg(Channel::from(h)), Channel::from(to_percent(l, c, max_safe_chroma(l))), Channel::from(l))
+    }
+}
+
+impl<T:Channel> ToHpluv for Rgb<T> {
+    #[inline]
+    fn to_hpluv<U:FloatChannel>(&self) -> Hpluv<U> {
+        self.to_xyz::<f64>().to_hpluv()
+    }
+}
+
+impl<T:Channel> ToHpluv for Srgb<T> {
+    #[inline]
+    fn to_hpluv<U:FloatChannel>(&self) -> Hpluv<U> {
+        self.to_xyz::<f64>().to_hpluv()
+    }
+}
+
+impl<T:FloatChannel> ToHpluv for Hsluv<T> {
+    #[inline]
+    fn to_hpluv<U:FloatChannel>(&self) -> Hpluv<U> {
+        self.to_xyz::<f64>().to_hpluv()
+    }
+}
+
+impl<T:FloatChannel> ToXyz for Hsluv<T> {
+    fn to_xyz<U:FloatChannel>(&self) -> Xyz<U> {
+        let (h, s, l) = (self.h.value().to_channel_f64(), self.s.to_channel_f64(), self.l.to_channel_f64());
+        Lchuv::new(l, from_percent(l, s, max_chroma(l, h)), Deg(h)).to_xyz()
+    }
+}
+
+impl<T:FloatChannel> ToXyz for Hpluv<T> {
+    fn to_xyz<U:FloatChannel>(&self) -> Xyz<U> {
+        let (h, p, l) = (self.h.value().to_channel_f64(), self.p.to_channel_f64(), self.l.to_channel_f64());
+        Lchuv::new(l, from_percent(l, p, max_safe_chroma(l)), Deg(h)).to_xyz()
+    }
+}
+
+impl<T:FloatChannel> ToRgb for Hsluv<T> {
+    #[inline]
+    fn to_rgb<U:Channel>(&self) -> Rgb<U> {
+        self.to_xyz::<f64>().to_rgb()
+    }
+}
+
+impl<T:FloatChannel> ToRgb for Hpluv<T> {
+    #[inline]
+    fn to_rgb<U:Channel>(&self) -> Rgb<U> {
+        self.to_xyz::<f64>().to_rgb()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {Hsluv, ToHsluv, Hpluv, ToHpluv, Srgb, ToSrgb};
+    use angle::*;
+
+    fn assert_close(a: (f64, f64, f64), b: (f64, f64, f64), tolerance: f64) {
+        let dh = ((a.0 - b.0) % 360.0 + 540.0) % 360.0 - 180.0;
+        assert!(dh.abs() < tolerance && (a.1 - b.1).abs() < tolerance && (a.2 - b.2).abs() < tolerance,
+                "{:?} != {:?}", a, b);
+    }
+
+    fn hsluv(c: Hsluv<f64>) -> (f64, f64, f64) { (c.h.0, c.s, c.l) }
+    fn hpluv(c: Hpluv<f64>) -> (f64, f64, f64) { (c.h.0, c.p, c.l) }
+
+    #[test]
+    fn test_srgb_to_hsluv() {
+        // reference values from the HSLuv test snapshot
+        assert_close(hsluv(Srgb::<u8>::new(0xFF, 0x00, 0x00).to_hsluv()), (12.1771, 100.0, 53.2371), 1e-2);
+        assert_close(hsluv(Srgb::<u8>::new(0x00, 0x00, 0xFF).to_hsluv()), (265.8743, 100.0, 32.3009), 1e-2);
+        assert_close(hsluv(Srgb::<u8>::new(0x33, 0x66, 0x99).to_hsluv()), (246.9424, 78.4512, 42.0092), 1e-2);
+        let white = Srgb::<u8>::new(0xFF, 0xFF, 0xFF).to_hsluv::<f64>();
+        assert_close((white.s, white.l, 0.0), (0.0, 100.0, 0.0), 1e-6);
+    }
+
+    #[test]
+    fn test_srgb_to_hpluv() {
+        // HPLuv magnifies the small difference between the matrices used here
+        // and by the reference implementation
+        assert_close(hpluv(Srgb::<u8>::new(0xFF, 0x00, 0x00).to_hpluv()), (12.1771, 426.7468, 53.2371), 0.2);
+        assert_close(hpluv(Srgb::<u8>::new(0x00, 0x00, 0xFF).to_hpluv()), (265.8743, 513.4127, 32.3009), 0.2);
+        assert_close(hpluv(Srgb::<u8>::new(0x33, 0x66, 0x99).to_hpluv()), (246.9424, 156.1315, 42.0092), 0.2);
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        for &(h, s, l) in [(0.0, 100.0, 50.0), (120.0, 40.0, 80.0), (250.0, 100.0, 10.0), (300.0, 0.0, 60.0)].iter() {
+            let srgb = Hsluv::<f64>::new(Deg(h), s, l).to_srgb::<f64>();
+            assert!(srgb.r >= -1e-9 && srgb.r <= 1.0 + 1e-9 && srgb.g >= -1e-9 && srgb.g <= 1.0 + 1e-9 &&
+                    srgb.b >= -1e-9 && srgb.b <= 1.0 + 1e-9, "{:?}", srgb);
+            let back = srgb.to_hsluv::<f64>();
+            assert_close((back.h.0, back.s, back.l), (if s == 0.0 { back.h.0 } else { h }, s, l), 1e-6);
+
+            let hp = Hpluv::<f64>::new(Deg(h), s, l).to_srgb::<f64>().to_hpluv::<f64>();
+            assert_close((hp.h.0, hp.p, hp.l), (if s == 0.0 { hp.h.0 } else { h }, s, l), 1e-6);
+        }
+    }
+}
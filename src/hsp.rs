@@ -0,0 +1,200 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Darel Rex Finley's HSP model. Hue and saturation are those of HSV, and
+//! perceived brightness replaces value:
+//!
+//! `P = sqrt(0.299 R² + 0.587 G² + 0.114 B²)`
+//!
+//! Pure blue has a brightness of about `0.34` and pure yellow of about
+//! `0.94`, where HSV gives both a value of one. As with HSI, some triples
+//! lie outside the RGB cube and convert to channels above one.
+//!
+//! http://alienryderflex.com/hsp.html
+
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use math::*;
+
+use angle::*;
+
+use {Color, FloatColor};
+use {Channel, FloatChannel};
+use channel::cast;
+use {Rgb, ToRgb, Hsv, ToHsv};
+
+const PR: f64 = 0.299;
+const PG: f64 = 0.587;
+const PB: f64 = 0.114;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Hsp<T: Channel> {
+    #[cfg_attr(feature = "serde", serde(with = "::serialize::deg"))]
+    pub h: Deg<T>,
+    pub s: T,
+    pub p: T,
+}
+
+impl<T: Channel> Hsp<T> {
+    pub const fn new(h: Deg<T>, s: T, p: T) -> Hsp<T> {
+        Hsp { h, s, p }
+    }
+}
+
+impl<T: Channel> Color<T> for Hsp<T> {
+    /// Clamps the components of the color to the range `(lo,hi)`.
+    #[inline]
+    fn clamp_s(self, lo: T, hi: T) -> Hsp<T> {
+        Hsp::new(self.h,
+                 self.s.clamp(lo, hi),
+                 self.p.clamp(lo, hi))
+    }
+
+    /// Clamps the components of the color component-wise between `lo` and `hi`.
+    #[inline]
+    fn clamp_c(self, lo: Hsp<T>, hi: Hsp<T>) -> Hsp<T> {
+        Hsp::new(self.h,
+                 self.s.clamp(lo.s, hi.s),
+                 self.p.clamp(lo.p, hi.p))
+    }
+
+    /// Inverts the color through RGB.
+    #[inline]
+    fn inverse(self) -> Hsp<T> {
+        self.to_rgb::<f64>().inverse().to_hsp()
+    }
+
+    #[inline]
+    fn mix(self, other: Self, value: T) -> Self {
+        self.to_rgb().mix(other.to_rgb(), value).to_hsp()
+    }
+}
+
+impl<T: FloatChannel> FloatColor<T> for Hsp<T> {
+    /// Normalizes the components of the color. Modulo `360` is applied to the
+    /// `h` component, and `s` and `p` are clamped to the range `(0,1)`.
+    #[inline]
+    fn saturate(self) -> Hsp<T> {
+        Hsp::new(self.h.wrap(),
+                 self.s.saturate(),
+                 self.p.saturate())
+    }
+}
+
+pub trait ToHsp {
+    fn to_hsp<U:Channel>(&self) -> Hsp<U>;
+}
+
+impl<T:Channel> ToHsp for Hsp<T> {
+    #[inline]
+    fn to_hsp<U:Channel>(&self) -> Hsp<U> {
+        Hsp::new(Deg(cast(self.h.value())),
+                 self.s.to_channel(),
+                 self.p.to_channel())
+    }
+}
+
+impl<T:Channel> ToHsp for Rgb<T> {
+    fn to_hsp<U:Channel>(&self) -> Hsp<U> {
+        let r = self.r.to_channel_f64();
+        let g = self.g.to_channel_f64();
+        let b = self.b.to_channel_f64();
+        let hsv = self.to_hsv::<f64>();
+        let p = (PR * r * r + PG * g * g + PB * b * b).sqrt();
+        Hsp::new(Deg(cast(hsv.h.value())), Channel::from(hsv.s), Channel::from(p))
+    }
+}
+
+impl<T:Channel> ToHsp for Hsv<T> {
+    #[inline]
+    fn to_hsp<U:Channel>(&self) -> Hsp<U> {
+        self.to_rgb::<f64>().to_hsp()
+    }
+}
+
+impl<T:Channel> ToRgb for Hsp<T> {
+    fn to_rgb<U:Channel>(&self) -> Rgb<U> {
+        let h: f64 = cast(self.h.value());
+        let h = h.rem_euclid(360.0) / 60.0;
+        let s = self.s.to_channel_f64();
+        let p = self.p.to_channel_f64();
+
+        // Each sextant has a largest, a middle and a smallest channel. `f`
+        // is how far the middle channel sits from the smallest towards the
+        // largest, and the largest is solved for from the brightness.
+        let sextant = (h as usize).min(5);
+        let f = if sextant.is_multiple_of(2) { h - sextant as f64 } else { sextant as f64 + 1.0 - h };
+        let (wmax, wmid, wmin) = match sextant {
+            0 => (PR, PG, PB),
+            1 => (PG, PR, PB),
+            2 => (PG, PB, PR),
+            3 => (PB, PG, PR),
+            4 => (PB, PR, PG),
+            _ => (PR, PB, PG),
+        };
+        let ratio = 1.0 - s;
+        let mid = ratio + f * (1.0 - ratio);
+        let max = p / (wmax + wmid * mid * mid + wmin * ratio * ratio).sqrt();
+        let (max, mid, min) = (max, max * mid, max * ratio);
+
+        let (r, g, b) = match sextant {
+            0 => (max, mid, min),
+            1 => (mid, max, min),
+            2 => (min, max, mid),
+            3 => (min, mid, max),
+            4 => (mid, min, max),
+            _ => (max, min, mid),
+        };
+
+        Rgb::new(Channel::from(r),
+                 Channel::from(g),
+                 Channel::from(b))
+    }
+}
+
+impl<T:Channel> ToHsv for Hsp<T> {
+    #[inline]
+    fn to_hsv<U:Channel>(&self) -> Hsv<U> {
+        self.to_rgb::<f64>().to_hsv()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {Hsp, ToHsp};
+    use {Rgb, ToRgb};
+    use angle::*;
+
+    #[test]
+    fn test_rgb_to_hsp() {
+        assert_eq!(Rgb::<u8>::new(0xFF, 0xFF, 0xFF).to_hsp::<f32>(), Hsp::<f32>::new(Deg(0.0), 0.0, 1.0));
+        assert_eq!(Rgb::<u8>::new(0x00, 0x00, 0x00).to_hsp::<f32>(), Hsp::<f32>::new(Deg(0.0), 0.0, 0.0));
+        let blue = Rgb::<f64>::new(0.0, 0.0, 1.0).to_hsp::<f64>();
+        let yellow = Rgb::<f64>::new(1.0, 1.0, 0.0).to_hsp::<f64>();
+        assert!((blue.p - 0.114f64.sqrt()).abs() < 1e-9 && (blue.h.0 - 240.0).abs() < 1e-9);
+        assert!((yellow.p - 0.886f64.sqrt()).abs() < 1e-9 && yellow.s == 1.0);
+    }
+
+    #[test]
+    fn test_hsp_roundtrip() {
+        for &(r, g, b) in &[(0.9, 0.2, 0.1), (0.1, 0.8, 0.3), (0.2, 0.3, 0.9), (0.7, 0.1, 0.6),
+                            (0.3, 0.9, 0.9), (0.9, 0.9, 0.2), (0.5, 0.5, 0.5), (0.0, 0.6, 1.0)] {
+            let back: Rgb<f64> = Rgb::<f64>::new(r, g, b).to_hsp::<f64>().to_rgb();
+            assert!((back.r - r).abs() < 1e-9 && (back.g - g).abs() < 1e-9 && (back.b - b).abs() < 1e-9,
+                    "{:?} {:?}", (r, g, b), back);
+        }
+    }
+}
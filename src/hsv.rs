@@ -13,25 +13,70 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use num::{self, zero};
-use num::traits::{Float, Zero};
+use num::zero;
 use angle::*;
 
 use {Color, FloatColor};
 use {Channel, FloatChannel};
+use channel::cast;
+use hue::wrap_hue;
 use {Rgb, ToRgb};
 
-#[inline]
-fn cast<T: num::NumCast, U: num::NumCast>(n: T) -> U {
-    num::traits::cast(n).unwrap()
+/// Which way around the hue circle to travel when interpolating hues.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HueDirection {
+    /// Take the shorter arc, at most 180 degrees.
+    Shortest,
+    /// Take the longer arc, at least 180 degrees.
+    Longest,
+    /// Move towards increasing hue values.
+    Clockwise,
+    /// Move towards decreasing hue values.
+    CounterClockwise,
 }
 
+impl HueDirection {
+    /// Interpolates between two hues given in degrees, returning a hue in
+    /// `[0, 360)`.
+    pub(crate) fn interpolate(self, from: f64, to: f64, value: f64) -> f64 {
+        let d = wrap_hue(to - from);
+        let d = match self {
+            HueDirection::Shortest         => if d > 180.0 { d - 360.0 } else { d },
+            HueDirection::Longest          => if d == 0.0 { 360.0 } else if d < 180.0 { d - 360.0 } else { d },
+            HueDirection::Clockwise        => d,
+            HueDirection::CounterClockwise => if d == 0.0 { d } else { d - 360.0 },
+        };
+        wrap_hue(from + d * value)
+    }
+}
+
+/// Hue, saturation and value. The hue is in degrees in the channel type,
+/// so an `Hsv<u8>` only holds hues up to `255`: larger ones saturate.
 #[derive(Clone, Copy, PartialEq, Debug)]
-pub struct Hsv<T: Channel> { pub h: Deg<T>, pub s: T, pub v: T }
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Hsv<T: Channel> {
+    #[cfg_attr(feature = "serde", serde(with = "::serialize::deg"))]
+    pub h: Deg<T>,
+    pub s: T,
+    pub v: T,
+}
 
 impl<T: Channel> Hsv<T> {
-    pub fn new(h: Deg<T>, s: T, v: T) -> Hsv<T> {
-        Hsv { h: h, s: s, v: v }
+    pub const fn new(h: Deg<T>, s: T, v: T) -> Hsv<T> {
+        Hsv { h, s, v }
+    }
+
+    /// Mixes two colors in HSV, interpolating the hue in the given
+    /// direction. The hue of a gray carries no information, so mixing with
+    /// a gray keeps the hue of the other color.
+    pub fn mix_hue(self, other: Hsv<T>, value: T, direction: HueDirection) -> Hsv<T> {
+        let (mut h0, mut h1): (f64, f64) = (cast(self.h.value()), cast(other.h.value()));
+        if self.s.is_zero() { h0 = h1; }
+        if other.s.is_zero() { h1 = h0; }
+        let h = direction.interpolate(h0, h1, value.to_channel_f64());
+        Hsv::new(Deg(cast(h)),
+                 self.s.mix(other.s, value),
+                 self.v.mix(other.v, value))
     }
 }
 
@@ -60,9 +105,11 @@ impl<T: Channel> Color<T> for Hsv<T> {
                  self.v.invert_channel())
     }
     
+    /// Mixes the colors in HSV, taking the shortest way around the hue
+    /// circle. See `mix_hue` for the other directions.
     #[inline]
     fn mix(self, other: Self, value: T) -> Self {
-        self.to_rgb().mix(other.to_rgb(),value).to_hsv() // TODO: can we mix the hsv directly?
+        self.mix_hue(other, value, HueDirection::Shortest)
     }
 }
 
@@ -82,16 +129,19 @@ pub trait ToHsv {
 }
 
 impl ToHsv for u32 {
+    /// Converts a packed `0xRRGGBB` value, ignoring the high byte.
     #[inline]
     fn to_hsv<U:Channel>(&self) -> Hsv<U> {
-        panic!("Not yet implemented")
+        self.to_rgb::<u8>().to_hsv()
     }
 }
 
 impl ToHsv for u64 {
+    /// Converts a packed `0xRRRRGGGGBBBB` value with 16-bit channels, ignoring
+    /// the high 16 bits.
     #[inline]
     fn to_hsv<U:Channel>(&self) -> Hsv<U> {
-        panic!("Not yet implemented")
+        self.to_rgb::<u16>().to_hsv()
     }
 }
 
@@ -104,6 +154,8 @@ impl<T:Channel> ToHsv for Hsv<T> {
     }
 }
 
+/// With `u8`, `u16` or `Fixed` components and an integer result, this uses
+/// integer arithmetic only.
 impl<T:Clone + Channel> ToRgb for Hsv<T> {
     fn to_rgb<U:Channel>(&self) -> Rgb<U> {
         if self.v.is_zero() {
@@ -112,23 +164,19 @@ impl<T:Clone + Channel> ToRgb for Hsv<T> {
             let gray = Channel::from(self.v);
             rgb!(gray, gray, gray)
         } else {
-            let max_f: f64 = cast(T::max()); 
-            let hue: f64 = cast(self.h.wrap().value());
-            let hue_six: f64 = hue / 360f64 * 6f64;
-            let hue_six_cat: usize = cast(hue_six);
-            let hue_six_rem: T = cast(hue_six.fract() * max_f);
+            let (hue_six_cat, hue_six_rem) = self.h.value().hue_sextant();
             let pv = Channel::from((T::max() - self.s).normalized_mul(self.v));
             let qv = Channel::from((T::max() - self.s.normalized_mul(hue_six_rem)).normalized_mul(self.v));
             let tv = Channel::from((T::max() - self.s.normalized_mul(T::max() - hue_six_rem)).normalized_mul(self.v));
             let b: U = Channel::from(self.v);
             match hue_six_cat {
-                0 | 6 => rgb!(b,tv,pv),
                 1 =>     rgb!(qv, b, pv),
                 2 =>     rgb!(pv, b, tv),
                 3 =>     rgb!(pv, qv, b),
                 4 =>     rgb!(tv, pv, b),
                 5 =>     rgb!(b, pv, qv),
-                _ => panic!("Unreachable code")
+                // 0, 6 for a hue that rounded up to 360, and NaN
+                _ =>     rgb!(b, tv, pv),
             }
         }
     }
@@ -136,8 +184,8 @@ impl<T:Clone + Channel> ToRgb for Hsv<T> {
 
 #[cfg(test)]
 mod tests {
-    use {Hsv, ToHsv};
-    use {Rgb, ToRgb};
+    use {Color, Hsv, ToHsv, HueDirection};
+    use {Rgb, ToRgb, ToRgba};
     use angle::*;
 
     #[test]
@@ -159,4 +207,95 @@ mod tests {
         assert_eq!(Hsv::<u16>::new(Deg(120), 65535, 39321).to_rgb::<u8>(), Rgb::<u8>::new(0x00, 0x99, 0x00));
         assert_eq!(Hsv::<u16>::new(Deg(240), 65535, 39321).to_rgb::<u8>(), Rgb::<u8>::new(0x00, 0x00, 0x99));
     }
+
+    /// Conversions by the `f64` implementation this crate had before the
+    /// integer one, which computed the hue sextant in `f64` and products in
+    /// `f32`, and so could be off by one.
+    const U8_GOLDENS: [(u8, u8, u8, [u8; 3]); 15] = [
+        (0, 255, 255, [255, 0, 0]), (1, 128, 200, [200, 101, 99]), (30, 77, 255, [255, 217, 178]),
+        (59, 255, 51, [51, 50, 0]), (60, 200, 128, [128, 128, 27]), (61, 1, 254, [254, 254, 253]),
+        (80, 255, 255, [171, 255, 0]), (119, 128, 200, [101, 200, 99]), (120, 77, 255, [178, 255, 178]),
+        (179, 255, 51, [0, 51, 50]), (180, 200, 128, [27, 128, 128]), (200, 1, 254, [253, 254, 254]),
+        (239, 255, 255, [0, 5, 255]), (240, 128, 200, [99, 99, 200]), (255, 77, 255, [198, 178, 255]),
+    ];
+    const U16_GOLDENS: [(u16, u16, u16, [u16; 3]); 14] = [
+        (0, 65535, 65535, [65535, 0, 0]), (1, 32768, 52000, [52000, 26433, 25999]),
+        (59, 20000, 65535, [65535, 65202, 45535]), (60, 65535, 13107, [13107, 13107, 0]),
+        (90, 1, 65534, [65534, 65534, 65533]), (120, 65535, 65535, [0, 65535, 0]),
+        (200, 32768, 52000, [25999, 43333, 52000]), (240, 20000, 65535, [45535, 45535, 65535]),
+        (300, 65535, 13107, [13107, 0, 13107]), (359, 1, 65534, [65534, 65533, 65534]),
+        (360, 65535, 65535, [65535, 0, 0]), (719, 32768, 52000, [52000, 25999, 26433]),
+        (1000, 20000, 65535, [58869, 45535, 65535]), (65535, 65535, 13107, [13107, 3276, 0]),
+    ];
+
+    #[test]
+    fn test_integer_hsv_to_rgb() {
+        let near = |a: [u32; 3], b: [u32; 3], d: i64| (0..3).all(|i| (a[i] as i64 - b[i] as i64).abs() <= d);
+        for &(h, s, v, rgb) in U8_GOLDENS.iter() {
+            let c = Hsv::<u8>::new(Deg(h), s, v).to_rgb::<u8>();
+            assert!(near([c.r as u32, c.g as u32, c.b as u32], rgb.map(u32::from), 1), "{} {} {}: {:?}", h, s, v, c);
+        }
+        for &(h, s, v, rgb) in U16_GOLDENS.iter() {
+            let c = Hsv::<u16>::new(Deg(h), s, v).to_rgb::<u16>();
+            assert!(near([c.r as u32, c.g as u32, c.b as u32], rgb.map(u32::from), 1), "{} {} {}: {:?}", h, s, v, c);
+        }
+
+        // against the exact result, which the integer products each truncate
+        for h in 0..=255u8 {
+            for s in (0..=255u8).step_by(5) {
+                for v in (0..=255u8).step_by(5) {
+                    let c = Hsv::<u8>::new(Deg(h), s, v).to_rgb::<u8>();
+                    let f = Hsv::<f64>::new(Deg(h as f64), s as f64 / 255.0, v as f64 / 255.0).to_rgb::<f64>();
+                    let f = [f.r, f.g, f.b].map(|x| (x * 255.0 + 1e-9) as u32);
+                    assert!(near([c.r as u32, c.g as u32, c.b as u32], f, 2), "{} {} {}: {:?} {:?}", h, s, v, c, f);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_packed_to_hsv() {
+        assert_eq!(0xFFFFFF_u32.to_hsv::<f32>(),   Hsv::<f32>::new(Deg(0.0), 0.0, 1.0));
+        assert_eq!(0x990000_u32.to_hsv::<f32>(),   Hsv::<f32>::new(Deg(0.0), 1.0, 0.6));
+        assert_eq!(0x009900_u32.to_hsv::<f32>(),   Hsv::<f32>::new(Deg(120.0), 1.0, 0.6));
+        assert_eq!(0xFF000099_u32.to_hsv::<f32>(), Hsv::<f32>::new(Deg(240.0), 1.0, 0.6));
+
+        assert_eq!(0xFFFF_FFFF_FFFF_u64.to_hsv::<f32>(), Hsv::<f32>::new(Deg(0.0), 0.0, 1.0));
+        assert_eq!(0x9999_0000_0000_u64.to_hsv::<f32>(), Hsv::<f32>::new(Deg(0.0), 1.0, 0.6));
+        assert_eq!(0x0000_9999_0000_u64.to_hsv::<f32>(), Hsv::<f32>::new(Deg(120.0), 1.0, 0.6));
+        assert_eq!(0x0000_0000_9999_u64.to_hsv::<f32>(), Hsv::<f32>::new(Deg(240.0), 1.0, 0.6));
+
+        // `ToRgb` and `ToHsv` read 0xRRGGBB, `ToRgba` the same color shifted
+        // above an alpha byte
+        let packed = 0x102030_u32;
+        assert_eq!(packed.to_hsv::<f32>(), packed.to_rgb::<u8>().to_hsv::<f32>());
+        assert_eq!((packed << 8 | 0x40).to_rgba::<u8>().c, packed.to_rgb::<u8>());
+    }
+
+    #[test]
+    fn test_hsv_mix() {
+        let red = Hsv::<f64>::new(Deg(0.0), 1.0, 1.0);
+        let blue = Hsv::<f64>::new(Deg(240.0), 1.0, 1.0);
+        assert_eq!(red.mix(blue, 0.5),                                     Hsv::new(Deg(300.0), 1.0, 1.0));
+        assert_eq!(red.mix_hue(blue, 0.5, HueDirection::Longest),          Hsv::new(Deg(120.0), 1.0, 1.0));
+        assert_eq!(red.mix_hue(blue, 0.5, HueDirection::Clockwise),        Hsv::new(Deg(120.0), 1.0, 1.0));
+        assert_eq!(red.mix_hue(blue, 0.5, HueDirection::CounterClockwise), Hsv::new(Deg(300.0), 1.0, 1.0));
+        assert_eq!(blue.mix_hue(red, 0.5, HueDirection::Clockwise),        Hsv::new(Deg(300.0), 1.0, 1.0));
+        assert_eq!(red.mix_hue(red, 0.25, HueDirection::Longest),          Hsv::new(Deg(90.0), 1.0, 1.0));
+
+        let gray = Hsv::<f64>::new(Deg(0.0), 0.0, 0.5);
+        assert_eq!(gray.mix(blue, 0.5), Hsv::new(Deg(240.0), 0.5, 0.75));
+    }
+
+    #[test]
+    fn test_hsv_no_panic() {
+        // negative and NaN hues, and hues a u8 cannot hold
+        assert_eq!(Hsv::<f64>::new(Deg(-120.0), 1.0, 1.0).to_rgb::<f32>(), Rgb::new(0.0, 0.0, 1.0));
+        assert_eq!(Hsv::<f64>::new(Deg(f64::NAN), 1.0, 1.0).to_rgb::<u8>(), Rgb::new(0xFF, 0x00, 0x00));
+        // a hue of 300 saturates to the 255 a u8 can hold
+        let hsv = Rgb::<u8>::new(0xFF, 0x00, 0xFF).to_hsv::<u8>();
+        assert_eq!((hsv.h.0, hsv.s, hsv.v), (255, 0xFF, 0xFF));
+        assert_eq!(hsv.to_rgb::<u8>(), Rgb::new(0x3F, 0x00, 0xFF));
+        assert_eq!(Rgb::<u8>::new(0xFF, 0x00, 0xFF).to_hsv::<u16>().h.0, 300);
+    }
 }
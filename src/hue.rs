@@ -0,0 +1,307 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Hues as points on a circle.
+//!
+//! The color types keep their hue as a plain `Deg`, which compares, adds
+//! and interpolates like any other number: `350°` and `10°` are `340°`
+//! apart and their average is `180°`. `Hue` always holds an angle in
+//! `[0, 360)` and does its arithmetic around the circle, so the two are
+//! `20°` apart and meet at `0°`. Convert with `Hue::from` and `Deg::from`.
+//!
+//! Hues can also be given in radians with `Rad`, or in `Turns`, the
+//! fraction of a full circle that graphics code often uses for `0..1`
+//! hues. The cylindrical RGB models take either through `from_rad` and
+//! `from_turns`.
+
+use core::ops::{Add, Sub};
+
+use angle::{Deg, Rad};
+
+use FloatChannel;
+use channel::cast;
+use {Hsv, Hsl, Hwb, HueDirection};
+
+/// Wraps a hue given in degrees into `[0, 360)`. NaN and infinite hues
+/// become `0`, as for an achromatic color.
+#[inline]
+pub(crate) fn wrap_hue(h: f64) -> f64 {
+    if !h.is_finite() { return 0.0; }
+    let h = h % 360.0;
+    if h < 0.0 { h + 360.0 } else { h }
+}
+
+/// An angle on the hue circle, in degrees, normalized to `[0, 360)`.
+#[derive(Clone, Copy, PartialEq, PartialOrd, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Hue<T>(T);
+
+impl<T: FloatChannel> Hue<T> {
+    /// Wraps an angle in degrees onto the circle. Non-finite angles give a
+    /// non-finite hue.
+    #[inline]
+    pub fn from_degrees(degrees: T) -> Hue<T> {
+        let full: T = cast(360.0);
+        let h = degrees % full;
+        let h = if h < T::zero() { h + full } else { h };
+        // a tiny negative angle can round up to exactly 360
+        Hue(if h >= full { T::zero() } else { h })
+    }
+
+    /// The angle in degrees, in `[0, 360)`.
+    #[inline]
+    pub fn degrees(self) -> T {
+        self.0
+    }
+
+    #[inline]
+    pub fn to_deg(self) -> Deg<T> {
+        Deg(self.0)
+    }
+
+    /// The signed angle of the shorter turn from `self` to `other`, in
+    /// `(-180, 180]`.
+    #[inline]
+    pub fn difference(self, other: Hue<T>) -> T {
+        let half: T = cast(180.0);
+        let d = Hue::from_degrees(other.0 - self.0).0;
+        if d > half { d - cast(360.0) } else { d }
+    }
+
+    /// The unsigned angle between the two hues, at most `180`.
+    #[inline]
+    pub fn distance(self, other: Hue<T>) -> T {
+        self.difference(other).abs()
+    }
+
+    /// The hue halfway between the two along the shorter arc.
+    #[inline]
+    pub fn midpoint(self, other: Hue<T>) -> Hue<T> {
+        self.lerp(other, cast(0.5))
+    }
+
+    /// Interpolates along the shorter arc.
+    #[inline]
+    pub fn lerp(self, other: Hue<T>, t: T) -> Hue<T> {
+        self.lerp_with(other, t, HueDirection::Shortest)
+    }
+
+    /// Interpolates around the circle in the given direction.
+    #[inline]
+    pub fn lerp_with(self, other: Hue<T>, t: T, direction: HueDirection) -> Hue<T> {
+        let h = direction.interpolate(cast(self.0), cast(other.0), cast(t));
+        Hue::from_degrees(cast(h))
+    }
+
+    /// Whether the hues are within `tolerance` degrees of each other around
+    /// the circle.
+    #[inline]
+    pub fn approx_eq(self, other: Hue<T>, tolerance: T) -> bool {
+        self.distance(other) <= tolerance
+    }
+}
+
+/// An angle in full turns, so `1` is `360°`.
+#[derive(Clone, Copy, PartialEq, PartialOrd, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Turns<T>(pub T);
+
+impl<T: FloatChannel> From<Turns<T>> for Deg<T> {
+    #[inline]
+    fn from(t: Turns<T>) -> Deg<T> {
+        Deg(t.0 * cast(360.0))
+    }
+}
+
+impl<T: FloatChannel> From<Deg<T>> for Turns<T> {
+    #[inline]
+    fn from(d: Deg<T>) -> Turns<T> {
+        Turns(d.0 / cast(360.0))
+    }
+}
+
+impl<T: FloatChannel> From<Turns<T>> for Rad<T> {
+    #[inline]
+    fn from(t: Turns<T>) -> Rad<T> {
+        Rad(t.0 * cast(::core::f64::consts::TAU))
+    }
+}
+
+impl<T: FloatChannel> From<Rad<T>> for Turns<T> {
+    #[inline]
+    fn from(r: Rad<T>) -> Turns<T> {
+        Turns(r.0 / cast(::core::f64::consts::TAU))
+    }
+}
+
+impl<T: FloatChannel> Hue<T> {
+    #[inline]
+    pub fn to_rad(self) -> Rad<T> {
+        Rad(self.0.to_radians())
+    }
+
+    /// The hue as a fraction of the circle, in `[0, 1)`.
+    #[inline]
+    pub fn to_turns(self) -> Turns<T> {
+        Turns::from(self.to_deg())
+    }
+}
+
+impl<T: FloatChannel> From<Rad<T>> for Hue<T> {
+    #[inline]
+    fn from(r: Rad<T>) -> Hue<T> {
+        Hue::from_degrees(r.0.to_degrees())
+    }
+}
+
+impl<T: FloatChannel> From<Turns<T>> for Hue<T> {
+    #[inline]
+    fn from(t: Turns<T>) -> Hue<T> {
+        Hue::from(Deg::from(t))
+    }
+}
+
+macro_rules! hue_angle_impls {
+    ($($ty:ident { $a:ident, $b:ident };)*) => {
+        $(
+            impl<T: FloatChannel> $ty<T> {
+                /// Builds the color from a hue in radians.
+                #[inline]
+                pub fn from_rad(h: Rad<T>, $a: T, $b: T) -> $ty<T> {
+                    $ty::new(Deg(h.0.to_degrees()), $a, $b)
+                }
+
+                /// Builds the color from a hue in turns, `0.5` being cyan.
+                #[inline]
+                pub fn from_turns(h: Turns<T>, $a: T, $b: T) -> $ty<T> {
+                    $ty::new(Deg::from(h), $a, $b)
+                }
+
+                #[inline]
+                pub fn hue_rad(&self) -> Rad<T> {
+                    Rad(self.h.0.to_radians())
+                }
+
+                #[inline]
+                pub fn hue_turns(&self) -> Turns<T> {
+                    Turns::from(self.h)
+                }
+            }
+        )*
+    }
+}
+
+hue_angle_impls! {
+    Hsv { s, v };
+    Hsl { s, l };
+    Hwb { w, b };
+}
+
+impl<T: FloatChannel> From<Deg<T>> for Hue<T> {
+    #[inline]
+    fn from(d: Deg<T>) -> Hue<T> {
+        Hue::from_degrees(d.0)
+    }
+}
+
+impl<T: FloatChannel> From<Hue<T>> for Deg<T> {
+    #[inline]
+    fn from(h: Hue<T>) -> Deg<T> {
+        h.to_deg()
+    }
+}
+
+/// Rotates the hue, wrapping around.
+impl<T: FloatChannel> Add<Deg<T>> for Hue<T> {
+    type Output = Hue<T>;
+
+    #[inline]
+    fn add(self, rhs: Deg<T>) -> Hue<T> {
+        Hue::from_degrees(self.0 + rhs.0)
+    }
+}
+
+impl<T: FloatChannel> Sub<Deg<T>> for Hue<T> {
+    type Output = Hue<T>;
+
+    #[inline]
+    fn sub(self, rhs: Deg<T>) -> Hue<T> {
+        Hue::from_degrees(self.0 - rhs.0)
+    }
+}
+
+/// The signed shortest difference, `rhs.difference(self)`.
+impl<T: FloatChannel> Sub for Hue<T> {
+    type Output = Deg<T>;
+
+    #[inline]
+    fn sub(self, rhs: Hue<T>) -> Deg<T> {
+        Deg(rhs.difference(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Hue, Turns};
+    use angle::{Deg, Rad};
+    use {HueDirection, Hsv, Hsl, ToRgb, Rgb};
+
+    #[test]
+    fn test_wrap() {
+        assert_eq!(Hue::from_degrees(370.0).degrees(), 10.0);
+        assert_eq!(Hue::from_degrees(-10.0).degrees(), 350.0);
+        assert_eq!(Hue::from_degrees(-720.0).degrees(), 0.0);
+        assert_eq!(Hue::from_degrees(-1e-20f64).degrees(), 0.0);
+        assert_eq!(Hue::from(Deg(360.0f32)), Hue::from_degrees(0.0));
+        assert_eq!(Hue::from_degrees(350.0) + Deg(20.0), Hue::from_degrees(10.0));
+        assert_eq!(Hue::from_degrees(10.0) - Deg(20.0), Hue::from_degrees(350.0));
+    }
+
+    #[test]
+    fn test_circular() {
+        let (a, b) = (Hue::from_degrees(350.0f64), Hue::from_degrees(10.0));
+        assert_eq!(a.difference(b), 20.0);
+        assert_eq!(b.difference(a), -20.0);
+        assert_eq!(b - a, Deg(20.0));
+        assert_eq!(a.distance(b), 20.0);
+        assert_eq!(a.midpoint(b).degrees(), 0.0);
+        assert_eq!(a.lerp(b, 0.25).degrees(), 355.0);
+        assert_eq!(a.lerp_with(b, 0.5, HueDirection::Longest).degrees(), 180.0);
+        assert_eq!(Hue::from_degrees(0.0).difference(Hue::from_degrees(180.0)), 180.0);
+        assert!(a.approx_eq(Hue::from_degrees(-9.5), 0.5));
+        assert!(!a.approx_eq(b, 19.0));
+    }
+
+    #[test]
+    fn test_angles() {
+        use core::f64::consts::PI;
+        assert_eq!(Deg::from(Turns(0.25f64)), Deg(90.0));
+        assert_eq!(Turns::from(Deg(90.0f64)), Turns(0.25));
+        assert!((Rad::from(Turns(0.5f64)).0 - PI).abs() < 1e-15);
+        assert!((Turns::from(Rad(PI)).0 - 0.5).abs() < 1e-15);
+        assert_eq!(Hue::from(Turns(1.25f64)).degrees(), 90.0);
+        assert!((Hue::from(Rad(-PI / 2.0)).degrees() - 270.0).abs() < 1e-12);
+        assert!((Hue::from_degrees(180.0f64).to_rad().0 - PI).abs() < 1e-15);
+        assert_eq!(Hue::from_degrees(270.0f64).to_turns(), Turns(0.75));
+
+        let cyan = Hsv::from_turns(Turns(0.5f64), 1.0, 1.0);
+        assert_eq!(cyan.h, Deg(180.0));
+        assert_eq!(cyan.to_rgb::<f64>(), Rgb::new(0.0, 1.0, 1.0));
+        assert_eq!(cyan.hue_turns(), Turns(0.5));
+        let green = Hsl::from_rad(Rad(2.0 * PI / 3.0), 1.0, 0.5f64);
+        assert!((green.h.0 - 120.0).abs() < 1e-12);
+        assert!((green.hue_rad().0 - 2.0 * PI / 3.0).abs() < 1e-15);
+    }
+}
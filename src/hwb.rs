@@ -0,0 +1,199 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! http://en.wikipedia.org/wiki/HWB_color_model
+//!
+//! When whiteness and blackness add up to more than one, they are scaled down
+//! proportionally, which gives a gray, as CSS specifies.
+
+use angle::*;
+
+use {Color, FloatColor};
+use {Channel, FloatChannel};
+use channel::cast;
+use {Rgb, ToRgb, Hsv, ToHsv, Hsl, ToHsl, HueDirection};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Hwb<T: Channel> {
+    #[cfg_attr(feature = "serde", serde(with = "::serialize::deg"))]
+    pub h: Deg<T>,
+    pub w: T,
+    pub b: T,
+}
+
+impl<T: Channel> Hwb<T> {
+    pub const fn new(h: Deg<T>, w: T, b: T) -> Hwb<T> {
+        Hwb { h, w, b }
+    }
+
+    /// Whiteness and blackness as `f64`, scaled down if they exceed one in
+    /// total.
+    fn normalized_wb(&self) -> (f64, f64) {
+        let (w, b) = (self.w.to_channel_f64(), self.b.to_channel_f64());
+        if w + b > 1.0 { (w / (w + b), b / (w + b)) } else { (w, b) }
+    }
+}
+
+impl<T: Channel> Color<T> for Hwb<T> {
+    /// Clamps the components of the color to the range `(lo,hi)`.
+    #[inline]
+    fn clamp_s(self, lo: T, hi: T) -> Hwb<T> {
+        Hwb::new(self.h,
+                 self.w.clamp(lo, hi),
+                 self.b.clamp(lo, hi))
+    }
+
+    /// Clamps the components of the color component-wise between `lo` and `hi`.
+    #[inline]
+    fn clamp_c(self, lo: Hwb<T>, hi: Hwb<T>) -> Hwb<T> {
+        Hwb::new(self.h,
+                 self.w.clamp(lo.w, hi.w),
+                 self.b.clamp(lo.b, hi.b))
+    }
+
+    /// Inverts the color. The hue is rotated by 180 degrees and whiteness and
+    /// blackness trade places, which matches the inverse of the equivalent
+    /// RGB color.
+    #[inline]
+    fn inverse(self) -> Hwb<T> {
+        Hwb::new((self.h + Deg(cast(180))).wrap(), self.b, self.w)
+    }
+
+    /// Mixes the colors in HWB, taking the shortest way around the hue
+    /// circle. Grays keep the hue of the other color.
+    fn mix(self, other: Self, value: T) -> Self {
+        let (mut h0, mut h1): (f64, f64) = (cast(self.h.value()), cast(other.h.value()));
+        let gray = |c: &Hwb<T>| { let (w, b) = c.normalized_wb(); w + b >= 1.0 };
+        if gray(&self) { h0 = h1; }
+        if gray(&other) { h1 = h0; }
+        let h = HueDirection::Shortest.interpolate(h0, h1, value.to_channel_f64());
+        Hwb::new(Deg(cast(h)),
+                 self.w.mix(other.w, value),
+                 self.b.mix(other.b, value))
+    }
+}
+
+impl<T: FloatChannel> FloatColor<T> for Hwb<T> {
+    /// Normalizes the components of the color. Modulo `360` is applied to the
+    /// `h` component, and `w` and `b` are clamped to the range `(0,1)`.
+    #[inline]
+    fn saturate(self) -> Hwb<T> {
+        Hwb::new(self.h.wrap(),
+                 self.w.saturate(),
+                 self.b.saturate())
+    }
+}
+
+pub trait ToHwb {
+    fn to_hwb<U:Channel>(&self) -> Hwb<U>;
+}
+
+impl<T:Channel> ToHwb for Hwb<T> {
+    #[inline]
+    fn to_hwb<U:Channel>(&self) -> Hwb<U> {
+        Hwb::new(Deg(cast(self.h.value())),
+                 self.w.to_channel(),
+                 self.b.to_channel())
+    }
+}
+
+impl<T:Channel> ToHwb for Hsv<T> {
+    fn to_hwb<U:Channel>(&self) -> Hwb<U> {
+        let s = self.s.to_channel_f64();
+        let v = self.v.to_channel_f64();
+        Hwb::new(Deg(cast(self.h.value())), Channel::from((1.0 - s) * v), Channel::from(1.0 - v))
+    }
+}
+
+impl<T:Channel> ToHwb for Rgb<T> {
+    #[inline]
+    fn to_hwb<U:Channel>(&self) -> Hwb<U> {
+        self.to_hsv::<f64>().to_hwb()
+    }
+}
+
+impl<T:Channel> ToHwb for Hsl<T> {
+    #[inline]
+    fn to_hwb<U:Channel>(&self) -> Hwb<U> {
+        self.to_hsv::<f64>().to_hwb()
+    }
+}
+
+impl<T:Channel> ToHsv for Hwb<T> {
+    fn to_hsv<U:Channel>(&self) -> Hsv<U> {
+        let (w, b) = self.normalized_wb();
+        let v = 1.0 - b;
+        let s = if v == 0.0 { 0.0 } else { 1.0 - w / v };
+        Hsv::new(Deg(cast(self.h.value())), Channel::from(s), Channel::from(v))
+    }
+}
+
+impl<T:Channel> ToHsl for Hwb<T> {
+    #[inline]
+    fn to_hsl<U:Channel>(&self) -> Hsl<U> {
+        self.to_hsv::<f64>().to_hsl()
+    }
+}
+
+impl<T:Channel> ToRgb for Hwb<T> {
+    #[inline]
+    fn to_rgb<U:Channel>(&self) -> Rgb<U> {
+        self.to_hsv::<f64>().to_rgb()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {Color, Hwb, ToHwb};
+    use {Hsv, ToHsv};
+    use {Rgb, ToRgb};
+    use angle::*;
+
+    #[test]
+    fn test_rgb_to_hwb() {
+        assert_eq!(Rgb::<u8>::new(0xFF, 0xFF, 0xFF).to_hwb::<f32>(), Hwb::<f32>::new(Deg(0.0), 1.0, 0.0));
+        assert_eq!(Rgb::<u8>::new(0x00, 0x00, 0x00).to_hwb::<f32>(), Hwb::<f32>::new(Deg(0.0), 0.0, 1.0));
+        assert_eq!(Rgb::<f32>::new(1.0, 0.0, 0.0).to_hwb::<f32>(),   Hwb::<f32>::new(Deg(0.0), 0.0, 0.0));
+        assert_eq!(Rgb::<f32>::new(0.5, 1.0, 1.0).to_hwb::<f32>(),   Hwb::<f32>::new(Deg(180.0), 0.5, 0.0));
+        assert_eq!(Rgb::<f32>::new(0.0, 0.5, 0.0).to_hwb::<f32>(),   Hwb::<f32>::new(Deg(120.0), 0.0, 0.5));
+    }
+
+    #[test]
+    fn test_hwb_to_rgb() {
+        assert_eq!(Hwb::<f32>::new(Deg(0.0), 0.0, 0.0).to_rgb::<u8>(),   Rgb::<u8>::new(0xFF, 0x00, 0x00));
+        assert_eq!(Hwb::<f32>::new(Deg(240.0), 0.0, 0.0).to_rgb::<u8>(), Rgb::<u8>::new(0x00, 0x00, 0xFF));
+        assert_eq!(Hwb::<f32>::new(Deg(180.0), 0.5, 0.0).to_rgb::<f32>(), Rgb::<f32>::new(0.5, 1.0, 1.0));
+        assert_eq!(Hwb::<f32>::new(Deg(0.0), 0.5, 0.5).to_rgb::<f32>(),   Rgb::<f32>::new(0.5, 0.5, 0.5));
+        assert_eq!(Hwb::<f32>::new(Deg(0.0), 1.0, 1.0).to_rgb::<f32>(),   Rgb::<f32>::new(0.5, 0.5, 0.5));
+    }
+
+    #[test]
+    fn test_hwb_hsv() {
+        assert_eq!(Hsv::<f64>::new(Deg(90.0), 0.5, 0.5).to_hwb::<f64>(),  Hwb::<f64>::new(Deg(90.0), 0.25, 0.5));
+        assert_eq!(Hwb::<f64>::new(Deg(90.0), 0.25, 0.5).to_hsv::<f64>(), Hsv::<f64>::new(Deg(90.0), 0.5, 0.5));
+        assert_eq!(Hwb::<f64>::new(Deg(0.0), 0.0, 1.0).to_hsv::<f64>(),   Hsv::<f64>::new(Deg(0.0), 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_hwb_color() {
+        let c = Hwb::<f64>::new(Deg(30.0), 0.2, 0.1);
+        assert_eq!(c.inverse(), Hwb::new(Deg(210.0), 0.1, 0.2));
+        assert_eq!(c.inverse().to_rgb::<f32>(), Rgb::new(0.1, 0.45, 0.8));
+        let gray = Hwb::<f64>::new(Deg(0.0), 0.5, 0.5);
+        assert_eq!(gray.mix(Hwb::new(Deg(120.0), 0.0, 0.0), 0.5), Hwb::new(Deg(120.0), 0.25, 0.25));
+        assert_eq!(c.mix(Hwb::new(Deg(330.0), 0.0, 0.0), 0.5), Hwb::new(Deg(0.0), 0.1, 0.05));
+    }
+}
@@ -0,0 +1,369 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Matrix/TRC ICC profiles, the kind embedded in most PNG and JPEG files.
+//!
+//! Only RGB profiles described by three tone reproduction curves and three
+//! colorants are supported; lookup table based profiles are rejected. The
+//! profile connection space is D50, and conversions to and from sRGB adapt
+//! to D65 with Bradford, as color managed applications do. Only the
+//! relative colorimetric intent is implemented.
+//!
+//! See http://www.color.org/specification/ICC.1-2022-05.pdf
+
+use core::error;
+use core::fmt;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use math::*;
+
+use Channel;
+use {Rgb, Srgb, Xyz, WhitePoint};
+use adaptation::Adaptation;
+use matrix::{self, Matrix3};
+use xyz::{SRGB_TO_XYZ, XYZ_TO_SRGB};
+
+/// The ways in which parsing a profile can fail.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum IccError {
+    /// The data ended before a header, tag table or tag did.
+    Truncated,
+    /// The data lacks the `acsp` signature.
+    NotAProfile,
+    /// The profile is not for RGB data, or its connection space is not XYZ.
+    UnsupportedColorSpace(String),
+    /// A tag needed for a matrix/TRC profile is missing.
+    MissingTag(String),
+    /// A tag has a type that cannot be read.
+    UnsupportedTagType(String),
+}
+
+impl fmt::Display for IccError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            IccError::Truncated => write!(f, "truncated ICC profile"),
+            IccError::NotAProfile => write!(f, "not an ICC profile"),
+            IccError::UnsupportedColorSpace(ref s) => write!(f, "unsupported profile color space `{}`", s),
+            IccError::MissingTag(ref s) => write!(f, "missing `{}` tag", s),
+            IccError::UnsupportedTagType(ref s) => write!(f, "unsupported tag type `{}`", s),
+        }
+    }
+}
+
+impl error::Error for IccError {}
+
+/// A tone reproduction curve, mapping encoded device values in `[0, 1]` to
+/// linear light.
+#[derive(Clone, PartialEq, Debug)]
+pub enum ToneCurve {
+    Gamma(f64),
+    /// Evenly spaced samples, linearly interpolated.
+    Table(Vec<f64>),
+    /// An ICC parametric curve: the function type `0..=4` and its
+    /// parameters `g, a, b, c, d, e, f`, of which only the first few may be
+    /// used.
+    Parametric(u16, [f64; 7]),
+}
+
+impl ToneCurve {
+    pub fn eval(&self, x: f64) -> f64 {
+        let x = x.clamp(0.0, 1.0);
+        match *self {
+            ToneCurve::Gamma(g) => x.powf(g),
+            ToneCurve::Table(ref t) => {
+                if t.len() < 2 {
+                    return t.first().cloned().unwrap_or(x);
+                }
+                let pos = x * (t.len() - 1) as f64;
+                let i = (pos.floor() as usize).min(t.len() - 2);
+                t[i] + (t[i + 1] - t[i]) * (pos - i as f64)
+            }
+            ToneCurve::Parametric(kind, [g, a, b, c, d, e, f]) => {
+                let pow = |v: f64| v.max(0.0).powf(g);
+                match kind {
+                    0 => pow(x),
+                    1 => if x >= -b / a { pow(a * x + b) } else { 0.0 },
+                    2 => if x >= -b / a { pow(a * x + b) + c } else { c },
+                    3 => if x >= d { pow(a * x + b) } else { c * x },
+                    _ => if x >= d { pow(a * x + b) + e } else { c * x + f },
+                }
+            }
+        }
+    }
+
+    /// The encoded value giving linear light `y`. Curves are assumed to be
+    /// increasing.
+    pub fn inverse(&self, y: f64) -> f64 {
+        if let ToneCurve::Gamma(g) = *self {
+            return y.clamp(0.0, 1.0).powf(1.0 / g);
+        }
+        let (mut lo, mut hi) = (0.0, 1.0);
+        for _ in 0..48 {
+            let mid = (lo + hi) / 2.0;
+            if self.eval(mid) < y { lo = mid } else { hi = mid }
+        }
+        (lo + hi) / 2.0
+    }
+}
+
+/// A parsed matrix/TRC RGB profile.
+#[derive(Clone, PartialEq, Debug)]
+pub struct IccProfile {
+    /// The D50 XYZ colorants of the red, green and blue channels.
+    pub colorants: [[f64; 3]; 3],
+    pub curves: [ToneCurve; 3],
+    to_srgb: Matrix3,
+    from_srgb: Matrix3,
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn bytes(&self, offset: usize, len: usize) -> Result<&'a [u8], IccError> {
+        self.0.get(offset..offset.checked_add(len).ok_or(IccError::Truncated)?).ok_or(IccError::Truncated)
+    }
+
+    fn u16(&self, offset: usize) -> Result<u16, IccError> {
+        let b = self.bytes(offset, 2)?;
+        Ok(u16::from_be_bytes([b[0], b[1]]))
+    }
+
+    fn u32(&self, offset: usize) -> Result<u32, IccError> {
+        let b = self.bytes(offset, 4)?;
+        Ok(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn s15_16(&self, offset: usize) -> Result<f64, IccError> {
+        Ok(self.u32(offset)? as i32 as f64 / 65536.0)
+    }
+
+    fn signature(&self, offset: usize) -> Result<String, IccError> {
+        Ok(String::from_utf8_lossy(self.bytes(offset, 4)?).into_owned())
+    }
+}
+
+impl IccProfile {
+    /// Parses a profile from its raw bytes.
+    pub fn parse(data: &[u8]) -> Result<IccProfile, IccError> {
+        let r = Reader(data);
+        if r.bytes(0, 132).is_err() {
+            return Err(IccError::Truncated);
+        }
+        if r.bytes(36, 4)? != b"acsp" {
+            return Err(IccError::NotAProfile);
+        }
+        for &(offset, expected) in [(16, "RGB "), (20, "XYZ ")].iter() {
+            let sig = r.signature(offset)?;
+            if sig != expected {
+                return Err(IccError::UnsupportedColorSpace(sig.trim().to_string()));
+            }
+        }
+
+        let count = r.u32(128)? as usize;
+        let find = |name: &str| -> Result<(usize, usize), IccError> {
+            for i in 0..count {
+                let entry = 132 + 12 * i;
+                if r.bytes(entry, 4)? == name.as_bytes() {
+                    return Ok((r.u32(entry + 4)? as usize, r.u32(entry + 8)? as usize));
+                }
+            }
+            Err(IccError::MissingTag(name.to_string()))
+        };
+
+        let xyz = |name: &str| -> Result<[f64; 3], IccError> {
+            let (offset, _) = find(name)?;
+            let kind = r.signature(offset)?;
+            if kind != "XYZ " {
+                return Err(IccError::UnsupportedTagType(kind));
+            }
+            Ok([r.s15_16(offset + 8)?, r.s15_16(offset + 12)?, r.s15_16(offset + 16)?])
+        };
+        let curve = |name: &str| -> Result<ToneCurve, IccError> {
+            let (offset, _) = find(name)?;
+            let kind = r.signature(offset)?;
+            match &*kind {
+                "curv" => {
+                    let n = r.u32(offset + 8)? as usize;
+                    match n {
+                        0 => Ok(ToneCurve::Gamma(1.0)),
+                        1 => Ok(ToneCurve::Gamma(r.u16(offset + 12)? as f64 / 256.0)),
+                        _ => (0..n).map(|i| r.u16(offset + 12 + 2 * i).map(|v| v as f64 / 65535.0))
+                                   .collect::<Result<Vec<_>, _>>()
+                                   .map(ToneCurve::Table),
+                    }
+                }
+                "para" => {
+                    let function = r.u16(offset + 8)?;
+                    let n = match function {
+                        0 => 1,
+                        1 => 3,
+                        2 => 4,
+                        3 => 5,
+                        4 => 7,
+                        _ => return Err(IccError::UnsupportedTagType(format!("para type {}", function))),
+                    };
+                    let mut params = [0.0; 7];
+                    for (i, p) in params.iter_mut().enumerate().take(n) {
+                        *p = r.s15_16(offset + 12 + 4 * i)?;
+                    }
+                    Ok(ToneCurve::Parametric(function, params))
+                }
+                _ => Err(IccError::UnsupportedTagType(kind)),
+            }
+        };
+
+        let (red, green, blue) = (xyz("rXYZ")?, xyz("gXYZ")?, xyz("bXYZ")?);
+        let colorants = [red, green, blue];
+        let curves = [curve("rTRC")?, curve("gTRC")?, curve("bTRC")?];
+        Ok(IccProfile::new(colorants, curves))
+    }
+
+    /// Builds a profile from D50 colorants and curves.
+    pub fn new(colorants: [[f64; 3]; 3], curves: [ToneCurve; 3]) -> IccProfile {
+        let [r, g, b] = colorants;
+        let to_xyz = [[r[0], g[0], b[0]], [r[1], g[1], b[1]], [r[2], g[2], b[2]]];
+        let adapt = Adaptation::Bradford.matrix_unchecked(WhitePoint::D50, WhitePoint::D65);
+        let to_srgb = matrix::mul(&XYZ_TO_SRGB, &matrix::mul(&adapt, &to_xyz));
+        let from_srgb = matrix::inverse(&to_srgb).unwrap_or([[0.0; 3]; 3]);
+        IccProfile { colorants, curves, to_srgb, from_srgb }
+    }
+
+    fn linearize<T: Channel>(&self, device: &Rgb<T>) -> [f64; 3] {
+        [self.curves[0].eval(device.r.to_channel_f64()),
+         self.curves[1].eval(device.g.to_channel_f64()),
+         self.curves[2].eval(device.b.to_channel_f64())]
+    }
+
+    /// Converts device values to D65 XYZ.
+    pub fn to_xyz<T: Channel>(&self, device: &Rgb<T>) -> Xyz<f64> {
+        Xyz::from_array(matrix::mul_vec(&matrix::mul(&SRGB_TO_XYZ, &self.to_srgb), self.linearize(device)))
+    }
+
+    /// Converts device values to sRGB. Colors outside the sRGB gamut are
+    /// clipped.
+    pub fn to_srgb<T: Channel, U: Channel>(&self, device: &Rgb<T>) -> Srgb<U> {
+        let linear = matrix::mul_vec(&self.to_srgb, self.linearize(device));
+        let c = |v: f64| v.clamp(0.0, 1.0);
+        Srgb::from_linear(&Rgb::<f64>::new(c(linear[0]), c(linear[1]), c(linear[2])))
+    }
+
+    /// Converts sRGB to device values. Colors outside the profile's gamut
+    /// are clipped.
+    pub fn from_srgb<T: Channel, U: Channel>(&self, color: &Srgb<T>) -> Rgb<U> {
+        let linear = color.to_linear::<f64>();
+        let v = matrix::mul_vec(&self.from_srgb, [linear.r, linear.g, linear.b]);
+        Rgb::new(Channel::from(self.curves[0].inverse(v[0])),
+                 Channel::from(self.curves[1].inverse(v[1])),
+                 Channel::from(self.curves[2].inverse(v[2])))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use {Rgb, Srgb};
+
+    const SRGB_COLORANTS: [[f64; 3]; 3] = [[0.4360747, 0.2225045, 0.0139322],
+                                           [0.3850649, 0.7168786, 0.0971045],
+                                           [0.1430804, 0.0606169, 0.7141733]];
+
+    fn s15_16(v: f64) -> [u8; 4] {
+        ((v * 65536.0).round() as i32).to_be_bytes()
+    }
+
+    /// Serializes a minimal matrix/TRC profile with a shared curve tag.
+    fn profile(curve: &[u8]) -> Vec<u8> {
+        let mut tags: Vec<(&[u8], Vec<u8>)> = Vec::new();
+        for (name, c) in [b"rXYZ", b"gXYZ", b"bXYZ"].iter().zip(SRGB_COLORANTS.iter()) {
+            let mut data = b"XYZ \0\0\0\0".to_vec();
+            for &v in c.iter() {
+                data.extend_from_slice(&s15_16(v));
+            }
+            tags.push((&name[..], data));
+        }
+        for name in [b"rTRC", b"gTRC", b"bTRC"].iter() {
+            tags.push((&name[..], curve.to_vec()));
+        }
+        let mut header = vec![0u8; 128];
+        header[16..20].copy_from_slice(b"RGB ");
+        header[20..24].copy_from_slice(b"XYZ ");
+        header[36..40].copy_from_slice(b"acsp");
+        let mut out = header;
+        out.extend_from_slice(&(tags.len() as u32).to_be_bytes());
+        let mut offset = 132 + 12 * tags.len();
+        for &(name, ref data) in &tags {
+            out.extend_from_slice(name);
+            out.extend_from_slice(&(offset as u32).to_be_bytes());
+            out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+            offset += data.len();
+        }
+        for (_, data) in tags {
+            out.extend_from_slice(&data);
+        }
+        out
+    }
+
+    fn srgb_curve() -> Vec<u8> {
+        let mut c = b"para\0\0\0\0\0\x03\0\0".to_vec();
+        for &v in [2.4, 1.0 / 1.055, 0.055 / 1.055, 1.0 / 12.92, 0.04045].iter() {
+            c.extend_from_slice(&s15_16(v));
+        }
+        c
+    }
+
+    #[test]
+    fn test_srgb_profile() {
+        let p = IccProfile::parse(&profile(&srgb_curve())).unwrap();
+        assert!(matches!(p.curves[0], ToneCurve::Parametric(3, _)));
+        for &c in [(1.0, 0.0, 0.0), (0.0, 1.0, 0.0), (0.2, 0.4, 0.6), (1.0, 1.0, 1.0)].iter() {
+            let out: Srgb<f64> = p.to_srgb(&Rgb::new(c.0, c.1, c.2));
+            assert!((out.r - c.0).abs() < 2e-3 && (out.g - c.1).abs() < 2e-3 && (out.b - c.2).abs() < 2e-3,
+                    "{:?} -> {:?}", c, out);
+            let back: Rgb<f64> = p.from_srgb(&out);
+            assert!((back.r - c.0).abs() < 2e-3 && (back.g - c.1).abs() < 2e-3 && (back.b - c.2).abs() < 2e-3);
+        }
+        let white = p.to_xyz(&Rgb::new(1.0, 1.0, 1.0));
+        assert!((white.y - 1.0).abs() < 1e-4 && (white.x - 0.95047).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_curves() {
+        // gamma 2.2 as a u8Fixed8.8 value
+        let p = IccProfile::parse(&profile(b"curv\0\0\0\0\0\0\0\x01\x02\x33")).unwrap();
+        assert_eq!(p.curves[0], ToneCurve::Gamma(2.19921875));
+        let table = ToneCurve::Table(vec![0.0, 0.25, 1.0]);
+        assert_eq!(table.eval(0.25), 0.125);
+        assert!((table.inverse(0.625) - 0.75).abs() < 1e-9);
+        let p = IccProfile::parse(&profile(b"curv\0\0\0\0\0\0\0\x03\0\0\x40\0\xFF\xFF")).unwrap();
+        assert!(matches!(p.curves[2], ToneCurve::Table(ref t) if t.len() == 3));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(IccProfile::parse(&[0; 10]), Err(IccError::Truncated));
+        let mut data = profile(&srgb_curve());
+        data[36] = b'x';
+        assert_eq!(IccProfile::parse(&data), Err(IccError::NotAProfile));
+        let mut data = profile(&srgb_curve());
+        data[16..20].copy_from_slice(b"CMYK");
+        assert_eq!(IccProfile::parse(&data), Err(IccError::UnsupportedColorSpace("CMYK".to_string())));
+        assert_eq!(IccProfile::parse(&profile(b"mft2\0\0\0\0")), Err(IccError::UnsupportedTagType("mft2".to_string())));
+        let mut data = profile(&srgb_curve());
+        data[132..136].copy_from_slice(b"wtpt");
+        assert_eq!(IccProfile::parse(&data), Err(IccError::MissingTag("rXYZ".to_string())));
+    }
+}
@@ -0,0 +1,86 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Interoperability with `iced` colors.
+//!
+//! `iced::Color` comes from the `iced_core` crate, which `iced` re-exports,
+//! so this feature only depends on that. Its components are gamma-encoded
+//! sRGB floats with straight alpha, so it converts to and from `Srgba<f32>`
+//! as is. `Srgba<u8>` converts through the byte constructors of `iced`.
+
+use iced_core::Color;
+
+use {Srgb, Srgba};
+
+impl From<Color> for Srgba<f32> {
+    #[inline]
+    fn from(c: Color) -> Srgba<f32> {
+        Srgba { c: Srgb::new(c.r, c.g, c.b), a: c.a }
+    }
+}
+
+impl From<Srgba<f32>> for Color {
+    #[inline]
+    fn from(c: Srgba<f32>) -> Color {
+        Color::from_rgba(c.c.r, c.c.g, c.c.b, c.a)
+    }
+}
+
+impl From<Srgb<f32>> for Color {
+    #[inline]
+    fn from(c: Srgb<f32>) -> Color {
+        Color::from_rgb(c.r, c.g, c.b)
+    }
+}
+
+impl From<Color> for Srgba<u8> {
+    #[inline]
+    fn from(c: Color) -> Srgba<u8> {
+        let [r, g, b, a] = c.into_rgba8();
+        Srgba { c: Srgb::new(r, g, b), a }
+    }
+}
+
+impl From<Srgba<u8>> for Color {
+    #[inline]
+    fn from(c: Srgba<u8>) -> Color {
+        Color::from_rgba8(c.c.r, c.c.g, c.c.b, c.a as f32 / 255.0)
+    }
+}
+
+impl From<Srgb<u8>> for Color {
+    #[inline]
+    fn from(c: Srgb<u8>) -> Color {
+        Color::from_rgb8(c.r, c.g, c.b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use iced_core::Color;
+
+    use {Srgb, Srgba};
+
+    #[test]
+    fn test_color() {
+        let c = Srgba::from_color(Srgb::new(1.0f32, 0.5, 0.25), 0.5);
+        assert_eq!(Color::from(c), Color::from_rgba(1.0, 0.5, 0.25, 0.5));
+        assert_eq!(Srgba::<f32>::from(Color::from(c)), c);
+        assert_eq!(Color::from(Srgb::new(0.1f32, 0.2, 0.3)), Color::from_rgb(0.1, 0.2, 0.3));
+        let c = Srgba::from_color(Srgb::new(0x10u8, 0x20, 0x30), 0x40);
+        assert_eq!(Srgba::<u8>::from(Color::from(c)), c);
+        assert_eq!(Color::from(Srgb::new(0xFFu8, 0x00, 0x00)), Color::from_rgb(1.0, 0.0, 0.0));
+    }
+}
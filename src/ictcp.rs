@@ -0,0 +1,147 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! ICtCp with the PQ transfer function, as specified by ITU-R BT.2100.
+//!
+//! Colors are taken through linear Rec. 2020 RGB, and relative luminance
+//! `1` is placed at `hdr::REFERENCE_WHITE`.
+
+use {Channel, FloatChannel};
+use {Rgb, ToRgb, Srgb, Xyz, ToXyz, WhitePoint};
+use hdr::{self, REFERENCE_WHITE, PEAK_LUMINANCE};
+use matrix::{self, Matrix3};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[repr(C)]
+pub struct ICtCp<T> { pub i: T, pub ct: T, pub cp: T }
+
+impl<T> ICtCp<T> {
+    #[inline]
+    pub const fn new(i: T, ct: T, cp: T) -> ICtCp<T> {
+        ICtCp { i, ct, cp }
+    }
+}
+
+const REC2020_PRIMARIES: [(f64, f64); 3] = [(0.708, 0.292), (0.170, 0.797), (0.131, 0.046)];
+
+const RGB_TO_LMS: Matrix3 = [[1688.0 / 4096.0, 2146.0 / 4096.0,  262.0 / 4096.0],
+                             [ 683.0 / 4096.0, 2951.0 / 4096.0,  462.0 / 4096.0],
+                             [  99.0 / 4096.0,  309.0 / 4096.0, 3688.0 / 4096.0]];
+const LMS_TO_ICTCP: Matrix3 = [[   2048.0 / 4096.0,   2048.0 / 4096.0,     0.0],
+                               [   6610.0 / 4096.0, -13613.0 / 4096.0,  7003.0 / 4096.0],
+                               [  17933.0 / 4096.0, -17390.0 / 4096.0,  -543.0 / 4096.0]];
+
+const REC2020_TO_XYZ: Matrix3 = match matrix::rgb_to_xyz(REC2020_PRIMARIES, WhitePoint::D65.to_array()) {
+    Some(m) => m,
+    None => panic!("degenerate Rec. 2020 primaries"),
+};
+const XYZ_TO_REC2020: Matrix3 = match matrix::inverse(&REC2020_TO_XYZ) {
+    Some(m) => m,
+    None => panic!("degenerate Rec. 2020 primaries"),
+};
+
+impl<T: FloatChannel> ICtCp<T> {
+    /// Converts from linear Rec. 2020 RGB, relative to the reference white.
+    pub fn from_rec2020<C: Channel>(rgb: &Rgb<C>) -> ICtCp<T> {
+        let scale = REFERENCE_WHITE / PEAK_LUMINANCE;
+        let rgb = [rgb.r.to_channel_f64() * scale, rgb.g.to_channel_f64() * scale, rgb.b.to_channel_f64() * scale];
+        let lms = matrix::mul_vec(&RGB_TO_LMS, rgb);
+        let lms = [hdr::encode_with(lms[0], hdr::M2), hdr::encode_with(lms[1], hdr::M2),
+                   hdr::encode_with(lms[2], hdr::M2)];
+        let [i, ct, cp] = matrix::mul_vec(&LMS_TO_ICTCP, lms);
+        ICtCp::new(Channel::from(i), Channel::from(ct), Channel::from(cp))
+    }
+
+    /// Converts to linear Rec. 2020 RGB, relative to the reference white.
+    pub fn to_rec2020<U: Channel>(&self) -> Rgb<U> {
+        let lms = matrix::mul_vec(&matrix::inverse(&LMS_TO_ICTCP).unwrap(),
+                                  [self.i.to_channel_f64(), self.ct.to_channel_f64(), self.cp.to_channel_f64()]);
+        let lms = [hdr::decode_with(lms[0], hdr::M2), hdr::decode_with(lms[1], hdr::M2),
+                   hdr::decode_with(lms[2], hdr::M2)];
+        let rgb = matrix::mul_vec(&matrix::inverse(&RGB_TO_LMS).unwrap(), lms);
+        let scale = PEAK_LUMINANCE / REFERENCE_WHITE;
+        Rgb::new(Channel::from(rgb[0] * scale), Channel::from(rgb[1] * scale), Channel::from(rgb[2] * scale))
+    }
+}
+
+pub trait ToICtCp {
+    fn to_ictcp<U:FloatChannel>(&self) -> ICtCp<U>;
+}
+
+impl<T:FloatChannel> ToICtCp for ICtCp<T> {
+    #[inline]
+    fn to_ictcp<U:FloatChannel>(&self) -> ICtCp<U> {
+        ICtCp::new(self.i.to_channel(), self.ct.to_channel(), self.cp.to_channel())
+    }
+}
+
+impl<T:FloatChannel> ToICtCp for Xyz<T> {
+    #[inline]
+    fn to_ictcp<U:FloatChannel>(&self) -> ICtCp<U> {
+        let rgb = matrix::mul_vec(&XYZ_TO_REC2020, self.to_array());
+        ICtCp::from_rec2020(&Rgb::new(rgb[0], rgb[1], rgb[2]))
+    }
+}
+
+impl<T:Channel> ToICtCp for Rgb<T> {
+    #[inline]
+    fn to_ictcp<U:FloatChannel>(&self) -> ICtCp<U> {
+        self.to_xyz::<f64>().to_ictcp()
+    }
+}
+
+impl<T:Channel> ToICtCp for Srgb<T> {
+    #[inline]
+    fn to_ictcp<U:FloatChannel>(&self) -> ICtCp<U> {
+        self.to_xyz::<f64>().to_ictcp()
+    }
+}
+
+impl<T:FloatChannel> ToXyz for ICtCp<T> {
+    #[inline]
+    fn to_xyz<U:FloatChannel>(&self) -> Xyz<U> {
+        let rgb = self.to_rec2020::<f64>();
+        Xyz::from_array(matrix::mul_vec(&REC2020_TO_XYZ, [rgb.r, rgb.g, rgb.b]))
+    }
+}
+
+impl<T:FloatChannel> ToRgb for ICtCp<T> {
+    #[inline]
+    fn to_rgb<U:Channel>(&self) -> Rgb<U> {
+        self.to_xyz::<f64>().to_rgb()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {ICtCp, ToICtCp, Rgb, Srgb, Xyz, ToXyz};
+    use hdr::{pq_encode, REFERENCE_WHITE};
+
+    #[test]
+    fn test_ictcp() {
+        // neutrals have no chroma, and I is the PQ signal of the luminance
+        for &y in [0.0, 0.5, 1.0, 10.0].iter() {
+            let c: ICtCp<f64> = ICtCp::from_rec2020(&Rgb::new(y, y, y));
+            assert!(c.ct.abs() < 1e-12 && c.cp.abs() < 1e-12, "{:?}", c);
+            assert!((c.i - pq_encode(y * REFERENCE_WHITE)).abs() < 1e-9);
+        }
+        for &c in [(0.8, 0.3, 0.1), (0.1, 0.6, 0.9), (0.5, 0.5, 0.5)].iter() {
+            let xyz: Xyz<f64> = Srgb::new(c.0, c.1, c.2).to_xyz();
+            let back: Xyz<f64> = xyz.to_ictcp::<f64>().to_xyz();
+            assert!((back.x - xyz.x).abs() < 1e-9 && (back.y - xyz.y).abs() < 1e-9 && (back.z - xyz.z).abs() < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,171 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Interoperability with the `image` crate.
+//!
+//! The pixel types convert to and from `Rgb`/`Rgba` and `Srgb`/`Srgba`, and
+//! implement `ToRgb`/`ToRgba` so they work with `FromColor` and the `batch`
+//! functions. Image buffers can be viewed as slices of this crate's colors
+//! without copying.
+
+use core::slice;
+use alloc::vec::Vec;
+
+use image::{self, ImageBuffer, Pixel, Primitive};
+
+use {Channel, AlphaColor, Rgb, ToRgb, Rgba, ToRgba, Srgb, Srgba};
+
+impl<T: Channel + Primitive> From<image::Rgb<T>> for Rgb<T> {
+    #[inline]
+    fn from(p: image::Rgb<T>) -> Rgb<T> {
+        Rgb::new(p.0[0], p.0[1], p.0[2])
+    }
+}
+
+impl<T: Channel + Primitive> From<Rgb<T>> for image::Rgb<T> {
+    #[inline]
+    fn from(c: Rgb<T>) -> image::Rgb<T> {
+        image::Rgb([c.r, c.g, c.b])
+    }
+}
+
+impl<T: Channel + Primitive> From<image::Rgba<T>> for Rgba<T> {
+    #[inline]
+    fn from(p: image::Rgba<T>) -> Rgba<T> {
+        Rgba::new(p.0[0], p.0[1], p.0[2], p.0[3])
+    }
+}
+
+impl<T: Channel + Primitive> From<Rgba<T>> for image::Rgba<T> {
+    #[inline]
+    fn from(c: Rgba<T>) -> image::Rgba<T> {
+        image::Rgba([c.c.r, c.c.g, c.c.b, c.a])
+    }
+}
+
+impl<T: Channel + Primitive> From<image::Rgb<T>> for Srgb<T> {
+    #[inline]
+    fn from(p: image::Rgb<T>) -> Srgb<T> {
+        Srgb::new(p.0[0], p.0[1], p.0[2])
+    }
+}
+
+impl<T: Channel + Primitive> From<Srgb<T>> for image::Rgb<T> {
+    #[inline]
+    fn from(c: Srgb<T>) -> image::Rgb<T> {
+        image::Rgb([c.r, c.g, c.b])
+    }
+}
+
+impl<T: Channel + Primitive> From<image::Rgba<T>> for Srgba<T> {
+    #[inline]
+    fn from(p: image::Rgba<T>) -> Srgba<T> {
+        AlphaColor { c: Srgb::new(p.0[0], p.0[1], p.0[2]), a: p.0[3] }
+    }
+}
+
+impl<T: Channel + Primitive> From<Srgba<T>> for image::Rgba<T> {
+    #[inline]
+    fn from(c: Srgba<T>) -> image::Rgba<T> {
+        image::Rgba([c.c.r, c.c.g, c.c.b, c.a])
+    }
+}
+
+impl<T: Channel + Primitive> ToRgb for image::Rgb<T> {
+    #[inline]
+    fn to_rgb<U: Channel>(&self) -> Rgb<U> {
+        Rgb::<T>::from(*self).to_rgb()
+    }
+}
+
+impl<T: Channel + Primitive> ToRgba for image::Rgba<T> {
+    #[inline]
+    fn to_rgba<U: Channel>(&self) -> Rgba<U> {
+        Rgba::from(*self).to_rgba()
+    }
+}
+
+// `image::Rgb<T>` and `image::Rgba<T>` are `#[repr(C)]` arrays of `T`, and
+// `Rgb<T>` and `Rgba<T>` are `#[repr(C)]` structs of the same number of `T`
+// fields, so the layouts match and a buffer's samples can be reinterpreted.
+
+/// Views the pixels of an RGB image as a slice of `Rgb`.
+pub fn as_rgb_slice<T>(buffer: &ImageBuffer<image::Rgb<T>, Vec<T>>) -> &[Rgb<T>]
+    where T: Channel + Primitive, image::Rgb<T>: Pixel<Subpixel = T>
+{
+    let samples: &[T] = buffer;
+    unsafe { slice::from_raw_parts(samples.as_ptr() as *const Rgb<T>, samples.len() / 3) }
+}
+
+/// Views the pixels of an RGB image as a mutable slice of `Rgb`.
+pub fn as_rgb_slice_mut<T>(buffer: &mut ImageBuffer<image::Rgb<T>, Vec<T>>) -> &mut [Rgb<T>]
+    where T: Channel + Primitive, image::Rgb<T>: Pixel<Subpixel = T>
+{
+    let samples: &mut [T] = buffer;
+    unsafe { slice::from_raw_parts_mut(samples.as_mut_ptr() as *mut Rgb<T>, samples.len() / 3) }
+}
+
+/// Views the pixels of an RGBA image as a slice of `Rgba`.
+pub fn as_rgba_slice<T>(buffer: &ImageBuffer<image::Rgba<T>, Vec<T>>) -> &[Rgba<T>]
+    where T: Channel + Primitive, image::Rgba<T>: Pixel<Subpixel = T>
+{
+    let samples: &[T] = buffer;
+    unsafe { slice::from_raw_parts(samples.as_ptr() as *const Rgba<T>, samples.len() / 4) }
+}
+
+/// Views the pixels of an RGBA image as a mutable slice of `Rgba`.
+pub fn as_rgba_slice_mut<T>(buffer: &mut ImageBuffer<image::Rgba<T>, Vec<T>>) -> &mut [Rgba<T>]
+    where T: Channel + Primitive, image::Rgba<T>: Pixel<Subpixel = T>
+{
+    let samples: &mut [T] = buffer;
+    unsafe { slice::from_raw_parts_mut(samples.as_mut_ptr() as *mut Rgba<T>, samples.len() / 4) }
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{self, ImageBuffer};
+
+    use super::*;
+    use {Rgb, Rgba, Srgb, FromColor, Hsv};
+    use batch::convert_vec;
+
+    #[test]
+    fn test_pixel_conversions() {
+        let p = image::Rgb([0x10u8, 0x20, 0x30]);
+        assert_eq!(Rgb::<u8>::from(p), Rgb::new(0x10, 0x20, 0x30));
+        assert_eq!(image::Rgb::from(Srgb::new(0x10u8, 0x20, 0x30)), p);
+        let q = image::Rgba([0x10u8, 0x20, 0x30, 0x40]);
+        assert_eq!(image::Rgba::from(Rgba::from(q)), q);
+        assert_eq!(Rgb::<f32>::from_color(&p), Rgb::new(0x10 as f32 / 255.0, 0x20 as f32 / 255.0, 0x30 as f32 / 255.0));
+    }
+
+    #[test]
+    fn test_buffer_views() {
+        let mut img = ImageBuffer::from_pixel(3, 2, image::Rgb([0xFFu8, 0x00, 0x00]));
+        img.put_pixel(1, 0, image::Rgb([0x00, 0xFF, 0x00]));
+        assert_eq!(as_rgb_slice(&img).len(), 6);
+        assert_eq!(as_rgb_slice(&img)[1], Rgb::new(0x00, 0xFF, 0x00));
+        let hsv: Vec<Hsv<f32>> = convert_vec(as_rgb_slice(&img));
+        assert_eq!(hsv[1].h.0, 120.0);
+
+        as_rgb_slice_mut(&mut img)[5] = Rgb::new(1, 2, 3);
+        assert_eq!(*img.get_pixel(2, 1), image::Rgb([1, 2, 3]));
+
+        let mut rgba = ImageBuffer::from_pixel(2, 2, image::Rgba([0u8, 0, 0, 0xFF]));
+        as_rgba_slice_mut(&mut rgba)[0].a = 0x80;
+        assert_eq!(rgba.get_pixel(0, 0).0[3], 0x80);
+        assert_eq!(as_rgba_slice(&rgba)[3], Rgba::new(0, 0, 0, 0xFF));
+    }
+}
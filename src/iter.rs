@@ -0,0 +1,311 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Lazy conversions over iterators of colors.
+//!
+//! With `ColorIterator` in scope, iterators of colors, or of references to
+//! them as from `pixels.iter()`, convert one color at a time as they are
+//! consumed, so pipelines over large buffers need no intermediate vectors,
+//! as in `pixels.iter().to_linear::<f32>().blend_with(overlay.iter(),
+//! BlendMode::Multiply).to_srgb::<u8>()`.
+//!
+//! For conversions between whole slices, see `batch`.
+
+use core::iter::Zip;
+use core::marker::PhantomData;
+
+use {AlphaColor, Channel, FromColor, Premultiplied};
+use {Rgb, Rg, Srgb, Hsv, Hsl, Hwb, Hsi, Hsp, Ryb, Cmyk, Luma, YCbCr};
+use {Xyz, XyY, Lab, Lch, Luv, Lchuv, Oklab, Oklch, Hsluv, Hpluv, Cam16, Cam16Ucs, Jzazbz, ICtCp};
+use blend::{Blend, BlendMode};
+use packed::{Rgb565, Rgba4444, Rgba5551, Rgb10a2};
+use gpu::{RgbF32Std140, RgbaF32Std140};
+
+/// An item of an iterator of colors: a color, or a reference to one.
+pub trait ColorItem {
+    type Color: Copy;
+
+    fn color(self) -> Self::Color;
+}
+
+impl<C: Copy> ColorItem for &C {
+    type Color = C;
+
+    #[inline]
+    fn color(self) -> C {
+        *self
+    }
+}
+
+impl<C: Copy> ColorItem for &mut C {
+    type Color = C;
+
+    #[inline]
+    fn color(self) -> C {
+        *self
+    }
+}
+
+macro_rules! color_items {
+    ($($name:ident),*) => {
+        $(
+            impl<T: Channel> ColorItem for $name<T> {
+                type Color = $name<T>;
+
+                #[inline]
+                fn color(self) -> $name<T> {
+                    self
+                }
+            }
+        )*
+    }
+}
+
+color_items! {
+    Rgb, Rg, Srgb, Hsv, Hsl, Hwb, Hsi, Hsp, Ryb, Cmyk, Luma, YCbCr,
+    Xyz, XyY, Lab, Lch, Luv, Lchuv, Oklab, Oklch, Hsluv, Hpluv, Cam16, Cam16Ucs, Jzazbz, ICtCp
+}
+
+macro_rules! packed_items {
+    ($($name:ident),*) => {
+        $(
+            impl ColorItem for $name {
+                type Color = $name;
+
+                #[inline]
+                fn color(self) -> $name {
+                    self
+                }
+            }
+        )*
+    }
+}
+
+packed_items! { Rgb565, Rgba4444, Rgba5551, Rgb10a2, RgbF32Std140, RgbaF32Std140 }
+
+impl<T: Copy, C: Copy> ColorItem for AlphaColor<T, C> {
+    type Color = AlphaColor<T, C>;
+
+    #[inline]
+    fn color(self) -> AlphaColor<T, C> {
+        self
+    }
+}
+
+impl<T: Copy, C: Copy> ColorItem for Premultiplied<T, C> {
+    type Color = Premultiplied<T, C>;
+
+    #[inline]
+    fn color(self) -> Premultiplied<T, C> {
+        self
+    }
+}
+
+/// Colors that are gamma-encoded, which `ColorIterator::to_linear` decodes.
+pub trait Encoded {
+    fn decode<U: Channel>(&self) -> Rgb<U>;
+}
+
+impl<T: Channel> Encoded for Srgb<T> {
+    #[inline]
+    fn decode<U: Channel>(&self) -> Rgb<U> {
+        self.to_linear()
+    }
+}
+
+/// Lazy conversions of the colors of an iterator. This is implemented for
+/// every iterator, and the methods are available when the items are colors
+/// or references to colors.
+pub trait ColorIterator: Iterator + Sized {
+    /// Converts each color to `D`.
+    #[inline]
+    fn convert<D>(self) -> Convert<Self, D>
+        where Self::Item: ColorItem, D: FromColor<<Self::Item as ColorItem>::Color>
+    {
+        Convert { iter: self, to: PhantomData }
+    }
+
+    /// Converts each color to `Rgb<U>`, which decodes `Srgb` colors.
+    #[inline]
+    fn to_rgb<U: Channel>(self) -> Convert<Self, Rgb<U>>
+        where Self::Item: ColorItem, Rgb<U>: FromColor<<Self::Item as ColorItem>::Color>
+    {
+        self.convert()
+    }
+
+    /// Converts each color to `Srgb<U>`, which encodes linear colors.
+    #[inline]
+    fn to_srgb<U: Channel>(self) -> Convert<Self, Srgb<U>>
+        where Self::Item: ColorItem, Srgb<U>: FromColor<<Self::Item as ColorItem>::Color>
+    {
+        self.convert()
+    }
+
+    /// Decodes each gamma-encoded color to linear light.
+    #[inline]
+    fn to_linear<U: Channel>(self) -> ToLinear<Self, U>
+        where Self::Item: ColorItem, <Self::Item as ColorItem>::Color: Encoded
+    {
+        ToLinear { iter: self, to: PhantomData }
+    }
+
+    /// Blends each color of `source` on top of the matching color of
+    /// `self`, stopping at the end of the shorter.
+    #[inline]
+    fn blend_with<J>(self, source: J, mode: BlendMode) -> BlendWith<Self, J::IntoIter>
+        where Self::Item: ColorItem, <Self::Item as ColorItem>::Color: Blend,
+              J: IntoIterator, J::Item: ColorItem<Color = <Self::Item as ColorItem>::Color>
+    {
+        BlendWith { iter: self.zip(source), mode }
+    }
+}
+
+impl<I: Iterator> ColorIterator for I {}
+
+/// The iterator of `ColorIterator::convert`, `to_rgb` and `to_srgb`.
+#[derive(Clone, Debug)]
+pub struct Convert<I, D> {
+    iter: I,
+    to: PhantomData<fn() -> D>,
+}
+
+impl<I, D> Iterator for Convert<I, D>
+    where I: Iterator, I::Item: ColorItem, D: FromColor<<I::Item as ColorItem>::Color>
+{
+    type Item = D;
+
+    #[inline]
+    fn next(&mut self) -> Option<D> {
+        self.iter.next().map(|c| D::from_color(&c.color()))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I, D> DoubleEndedIterator for Convert<I, D>
+    where I: DoubleEndedIterator, I::Item: ColorItem, D: FromColor<<I::Item as ColorItem>::Color>
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<D> {
+        self.iter.next_back().map(|c| D::from_color(&c.color()))
+    }
+}
+
+impl<I, D> ExactSizeIterator for Convert<I, D>
+    where I: ExactSizeIterator, I::Item: ColorItem, D: FromColor<<I::Item as ColorItem>::Color> {}
+
+/// The iterator of `ColorIterator::to_linear`.
+#[derive(Clone, Debug)]
+pub struct ToLinear<I, U> {
+    iter: I,
+    to: PhantomData<fn() -> U>,
+}
+
+impl<I, U: Channel> Iterator for ToLinear<I, U>
+    where I: Iterator, I::Item: ColorItem, <I::Item as ColorItem>::Color: Encoded
+{
+    type Item = Rgb<U>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Rgb<U>> {
+        self.iter.next().map(|c| c.color().decode())
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I, U: Channel> DoubleEndedIterator for ToLinear<I, U>
+    where I: DoubleEndedIterator, I::Item: ColorItem, <I::Item as ColorItem>::Color: Encoded
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Rgb<U>> {
+        self.iter.next_back().map(|c| c.color().decode())
+    }
+}
+
+impl<I, U: Channel> ExactSizeIterator for ToLinear<I, U>
+    where I: ExactSizeIterator, I::Item: ColorItem, <I::Item as ColorItem>::Color: Encoded {}
+
+/// The iterator of `ColorIterator::blend_with`.
+#[derive(Clone, Debug)]
+pub struct BlendWith<I, J> {
+    iter: Zip<I, J>,
+    mode: BlendMode,
+}
+
+impl<I, J> Iterator for BlendWith<I, J>
+    where I: Iterator, I::Item: ColorItem, <I::Item as ColorItem>::Color: Blend,
+          J: Iterator, J::Item: ColorItem<Color = <I::Item as ColorItem>::Color>
+{
+    type Item = <I::Item as ColorItem>::Color;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let mode = self.mode;
+        self.iter.next().map(|(b, s)| b.color().blend(s.color(), mode))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I, J> ExactSizeIterator for BlendWith<I, J>
+    where I: ExactSizeIterator, I::Item: ColorItem, <I::Item as ColorItem>::Color: Blend,
+          J: ExactSizeIterator, J::Item: ColorItem<Color = <I::Item as ColorItem>::Color> {}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use angle::Deg;
+
+    use super::ColorIterator;
+    use {Rgb, Srgb, Hsv, ToRgb, ToHsv, ToSrgb};
+    use blend::{Blend, BlendMode};
+
+    #[test]
+    fn test_convert() {
+        let pixels = [Srgb::<u8>::new(0xFF, 0x80, 0x00), Srgb::new(0x00, 0x33, 0x66), Srgb::new(0, 0, 0)];
+        let linear: Vec<Rgb<f32>> = pixels.iter().to_linear().collect();
+        assert_eq!(linear, pixels.iter().map(|c| c.to_rgb()).collect::<Vec<Rgb<f32>>>());
+        assert_eq!(pixels.iter().to_rgb::<f32>().collect::<Vec<_>>(), linear);
+
+        let back: Vec<Srgb<u8>> = linear.iter().to_srgb().collect();
+        assert_eq!(back, linear.iter().map(|c| c.to_srgb()).collect::<Vec<Srgb<u8>>>());
+
+        let hsv: Vec<Hsv<f64>> = back.iter().to_rgb::<u8>().convert().collect();
+        assert_eq!(hsv[0], back[0].to_rgb::<u8>().to_hsv::<f64>());
+        let mut iter = pixels.iter().to_rgb::<u8>().convert::<Hsv<f64>>();
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.next_back(), Some(Hsv::new(Deg(0.0), 0.0, 0.0)));
+    }
+
+    #[test]
+    fn test_blend_with() {
+        let backdrop = [Rgb::<f64>::new(0.5, 0.2, 1.0), Rgb::new(0.1, 0.9, 0.4), Rgb::new(1.0, 1.0, 1.0)];
+        let source = [Rgb::<f64>::new(0.5, 0.5, 0.5), Rgb::new(0.0, 1.0, 0.2)];
+        let out: Vec<Rgb<f64>> = backdrop.iter().blend_with(source.iter(), BlendMode::Multiply).collect();
+        assert_eq!(out, vec![backdrop[0].blend(source[0], BlendMode::Multiply),
+                             backdrop[1].blend(source[1], BlendMode::Multiply)]);
+        assert_eq!(backdrop.iter().blend_with(source.to_vec(), BlendMode::Screen).len(), 2);
+    }
+}
@@ -0,0 +1,152 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Jzazbz, a uniform color space for HDR.
+//!
+//! Safdar et al., "Perceptually uniform color space for image signals
+//! including high dynamic range and wide gamut" (2017).
+//!
+//! Relative XYZ is scaled so that `Y = 1` is `hdr::REFERENCE_WHITE`; values
+//! above `1` are highlights brighter than diffuse white.
+
+use {Channel, FloatChannel};
+use {Rgb, ToRgb, Srgb, Xyz, ToXyz};
+use hdr::{self, REFERENCE_WHITE, PEAK_LUMINANCE};
+use matrix::{self, Matrix3};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[repr(C)]
+pub struct Jzazbz<T> { pub jz: T, pub az: T, pub bz: T }
+
+impl<T> Jzazbz<T> {
+    #[inline]
+    pub const fn new(jz: T, az: T, bz: T) -> Jzazbz<T> {
+        Jzazbz { jz, az, bz }
+    }
+}
+
+const B: f64 = 1.15;
+const G: f64 = 0.66;
+const D: f64 = -0.56;
+const D0: f64 = 1.6295499532821566e-11;
+const P: f64 = 1.7 * 2523.0 / 32.0;
+
+const XYZ_TO_LMS: Matrix3 = [[ 0.41478972, 0.579999, 0.0146480],
+                             [-0.2015100,  1.120649, 0.0531008],
+                             [-0.0166008,  0.264800, 0.6684799]];
+const LMS_TO_IAB: Matrix3 = [[0.5,       0.5,       0.0],
+                             [3.524000, -4.066708,  0.542708],
+                             [0.199076,  1.096799, -1.295875]];
+
+impl<T: FloatChannel> Jzazbz<T> {
+    /// The colorfulness of the color.
+    #[inline]
+    pub fn chroma(&self) -> T {
+        self.az.hypot(self.bz)
+    }
+
+    fn from_xyz_array(xyz: [f64; 3]) -> Jzazbz<T> {
+        let scale = REFERENCE_WHITE / PEAK_LUMINANCE;
+        let [x, y, z] = [xyz[0] * scale, xyz[1] * scale, xyz[2] * scale];
+        let xp = B * x - (B - 1.0) * z;
+        let yp = G * y - (G - 1.0) * x;
+        let lms = matrix::mul_vec(&XYZ_TO_LMS, [xp, yp, z]);
+        let lms = [hdr::encode_with(lms[0], P), hdr::encode_with(lms[1], P), hdr::encode_with(lms[2], P)];
+        let iab = matrix::mul_vec(&LMS_TO_IAB, lms);
+        let jz = (1.0 + D) * iab[0] / (1.0 + D * iab[0]) - D0;
+        Jzazbz::new(Channel::from(jz), Channel::from(iab[1]), Channel::from(iab[2]))
+    }
+
+    fn to_xyz_array(self) -> [f64; 3] {
+        let jz = self.jz.to_channel_f64() + D0;
+        let iz = jz / (1.0 + D - D * jz);
+        let lms = matrix::mul_vec(&matrix::inverse(&LMS_TO_IAB).unwrap(),
+                                  [iz, self.az.to_channel_f64(), self.bz.to_channel_f64()]);
+        let lms = [hdr::decode_with(lms[0], P), hdr::decode_with(lms[1], P), hdr::decode_with(lms[2], P)];
+        let [xp, yp, z] = matrix::mul_vec(&matrix::inverse(&XYZ_TO_LMS).unwrap(), lms);
+        let x = (xp + (B - 1.0) * z) / B;
+        let y = (yp + (G - 1.0) * x) / G;
+        let scale = PEAK_LUMINANCE / REFERENCE_WHITE;
+        [x * scale, y * scale, z * scale]
+    }
+}
+
+pub trait ToJzazbz {
+    fn to_jzazbz<U:FloatChannel>(&self) -> Jzazbz<U>;
+}
+
+impl<T:FloatChannel> ToJzazbz for Jzazbz<T> {
+    #[inline]
+    fn to_jzazbz<U:FloatChannel>(&self) -> Jzazbz<U> {
+        Jzazbz::new(self.jz.to_channel(), self.az.to_channel(), self.bz.to_channel())
+    }
+}
+
+impl<T:FloatChannel> ToJzazbz for Xyz<T> {
+    #[inline]
+    fn to_jzazbz<U:FloatChannel>(&self) -> Jzazbz<U> {
+        Jzazbz::from_xyz_array(self.to_array())
+    }
+}
+
+impl<T:Channel> ToJzazbz for Rgb<T> {
+    #[inline]
+    fn to_jzazbz<U:FloatChannel>(&self) -> Jzazbz<U> {
+        self.to_xyz::<f64>().to_jzazbz()
+    }
+}
+
+impl<T:Channel> ToJzazbz for Srgb<T> {
+    #[inline]
+    fn to_jzazbz<U:FloatChannel>(&self) -> Jzazbz<U> {
+        self.to_xyz::<f64>().to_jzazbz()
+    }
+}
+
+impl<T:FloatChannel> ToXyz for Jzazbz<T> {
+    #[inline]
+    fn to_xyz<U:FloatChannel>(&self) -> Xyz<U> {
+        Xyz::from_array(self.to_xyz_array())
+    }
+}
+
+impl<T:FloatChannel> ToRgb for Jzazbz<T> {
+    #[inline]
+    fn to_rgb<U:Channel>(&self) -> Rgb<U> {
+        self.to_xyz::<f64>().to_rgb()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {Jzazbz, ToJzazbz, Srgb, Xyz, ToXyz, WhitePoint};
+
+    #[test]
+    fn test_jzazbz() {
+        let white: Jzazbz<f64> = WhitePoint::D65.to_xyz::<f64>().to_jzazbz();
+        assert!(white.chroma() < 2e-4, "{:?}", white);
+        let black: Jzazbz<f64> = Xyz::new(0.0, 0.0, 0.0).to_jzazbz();
+        assert!(black.jz.abs() < 1e-9);
+        // brighter highlights keep increasing in lightness
+        let hdr: Jzazbz<f64> = Xyz::new(4.0 * 0.95047, 4.0, 4.0 * 1.08883).to_jzazbz();
+        assert!(hdr.jz > white.jz);
+        for &c in [(0.8, 0.3, 0.1), (0.1, 0.6, 0.9), (0.5, 0.5, 0.5)].iter() {
+            let xyz: Xyz<f64> = Srgb::new(c.0, c.1, c.2).to_xyz();
+            let back: Xyz<f64> = xyz.to_jzazbz::<f64>().to_xyz();
+            assert!((back.x - xyz.x).abs() < 1e-9 && (back.y - xyz.y).abs() < 1e-9 && (back.z - xyz.z).abs() < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,210 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! http://en.wikipedia.org/wiki/Lab_color_space
+//!
+//! Unless a white point is given explicitly, `Lab` values are relative to
+//! D65, matching the white point `Xyz` uses for `Rgb`.
+
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use math::*;
+
+use angle::*;
+
+use {Channel, FloatChannel};
+use {Rgb, ToRgb, Srgb, Hsv, Hsl, Xyz, ToXyz, WhitePoint, ColorError};
+
+const EPSILON: f64 = 216.0 / 24389.0;
+const KAPPA: f64 = 24389.0 / 27.0;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[repr(C)]
+pub struct Lab<T> { pub l: T, pub a: T, pub b: T }
+
+impl<T> Lab<T> {
+    #[inline]
+    pub const fn new(l: T, a: T, b: T) -> Lab<T> {
+        Lab { l, a, b }
+    }
+}
+
+impl<T: FloatChannel> Lab<T> {
+    /// The distance of the color from the neutral axis.
+    #[inline]
+    pub fn chroma(&self) -> T {
+        self.a.hypot(self.b)
+    }
+
+    /// The hue angle of the color, in the range `[0, 360)`.
+    #[inline]
+    pub fn hue(&self) -> Deg<T> {
+        let h = self.b.to_channel_f64().atan2(self.a.to_channel_f64()).to_degrees();
+        Deg(Channel::from(if h < 0.0 { h + 360.0 } else { h }))
+    }
+
+    /// Converts a color relative to `white`. Fails for a degenerate white.
+    pub fn from_xyz_with_white<C: ToXyz>(color: &C, white: WhitePoint) -> Result<Lab<T>, ColorError> {
+        white.check()?;
+        Ok(Lab::from_xyz_unchecked(color, white))
+    }
+
+    /// Converts the color to XYZ relative to `white`. Fails for a
+    /// degenerate white.
+    pub fn to_xyz_with_white<U: FloatChannel>(&self, white: WhitePoint) -> Result<Xyz<U>, ColorError> {
+        white.check()?;
+        Ok(self.to_xyz_unchecked(white))
+    }
+
+    /// `from_xyz_with_white` for the crate's own white points.
+    pub(crate) fn from_xyz_unchecked<C: ToXyz>(color: &C, white: WhitePoint) -> Lab<T> {
+        let f = |t: f64| if t > EPSILON { t.cbrt() } else { (KAPPA * t + 16.0) / 116.0 };
+        let xyz = color.to_xyz::<f64>();
+        let fx = f(xyz.x / white.x);
+        let fy = f(xyz.y / white.y);
+        let fz = f(xyz.z / white.z);
+        Lab::new(Channel::from(116.0 * fy - 16.0),
+                 Channel::from(500.0 * (fx - fy)),
+                 Channel::from(200.0 * (fy - fz)))
+    }
+
+    /// `to_xyz_with_white` for the crate's own white points.
+    pub(crate) fn to_xyz_unchecked<U: FloatChannel>(self, white: WhitePoint) -> Xyz<U> {
+        let l = self.l.to_channel_f64();
+        let fy = (l + 16.0) / 116.0;
+        let fx = self.a.to_channel_f64() / 500.0 + fy;
+        let fz = fy - self.b.to_channel_f64() / 200.0;
+        let finv = |f: f64| if f * f * f > EPSILON { f * f * f } else { (116.0 * f - 16.0) / KAPPA };
+        let yr = if l > KAPPA * EPSILON { fy * fy * fy } else { l / KAPPA };
+        Xyz::new(Channel::from(finv(fx) * white.x),
+                 Channel::from(yr * white.y),
+                 Channel::from(finv(fz) * white.z))
+    }
+}
+
+pub trait ToLab {
+    fn to_lab<U:FloatChannel>(&self) -> Lab<U>;
+}
+
+impl<T:FloatChannel> ToLab for Lab<T> {
+    #[inline]
+    fn to_lab<U:FloatChannel>(&self) -> Lab<U> {
+        Lab::new(self.l.to_channel(), self.a.to_channel(), self.b.to_channel())
+    }
+}
+
+impl<T:FloatChannel> ToLab for Xyz<T> {
+    #[inline]
+    fn to_lab<U:FloatChannel>(&self) -> Lab<U> {
+        Lab::from_xyz_unchecked(self, WhitePoint::D65)
+    }
+}
+
+impl<T:Channel> ToLab for Rgb<T> {
+    #[inline]
+    fn to_lab<U:FloatChannel>(&self) -> Lab<U> {
+        self.to_xyz::<f64>().to_lab()
+    }
+}
+
+impl<T:Channel> ToLab for Srgb<T> {
+    /// Decodes the sRGB transfer function before converting.
+    #[inline]
+    fn to_lab<U:FloatChannel>(&self) -> Lab<U> {
+        self.to_linear::<f64>().to_lab()
+    }
+}
+
+impl<T:Channel> ToLab for Hsv<T> {
+    /// Reads the color as sRGB encoded, as CSS does, and decodes it before
+    /// converting.
+    #[inline]
+    fn to_lab<U:FloatChannel>(&self) -> Lab<U> {
+        let c = self.to_rgb::<f64>();
+        Srgb::new(c.r, c.g, c.b).to_lab()
+    }
+}
+
+impl<T:Channel> ToLab for Hsl<T> {
+    /// Reads the color as sRGB encoded, as CSS does, and decodes it before
+    /// converting.
+    #[inline]
+    fn to_lab<U:FloatChannel>(&self) -> Lab<U> {
+        let c = self.to_rgb::<f64>();
+        Srgb::new(c.r, c.g, c.b).to_lab()
+    }
+}
+
+impl<T:FloatChannel> ToXyz for Lab<T> {
+    #[inline]
+    fn to_xyz<U:FloatChannel>(&self) -> Xyz<U> {
+        self.to_xyz_unchecked(WhitePoint::D65)
+    }
+}
+
+impl<T:FloatChannel> ToRgb for Lab<T> {
+    #[inline]
+    fn to_rgb<U:Channel>(&self) -> Rgb<U> {
+        self.to_xyz::<f64>().to_rgb()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {Lab, ToLab, Xyz, ToXyz, Rgb, Srgb, Hsv, Hsl, WhitePoint};
+    use angle::*;
+
+    fn assert_lab_eq(a: Lab<f64>, b: Lab<f64>) {
+        assert!((a.l - b.l).abs() < 1e-2 && (a.a - b.a).abs() < 1e-2 && (a.b - b.b).abs() < 1e-2,
+                "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn test_xyz_to_lab() {
+        assert_lab_eq(WhitePoint::D65.to_xyz::<f64>().to_lab(), Lab::new(100.0, 0.0, 0.0));
+        assert_lab_eq(Xyz::<f64>::new(0.0, 0.0, 0.0).to_lab(), Lab::new(0.0, 0.0, 0.0));
+        assert_lab_eq(Rgb::<f64>::new(1.0, 0.0, 0.0).to_lab(), Lab::new(53.24, 80.09, 67.20));
+        assert_lab_eq(Rgb::<f64>::new(0.0, 0.0, 1.0).to_lab(), Lab::new(32.30, 79.19, -107.86));
+    }
+
+    #[test]
+    fn test_lab_to_xyz() {
+        for &lab in [Lab::new(53.24, 80.09, 67.20), Lab::new(5.0, -3.0, 2.0), Lab::new(75.0, -20.0, 40.0)].iter() {
+            assert_lab_eq(lab.to_xyz::<f64>().to_lab(), lab);
+            let xyz = lab.to_xyz_with_white::<f64>(WhitePoint::D50).unwrap();
+            assert_lab_eq(Lab::from_xyz_with_white(&xyz, WhitePoint::D50).unwrap(), lab);
+        }
+    }
+
+    #[test]
+    fn test_lab_chroma_hue() {
+        let lab = Lab::<f64>::new(50.0, 3.0, 4.0);
+        assert_eq!(lab.chroma(), 5.0);
+        assert_eq!(Lab::<f64>::new(50.0, 0.0, 10.0).hue(), Deg(90.0));
+        assert_eq!(Lab::<f64>::new(50.0, 0.0, -10.0).hue(), Deg(270.0));
+    }
+
+    #[test]
+    fn test_hsv_hsl_to_lab() {
+        // the cylindrical models are over encoded sRGB, as in CSS
+        let gray = Srgb::<f64>::new(0.5, 0.5, 0.5).to_lab::<f64>();
+        assert!((gray.l - 53.39).abs() < 1e-2);
+        assert_lab_eq(Hsl::<f64>::new(Deg(0.0), 0.0, 0.5).to_lab(), gray);
+        assert_lab_eq(Hsv::<f64>::new(Deg(0.0), 0.0, 0.5).to_lab(), gray);
+        assert_lab_eq(Hsv::<f64>::new(Deg(120.0), 1.0, 1.0).to_xyz::<f64>().to_lab(),
+                      Srgb::<f64>::new(0.0, 1.0, 0.0).to_lab());
+    }
+}
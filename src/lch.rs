@@ -0,0 +1,124 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The cylindrical form of CIELAB: lightness, chroma and hue.
+
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use math::*;
+
+use angle::*;
+
+use {Channel, FloatChannel};
+use {Rgb, ToRgb, Xyz, ToXyz, Lab, ToLab};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Lch<T: Channel> {
+    pub l: T,
+    pub c: T,
+    #[cfg_attr(feature = "serde", serde(with = "::serialize::deg"))]
+    pub h: Deg<T>,
+}
+
+impl<T: Channel> Lch<T> {
+    #[inline]
+    pub const fn new(l: T, c: T, h: Deg<T>) -> Lch<T> {
+        Lch { l, c, h }
+    }
+}
+
+pub trait ToLch {
+    fn to_lch<U:FloatChannel>(&self) -> Lch<U>;
+}
+
+impl<T:FloatChannel> ToLch for Lch<T> {
+    #[inline]
+    fn to_lch<U:FloatChannel>(&self) -> Lch<U> {
+        Lch::new(self.l.to_channel(), self.c.to_channel(), Deg(self.h.value().to_channel()))
+    }
+}
+
+impl<T:FloatChannel> ToLch for Lab<T> {
+    #[inline]
+    fn to_lch<U:FloatChannel>(&self) -> Lch<U> {
+        let lab = self.to_lab::<U>();
+        Lch::new(lab.l, lab.chroma(), lab.hue())
+    }
+}
+
+impl<T:FloatChannel> ToLch for Xyz<T> {
+    #[inline]
+    fn to_lch<U:FloatChannel>(&self) -> Lch<U> {
+        self.to_lab::<f64>().to_lch()
+    }
+}
+
+impl<T:Channel> ToLch for Rgb<T> {
+    #[inline]
+    fn to_lch<U:FloatChannel>(&self) -> Lch<U> {
+        self.to_lab::<f64>().to_lch()
+    }
+}
+
+impl<T:FloatChannel> ToLab for Lch<T> {
+    #[inline]
+    fn to_lab<U:FloatChannel>(&self) -> Lab<U> {
+        let (sin, cos) = self.h.value().to_channel_f64().to_radians().sin_cos();
+        let c = self.c.to_channel_f64();
+        Lab::new(self.l.to_channel(), Channel::from(c * cos), Channel::from(c * sin))
+    }
+}
+
+impl<T:FloatChannel> ToXyz for Lch<T> {
+    #[inline]
+    fn to_xyz<U:FloatChannel>(&self) -> Xyz<U> {
+        self.to_lab::<f64>().to_xyz()
+    }
+}
+
+impl<T:FloatChannel> ToRgb for Lch<T> {
+    #[inline]
+    fn to_rgb<U:Channel>(&self) -> Rgb<U> {
+        self.to_lab::<f64>().to_rgb()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {Lch, ToLch, Lab, ToLab, Rgb};
+    use angle::*;
+
+    #[test]
+    fn test_lab_to_lch() {
+        assert_eq!(Lab::<f64>::new(50.0, 0.0, 10.0).to_lch::<f64>(), Lch::new(50.0, 10.0, Deg(90.0)));
+        assert_eq!(Lab::<f64>::new(50.0, -10.0, 0.0).to_lch::<f64>(), Lch::new(50.0, 10.0, Deg(180.0)));
+    }
+
+    #[test]
+    fn test_lch_to_lab() {
+        let lab = Lch::<f64>::new(50.0, 10.0, Deg(180.0)).to_lab::<f64>();
+        assert!((lab.a + 10.0).abs() < 1e-9 && lab.b.abs() < 1e-9);
+        let lab = Lch::<f64>::new(60.0, 20.0, Deg(30.0)).to_lch::<f64>().to_lab::<f64>().to_lch::<f64>();
+        assert!((lab.c - 20.0).abs() < 1e-9 && (lab.h.value() - 30.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_rgb_to_lch() {
+        let lch = Rgb::<f64>::new(1.0, 0.0, 0.0).to_lch::<f64>();
+        assert!((lch.l - 53.24).abs() < 1e-2 && (lch.c - 104.55).abs() < 1e-2 && (lch.h.value() - 40.0).abs() < 1e-1,
+                "{:?}", lch);
+    }
+}
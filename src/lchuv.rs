@@ -0,0 +1,124 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The cylindrical form of CIELUV: lightness, chroma and hue.
+
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use math::*;
+
+use angle::*;
+
+use {Channel, FloatChannel};
+use {Rgb, ToRgb, Srgb, Xyz, ToXyz, Luv, ToLuv};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Lchuv<T: Channel> {
+    pub l: T,
+    pub c: T,
+    #[cfg_attr(feature = "serde", serde(with = "::serialize::deg"))]
+    pub h: Deg<T>,
+}
+
+impl<T: Channel> Lchuv<T> {
+    #[inline]
+    pub const fn new(l: T, c: T, h: Deg<T>) -> Lchuv<T> {
+        Lchuv { l, c, h }
+    }
+}
+
+pub trait ToLchuv {
+    fn to_lchuv<U:FloatChannel>(&self) -> Lchuv<U>;
+}
+
+impl<T:FloatChannel> ToLchuv for Lchuv<T> {
+    #[inline]
+    fn to_lchuv<U:FloatChannel>(&self) -> Lchuv<U> {
+        Lchuv::new(self.l.to_channel(), self.c.to_channel(), Deg(self.h.value().to_channel()))
+    }
+}
+
+impl<T:FloatChannel> ToLchuv for Luv<T> {
+    #[inline]
+    fn to_lchuv<U:FloatChannel>(&self) -> Lchuv<U> {
+        let luv = self.to_luv::<U>();
+        Lchuv::new(luv.l, luv.chroma(), luv.hue())
+    }
+}
+
+impl<T:FloatChannel> ToLchuv for Xyz<T> {
+    #[inline]
+    fn to_lchuv<U:FloatChannel>(&self) -> Lchuv<U> {
+        self.to_luv::<f64>().to_lchuv()
+    }
+}
+
+impl<T:Channel> ToLchuv for Rgb<T> {
+    #[inline]
+    fn to_lchuv<U:FloatChannel>(&self) -> Lchuv<U> {
+        self.to_luv::<f64>().to_lchuv()
+    }
+}
+
+impl<T:Channel> ToLchuv for Srgb<T> {
+    #[inline]
+    fn to_lchuv<U:FloatChannel>(&self) -> Lchuv<U> {
+        self.to_luv::<f64>().to_lchuv()
+    }
+}
+
+impl<T:FloatChannel> ToLuv for Lchuv<T> {
+    #[inline]
+    fn to_luv<U:FloatChannel>(&self) -> Luv<U> {
+        let (sin, cos) = self.h.value().to_channel_f64().to_radians().sin_cos();
+        let c = self.c.to_channel_f64();
+        Luv::new(self.l.to_channel(), Channel::from(c * cos), Channel::from(c * sin))
+    }
+}
+
+impl<T:FloatChannel> ToXyz for Lchuv<T> {
+    #[inline]
+    fn to_xyz<U:FloatChannel>(&self) -> Xyz<U> {
+        self.to_luv::<f64>().to_xyz()
+    }
+}
+
+impl<T:FloatChannel> ToRgb for Lchuv<T> {
+    #[inline]
+    fn to_rgb<U:Channel>(&self) -> Rgb<U> {
+        self.to_luv::<f64>().to_rgb()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {Lchuv, ToLchuv, Luv, ToLuv, Rgb};
+    use angle::*;
+
+    #[test]
+    fn test_luv_to_lchuv() {
+        assert_eq!(Luv::<f64>::new(50.0, 0.0, 10.0).to_lchuv::<f64>(), Lchuv::new(50.0, 10.0, Deg(90.0)));
+        let luv = Lchuv::<f64>::new(50.0, 10.0, Deg(180.0)).to_luv::<f64>();
+        assert!((luv.u + 10.0).abs() < 1e-9 && luv.v.abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_rgb_to_lchuv() {
+        let lch = Rgb::<f64>::new(1.0, 0.0, 0.0).to_lchuv::<f64>();
+        assert!((lch.l - 53.2408).abs() < 1e-3 && (lch.c - 179.0414).abs() < 1e-3 && (lch.h.value() - 12.1740).abs() < 1e-3,
+                "{:?}", lch);
+    }
+}
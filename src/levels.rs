@@ -0,0 +1,196 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tonal adjustments of 8-bit images: levels, auto contrast and histogram
+//! equalization.
+//!
+//! Each operation builds a lookup table from the pixels and applies it in
+//! place, either to every channel separately or to the Rec. 709 luma of the
+//! encoded values. Per channel adjustments can shift colors, as each channel
+//! is stretched on its own; luma adjustments move all three channels by the
+//! same amount and keep the hue.
+
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use math::*;
+
+use histogram::{Histogram, RgbHistogram};
+use {Rgb, LumaWeights};
+
+/// What an adjustment works on.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Target {
+    /// Red, green and blue, each with its own table.
+    #[default]
+    PerChannel,
+    /// The luma of the encoded values, with chroma kept.
+    Luma,
+}
+
+type Lut = [u8; 256];
+
+#[inline]
+fn identity() -> Lut {
+    let mut lut = [0; 256];
+    for (i, v) in lut.iter_mut().enumerate() {
+        *v = i as u8;
+    }
+    lut
+}
+
+#[inline]
+fn luma(p: &Rgb<u8>) -> f64 {
+    LumaWeights::Rec709.apply(p.r as f64, p.g as f64, p.b as f64)
+}
+
+fn luma_histogram(pixels: &[Rgb<u8>]) -> Histogram {
+    Histogram::new(pixels.iter().map(|p| luma(p).round() as u8), 256)
+}
+
+/// Builds the tables for `target` from the 256 bin histograms of the
+/// pixels, and applies them.
+fn adjust<F: Fn(&Histogram) -> Lut>(pixels: &mut [Rgb<u8>], target: Target, lut: F) {
+    match target {
+        Target::PerChannel => {
+            let h = RgbHistogram::new(&*pixels, 256);
+            let (r, g, b) = (lut(&h.r), lut(&h.g), lut(&h.b));
+            for p in pixels.iter_mut() {
+                *p = Rgb::new(r[p.r as usize], g[p.g as usize], b[p.b as usize]);
+            }
+        }
+        Target::Luma => {
+            let lut = lut(&luma_histogram(pixels));
+            for p in pixels.iter_mut() {
+                let y = luma(p);
+                let shift = lut[y.round() as usize] as f64 - y;
+                *p = p.map(|c| (c as f64 + shift).round().clamp(0.0, 255.0) as u8);
+            }
+        }
+    }
+}
+
+fn levels_lut(black: u8, white: u8, gamma: f64) -> Lut {
+    let mut lut = [0; 256];
+    let (black, white) = (black as f64, white as f64);
+    for (i, v) in lut.iter_mut().enumerate() {
+        let t = ((i as f64 - black) / (white - black)).clamp(0.0, 1.0);
+        *v = (t.powf(1.0 / gamma) * 255.0).round() as u8;
+    }
+    lut
+}
+
+/// Maps `black` to `0` and `white` to `255`, clipping values outside that
+/// range, and applies the gamma correction `x^(1/gamma)` in between, so a
+/// `gamma` above one brightens the midtones.
+///
+/// # Panics
+///
+/// If `black` is not below `white` or `gamma` is not positive.
+pub fn levels(pixels: &mut [Rgb<u8>], black: u8, white: u8, gamma: f64, target: Target) {
+    assert!(black < white, "black point {} must be below white point {}", black, white);
+    assert!(gamma > 0.0, "gamma must be positive, got {}", gamma);
+    let lut = levels_lut(black, white, gamma);
+    adjust(pixels, target, |_| lut);
+}
+
+/// Stretches the values to the full range, ignoring `clip_percent` percent
+/// of the values at each end as outliers. Images with a single value are
+/// left alone.
+pub fn auto_contrast(pixels: &mut [Rgb<u8>], clip_percent: f64, target: Target) {
+    let clip = (clip_percent / 100.0).clamp(0.0, 0.5);
+    adjust(pixels, target, |h| {
+        match (h.percentile(clip), h.percentile(1.0 - clip)) {
+            (Some(lo), Some(hi)) if lo < hi => levels_lut(lo as u8, hi as u8, 1.0),
+            _ => identity(),
+        }
+    });
+}
+
+/// Spreads the values so that their cumulative distribution becomes close to
+/// linear, which brings out detail in the most common tones.
+pub fn equalize(pixels: &mut [Rgb<u8>], target: Target) {
+    adjust(pixels, target, |h| {
+        let cumulative = h.cumulative();
+        let total = h.total();
+        let min = cumulative.iter().cloned().find(|&c| c > 0).unwrap_or(0);
+        if total == min {
+            return identity();
+        }
+        let mut lut = [0; 256];
+        for (v, &c) in lut.iter_mut().zip(cumulative.iter()) {
+            *v = (c.saturating_sub(min) as f64 / (total - min) as f64 * 255.0).round() as u8;
+        }
+        lut
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use super::{levels, auto_contrast, equalize, Target};
+    use Rgb;
+
+    fn grays(values: &[u8]) -> Vec<Rgb<u8>> {
+        values.iter().map(|&v| Rgb::new(v, v, v)).collect()
+    }
+
+    #[test]
+    fn test_levels() {
+        let mut pixels = grays(&[0, 50, 100, 150, 200, 255]);
+        levels(&mut pixels, 50, 150, 1.0, Target::PerChannel);
+        assert_eq!(pixels, grays(&[0, 0, 128, 255, 255, 255]));
+
+        let mut pixels = grays(&[64]);
+        levels(&mut pixels, 0, 255, 2.0, Target::Luma);
+        assert_eq!(pixels, grays(&[128]));
+    }
+
+    #[test]
+    fn test_auto_contrast() {
+        let mut pixels = grays(&[100, 120, 140]);
+        auto_contrast(&mut pixels, 0.0, Target::PerChannel);
+        assert_eq!(pixels, grays(&[0, 128, 255]));
+
+        // the outliers are clipped
+        let mut pixels = grays(&[0, 100, 100, 100, 100, 100, 100, 100, 100, 140, 140, 140, 140, 140, 140, 140, 140, 255]);
+        auto_contrast(&mut pixels, 10.0, Target::PerChannel);
+        assert_eq!(pixels[1], Rgb::new(0, 0, 0));
+        assert_eq!(pixels[9], Rgb::new(255, 255, 255));
+
+        // luma keeps the differences between the channels
+        let mut pixels = vec![Rgb::new(50, 50, 50), Rgb::new(100, 90, 90), Rgb::new(200, 200, 200)];
+        auto_contrast(&mut pixels, 0.0, Target::Luma);
+        assert_eq!(pixels[0], Rgb::new(0, 0, 0));
+        assert_eq!(pixels[1].r - pixels[1].g, 10);
+        assert!(pixels[1].g < 90);
+        assert_eq!(pixels[2], Rgb::new(255, 255, 255));
+
+        let mut flat = grays(&[80, 80]);
+        auto_contrast(&mut flat, 0.0, Target::Luma);
+        assert_eq!(flat, grays(&[80, 80]));
+    }
+
+    #[test]
+    fn test_equalize() {
+        let mut pixels = grays(&[10, 10, 11, 12]);
+        equalize(&mut pixels, Target::PerChannel);
+        assert_eq!(pixels, grays(&[0, 0, 128, 255]));
+
+        let mut pixels = vec![Rgb::new(200, 0, 0)];
+        equalize(&mut pixels, Target::PerChannel);
+        assert_eq!(pixels, vec![Rgb::new(200, 0, 0)]);
+        equalize(&mut [], Target::Luma);
+    }
+}
@@ -12,23 +12,188 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
-extern crate num;
-extern crate angle;
+#![no_std]
+
+#[cfg(feature = "std")]
+extern crate std;
+#[macro_use]
+extern crate alloc;
+extern crate num_traits as num;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
+#[cfg(feature = "rayon")]
+extern crate rayon;
+#[cfg(feature = "bytemuck")]
+extern crate bytemuck;
+#[cfg(feature = "image")]
+extern crate image;
+#[cfg(feature = "rand")]
+extern crate rand;
+#[cfg(feature = "approx")]
+#[cfg_attr(test, macro_use)]
+extern crate approx;
+#[cfg(feature = "half")]
+extern crate half;
+#[cfg(feature = "wgpu")]
+extern crate wgpu_types;
+#[cfg(feature = "palette")]
+extern crate palette as palette_crate;
+#[cfg(feature = "glam")]
+extern crate glam;
+#[cfg(feature = "cgmath")]
+extern crate cgmath;
+#[cfg(feature = "egui")]
+extern crate ecolor;
+#[cfg(feature = "iced")]
+extern crate iced_core;
 
 pub use alpha::AlphaColor;
-pub use alpha::{Rgba, Hsva, Srgba, YCbCra, ToRgba};
+pub use alpha::{Rgba, Hsva, Hsla, Hwba, Srgba, YCbCra, ToRgba};
 pub use channel::{Channel, FloatChannel};
-pub use hsv::{Hsv, ToHsv};
-pub use rgb::{Rgb, Rg, ToRgb, consts};
-pub use srgb::Srgb;
-pub use ycbcr::YCbCr;
+pub use fixed::{Fixed, Fixed16};
+pub use hsv::{Hsv, ToHsv, HueDirection};
+pub use hue::{Hue, Turns};
+pub use hsl::{Hsl, ToHsl};
+pub use hwb::{Hwb, ToHwb};
+pub use hsi::{Hsi, ToHsi};
+pub use hsp::{Hsp, ToHsp};
+pub use ryb::{Ryb, ToRyb};
+pub use cmyk::{Cmyk, ToCmyk};
+pub use luma::{Luma, ToLuma, LumaWeights, LumaSpace};
+pub use rgb::{Rgb, Rg, ToRgb};
+pub use encoding::Encoding;
+pub use srgb::{Srgb, ToSrgb, consts};
+pub use ycbcr::{YCbCr, ToYCbCr, YCbCrStandard, YCbCrRange};
+pub use xyz::{Xyz, ToXyz, WhitePoint};
+pub use xyy::{XyY, ToXyY, Chromaticity};
+pub use temperature::TEMPERATURE_RANGE;
+pub use lab::{Lab, ToLab};
+pub use lch::{Lch, ToLch};
+pub use luv::{Luv, ToLuv};
+pub use lchuv::{Lchuv, ToLchuv};
+pub use oklab::{Oklab, ToOklab};
+pub use oklch::{Oklch, ToOklch};
+pub use cam16::{Cam16, ToCam16, Cam16Ucs, ToCam16Ucs};
+pub use jzazbz::{Jzazbz, ToJzazbz};
+pub use ictcp::{ICtCp, ToICtCp};
+pub use hsluv::{Hsluv, ToHsluv, Hpluv, ToHpluv};
+pub use premultiplied::{Premultiplied, PremultipliedRgba};
+pub use gradient::{Gradient, Interpolation, Curve};
+pub use rgb_space::RgbSpace;
+pub use packed::PixelLayout;
+pub use convert::FromColor;
+pub use pixel::Pixel;
+pub use error::ColorError;
+pub use finite::Finite;
+pub use adjust::Adjust;
+pub use palette::Palette;
+pub use spectrum::Spd;
 
+pub mod angle;
 #[macro_use] mod rgb;
+pub mod encoding;
 #[macro_use] mod alpha;
 mod channel;
+#[cfg(not(feature = "std"))]
+mod math;
+#[cfg(all(feature = "simd", any(target_arch = "x86_64", target_arch = "aarch64")))]
+mod simd;
+mod fixed;
 mod hsv;
-mod srgb;
+mod hue;
+mod hsl;
+mod hwb;
+mod hsi;
+mod hsp;
+mod ryb;
+#[macro_use] mod srgb;
+mod luma;
 mod ycbcr;
+mod xyz;
+mod xyy;
+mod temperature;
+mod lab;
+mod lch;
+mod luv;
+mod lchuv;
+mod oklab;
+mod oklch;
+pub mod cam16;
+mod jzazbz;
+mod ictcp;
+pub mod hdr;
+mod hsluv;
+mod gradient;
+mod premultiplied;
+mod matrix;
+mod convert;
+mod error;
+mod finite;
+mod display;
+mod constants;
+mod array;
+pub mod pixel;
+mod adjust;
+#[cfg(feature = "bytemuck")]
+mod pod;
+#[cfg(feature = "approx")]
+mod approx_eq;
+
+pub mod css;
+pub mod contrast;
+pub mod difference;
+pub mod blend;
+pub mod composite;
+pub mod cmyk;
+pub mod adaptation;
+pub mod spectrum;
+pub mod rendering;
+pub mod pigment;
+pub mod rgb_space;
+pub mod gamut;
+pub mod packed;
+pub mod gpu;
+pub mod batch;
+pub mod iter;
+pub mod terminal;
+pub mod harmony;
+pub mod colormap;
+pub mod theme;
+pub mod cvd;
+pub mod average;
+pub mod histogram;
+pub mod levels;
+pub mod quantize;
+pub mod sort;
+pub mod dither;
+pub mod palette;
+pub mod tonemap;
+pub mod color_matrix;
+#[cfg(feature = "palette_io")]
+pub mod palette_io;
+#[cfg(feature = "icc")]
+pub mod icc;
+#[cfg(feature = "lut3d")]
+pub mod lut3d;
+#[cfg(feature = "image")]
+pub mod image_interop;
+#[cfg(feature = "wgpu")]
+pub mod wgpu_interop;
+#[cfg(feature = "palette")]
+pub mod palette_interop;
+#[cfg(feature = "glam")]
+pub mod glam_interop;
+#[cfg(feature = "cgmath")]
+pub mod cgmath_interop;
+#[cfg(feature = "egui")]
+pub mod egui_interop;
+#[cfg(feature = "iced")]
+pub mod iced_interop;
+#[cfg(feature = "rand")]
+pub mod random;
+#[cfg(feature = "serde")]
+pub mod serialize;
 
 pub trait Color<T>: Copy {
     fn clamp_s(self, lo: T, hi: T) -> Self;
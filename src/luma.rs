@@ -0,0 +1,203 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Single channel gray levels.
+//!
+//! A `Luma` keeps the encoding of the color it was taken from: the luma of
+//! an `Rgb` is linear, and the luma of an `Srgb` is gamma-encoded, so it can
+//! be written straight back out as a gray pixel. `to_rgb` and `to_srgb`
+//! replicate the level into all three channels without re-encoding it.
+
+use {Color, Channel};
+use {Rgb, ToRgb, Srgb, ToSrgb};
+use srgb;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[repr(C)]
+pub struct Luma<T> { pub l: T }
+
+impl<T> Luma<T> {
+    #[inline]
+    pub const fn new(l: T) -> Luma<T> {
+        Luma { l }
+    }
+}
+
+/// The weights of red, green and blue in a gray level.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum LumaWeights {
+    /// ITU-R BT.601, the weights of JPEG and of standard definition video.
+    Rec601,
+    /// ITU-R BT.709, the primaries of sRGB. On linear values this gives the
+    /// CIE luminance `Y`.
+    #[default]
+    Rec709,
+    /// ITU-R BT.2020.
+    Rec2020,
+    /// The plain mean of the three channels.
+    Average,
+}
+
+impl LumaWeights {
+    /// The `[r, g, b]` weights, which add up to one.
+    #[inline]
+    pub fn coefficients(self) -> [f64; 3] {
+        match self {
+            LumaWeights::Rec601 => [0.299, 0.587, 0.114],
+            LumaWeights::Rec709 => [0.2126, 0.7152, 0.0722],
+            LumaWeights::Rec2020 => [0.2627, 0.6780, 0.0593],
+            LumaWeights::Average => [1.0 / 3.0, 1.0 / 3.0, 1.0 / 3.0],
+        }
+    }
+
+    /// The weighted sum of the channels.
+    #[inline]
+    pub fn apply(self, r: f64, g: f64, b: f64) -> f64 {
+        let [wr, wg, wb] = self.coefficients();
+        wr * r + wg * g + wb * b
+    }
+}
+
+/// Where the channels of an encoded color are weighted.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LumaSpace {
+    /// Weights the encoded values directly, giving the `Y'` of video. This
+    /// is cheap but darkens saturated colors.
+    Encoded,
+    /// Decodes, weights and re-encodes, giving the gray of the same
+    /// luminance.
+    Linear,
+}
+
+impl<T: Channel> Rgb<T> {
+    /// The weighted sum of the channels. For linear colors with
+    /// `LumaWeights::Rec709` this is the relative luminance.
+    #[inline]
+    pub fn to_luma_with<U: Channel>(&self, weights: LumaWeights) -> Luma<U> {
+        let l = weights.apply(self.r.to_channel_f64(), self.g.to_channel_f64(), self.b.to_channel_f64());
+        Luma::new(Channel::from(l))
+    }
+}
+
+impl<T: Channel> Srgb<T> {
+    /// The gray level of the color, still gamma-encoded.
+    pub fn to_luma_with<U: Channel>(&self, weights: LumaWeights, space: LumaSpace) -> Luma<U> {
+        let (r, g, b) = (self.r.to_channel_f64(), self.g.to_channel_f64(), self.b.to_channel_f64());
+        let l = match space {
+            LumaSpace::Encoded => weights.apply(r, g, b),
+            LumaSpace::Linear => srgb::encode(weights.apply(srgb::decode(r), srgb::decode(g), srgb::decode(b))),
+        };
+        Luma::new(Channel::from(l))
+    }
+}
+
+impl<T: Channel> Color<T> for Luma<T> {
+    /// Clamps the level to the range `(lo,hi)`.
+    #[inline]
+    fn clamp_s(self, lo: T, hi: T) -> Luma<T> {
+        Luma::new(self.l.clamp(lo, hi))
+    }
+
+    /// Clamps the level between `lo` and `hi`.
+    #[inline]
+    fn clamp_c(self, lo: Luma<T>, hi: Luma<T>) -> Luma<T> {
+        Luma::new(self.l.clamp(lo.l, hi.l))
+    }
+
+    #[inline]
+    fn inverse(self) -> Luma<T> {
+        Luma::new(self.l.invert_channel())
+    }
+
+    #[inline]
+    fn mix(self, other: Self, value: T) -> Self {
+        Luma::new(self.l.mix(other.l, value))
+    }
+}
+
+/// Conversion to a gray level, with the Rec. 709 weights. Linear colors are
+/// weighted as they are, and `Srgb` is weighted in linear light.
+pub trait ToLuma {
+    fn to_luma<U:Channel>(&self) -> Luma<U>;
+}
+
+impl<T:Channel> ToLuma for Luma<T> {
+    #[inline]
+    fn to_luma<U:Channel>(&self) -> Luma<U> {
+        Luma::new(self.l.to_channel())
+    }
+}
+
+impl<T:Channel> ToLuma for Rgb<T> {
+    #[inline]
+    fn to_luma<U:Channel>(&self) -> Luma<U> {
+        self.to_luma_with(LumaWeights::Rec709)
+    }
+}
+
+impl<T:Channel> ToLuma for Srgb<T> {
+    #[inline]
+    fn to_luma<U:Channel>(&self) -> Luma<U> {
+        self.to_luma_with(LumaWeights::Rec709, LumaSpace::Linear)
+    }
+}
+
+impl<T:Channel> ToRgb for Luma<T> {
+    #[inline]
+    fn to_rgb<U:Channel>(&self) -> Rgb<U> {
+        let l = self.l.to_channel();
+        Rgb::new(l, l, l)
+    }
+}
+
+impl<T:Channel> ToSrgb for Luma<T> {
+    #[inline]
+    fn to_srgb<U:Channel>(&self) -> Srgb<U> {
+        let l = self.l.to_channel();
+        Srgb::new(l, l, l)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LumaWeights, LumaSpace};
+    use {Luma, ToLuma, Rgb, ToRgb, Srgb, ToSrgb};
+
+    #[test]
+    fn test_luma_weights() {
+        for &w in &[LumaWeights::Rec601, LumaWeights::Rec709, LumaWeights::Rec2020, LumaWeights::Average] {
+            assert!((w.coefficients().iter().sum::<f64>() - 1.0).abs() < 1e-12, "{:?}", w);
+            assert!((Rgb::<u8>::new(0xFF, 0xFF, 0xFF).to_luma_with::<f64>(w).l - 1.0).abs() < 1e-12);
+        }
+        let green = Rgb::<f64>::new(0.0, 1.0, 0.0);
+        assert_eq!(green.to_luma_with::<f64>(LumaWeights::Rec601), Luma::new(0.587));
+        assert_eq!(green.to_luma::<f64>(), Luma::new(0.7152));
+        assert!((green.to_luma::<f64>().l - green.relative_luminance()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_srgb_luma() {
+        let blue = Srgb::<f64>::new(0.0, 0.0, 1.0);
+        let encoded = blue.to_luma_with::<f64>(LumaWeights::Rec709, LumaSpace::Encoded);
+        assert!((encoded.l - 0.0722).abs() < 1e-12);
+        // the gray of the same luminance is much lighter than Y'
+        let linear = blue.to_luma::<f64>();
+        assert!((linear.l - 0.2979).abs() < 1e-3, "{:?}", linear);
+        let gray: Srgb<f64> = linear.to_srgb();
+        assert!((gray.relative_luminance() - blue.relative_luminance()).abs() < 1e-12);
+        assert_eq!(Luma::new(0.25f64).to_rgb::<f64>(), Rgb::new(0.25, 0.25, 0.25));
+    }
+}
@@ -0,0 +1,428 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 3D lookup tables, read from and written to `.cube` files.
+//!
+//! A `Lut3d` maps each RGB color to another through a cube of output colors
+//! sampled on a regular grid, interpolating between the samples around the
+//! input. Trilinear interpolation blends all eight corners of the cell;
+//! tetrahedral interpolation blends the four corners of the tetrahedron the
+//! input lies in, which is cheaper and keeps the neutral axis neutral, and
+//! is what grading tools usually use.
+//!
+//! The tables are applied to the components of colors as they are: a LUT
+//! made for gamma-encoded or log footage should be given `Srgb` or other
+//! encoded values, not `Rgb`. Inputs outside the domain are clamped to it.
+//!
+//! The `.cube` format is the one of Adobe and DaVinci Resolve: `TITLE`,
+//! `LUT_3D_SIZE`, `DOMAIN_MIN` and `DOMAIN_MAX` keywords, and Resolve's
+//! `LUT_3D_INPUT_RANGE`, followed by one output color per line with red
+//! changing fastest. Files holding a 1D table are not supported.
+
+use core::error;
+use core::fmt;
+use std::io::{self, Read, Write};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use AlphaColor;
+use blend::Blend;
+
+/// How `Lut3d::apply` interpolates between the samples of the table.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum Interpolation {
+    /// The eight corners of the cell around the input.
+    Trilinear,
+    /// The four corners of the tetrahedron of the cell around the input.
+    #[default]
+    Tetrahedral,
+}
+
+/// The ways in which reading a `.cube` file can fail.
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    /// A line could not be parsed. The line number counts from 1.
+    InvalidLine(usize, String),
+    /// The size is missing or out of range, or the number of entries does
+    /// not match it.
+    InvalidSize,
+    /// The file holds a 1D table.
+    Unsupported1d,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Io(ref e) => write!(f, "{}", e),
+            Error::InvalidLine(n, ref s) => write!(f, "invalid line {} `{}`", n, s),
+            Error::InvalidSize => write!(f, "missing or mismatched LUT size"),
+            Error::Unsupported1d => write!(f, "1D LUTs are not supported"),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::Io(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    #[inline]
+    fn from(e: io::Error) -> Error {
+        Error::Io(e)
+    }
+}
+
+/// The smallest and largest sizes of a table, as allowed by the format.
+const MIN_SIZE: usize = 2;
+const MAX_SIZE: usize = 256;
+
+/// A 3D lookup table.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Lut3d {
+    title: Option<String>,
+    size: usize,
+    domain_min: [f64; 3],
+    domain_max: [f64; 3],
+    /// The output colors, with red changing fastest and blue slowest.
+    table: Vec<[f64; 3]>,
+}
+
+impl Lut3d {
+    /// A table of `size` samples per side, with red changing fastest and
+    /// blue slowest, over the domain `[0, 1]`. Returns `None` if the size
+    /// is out of `[2, 256]` or does not match the table.
+    pub fn new(size: usize, table: Vec<[f64; 3]>) -> Option<Lut3d> {
+        if !(MIN_SIZE..=MAX_SIZE).contains(&size) || table.len() != size * size * size {
+            return None;
+        }
+        Some(Lut3d { title: None, size, domain_min: [0.0; 3], domain_max: [1.0; 3], table })
+    }
+
+    /// The table sampling `f` at each point of the grid. Panics if the size
+    /// is out of `[2, 256]`.
+    pub fn from_fn<F: FnMut([f64; 3]) -> [f64; 3]>(size: usize, mut f: F) -> Lut3d {
+        assert!((MIN_SIZE..=MAX_SIZE).contains(&size), "LUT size {} out of range", size);
+        let step = 1.0 / (size - 1) as f64;
+        let mut table = Vec::with_capacity(size * size * size);
+        for b in 0..size {
+            for g in 0..size {
+                for r in 0..size {
+                    table.push(f([r as f64 * step, g as f64 * step, b as f64 * step]));
+                }
+            }
+        }
+        Lut3d { title: None, size, domain_min: [0.0; 3], domain_max: [1.0; 3], table }
+    }
+
+    /// The table leaving every color as it is.
+    #[inline]
+    pub fn identity(size: usize) -> Lut3d {
+        Lut3d::from_fn(size, |c| c)
+    }
+
+    /// Sets the range of input values the grid spans, for each of red,
+    /// green and blue.
+    #[inline]
+    pub fn with_domain(self, min: [f64; 3], max: [f64; 3]) -> Lut3d {
+        Lut3d { domain_min: min, domain_max: max, ..self }
+    }
+
+    #[inline]
+    pub fn with_title(self, title: &str) -> Lut3d {
+        Lut3d { title: Some(title.to_string()), ..self }
+    }
+
+    /// The number of samples per side.
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    #[inline]
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    /// The smallest and largest input values of red, green and blue.
+    #[inline]
+    pub fn domain(&self) -> ([f64; 3], [f64; 3]) {
+        (self.domain_min, self.domain_max)
+    }
+
+    /// The sample at the given grid coordinates. Panics if any is not less
+    /// than the size.
+    #[inline]
+    pub fn get(&self, r: usize, g: usize, b: usize) -> [f64; 3] {
+        assert!(r < self.size && g < self.size && b < self.size, "LUT index out of range");
+        self.table[(b * self.size + g) * self.size + r]
+    }
+
+    /// Maps a color through the table.
+    pub fn apply<C: Blend>(&self, color: &C, interpolation: Interpolation) -> C {
+        let c = color.to_components();
+        let n = (self.size - 1) as f64;
+        // the cell of the input and its position within it
+        let mut cell = [0; 3];
+        let mut t = [0.0; 3];
+        for i in 0..3 {
+            let range = self.domain_max[i] - self.domain_min[i];
+            let x = ((c[i] - self.domain_min[i]) / range).clamp(0.0, 1.0) * n;
+            // NaN clamps to NaN, and casts to the first cell
+            cell[i] = (x as usize).min(self.size - 2);
+            t[i] = x - cell[i] as f64;
+        }
+        let [r, g, b] = cell;
+        let at = |dr: usize, dg: usize, db: usize| self.get(r + dr, g + dg, b + db);
+        let out = match interpolation {
+            Interpolation::Trilinear => trilinear(&at, t),
+            Interpolation::Tetrahedral => tetrahedral(&at, t),
+        };
+        C::from_components(out)
+    }
+
+    /// Maps the color of an `AlphaColor` through the table, leaving alpha
+    /// untouched.
+    #[inline]
+    pub fn apply_alpha<T: Copy, C: Blend>(&self, color: &AlphaColor<T, C>, interpolation: Interpolation)
+            -> AlphaColor<T, C> {
+        AlphaColor { c: self.apply(&color.c, interpolation), a: color.a }
+    }
+
+    pub fn apply_slice<C: Blend>(&self, colors: &mut [C], interpolation: Interpolation) {
+        for c in colors.iter_mut() {
+            *c = self.apply(c, interpolation);
+        }
+    }
+
+    /// Reads a `.cube` file.
+    pub fn read<R: Read>(mut reader: R) -> Result<Lut3d, Error> {
+        let mut s = String::new();
+        reader.read_to_string(&mut s)?;
+        Lut3d::parse(&s)
+    }
+
+    /// Parses the text of a `.cube` file.
+    pub fn parse(s: &str) -> Result<Lut3d, Error> {
+        let mut title = None;
+        let mut size = None;
+        let mut domain_min = [0.0; 3];
+        let mut domain_max = [1.0; 3];
+        let mut table = Vec::new();
+        for (i, line) in s.lines().enumerate() {
+            let line = line.trim();
+            let invalid = || Error::InvalidLine(i + 1, line.to_string());
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (keyword, rest) = match line.find(char::is_whitespace) {
+                Some(n) => (&line[..n], line[n..].trim()),
+                None => (line, ""),
+            };
+            match keyword {
+                "TITLE" => title = Some(rest.trim_matches('"').to_string()),
+                "LUT_3D_SIZE" => size = Some(rest.parse::<usize>().map_err(|_| invalid())?),
+                "LUT_1D_SIZE" | "LUT_1D_INPUT_RANGE" => return Err(Error::Unsupported1d),
+                "DOMAIN_MIN" => domain_min = parse_triple(rest).ok_or_else(invalid)?,
+                "DOMAIN_MAX" => domain_max = parse_triple(rest).ok_or_else(invalid)?,
+                "LUT_3D_INPUT_RANGE" => {
+                    let mut parts = rest.split_whitespace().map(str::parse::<f64>);
+                    match (parts.next(), parts.next(), parts.next()) {
+                        (Some(Ok(min)), Some(Ok(max)), None) => {
+                            domain_min = [min; 3];
+                            domain_max = [max; 3];
+                        }
+                        _ => return Err(invalid()),
+                    }
+                }
+                _ => table.push(parse_triple(line).ok_or_else(invalid)?),
+            }
+        }
+        let size = size.ok_or(Error::InvalidSize)?;
+        let lut = Lut3d::new(size, table).ok_or(Error::InvalidSize)?;
+        Ok(Lut3d { title, domain_min, domain_max, ..lut })
+    }
+
+    /// Writes the table as a `.cube` file.
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let mut s = String::new();
+        if let Some(ref title) = self.title {
+            s.push_str(&format!("TITLE \"{}\"\n", title));
+        }
+        s.push_str(&format!("LUT_3D_SIZE {}\n", self.size));
+        let [r0, g0, b0] = self.domain_min;
+        let [r1, g1, b1] = self.domain_max;
+        s.push_str(&format!("DOMAIN_MIN {} {} {}\nDOMAIN_MAX {} {} {}\n", r0, g0, b0, r1, g1, b1));
+        for &[r, g, b] in &self.table {
+            s.push_str(&format!("{} {} {}\n", r, g, b));
+        }
+        writer.write_all(s.as_bytes())
+    }
+}
+
+fn parse_triple(s: &str) -> Option<[f64; 3]> {
+    let mut parts = s.split_whitespace().map(|p| p.parse::<f64>().ok());
+    match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(Some(r)), Some(Some(g)), Some(Some(b)), None) => Some([r, g, b]),
+        _ => None,
+    }
+}
+
+#[inline]
+fn lerp(a: [f64; 3], b: [f64; 3], t: f64) -> [f64; 3] {
+    [a[0] + (b[0] - a[0]) * t, a[1] + (b[1] - a[1]) * t, a[2] + (b[2] - a[2]) * t]
+}
+
+fn trilinear<F: Fn(usize, usize, usize) -> [f64; 3]>(at: &F, t: [f64; 3]) -> [f64; 3] {
+    let [tr, tg, tb] = t;
+    let c00 = lerp(at(0, 0, 0), at(1, 0, 0), tr);
+    let c10 = lerp(at(0, 1, 0), at(1, 1, 0), tr);
+    let c01 = lerp(at(0, 0, 1), at(1, 0, 1), tr);
+    let c11 = lerp(at(0, 1, 1), at(1, 1, 1), tr);
+    lerp(lerp(c00, c10, tg), lerp(c01, c11, tg), tb)
+}
+
+fn tetrahedral<F: Fn(usize, usize, usize) -> [f64; 3]>(at: &F, t: [f64; 3]) -> [f64; 3] {
+    let [tr, tg, tb] = t;
+    // walk from the black corner to the white one along the edges, largest
+    // fraction first, weighting each step by its fraction
+    let steps: [((usize, usize, usize), f64); 3] = if tr > tg {
+        if tg > tb {
+            [((1, 0, 0), tr), ((1, 1, 0), tg), ((1, 1, 1), tb)]
+        } else if tr > tb {
+            [((1, 0, 0), tr), ((1, 0, 1), tb), ((1, 1, 1), tg)]
+        } else {
+            [((0, 0, 1), tb), ((1, 0, 1), tr), ((1, 1, 1), tg)]
+        }
+    } else if tb > tg {
+        [((0, 0, 1), tb), ((0, 1, 1), tg), ((1, 1, 1), tr)]
+    } else if tb > tr {
+        [((0, 1, 0), tg), ((0, 1, 1), tb), ((1, 1, 1), tr)]
+    } else {
+        [((0, 1, 0), tg), ((1, 1, 0), tr), ((1, 1, 1), tb)]
+    };
+    let mut prev = at(0, 0, 0);
+    let mut out = prev;
+    for &((r, g, b), w) in &steps {
+        let next = at(r, g, b);
+        for i in 0..3 {
+            out[i] += (next[i] - prev[i]) * w;
+        }
+        prev = next;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use super::{Error, Interpolation, Lut3d};
+    use {Rgb, Srgb, Srgba};
+
+    const CUBE: &str = "# Created by hand
+TITLE \"Swap\"
+LUT_3D_SIZE 2
+DOMAIN_MIN 0 0 0
+DOMAIN_MAX 1 1 1
+
+0 0 0
+0 0 1
+0 1 0
+0 1 1
+1 0 0
+1 0 1
+1 1 0
+1 1 1
+";
+
+    fn close(a: Rgb<f64>, b: Rgb<f64>) -> bool {
+        (a.r - b.r).abs() < 1e-9 && (a.g - b.g).abs() < 1e-9 && (a.b - b.b).abs() < 1e-9
+    }
+
+    #[test]
+    fn test_identity() {
+        let lut = Lut3d::identity(17);
+        for &c in &[Rgb::new(0.0, 0.0, 0.0), Rgb::new(0.3, 0.7, 0.1), Rgb::new(1.0, 0.5, 0.25), Rgb::new(0.9, 0.9, 0.9)] {
+            assert!(close(lut.apply(&c, Interpolation::Trilinear), c));
+            assert!(close(lut.apply(&c, Interpolation::Tetrahedral), c));
+        }
+        // out of the domain clamps
+        assert!(close(lut.apply(&Rgb::new(-1.0, 2.0, 0.5), Interpolation::Tetrahedral), Rgb::new(0.0, 1.0, 0.5)));
+    }
+
+    #[test]
+    fn test_interpolation() {
+        // both are exact for affine maps
+        let lut = Lut3d::from_fn(3, |[r, g, b]| [1.0 - r, 0.5 * g + 0.25 * b, r]);
+        let c = Rgb::new(0.2, 0.9, 0.6);
+        let expected = Rgb::new(0.8, 0.6, 0.2);
+        assert!(close(lut.apply(&c, Interpolation::Trilinear), expected));
+        assert!(close(lut.apply(&c, Interpolation::Tetrahedral), expected));
+
+        // and differ elsewhere, except at the grid points
+        let lut = Lut3d::from_fn(2, |[r, g, b]| [r * g * b; 3]);
+        let c = Rgb::<f64>::new(0.5, 0.5, 0.5);
+        assert_eq!(lut.apply(&c, Interpolation::Trilinear), Rgb::new(0.125, 0.125, 0.125));
+        assert_eq!(lut.apply(&c, Interpolation::Tetrahedral), Rgb::new(0.5, 0.5, 0.5));
+        assert_eq!(lut.apply(&Rgb::<f64>::new(1.0, 1.0, 0.0), Interpolation::Tetrahedral), Rgb::new(0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_parse() {
+        let lut = Lut3d::parse(CUBE).unwrap();
+        assert_eq!((lut.title(), lut.size()), (Some("Swap"), 2));
+        // red changes fastest, so this table swaps red and blue
+        assert_eq!(lut.get(1, 0, 0), [0.0, 0.0, 1.0]);
+        let c = Srgb::<u8>::new(0xFF, 0x80, 0x00);
+        assert_eq!(lut.apply(&c, Interpolation::Tetrahedral), Srgb::new(0x00, 0x80, 0xFF));
+        let mut pixels = [Srgba::from_color(c, 0x40)];
+        pixels[0] = lut.apply_alpha(&pixels[0], Interpolation::Trilinear);
+        assert_eq!(pixels[0], Srgba::from_color(Srgb::new(0x00, 0x80, 0xFF), 0x40));
+
+        let mut out = Vec::new();
+        lut.write(&mut out).unwrap();
+        assert_eq!(Lut3d::read(&out[..]).unwrap(), lut);
+
+        let lut = Lut3d::parse("LUT_3D_INPUT_RANGE 0 2\nLUT_3D_SIZE 2\n0 0 0\n1 0 0\n0 1 0\n1 1 0\n0 0 1\n1 0 1\n0 1 1\n1 1 1").unwrap();
+        assert_eq!(lut.domain(), ([0.0; 3], [2.0; 3]));
+        assert!(close(lut.apply(&Rgb::new(1.0, 0.5, 2.0), Interpolation::Tetrahedral), Rgb::new(0.5, 0.25, 1.0)));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        match Lut3d::parse("LUT_3D_SIZE 2\n0 0 0\n") {
+            Err(Error::InvalidSize) => {}
+            r => panic!("{:?}", r),
+        }
+        match Lut3d::parse("0 0 0\n") {
+            Err(Error::InvalidSize) => {}
+            r => panic!("{:?}", r),
+        }
+        match Lut3d::parse("LUT_3D_SIZE 2\n0 0 x\n") {
+            Err(Error::InvalidLine(2, ref s)) if s == "0 0 x" => {}
+            r => panic!("{:?}", r),
+        }
+        match Lut3d::parse("LUT_1D_SIZE 4\n") {
+            Err(Error::Unsupported1d) => {}
+            r => panic!("{:?}", r),
+        }
+        assert!(Lut3d::new(1, vec![[0.0; 3]]).is_none());
+    }
+}
@@ -0,0 +1,185 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! http://en.wikipedia.org/wiki/CIELUV
+//!
+//! Unless a white point is given explicitly, `Luv` values are relative to
+//! D65, matching the white point `Xyz` uses for `Rgb`.
+
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use math::*;
+
+use angle::*;
+
+use {Channel, FloatChannel};
+use {Rgb, ToRgb, Srgb, Xyz, ToXyz, WhitePoint, ColorError};
+
+pub(crate) const EPSILON: f64 = 216.0 / 24389.0;
+pub(crate) const KAPPA: f64 = 24389.0 / 27.0;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[repr(C)]
+pub struct Luv<T> { pub l: T, pub u: T, pub v: T }
+
+impl<T> Luv<T> {
+    #[inline]
+    pub const fn new(l: T, u: T, v: T) -> Luv<T> {
+        Luv { l, u, v }
+    }
+}
+
+/// The CIE 1976 UCS chromaticity `(u', v')` of a color.
+#[inline]
+pub(crate) fn uv_prime(x: f64, y: f64, z: f64) -> (f64, f64) {
+    let d = x + 15.0 * y + 3.0 * z;
+    if d == 0.0 { (0.0, 0.0) } else { (4.0 * x / d, 9.0 * y / d) }
+}
+
+impl<T: FloatChannel> Luv<T> {
+    /// The distance of the color from the neutral axis.
+    #[inline]
+    pub fn chroma(&self) -> T {
+        self.u.hypot(self.v)
+    }
+
+    /// The hue angle of the color, in the range `[0, 360)`.
+    #[inline]
+    pub fn hue(&self) -> Deg<T> {
+        let h = self.v.to_channel_f64().atan2(self.u.to_channel_f64()).to_degrees();
+        let h = if h < 0.0 { h + 360.0 } else { h };
+        Deg(Channel::from(if h >= 360.0 { 0.0 } else { h }))
+    }
+
+    /// Converts a color relative to `white`. Fails for a degenerate white.
+    pub fn from_xyz_with_white<C: ToXyz>(color: &C, white: WhitePoint) -> Result<Luv<T>, ColorError> {
+        white.check()?;
+        Ok(Luv::from_xyz_unchecked(color, white))
+    }
+
+    /// Converts the color to XYZ relative to `white`. Fails for a
+    /// degenerate white.
+    pub fn to_xyz_with_white<U: FloatChannel>(&self, white: WhitePoint) -> Result<Xyz<U>, ColorError> {
+        white.check()?;
+        Ok(self.to_xyz_unchecked(white))
+    }
+
+    fn from_xyz_unchecked<C: ToXyz>(color: &C, white: WhitePoint) -> Luv<T> {
+        let xyz = color.to_xyz::<f64>();
+        let yr = xyz.y / white.y;
+        let l = if yr > EPSILON { 116.0 * yr.cbrt() - 16.0 } else { KAPPA * yr };
+        let (u, v) = uv_prime(xyz.x, xyz.y, xyz.z);
+        let (un, vn) = uv_prime(white.x, white.y, white.z);
+        if l == 0.0 {
+            return Luv::new(Channel::from(0.0f64), Channel::from(0.0f64), Channel::from(0.0f64));
+        }
+        Luv::new(Channel::from(l),
+                 Channel::from(13.0 * l * (u - un)),
+                 Channel::from(13.0 * l * (v - vn)))
+    }
+
+    fn to_xyz_unchecked<U: FloatChannel>(self, white: WhitePoint) -> Xyz<U> {
+        let l = self.l.to_channel_f64();
+        if l <= 0.0 {
+            return Xyz::from_array([0.0; 3]);
+        }
+        let (un, vn) = uv_prime(white.x, white.y, white.z);
+        let u = self.u.to_channel_f64() / (13.0 * l) + un;
+        let v = self.v.to_channel_f64() / (13.0 * l) + vn;
+        let y = white.y * if l > KAPPA * EPSILON { ((l + 16.0) / 116.0).powi(3) } else { l / KAPPA };
+        let x = 9.0 * y * u / (4.0 * v);
+        let z = (9.0 * y - 15.0 * v * y - v * x) / (3.0 * v);
+        Xyz::from_array([x, y, z])
+    }
+}
+
+pub trait ToLuv {
+    fn to_luv<U:FloatChannel>(&self) -> Luv<U>;
+}
+
+impl<T:FloatChannel> ToLuv for Luv<T> {
+    #[inline]
+    fn to_luv<U:FloatChannel>(&self) -> Luv<U> {
+        Luv::new(self.l.to_channel(), self.u.to_channel(), self.v.to_channel())
+    }
+}
+
+impl<T:FloatChannel> ToLuv for Xyz<T> {
+    #[inline]
+    fn to_luv<U:FloatChannel>(&self) -> Luv<U> {
+        Luv::from_xyz_unchecked(self, WhitePoint::D65)
+    }
+}
+
+impl<T:Channel> ToLuv for Rgb<T> {
+    #[inline]
+    fn to_luv<U:FloatChannel>(&self) -> Luv<U> {
+        self.to_xyz::<f64>().to_luv()
+    }
+}
+
+impl<T:Channel> ToLuv for Srgb<T> {
+    /// Decodes the sRGB transfer function before converting.
+    #[inline]
+    fn to_luv<U:FloatChannel>(&self) -> Luv<U> {
+        self.to_xyz::<f64>().to_luv()
+    }
+}
+
+impl<T:FloatChannel> ToXyz for Luv<T> {
+    #[inline]
+    fn to_xyz<U:FloatChannel>(&self) -> Xyz<U> {
+        self.to_xyz_unchecked(WhitePoint::D65)
+    }
+}
+
+impl<T:FloatChannel> ToRgb for Luv<T> {
+    #[inline]
+    fn to_rgb<U:Channel>(&self) -> Rgb<U> {
+        self.to_xyz::<f64>().to_rgb()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {Luv, ToLuv, Rgb, ToRgb, Xyz, ToXyz, WhitePoint};
+
+    fn assert_luv_eq(a: Luv<f64>, b: Luv<f64>) {
+        assert!((a.l - b.l).abs() < 1e-3 && (a.u - b.u).abs() < 1e-3 && (a.v - b.v).abs() < 1e-3,
+                "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn test_rgb_to_luv() {
+        assert_luv_eq(Rgb::<f64>::new(1.0, 1.0, 1.0).to_luv(), Luv::new(100.0, 0.0, 0.0));
+        assert_luv_eq(Rgb::<f64>::new(0.0, 0.0, 0.0).to_luv(), Luv::new(0.0, 0.0, 0.0));
+        assert_luv_eq(Rgb::<f64>::new(1.0, 0.0, 0.0).to_luv(), Luv::new(53.2408, 175.0151, 37.7564));
+        assert_luv_eq(Rgb::<f64>::new(0.0, 0.0, 1.0).to_luv(), Luv::new(32.2970, -9.4054, -130.3423));
+    }
+
+    #[test]
+    fn test_luv_roundtrip() {
+        let xyz = Xyz::<f64>::new(0.3, 0.2, 0.5);
+        let back = xyz.to_luv::<f64>().to_xyz::<f64>();
+        assert!((back.x - 0.3).abs() < 1e-9 && (back.y - 0.2).abs() < 1e-9 && (back.z - 0.5).abs() < 1e-9);
+        let d50 = Luv::<f64>::from_xyz_with_white(&WhitePoint::D50.to_xyz::<f64>(), WhitePoint::D50).unwrap();
+        assert_luv_eq(d50, Luv::new(100.0, 0.0, 0.0));
+        let low = Luv::<f64>::new(5.0, 10.0, -3.0);
+        assert_luv_eq(low.to_xyz::<f64>().to_luv(), low);
+        let red = Luv::<f64>::new(53.2408, 175.0151, 37.7564).to_rgb::<f64>();
+        assert!((red.r - 1.0).abs() < 1e-4 && red.g.abs() < 1e-4 && red.b.abs() < 1e-4);
+    }
+}
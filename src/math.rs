@@ -0,0 +1,46 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The float functions that `core` lacks, for builds without `std`.
+//!
+//! With `std` the inherent methods of `f32` and `f64` are used. Without it,
+//! modules import this one to get them from `Float`, which `num-traits`
+//! implements with `libm`, and `rem_euclid`, which `Float` lacks, from here.
+//! A dependency that links `std` brings the inherent methods back, and they
+//! take precedence, so the imports and `RemEuclid` may go unused.
+
+pub use num::Float;
+
+#[allow(dead_code)]
+pub trait RemEuclid {
+    fn rem_euclid(self, rhs: Self) -> Self;
+}
+
+macro_rules! rem_euclid_impls {
+    ($($T:ident)*) => {
+        $(
+            impl RemEuclid for $T {
+                /// As `std` computes it.
+                #[inline]
+                fn rem_euclid(self, rhs: $T) -> $T {
+                    let r = self % rhs;
+                    if r < 0.0 { r + Float::abs(rhs) } else { r }
+                }
+            }
+        )*
+    }
+}
+
+rem_euclid_impls! { f32 f64 }
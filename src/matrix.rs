@@ -0,0 +1,112 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Row-major 3x3 matrix helpers used by the colorimetric conversions.
+
+pub type Matrix3 = [[f64; 3]; 3];
+
+#[inline]
+pub const fn mul_vec(m: &Matrix3, v: [f64; 3]) -> [f64; 3] {
+    [m[0][0] * v[0] + m[0][1] * v[1] + m[0][2] * v[2],
+     m[1][0] * v[0] + m[1][1] * v[1] + m[1][2] * v[2],
+     m[2][0] * v[0] + m[2][1] * v[1] + m[2][2] * v[2]]
+}
+
+pub fn mul(a: &Matrix3, b: &Matrix3) -> Matrix3 {
+    let mut m = [[0.0; 3]; 3];
+    for (i, row) in m.iter_mut().enumerate() {
+        for (j, v) in row.iter_mut().enumerate() {
+            *v = a[i][0] * b[0][j] + a[i][1] * b[1][j] + a[i][2] * b[2][j];
+        }
+    }
+    m
+}
+
+#[inline]
+pub const fn determinant(m: &Matrix3) -> f64 {
+    m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1]) -
+    m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0]) +
+    m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+}
+
+/// Inverts the matrix, returning `None` if it is singular or not finite.
+pub const fn inverse(m: &Matrix3) -> Option<Matrix3> {
+    let det = determinant(m);
+    if det == 0.0 || !det.is_finite() {
+        return None;
+    }
+    let inv = 1.0 / det;
+    Some([[(m[1][1] * m[2][2] - m[1][2] * m[2][1]) * inv,
+           (m[0][2] * m[2][1] - m[0][1] * m[2][2]) * inv,
+           (m[0][1] * m[1][2] - m[0][2] * m[1][1]) * inv],
+          [(m[1][2] * m[2][0] - m[1][0] * m[2][2]) * inv,
+           (m[0][0] * m[2][2] - m[0][2] * m[2][0]) * inv,
+           (m[0][2] * m[1][0] - m[0][0] * m[1][2]) * inv],
+          [(m[1][0] * m[2][1] - m[1][1] * m[2][0]) * inv,
+           (m[0][1] * m[2][0] - m[0][0] * m[2][1]) * inv,
+           (m[0][0] * m[1][1] - m[0][1] * m[1][0]) * inv]])
+}
+
+/// The XYZ of a primary with chromaticity `(x, y)` and luminance 1.
+#[inline]
+const fn column((x, y): (f64, f64)) -> [f64; 3] {
+    [x / y, 1.0, (1.0 - x - y) / y]
+}
+
+/// Builds the matrix taking linear RGB to XYZ for the given primary
+/// chromaticities `(x, y)` and white point tristimulus values, or `None`
+/// if they span no color space, e.g. collinear primaries or a white on an
+/// edge of their triangle.
+///
+/// See http://www.brucelindbloom.com/index.html?Eqn_RGB_XYZ_Matrix.html
+pub const fn rgb_to_xyz(primaries: [(f64, f64); 3], white: [f64; 3]) -> Option<Matrix3> {
+    let (r, g, b) = (column(primaries[0]), column(primaries[1]), column(primaries[2]));
+    let m = [[r[0], g[0], b[0]],
+             [r[1], g[1], b[1]],
+             [r[2], g[2], b[2]]];
+    let s = match inverse(&m) {
+        Some(i) => mul_vec(&i, white),
+        None => return None,
+    };
+    let m = [[s[0] * r[0], s[1] * g[0], s[2] * b[0]],
+             [s[0] * r[1], s[1] * g[1], s[2] * b[1]],
+             [s[0] * r[2], s[1] * g[2], s[2] * b[2]]];
+    match inverse(&m) {
+        Some(_) => Some(m),
+        None => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inverse() {
+        let m = [[2.0, 0.0, 0.0], [0.0, 4.0, 0.0], [1.0, 0.0, 1.0]];
+        let i = inverse(&m).unwrap();
+        assert_eq!(mul_vec(&i, mul_vec(&m, [1.0, 2.0, 3.0])), [1.0, 2.0, 3.0]);
+        assert_eq!(inverse(&[[1.0, 2.0, 3.0], [2.0, 4.0, 6.0], [0.0, 0.0, 1.0]]), None);
+        assert_eq!(inverse(&[[f64::NAN, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]), None);
+    }
+
+    #[test]
+    fn test_rgb_to_xyz() {
+        let primaries = [(0.64, 0.33), (0.30, 0.60), (0.15, 0.06)];
+        assert!(rgb_to_xyz(primaries, [0.95047, 1.0, 1.08883]).is_some());
+        assert_eq!(rgb_to_xyz(primaries, [0.0, 0.0, 0.0]), None);
+        assert_eq!(rgb_to_xyz([(0.1, 0.1), (0.2, 0.2), (0.3, 0.3)], [0.95047, 1.0, 1.08883]), None);
+    }
+}
@@ -0,0 +1,212 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! https://bottosson.github.io/posts/oklab/
+//!
+//! Conversions from `Rgb` treat it as linear light; use `Srgb` for
+//! gamma-encoded values.
+
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use math::*;
+
+use angle::*;
+
+use Color;
+use {Channel, FloatChannel};
+use {Rgb, ToRgb, Srgb, Hsv, Hsl, Xyz, ToXyz};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[repr(C)]
+pub struct Oklab<T> { pub l: T, pub a: T, pub b: T }
+
+impl<T> Oklab<T> {
+    #[inline]
+    pub const fn new(l: T, a: T, b: T) -> Oklab<T> {
+        Oklab { l, a, b }
+    }
+}
+
+impl<T: FloatChannel> Oklab<T> {
+    /// The distance of the color from the neutral axis.
+    #[inline]
+    pub fn chroma(&self) -> T {
+        self.a.hypot(self.b)
+    }
+
+    /// The hue angle of the color, in the range `[0, 360)`.
+    #[inline]
+    pub fn hue(&self) -> Deg<T> {
+        let h = self.b.to_channel_f64().atan2(self.a.to_channel_f64()).to_degrees();
+        Deg(Channel::from(if h < 0.0 { h + 360.0 } else { h }))
+    }
+}
+
+impl<T: FloatChannel> Color<T> for Oklab<T> {
+    /// Clamps the components of the color to the range `(lo,hi)`.
+    #[inline]
+    fn clamp_s(self, lo: T, hi: T) -> Oklab<T> {
+        Oklab::new(Channel::clamp(self.l, lo, hi),
+                   Channel::clamp(self.a, lo, hi),
+                   Channel::clamp(self.b, lo, hi))
+    }
+
+    /// Clamps the components of the color component-wise between `lo` and `hi`.
+    #[inline]
+    fn clamp_c(self, lo: Oklab<T>, hi: Oklab<T>) -> Oklab<T> {
+        Oklab::new(Channel::clamp(self.l, lo.l, hi.l),
+                   Channel::clamp(self.a, lo.a, hi.a),
+                   Channel::clamp(self.b, lo.b, hi.b))
+    }
+
+    /// Inverts the lightness and moves the color to the opposite hue.
+    #[inline]
+    fn inverse(self) -> Oklab<T> {
+        Oklab::new(self.l.invert_channel(), -self.a, -self.b)
+    }
+
+    /// Interpolates linearly in Oklab, which keeps gradients between
+    /// saturated colors from dipping through gray.
+    #[inline]
+    fn mix(self, other: Self, value: T) -> Self {
+        Oklab::new(self.l.mix(other.l, value),
+                   self.a.mix(other.a, value),
+                   self.b.mix(other.b, value))
+    }
+}
+
+pub trait ToOklab {
+    fn to_oklab<U:FloatChannel>(&self) -> Oklab<U>;
+}
+
+impl<T:FloatChannel> ToOklab for Oklab<T> {
+    #[inline]
+    fn to_oklab<U:FloatChannel>(&self) -> Oklab<U> {
+        Oklab::new(self.l.to_channel(), self.a.to_channel(), self.b.to_channel())
+    }
+}
+
+impl<T:Channel> ToOklab for Rgb<T> {
+    fn to_oklab<U:FloatChannel>(&self) -> Oklab<U> {
+        let (r, g, b) = (self.r.to_channel_f64(), self.g.to_channel_f64(), self.b.to_channel_f64());
+
+        let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+        let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+        let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+
+        Oklab::new(Channel::from(0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s),
+                   Channel::from(1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s),
+                   Channel::from(0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s))
+    }
+}
+
+impl<T:Channel> ToOklab for Srgb<T> {
+    /// Decodes the sRGB transfer function before converting.
+    #[inline]
+    fn to_oklab<U:FloatChannel>(&self) -> Oklab<U> {
+        self.to_linear::<f64>().to_oklab()
+    }
+}
+
+impl<T:Channel> ToOklab for Hsv<T> {
+    /// Reads the color as sRGB encoded, as CSS does, and decodes it before
+    /// converting.
+    #[inline]
+    fn to_oklab<U:FloatChannel>(&self) -> Oklab<U> {
+        let c = self.to_rgb::<f64>();
+        Srgb::new(c.r, c.g, c.b).to_oklab()
+    }
+}
+
+impl<T:Channel> ToOklab for Hsl<T> {
+    /// Reads the color as sRGB encoded, as CSS does, and decodes it before
+    /// converting.
+    #[inline]
+    fn to_oklab<U:FloatChannel>(&self) -> Oklab<U> {
+        let c = self.to_rgb::<f64>();
+        Srgb::new(c.r, c.g, c.b).to_oklab()
+    }
+}
+
+impl<T:FloatChannel> ToOklab for Xyz<T> {
+    #[inline]
+    fn to_oklab<U:FloatChannel>(&self) -> Oklab<U> {
+        self.to_rgb::<f64>().to_oklab()
+    }
+}
+
+impl<T:FloatChannel> ToRgb for Oklab<T> {
+    fn to_rgb<U:Channel>(&self) -> Rgb<U> {
+        let (l, a, b) = (self.l.to_channel_f64(), self.a.to_channel_f64(), self.b.to_channel_f64());
+
+        let l_ = l + 0.3963377774 * a + 0.2158037573 * b;
+        let m_ = l - 0.1055613458 * a - 0.0638541728 * b;
+        let s_ = l - 0.0894841775 * a - 1.2914855480 * b;
+        let (l, m, s) = (l_ * l_ * l_, m_ * m_ * m_, s_ * s_ * s_);
+
+        Rgb::new(Channel::from( 4.0767416621 * l - 3.3077115913 * m + 0.2309699292 * s),
+                 Channel::from(-1.2684380046 * l + 2.6097574011 * m - 0.3413193965 * s),
+                 Channel::from(-0.0041960863 * l - 0.7034186147 * m + 1.7076147010 * s))
+    }
+}
+
+impl<T:FloatChannel> ToXyz for Oklab<T> {
+    #[inline]
+    fn to_xyz<U:FloatChannel>(&self) -> Xyz<U> {
+        self.to_rgb::<f64>().to_xyz()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {Oklab, ToOklab, Rgb, ToRgb, Srgb, ToSrgb, Color};
+
+    fn assert_oklab_eq(a: Oklab<f64>, b: Oklab<f64>) {
+        assert!((a.l - b.l).abs() < 1e-3 && (a.a - b.a).abs() < 1e-3 && (a.b - b.b).abs() < 1e-3,
+                "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn test_rgb_to_oklab() {
+        assert_oklab_eq(Rgb::<f64>::new(1.0, 1.0, 1.0).to_oklab(), Oklab::new(1.0, 0.0, 0.0));
+        assert_oklab_eq(Rgb::<f64>::new(0.0, 0.0, 0.0).to_oklab(), Oklab::new(0.0, 0.0, 0.0));
+        assert_oklab_eq(Rgb::<f64>::new(1.0, 0.0, 0.0).to_oklab(), Oklab::new(0.628, 0.225, 0.126));
+        assert_oklab_eq(Rgb::<f64>::new(0.0, 0.0, 1.0).to_oklab(), Oklab::new(0.452, -0.032, -0.312));
+    }
+
+    #[test]
+    fn test_srgb_to_oklab() {
+        assert_oklab_eq(Srgb::<u8>::new(0xFF, 0xFF, 0xFF).to_oklab(), Oklab::new(1.0, 0.0, 0.0));
+        assert_oklab_eq(Srgb::<f64>::new(0.5, 0.5, 0.5).to_oklab(), Oklab::new(0.5981, 0.0, 0.0));
+        let srgb = Oklab::<f64>::new(0.5981, 0.0, 0.0).to_srgb::<f64>();
+        assert!((srgb.r - 0.5).abs() < 1e-3 && (srgb.g - 0.5).abs() < 1e-3 && (srgb.b - 0.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_oklab_roundtrip() {
+        let rgb = Rgb::<f64>::new(0.2, 0.5, 0.8).to_oklab::<f64>().to_rgb::<f64>();
+        assert!((rgb.r - 0.2).abs() < 1e-6 && (rgb.g - 0.5).abs() < 1e-6 && (rgb.b - 0.8).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_oklab_mix() {
+        let a = Oklab::<f64>::new(0.2, 0.1, -0.1);
+        let b = Oklab::<f64>::new(0.8, -0.1, 0.3);
+        assert_oklab_eq(a.mix(b, 0.5), Oklab::new(0.5, 0.0, 0.1));
+        assert_oklab_eq(a.mix(b, 0.0), a);
+        assert_oklab_eq(a.mix(b, 1.0), b);
+    }
+}
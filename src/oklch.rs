@@ -0,0 +1,118 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The cylindrical form of Oklab: lightness, chroma and hue.
+
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use math::*;
+
+use angle::*;
+
+use {Channel, FloatChannel};
+use {Rgb, ToRgb, Srgb, Xyz, Oklab, ToOklab};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Oklch<T: Channel> {
+    pub l: T,
+    pub c: T,
+    #[cfg_attr(feature = "serde", serde(with = "::serialize::deg"))]
+    pub h: Deg<T>,
+}
+
+impl<T: Channel> Oklch<T> {
+    #[inline]
+    pub const fn new(l: T, c: T, h: Deg<T>) -> Oklch<T> {
+        Oklch { l, c, h }
+    }
+}
+
+pub trait ToOklch {
+    fn to_oklch<U:FloatChannel>(&self) -> Oklch<U>;
+}
+
+impl<T:FloatChannel> ToOklch for Oklch<T> {
+    #[inline]
+    fn to_oklch<U:FloatChannel>(&self) -> Oklch<U> {
+        Oklch::new(self.l.to_channel(), self.c.to_channel(), Deg(self.h.value().to_channel()))
+    }
+}
+
+impl<T:FloatChannel> ToOklch for Oklab<T> {
+    #[inline]
+    fn to_oklch<U:FloatChannel>(&self) -> Oklch<U> {
+        let lab = self.to_oklab::<U>();
+        Oklch::new(lab.l, lab.chroma(), lab.hue())
+    }
+}
+
+impl<T:Channel> ToOklch for Rgb<T> {
+    #[inline]
+    fn to_oklch<U:FloatChannel>(&self) -> Oklch<U> {
+        self.to_oklab::<f64>().to_oklch()
+    }
+}
+
+impl<T:Channel> ToOklch for Srgb<T> {
+    #[inline]
+    fn to_oklch<U:FloatChannel>(&self) -> Oklch<U> {
+        self.to_oklab::<f64>().to_oklch()
+    }
+}
+
+impl<T:FloatChannel> ToOklch for Xyz<T> {
+    #[inline]
+    fn to_oklch<U:FloatChannel>(&self) -> Oklch<U> {
+        self.to_oklab::<f64>().to_oklch()
+    }
+}
+
+impl<T:FloatChannel> ToOklab for Oklch<T> {
+    #[inline]
+    fn to_oklab<U:FloatChannel>(&self) -> Oklab<U> {
+        let (sin, cos) = self.h.value().to_channel_f64().to_radians().sin_cos();
+        let c = self.c.to_channel_f64();
+        Oklab::new(self.l.to_channel(), Channel::from(c * cos), Channel::from(c * sin))
+    }
+}
+
+impl<T:FloatChannel> ToRgb for Oklch<T> {
+    #[inline]
+    fn to_rgb<U:Channel>(&self) -> Rgb<U> {
+        self.to_oklab::<f64>().to_rgb()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {Oklch, ToOklch, Oklab, ToOklab, Srgb};
+    use angle::*;
+
+    #[test]
+    fn test_oklab_to_oklch() {
+        assert_eq!(Oklab::<f64>::new(0.5, 0.0, 0.1).to_oklch::<f64>(), Oklch::new(0.5, 0.1, Deg(90.0)));
+        let lab = Oklch::<f64>::new(0.5, 0.1, Deg(90.0)).to_oklab::<f64>();
+        assert!(lab.a.abs() < 1e-9 && (lab.b - 0.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_srgb_to_oklch() {
+        // CSS Color 4 reference value for `red`
+        let lch = Srgb::<u8>::new(0xFF, 0x00, 0x00).to_oklch::<f64>();
+        assert!((lch.l - 0.628).abs() < 1e-3 && (lch.c - 0.2577).abs() < 1e-3 && (lch.h.value() - 29.23).abs() < 1e-1,
+                "{:?}", lch);
+    }
+}
@@ -0,0 +1,244 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Packed pixel formats used by GPUs and embedded displays.
+//!
+//! Packing rounds each channel to the nearest representable value.
+//! Unpacking replicates the high bits of each field into the low bits, so
+//! that the largest field value expands to full intensity, e.g. a 5-bit
+//! `0b11111` becomes `0xFF` rather than `0xF8`.
+
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use math::*;
+
+use Channel;
+use {Rgb, ToRgb, Rgba, ToRgba};
+
+/// Quantizes a channel to `bits` bits, rounding to nearest.
+#[inline]
+fn quantize<T: Channel>(c: T, bits: u32) -> u32 {
+    let max = ((1u32 << bits) - 1) as f64;
+    (c.to_channel_f64().clamp(0.0, 1.0) * max).round() as u32
+}
+
+/// Expands a `bits` wide field to 16 bits by bit replication.
+#[inline]
+fn expand<U: Channel>(v: u32, bits: u32) -> U {
+    let v = v & ((1 << bits) - 1);
+    let mut out = 0u32;
+    let mut shift = 16 - bits as i32;
+    while shift > -(bits as i32) {
+        out |= if shift >= 0 { v << shift } else { v >> -shift };
+        shift -= bits as i32;
+    }
+    Channel::from(out as u16)
+}
+
+/// 16-bit RGB with 5 bits of red in the high bits, 6 of green and 5 of
+/// blue.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[repr(transparent)]
+pub struct Rgb565(pub u16);
+
+impl Rgb565 {
+    pub fn from_rgb<C: ToRgb>(color: &C) -> Rgb565 {
+        let c = color.to_rgb::<f64>();
+        Rgb565((quantize(c.r, 5) << 11 | quantize(c.g, 6) << 5 | quantize(c.b, 5)) as u16)
+    }
+}
+
+impl ToRgb for Rgb565 {
+    #[inline]
+    fn to_rgb<U: Channel>(&self) -> Rgb<U> {
+        let v = self.0 as u32;
+        Rgb::new(expand(v >> 11, 5), expand(v >> 5, 6), expand(v, 5))
+    }
+}
+
+/// 16-bit RGBA with 4 bits per channel, red in the high bits.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[repr(transparent)]
+pub struct Rgba4444(pub u16);
+
+impl Rgba4444 {
+    pub fn from_rgba<C: ToRgba>(color: &C) -> Rgba4444 {
+        let c = color.to_rgba::<f64>();
+        Rgba4444((quantize(c.c.r, 4) << 12 | quantize(c.c.g, 4) << 8 |
+                  quantize(c.c.b, 4) << 4 | quantize(c.a, 4)) as u16)
+    }
+}
+
+impl ToRgba for Rgba4444 {
+    #[inline]
+    fn to_rgba<U: Channel>(&self) -> Rgba<U> {
+        let v = self.0 as u32;
+        Rgba::new(expand(v >> 12, 4), expand(v >> 8, 4), expand(v >> 4, 4), expand(v, 4))
+    }
+}
+
+/// 16-bit RGBA with 5 bits per color channel, red in the high bits, and a
+/// single alpha bit in the lowest bit.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[repr(transparent)]
+pub struct Rgba5551(pub u16);
+
+impl Rgba5551 {
+    pub fn from_rgba<C: ToRgba>(color: &C) -> Rgba5551 {
+        let c = color.to_rgba::<f64>();
+        Rgba5551((quantize(c.c.r, 5) << 11 | quantize(c.c.g, 5) << 6 |
+                  quantize(c.c.b, 5) << 1 | quantize(c.a, 1)) as u16)
+    }
+}
+
+impl ToRgba for Rgba5551 {
+    #[inline]
+    fn to_rgba<U: Channel>(&self) -> Rgba<U> {
+        let v = self.0 as u32;
+        Rgba::new(expand(v >> 11, 5), expand(v >> 6, 5), expand(v >> 1, 5), expand(v, 1))
+    }
+}
+
+/// 32-bit RGBA with 10 bits per color channel and 2 bits of alpha. Red
+/// occupies the lowest bits and alpha the highest, matching
+/// `DXGI_FORMAT_R10G10B10A2_UNORM` and `GL_UNSIGNED_INT_2_10_10_10_REV`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[repr(transparent)]
+pub struct Rgb10a2(pub u32);
+
+impl Rgb10a2 {
+    pub fn from_rgba<C: ToRgba>(color: &C) -> Rgb10a2 {
+        let c = color.to_rgba::<f64>();
+        Rgb10a2(quantize(c.c.r, 10) | quantize(c.c.g, 10) << 10 |
+                quantize(c.c.b, 10) << 20 | quantize(c.a, 2) << 30)
+    }
+}
+
+impl ToRgba for Rgb10a2 {
+    #[inline]
+    fn to_rgba<U: Channel>(&self) -> Rgba<U> {
+        let v = self.0;
+        Rgba::new(expand(v, 10), expand(v >> 10, 10), expand(v >> 20, 10), expand(v >> 30, 2))
+    }
+}
+
+/// The order of the channels in a 32-bit pixel, from the most significant
+/// byte to the least. This is the order of the `u32` value, not of its bytes
+/// in memory; `0xAARRGGBB` is stored as `BB GG RR AA` on little endian
+/// machines.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum PixelLayout {
+    /// `0xAARRGGBB`, as used by Win32, Cairo and Skia on little endian.
+    Argb,
+    /// `0xAABBGGRR`, the value of `RGBA` bytes read as a little endian
+    /// word.
+    Abgr,
+    /// `0xRRGGBBAA`, the layout of `Rgba::to_u32`.
+    Rgba,
+    /// `0xBBGGRRAA`.
+    Bgra,
+}
+
+impl PixelLayout {
+    /// The shifts of red, green, blue and alpha.
+    #[inline]
+    fn shifts(self) -> [u32; 4] {
+        match self {
+            PixelLayout::Argb => [16, 8, 0, 24],
+            PixelLayout::Abgr => [0, 8, 16, 24],
+            PixelLayout::Rgba => [24, 16, 8, 0],
+            PixelLayout::Bgra => [8, 16, 24, 0],
+        }
+    }
+}
+
+impl<T: Channel> Rgba<T> {
+    /// Packs the color into a `u32` with the given channel order.
+    #[inline]
+    pub fn to_u32_with(&self, layout: PixelLayout) -> u32 {
+        let s = layout.shifts();
+        (self.c.r.to_channel_u8() as u32) << s[0] |
+        (self.c.g.to_channel_u8() as u32) << s[1] |
+        (self.c.b.to_channel_u8() as u32) << s[2] |
+        (self.a.to_channel_u8() as u32) << s[3]
+    }
+
+    /// Unpacks a `u32` with the given channel order.
+    #[inline]
+    pub fn from_u32_with(v: u32, layout: PixelLayout) -> Rgba<T> {
+        let s = layout.shifts();
+        let channel = |shift: u32| Channel::from((v >> shift) as u8);
+        Rgba::new(channel(s[0]), channel(s[1]), channel(s[2]), channel(s[3]))
+    }
+
+    #[inline] pub fn to_argb8888(&self) -> u32 { self.to_u32_with(PixelLayout::Argb) }
+    #[inline] pub fn to_abgr8888(&self) -> u32 { self.to_u32_with(PixelLayout::Abgr) }
+    #[inline] pub fn to_rgba8888(&self) -> u32 { self.to_u32_with(PixelLayout::Rgba) }
+    #[inline] pub fn to_bgra8888(&self) -> u32 { self.to_u32_with(PixelLayout::Bgra) }
+
+    #[inline] pub fn from_argb8888(v: u32) -> Rgba<T> { Rgba::from_u32_with(v, PixelLayout::Argb) }
+    #[inline] pub fn from_abgr8888(v: u32) -> Rgba<T> { Rgba::from_u32_with(v, PixelLayout::Abgr) }
+    #[inline] pub fn from_rgba8888(v: u32) -> Rgba<T> { Rgba::from_u32_with(v, PixelLayout::Rgba) }
+    #[inline] pub fn from_bgra8888(v: u32) -> Rgba<T> { Rgba::from_u32_with(v, PixelLayout::Bgra) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Rgb565, Rgba4444, Rgba5551, Rgb10a2, PixelLayout};
+    use {Rgb, ToRgb, Rgba, ToRgba};
+
+    #[test]
+    fn test_rgb565() {
+        assert_eq!(Rgb565::from_rgb(&Rgb::<u8>::new(0xFF, 0x00, 0x00)), Rgb565(0xF800));
+        assert_eq!(Rgb565::from_rgb(&Rgb::<u8>::new(0x00, 0xFF, 0x00)), Rgb565(0x07E0));
+        assert_eq!(Rgb565::from_rgb(&Rgb::<f32>::new(0.0, 0.0, 1.0)), Rgb565(0x001F));
+        assert_eq!(Rgb565(0xFFFF).to_rgb::<u8>(), Rgb::new(0xFF, 0xFF, 0xFF));
+        assert_eq!(Rgb565(0x8410).to_rgb::<u8>(), Rgb::new(0x84, 0x82, 0x84));
+        for v in 0..=0xFFFF_u16 {
+            assert_eq!(Rgb565::from_rgb(&Rgb565(v).to_rgb::<u8>()), Rgb565(v));
+        }
+    }
+
+    #[test]
+    fn test_rgba4444_5551() {
+        assert_eq!(Rgba4444::from_rgba(&Rgba::<u8>::new(0xFF, 0x88, 0x00, 0xFF)), Rgba4444(0xF80F));
+        assert_eq!(Rgba4444(0xF80F).to_rgba::<u8>(), Rgba::new(0xFF, 0x88, 0x00, 0xFF));
+        assert_eq!(Rgba5551::from_rgba(&Rgba::<u8>::new(0xFF, 0x00, 0xFF, 0xFF)), Rgba5551(0xF83F));
+        assert_eq!(Rgba5551::from_rgba(&Rgba::<u8>::new(0xFF, 0x00, 0xFF, 0x40)), Rgba5551(0xF83E));
+        assert_eq!(Rgba5551(0xF83F).to_rgba::<u8>(), Rgba::new(0xFF, 0x00, 0xFF, 0xFF));
+    }
+
+    #[test]
+    fn test_rgb10a2() {
+        let c = Rgb10a2::from_rgba(&Rgba::<f64>::new(1.0, 0.0, 0.5, 1.0));
+        assert_eq!(c, Rgb10a2(0x3FF | 0x200 << 20 | 0x3 << 30));
+        assert_eq!(c.to_rgba::<u16>(), Rgba::new(0xFFFF, 0x0000, 0x8020, 0xFFFF));
+        assert_eq!(Rgb10a2(0x1 << 30).to_rgba::<u8>().a, 0x55);
+    }
+
+    #[test]
+    fn test_pixel_layout() {
+        let c = Rgba::<u8>::new(0x11, 0x22, 0x33, 0x44);
+        assert_eq!(c.to_argb8888(), 0x44112233);
+        assert_eq!(c.to_abgr8888(), 0x44332211);
+        assert_eq!(c.to_rgba8888(), c.to_u32());
+        assert_eq!(c.to_bgra8888(), 0x33221144);
+        for &layout in &[PixelLayout::Argb, PixelLayout::Abgr, PixelLayout::Rgba, PixelLayout::Bgra] {
+            assert_eq!(Rgba::<u8>::from_u32_with(c.to_u32_with(layout), layout), c);
+        }
+        assert_eq!(Rgba::<u8>::from_abgr8888(u32::from_le_bytes([0x11, 0x22, 0x33, 0x44])), c);
+        assert_eq!(Rgba::<f32>::from_argb8888(0xFF0000FF), Rgba::new(0.0, 0.0, 1.0, 1.0));
+    }
+}
@@ -0,0 +1,336 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Fixed palettes with fast nearest color lookup, and the palettes of
+//! the web and of early PC graphics for retro rendering.
+
+use alloc::vec::Vec;
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use math::*;
+
+use {Channel, Srgb, Lab, ToLab};
+use difference::ciede2000;
+
+/// How the distance between colors is measured.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Metric {
+    /// Euclidean distance between the 8-bit components.
+    RgbEuclidean,
+    /// Euclidean distance in Lab.
+    Cie76,
+    /// CIEDE2000. Much slower, as it cannot use the k-d tree.
+    Ciede2000,
+}
+
+/// A list of gamma-encoded sRGB colors, as produced by the quantizers,
+/// with a nearest color query.
+///
+/// The Euclidean metrics are answered with a k-d tree built up front, and
+/// CIEDE2000 by scanning a cached Lab copy of the palette. Ties go to the
+/// earliest entry.
+#[derive(Clone, Debug)]
+pub struct Palette {
+    colors: Vec<Srgb<u8>>,
+    metric: Metric,
+    points: Vec<[f64; 3]>,
+    tree: Vec<KdNode>,
+}
+
+/// The 16 colors of EGA, which VGA keeps as its default text palette.
+/// Index `6` is brown rather than dark yellow.
+pub static EGA: [Srgb<u8>; 16] = [
+    Srgb::new(0x00, 0x00, 0x00), Srgb::new(0x00, 0x00, 0xAA),
+    Srgb::new(0x00, 0xAA, 0x00), Srgb::new(0x00, 0xAA, 0xAA),
+    Srgb::new(0xAA, 0x00, 0x00), Srgb::new(0xAA, 0x00, 0xAA),
+    Srgb::new(0xAA, 0x55, 0x00), Srgb::new(0xAA, 0xAA, 0xAA),
+    Srgb::new(0x55, 0x55, 0x55), Srgb::new(0x55, 0x55, 0xFF),
+    Srgb::new(0x55, 0xFF, 0x55), Srgb::new(0x55, 0xFF, 0xFF),
+    Srgb::new(0xFF, 0x55, 0x55), Srgb::new(0xFF, 0x55, 0xFF),
+    Srgb::new(0xFF, 0xFF, 0x55), Srgb::new(0xFF, 0xFF, 0xFF),
+];
+
+/// The levels of each axis of the web-safe color cube.
+const WEB_SAFE_LEVELS: [u8; 6] = [0x00, 0x33, 0x66, 0x99, 0xCC, 0xFF];
+
+/// The foreground colors of the CGA's four-color graphics mode, which are
+/// drawn from `EGA` and shown on a black background.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CgaPalette {
+    /// Palette 0: green, red and brown.
+    GreenRedBrown,
+    /// Palette 1: cyan, magenta and light gray.
+    CyanMagentaWhite,
+    /// The palette of mode 5: cyan, red and light gray.
+    CyanRedWhite,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct KdNode {
+    index: usize,
+    axis: usize,
+    left: Option<usize>,
+    right: Option<usize>,
+}
+
+impl Palette {
+    /// A palette using `Metric::Cie76`.
+    pub fn new(colors: Vec<Srgb<u8>>) -> Palette {
+        Palette::with_metric(colors, Metric::Cie76)
+    }
+
+    /// The 216 colors of the web-safe palette, a 6x6x6 cube in steps of
+    /// `0x33`, ordered by red, then green, then blue.
+    ///
+    /// Like the other legacy palettes, it uses `Metric::RgbEuclidean`, which
+    /// is how such palettes have always been matched.
+    pub fn web_safe() -> Palette {
+        let mut colors = Vec::with_capacity(216);
+        for &r in &WEB_SAFE_LEVELS {
+            for &g in &WEB_SAFE_LEVELS {
+                for &b in &WEB_SAFE_LEVELS {
+                    colors.push(Srgb::new(r, g, b));
+                }
+            }
+        }
+        Palette::with_metric(colors, Metric::RgbEuclidean)
+    }
+
+    /// The 16 colors of EGA and VGA text mode.
+    #[inline]
+    pub fn ega() -> Palette {
+        Palette::with_metric(EGA.to_vec(), Metric::RgbEuclidean)
+    }
+
+    /// The four colors of a CGA graphics palette, black first. Bright
+    /// palettes use the high-intensity variant of each color.
+    pub fn cga(palette: CgaPalette, bright: bool) -> Palette {
+        let colors = match palette {
+            CgaPalette::GreenRedBrown => [2, 4, 6],
+            CgaPalette::CyanMagentaWhite => [3, 5, 7],
+            CgaPalette::CyanRedWhite => [3, 4, 7],
+        };
+        let mut out = vec![EGA[0]];
+        // brown brightens to yellow
+        out.extend(colors.iter().map(|&i| EGA[if bright { i + 8 } else { i }]));
+        Palette::with_metric(out, Metric::RgbEuclidean)
+    }
+
+    pub fn with_metric(colors: Vec<Srgb<u8>>, metric: Metric) -> Palette {
+        let points: Vec<[f64; 3]> = colors.iter().map(|c| point(metric, c)).collect();
+        let mut tree = Vec::with_capacity(colors.len());
+        if metric != Metric::Ciede2000 {
+            let mut indices: Vec<usize> = (0..colors.len()).collect();
+            build(&points, &mut indices, &mut tree);
+        }
+        Palette { colors, metric, points, tree }
+    }
+
+    #[inline]
+    pub fn colors(&self) -> &[Srgb<u8>] {
+        &self.colors
+    }
+
+    #[inline]
+    pub fn metric(&self) -> Metric {
+        self.metric
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.colors.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.colors.is_empty()
+    }
+
+    /// The index of the entry closest to `color`, or `None` if the palette
+    /// is empty.
+    pub fn nearest(&self, color: &Srgb<u8>) -> Option<usize> {
+        self.nearest_point(point(self.metric, color))
+    }
+
+    /// The entry closest to `color`.
+    #[inline]
+    pub fn nearest_color(&self, color: &Srgb<u8>) -> Option<Srgb<u8>> {
+        self.nearest(color).map(|i| self.colors[i])
+    }
+
+    /// Looks up a point in the metric's space: 8-bit components for
+    /// `RgbEuclidean`, Lab otherwise.
+    pub(crate) fn nearest_point(&self, p: [f64; 3]) -> Option<usize> {
+        if self.colors.is_empty() {
+            return None;
+        }
+        if self.metric == Metric::Ciede2000 {
+            let lab = Lab::new(p[0], p[1], p[2]);
+            let mut best = (0, f64::INFINITY);
+            for (i, q) in self.points.iter().enumerate() {
+                let d = ciede2000(&lab, &Lab::new(q[0], q[1], q[2]));
+                if d < best.1 {
+                    best = (i, d);
+                }
+            }
+            return Some(best.0);
+        }
+        let mut best = (usize::MAX, f64::INFINITY);
+        self.search(0, p, &mut best);
+        Some(best.0)
+    }
+
+    fn search(&self, node: usize, p: [f64; 3], best: &mut (usize, f64)) {
+        let n = self.tree[node];
+        let q = self.points[n.index];
+        let d = (p[0] - q[0]).powi(2) + (p[1] - q[1]).powi(2) + (p[2] - q[2]).powi(2);
+        if d < best.1 || (d == best.1 && n.index < best.0) {
+            *best = (n.index, d);
+        }
+        let diff = p[n.axis] - q[n.axis];
+        let (near, far) = if diff < 0.0 { (n.left, n.right) } else { (n.right, n.left) };
+        if let Some(near) = near {
+            self.search(near, p, best);
+        }
+        if let Some(far) = far {
+            if diff * diff <= best.1 {
+                self.search(far, p, best);
+            }
+        }
+    }
+}
+
+impl From<Vec<Srgb<u8>>> for Palette {
+    #[inline]
+    fn from(colors: Vec<Srgb<u8>>) -> Palette {
+        Palette::new(colors)
+    }
+}
+
+impl<T: Channel> Srgb<T> {
+    /// The entry of `palette` closest to the color. An empty palette leaves
+    /// the color as is.
+    pub fn snap_to(&self, palette: &Palette) -> Srgb<T> {
+        let c: Srgb<u8> = Srgb::new(self.r.to_channel(), self.g.to_channel(), self.b.to_channel());
+        match palette.nearest_color(&c) {
+            Some(c) => Srgb::new(c.r.to_channel(), c.g.to_channel(), c.b.to_channel()),
+            None => *self,
+        }
+    }
+}
+
+pub(crate) fn point(metric: Metric, c: &Srgb<u8>) -> [f64; 3] {
+    match metric {
+        Metric::RgbEuclidean => [c.r as f64, c.g as f64, c.b as f64],
+        Metric::Cie76 | Metric::Ciede2000 => {
+            let lab: Lab<f64> = c.to_lab();
+            [lab.l, lab.a, lab.b]
+        }
+    }
+}
+
+/// Builds the subtree for `indices`, splitting on the axis of widest spread,
+/// and returns its root.
+fn build(points: &[[f64; 3]], indices: &mut [usize], tree: &mut Vec<KdNode>) -> Option<usize> {
+    if indices.is_empty() {
+        return None;
+    }
+    let axis = (0..3).max_by(|&a, &b| {
+        let spread = |axis: usize| {
+            let values = indices.iter().map(|&i| points[i][axis]);
+            values.clone().fold(f64::NEG_INFINITY, f64::max) - values.fold(f64::INFINITY, f64::min)
+        };
+        spread(a).total_cmp(&spread(b))
+    }).unwrap();
+    indices.sort_by(|&a, &b| points[a][axis].total_cmp(&points[b][axis]));
+    let mid = indices.len() / 2;
+    let node = tree.len();
+    tree.push(KdNode { index: indices[mid], axis, left: None, right: None });
+    let (left, rest) = indices.split_at_mut(mid);
+    let left = build(points, left, tree);
+    let right = build(points, &mut rest[1..], tree);
+    tree[node].left = left;
+    tree[node].right = right;
+    Some(node)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn linear_scan(palette: &Palette, c: &Srgb<u8>) -> usize {
+        let p = point(palette.metric(), c);
+        let mut best = (0, f64::INFINITY);
+        for (i, q) in palette.colors().iter().enumerate() {
+            let q = point(palette.metric(), q);
+            let d = match palette.metric() {
+                Metric::Ciede2000 => ciede2000(&Lab::new(p[0], p[1], p[2]), &Lab::new(q[0], q[1], q[2])),
+                _ => (p[0] - q[0]).powi(2) + (p[1] - q[1]).powi(2) + (p[2] - q[2]).powi(2),
+            };
+            if d < best.1 {
+                best = (i, d);
+            }
+        }
+        best.0
+    }
+
+    fn colors() -> Vec<Srgb<u8>> {
+        // a deterministic scatter over the cube
+        (0..97u32).map(|i| Srgb::new((i * 73 % 256) as u8, (i * 151 % 256) as u8, (i * 199 % 256) as u8)).collect()
+    }
+
+    #[test]
+    fn test_matches_linear_scan() {
+        for &metric in [Metric::RgbEuclidean, Metric::Cie76, Metric::Ciede2000].iter() {
+            let palette = Palette::with_metric(colors(), metric);
+            for i in 0..200u32 {
+                let c = Srgb::new((i * 37 % 256) as u8, (i * 101 % 256) as u8, (i * 13 % 256) as u8);
+                assert_eq!(palette.nearest(&c), Some(linear_scan(&palette, &c)), "{:?} {:?}", metric, c);
+            }
+        }
+    }
+
+    #[test]
+    fn test_palette() {
+        let palette = Palette::from(vec![Srgb::new(0, 0, 0), Srgb::new(255, 255, 255), Srgb::new(255, 0, 0)]);
+        assert_eq!(palette.len(), 3);
+        assert_eq!(palette.nearest_color(&Srgb::new(200, 30, 20)), Some(Srgb::new(255, 0, 0)));
+        assert_eq!(palette.nearest(&Srgb::new(250, 250, 240)), Some(1));
+        // ties go to the first entry
+        let dup = Palette::with_metric(vec![Srgb::new(9, 9, 9), Srgb::new(9, 9, 9)], Metric::RgbEuclidean);
+        assert_eq!(dup.nearest(&Srgb::new(9, 9, 9)), Some(0));
+        assert!(Palette::new(vec![]).nearest(&Srgb::new(0, 0, 0)).is_none());
+    }
+
+    #[test]
+    fn test_legacy_palettes() {
+        let web = Palette::web_safe();
+        assert_eq!(web.len(), 216);
+        assert_eq!(web.colors()[215], Srgb::new(0xFF, 0xFF, 0xFF));
+        assert_eq!(Srgb::<u8>::new(0x30, 0x68, 0xF0).snap_to(&web), Srgb::new(0x33, 0x66, 0xFF));
+        assert_eq!(Srgb::<f64>::new(0.2, 0.4, 1.0).snap_to(&web), Srgb::new(0.2, 0.4, 1.0));
+
+        let ega = Palette::ega();
+        assert_eq!(ega.len(), 16);
+        assert_eq!(Srgb::<u8>::new(0xA0, 0x50, 0x10).snap_to(&ega), Srgb::new(0xAA, 0x55, 0x00));
+
+        let cga = Palette::cga(CgaPalette::CyanMagentaWhite, true);
+        assert_eq!(cga.colors(), &[Srgb::new(0x00, 0x00, 0x00), Srgb::new(0x55, 0xFF, 0xFF),
+                                   Srgb::new(0xFF, 0x55, 0xFF), Srgb::new(0xFF, 0xFF, 0xFF)][..]);
+        assert_eq!(Palette::cga(CgaPalette::GreenRedBrown, true).colors()[3], Srgb::new(0xFF, 0xFF, 0x55));
+        assert_eq!(Srgb::<u8>::new(0x20, 0xE0, 0xF0).snap_to(&cga), Srgb::new(0x55, 0xFF, 0xFF));
+        assert_eq!(Srgb::<u8>::new(1, 2, 3).snap_to(&Palette::new(vec![])), Srgb::new(1, 2, 3));
+    }
+}
@@ -0,0 +1,207 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Interoperability with the `palette` crate.
+//!
+//! The colors convert with `From` and `Into` to and from the `palette` types
+//! that mean the same thing, component for component, without changing the
+//! values: `Rgb` is `LinSrgb`, `Srgb` is `palette::Srgb`, and the alpha
+//! forms are `LinSrgba` and `palette::Srgba`. `Xyz`, `Lab` and `Lch` are
+//! relative to D65 on both sides, and `Oklab` and `Oklch` have the same
+//! ranges. Hues are in degrees on both sides, so `Lch` and `Oklch` convert
+//! for float channels only.
+//!
+//! `Luma`, `Hsv` and `Hsl` take the encoding of the color they came from,
+//! while the `palette` types fix it, so they have no conversions here: go
+//! through `Rgb` or `Srgb`.
+
+use palette_crate::{LinSrgb, LinSrgba, LabHue, OklabHue};
+use palette_crate::white_point::D65;
+
+use {Channel, FloatChannel, Rgb, Rgba, Srgb, Srgba, Xyz, Lab, Lch, Oklab, Oklch};
+use angle::Deg;
+
+impl<T: Channel> From<LinSrgb<T>> for Rgb<T> {
+    #[inline]
+    fn from(c: LinSrgb<T>) -> Rgb<T> {
+        Rgb::new(c.red, c.green, c.blue)
+    }
+}
+
+impl<T: Channel> From<Rgb<T>> for LinSrgb<T> {
+    #[inline]
+    fn from(c: Rgb<T>) -> LinSrgb<T> {
+        LinSrgb::new(c.r, c.g, c.b)
+    }
+}
+
+impl<T: Channel> From<palette_crate::Srgb<T>> for Srgb<T> {
+    #[inline]
+    fn from(c: palette_crate::Srgb<T>) -> Srgb<T> {
+        Srgb::new(c.red, c.green, c.blue)
+    }
+}
+
+impl<T: Channel> From<Srgb<T>> for palette_crate::Srgb<T> {
+    #[inline]
+    fn from(c: Srgb<T>) -> palette_crate::Srgb<T> {
+        palette_crate::Srgb::new(c.r, c.g, c.b)
+    }
+}
+
+impl<T: Channel> From<LinSrgba<T>> for Rgba<T> {
+    #[inline]
+    fn from(c: LinSrgba<T>) -> Rgba<T> {
+        Rgba { c: c.color.into(), a: c.alpha }
+    }
+}
+
+impl<T: Channel> From<Rgba<T>> for LinSrgba<T> {
+    #[inline]
+    fn from(c: Rgba<T>) -> LinSrgba<T> {
+        LinSrgba::new(c.c.r, c.c.g, c.c.b, c.a)
+    }
+}
+
+impl<T: Channel> From<palette_crate::Srgba<T>> for Srgba<T> {
+    #[inline]
+    fn from(c: palette_crate::Srgba<T>) -> Srgba<T> {
+        Srgba { c: c.color.into(), a: c.alpha }
+    }
+}
+
+impl<T: Channel> From<Srgba<T>> for palette_crate::Srgba<T> {
+    #[inline]
+    fn from(c: Srgba<T>) -> palette_crate::Srgba<T> {
+        palette_crate::Srgba::new(c.c.r, c.c.g, c.c.b, c.a)
+    }
+}
+
+impl<T> From<palette_crate::Xyz<D65, T>> for Xyz<T> {
+    #[inline]
+    fn from(c: palette_crate::Xyz<D65, T>) -> Xyz<T> {
+        Xyz::new(c.x, c.y, c.z)
+    }
+}
+
+impl<T> From<Xyz<T>> for palette_crate::Xyz<D65, T> {
+    #[inline]
+    fn from(c: Xyz<T>) -> palette_crate::Xyz<D65, T> {
+        palette_crate::Xyz::new(c.x, c.y, c.z)
+    }
+}
+
+impl<T> From<palette_crate::Lab<D65, T>> for Lab<T> {
+    #[inline]
+    fn from(c: palette_crate::Lab<D65, T>) -> Lab<T> {
+        Lab::new(c.l, c.a, c.b)
+    }
+}
+
+impl<T> From<Lab<T>> for palette_crate::Lab<D65, T> {
+    #[inline]
+    fn from(c: Lab<T>) -> palette_crate::Lab<D65, T> {
+        palette_crate::Lab::new(c.l, c.a, c.b)
+    }
+}
+
+impl<T> From<palette_crate::Oklab<T>> for Oklab<T> {
+    #[inline]
+    fn from(c: palette_crate::Oklab<T>) -> Oklab<T> {
+        Oklab::new(c.l, c.a, c.b)
+    }
+}
+
+impl<T> From<Oklab<T>> for palette_crate::Oklab<T> {
+    #[inline]
+    fn from(c: Oklab<T>) -> palette_crate::Oklab<T> {
+        palette_crate::Oklab::new(c.l, c.a, c.b)
+    }
+}
+
+impl<T: FloatChannel> From<palette_crate::Lch<D65, T>> for Lch<T> {
+    #[inline]
+    fn from(c: palette_crate::Lch<D65, T>) -> Lch<T> {
+        Lch { l: c.l, c: c.chroma, h: Deg(c.hue.into_inner()) }
+    }
+}
+
+impl<T: FloatChannel> From<Lch<T>> for palette_crate::Lch<D65, T> {
+    #[inline]
+    fn from(c: Lch<T>) -> palette_crate::Lch<D65, T> {
+        palette_crate::Lch::new_const(c.l, c.c, LabHue::new(c.h.0))
+    }
+}
+
+impl<T: FloatChannel> From<palette_crate::Oklch<T>> for Oklch<T> {
+    #[inline]
+    fn from(c: palette_crate::Oklch<T>) -> Oklch<T> {
+        Oklch { l: c.l, c: c.chroma, h: Deg(c.hue.into_inner()) }
+    }
+}
+
+impl<T: FloatChannel> From<Oklch<T>> for palette_crate::Oklch<T> {
+    #[inline]
+    fn from(c: Oklch<T>) -> palette_crate::Oklch<T> {
+        palette_crate::Oklch::new_const(c.l, c.c, OklabHue::new(c.h.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use palette_crate::{IntoColor, LinSrgb, LinSrgba};
+    use palette_crate::white_point::D65;
+
+    use {Rgb, Rgba, Srgb, Srgba, Lab, ToLab, Lch, Oklab, ToOklab, Oklch, ToSrgb};
+    use angle::Deg;
+
+    #[test]
+    fn test_rgb() {
+        assert_eq!(Rgb::from(LinSrgb::new(0.25f32, 0.5, 1.0)), Rgb::new(0.25, 0.5, 1.0));
+        assert_eq!(palette_crate::Srgb::from(Srgb::new(0x10u8, 0x20, 0x30)), palette_crate::Srgb::new(0x10, 0x20, 0x30));
+        let c = Srgba::from_color(Srgb::new(0x10u8, 0x20, 0x30), 0x40);
+        assert_eq!(Srgba::from(palette_crate::Srgba::from(c)), c);
+        let c = Rgba::new(0.1f64, 0.2, 0.3, 0.4);
+        assert_eq!(Rgba::from(LinSrgba::from(c)), c);
+    }
+
+    #[test]
+    fn test_agrees() {
+        // Converting on either side gives the same colors.
+        let rgb = Rgb::new(0.8f64, 0.3, 0.1);
+        let lab: palette_crate::Lab<D65, f64> = LinSrgb::from(rgb).into_color();
+        let ours = rgb.to_lab::<f64>();
+        assert!((lab.l - ours.l).abs() < 1e-2 && (lab.a - ours.a).abs() < 1e-2 && (lab.b - ours.b).abs() < 1e-2);
+        let oklab: palette_crate::Oklab<f64> = LinSrgb::from(rgb).into_color();
+        let ours = rgb.to_oklab::<f64>();
+        assert!((oklab.l - ours.l).abs() < 1e-4 && (oklab.a - ours.a).abs() < 1e-4 && (oklab.b - ours.b).abs() < 1e-4);
+
+        let srgb: palette_crate::Srgb<f64> = LinSrgb::from(rgb).into_color();
+        let ours = rgb.to_srgb::<f64>();
+        assert!((srgb.red - ours.r).abs() < 1e-6 && (srgb.blue - ours.b).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_cylindrical() {
+        let c = Lch { l: 50.0f32, c: 20.0, h: Deg(120.0) };
+        let p = palette_crate::Lch::<D65, f32>::from(c);
+        assert_eq!((p.l, p.chroma, p.hue.into_degrees()), (50.0, 20.0, 120.0));
+        assert_eq!(Lch::from(p), c);
+        let c = Oklch { l: 0.5f64, c: 0.1, h: Deg(300.0) };
+        assert_eq!(Oklch::from(palette_crate::Oklch::from(c)), c);
+        assert_eq!(Lab::from(palette_crate::Lab::<D65, f32>::from(Lab::new(1.0f32, 2.0, 3.0))), Lab::new(1.0, 2.0, 3.0));
+        assert_eq!(Oklab::from(palette_crate::Oklab::new(0.5f32, 0.1, -0.1)), Oklab::new(0.5, 0.1, -0.1));
+    }
+}
@@ -0,0 +1,411 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Reading and writing palette interchange formats.
+//!
+//! Colors are read as 8-bit `Srgb`. Swatch names are not kept;
+//! when writing a format that has them, each color is named by its hex
+//! code. CMYK swatches are converted naively, without a profile, and Lab
+//! swatches are not supported.
+
+use core::error;
+use core::fmt;
+use std::io::{self, Read, Write};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use {Hsv, Srgb, ToRgb};
+use angle::Deg;
+use palette::Palette;
+
+/// A palette file format.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Format {
+    /// GIMP and Inkscape `.gpl`.
+    Gpl,
+    /// Adobe Color Swatch `.aco`, as written by Photoshop.
+    Aco,
+    /// Adobe Swatch Exchange `.ase`.
+    Ase,
+    /// JASC `.pal`, as used by Paint Shop Pro. Not to be confused with
+    /// RIFF `.pal` files.
+    JascPal,
+}
+
+impl Format {
+    /// Guesses the format from a file extension, without the dot.
+    pub fn from_extension(ext: &str) -> Option<Format> {
+        match &*ext.to_ascii_lowercase() {
+            "gpl" => Some(Format::Gpl),
+            "aco" => Some(Format::Aco),
+            "ase" => Some(Format::Ase),
+            "pal" => Some(Format::JascPal),
+            _ => None,
+        }
+    }
+}
+
+/// The ways in which reading or writing a palette can fail.
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    /// The data does not start with the format's signature.
+    InvalidHeader,
+    /// An entry could not be parsed, or the data ended early.
+    InvalidEntry(String),
+    /// A swatch uses a color model that cannot be converted.
+    UnsupportedColorModel(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Io(ref e) => write!(f, "{}", e),
+            Error::InvalidHeader => write!(f, "not a palette of the expected format"),
+            Error::InvalidEntry(ref s) => write!(f, "invalid palette entry `{}`", s),
+            Error::UnsupportedColorModel(ref s) => write!(f, "unsupported color model `{}`", s),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::Io(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    #[inline]
+    fn from(e: io::Error) -> Error {
+        Error::Io(e)
+    }
+}
+
+/// Reads a palette in the given format.
+pub fn read<R: Read>(mut reader: R, format: Format) -> Result<Palette, Error> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+    let colors = match format {
+        Format::Gpl => read_gpl(&text(&data)?)?,
+        Format::JascPal => read_jasc(&text(&data)?)?,
+        Format::Aco => read_aco(&data)?,
+        Format::Ase => read_ase(&data)?,
+    };
+    Ok(Palette::new(colors))
+}
+
+/// Writes a palette in the given format.
+pub fn write<W: Write>(mut writer: W, palette: &Palette, format: Format) -> Result<(), Error> {
+    let data = match format {
+        Format::Gpl => write_gpl(palette.colors()),
+        Format::JascPal => write_jasc(palette.colors()),
+        Format::Aco => write_aco(palette.colors()),
+        Format::Ase => write_ase(palette.colors()),
+    };
+    writer.write_all(&data)?;
+    Ok(())
+}
+
+fn text(data: &[u8]) -> Result<String, Error> {
+    String::from_utf8(data.to_vec()).map_err(|_| Error::InvalidHeader)
+}
+
+fn hex(c: &Srgb<u8>) -> String {
+    format!("#{:02X}{:02X}{:02X}", c.r, c.g, c.b)
+}
+
+#[inline]
+fn unit_to_byte(v: f64) -> u8 {
+    (v.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+fn parse_rgb(line: &str) -> Result<Srgb<u8>, Error> {
+    let invalid = || Error::InvalidEntry(line.to_string());
+    let mut parts = line.split_whitespace().map(|s| s.parse::<u8>().map_err(|_| invalid()));
+    let mut next = || parts.next().unwrap_or_else(|| Err(invalid()));
+    Ok(Srgb::new(next()?, next()?, next()?))
+}
+
+fn read_gpl(s: &str) -> Result<Vec<Srgb<u8>>, Error> {
+    let mut lines = s.lines();
+    if lines.next().map(str::trim) != Some("GIMP Palette") {
+        return Err(Error::InvalidHeader);
+    }
+    lines.map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#') && !l.starts_with("Name:") && !l.starts_with("Columns:"))
+        .map(|l| {
+            // the components may be followed by a name
+            let components: Vec<&str> = l.split_whitespace().take(3).collect();
+            parse_rgb(&components.join(" ")).map_err(|_| Error::InvalidEntry(l.to_string()))
+        })
+        .collect()
+}
+
+fn write_gpl(colors: &[Srgb<u8>]) -> Vec<u8> {
+    let mut s = String::from("GIMP Palette\nName: color-rs\n#\n");
+    for c in colors {
+        s.push_str(&format!("{:3} {:3} {:3}\t{}\n", c.r, c.g, c.b, hex(c)));
+    }
+    s.into_bytes()
+}
+
+fn read_jasc(s: &str) -> Result<Vec<Srgb<u8>>, Error> {
+    let mut lines = s.lines().map(str::trim).filter(|l| !l.is_empty());
+    if lines.next() != Some("JASC-PAL") || lines.next() != Some("0100") {
+        return Err(Error::InvalidHeader);
+    }
+    let count = lines.next().ok_or(Error::InvalidHeader)?;
+    let count: usize = count.parse().map_err(|_| Error::InvalidEntry(count.to_string()))?;
+    let colors = lines.take(count).map(parse_rgb).collect::<Result<Vec<_>, _>>()?;
+    if colors.len() != count {
+        return Err(Error::InvalidEntry(format!("expected {} colors, found {}", count, colors.len())));
+    }
+    Ok(colors)
+}
+
+fn write_jasc(colors: &[Srgb<u8>]) -> Vec<u8> {
+    let mut s = format!("JASC-PAL\r\n0100\r\n{}\r\n", colors.len());
+    for c in colors {
+        s.push_str(&format!("{} {} {}\r\n", c.r, c.g, c.b));
+    }
+    s.into_bytes()
+}
+
+/// A big-endian reader over a byte slice.
+struct Cursor<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn bytes(&mut self, n: usize) -> Result<&'a [u8], Error> {
+        if self.data.len() - self.pos < n {
+            return Err(Error::InvalidEntry("unexpected end of data".to_string()));
+        }
+        self.pos += n;
+        Ok(&self.data[self.pos - n..self.pos])
+    }
+
+    fn u16(&mut self) -> Result<u16, Error> {
+        let b = self.bytes(2)?;
+        Ok(u16::from_be_bytes([b[0], b[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, Error> {
+        let b = self.bytes(4)?;
+        Ok(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn f32(&mut self) -> Result<f32, Error> {
+        self.u32().map(f32::from_bits)
+    }
+
+    fn at_end(&self) -> bool {
+        self.pos == self.data.len()
+    }
+}
+
+fn push_utf16(out: &mut Vec<u8>, s: &str) {
+    for unit in s.encode_utf16().chain(Some(0)) {
+        out.extend_from_slice(&unit.to_be_bytes());
+    }
+}
+
+fn read_aco(data: &[u8]) -> Result<Vec<Srgb<u8>>, Error> {
+    let mut cur = Cursor { data, pos: 0 };
+    let mut colors = Vec::new();
+    // a version 1 section, optionally followed by a version 2 section with
+    // the same colors and their names
+    while !cur.at_end() {
+        let version = cur.u16()?;
+        if version != 1 && version != 2 {
+            return Err(Error::InvalidHeader);
+        }
+        let count = cur.u16()?;
+        colors.clear();
+        for _ in 0..count {
+            let space = cur.u16()?;
+            let w = [cur.u16()?, cur.u16()?, cur.u16()?, cur.u16()?];
+            if version == 2 {
+                let len = cur.u32()? as usize;
+                cur.bytes(len * 2)?;
+            }
+            colors.push(aco_color(space, w)?);
+        }
+    }
+    if colors.is_empty() && data.is_empty() {
+        return Err(Error::InvalidHeader);
+    }
+    Ok(colors)
+}
+
+fn aco_color(space: u16, w: [u16; 4]) -> Result<Srgb<u8>, Error> {
+    let unit = |v: u16| v as f64 / 65535.0;
+    match space {
+        0 => Ok(Srgb::new(unit_to_byte(unit(w[0])), unit_to_byte(unit(w[1])), unit_to_byte(unit(w[2])))),
+        1 => {
+            let hsv = Hsv::new(Deg(w[0] as f64 / 65535.0 * 360.0), unit(w[1]), unit(w[2]));
+            let c = hsv.to_rgb::<f64>();
+            Ok(Srgb::new(unit_to_byte(c.r), unit_to_byte(c.g), unit_to_byte(c.b)))
+        }
+        // stored inverted: 0 is full ink
+        2 => {
+            let k = unit(w[3]);
+            Ok(Srgb::new(unit_to_byte(unit(w[0]) * k), unit_to_byte(unit(w[1]) * k), unit_to_byte(unit(w[2]) * k)))
+        }
+        8 => {
+            let v = unit_to_byte(1.0 - w[0] as f64 / 10000.0);
+            Ok(Srgb::new(v, v, v))
+        }
+        7 => Err(Error::UnsupportedColorModel("Lab".to_string())),
+        n => Err(Error::UnsupportedColorModel(n.to_string())),
+    }
+}
+
+fn write_aco(colors: &[Srgb<u8>]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for &version in [1u16, 2].iter() {
+        out.extend_from_slice(&version.to_be_bytes());
+        out.extend_from_slice(&(colors.len() as u16).to_be_bytes());
+        for c in colors {
+            for &v in [0, c.r as u16 * 257, c.g as u16 * 257, c.b as u16 * 257, 0].iter() {
+                out.extend_from_slice(&v.to_be_bytes());
+            }
+            if version == 2 {
+                let name = hex(c);
+                out.extend_from_slice(&(name.encode_utf16().count() as u32 + 1).to_be_bytes());
+                push_utf16(&mut out, &name);
+            }
+        }
+    }
+    out
+}
+
+const ASE_COLOR: u16 = 0x0001;
+const ASE_GROUP_START: u16 = 0xC001;
+const ASE_GROUP_END: u16 = 0xC002;
+
+fn read_ase(data: &[u8]) -> Result<Vec<Srgb<u8>>, Error> {
+    let mut cur = Cursor { data, pos: 0 };
+    if cur.bytes(4).ok() != Some(&b"ASEF"[..]) || cur.u16()? != 1 {
+        return Err(Error::InvalidHeader);
+    }
+    cur.u16()?;
+    let blocks = cur.u32()?;
+    let mut colors = Vec::new();
+    for _ in 0..blocks {
+        let kind = cur.u16()?;
+        let len = cur.u32()? as usize;
+        let mut block = Cursor { data: cur.bytes(len)?, pos: 0 };
+        match kind {
+            ASE_COLOR => {
+                let name_len = block.u16()? as usize;
+                block.bytes(name_len * 2)?;
+                let model = block.bytes(4)?;
+                let mut f = || block.f32().map(|v| v as f64);
+                let color = match model {
+                    b"RGB " => Srgb::new(unit_to_byte(f()?), unit_to_byte(f()?), unit_to_byte(f()?)),
+                    b"CMYK" => {
+                        let (c, m, y, k) = (f()?, f()?, f()?, f()?);
+                        let channel = |v: f64| unit_to_byte((1.0 - v) * (1.0 - k));
+                        Srgb::new(channel(c), channel(m), channel(y))
+                    }
+                    b"Gray" => {
+                        let v = unit_to_byte(f()?);
+                        Srgb::new(v, v, v)
+                    }
+                    other => return Err(Error::UnsupportedColorModel(String::from_utf8_lossy(other).trim().to_string())),
+                };
+                colors.push(color);
+            }
+            ASE_GROUP_START | ASE_GROUP_END => {}
+            n => return Err(Error::InvalidEntry(format!("block type {:#06x}", n))),
+        }
+    }
+    Ok(colors)
+}
+
+fn write_ase(colors: &[Srgb<u8>]) -> Vec<u8> {
+    let mut out = b"ASEF".to_vec();
+    out.extend_from_slice(&1u16.to_be_bytes());
+    out.extend_from_slice(&0u16.to_be_bytes());
+    out.extend_from_slice(&(colors.len() as u32).to_be_bytes());
+    for c in colors {
+        let name = hex(c);
+        let mut block = Vec::new();
+        block.extend_from_slice(&(name.encode_utf16().count() as u16 + 1).to_be_bytes());
+        push_utf16(&mut block, &name);
+        block.extend_from_slice(b"RGB ");
+        for &v in [c.r, c.g, c.b].iter() {
+            block.extend_from_slice(&(v as f32 / 255.0).to_be_bytes());
+        }
+        // a global swatch
+        block.extend_from_slice(&0u16.to_be_bytes());
+        out.extend_from_slice(&ASE_COLOR.to_be_bytes());
+        out.extend_from_slice(&(block.len() as u32).to_be_bytes());
+        out.extend_from_slice(&block);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Srgb;
+
+    fn palette() -> Palette {
+        Palette::new(vec![Srgb::new(255, 0, 0), Srgb::new(18, 52, 86), Srgb::new(0, 0, 0), Srgb::new(254, 255, 1)])
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for &format in [Format::Gpl, Format::Aco, Format::Ase, Format::JascPal].iter() {
+            let mut data = Vec::new();
+            write(&mut data, &palette(), format).unwrap();
+            let read = read(&data[..], format).unwrap();
+            assert_eq!(read.colors(), palette().colors(), "{:?}", format);
+        }
+    }
+
+    #[test]
+    fn test_read_text() {
+        let gpl = "GIMP Palette\nName: Test\nColumns: 2\n# comment\n255   0   0\tRed\n  0 128 255 Sky blue\n";
+        assert_eq!(read(gpl.as_bytes(), Format::Gpl).unwrap().colors(),
+                   &[Srgb::new(255, 0, 0), Srgb::new(0, 128, 255)]);
+        let pal = "JASC-PAL\r\n0100\r\n2\r\n1 2 3\r\n4 5 6\r\n";
+        assert_eq!(read(pal.as_bytes(), Format::JascPal).unwrap().colors(),
+                   &[Srgb::new(1, 2, 3), Srgb::new(4, 5, 6)]);
+        assert!(matches!(read(&b"JASC-PAL\n0100\n3\n1 2 3\n"[..], Format::JascPal), Err(Error::InvalidEntry(_))));
+        assert!(matches!(read(&b"GIMP Palette\n1 2\n"[..], Format::Gpl), Err(Error::InvalidEntry(_))));
+        assert!(matches!(read(&b"Not a palette\n"[..], Format::Gpl), Err(Error::InvalidHeader)));
+    }
+
+    #[test]
+    fn test_read_binary() {
+        // version 1 only, with a CMYK swatch of pure cyan and a 50% gray
+        let aco = [0u8, 1, 0, 2, 0, 2, 0, 0, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0, 8, 0x13, 0x88, 0, 0, 0, 0, 0, 0];
+        assert_eq!(read(&aco[..], Format::Aco).unwrap().colors(), &[Srgb::new(0, 255, 255), Srgb::new(128, 128, 128)]);
+        let mut lab = vec![0u8, 1, 0, 1, 0, 7];
+        lab.extend_from_slice(&[0; 8]);
+        assert!(matches!(read(&lab[..], Format::Aco), Err(Error::UnsupportedColorModel(_))));
+        assert!(matches!(read(&b"ASEF\x00\x01\x00\x00\x00\x00\x00\x01\x00\x01"[..], Format::Ase), Err(Error::InvalidEntry(_))));
+        assert!(matches!(read(&b"RIFF"[..], Format::Ase), Err(Error::InvalidHeader)));
+        assert_eq!(Format::from_extension("ASE"), Some(Format::Ase));
+        assert_eq!(Format::from_extension("png"), None);
+    }
+}
@@ -0,0 +1,225 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Mixing colors as paints rather than lights.
+//!
+//! Lights add: half blue and half yellow light is a gray. Paints absorb, and
+//! what is left of a blue paint and a yellow paint mixed is the green both
+//! reflect. To mix this way, each color is given a smooth reflectance
+//! spectrum that has that color under D65, after Jakob and Hanika, "A
+//! Low-Dimensional Function Space for Efficient Spectral Upsampling" (2019).
+//! The spectra are mixed by the single-constant Kubelka-Munk model, and the
+//! mix is seen under D65 again.
+//!
+//! Not every color has such a spectrum: the sRGB primaries are more
+//! saturated than any paint. What the spectrum misses is added back
+//! linearly, so mixing a color with itself gives it back exactly. The blues
+//! that mix to green are those leaning towards cyan, like phthalo blue, as
+//! with real paints; pure `#0000FF` gives a teal.
+
+use alloc::vec::Vec;
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use math::*;
+
+use FromColor;
+use {Rgb, ToRgb};
+use matrix;
+use spectrum::{Spd, VISIBLE_RANGE};
+
+/// The spacing of the spectra, in nanometers.
+const STEP: f64 = 5.0;
+/// The number of samples of the spectra.
+const SAMPLES: usize = 81;
+/// The lowest reflectance, as no paint absorbs all the light.
+const MIN_REFLECTANCE: f64 = 0.01;
+
+/// The spectrum with the sigmoid of the quadratic `c` as its reflectance.
+fn sigmoid_spectrum(c: [f64; 3]) -> Vec<f64> {
+    (0..SAMPLES).map(|i| {
+        let t = i as f64 / (SAMPLES - 1) as f64;
+        let z = (c[0] * t + c[1]) * t + c[2];
+        0.5 + z / (2.0 * (1.0 + z * z).sqrt())
+    }).collect()
+}
+
+/// Reflectances seen under D65.
+struct Viewing {
+    d65: Spd,
+    /// The color of a perfect white, which the tabulated D65 gives a slight
+    /// tint.
+    white: [f64; 3],
+}
+
+impl Viewing {
+    fn new() -> Viewing {
+        let mut viewing = Viewing { d65: Spd::d65(), white: [1.0; 3] };
+        viewing.white = viewing.color(&[1.0; SAMPLES]);
+        viewing
+    }
+
+    /// The linear light color of `reflectance`, with white `[1, 1, 1]`.
+    fn color(&self, reflectance: &[f64]) -> [f64; 3] {
+        let spd = Spd::new(VISIBLE_RANGE.0, STEP, reflectance.to_vec());
+        let rgb: Rgb<f64> = spd.reflectance_to_xyz::<f64>(&self.d65).to_rgb();
+        [rgb.r / self.white[0], rgb.g / self.white[1], rgb.b / self.white[2]]
+    }
+}
+
+/// Compresses the components much as lightness does, so that dark colors
+/// are fitted as closely as light ones.
+#[inline]
+fn perceptual(c: [f64; 3]) -> [f64; 3] {
+    [c[0].cbrt(), c[1].cbrt(), c[2].cbrt()]
+}
+
+/// Fits the sigmoid spectrum to `target` by Gauss-Newton iteration.
+fn fit(target: [f64; 3], viewing: &Viewing) -> Vec<f64> {
+    let goal = perceptual(target);
+    let residual = |c: [f64; 3]| {
+        let p = perceptual(viewing.color(&sigmoid_spectrum(c)));
+        [p[0] - goal[0], p[1] - goal[1], p[2] - goal[2]]
+    };
+    let mut c = [0.0; 3];
+    for _ in 0..50 {
+        let r = residual(c);
+        if r.iter().map(|x| x * x).sum::<f64>() < 1e-12 {
+            break;
+        }
+        let h = 1e-4;
+        let mut jacobian = [[0.0; 3]; 3];
+        for j in 0..3 {
+            let mut cj = c;
+            cj[j] += h;
+            let rj = residual(cj);
+            for i in 0..3 {
+                jacobian[i][j] = (rj[i] - r[i]) / h;
+            }
+        }
+        let step = match matrix::inverse(&jacobian) {
+            Some(inv) => matrix::mul_vec(&inv, [-r[0], -r[1], -r[2]]),
+            None => break,
+        };
+        // large steps overshoot where the sigmoid saturates
+        let len = step.iter().fold(0.0f64, |m, s| m.max(s.abs()));
+        let scale = if len > 20.0 { 20.0 / len } else { 1.0 };
+        for j in 0..3 {
+            c[j] += step[j] * scale;
+        }
+    }
+    sigmoid_spectrum(c)
+}
+
+/// A smooth reflectance spectrum from 380 nm to 780 nm that has the color
+/// of `color` under D65, as near as one exists. Grays are flat.
+pub fn reflectance<C: ToRgb>(color: &C) -> Spd {
+    let rgb = color.to_rgb::<f64>();
+    Spd::new(VISIBLE_RANGE.0, STEP, fit([rgb.r, rgb.g, rgb.b], &Viewing::new()))
+}
+
+#[inline]
+fn ks(r: f64) -> f64 {
+    (1.0 - r) * (1.0 - r) / (2.0 * r)
+}
+
+/// Mixes `a` and `b` as paints, with `t` of `b`, which is clamped to
+/// `[0, 1]`.
+#[inline]
+pub fn mix<C: ToRgb, D: FromColor<Rgb<f64>>>(a: &C, b: &C, t: f64) -> D {
+    let t = t.clamp(0.0, 1.0);
+    mix_many(vec![(a.to_rgb::<f64>(), 1.0 - t), (b.to_rgb::<f64>(), t)]).unwrap()
+}
+
+/// Mixes paints of the `(color, amount)` pairs, or `None` if the amounts
+/// add up to zero. Negative amounts are treated as zero.
+pub fn mix_many<C: ToRgb, D: FromColor<Rgb<f64>>, I: IntoIterator<Item = (C, f64)>>(colors: I) -> Option<D> {
+    let viewing = Viewing::new();
+    let mut ks_sum = vec![0.0; SAMPLES];
+    let mut missing = [0.0; 3];
+    let mut total = 0.0;
+    for (c, w) in colors {
+        let w = w.max(0.0);
+        if w == 0.0 {
+            continue;
+        }
+        let rgb = c.to_rgb::<f64>();
+        let target = [rgb.r, rgb.g, rgb.b];
+        let spectrum: Vec<f64> = fit(target, &viewing).into_iter().map(|r| r.max(MIN_REFLECTANCE)).collect();
+        let seen = viewing.color(&spectrum);
+        for (s, r) in ks_sum.iter_mut().zip(spectrum.iter()) {
+            *s += w * ks(*r);
+        }
+        for i in 0..3 {
+            missing[i] += w * (target[i] - seen[i]);
+        }
+        total += w;
+    }
+    if total <= 0.0 {
+        return None;
+    }
+    let mixed: Vec<f64> = ks_sum.iter().map(|s| {
+        let k = s / total;
+        1.0 + k - (k * k + 2.0 * k).sqrt()
+    }).collect();
+    let seen = viewing.color(&mixed);
+    let [r, g, b] = [0, 1, 2].map(|i| seen[i] + missing[i] / total);
+    Some(D::from_color(&Rgb::new(r, g, b)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{mix, mix_many, reflectance};
+    use {Rgb, Srgb, ToRgb, ToSrgb};
+    use spectrum::Spd;
+
+    #[test]
+    fn test_reflectance() {
+        for &c in &[Srgb::<u8>::new(200, 120, 40), Srgb::new(40, 90, 160), Srgb::new(128, 128, 128)] {
+            let spd = reflectance(&c);
+            assert!(spd.values().iter().all(|&r| (0.0..=1.0).contains(&r)));
+            let back: Srgb<f64> = spd.reflectance_to_xyz::<f64>(&Spd::d65()).to_rgb::<f64>().to_srgb();
+            let c = c.to_srgb::<f64>();
+            assert!((back.r - c.r).abs() < 2e-3 && (back.g - c.g).abs() < 2e-3 && (back.b - c.b).abs() < 2e-3,
+                    "{:?} != {:?}", back, c);
+        }
+        // grays are flat
+        let gray = reflectance(&Rgb::<f64>::new(0.2, 0.2, 0.2));
+        assert!(gray.values().iter().all(|r| (r - 0.2).abs() < 1e-3));
+    }
+
+    #[test]
+    fn test_mix() {
+        let blue = Srgb::<u8>::new(0, 90, 200);
+        let yellow = Srgb::<u8>::new(255, 220, 0);
+        let green: Srgb<u8> = mix(&blue, &yellow, 0.5);
+        assert!(green.g > green.r + 60 && green.g > green.b + 20, "{:?}", green);
+        // mixing the lights is not green at all
+        let (b, y) = (blue.to_rgb::<f64>(), yellow.to_rgb::<f64>());
+        assert!(b.r + y.r > b.g + y.g);
+
+        let close = |a: Srgb<f64>, b: Srgb<u8>| {
+            let b = b.to_srgb::<f64>();
+            (a.r - b.r).abs() < 1e-9 && (a.g - b.g).abs() < 1e-9 && (a.b - b.b).abs() < 1e-9
+        };
+        assert!(close(mix(&blue, &yellow, 0.0), blue));
+        assert!(close(mix(&blue, &yellow, 1.0), yellow));
+        assert!(close(mix(&blue, &blue, 0.3), blue));
+
+        // red in white is a lighter red
+        let pink: Srgb<u8> = mix_many(vec![(Srgb::<u8>::new(255, 255, 255), 0.5), (Srgb::new(200, 0, 0), 0.5)]).unwrap();
+        assert!(pink.r > 200 && pink.g > 0 && pink.g < pink.r, "{:?}", pink);
+        assert_eq!(mix_many::<Srgb<u8>, Srgb<u8>, _>(vec![(blue, 0.0)]), None);
+    }
+}
@@ -0,0 +1,297 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A common interface to the channels of every color type, so that image
+//! operations can be written once over any of them.
+//!
+//! `Pixel` gives the number of channels, their names in memory order, the
+//! position of alpha, indexed access to the channels as values of the
+//! channel type, and conversions through linear `Rgba`. It is implemented
+//! for each color type and its `AlphaColor`, with alpha last.
+//!
+//! Hues are channels too, read and written as the value of their `Deg`, so
+//! per-channel arithmetic such as a blur does not wrap them around; filter
+//! in a rectangular space such as `Oklab` instead. `Rg` has no blue, which
+//! its conversions drop and fill with zero. The packed types are not
+//! pixels here, since their channels are narrower than any channel type.
+
+use core::marker::PhantomData;
+
+use angle::Deg;
+
+use {AlphaColor, Channel, FloatChannel, FromColor, Rgb, ToRgb, Rgba};
+use {Rg, Srgb, Hsv, Hsl, Hwb, Hsi, Hsp, Ryb, Cmyk, Luma, YCbCr};
+use {Xyz, XyY, Lab, Lch, Luv, Lchuv, Oklab, Oklch, Hsluv, Hpluv, Cam16, Cam16Ucs, Jzazbz, ICtCp};
+
+/// A color as an array of channels of one type.
+pub trait Pixel: Copy {
+    type Channel: Channel;
+
+    /// The number of channels, alpha included.
+    const CHANNELS: usize;
+
+    /// The names of the channels in memory order, as in `["r", "g", "b",
+    /// "a"]`, which are the names of the fields.
+    const LAYOUT: &'static [&'static str];
+
+    /// The index of the alpha channel, if there is one.
+    const ALPHA: Option<usize>;
+
+    /// The channel at `index`. Panics if `index` is not less than
+    /// `CHANNELS`.
+    fn channel(&self, index: usize) -> Self::Channel;
+
+    /// Builds a pixel from the channel values `f` gives for each index.
+    fn from_channels<F: FnMut(usize) -> Self::Channel>(f: F) -> Self;
+
+    /// Converts to linear `Rgba`, opaque for colors without alpha.
+    fn to_rgba<U: Channel>(&self) -> Rgba<U>;
+
+    /// Converts from linear `Rgba`, dropping alpha for colors without it.
+    fn from_rgba<U: Channel>(color: &Rgba<U>) -> Self;
+
+    /// Sets the channel at `index`. Panics if `index` is not less than
+    /// `CHANNELS`.
+    #[inline]
+    fn set_channel(&mut self, index: usize, value: Self::Channel) {
+        assert!(index < Self::CHANNELS, "channel index {} out of range", index);
+        let c = *self;
+        *self = Self::from_channels(|i| if i == index { value } else { c.channel(i) });
+    }
+
+    /// Applies `f` to each channel, alpha included.
+    #[inline]
+    fn map_channels<F: FnMut(Self::Channel) -> Self::Channel>(self, mut f: F) -> Self {
+        Self::from_channels(|i| f(self.channel(i)))
+    }
+
+    /// Combines the channels of two pixels pairwise, alpha included.
+    #[inline]
+    fn zip_channels<F>(self, other: Self, mut f: F) -> Self
+        where F: FnMut(Self::Channel, Self::Channel) -> Self::Channel
+    {
+        Self::from_channels(|i| f(self.channel(i), other.channel(i)))
+    }
+}
+
+/// The conversions of the colors without alpha through linear `Rgb`.
+trait ViaRgb {
+    fn via_rgb<U: Channel>(&self) -> Rgb<U>;
+
+    fn from_rgb<U: Channel>(color: &Rgb<U>) -> Self;
+}
+
+macro_rules! via_rgb_impls {
+    ($($ty:ident<$bound:ident>),*) => {
+        $(
+            impl<T: $bound> ViaRgb for $ty<T> {
+                #[inline]
+                fn via_rgb<U: Channel>(&self) -> Rgb<U> {
+                    self.to_rgb()
+                }
+
+                #[inline]
+                fn from_rgb<U: Channel>(color: &Rgb<U>) -> $ty<T> {
+                    $ty::from_color(color)
+                }
+            }
+        )*
+    }
+}
+
+via_rgb_impls! {
+    Rgb<Channel>, Srgb<Channel>, Hsv<Channel>, Hsl<Channel>, Hwb<Channel>, Hsi<Channel>,
+    Hsp<Channel>, Ryb<Channel>, Cmyk<Channel>, Luma<Channel>, YCbCr<Channel>,
+    Xyz<FloatChannel>, XyY<FloatChannel>, Lab<FloatChannel>, Lch<FloatChannel>, Luv<FloatChannel>,
+    Lchuv<FloatChannel>, Oklab<FloatChannel>, Oklch<FloatChannel>, Hsluv<FloatChannel>,
+    Hpluv<FloatChannel>, Cam16<FloatChannel>, Cam16Ucs<FloatChannel>, Jzazbz<FloatChannel>,
+    ICtCp<FloatChannel>
+}
+
+impl<T: Channel> ViaRgb for Rg<T> {
+    #[inline]
+    fn via_rgb<U: Channel>(&self) -> Rgb<U> {
+        Rgb::new(self.r.to_channel(), self.g.to_channel(), U::zero())
+    }
+
+    #[inline]
+    fn from_rgb<U: Channel>(color: &Rgb<U>) -> Rg<T> {
+        Rg { r: color.r.to_channel(), g: color.g.to_channel() }
+    }
+}
+
+macro_rules! get_channel {
+    ($v:expr) => { $v };
+    ($v:expr, deg) => { $v.0 };
+}
+
+macro_rules! make_channel {
+    ($v:expr) => { $v };
+    ($v:expr, deg) => { Deg($v) };
+}
+
+macro_rules! pixel_impls {
+    ($($ty:ident<$bound:ident> { $($field:ident $(: $deg:ident)?),+ $(; $phantom:ident)? } [$n:expr];)*) => {
+        $(
+            impl<T: $bound> Pixel for $ty<T> {
+                type Channel = T;
+
+                const CHANNELS: usize = $n;
+                const LAYOUT: &'static [&'static str] = &[$(stringify!($field)),+];
+                const ALPHA: Option<usize> = None;
+
+                #[inline]
+                fn channel(&self, index: usize) -> T {
+                    [$(get_channel!(self.$field $(, $deg)?)),+][index]
+                }
+
+                #[inline]
+                fn from_channels<F: FnMut(usize) -> T>(mut f: F) -> $ty<T> {
+                    let mut i = 0..$n;
+                    $ty { $($field: make_channel!(f(i.next().unwrap()) $(, $deg)?),)+ $($phantom: PhantomData)? }
+                }
+
+                #[inline]
+                fn to_rgba<U: Channel>(&self) -> Rgba<U> {
+                    AlphaColor { c: ViaRgb::via_rgb(self), a: U::max() }
+                }
+
+                #[inline]
+                fn from_rgba<U: Channel>(color: &Rgba<U>) -> $ty<T> {
+                    ViaRgb::from_rgb(&color.c)
+                }
+            }
+
+            impl<T: $bound> Pixel for AlphaColor<T, $ty<T>> {
+                type Channel = T;
+
+                const CHANNELS: usize = $n + 1;
+                const LAYOUT: &'static [&'static str] = &[$(stringify!($field),)+ "a"];
+                const ALPHA: Option<usize> = Some($n);
+
+                #[inline]
+                fn channel(&self, index: usize) -> T {
+                    [$(get_channel!(self.c.$field $(, $deg)?),)+ self.a][index]
+                }
+
+                #[inline]
+                fn from_channels<F: FnMut(usize) -> T>(mut f: F) -> AlphaColor<T, $ty<T>> {
+                    let mut i = 0..$n + 1;
+                    let c = $ty { $($field: make_channel!(f(i.next().unwrap()) $(, $deg)?),)+ $($phantom: PhantomData)? };
+                    AlphaColor { c, a: f($n) }
+                }
+
+                #[inline]
+                fn to_rgba<U: Channel>(&self) -> Rgba<U> {
+                    AlphaColor { c: ViaRgb::via_rgb(&self.c), a: self.a.to_channel() }
+                }
+
+                #[inline]
+                fn from_rgba<U: Channel>(color: &Rgba<U>) -> AlphaColor<T, $ty<T>> {
+                    AlphaColor { c: ViaRgb::from_rgb(&color.c), a: color.a.to_channel() }
+                }
+            }
+        )*
+    }
+}
+
+pixel_impls! {
+    Rgb<Channel> { r, g, b; encoding } [3];
+    Rg<Channel> { r, g } [2];
+    Srgb<Channel> { r, g, b; encoding } [3];
+    Hsv<Channel> { h: deg, s, v } [3];
+    Hsl<Channel> { h: deg, s, l } [3];
+    Hwb<Channel> { h: deg, w, b } [3];
+    Hsi<Channel> { h: deg, s, i } [3];
+    Hsp<Channel> { h: deg, s, p } [3];
+    Ryb<Channel> { r, y, b } [3];
+    Cmyk<Channel> { c, m, y, k } [4];
+    Luma<Channel> { l } [1];
+    YCbCr<Channel> { y, cb, cr } [3];
+    Xyz<FloatChannel> { x, y, z } [3];
+    XyY<FloatChannel> { x, y, luminance } [3];
+    Lab<FloatChannel> { l, a, b } [3];
+    Lch<FloatChannel> { l, c, h: deg } [3];
+    Luv<FloatChannel> { l, u, v } [3];
+    Lchuv<FloatChannel> { l, c, h: deg } [3];
+    Oklab<FloatChannel> { l, a, b } [3];
+    Oklch<FloatChannel> { l, c, h: deg } [3];
+    Hsluv<FloatChannel> { h: deg, s, l } [3];
+    Hpluv<FloatChannel> { h: deg, p, l } [3];
+    Cam16<FloatChannel> { j, c, h: deg, m, s, q } [6];
+    Cam16Ucs<FloatChannel> { j, a, b } [3];
+    Jzazbz<FloatChannel> { jz, az, bz } [3];
+    ICtCp<FloatChannel> { i, ct, cp } [3];
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use angle::Deg;
+
+    use super::Pixel;
+    use {FromColor, Rgb, Rg, Rgba, Srgb, Srgba, Hsv, Hsva, Oklab, ToOklab, Cmyk, Luma};
+
+    /// A box blur of a row, written once for every pixel type.
+    fn blur<P: Pixel<Channel = f32>>(row: &[P]) -> Vec<P> {
+        (0..row.len()).map(|i| {
+            let window = &row[i.saturating_sub(1)..(i + 2).min(row.len())];
+            P::from_channels(|k| window.iter().map(|p| p.channel(k)).sum::<f32>() / window.len() as f32)
+        }).collect()
+    }
+
+    #[test]
+    fn test_layout() {
+        assert_eq!((Rgb::<u8>::CHANNELS, Rgb::<u8>::LAYOUT, Rgb::<u8>::ALPHA), (3, &["r", "g", "b"][..], None));
+        assert_eq!((Srgba::<u8>::CHANNELS, Srgba::<u8>::LAYOUT, Srgba::<u8>::ALPHA),
+                   (4, &["r", "g", "b", "a"][..], Some(3)));
+        assert_eq!(Hsva::<f32>::LAYOUT, &["h", "s", "v", "a"]);
+        assert_eq!(Cmyk::<u8>::CHANNELS, 4);
+        assert_eq!(Luma::<u16>::LAYOUT, &["l"]);
+    }
+
+    #[test]
+    fn test_channels() {
+        let mut c = Rgba::<u8>::new(0x10, 0x20, 0x30, 0x40);
+        assert_eq!((0..4).map(|i| c.channel(i)).collect::<Vec<_>>(), vec![0x10, 0x20, 0x30, 0x40]);
+        c.set_channel(3, 0xFF);
+        assert_eq!(c, Rgba::new(0x10, 0x20, 0x30, 0xFF));
+        assert_eq!(c.map_channels(|v| v / 0x10), Rgba::new(0x01, 0x02, 0x03, 0x0F));
+        let hsv = Hsv::<f32>::new(Deg(120.0), 0.5, 1.0);
+        assert_eq!(hsv.channel(0), 120.0);
+        assert_eq!(Hsv::from_channels(|i| hsv.channel(i)), hsv);
+        assert_eq!(hsv.zip_channels(hsv, |a, b| a + b), Hsv::new(Deg(240.0), 1.0, 2.0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_channel_out_of_range() {
+        Rgb::<u8>::new(0, 0, 0).channel(3);
+    }
+
+    #[test]
+    fn test_generic() {
+        let rgb = [Rgb::<f32>::new(0.0, 0.0, 0.0), Rgb::new(0.75, 0.375, 1.5), Rgb::new(0.0, 0.0, 0.0)];
+        assert_eq!(blur(&rgb)[1], Rgb::new(0.25, 0.125, 0.5));
+        let lab: Vec<Oklab<f32>> = rgb.iter().map(|c| c.to_oklab()).collect();
+        let blurred = blur(&lab)[1];
+        assert!((blurred.l - lab[1].l / 3.0).abs() < 1e-6);
+
+        assert_eq!(Srgba::<u8>::from_rgba(&Rgba::<f32>::new(1.0, 0.0, 0.0, 0.5)),
+                   Srgba::from_color(Srgb::from_color(&Rgb::<f32>::new(1.0, 0.0, 0.0)), 0x7F));
+        assert_eq!(Srgb::<u8>::new(0xFF, 0x00, 0x00).to_rgba::<u8>(), Rgba::new(0xFF, 0x00, 0x00, 0xFF));
+        assert_eq!(Rg { r: 1u8, g: 2 }.to_rgba::<u8>(), Rgba::new(1, 2, 0, 0xFF));
+    }
+}
@@ -0,0 +1,83 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `bytemuck` impls for the plain-data color types, so buffers of them can
+//! be cast to and from bytes.
+//!
+//! Every type here is `#[repr(C)]` (or `#[repr(transparent)]`) with all
+//! fields of the channel type, so it has no padding whenever the channel
+//! type has none. Types with a hue are left out, as `angle::Deg` makes no
+//! layout guarantees, and so is `Premultiplied`, whose invariant a byte cast
+//! could break.
+
+use bytemuck::{Pod, Zeroable};
+
+use AlphaColor;
+use {Rgb, Rg, Srgb, Luma, Ryb, Cmyk, YCbCr, Xyz, XyY, Lab, Luv, Oklab, Cam16Ucs, Jzazbz, ICtCp};
+use packed::{Rgb565, Rgba4444, Rgba5551, Rgb10a2};
+use gpu::{RgbF32Std140, RgbaF32Std140};
+
+macro_rules! pod_impls {
+    ($($ty:ident),*) => {
+        $(
+            unsafe impl<T: Zeroable> Zeroable for $ty<T> {}
+            unsafe impl<T: Pod> Pod for $ty<T> {}
+
+            // the color and alpha share a type, so there is no padding
+            // between them
+            unsafe impl<T: Zeroable> Zeroable for AlphaColor<T, $ty<T>> {}
+            unsafe impl<T: Pod> Pod for AlphaColor<T, $ty<T>> {}
+        )*
+    }
+}
+
+pod_impls!(Rgb, Rg, Srgb, Luma, Ryb, Cmyk, YCbCr, Xyz, XyY, Lab, Luv, Oklab, Cam16Ucs, Jzazbz, ICtCp);
+
+unsafe impl Zeroable for Rgb565 {}
+unsafe impl Pod for Rgb565 {}
+unsafe impl Zeroable for Rgba4444 {}
+unsafe impl Pod for Rgba4444 {}
+unsafe impl Zeroable for Rgba5551 {}
+unsafe impl Pod for Rgba5551 {}
+unsafe impl Zeroable for Rgb10a2 {}
+unsafe impl Pod for Rgb10a2 {}
+
+// sixteen bytes of `f32`s, the padding of `RgbF32Std140` being a field
+unsafe impl Zeroable for RgbF32Std140 {}
+unsafe impl Pod for RgbF32Std140 {}
+unsafe impl Zeroable for RgbaF32Std140 {}
+unsafe impl Pod for RgbaF32Std140 {}
+
+#[cfg(test)]
+mod tests {
+    use bytemuck::{cast_slice, cast_slice_mut};
+    use {Rgb, Rgba, Srgba};
+    use gpu::RgbF32Std140;
+
+    #[test]
+    fn test_cast() {
+        let pixels = [Rgb::<u8>::new(1, 2, 3), Rgb::new(4, 5, 6)];
+        assert_eq!(cast_slice::<_, u8>(&pixels), &[1, 2, 3, 4, 5, 6]);
+        let rgba = [Rgba::<f32>::new(0.0, 0.25, 0.5, 1.0)];
+        assert_eq!(cast_slice::<_, f32>(&rgba), &[0.0, 0.25, 0.5, 1.0]);
+
+        let mut bytes = [0u8; 8];
+        cast_slice_mut::<u8, Srgba<u8>>(&mut bytes)[1] = Srgba { c: ::Srgb::new(9, 8, 7), a: 6 };
+        assert_eq!(bytes, [0, 0, 0, 0, 9, 8, 7, 6]);
+
+        let uniform = [RgbF32Std140::new(0.25, 0.5, 1.0), RgbF32Std140::new(1.0, 1.0, 1.0)];
+        assert_eq!(cast_slice::<_, f32>(&uniform), &[0.25, 0.5, 1.0, 0.0, 1.0, 1.0, 1.0, 0.0]);
+    }
+}
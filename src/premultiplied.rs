@@ -0,0 +1,119 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Colors with premultiplied (associated) alpha.
+
+use {AlphaColor, Channel, Rgb};
+use blend::Blend;
+use composite::Operator;
+
+/// A color whose components have been multiplied by its alpha. Keeping this
+/// separate from `AlphaColor` means a color can't be premultiplied twice.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Premultiplied<T, C> { c: C, a: T }
+
+pub type PremultipliedRgba<T> = Premultiplied<T, Rgb<T>>;
+
+impl<T: Channel, C: Blend> Premultiplied<T, C> {
+    /// Wraps components that are already premultiplied, returning `None` if
+    /// any of them is negative or exceeds the alpha, which can't happen for
+    /// valid premultiplied data.
+    pub fn from_premultiplied(c: C, a: T) -> Option<Premultiplied<T, C>> {
+        let alpha = a.to_channel_f64();
+        if c.to_components().iter().all(|&v| v >= 0.0 && v <= alpha) {
+            Some(Premultiplied { c, a })
+        } else {
+            None
+        }
+    }
+
+    /// The premultiplied color components.
+    #[inline]
+    pub fn color(&self) -> C {
+        self.c
+    }
+
+    #[inline]
+    pub fn alpha(&self) -> T {
+        self.a
+    }
+
+    /// Divides the alpha back out. A fully transparent color has no color
+    /// information left and comes out black.
+    pub fn unpremultiply(self) -> AlphaColor<T, C> {
+        let a = self.a.to_channel_f64();
+        let c = self.c.to_components();
+        let c = if a == 0.0 { [0.0; 3] } else { [c[0] / a, c[1] / a, c[2] / a] };
+        AlphaColor { c: C::from_components(c), a: self.a }
+    }
+
+    /// Composites `self` as the source onto `dst` with a Porter-Duff
+    /// operator.
+    pub fn composite(self, dst: Premultiplied<T, C>, op: Operator) -> Premultiplied<T, C> {
+        let (c, a) = op.apply(self.c.to_components(), self.a.to_channel_f64(),
+                              dst.c.to_components(), dst.a.to_channel_f64());
+        Premultiplied { c: C::from_components(c), a: Channel::from(a) }
+    }
+
+    /// Composites `self` over `dst`.
+    #[inline]
+    pub fn over(self, dst: Premultiplied<T, C>) -> Premultiplied<T, C> {
+        self.composite(dst, Operator::SourceOver)
+    }
+}
+
+impl<T: Channel, C: Blend> AlphaColor<T, C> {
+    /// Multiplies the color components by the alpha.
+    pub fn premultiply(self) -> Premultiplied<T, C> {
+        let a = self.a.to_channel_f64();
+        let c = self.c.to_components();
+        Premultiplied { c: C::from_components([c[0] * a, c[1] * a, c[2] * a]), a: self.a }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PremultipliedRgba;
+    use composite::Operator;
+    use {Rgb, Rgba};
+
+    #[test]
+    fn test_premultiply() {
+        let c = Rgba::<f64>::new(1.0, 0.5, 0.0, 0.5).premultiply();
+        assert_eq!(c.color(), Rgb::new(0.5, 0.25, 0.0));
+        assert_eq!(c.alpha(), 0.5);
+        assert_eq!(c.unpremultiply(), Rgba::new(1.0, 0.5, 0.0, 0.5));
+        assert_eq!(Rgba::<f64>::new(1.0, 0.5, 0.0, 0.0).premultiply().unpremultiply(), Rgba::new(0.0, 0.0, 0.0, 0.0));
+        assert_eq!(Rgba::<u8>::new(0xFF, 0xFF, 0x00, 0xFF).premultiply().color(), Rgb::new(0xFF, 0xFF, 0x00));
+    }
+
+    #[test]
+    fn test_from_premultiplied() {
+        assert!(PremultipliedRgba::<f64>::from_premultiplied(Rgb::new(0.5, 0.25, 0.0), 0.5).is_some());
+        assert!(PremultipliedRgba::<f64>::from_premultiplied(Rgb::new(0.75, 0.25, 0.0), 0.5).is_none());
+        assert!(PremultipliedRgba::<u8>::from_premultiplied(Rgb::new(0x80, 0x00, 0x00), 0x7F).is_none());
+    }
+
+    #[test]
+    fn test_composite() {
+        let src = Rgba::<f64>::new(1.0, 0.0, 0.0, 0.5).premultiply();
+        let dst = Rgba::<f64>::new(0.0, 0.0, 1.0, 0.5).premultiply();
+        let c = src.over(dst);
+        assert_eq!(c.color(), Rgb::new(0.5, 0.0, 0.25));
+        assert_eq!(c.alpha(), 0.75);
+        assert_eq!(src.composite(dst, Operator::Xor).color(), Rgb::new(0.25, 0.0, 0.25));
+        assert_eq!(c.unpremultiply().c, Rgba::<f64>::new(1.0, 0.0, 0.0, 0.5).over(Rgba::new(0.0, 0.0, 1.0, 0.5)).c);
+    }
+}
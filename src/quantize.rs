@@ -0,0 +1,497 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Reducing images to a small palette.
+//!
+//! `median_cut` and `octree` work directly on the 8-bit components, so they
+//! are fast but not perceptually uniform. `KMeans` refines a median cut
+//! palette in Lab or Oklab and gives better results for artwork at a higher
+//! cost. Palettes have at most 256 entries so that the indices fit in a
+//! byte, as GIF and PNG expect.
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use math::*;
+
+use {Srgb, ToSrgb, Lab, ToLab, Oklab, ToOklab};
+use palette::{Metric, Palette};
+
+/// A palette and, for each input pixel, the index of its palette entry.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Quantized {
+    pub palette: Vec<Srgb<u8>>,
+    pub indices: Vec<u8>,
+}
+
+#[inline]
+fn check_colors(colors: usize) {
+    assert!((1..=256).contains(&colors), "palette size must be between 1 and 256, got {}", colors);
+}
+
+#[inline]
+fn components(c: &Srgb<u8>) -> [u8; 3] {
+    [c.r, c.g, c.b]
+}
+
+/// Assigns each pixel its closest palette entry by squared distance.
+pub(crate) fn nearest_indices(pixels: &[Srgb<u8>], palette: &[Srgb<u8>]) -> Vec<u8> {
+    let palette = Palette::with_metric(palette.to_vec(), Metric::RgbEuclidean);
+    let mut cache = BTreeMap::new();
+    pixels.iter().map(|p| {
+        *cache.entry(components(p)).or_insert_with(|| palette.nearest(p).unwrap() as u8)
+    }).collect()
+}
+
+/// Heckbert's median cut: repeatedly splits the box of pixels with the
+/// widest spread at the median of its widest channel, and takes the mean of
+/// each box.
+///
+/// # Panics
+///
+/// If `colors` is not in `1..=256`.
+pub fn median_cut(pixels: &[Srgb<u8>], colors: usize) -> Quantized {
+    check_colors(colors);
+    let mut work: Vec<[u8; 3]> = pixels.iter().map(components).collect();
+    let mut boxes = if work.is_empty() { vec![] } else { vec![(0, work.len())] };
+
+    while boxes.len() < colors {
+        let widest = boxes.iter().enumerate()
+            .map(|(i, &(start, end))| {
+                let (channel, spread) = widest_channel(&work[start..end]);
+                (i, channel, spread)
+            })
+            .max_by_key(|&(_, _, spread)| spread);
+        let (i, channel) = match widest {
+            Some((i, channel, spread)) if spread > 0 => (i, channel),
+            _ => break,
+        };
+        let (start, end) = boxes[i];
+        work[start..end].sort_unstable_by_key(|c| c[channel]);
+        // never split a run of equal values, so that boxes stay disjoint
+        let box_ = &work[start..end];
+        let median = box_[box_.len() / 2][channel];
+        let mid = match box_.iter().position(|c| c[channel] == median).unwrap() {
+            0 => box_.iter().position(|c| c[channel] > median).unwrap(),
+            i => i,
+        };
+        let mid = start + mid;
+        boxes[i] = (start, mid);
+        boxes.push((mid, end));
+    }
+
+    let palette: Vec<Srgb<u8>> = boxes.iter().map(|&(start, end)| mean(&work[start..end])).collect();
+    let indices = nearest_indices(pixels, &palette);
+    Quantized { palette, indices }
+}
+
+fn widest_channel(colors: &[[u8; 3]]) -> (usize, u8) {
+    let mut lo = [u8::MAX; 3];
+    let mut hi = [0u8; 3];
+    for c in colors {
+        for i in 0..3 {
+            lo[i] = lo[i].min(c[i]);
+            hi[i] = hi[i].max(c[i]);
+        }
+    }
+    (0..3).map(|i| (i, hi[i] - lo[i])).max_by_key(|&(_, s)| s).unwrap()
+}
+
+fn mean(colors: &[[u8; 3]]) -> Srgb<u8> {
+    let mut sum = [0u64; 3];
+    for c in colors {
+        for i in 0..3 {
+            sum[i] += c[i] as u64;
+        }
+    }
+    let n = colors.len() as u64;
+    let avg = |s: u64| ((s + n / 2) / n) as u8;
+    Srgb::new(avg(sum[0]), avg(sum[1]), avg(sum[2]))
+}
+
+const OCTREE_DEPTH: usize = 8;
+
+struct OctreeNode {
+    children: [Option<usize>; 8],
+    sum: [u64; 3],
+    count: u64,
+    leaf: bool,
+    index: u8,
+}
+
+impl OctreeNode {
+    fn new(leaf: bool) -> OctreeNode {
+        OctreeNode { children: [None; 8], sum: [0; 3], count: 0, leaf, index: 0 }
+    }
+}
+
+#[inline]
+fn octant(c: [u8; 3], level: usize) -> usize {
+    let shift = 7 - level;
+    ((((c[0] >> shift) & 1) << 2) | (((c[1] >> shift) & 1) << 1) | ((c[2] >> shift) & 1)) as usize
+}
+
+/// Gervautz and Purgathofer's octree quantizer: builds an octree of all
+/// pixels and merges the deepest nodes until few enough leaves remain. The
+/// palette may end up with slightly fewer than `colors` entries.
+///
+/// # Panics
+///
+/// If `colors` is not in `1..=256`.
+pub fn octree(pixels: &[Srgb<u8>], colors: usize) -> Quantized {
+    check_colors(colors);
+    let mut nodes = vec![OctreeNode::new(false)];
+    let mut reducible: Vec<Vec<usize>> = vec![vec![]; OCTREE_DEPTH];
+    reducible[0].push(0);
+    let mut leaves = 0;
+
+    for p in pixels {
+        let c = components(p);
+        let mut node = 0;
+        for level in 0..OCTREE_DEPTH {
+            if nodes[node].leaf {
+                break;
+            }
+            let o = octant(c, level);
+            node = match nodes[node].children[o] {
+                Some(child) => child,
+                None => {
+                    let leaf = level + 1 == OCTREE_DEPTH;
+                    nodes.push(OctreeNode::new(leaf));
+                    let child = nodes.len() - 1;
+                    nodes[node].children[o] = Some(child);
+                    if leaf {
+                        leaves += 1;
+                    } else {
+                        reducible[level + 1].push(child);
+                    }
+                    child
+                }
+            };
+        }
+        let n = &mut nodes[node];
+        for (s, &v) in n.sum.iter_mut().zip(c.iter()) {
+            *s += v as u64;
+        }
+        n.count += 1;
+    }
+
+    while leaves > colors {
+        let node = reducible.iter_mut().rev().find_map(|level| level.pop()).unwrap();
+        let mut merged = 0;
+        for o in 0..8 {
+            if let Some(child) = nodes[node].children[o].take() {
+                let (sum, count) = (nodes[child].sum, nodes[child].count);
+                for (s, &v) in nodes[node].sum.iter_mut().zip(sum.iter()) {
+                    *s += v;
+                }
+                nodes[node].count += count;
+                merged += 1;
+            }
+        }
+        nodes[node].leaf = true;
+        leaves = leaves + 1 - merged;
+    }
+
+    let mut palette = Vec::with_capacity(leaves);
+    let mut stack = if pixels.is_empty() { vec![] } else { vec![0] };
+    while let Some(node) = stack.pop() {
+        if nodes[node].leaf {
+            let n = &mut nodes[node];
+            n.index = palette.len() as u8;
+            let avg = |s: u64| ((s + n.count / 2) / n.count) as u8;
+            palette.push(Srgb::new(avg(n.sum[0]), avg(n.sum[1]), avg(n.sum[2])));
+        } else {
+            stack.extend(nodes[node].children.iter().rev().filter_map(|&c| c));
+        }
+    }
+
+    let indices = pixels.iter().map(|p| {
+        let c = components(p);
+        let mut node = 0;
+        let mut level = 0;
+        while !nodes[node].leaf {
+            node = nodes[node].children[octant(c, level)].unwrap();
+            level += 1;
+        }
+        nodes[node].index
+    }).collect();
+    Quantized { palette, indices }
+}
+
+/// The space `KMeans` clusters in.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum KMeansSpace {
+    Lab,
+    Oklab,
+}
+
+impl KMeansSpace {
+    /// Coordinates scaled so that distances are comparable to ΔE*ab.
+    fn coordinates(self, c: &Srgb<u8>) -> [f64; 3] {
+        match self {
+            KMeansSpace::Lab => {
+                let lab: Lab<f64> = c.to_lab();
+                [lab.l, lab.a, lab.b]
+            }
+            KMeansSpace::Oklab => {
+                let lab: Oklab<f64> = c.to_oklab();
+                [lab.l * 100.0, lab.a * 100.0, lab.b * 100.0]
+            }
+        }
+    }
+
+    fn to_srgb(self, c: [f64; 3]) -> Srgb<u8> {
+        let srgb: Srgb<f64> = match self {
+            KMeansSpace::Lab => Lab::new(c[0], c[1], c[2]).to_srgb(),
+            KMeansSpace::Oklab => Oklab::new(c[0] / 100.0, c[1] / 100.0, c[2] / 100.0).to_srgb(),
+        };
+        let byte = |v: f64| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+        Srgb::new(byte(srgb.r), byte(srgb.g), byte(srgb.b))
+    }
+}
+
+/// A k-means quantizer clustering in a perceptual space.
+///
+/// Clustering starts from a median cut palette and runs until no palette
+/// entry moves by more than the tolerance, or the iteration limit is
+/// reached.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct KMeans {
+    colors: usize,
+    max_iterations: usize,
+    tolerance: f64,
+    space: KMeansSpace,
+}
+
+impl KMeans {
+    /// A quantizer producing at most `colors` entries, clustering in Oklab
+    /// for up to 16 iterations with a tolerance of `0.5`.
+    ///
+    /// # Panics
+    ///
+    /// If `colors` is not in `1..=256`.
+    pub fn new(colors: usize) -> KMeans {
+        check_colors(colors);
+        KMeans { colors, max_iterations: 16, tolerance: 0.5, space: KMeansSpace::Oklab }
+    }
+
+    /// Limits the number of refinement passes. `0` returns the median cut
+    /// palette unchanged.
+    pub fn max_iterations(mut self, max_iterations: usize) -> KMeans {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Stops once no entry moves by more than `tolerance`, measured as ΔE*ab
+    /// (Oklab distances are scaled by 100 to match).
+    pub fn tolerance(mut self, tolerance: f64) -> KMeans {
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn space(mut self, space: KMeansSpace) -> KMeans {
+        self.space = space;
+        self
+    }
+
+    pub fn quantize(&self, pixels: &[Srgb<u8>]) -> Quantized {
+        let mut weights: BTreeMap<[u8; 3], u64> = BTreeMap::new();
+        for p in pixels {
+            *weights.entry(components(p)).or_insert(0) += 1;
+        }
+        let unique: Vec<([f64; 3], u64)> = weights.iter()
+            .map(|(c, &n)| (self.space.coordinates(&Srgb::new(c[0], c[1], c[2])), n))
+            .collect();
+
+        let mut centroids: Vec<[f64; 3]> = median_cut(pixels, self.colors).palette.iter()
+            .map(|c| self.space.coordinates(c))
+            .collect();
+        for _ in 0..self.max_iterations {
+            let mut sums = vec![([0.0; 3], 0u64); centroids.len()];
+            for &(c, n) in &unique {
+                let (sum, count) = &mut sums[nearest(&centroids, c)];
+                for (s, v) in sum.iter_mut().zip(c.iter()) {
+                    *s += v * n as f64;
+                }
+                *count += n;
+            }
+            let mut moved: f64 = 0.0;
+            for (centroid, &(sum, count)) in centroids.iter_mut().zip(sums.iter()) {
+                if count == 0 {
+                    continue;
+                }
+                let next = [sum[0] / count as f64, sum[1] / count as f64, sum[2] / count as f64];
+                moved = moved.max(squared_distance(*centroid, next).sqrt());
+                *centroid = next;
+            }
+            if moved <= self.tolerance {
+                break;
+            }
+        }
+
+        let palette: Vec<Srgb<u8>> = centroids.iter().map(|&c| self.space.to_srgb(c)).collect();
+        let lookup: BTreeMap<[u8; 3], u8> = weights.keys()
+            .map(|&c| (c, nearest(&centroids, self.space.coordinates(&Srgb::new(c[0], c[1], c[2]))) as u8))
+            .collect();
+        let indices = pixels.iter().map(|p| lookup[&components(p)]).collect();
+        Quantized { palette, indices }
+    }
+}
+
+/// The `k` colors that best represent `pixels`, each with the fraction of
+/// the pixels it stands for, most common first. Clustering is done with
+/// `KMeans` in Oklab, so the colors are averages that need not occur in the
+/// image. Fewer than `k` colors come back if the image has fewer distinct
+/// ones.
+///
+/// # Panics
+///
+/// If `k` is not in `1..=256`.
+pub fn dominant_colors(pixels: &[Srgb<u8>], k: usize) -> Vec<(Srgb<u8>, f64)> {
+    let q = KMeans::new(k).space(KMeansSpace::Oklab).quantize(pixels);
+    let mut counts = vec![0u64; q.palette.len()];
+    for &i in &q.indices {
+        counts[i as usize] += 1;
+    }
+    let mut colors: Vec<(Srgb<u8>, u64)> = q.palette.into_iter().zip(counts)
+        .filter(|&(_, n)| n > 0)
+        .collect();
+    colors.sort_by_key(|&(_, n)| ::core::cmp::Reverse(n));
+    colors.into_iter().map(|(c, n)| (c, n as f64 / pixels.len() as f64)).collect()
+}
+
+#[inline]
+fn squared_distance(a: [f64; 3], b: [f64; 3]) -> f64 {
+    (a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)
+}
+
+fn nearest(centroids: &[[f64; 3]], c: [f64; 3]) -> usize {
+    let mut best = (0, f64::INFINITY);
+    for (i, &centroid) in centroids.iter().enumerate() {
+        let d = squared_distance(centroid, c);
+        if d < best.1 {
+            best = (i, d);
+        }
+    }
+    best.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn distance(a: &Srgb<u8>, b: &Srgb<u8>) -> u32 {
+        let d = |x: u8, y: u8| (x as i32 - y as i32).pow(2) as u32;
+        d(a.r, b.r) + d(a.g, b.g) + d(a.b, b.b)
+    }
+
+    fn image() -> Vec<Srgb<u8>> {
+        let mut pixels = Vec::new();
+        for i in 0..64u32 {
+            let v = (i * 4) as u8;
+            pixels.push(Srgb::new(v, 0, 0));
+            pixels.push(Srgb::new(0, v, 0));
+            pixels.push(Srgb::new(0, 0, v));
+            pixels.push(Srgb::new(v, v, v));
+        }
+        pixels
+    }
+
+    /// Checks the shape of the result and returns the total squared error.
+    fn error(q: &Quantized, pixels: &[Srgb<u8>], colors: usize) -> u64 {
+        assert!(!q.palette.is_empty() && q.palette.len() <= colors);
+        assert_eq!(q.indices.len(), pixels.len());
+        pixels.iter().zip(q.indices.iter()).map(|(p, &i)| distance(p, &q.palette[i as usize]) as u64).sum()
+    }
+
+    #[test]
+    fn test_median_cut() {
+        let pixels = image();
+        let errors: Vec<u64> = [1, 4, 16, 256].iter().map(|&n| error(&median_cut(&pixels, n), &pixels, n)).collect();
+        assert!(errors[0] > errors[1] && errors[1] > errors[2]);
+        assert_eq!(errors[3], 0);
+        assert_eq!(median_cut(&pixels, 16).palette.len(), 16);
+        // few distinct colors are kept exactly
+        let two = [Srgb::new(1, 2, 3), Srgb::new(200, 100, 0), Srgb::new(1, 2, 3)];
+        let q = median_cut(&two, 8);
+        assert_eq!(q.palette.len(), 2);
+        assert_eq!(q.palette[q.indices[0] as usize], Srgb::new(1, 2, 3));
+        assert_eq!(q.palette[q.indices[1] as usize], Srgb::new(200, 100, 0));
+        assert!(median_cut(&[], 4).palette.is_empty());
+    }
+
+    #[test]
+    fn test_octree() {
+        let pixels = image();
+        let errors: Vec<u64> = [4, 16, 64, 256].iter().map(|&n| error(&octree(&pixels, n), &pixels, n)).collect();
+        assert!(errors[0] > errors[1] && errors[1] > errors[2]);
+        assert_eq!(errors[3], 0);
+        let q = octree(&pixels, 1);
+        assert_eq!(q.palette.len(), 1);
+        let two = [Srgb::new(1, 2, 3), Srgb::new(200, 100, 0), Srgb::new(1, 2, 3)];
+        let q = octree(&two, 8);
+        assert_eq!(q.palette.len(), 2);
+        assert_eq!(q.indices[0], q.indices[2]);
+        assert_eq!(q.palette[q.indices[1] as usize], Srgb::new(200, 100, 0));
+        assert!(octree(&[], 4).palette.is_empty());
+    }
+
+    #[test]
+    fn test_kmeans() {
+        let pixels = image();
+        // k-means minimizes the squared error in its own space
+        let error_in = |space: KMeansSpace, q: &Quantized| -> f64 {
+            pixels.iter().zip(q.indices.iter()).map(|(p, &i)| {
+                squared_distance(space.coordinates(p), space.coordinates(&q.palette[i as usize]))
+            }).sum()
+        };
+        let baseline = median_cut(&pixels, 8);
+        for &space in [KMeansSpace::Lab, KMeansSpace::Oklab].iter() {
+            let q = KMeans::new(8).space(space).quantize(&pixels);
+            assert!(q.palette.len() <= 8);
+            assert_eq!(q.indices.len(), pixels.len());
+            assert!(error_in(space, &q) < error_in(space, &baseline), "{:?}", space);
+        }
+        // without iterations the median cut palette comes back
+        let q = KMeans::new(8).max_iterations(0).quantize(&pixels);
+        assert_eq!(q.palette.len(), median_cut(&pixels, 8).palette.len());
+        assert!(KMeans::new(4).tolerance(0.0).quantize(&[]).palette.is_empty());
+    }
+
+    #[test]
+    fn test_dominant_colors() {
+        let mut pixels = vec![Srgb::new(200, 30, 30); 60];
+        pixels.extend(vec![Srgb::new(20, 40, 180); 30]);
+        pixels.extend(vec![Srgb::new(240, 240, 240); 10]);
+        let colors = dominant_colors(&pixels, 3);
+        assert_eq!(colors.len(), 3);
+        assert_eq!(colors[0], (Srgb::new(200, 30, 30), 0.6));
+        assert_eq!(colors[1], (Srgb::new(20, 40, 180), 0.3));
+        assert_eq!(colors[2], (Srgb::new(240, 240, 240), 0.1));
+        // the weights always add up to one
+        let colors = dominant_colors(&image(), 5);
+        assert!((colors.iter().map(|&(_, w)| w).sum::<f64>() - 1.0).abs() < 1e-9);
+        assert!(colors.windows(2).all(|w| w[0].1 >= w[1].1));
+        assert!(dominant_colors(&[], 4).is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_too_many_colors() {
+        median_cut(&image(), 257);
+    }
+}
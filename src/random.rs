@@ -0,0 +1,159 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Sampling random colors with `rand`.
+//!
+//! `Standard` samples each channel with its own `Standard` distribution, so
+//! integer channels cover their whole range and float channels `[0, 1)`.
+//! Hues are uniform over `[0, 360)`.
+
+use angle::Deg;
+use rand::Rng;
+use rand::distributions::{Distribution, Standard};
+
+use {Channel, FloatChannel, AlphaColor, FromColor};
+use {Rgb, Srgb, Hsv, Hsl, Lab};
+
+impl<T> Distribution<Rgb<T>> for Standard where Standard: Distribution<T> {
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Rgb<T> {
+        Rgb::new(rng.gen(), rng.gen(), rng.gen())
+    }
+}
+
+impl<T> Distribution<Srgb<T>> for Standard where Standard: Distribution<T> {
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Srgb<T> {
+        Srgb::new(rng.gen(), rng.gen(), rng.gen())
+    }
+}
+
+impl<T, C> Distribution<AlphaColor<T, C>> for Standard
+    where Standard: Distribution<T> + Distribution<C>
+{
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> AlphaColor<T, C> {
+        AlphaColor { c: rng.gen(), a: rng.gen() }
+    }
+}
+
+#[inline]
+fn random_hue<T: FloatChannel, R: Rng + ?Sized>(rng: &mut R) -> Deg<T> {
+    Deg(Channel::from(rng.gen::<f64>() * 360.0))
+}
+
+impl<T: FloatChannel> Distribution<Hsv<T>> for Standard where Standard: Distribution<T> {
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Hsv<T> {
+        Hsv::new(random_hue(rng), rng.gen(), rng.gen())
+    }
+}
+
+impl<T: FloatChannel> Distribution<Hsl<T>> for Standard where Standard: Distribution<T> {
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Hsl<T> {
+        Hsl::new(random_hue(rng), rng.gen(), rng.gen())
+    }
+}
+
+/// Samples colors of a random hue with a fixed saturation and value.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct RandomHue<T> {
+    pub saturation: T,
+    pub value: T,
+}
+
+impl<T: FloatChannel> RandomHue<T> {
+    #[inline]
+    pub fn new(saturation: T, value: T) -> RandomHue<T> {
+        RandomHue { saturation, value }
+    }
+}
+
+impl<T: FloatChannel> Distribution<Hsv<T>> for RandomHue<T> {
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Hsv<T> {
+        Hsv::new(random_hue(rng), self.saturation, self.value)
+    }
+}
+
+/// Samples colors uniformly from the ball of CIE76 ΔE `radius` around
+/// `center` in CIELAB. The samples may fall outside the sRGB gamut.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct WithinDeltaE {
+    pub center: Lab<f64>,
+    pub radius: f64,
+}
+
+impl WithinDeltaE {
+    #[inline]
+    pub fn new(center: Lab<f64>, radius: f64) -> WithinDeltaE {
+        WithinDeltaE { center, radius }
+    }
+}
+
+impl<C: FromColor<Lab<f64>>> Distribution<C> for WithinDeltaE {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> C {
+        // rejection sampling from the enclosing cube accepts about half
+        // the candidates
+        loop {
+            let (x, y, z) = (rng.gen_range(-1.0..=1.0), rng.gen_range(-1.0..=1.0), rng.gen_range(-1.0..=1.0));
+            if x * x + y * y + z * z <= 1.0 {
+                let c = self.center;
+                return C::from_color(&Lab::new(c.l + x * self.radius, c.a + y * self.radius, c.b + z * self.radius));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng};
+    use rand::rngs::StdRng;
+
+    use super::{RandomHue, WithinDeltaE};
+    use {Rgb, Rgba, Hsv, Lab, Srgb};
+    use difference::cie76;
+
+    #[test]
+    fn test_standard() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let _: Rgb<u8> = rng.gen();
+        let _: Rgba<u16> = rng.gen();
+        for _ in 0..100 {
+            let c: Rgb<f32> = rng.gen();
+            assert!(c.r >= 0.0 && c.r < 1.0 && c.g >= 0.0 && c.g < 1.0 && c.b >= 0.0 && c.b < 1.0);
+            let h: Hsv<f64> = rng.gen();
+            assert!(h.h.0 >= 0.0 && h.h.0 < 360.0);
+        }
+    }
+
+    #[test]
+    fn test_constrained() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let hue = RandomHue::new(0.8f32, 0.6);
+        for _ in 0..100 {
+            let c: Hsv<f32> = rng.sample(hue);
+            assert!(c.s == 0.8 && c.v == 0.6 && c.h.0 >= 0.0 && c.h.0 < 360.0);
+        }
+        let center = Lab::new(50.0, 10.0, -20.0);
+        let near = WithinDeltaE::new(center, 5.0);
+        for _ in 0..100 {
+            let c: Lab<f64> = rng.sample(near);
+            assert!(cie76(&c, &center) <= 5.0 + 1e-9);
+        }
+        let _: Srgb<u8> = rng.sample(near);
+    }
+}
@@ -0,0 +1,238 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Color rendering of light sources: how faithfully surfaces lit by a
+//! source keep the colors they have under a reference illuminant of the
+//! same correlated color temperature.
+//!
+//! Both metrics compare a set of sample reflectances, which are passed in
+//! as `Spd`s. The standard sets, the CIE's test color samples for CRI and
+//! the 99 color evaluation samples of IES TM-30, are published by their
+//! bodies and not bundled; scores are only comparable between runs on the
+//! same samples.
+//!
+//! `tm30` follows TM-30-18, with two deviations: colors are computed with
+//! the CIE 1931 2° observer rather than the 1964 10° one, and CAM16-UCS
+//! stands in for CAM02-UCS.
+
+use core::f64::consts::PI;
+use alloc::vec::Vec;
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use math::*;
+
+use Xyz;
+use cam16::{Cam16, Cam16Ucs, ViewingConditions, Surround};
+use spectrum::Spd;
+
+/// The CIE color rendering index of a source.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Cri {
+    /// The general index Ra, the mean of the first eight special indices.
+    pub ra: f64,
+    /// The special index of each sample, at most `100`.
+    pub special: Vec<f64>,
+    /// The correlated color temperature of the source in kelvin.
+    pub cct: f64,
+    /// The distance of the source from the Planckian locus. The CIE deems
+    /// the index meaningless beyond `0.0054`.
+    pub duv: f64,
+}
+
+/// The IES TM-30 fidelity and gamut indices of a source.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Tm30 {
+    /// Fidelity, from `0` to `100`.
+    pub rf: f64,
+    /// Gamut, `100` when saturation is kept, higher when the source makes
+    /// colors more saturated and lower when it makes them duller.
+    pub rg: f64,
+    /// The correlated color temperature of the source in kelvin.
+    pub cct: f64,
+    /// The distance of the source from the Planckian locus.
+    pub duv: f64,
+}
+
+/// The reference illuminant of the CIE color rendering index at `cct`: a
+/// black body below 5000 K, and CIE daylight from there up.
+pub fn reference_illuminant(cct: f64) -> Spd {
+    if cct < 5000.0 { Spd::blackbody(cct) } else { Spd::daylight(cct) }
+}
+
+/// The reference illuminant of TM-30 at `cct`: a black body below 4000 K,
+/// CIE daylight above 5000 K, and a mix of the two, of equal luminance, in
+/// between.
+fn tm30_reference(cct: f64) -> Spd {
+    if cct < 4000.0 {
+        return Spd::blackbody(cct);
+    }
+    if cct > 5000.0 {
+        return Spd::daylight(cct);
+    }
+    let (planck, daylight) = (Spd::blackbody(cct), Spd::daylight(cct));
+    let (yp, yd) = (planck.to_xyz::<f64>().y, daylight.to_xyz::<f64>().y);
+    let m = (cct - 4000.0) / 1000.0;
+    Spd::from_fn(|nm| (1.0 - m) * planck.value_at(nm) / yp + m * daylight.value_at(nm) / yd)
+}
+
+/// The CIE 1960 UCS coordinates of `xyz`.
+#[inline]
+fn uv(xyz: &Xyz<f64>) -> (f64, f64) {
+    let d = xyz.x + 15.0 * xyz.y + 3.0 * xyz.z;
+    (4.0 * xyz.x / d, 6.0 * xyz.y / d)
+}
+
+/// The `c` and `d` coordinates of the CIE's von Kries transform in uv.
+#[inline]
+fn cd((u, v): (f64, f64)) -> (f64, f64) {
+    ((4.0 - u - 10.0 * v) / v, (1.708 * v + 0.404 - 1.481 * u) / v)
+}
+
+/// CIE 1964 U*V*W* of a color with luminance `y` out of `100`, relative to
+/// the white `w`.
+#[inline]
+fn uvw((u, v): (f64, f64), y: f64, w: (f64, f64)) -> [f64; 3] {
+    let w_star = 25.0 * y.cbrt() - 17.0;
+    [13.0 * w_star * (u - w.0), 13.0 * w_star * (v - w.1), w_star]
+}
+
+#[inline]
+fn distance(a: [f64; 3], b: [f64; 3]) -> f64 {
+    ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)).sqrt()
+}
+
+/// The CIE 1995 color rendering index of `source` over `samples`, by
+/// CIE 13.3. `None` if there are no samples.
+pub fn cri(source: &Spd, samples: &[Spd]) -> Option<Cri> {
+    if samples.is_empty() {
+        return None;
+    }
+    let (cct, duv) = source.temperature_tint();
+    let reference = reference_illuminant(cct);
+    let (wk, wr) = (uv(&source.to_xyz()), uv(&reference.to_xyz()));
+    let ((ck, dk), (cr, dr)) = (cd(wk), cd(wr));
+    let special: Vec<f64> = samples.iter().map(|sample| {
+        let (test, refl): (Xyz<f64>, Xyz<f64>) = (sample.reflectance_to_xyz(source), sample.reflectance_to_xyz(&reference));
+        // adapt the test color to the reference white
+        let (ci, di) = cd(uv(&test));
+        let den = 16.518 + 1.481 * cr / ck * ci - dr / dk * di;
+        let adapted = ((10.872 + 0.404 * cr / ck * ci - 4.0 * dr / dk * di) / den, 5.520 / den);
+        let e = distance(uvw(adapted, test.y * 100.0, wr), uvw(uv(&refl), refl.y * 100.0, wr));
+        100.0 - 4.6 * e
+    }).collect();
+    let general = &special[..special.len().min(8)];
+    let ra = general.iter().sum::<f64>() / general.len() as f64;
+    Some(Cri { ra, special, cct, duv })
+}
+
+/// The ANSI/IES TM-30-18 fidelity and gamut indices of `source` over
+/// `samples`. `None` if there are no samples, or if `source` is dark and
+/// so has no white point.
+pub fn tm30(source: &Spd, samples: &[Spd]) -> Option<Tm30> {
+    if samples.is_empty() {
+        return None;
+    }
+    let (cct, duv) = source.temperature_tint();
+    let reference = tm30_reference(cct);
+    let ucs = |illuminant: &Spd| {
+        let vc = ViewingConditions::new(illuminant.white_point(), 100.0, 20.0, Surround::Average, true).ok()?;
+        Some(samples.iter().map(|s| {
+            let xyz: Xyz<f64> = s.reflectance_to_xyz(illuminant);
+            let ucs: Cam16Ucs<f64> = Cam16Ucs::from_cam16(&Cam16::<f64>::from_xyz_with(&xyz, &vc));
+            [ucs.j, ucs.a, ucs.b]
+        }).collect::<Vec<_>>())
+    };
+    let (test, refr) = (ucs(source)?, ucs(&reference)?);
+    let mean_de = test.iter().zip(refr.iter()).map(|(t, r)| distance(*t, *r)).sum::<f64>() / samples.len() as f64;
+    let rf = 10.0 * (((100.0 - 6.73 * mean_de) / 10.0).exp() + 1.0).ln();
+
+    // average each set in 16 bins of reference hue, and compare the areas
+    // of the polygons through the averages
+    let mut bins = [([0.0; 2], [0.0; 2], 0); 16];
+    for (t, r) in test.iter().zip(refr.iter()) {
+        let hue = r[2].atan2(r[1]).rem_euclid(2.0 * PI);
+        let bin = &mut bins[((hue / (2.0 * PI) * 16.0) as usize).min(15)];
+        bin.0[0] += t[1];
+        bin.0[1] += t[2];
+        bin.1[0] += r[1];
+        bin.1[1] += r[2];
+        bin.2 += 1;
+    }
+    let points: Vec<([f64; 2], [f64; 2])> = bins.iter()
+        .filter(|b| b.2 > 0)
+        .map(|&(t, r, n)| {
+            let n = n as f64;
+            ([t[0] / n, t[1] / n], [r[0] / n, r[1] / n])
+        })
+        .collect();
+    let area = |p: &dyn Fn(usize) -> [f64; 2]| {
+        (0..points.len()).map(|i| {
+            let (a, b) = (p(i), p((i + 1) % points.len()));
+            a[0] * b[1] - b[0] * a[1]
+        }).sum::<f64>().abs() / 2.0
+    };
+    let (at, ar) = (area(&|i| points[i].0), area(&|i| points[i].1));
+    let rg = if ar > 0.0 { 100.0 * at / ar } else { 100.0 };
+    Some(Tm30 { rf, rg, cct, duv })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use super::{cri, tm30, reference_illuminant, tm30_reference};
+    use spectrum::Spd;
+
+    /// Smooth reflectances peaking around the spectrum, standing in for the
+    /// standard samples.
+    fn samples() -> Vec<Spd> {
+        (0..16).map(|i| {
+            let peak = 400.0 + 22.0 * i as f64;
+            Spd::from_fn(|nm| 0.2 + 0.6 * (-((nm - peak) / 50.0).powi(2)).exp())
+        }).collect()
+    }
+
+    #[test]
+    fn test_reference_sources() {
+        let samples = samples();
+        for &t in &[2700.0, 4500.0, 6500.0] {
+            let source = reference_illuminant(t);
+            let c = cri(&source, &samples).unwrap();
+            assert!((c.ra - 100.0).abs() < 0.5, "{}: {:?}", t, c);
+            assert!((c.cct - t).abs() / t < 0.01, "{:?}", c);
+            let m = tm30(&tm30_reference(t), &samples).unwrap();
+            assert!((m.rf - 100.0).abs() < 0.5 && (m.rg - 100.0).abs() < 0.5, "{}: {:?}", t, m);
+        }
+        assert_eq!(cri(&Spd::d65(), &[]), None);
+    }
+
+    #[test]
+    fn test_fluorescent() {
+        let samples = samples();
+        let f2 = cri(&Spd::f2(), &samples).unwrap();
+        assert!(f2.ra < 90.0 && f2.ra > 30.0, "{:?}", f2);
+        assert!((f2.cct - 4230.0).abs() < 50.0, "{:?}", f2);
+        assert_eq!(f2.special.len(), 16);
+        assert!(tm30(&Spd::f2(), &samples).unwrap().rf < 95.0);
+
+        // three narrow lines render worse than a broad fluorescent
+        let lines = Spd::from_fn(|nm| {
+            [(450.0, 1.0), (540.0, 1.6), (610.0, 1.4)].iter()
+                .map(|&(c, a)| a * (-((nm - c) / 8.0).powi(2)).exp())
+                .sum()
+        });
+        let rgb = tm30(&lines, &samples).unwrap();
+        assert!(rgb.rf < tm30(&Spd::f2(), &samples).unwrap().rf, "{:?}", rgb);
+    }
+}
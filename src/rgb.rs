@@ -13,34 +13,71 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use num;
-use num::traits::{self, Float, Zero, Saturating};
-use std::ops::{Mul, Div, Add, Sub, Index, IndexMut};
-use std::slice;
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use math::*;
+
+use num::{Zero, Saturating};
+use core::ops::{Mul, Div, Add, Sub, Neg, AddAssign, SubAssign, MulAssign, DivAssign};
+use core::iter::Sum;
+use core::fmt;
+use core::marker::PhantomData;
 
 use angle::*;
 
 use AlphaColor;
 use {Color, FloatColor};
 use {Channel, FloatChannel};
+use channel::cast;
+use encoding::{Encoding, Linear};
 use {Hsv, ToHsv};
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
-pub struct Rgb<T> { pub r: T, pub g: T, pub b: T }
+/// A color in RGB.
+///
+/// With integer channels, `Rgb` and the other types without a hue are `Eq`,
+/// `Hash` and `Ord`, so they can key maps. The order is lexicographic in the
+/// order of the fields and has no meaning beyond that. Hues compare modulo
+/// a turn, so the types with one only get `PartialEq`.
+///
+/// `E` is the transfer encoding of the channels. It defaults to linear
+/// light, and `Srgb<T>` is `Rgb<T, encoding::Srgb>`; see `encoding`.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(C)]
+pub struct Rgb<T, E: Encoding = Linear> {
+    pub r: T,
+    pub g: T,
+    pub b: T,
+    pub(crate) encoding: PhantomData<E>,
+}
+
+impl<T: fmt::Debug, E: Encoding> fmt::Debug for Rgb<T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct(E::NAME).field("r", &self.r).field("g", &self.g).field("b", &self.b).finish()
+    }
+}
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[repr(C)]
 pub struct Rg<T> { pub r: T, pub g: T }
 
-fn cast<T: num::NumCast, U: num::NumCast>(n: T) -> U {
-    traits::cast(n).unwrap()
+impl<T, E: Encoding> Rgb<T, E> {
+    #[inline]
+    pub const fn new(r: T, g: T, b: T) -> Rgb<T, E> {
+        Rgb { r, g, b, encoding: PhantomData }
+    }
 }
 
 impl<T:Channel> Rgb<T> {
+    
+    /// Packs the color into a `0xRRGGBB` value.
     #[inline]
-    pub fn new(r: T, g: T, b: T) -> Rgb<T> {
-        Rgb { r: r, g: g, b: b }
+    pub fn to_u32(&self) -> u32 {
+        ((self.r.to_channel_u8() as u32) << 16) |
+        ((self.g.to_channel_u8() as u32) << 8) |
+        (self.b.to_channel_u8() as u32)
     }
-    
+
     #[inline]
     pub fn rg(&self) -> Rg<T> {
         Rg{r: self.r, g: self.g}
@@ -73,45 +110,45 @@ impl<T:Channel> Rgb<T> {
     
     #[inline]
     pub fn rgb(&self) -> Rgb<T> {
-        Rgb{r: self.r, g: self.g, b: self.b}
+        Rgb::new(self.r, self.g, self.b)
     }
     
     #[inline]
     pub fn rbg(&self) -> Rgb<T> {
-        Rgb{r: self.r, g: self.b, b: self.g}
+        Rgb::new(self.r, self.b, self.g)
     }
     
     #[inline]
     pub fn bgr(&self) -> Rgb<T> {
-        Rgb{r: self.b, g: self.g, b: self.r}
+        Rgb::new(self.b, self.g, self.r)
     }
     
     #[inline]
     pub fn brg(&self) -> Rgb<T> {
-        Rgb{r: self.b, g: self.r, b: self.g}
+        Rgb::new(self.b, self.r, self.g)
     }
     
     #[inline]
     pub fn grb(&self) -> Rgb<T> {
-        Rgb{r: self.g, g: self.r, b: self.b}
+        Rgb::new(self.g, self.r, self.b)
     }
     
     #[inline]
     pub fn gbr(&self) -> Rgb<T> {
-        Rgb{r: self.g, g: self.b, b: self.r}
+        Rgb::new(self.g, self.b, self.r)
     }
 }
 
 #[macro_export]
 macro_rules! rgb{
     ( $r: expr, $g: expr, $b: expr ) => {
-        Rgb{ r: $r, g: $g, b: $b } 
+        Rgb::<_>::new($r, $g, $b) 
     };
     ( $rg: expr, $b: expr ) => {
-        Rgb{ r: $rg.r, g: $rg.g, b: $b } 
+        Rgb::<_>::new($rg.r, $rg.g, $b) 
     };
     ( $r: expr, $gb: expr ) => {
-        Rgb{ r: $r, g: $gb.r, b: $gb.g } 
+        Rgb::<_>::new($r, $gb.r, $gb.g) 
     };
 }
 
@@ -119,9 +156,7 @@ impl<T:Channel> Color<T> for Rgb<T> {
     /// Clamps the components of the color to the range `(lo,hi)`.
     #[inline]
     fn clamp_s(self, lo: T, hi: T) -> Rgb<T> {
-        Rgb::new(self.r.clamp(lo, hi),
-                 self.g.clamp(lo, hi),
-                 self.b.clamp(lo, hi))
+        self.map(|c| c.clamp(lo, hi))
     }
 
     /// Clamps the components of the color component-wise between `lo` and `hi`.
@@ -135,16 +170,12 @@ impl<T:Channel> Color<T> for Rgb<T> {
     /// Inverts the color.
     #[inline]
     fn inverse(self) -> Rgb<T> {
-        Rgb::new(self.r.invert_channel(),
-                 self.g.invert_channel(),
-                 self.b.invert_channel())
+        self.map(Channel::invert_channel)
     }
     
     #[inline]
     fn mix(self, other: Self, value: T) -> Self {
-        rgb!(self.r.mix(other.r, value),
-             self.g.mix(other.g, value),
-             self.b.mix(other.b, value)) 
+        self.zip_with(other, |a, b| a.mix(b, value))
     }
 }
 
@@ -152,9 +183,7 @@ impl<T:FloatChannel> FloatColor<T> for Rgb<T> {
     /// Clamps the components of the color to the range `(0,1)`.
     #[inline]
     fn saturate(self) -> Rgb<T> {
-        Rgb::new(self.r.saturate(),
-                 self.g.saturate(),
-                 self.b.saturate())
+        self.map(FloatChannel::saturate)
     }
 }
 
@@ -167,7 +196,7 @@ impl ToRgb for u32 {
     fn to_rgb<U:Channel>(&self) -> Rgb<U> {
         let r: u8 = cast((*self >> 16) & 0xff);
         let g: u8 = cast((*self >> 8) & 0xff);
-        let b: u8 = cast((*self >> 0) & 0xff); 
+        let b: u8 = cast(*self & 0xff); 
         let r: U = Channel::from(r);
         let g: U = Channel::from(g);
         let b: U = Channel::from(b); 
@@ -175,12 +204,23 @@ impl ToRgb for u32 {
     }
 }
 
+impl ToRgb for u64 {
+    #[inline]
+    fn to_rgb<U:Channel>(&self) -> Rgb<U> {
+        let r: u16 = cast((*self >> 32) & 0xffff);
+        let g: u16 = cast((*self >> 16) & 0xffff);
+        let b: u16 = cast(*self & 0xffff);
+        let r: U = Channel::from(r);
+        let g: U = Channel::from(g);
+        let b: U = Channel::from(b);
+        rgb!(r, g, b)
+    }
+}
+
 impl<T:Clone + Channel> ToRgb for Rgb<T> {
     #[inline]
     fn to_rgb<U:Channel>(&self) -> Rgb<U> {
-        Rgb::new(self.r.to_channel(),
-                 self.g.to_channel(),
-                 self.b.to_channel())
+        self.map(Channel::to_channel)
     }
 }
 
@@ -196,9 +236,7 @@ impl<T:Channel> Mul for Rgb<T> {
 
     #[inline]
     fn mul(self, rhs: Rgb<T>) -> Rgb<T> {
-        Rgb::new(self.r.normalized_mul(rhs.r),
-                 self.g.normalized_mul(rhs.g),
-                 self.b.normalized_mul(rhs.b))
+        self.zip_with(rhs, Channel::normalized_mul)
     }
 }
 
@@ -207,9 +245,7 @@ impl<T:Channel + Mul<T,Output=T>> Mul<T> for Rgb<T> {
 
     #[inline]
     fn mul(self, rhs: T) -> Rgb<T> {
-        Rgb::new(self.r * rhs,
-                 self.g * rhs,
-                 self.b * rhs)
+        self.map(|c| c * rhs)
     }
 }
 
@@ -219,9 +255,7 @@ impl<T:Channel> Div for Rgb<T> {
 
     #[inline]
     fn div(self, rhs: Rgb<T>) -> Rgb<T> {
-        Rgb::new(self.r.normalized_div(rhs.r),
-                 self.g.normalized_div(rhs.g),
-                 self.b.normalized_div(rhs.b))
+        self.zip_with(rhs, Channel::normalized_div)
     }
 }
 
@@ -230,20 +264,21 @@ impl<T:Channel + Div<T,Output=T>> Div<T> for Rgb<T> {
 
     #[inline]
     fn div(self, rhs: T) -> Rgb<T> {
-        Rgb::new(self.r / rhs,
-                 self.g / rhs,
-                 self.b / rhs)
+        self.map(|c| c / rhs)
     }
 }
 
+/// Channel-wise sum. Like the other arithmetic operators this treats the
+/// channels as amounts of light, which only holds for linear values: adding
+/// two lights, scaling by an intensity and filtering by a transmittance are
+/// `+`, `* k` and `*`. On gamma-encoded colors the results are not
+/// physically meaningful.
 impl<T:Channel + Add<T,Output=T>> Add for Rgb<T> {
     type Output = Rgb<T>;
 
     #[inline]
     fn add(self, rhs: Rgb<T>) -> Rgb<T> {
-        Rgb::new(self.r + rhs.r,
-                 self.g + rhs.g,
-                 self.b + rhs.b)
+        self.zip_with(rhs, Add::add)
     }
 }
 
@@ -252,48 +287,123 @@ impl<T:Channel + Sub<T,Output=T>> Sub for Rgb<T> {
 
     #[inline]
     fn sub(self, rhs: Rgb<T>) -> Rgb<T> {
-        Rgb::new(self.r - rhs.r,
-                 self.g - rhs.g,
-                 self.b - rhs.b)
+        self.zip_with(rhs, Sub::sub)
     }
 }
 
-impl<T:Channel + Saturating> Saturating for Rgb<T> {
-    fn saturating_add(self, v: Rgb<T>) -> Rgb<T> {
-        Rgb::new(self.r.saturating_add(v.r),
-            self.g.saturating_add(v.g),
-            self.b.saturating_add(v.b))
+impl<T:Channel + Neg<Output=T>> Neg for Rgb<T> {
+    type Output = Rgb<T>;
+
+    #[inline]
+    fn neg(self) -> Rgb<T> {
+        self.map(Neg::neg)
     }
-    
-    fn saturating_sub(self, v: Rgb<T>) -> Rgb<T> {
-        Rgb::new(self.r.saturating_sub(v.r),
-            self.g.saturating_sub(v.g),
-            self.b.saturating_sub(v.b))
+}
+
+impl<T:Channel + Add<T,Output=T>> AddAssign for Rgb<T> {
+    #[inline]
+    fn add_assign(&mut self, rhs: Rgb<T>) {
+        *self = *self + rhs;
     }
 }
 
-impl<T> Index<usize> for Rgb<T> {
-    type Output = T;
-    fn index<'a>(&'a self, index: usize) -> &'a T {
-        self.as_ref().index(index)
+impl<T:Channel + Sub<T,Output=T>> SubAssign for Rgb<T> {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Rgb<T>) {
+        *self = *self - rhs;
     }
 }
 
-impl<T> IndexMut<usize> for Rgb<T> {
-    fn index_mut<'a>(&'a mut self, index: usize) -> &'a mut T {
-        self.as_mut().index_mut(index)
+impl<T:Channel> MulAssign for Rgb<T> {
+    #[inline]
+    fn mul_assign(&mut self, rhs: Rgb<T>) {
+        *self = *self * rhs;
     }
 }
 
-impl<T> AsRef<[T]> for Rgb<T> {
-    fn as_ref(&self) -> &[T] {
-        unsafe{ slice::from_raw_parts(&self.r, 3) }
+impl<T:Channel + Mul<T,Output=T>> MulAssign<T> for Rgb<T> {
+    #[inline]
+    fn mul_assign(&mut self, rhs: T) {
+        *self = *self * rhs;
     }
 }
 
-impl<T> AsMut<[T]> for Rgb<T> {
-    fn as_mut(&mut self) -> &mut [T] {
-        unsafe{ slice::from_raw_parts_mut(&mut self.r, 3) }
+impl<T:Channel> DivAssign for Rgb<T> {
+    #[inline]
+    fn div_assign(&mut self, rhs: Rgb<T>) {
+        *self = *self / rhs;
+    }
+}
+
+impl<T:Channel + Div<T,Output=T>> DivAssign<T> for Rgb<T> {
+    #[inline]
+    fn div_assign(&mut self, rhs: T) {
+        *self = *self / rhs;
+    }
+}
+
+impl Mul<Rgb<f32>> for f32 {
+    type Output = Rgb<f32>;
+
+    #[inline]
+    fn mul(self, rhs: Rgb<f32>) -> Rgb<f32> {
+        rhs * self
+    }
+}
+
+impl Mul<Rgb<f64>> for f64 {
+    type Output = Rgb<f64>;
+
+    #[inline]
+    fn mul(self, rhs: Rgb<f64>) -> Rgb<f64> {
+        rhs * self
+    }
+}
+
+impl<T:Channel + Add<T,Output=T> + Zero> Sum for Rgb<T> {
+    #[inline]
+    fn sum<I: Iterator<Item = Rgb<T>>>(iter: I) -> Rgb<T> {
+        iter.fold(Rgb::new(T::zero(), T::zero(), T::zero()), Add::add)
+    }
+}
+
+macro_rules! int_rgb_impls {
+    ($($t:ident),*) => {
+        $(
+            impl Rgb<$t> {
+                /// Channel-wise addition, wrapping around on overflow.
+                #[inline]
+                pub fn wrapping_add(self, rhs: Rgb<$t>) -> Rgb<$t> {
+                    self.zip_with(rhs, $t::wrapping_add)
+                }
+
+                /// Channel-wise subtraction, wrapping around on underflow.
+                #[inline]
+                pub fn wrapping_sub(self, rhs: Rgb<$t>) -> Rgb<$t> {
+                    self.zip_with(rhs, $t::wrapping_sub)
+                }
+
+                /// Multiplies each channel by `factor`, rounding to nearest and
+                /// clamping to the channel range.
+                #[inline]
+                pub fn scale(self, factor: f32) -> Rgb<$t> {
+                    self.map(|c| (c as f32 * factor).round().clamp(0.0, $t::MAX as f32) as $t)
+                }
+            }
+        )*
+    }
+}
+
+int_rgb_impls!(u8, u16);
+
+/// Channel-wise, clamping at the limits of the channel type.
+impl<T:Channel + Saturating> Saturating for Rgb<T> {
+    fn saturating_add(self, v: Rgb<T>) -> Rgb<T> {
+        self.zip_with(v, Saturating::saturating_add)
+    }
+    
+    fn saturating_sub(self, v: Rgb<T>) -> Rgb<T> {
+        self.zip_with(v, Saturating::saturating_sub)
     }
 }
 
@@ -303,178 +413,37 @@ impl<T:Channel> ToHsv for Rgb<T> {
         // Algorithm taken from the Wikipedia article on HSL and Hsv:
         // http://en.wikipedia.org/wiki/HSL_and_Hsv#From_Hsv
 
-        let rgb_u = self.to_rgb::<U>();
+        // computed in f64, as integer channels would underflow
+        let rgb = self.to_rgb::<f64>();
 
-        let mx = cast(cast::<U,f64>(rgb_u.r).max(cast(rgb_u.g)).max(cast(rgb_u.b)));
-        let mn = cast(cast::<U,f64>(rgb_u.r).min(cast(rgb_u.g)).min(cast(rgb_u.b)));
+        let mx = rgb.r.max(rgb.g).max(rgb.b);
+        let mn = rgb.r.min(rgb.g).min(rgb.b);
         let chr = mx - mn;
 
-        if chr != Zero::zero() {
+        if chr != 0.0 {
             let h =
-                if      rgb_u.r == mx       { ((rgb_u.g - rgb_u.b) / chr) % cast(6u8) }
-                else if rgb_u.g == mx       { ((rgb_u.b - rgb_u.r) / chr) + cast(2u8) }
-                else    /* rgb_u.b == mx */ { ((rgb_u.r - rgb_u.g) / chr) + cast(4u8) }
-            * cast(60u8);
+                if      rgb.r == mx       { ((rgb.g - rgb.b) / chr) % 6.0 }
+                else if rgb.g == mx       { ((rgb.b - rgb.r) / chr) + 2.0 }
+                else    /* rgb.b == mx */ { ((rgb.r - rgb.g) / chr) + 4.0 }
+            * 60.0;
+            // magentas come out negative
+            let h = if h < 0.0 { h + 360.0 } else { h };
 
             let s = chr / mx;
 
-            Hsv::new(Deg(h), s, mx)
+            Hsv::new(Deg(cast(h)), Channel::from(s), Channel::from(mx))
 
         } else {
-            Hsv::new(Zero::zero(), Zero::zero(), mx)
+            Hsv::new(Zero::zero(), Zero::zero(), Channel::from(mx))
         }
     }
 }
 
-/// SVG 1.0 color constants: http://www.w3.org/TR/SVG/types.html#ColorKeywords
-pub mod consts {
-    use Rgb;
-
-    pub static ALICEBLUE:               Rgb<u8> = Rgb { r: 0xF0, g: 0xF8, b: 0xFF };
-    pub static ANTIQUEWHITE:            Rgb<u8> = Rgb { r: 0xFA, g: 0xEB, b: 0xD7 };
-    pub static AQUA:                    Rgb<u8> = Rgb { r: 0x00, g: 0xFF, b: 0xFF };
-    pub static AQUAMARINE:              Rgb<u8> = Rgb { r: 0x7F, g: 0xFF, b: 0xD4 };
-    pub static AZURE:                   Rgb<u8> = Rgb { r: 0xF0, g: 0xFF, b: 0xFF };
-    pub static BEIGE:                   Rgb<u8> = Rgb { r: 0xF5, g: 0xF5, b: 0xDC };
-    pub static BISQUE:                  Rgb<u8> = Rgb { r: 0xFF, g: 0xE4, b: 0xC4 };
-    pub static BLACK:                   Rgb<u8> = Rgb { r: 0x00, g: 0x00, b: 0x00 };
-    pub static BLANCHEDALMOND:          Rgb<u8> = Rgb { r: 0xFF, g: 0xEB, b: 0xCD };
-    pub static BLUE:                    Rgb<u8> = Rgb { r: 0x00, g: 0x00, b: 0xFF };
-    pub static BLUEVIOLET:              Rgb<u8> = Rgb { r: 0x8A, g: 0x2B, b: 0xE2 };
-    pub static BROWN:                   Rgb<u8> = Rgb { r: 0xA5, g: 0x2A, b: 0x2A };
-    pub static BURLYWOOD:               Rgb<u8> = Rgb { r: 0xDE, g: 0xB8, b: 0x87 };
-    pub static CADETBLUE:               Rgb<u8> = Rgb { r: 0x5F, g: 0x9E, b: 0xA0 };
-    pub static CHARTREUSE:              Rgb<u8> = Rgb { r: 0x7F, g: 0xFF, b: 0x00 };
-    pub static CHOCOLATE:               Rgb<u8> = Rgb { r: 0xD2, g: 0x69, b: 0x1E };
-    pub static CORAL:                   Rgb<u8> = Rgb { r: 0xFF, g: 0x7F, b: 0x50 };
-    pub static CORNFLOWERBLUE:          Rgb<u8> = Rgb { r: 0x64, g: 0x95, b: 0xED };
-    pub static CORNSILK:                Rgb<u8> = Rgb { r: 0xFF, g: 0xF8, b: 0xDC };
-    pub static CRIMSON:                 Rgb<u8> = Rgb { r: 0xDC, g: 0x14, b: 0x3C };
-    pub static CYAN:                    Rgb<u8> = Rgb { r: 0x00, g: 0xFF, b: 0xFF };
-    pub static DARKBLUE:                Rgb<u8> = Rgb { r: 0x00, g: 0x00, b: 0x8B };
-    pub static DARKCYAN:                Rgb<u8> = Rgb { r: 0x00, g: 0x8B, b: 0x8B };
-    pub static DARKGOLDENROD:           Rgb<u8> = Rgb { r: 0xB8, g: 0x86, b: 0x0B };
-    pub static DARKGRAY:                Rgb<u8> = Rgb { r: 0xA9, g: 0xA9, b: 0xA9 };
-    pub static DARKGREEN:               Rgb<u8> = Rgb { r: 0x00, g: 0x64, b: 0x00 };
-    pub static DARKKHAKI:               Rgb<u8> = Rgb { r: 0xBD, g: 0xB7, b: 0x6B };
-    pub static DARKMAGENTA:             Rgb<u8> = Rgb { r: 0x8B, g: 0x00, b: 0x8B };
-    pub static DARKOLIVEGREEN:          Rgb<u8> = Rgb { r: 0x55, g: 0x6B, b: 0x2F };
-    pub static DARKORANGE:              Rgb<u8> = Rgb { r: 0xFF, g: 0x8C, b: 0x00 };
-    pub static DARKORCHID:              Rgb<u8> = Rgb { r: 0x99, g: 0x32, b: 0xCC };
-    pub static DARKRED:                 Rgb<u8> = Rgb { r: 0x8B, g: 0x00, b: 0x00 };
-    pub static DARKSALMON:              Rgb<u8> = Rgb { r: 0xE9, g: 0x96, b: 0x7A };
-    pub static DARKSEAGREEN:            Rgb<u8> = Rgb { r: 0x8F, g: 0xBC, b: 0x8F };
-    pub static DARKSLATEBLUE:           Rgb<u8> = Rgb { r: 0x48, g: 0x3D, b: 0x8B };
-    pub static DARKSLATEGRAY:           Rgb<u8> = Rgb { r: 0x2F, g: 0x4F, b: 0x4F };
-    pub static DARKTURQUOISE:           Rgb<u8> = Rgb { r: 0x00, g: 0xCE, b: 0xD1 };
-    pub static DARKVIOLET:              Rgb<u8> = Rgb { r: 0x94, g: 0x00, b: 0xD3 };
-    pub static DEEPPINK:                Rgb<u8> = Rgb { r: 0xFF, g: 0x14, b: 0x93 };
-    pub static DEEPSKYBLUE:             Rgb<u8> = Rgb { r: 0x00, g: 0xBF, b: 0xFF };
-    pub static DIMGRAY:                 Rgb<u8> = Rgb { r: 0x69, g: 0x69, b: 0x69 };
-    pub static DODGERBLUE:              Rgb<u8> = Rgb { r: 0x1E, g: 0x90, b: 0xFF };
-    pub static FIREBRICK:               Rgb<u8> = Rgb { r: 0xB2, g: 0x22, b: 0x22 };
-    pub static FLORALWHITE:             Rgb<u8> = Rgb { r: 0xFF, g: 0xFA, b: 0xF0 };
-    pub static FORESTGREEN:             Rgb<u8> = Rgb { r: 0x22, g: 0x8B, b: 0x22 };
-    pub static FUCHSIA:                 Rgb<u8> = Rgb { r: 0xFF, g: 0x00, b: 0xFF };
-    pub static GAINSBORO:               Rgb<u8> = Rgb { r: 0xDC, g: 0xDC, b: 0xDC };
-    pub static GHOSTWHITE:              Rgb<u8> = Rgb { r: 0xF8, g: 0xF8, b: 0xFF };
-    pub static GOLD:                    Rgb<u8> = Rgb { r: 0xFF, g: 0xD7, b: 0x00 };
-    pub static GOLDENROD:               Rgb<u8> = Rgb { r: 0xDA, g: 0xA5, b: 0x20 };
-    pub static GRAY:                    Rgb<u8> = Rgb { r: 0x80, g: 0x80, b: 0x80 };
-    pub static GREEN:                   Rgb<u8> = Rgb { r: 0x00, g: 0x80, b: 0x00 };
-    pub static GREENYELLOW:             Rgb<u8> = Rgb { r: 0xAD, g: 0xFF, b: 0x2F };
-    pub static HONEYDEW:                Rgb<u8> = Rgb { r: 0xF0, g: 0xFF, b: 0xF0 };
-    pub static HOTPINK:                 Rgb<u8> = Rgb { r: 0xFF, g: 0x69, b: 0xB4 };
-    pub static INDIANRED:               Rgb<u8> = Rgb { r: 0xCD, g: 0x5C, b: 0x5C };
-    pub static INDIGO:                  Rgb<u8> = Rgb { r: 0x4B, g: 0x00, b: 0x82 };
-    pub static IVORY:                   Rgb<u8> = Rgb { r: 0xFF, g: 0xFF, b: 0xF0 };
-    pub static KHAKI:                   Rgb<u8> = Rgb { r: 0xF0, g: 0xE6, b: 0x8C };
-    pub static LAVENDER:                Rgb<u8> = Rgb { r: 0xE6, g: 0xE6, b: 0xFA };
-    pub static LAVENDERBLUSH:           Rgb<u8> = Rgb { r: 0xFF, g: 0xF0, b: 0xF5 };
-    pub static LAWNGREEN:               Rgb<u8> = Rgb { r: 0x7C, g: 0xFC, b: 0x00 };
-    pub static LEMONCHIFFON:            Rgb<u8> = Rgb { r: 0xFF, g: 0xFA, b: 0xCD };
-    pub static LIGHTBLUE:               Rgb<u8> = Rgb { r: 0xAD, g: 0xD8, b: 0xE6 };
-    pub static LIGHTCORAL:              Rgb<u8> = Rgb { r: 0xF0, g: 0x80, b: 0x80 };
-    pub static LIGHTCYAN:               Rgb<u8> = Rgb { r: 0xE0, g: 0xFF, b: 0xFF };
-    pub static LIGHTGOLDENRODYELLOW:    Rgb<u8> = Rgb { r: 0xFA, g: 0xFA, b: 0xD2 };
-    pub static LIGHTGREEN:              Rgb<u8> = Rgb { r: 0x90, g: 0xEE, b: 0x90 };
-    pub static LIGHTGREY:               Rgb<u8> = Rgb { r: 0xD3, g: 0xD3, b: 0xD3 };
-    pub static LIGHTPINK:               Rgb<u8> = Rgb { r: 0xFF, g: 0xB6, b: 0xC1 };
-    pub static LIGHTSALMON:             Rgb<u8> = Rgb { r: 0xFF, g: 0xA0, b: 0x7A };
-    pub static LIGHTSEAGREEN:           Rgb<u8> = Rgb { r: 0x20, g: 0xB2, b: 0xAA };
-    pub static LIGHTSKYBLUE:            Rgb<u8> = Rgb { r: 0x87, g: 0xCE, b: 0xFA };
-    pub static LIGHTSLATEGRAY:          Rgb<u8> = Rgb { r: 0x77, g: 0x88, b: 0x99 };
-    pub static LIGHTSTEELBLUE:          Rgb<u8> = Rgb { r: 0xB0, g: 0xC4, b: 0xDE };
-    pub static LIGHTYELLOW:             Rgb<u8> = Rgb { r: 0xFF, g: 0xFF, b: 0xE0 };
-    pub static LIME:                    Rgb<u8> = Rgb { r: 0x00, g: 0xFF, b: 0x00 };
-    pub static LIMEGREEN:               Rgb<u8> = Rgb { r: 0x32, g: 0xCD, b: 0x32 };
-    pub static LINEN:                   Rgb<u8> = Rgb { r: 0xFA, g: 0xF0, b: 0xE6 };
-    pub static MAGENTA:                 Rgb<u8> = Rgb { r: 0xFF, g: 0x00, b: 0xFF };
-    pub static MAROON:                  Rgb<u8> = Rgb { r: 0x80, g: 0x00, b: 0x00 };
-    pub static MEDIUMAQUAMARINE:        Rgb<u8> = Rgb { r: 0x66, g: 0xCD, b: 0xAA };
-    pub static MEDIUMBLUE:              Rgb<u8> = Rgb { r: 0x00, g: 0x00, b: 0xCD };
-    pub static MEDIUMORCHID:            Rgb<u8> = Rgb { r: 0xBA, g: 0x55, b: 0xD3 };
-    pub static MEDIUMPURPLE:            Rgb<u8> = Rgb { r: 0x93, g: 0x70, b: 0xDB };
-    pub static MEDIUMSEAGREEN:          Rgb<u8> = Rgb { r: 0x3C, g: 0xB3, b: 0x71 };
-    pub static MEDIUMSLATEBLUE:         Rgb<u8> = Rgb { r: 0x7B, g: 0x68, b: 0xEE };
-    pub static MEDIUMSPRINGGREEN:       Rgb<u8> = Rgb { r: 0x00, g: 0xFA, b: 0x9A };
-    pub static MEDIUMTURQUOISE:         Rgb<u8> = Rgb { r: 0x48, g: 0xD1, b: 0xCC };
-    pub static MEDIUMVIOLETRED:         Rgb<u8> = Rgb { r: 0xC7, g: 0x15, b: 0x85 };
-    pub static MIDNIGHTBLUE:            Rgb<u8> = Rgb { r: 0x19, g: 0x19, b: 0x70 };
-    pub static MINTCREAM:               Rgb<u8> = Rgb { r: 0xF5, g: 0xFF, b: 0xFA };
-    pub static MISTYROSE:               Rgb<u8> = Rgb { r: 0xFF, g: 0xE4, b: 0xE1 };
-    pub static MOCCASIN:                Rgb<u8> = Rgb { r: 0xFF, g: 0xE4, b: 0xB5 };
-    pub static NAVAJOWHITE:             Rgb<u8> = Rgb { r: 0xFF, g: 0xDE, b: 0xAD };
-    pub static NAVY:                    Rgb<u8> = Rgb { r: 0x00, g: 0x00, b: 0x80 };
-    pub static OLDLACE:                 Rgb<u8> = Rgb { r: 0xFD, g: 0xF5, b: 0xE6 };
-    pub static OLIVE:                   Rgb<u8> = Rgb { r: 0x80, g: 0x80, b: 0x00 };
-    pub static OLIVEDRAB:               Rgb<u8> = Rgb { r: 0x6B, g: 0x8E, b: 0x23 };
-    pub static ORANGE:                  Rgb<u8> = Rgb { r: 0xFF, g: 0xA5, b: 0x00 };
-    pub static ORANGERED:               Rgb<u8> = Rgb { r: 0xFF, g: 0x45, b: 0x00 };
-    pub static ORCHID:                  Rgb<u8> = Rgb { r: 0xDA, g: 0x70, b: 0xD6 };
-    pub static PALEGOLDENROD:           Rgb<u8> = Rgb { r: 0xEE, g: 0xE8, b: 0xAA };
-    pub static PALEGREEN:               Rgb<u8> = Rgb { r: 0x98, g: 0xFB, b: 0x98 };
-    pub static PALEVIOLETRED:           Rgb<u8> = Rgb { r: 0xDB, g: 0x70, b: 0x93 };
-    pub static PAPAYAWHIP:              Rgb<u8> = Rgb { r: 0xFF, g: 0xEF, b: 0xD5 };
-    pub static PEACHPUFF:               Rgb<u8> = Rgb { r: 0xFF, g: 0xDA, b: 0xB9 };
-    pub static PERU:                    Rgb<u8> = Rgb { r: 0xCD, g: 0x85, b: 0x3F };
-    pub static PINK:                    Rgb<u8> = Rgb { r: 0xFF, g: 0xC0, b: 0xCB };
-    pub static PLUM:                    Rgb<u8> = Rgb { r: 0xDD, g: 0xA0, b: 0xDD };
-    pub static POWDERBLUE:              Rgb<u8> = Rgb { r: 0xB0, g: 0xE0, b: 0xE6 };
-    pub static PURPLE:                  Rgb<u8> = Rgb { r: 0x80, g: 0x00, b: 0x80 };
-    pub static RED:                     Rgb<u8> = Rgb { r: 0xFF, g: 0x00, b: 0x00 };
-    pub static ROSYBROWN:               Rgb<u8> = Rgb { r: 0xBC, g: 0x8F, b: 0x8F };
-    pub static ROYALBLUE:               Rgb<u8> = Rgb { r: 0x41, g: 0x69, b: 0xE1 };
-    pub static SADDLEBROWN:             Rgb<u8> = Rgb { r: 0x8B, g: 0x45, b: 0x13 };
-    pub static SALMON:                  Rgb<u8> = Rgb { r: 0xFA, g: 0x80, b: 0x72 };
-    pub static SANDYBROWN:              Rgb<u8> = Rgb { r: 0xFA, g: 0xA4, b: 0x60 };
-    pub static SEAGREEN:                Rgb<u8> = Rgb { r: 0x2E, g: 0x8B, b: 0x57 };
-    pub static SEASHELL:                Rgb<u8> = Rgb { r: 0xFF, g: 0xF5, b: 0xEE };
-    pub static SIENNA:                  Rgb<u8> = Rgb { r: 0xA0, g: 0x52, b: 0x2D };
-    pub static SILVER:                  Rgb<u8> = Rgb { r: 0xC0, g: 0xC0, b: 0xC0 };
-    pub static SKYBLUE:                 Rgb<u8> = Rgb { r: 0x87, g: 0xCE, b: 0xEB };
-    pub static SLATEBLUE:               Rgb<u8> = Rgb { r: 0x6A, g: 0x5A, b: 0xCD };
-    pub static SLATEGRAY:               Rgb<u8> = Rgb { r: 0x70, g: 0x80, b: 0x90 };
-    pub static SNOW:                    Rgb<u8> = Rgb { r: 0xFF, g: 0xFA, b: 0xFA };
-    pub static SPRINGGREEN:             Rgb<u8> = Rgb { r: 0x00, g: 0xFF, b: 0x7F };
-    pub static STEELBLUE:               Rgb<u8> = Rgb { r: 0x46, g: 0x82, b: 0xB4 };
-    pub static TAN:                     Rgb<u8> = Rgb { r: 0xD2, g: 0xB4, b: 0x8C };
-    pub static TEAL:                    Rgb<u8> = Rgb { r: 0x00, g: 0x80, b: 0x80 };
-    pub static THISTLE:                 Rgb<u8> = Rgb { r: 0xD8, g: 0xBF, b: 0xD8 };
-    pub static TOMATO:                  Rgb<u8> = Rgb { r: 0xFF, g: 0x63, b: 0x47 };
-    pub static TURQUOISE:               Rgb<u8> = Rgb { r: 0x40, g: 0xE0, b: 0xD0 };
-    pub static VIOLET:                  Rgb<u8> = Rgb { r: 0xEE, g: 0x82, b: 0xEE };
-    pub static WHEAT:                   Rgb<u8> = Rgb { r: 0xF5, g: 0xDE, b: 0xB3 };
-    pub static WHITE:                   Rgb<u8> = Rgb { r: 0xFF, g: 0xFF, b: 0xFF };
-    pub static WHITESMOKE:              Rgb<u8> = Rgb { r: 0xF5, g: 0xF5, b: 0xF5 };
-    pub static YELLOW:                  Rgb<u8> = Rgb { r: 0xFF, g: 0xFF, b: 0x00 };
-    pub static YELLOWGREEN:             Rgb<u8> = Rgb { r: 0x9A, g: 0xCD, b: 0x32 };
-}
-
 #[cfg(test)]
 mod tests {
+    use alloc::vec::Vec;
     use {Hsv, ToHsv};
-    use {Rgb, ToRgb};
+    use {Rgb, ToRgb, Rgba};
     use FloatColor;
     use angle::*;
     use num::Saturating;
@@ -485,6 +454,14 @@ mod tests {
         assert_eq!(Rgb::<u8>::new(0xA0, 0xA0, 0xA0).to_rgb::<u16>(), Rgb::<u16>::new(0xA0A0, 0xA0A0, 0xA0A0));
     }
 
+    #[test]
+    fn test_packed_to_rgb() {
+        assert_eq!(0x102030_u32.to_rgb::<u8>(), Rgb::<u8>::new(0x10, 0x20, 0x30));
+        assert_eq!(Rgb::<u8>::new(0x10, 0x20, 0x30).to_u32(), 0x102030);
+        assert_eq!(0x1111_2222_3333_u64.to_rgb::<u16>(), Rgb::<u16>::new(0x1111, 0x2222, 0x3333));
+        assert_eq!(0x1111_2222_3333_u64.to_rgb::<u8>(), Rgb::<u8>::new(0x11, 0x22, 0x33));
+    }
+
     #[test]
     fn test_rgb_to_hsv() {
         assert_eq!(Rgb::<u8>::new(0xFF, 0xFF, 0xFF).to_hsv::<f32>(), Hsv::<f32>::new(Deg(0.0), 0.0, 1.0));
@@ -503,4 +480,56 @@ mod tests {
         assert_eq!( rgb!(1.0f32, 1.0, 1.0) * 2.0, rgb!(2.0, 2.0, 2.0));
         assert_eq!( (rgb!(1.0f32, 1.0, 1.0) * 2.0).saturate(), rgb!(1.0, 1.0, 1.0));
     }
+
+    #[test]
+    fn test_float_ops() {
+        let light = Rgb::new(0.5f32, 0.25, 1.0);
+        let filter = Rgb::new(0.5f32, 1.0, 0.0);
+        assert_eq!(light * filter, Rgb::new(0.25, 0.25, 0.0));
+        assert_eq!(2.0 * light, light * 2.0);
+        assert_eq!(-light, Rgb::new(-0.5, -0.25, -1.0));
+        let mut acc = Rgb::new(0.0f32, 0.0, 0.0);
+        acc += light;
+        acc += light;
+        acc /= 4.0;
+        assert_eq!(acc, Rgb::new(0.25, 0.125, 0.5));
+        acc -= Rgb::new(0.25, 0.125, 0.5);
+        assert_eq!(acc, Rgb::new(0.0, 0.0, 0.0));
+        let sum: Rgb<f32> = vec![light, filter, light].into_iter().sum();
+        assert_eq!(sum, Rgb::new(1.5, 1.5, 2.0));
+
+        let mut rgba = Rgba::new(0.5f32, 0.5, 0.5, 1.0);
+        rgba *= 0.5;
+        assert_eq!(rgba, Rgba::new(0.25, 0.25, 0.25, 0.5));
+        assert_eq!(2.0 * rgba + rgba, Rgba::new(0.75, 0.75, 0.75, 1.5));
+    }
+
+    #[test]
+    fn test_int_ops() {
+        let a = Rgb::<u8>::new(200, 100, 10);
+        let b = Rgb::<u8>::new(100, 100, 20);
+        assert_eq!(a.saturating_add(b), Rgb::new(255, 200, 30));
+        assert_eq!(a.saturating_sub(b), Rgb::new(100, 0, 0));
+        assert_eq!(a.wrapping_add(b), Rgb::new(44, 200, 30));
+        assert_eq!(a.wrapping_sub(b), Rgb::new(100, 0, 246));
+        assert_eq!(a.scale(0.5), Rgb::new(100, 50, 5));
+        assert_eq!(Rgb::<u8>::new(3, 1, 255).scale(0.5), Rgb::new(2, 1, 128));
+        assert_eq!(a.scale(2.0), Rgb::new(255, 200, 20));
+        assert_eq!(Rgb::<u16>::new(0xFFFF, 1, 0).saturating_add(Rgb::new(1, 1, 1)), Rgb::new(0xFFFF, 2, 1));
+        assert_eq!(Rgb::<u16>::new(1000, 0, 0).scale(-1.0), Rgb::new(0, 0, 0));
+    }
+
+    #[test]
+    fn test_keys() {
+        use std::collections::{BTreeSet, HashMap};
+        let pixels = [Rgb::<u8>::new(9, 0, 0), Rgb::new(1, 2, 3), Rgb::new(9, 0, 0), Rgb::new(1, 2, 4)];
+        let mut counts = HashMap::new();
+        for p in pixels.iter() {
+            *counts.entry(*p).or_insert(0) += 1;
+        }
+        assert_eq!(counts[&Rgb::new(9, 0, 0)], 2);
+        let sorted: Vec<Rgb<u8>> = pixels.iter().cloned().collect::<BTreeSet<_>>().into_iter().collect();
+        assert_eq!(sorted, vec![Rgb::new(1, 2, 3), Rgb::new(1, 2, 4), Rgb::new(9, 0, 0)]);
+        assert!(Rgba::<u8>::new(1, 2, 3, 0) < Rgba::new(1, 2, 3, 1));
+    }
 }
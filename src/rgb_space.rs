@@ -0,0 +1,302 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! RGB working spaces: a set of primaries, a reference white and a transfer
+//! function.
+//!
+//! The `Rgb` values passed to and returned from an `RgbSpace` are encoded
+//! with that space's transfer function. The `Xyz` values are relative to
+//! D65, like everywhere else in the crate; spaces with another white are
+//! adapted with the Bradford transform.
+//!
+//! Transfer functions are extended to negative values by symmetry, so
+//! colors that fall outside the destination gamut survive a conversion
+//! unclipped.
+
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use math::*;
+
+use {Channel, FloatChannel};
+use {Rgb, Xyz, ToXyz, WhitePoint, ColorError};
+use adaptation::Adaptation;
+use matrix::{self, Matrix3};
+use srgb;
+use hdr;
+
+/// The function relating encoded values to linear light.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum TransferFunction {
+    /// No encoding.
+    Linear,
+    /// The piecewise sRGB curve, also used by Display P3.
+    Srgb,
+    /// A pure power law with the given exponent.
+    Gamma(f64),
+    /// The ITU-R BT.709 / BT.2020 camera curve.
+    Rec709,
+    /// The ROMM RGB curve of ProPhoto.
+    ProPhoto,
+    /// The SMPTE ST 2084 perceptual quantizer, with linear `1` at
+    /// `hdr::REFERENCE_WHITE`.
+    Pq,
+}
+
+const REC709_ALPHA: f64 = 1.09929682680944;
+const REC709_BETA: f64 = 0.018053968510807;
+
+impl TransferFunction {
+    /// Takes an encoded value to linear light.
+    pub fn decode(self, v: f64) -> f64 {
+        let a = v.abs();
+        let linear = match self {
+            TransferFunction::Linear => a,
+            TransferFunction::Srgb => srgb::decode(a),
+            TransferFunction::Gamma(g) => a.powf(g),
+            TransferFunction::Rec709 => {
+                if a < REC709_BETA * 4.5 { a / 4.5 } else { ((a + REC709_ALPHA - 1.0) / REC709_ALPHA).powf(1.0 / 0.45) }
+            }
+            TransferFunction::ProPhoto => {
+                if a < 16.0 / 512.0 { a / 16.0 } else { a.powf(1.8) }
+            }
+            TransferFunction::Pq => hdr::pq_decode(a) / hdr::REFERENCE_WHITE,
+        };
+        linear.copysign(v)
+    }
+
+    /// Takes linear light to an encoded value.
+    pub fn encode(self, v: f64) -> f64 {
+        let a = v.abs();
+        let encoded = match self {
+            TransferFunction::Linear => a,
+            TransferFunction::Srgb => srgb::encode(a),
+            TransferFunction::Gamma(g) => a.powf(1.0 / g),
+            TransferFunction::Rec709 => {
+                if a < REC709_BETA { a * 4.5 } else { REC709_ALPHA * a.powf(0.45) - (REC709_ALPHA - 1.0) }
+            }
+            TransferFunction::ProPhoto => {
+                if a < 1.0 / 512.0 { a * 16.0 } else { a.powf(1.0 / 1.8) }
+            }
+            TransferFunction::Pq => hdr::pq_encode(a * hdr::REFERENCE_WHITE),
+        };
+        encoded.copysign(v)
+    }
+}
+
+/// An RGB color space. Build custom spaces with `RgbSpace::new`, which
+/// rejects primaries and whites that span no space.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct RgbSpace {
+    name: &'static str,
+    primaries: [(f64, f64); 3],
+    white: WhitePoint,
+    transfer: TransferFunction,
+    to_xyz: Matrix3,
+    from_xyz: Matrix3,
+}
+
+impl RgbSpace {
+    /// A space with the given primary chromaticities `(x, y)` for red,
+    /// green and blue, reference white and transfer function. Fails if the
+    /// white is degenerate or the primaries and white span no space, e.g.
+    /// collinear primaries.
+    pub fn new(name: &'static str, primaries: [(f64, f64); 3], white: WhitePoint,
+               transfer: TransferFunction) -> Result<RgbSpace, ColorError> {
+        white.check()?;
+        let to_xyz = matrix::rgb_to_xyz(primaries, white.to_array()).ok_or(ColorError::Degenerate)?;
+        let from_xyz = matrix::inverse(&to_xyz).ok_or(ColorError::Degenerate)?;
+        Ok(RgbSpace { name, primaries, white, transfer, to_xyz, from_xyz })
+    }
+
+    /// `new` for the spaces defined here, which fail to compile if they are
+    /// degenerate.
+    const fn built_in(name: &'static str, primaries: [(f64, f64); 3], white: WhitePoint,
+                      transfer: TransferFunction) -> RgbSpace {
+        let to_xyz = match matrix::rgb_to_xyz(primaries, white.to_array()) {
+            Some(m) => m,
+            None => panic!("degenerate RGB primaries"),
+        };
+        let from_xyz = match matrix::inverse(&to_xyz) {
+            Some(m) => m,
+            None => panic!("degenerate RGB primaries"),
+        };
+        RgbSpace { name, primaries, white, transfer, to_xyz, from_xyz }
+    }
+
+    pub const SRGB: RgbSpace = RgbSpace::built_in(
+        "sRGB",
+        [(0.64, 0.33), (0.30, 0.60), (0.15, 0.06)],
+        WhitePoint::D65,
+        TransferFunction::Srgb,
+    );
+    pub const LINEAR_SRGB: RgbSpace = RgbSpace::built_in(
+        "Linear sRGB",
+        [(0.64, 0.33), (0.30, 0.60), (0.15, 0.06)],
+        WhitePoint::D65,
+        TransferFunction::Linear,
+    );
+    pub const ADOBE_RGB: RgbSpace = RgbSpace::built_in(
+        "Adobe RGB (1998)",
+        [(0.64, 0.33), (0.21, 0.71), (0.15, 0.06)],
+        WhitePoint::D65,
+        TransferFunction::Gamma(563.0 / 256.0),
+    );
+    pub const PROPHOTO: RgbSpace = RgbSpace::built_in(
+        "ProPhoto RGB",
+        [(0.734699, 0.265301), (0.159597, 0.840403), (0.036598, 0.000105)],
+        WhitePoint::D50,
+        TransferFunction::ProPhoto,
+    );
+    pub const DISPLAY_P3: RgbSpace = RgbSpace::built_in(
+        "Display P3",
+        [(0.680, 0.320), (0.265, 0.690), (0.150, 0.060)],
+        WhitePoint::D65,
+        TransferFunction::Srgb,
+    );
+    pub const REC709: RgbSpace = RgbSpace::built_in(
+        "Rec. 709",
+        [(0.64, 0.33), (0.30, 0.60), (0.15, 0.06)],
+        WhitePoint::D65,
+        TransferFunction::Rec709,
+    );
+    pub const REC2020: RgbSpace = RgbSpace::built_in(
+        "Rec. 2020",
+        [(0.708, 0.292), (0.170, 0.797), (0.131, 0.046)],
+        WhitePoint::D65,
+        TransferFunction::Rec709,
+    );
+    /// Rec. 2100 with the PQ transfer function, as used by HDR10.
+    pub const REC2100_PQ: RgbSpace = RgbSpace::built_in(
+        "Rec. 2100 PQ",
+        [(0.708, 0.292), (0.170, 0.797), (0.131, 0.046)],
+        WhitePoint::D65,
+        TransferFunction::Pq,
+    );
+
+    #[inline]
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Chromaticities `(x, y)` of the red, green and blue primaries.
+    #[inline]
+    pub fn primaries(&self) -> [(f64, f64); 3] {
+        self.primaries
+    }
+
+    #[inline]
+    pub fn white(&self) -> WhitePoint {
+        self.white
+    }
+
+    #[inline]
+    pub fn transfer(&self) -> TransferFunction {
+        self.transfer
+    }
+
+    /// The matrix taking linear RGB in this space to XYZ relative to the
+    /// space's own white.
+    #[inline]
+    pub fn to_xyz_matrix(&self) -> [[f64; 3]; 3] {
+        self.to_xyz
+    }
+
+    /// Decodes `color` and converts it to D65-relative XYZ.
+    pub fn to_xyz<T: Channel, U: FloatChannel>(&self, color: &Rgb<T>) -> Xyz<U> {
+        let f = self.transfer;
+        let linear = [f.decode(color.r.to_channel_f64()),
+                      f.decode(color.g.to_channel_f64()),
+                      f.decode(color.b.to_channel_f64())];
+        let xyz = Xyz::<f64>::from_array(matrix::mul_vec(&self.to_xyz, linear));
+        xyz.adapt_unchecked(self.white, WhitePoint::D65, Adaptation::Bradford).to_xyz()
+    }
+
+    /// Converts `color` into this space and encodes it. Out of gamut colors
+    /// are not clipped.
+    pub fn from_xyz<C: ToXyz, U: Channel>(&self, color: &C) -> Rgb<U> {
+        let xyz = color.to_xyz::<f64>().adapt_unchecked(WhitePoint::D65, self.white, Adaptation::Bradford);
+        let linear = matrix::mul_vec(&self.from_xyz, xyz.to_array());
+        let f = self.transfer;
+        Rgb::new(Channel::from(f.encode(linear[0])),
+                 Channel::from(f.encode(linear[1])),
+                 Channel::from(f.encode(linear[2])))
+    }
+
+    /// Converts `color` from this space to `to`.
+    #[inline]
+    pub fn convert<T: Channel, U: Channel>(&self, color: &Rgb<T>, to: &RgbSpace) -> Rgb<U> {
+        to.from_xyz(&self.to_xyz::<T, f64>(color))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RgbSpace, TransferFunction};
+    use {Rgb, Srgb, ToXyz, WhitePoint, ColorError};
+
+    fn assert_rgb_eq(a: Rgb<f64>, b: Rgb<f64>) {
+        assert!((a.r - b.r).abs() < 1e-3 && (a.g - b.g).abs() < 1e-3 && (a.b - b.b).abs() < 1e-3,
+                "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn test_transfer_functions() {
+        for &f in &[TransferFunction::Linear, TransferFunction::Srgb, TransferFunction::Gamma(2.2),
+                    TransferFunction::Rec709, TransferFunction::ProPhoto, TransferFunction::Pq] {
+            for &v in &[0.0, 0.001, 0.01, 0.2, 0.5, 1.0, -0.3] {
+                assert!((f.decode(f.encode(v)) - v).abs() < 1e-9, "{:?} {}", f, v);
+            }
+        }
+        assert!((TransferFunction::Srgb.encode(-0.5) + TransferFunction::Srgb.encode(0.5)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_srgb_space() {
+        let xyz = RgbSpace::SRGB.to_xyz::<f64, f64>(&Rgb::new(0.2, 0.5, 0.8));
+        let expected = Srgb::<f64>::new(0.2, 0.5, 0.8).to_xyz::<f64>();
+        assert!((xyz.x - expected.x).abs() < 1e-9 && (xyz.y - expected.y).abs() < 1e-9 && (xyz.z - expected.z).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_convert() {
+        let white = Rgb::new(1.0, 1.0, 1.0);
+        for space in &[RgbSpace::ADOBE_RGB, RgbSpace::PROPHOTO, RgbSpace::DISPLAY_P3, RgbSpace::REC709, RgbSpace::REC2020] {
+            assert_rgb_eq(space.convert(&white, &RgbSpace::SRGB), white);
+            let c = Rgb::new(0.3, 0.6, 0.9);
+            assert_rgb_eq(RgbSpace::SRGB.convert::<f64, f64>(&space.convert(&c, &RgbSpace::SRGB), space), c);
+        }
+        // P3 red lies outside sRGB
+        assert_rgb_eq(RgbSpace::DISPLAY_P3.convert(&Rgb::new(1.0, 0.0, 0.0), &RgbSpace::LINEAR_SRGB),
+                      Rgb::new(1.2249, -0.0421, -0.0196));
+        assert_rgb_eq(RgbSpace::SRGB.convert(&Rgb::new(1.0, 0.0, 0.0), &RgbSpace::DISPLAY_P3),
+                      Rgb::new(0.9175, 0.2003, 0.1386));
+        // PQ puts diffuse white well below the peak signal
+        let white = RgbSpace::SRGB.convert::<f64, f64>(&Rgb::new(1.0, 1.0, 1.0), &RgbSpace::REC2100_PQ);
+        assert_rgb_eq(white, Rgb::new(0.5806, 0.5806, 0.5806));
+        let c = Rgb::new(0.3, 0.6, 0.9);
+        assert_rgb_eq(RgbSpace::REC2100_PQ.convert::<f64, f64>(&RgbSpace::SRGB.convert(&c, &RgbSpace::REC2100_PQ), &RgbSpace::SRGB), c);
+    }
+
+    #[test]
+    fn test_custom_space() {
+        let primaries = [(0.64, 0.33), (0.30, 0.60), (0.15, 0.06)];
+        let srgb = RgbSpace::new("sRGB", primaries, WhitePoint::D65, TransferFunction::Srgb).unwrap();
+        assert_eq!(srgb, RgbSpace::SRGB);
+        assert_eq!(RgbSpace::new("zero", primaries, WhitePoint { x: 0.0, y: 0.0, z: 0.0 }, TransferFunction::Srgb),
+                   Err(ColorError::Degenerate));
+        assert_eq!(RgbSpace::new("line", [(0.1, 0.1), (0.2, 0.2), (0.3, 0.3)], WhitePoint::D65, TransferFunction::Srgb),
+                   Err(ColorError::Degenerate));
+    }
+}
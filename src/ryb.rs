@@ -0,0 +1,225 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The red, yellow and blue of painters' color wheels.
+//!
+//! The components are amounts of paint: no paint is white, and all three at
+//! full strength are black. Yellow and blue make green, red and yellow
+//! orange, and red and blue purple, so the complement of red is green rather
+//! than the cyan of the RGB wheel.
+//!
+//! The conversions are those of Sugita and Takahashi, "Computational RYB
+//! Color Model and its Applications" (2017), which are exact inverses of
+//! each other. Like HSV, they work on the RGB components as given.
+
+use angle::Deg;
+
+use {Color, Channel, Hue};
+use {Rgb, ToRgb, Hsv, ToHsv};
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[repr(C)]
+pub struct Ryb<T> { pub r: T, pub y: T, pub b: T }
+
+impl<T> Ryb<T> {
+    #[inline]
+    pub const fn new(r: T, y: T, b: T) -> Ryb<T> {
+        Ryb { r, y, b }
+    }
+}
+
+impl<T: Channel> Ryb<T> {
+    /// The angle of the color on the RYB wheel: red at `0`, yellow at
+    /// `120` and blue at `240` degrees, with green halfway between yellow
+    /// and blue.
+    #[inline]
+    pub fn hue(&self) -> Deg<f64> {
+        self.as_hsv().h
+    }
+
+    /// The color `degrees` further round the RYB wheel, with the same
+    /// amounts of paint.
+    pub fn rotate_hue(&self, degrees: f64) -> Ryb<T> {
+        let mut hsv = self.as_hsv();
+        hsv.h = (Hue::from(hsv.h) + Deg(degrees)).to_deg();
+        let c = hsv.to_rgb::<f64>();
+        Ryb::new(Channel::from(c.r), Channel::from(c.g), Channel::from(c.b))
+    }
+
+    /// The amounts of paint as if they were RGB components, in HSV.
+    #[inline]
+    fn as_hsv(&self) -> Hsv<f64> {
+        Rgb::new(self.r.to_channel_f64(), self.y.to_channel_f64(), self.b.to_channel_f64()).to_hsv()
+    }
+}
+
+impl<T: Channel> Color<T> for Ryb<T> {
+    /// Clamps the components of the color to the range `(lo,hi)`.
+    #[inline]
+    fn clamp_s(self, lo: T, hi: T) -> Ryb<T> {
+        Ryb::new(self.r.clamp(lo, hi),
+                 self.y.clamp(lo, hi),
+                 self.b.clamp(lo, hi))
+    }
+
+    /// Clamps the components of the color component-wise between `lo` and `hi`.
+    #[inline]
+    fn clamp_c(self, lo: Ryb<T>, hi: Ryb<T>) -> Ryb<T> {
+        Ryb::new(self.r.clamp(lo.r, hi.r),
+                 self.y.clamp(lo.y, hi.y),
+                 self.b.clamp(lo.b, hi.b))
+    }
+
+    /// Inverts the color through RGB.
+    #[inline]
+    fn inverse(self) -> Ryb<T> {
+        self.to_rgb::<f64>().inverse().to_ryb()
+    }
+
+    /// Mixes the amounts of paint linearly.
+    #[inline]
+    fn mix(self, other: Self, value: T) -> Self {
+        Ryb::new(self.r.mix(other.r, value),
+                 self.y.mix(other.y, value),
+                 self.b.mix(other.b, value))
+    }
+}
+
+pub trait ToRyb {
+    fn to_ryb<U:Channel>(&self) -> Ryb<U>;
+}
+
+impl<T:Channel> ToRyb for Ryb<T> {
+    #[inline]
+    fn to_ryb<U:Channel>(&self) -> Ryb<U> {
+        Ryb::new(self.r.to_channel(), self.y.to_channel(), self.b.to_channel())
+    }
+}
+
+impl<T:Channel> ToRyb for Rgb<T> {
+    fn to_ryb<U:Channel>(&self) -> Ryb<U> {
+        let (red, green, blue) = (self.r.to_channel_f64(), self.g.to_channel_f64(), self.b.to_channel_f64());
+
+        // take out the white, and the yellow red and green share
+        let white = red.min(green).min(blue);
+        let (mut r, mut g, mut b) = (red - white, green - white, blue - white);
+        let max_g = r.max(g).max(b);
+        let mut y = r.min(g);
+        r -= y;
+        g -= y;
+
+        // green is yellow and blue
+        if b > 0.0 && g > 0.0 {
+            b /= 2.0;
+            g /= 2.0;
+        }
+        y += g;
+        b += g;
+
+        // keep the strongest component as strong as before, and add the
+        // black
+        let max_y = r.max(y).max(b);
+        if max_y > 0.0 {
+            let n = max_g / max_y;
+            r *= n;
+            y *= n;
+            b *= n;
+        }
+        let black = (1.0 - red).min(1.0 - green).min(1.0 - blue);
+        Ryb::new(Channel::from(r + black), Channel::from(y + black), Channel::from(b + black))
+    }
+}
+
+impl<T:Channel> ToRyb for Hsv<T> {
+    #[inline]
+    fn to_ryb<U:Channel>(&self) -> Ryb<U> {
+        self.to_rgb::<f64>().to_ryb()
+    }
+}
+
+impl<T:Channel> ToRgb for Ryb<T> {
+    fn to_rgb<U:Channel>(&self) -> Rgb<U> {
+        let (red, yellow, blue) = (self.r.to_channel_f64(), self.y.to_channel_f64(), self.b.to_channel_f64());
+
+        let black = red.min(yellow).min(blue);
+        let (mut r, mut y, mut b) = (red - black, yellow - black, blue - black);
+        let max_y = r.max(y).max(b);
+        let mut g = y.min(b);
+        y -= g;
+        b -= g;
+
+        if b > 0.0 && g > 0.0 {
+            b *= 2.0;
+            g *= 2.0;
+        }
+        r += y;
+        g += y;
+
+        let max_g = r.max(g).max(b);
+        if max_g > 0.0 {
+            let n = max_y / max_g;
+            r *= n;
+            g *= n;
+            b *= n;
+        }
+        let white = (1.0 - red).min(1.0 - yellow).min(1.0 - blue);
+        Rgb::new(Channel::from(r + white), Channel::from(g + white), Channel::from(b + white))
+    }
+}
+
+impl<T:Channel> ToHsv for Ryb<T> {
+    #[inline]
+    fn to_hsv<U:Channel>(&self) -> Hsv<U> {
+        self.to_rgb::<f64>().to_hsv()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {Ryb, ToRyb, Rgb, ToRgb};
+
+    #[test]
+    fn test_primaries() {
+        assert_eq!(Rgb::<f64>::new(1.0, 1.0, 1.0).to_ryb::<f64>(), Ryb::new(0.0, 0.0, 0.0));
+        assert_eq!(Rgb::<f64>::new(0.0, 0.0, 0.0).to_ryb::<f64>(), Ryb::new(1.0, 1.0, 1.0));
+        assert_eq!(Rgb::<f64>::new(1.0, 0.0, 0.0).to_ryb::<f64>(), Ryb::new(1.0, 0.0, 0.0));
+        assert_eq!(Rgb::<f64>::new(1.0, 1.0, 0.0).to_ryb::<f64>(), Ryb::new(0.0, 1.0, 0.0));
+        assert_eq!(Rgb::<f64>::new(0.0, 0.0, 1.0).to_ryb::<f64>(), Ryb::new(0.0, 0.0, 1.0));
+        // yellow and blue paint make green
+        assert_eq!(Ryb::<f64>::new(0.0, 1.0, 1.0).to_rgb::<f64>(), Rgb::new(0.0, 1.0, 0.0));
+        assert_eq!(Ryb::<u8>::new(0xFF, 0xFF, 0x00).to_rgb::<u8>(), Rgb::new(0xFF, 0x7F, 0x00));
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        for &(r, g, b) in &[(0.9, 0.2, 0.1), (0.1, 0.8, 0.3), (0.2, 0.3, 0.9), (0.7, 0.1, 0.6),
+                            (0.3, 0.9, 0.9), (0.9, 0.9, 0.2), (0.5, 0.5, 0.5), (0.0, 0.6, 1.0)] {
+            let back: Rgb<f64> = Rgb::<f64>::new(r, g, b).to_ryb::<f64>().to_rgb();
+            assert!((back.r - r).abs() < 1e-9 && (back.g - g).abs() < 1e-9 && (back.b - b).abs() < 1e-9,
+                    "{:?} {:?}", (r, g, b), back);
+        }
+    }
+
+    #[test]
+    fn test_wheel() {
+        let red = Ryb::<f64>::new(1.0, 0.0, 0.0);
+        assert_eq!(red.hue().0, 0.0);
+        assert_eq!(Ryb::<f64>::new(0.0, 1.0, 0.0).hue().0, 120.0);
+        let green = red.rotate_hue(180.0);
+        assert!((green.r - 0.0).abs() < 1e-9 && (green.y - 1.0).abs() < 1e-9 && (green.b - 1.0).abs() < 1e-9);
+        assert_eq!(green.to_rgb::<u8>(), Rgb::new(0, 0xFF, 0));
+    }
+}
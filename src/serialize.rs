@@ -0,0 +1,244 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Serde support, enabled by the `serde` feature.
+//!
+//! Color types serialize as structs with one field per component. The
+//! exception is `Rgb<u8>`, which is written as a `"#rrggbb"` string by
+//! human-readable formats such as JSON or TOML, and still accepts the struct
+//! form when deserializing. Wrap a color in `Compact` to serialize it as a
+//! plain tuple of its components instead.
+
+use core::fmt;
+
+use angle::Deg;
+use serde::{Serialize, Serializer, Deserialize, Deserializer};
+use serde::de::{self, Visitor, MapAccess, SeqAccess};
+
+use {AlphaColor, Channel};
+use {Rgb, Srgb, Hsv, Hsl, Hwb, Hsi, Hsp, Ryb, YCbCr, Xyz, XyY, Lab, Lch, Luv, Lchuv, Oklab, Oklch, Hsluv, Hpluv, Cam16Ucs, Jzazbz, ICtCp};
+
+/// (De)serializes a hue as its plain value in degrees.
+pub mod deg {
+    use angle::Deg;
+    use serde::{Serialize, Serializer, Deserialize, Deserializer};
+
+    pub fn serialize<T: Serialize + Copy, S: Serializer>(h: &Deg<T>, s: S) -> Result<S::Ok, S::Error> {
+        h.0.serialize(s)
+    }
+
+    pub fn deserialize<'de, T: Deserialize<'de>, D: Deserializer<'de>>(d: D) -> Result<Deg<T>, D::Error> {
+        T::deserialize(d).map(Deg)
+    }
+}
+
+/// A wrapper that serializes a color as a tuple of its components, with the
+/// alpha component last, e.g. `[1.0, 0.5, 0.0]` or `[255, 128, 0, 255]`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Compact<C>(pub C);
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Rgb")]
+struct RgbFields<T> { r: T, g: T, b: T }
+
+/// Selects how `Rgb<T>` is represented for a given channel type.
+pub trait SerializeChannel: Channel + Serialize + for<'de> Deserialize<'de> {
+    #[doc(hidden)]
+    fn serialize_rgb<S: Serializer>(rgb: &Rgb<Self>, s: S) -> Result<S::Ok, S::Error> {
+        RgbFields { r: rgb.r, g: rgb.g, b: rgb.b }.serialize(s)
+    }
+
+    #[doc(hidden)]
+    fn deserialize_rgb<'de, D: Deserializer<'de>>(d: D) -> Result<Rgb<Self>, D::Error> {
+        RgbFields::deserialize(d).map(|c| Rgb::new(c.r, c.g, c.b))
+    }
+}
+
+impl SerializeChannel for u8 {
+    fn serialize_rgb<S: Serializer>(rgb: &Rgb<u8>, s: S) -> Result<S::Ok, S::Error> {
+        if s.is_human_readable() {
+            s.collect_str(&format_args!("#{:02x}{:02x}{:02x}", rgb.r, rgb.g, rgb.b))
+        } else {
+            RgbFields { r: rgb.r, g: rgb.g, b: rgb.b }.serialize(s)
+        }
+    }
+
+    fn deserialize_rgb<'de, D: Deserializer<'de>>(d: D) -> Result<Rgb<u8>, D::Error> {
+        if d.is_human_readable() {
+            d.deserialize_any(HexVisitor)
+        } else {
+            RgbFields::deserialize(d).map(|c| Rgb::new(c.r, c.g, c.b))
+        }
+    }
+}
+
+impl SerializeChannel for u16 {}
+impl SerializeChannel for f32 {}
+impl SerializeChannel for f64 {}
+
+impl<T: SerializeChannel> Serialize for Rgb<T> {
+    #[inline]
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        T::serialize_rgb(self, s)
+    }
+}
+
+impl<'de, T: SerializeChannel> Deserialize<'de> for Rgb<T> {
+    #[inline]
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Rgb<T>, D::Error> {
+        T::deserialize_rgb(d)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Srgb")]
+struct SrgbFields<T> { r: T, g: T, b: T }
+
+impl<T: Serialize> Serialize for Srgb<T> {
+    #[inline]
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        SrgbFields { r: &self.r, g: &self.g, b: &self.b }.serialize(s)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Srgb<T> {
+    #[inline]
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Srgb<T>, D::Error> {
+        SrgbFields::deserialize(d).map(|c| Srgb::new(c.r, c.g, c.b))
+    }
+}
+
+/// Accepts `Rgb<u8>` as a `"#rrggbb"` string or in struct form.
+struct HexVisitor;
+
+impl<'de> Visitor<'de> for HexVisitor {
+    type Value = Rgb<u8>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a \"#rrggbb\" string or an r, g, b struct")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Rgb<u8>, E> {
+        let hex = v.strip_prefix('#').unwrap_or(v);
+        if hex.len() != 6 || !hex.is_ascii() {
+            return Err(E::invalid_value(de::Unexpected::Str(v), &self));
+        }
+        u32::from_str_radix(hex, 16)
+            .map(|n| Rgb::new((n >> 16) as u8, (n >> 8) as u8, n as u8))
+            .map_err(|_| E::invalid_value(de::Unexpected::Str(v), &self))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Rgb<u8>, A::Error> {
+        RgbFields::deserialize(de::value::MapAccessDeserializer::new(map)).map(|c| Rgb::new(c.r, c.g, c.b))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Rgb<u8>, A::Error> {
+        RgbFields::deserialize(de::value::SeqAccessDeserializer::new(seq)).map(|c| Rgb::new(c.r, c.g, c.b))
+    }
+}
+
+macro_rules! compact_impls {
+    ($ty:ident<T: $($bound:ident)*>, |$c:ident| ($x:expr, $y:expr, $z:expr), |$a:ident, $b:ident, $d:ident| $new:expr) => {
+        impl<T: $($bound +)* Serialize + Copy> Serialize for Compact<$ty<T>> {
+            fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+                let $c = &self.0;
+                ($x, $y, $z).serialize(s)
+            }
+        }
+
+        impl<'de, T: $($bound +)* Deserialize<'de>> Deserialize<'de> for Compact<$ty<T>> {
+            fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+                let ($a, $b, $d) = <(T, T, T)>::deserialize(d)?;
+                Ok(Compact($new))
+            }
+        }
+
+        impl<T: $($bound +)* Serialize + Copy> Serialize for Compact<AlphaColor<T, $ty<T>>> {
+            fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+                let $c = &self.0.c;
+                ($x, $y, $z, self.0.a).serialize(s)
+            }
+        }
+
+        impl<'de, T: $($bound +)* Deserialize<'de>> Deserialize<'de> for Compact<AlphaColor<T, $ty<T>>> {
+            fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+                let ($a, $b, $d, alpha) = <(T, T, T, T)>::deserialize(d)?;
+                Ok(Compact(AlphaColor { c: $new, a: alpha }))
+            }
+        }
+    }
+}
+
+compact_impls!(Rgb<T: Channel>,   |c| (c.r, c.g, c.b),   |r, g, b| Rgb::new(r, g, b));
+compact_impls!(Srgb<T:>,          |c| (c.r, c.g, c.b),   |r, g, b| Srgb::new(r, g, b));
+compact_impls!(Hsv<T: Channel>,   |c| (c.h.0, c.s, c.v), |h, s, v| Hsv::new(Deg(h), s, v));
+compact_impls!(Hsl<T: Channel>,   |c| (c.h.0, c.s, c.l), |h, s, l| Hsl::new(Deg(h), s, l));
+compact_impls!(Hwb<T: Channel>,   |c| (c.h.0, c.w, c.b), |h, w, b| Hwb::new(Deg(h), w, b));
+compact_impls!(Hsi<T: Channel>,   |c| (c.h.0, c.s, c.i), |h, s, i| Hsi::new(Deg(h), s, i));
+compact_impls!(Hsp<T: Channel>,   |c| (c.h.0, c.s, c.p), |h, s, p| Hsp::new(Deg(h), s, p));
+compact_impls!(Ryb<T:>,          |c| (c.r, c.y, c.b),   |r, y, b| Ryb::new(r, y, b));
+compact_impls!(YCbCr<T:>,         |c| (c.y, c.cb, c.cr), |y, cb, cr| YCbCr::new(y, cb, cr));
+compact_impls!(Xyz<T:>,           |c| (c.x, c.y, c.z),   |x, y, z| Xyz::new(x, y, z));
+compact_impls!(XyY<T:>,           |c| (c.x, c.y, c.luminance), |x, y, l| XyY::new(x, y, l));
+compact_impls!(Lab<T:>,           |c| (c.l, c.a, c.b),   |l, a, b| Lab::new(l, a, b));
+compact_impls!(Lch<T: Channel>,   |c| (c.l, c.c, c.h.0), |l, c, h| Lch::new(l, c, Deg(h)));
+compact_impls!(Luv<T:>,           |c| (c.l, c.u, c.v),   |l, u, v| Luv::new(l, u, v));
+compact_impls!(Lchuv<T: Channel>, |c| (c.l, c.c, c.h.0), |l, c, h| Lchuv::new(l, c, Deg(h)));
+compact_impls!(Oklab<T:>,         |c| (c.l, c.a, c.b),   |l, a, b| Oklab::new(l, a, b));
+compact_impls!(Oklch<T: Channel>, |c| (c.l, c.c, c.h.0), |l, c, h| Oklch::new(l, c, Deg(h)));
+compact_impls!(Hsluv<T: Channel>, |c| (c.h.0, c.s, c.l), |h, s, l| Hsluv::new(Deg(h), s, l));
+compact_impls!(Hpluv<T: Channel>, |c| (c.h.0, c.p, c.l), |h, p, l| Hpluv::new(Deg(h), p, l));
+compact_impls!(Cam16Ucs<T:>,      |c| (c.j, c.a, c.b),   |j, a, b| Cam16Ucs::new(j, a, b));
+compact_impls!(Jzazbz<T:>,        |c| (c.jz, c.az, c.bz), |j, a, b| Jzazbz::new(j, a, b));
+compact_impls!(ICtCp<T:>,         |c| (c.i, c.ct, c.cp), |i, t, p| ICtCp::new(i, t, p));
+
+#[cfg(test)]
+mod tests {
+    extern crate serde_json;
+
+    use self::serde_json::{to_string, from_str};
+    use super::Compact;
+    use {Rgb, Rgba, Hsv, Lab};
+    use angle::Deg;
+
+    #[test]
+    fn test_rgb_u8_hex() {
+        assert_eq!(to_string(&Rgb::<u8>::new(0xFF, 0x80, 0x00)).unwrap(), "\"#ff8000\"");
+        assert_eq!(from_str::<Rgb<u8>>("\"#ff8000\"").unwrap(), Rgb::new(0xFF, 0x80, 0x00));
+        assert_eq!(from_str::<Rgb<u8>>("\"FF8000\"").unwrap(), Rgb::new(0xFF, 0x80, 0x00));
+        assert_eq!(from_str::<Rgb<u8>>("{\"r\":255,\"g\":128,\"b\":0}").unwrap(), Rgb::new(0xFF, 0x80, 0x00));
+        assert!(from_str::<Rgb<u8>>("\"#ff80\"").is_err());
+        assert!(from_str::<Rgb<u8>>("\"#ff80zz\"").is_err());
+    }
+
+    #[test]
+    fn test_float_struct() {
+        assert_eq!(to_string(&Rgb::<f32>::new(1.0, 0.5, 0.0)).unwrap(), "{\"r\":1.0,\"g\":0.5,\"b\":0.0}");
+        assert_eq!(to_string(&Hsv::<f32>::new(Deg(120.0), 1.0, 0.5)).unwrap(), "{\"h\":120.0,\"s\":1.0,\"v\":0.5}");
+        assert_eq!(from_str::<Hsv<f32>>("{\"h\":120.0,\"s\":1.0,\"v\":0.5}").unwrap(), Hsv::new(Deg(120.0), 1.0, 0.5));
+        assert_eq!(to_string(&Rgba::<u8>::new(1, 2, 3, 4)).unwrap(), "{\"c\":\"#010203\",\"a\":4}");
+        assert_eq!(from_str::<Lab<f64>>("{\"l\":50.0,\"a\":-3.0,\"b\":2.5}").unwrap(), Lab::new(50.0, -3.0, 2.5));
+    }
+
+    #[test]
+    fn test_compact() {
+        assert_eq!(to_string(&Compact(Rgb::<u8>::new(1, 2, 3))).unwrap(), "[1,2,3]");
+        assert_eq!(to_string(&Compact(Rgba::<u8>::new(1, 2, 3, 4))).unwrap(), "[1,2,3,4]");
+        assert_eq!(to_string(&Compact(Hsv::<f32>::new(Deg(90.0), 0.5, 1.0))).unwrap(), "[90.0,0.5,1.0]");
+        assert_eq!(from_str::<Compact<Rgba<u8>>>("[1,2,3,4]").unwrap(), Compact(Rgba::new(1, 2, 3, 4)));
+        assert_eq!(from_str::<Compact<Hsv<f32>>>("[90,0.5,1]").unwrap(), Compact(Hsv::new(Deg(90.0), 0.5, 1.0)));
+        assert!(from_str::<Compact<Rgb<u8>>>("[1,2]").is_err());
+    }
+}
@@ -0,0 +1,270 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Four `f32` lanes in a SIMD register, for the kernels of `batch`.
+//!
+//! Only the operations the kernels need are provided, with SSE2 on `x86_64`
+//! and NEON on `aarch64`. Both are part of the baseline of those targets, so
+//! no runtime detection is needed. Masks are vectors with every bit of a
+//! lane set or clear, as the comparison instructions produce them.
+
+use core::ops::{Add, Sub, Mul, Div};
+
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+#[cfg(target_arch = "aarch64")]
+use core::arch::aarch64::*;
+
+#[cfg(target_arch = "x86_64")]
+type Register = __m128;
+#[cfg(target_arch = "aarch64")]
+type Register = float32x4_t;
+
+#[derive(Clone, Copy)]
+pub(crate) struct F32x4(Register);
+
+// The intrinsics are safe to call on any target with the instruction set,
+// which the `target_arch` gates guarantee, and the loads and stores go
+// through arrays of the right size.
+#[cfg(target_arch = "x86_64")]
+impl F32x4 {
+    #[inline(always)]
+    pub fn splat(v: f32) -> F32x4 {
+        unsafe { F32x4(_mm_set1_ps(v)) }
+    }
+
+    #[inline(always)]
+    pub fn load(v: &[f32; 4]) -> F32x4 {
+        unsafe { F32x4(_mm_loadu_ps(v.as_ptr())) }
+    }
+
+    #[inline(always)]
+    pub fn store(self, v: &mut [f32; 4]) {
+        unsafe { _mm_storeu_ps(v.as_mut_ptr(), self.0) }
+    }
+
+    #[inline(always)]
+    pub fn min(self, rhs: F32x4) -> F32x4 {
+        unsafe { F32x4(_mm_min_ps(self.0, rhs.0)) }
+    }
+
+    #[inline(always)]
+    pub fn max(self, rhs: F32x4) -> F32x4 {
+        unsafe { F32x4(_mm_max_ps(self.0, rhs.0)) }
+    }
+
+    #[inline(always)]
+    pub fn eq(self, rhs: F32x4) -> F32x4 {
+        unsafe { F32x4(_mm_cmpeq_ps(self.0, rhs.0)) }
+    }
+
+    #[inline(always)]
+    pub fn lt(self, rhs: F32x4) -> F32x4 {
+        unsafe { F32x4(_mm_cmplt_ps(self.0, rhs.0)) }
+    }
+
+    #[inline(always)]
+    pub fn le(self, rhs: F32x4) -> F32x4 {
+        unsafe { F32x4(_mm_cmple_ps(self.0, rhs.0)) }
+    }
+
+    /// The lanes of `a` where `self` is set, and of `b` elsewhere.
+    #[inline(always)]
+    pub fn select(self, a: F32x4, b: F32x4) -> F32x4 {
+        unsafe { F32x4(_mm_or_ps(_mm_and_ps(self.0, a.0), _mm_andnot_ps(self.0, b.0))) }
+    }
+
+    /// Rounds to the nearest integer, ties to even. Lanes beyond the range
+    /// of `i32` are unspecified.
+    #[inline(always)]
+    pub fn round(self) -> F32x4 {
+        unsafe { F32x4(_mm_cvtepi32_ps(_mm_cvtps_epi32(self.0))) }
+    }
+
+    /// Rounds down. Lanes beyond the range of `i32` are unspecified.
+    #[inline(always)]
+    pub fn floor(self) -> F32x4 {
+        unsafe {
+            let t = _mm_cvtepi32_ps(_mm_cvttps_epi32(self.0));
+            // truncation rounds negative values up
+            F32x4(_mm_sub_ps(t, _mm_and_ps(_mm_cmpgt_ps(t, self.0), _mm_set1_ps(1.0))))
+        }
+    }
+
+    /// The unbiased exponent and the mantissa in `[1, 2)` of positive normal
+    /// lanes.
+    #[inline(always)]
+    pub fn split_exponent(self) -> (F32x4, F32x4) {
+        unsafe {
+            let bits = _mm_castps_si128(self.0);
+            let e = _mm_sub_epi32(_mm_srli_epi32(bits, 23), _mm_set1_epi32(127));
+            let m = _mm_or_si128(_mm_and_si128(bits, _mm_set1_epi32(0x007F_FFFF)), _mm_set1_epi32(0x3F80_0000));
+            (F32x4(_mm_cvtepi32_ps(e)), F32x4(_mm_castsi128_ps(m)))
+        }
+    }
+
+    /// `2^n` of lanes holding integers in `[-126, 127]`.
+    #[inline(always)]
+    pub fn exp2_int(self) -> F32x4 {
+        unsafe {
+            let n = _mm_add_epi32(_mm_cvtps_epi32(self.0), _mm_set1_epi32(127));
+            F32x4(_mm_castsi128_ps(_mm_slli_epi32(n, 23)))
+        }
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+impl F32x4 {
+    #[inline(always)]
+    pub fn splat(v: f32) -> F32x4 {
+        unsafe { F32x4(vdupq_n_f32(v)) }
+    }
+
+    #[inline(always)]
+    pub fn load(v: &[f32; 4]) -> F32x4 {
+        unsafe { F32x4(vld1q_f32(v.as_ptr())) }
+    }
+
+    #[inline(always)]
+    pub fn store(self, v: &mut [f32; 4]) {
+        unsafe { vst1q_f32(v.as_mut_ptr(), self.0) }
+    }
+
+    #[inline(always)]
+    pub fn min(self, rhs: F32x4) -> F32x4 {
+        unsafe { F32x4(vminq_f32(self.0, rhs.0)) }
+    }
+
+    #[inline(always)]
+    pub fn max(self, rhs: F32x4) -> F32x4 {
+        unsafe { F32x4(vmaxq_f32(self.0, rhs.0)) }
+    }
+
+    #[inline(always)]
+    pub fn eq(self, rhs: F32x4) -> F32x4 {
+        unsafe { F32x4(vreinterpretq_f32_u32(vceqq_f32(self.0, rhs.0))) }
+    }
+
+    #[inline(always)]
+    pub fn lt(self, rhs: F32x4) -> F32x4 {
+        unsafe { F32x4(vreinterpretq_f32_u32(vcltq_f32(self.0, rhs.0))) }
+    }
+
+    #[inline(always)]
+    pub fn le(self, rhs: F32x4) -> F32x4 {
+        unsafe { F32x4(vreinterpretq_f32_u32(vcleq_f32(self.0, rhs.0))) }
+    }
+
+    /// The lanes of `a` where `self` is set, and of `b` elsewhere.
+    #[inline(always)]
+    pub fn select(self, a: F32x4, b: F32x4) -> F32x4 {
+        unsafe { F32x4(vbslq_f32(vreinterpretq_u32_f32(self.0), a.0, b.0)) }
+    }
+
+    /// Rounds to the nearest integer, ties to even.
+    #[inline(always)]
+    pub fn round(self) -> F32x4 {
+        unsafe { F32x4(vrndnq_f32(self.0)) }
+    }
+
+    /// Rounds down.
+    #[inline(always)]
+    pub fn floor(self) -> F32x4 {
+        unsafe { F32x4(vrndmq_f32(self.0)) }
+    }
+
+    /// The unbiased exponent and the mantissa in `[1, 2)` of positive normal
+    /// lanes.
+    #[inline(always)]
+    pub fn split_exponent(self) -> (F32x4, F32x4) {
+        unsafe {
+            let bits = vreinterpretq_s32_f32(self.0);
+            let e = vsubq_s32(vreinterpretq_s32_u32(vshrq_n_u32::<23>(vreinterpretq_u32_s32(bits))), vdupq_n_s32(127));
+            let m = vorrq_s32(vandq_s32(bits, vdupq_n_s32(0x007F_FFFF)), vdupq_n_s32(0x3F80_0000));
+            (F32x4(vcvtq_f32_s32(e)), F32x4(vreinterpretq_f32_s32(m)))
+        }
+    }
+
+    /// `2^n` of lanes holding integers in `[-126, 127]`.
+    #[inline(always)]
+    pub fn exp2_int(self) -> F32x4 {
+        unsafe {
+            let n = vaddq_s32(vcvtnq_s32_f32(self.0), vdupq_n_s32(127));
+            F32x4(vreinterpretq_f32_s32(vshlq_n_s32::<23>(n)))
+        }
+    }
+}
+
+macro_rules! binary_ops {
+    ($($Op:ident $op:ident $sse:ident $neon:ident;)*) => {
+        $(
+            impl $Op for F32x4 {
+                type Output = F32x4;
+
+                #[inline(always)]
+                fn $op(self, rhs: F32x4) -> F32x4 {
+                    #[cfg(target_arch = "x86_64")]
+                    unsafe { F32x4($sse(self.0, rhs.0)) }
+                    #[cfg(target_arch = "aarch64")]
+                    unsafe { F32x4($neon(self.0, rhs.0)) }
+                }
+            }
+        )*
+    }
+}
+
+binary_ops! {
+    Add add _mm_add_ps vaddq_f32;
+    Sub sub _mm_sub_ps vsubq_f32;
+    Mul mul _mm_mul_ps vmulq_f32;
+    Div div _mm_div_ps vdivq_f32;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::F32x4;
+
+    fn lanes(v: F32x4) -> [f32; 4] {
+        let mut out = [0.0; 4];
+        v.store(&mut out);
+        out
+    }
+
+    #[test]
+    fn test_rounding() {
+        let v = F32x4::load(&[-1.5, -0.25, 2.5, 3.75]);
+        assert_eq!(lanes(v.floor()), [-2.0, -1.0, 2.0, 3.0]);
+        assert_eq!(lanes(v.round()), [-2.0, 0.0, 2.0, 4.0]);
+        assert_eq!(lanes(F32x4::load(&[-2.0, 0.0, 1.0, -0.0]).floor()), [-2.0, 0.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn test_exponent() {
+        let (e, m) = F32x4::load(&[1.0, 3.0, 0.375, 1024.0]).split_exponent();
+        assert_eq!(lanes(e), [0.0, 1.0, -2.0, 10.0]);
+        assert_eq!(lanes(m), [1.0, 1.5, 1.5, 1.0]);
+        assert_eq!(lanes(F32x4::load(&[0.0, -3.0, 127.0, -126.0]).exp2_int()),
+                   [1.0, 0.125, 2f32.powi(127), 2f32.powi(-126)]);
+    }
+
+    #[test]
+    fn test_select() {
+        let (a, b) = (F32x4::load(&[1.0, 2.0, 3.0, 4.0]), F32x4::splat(2.5));
+        assert_eq!(lanes(a.lt(b).select(a, b)), [1.0, 2.0, 2.5, 2.5]);
+        assert_eq!(lanes(a.le(F32x4::splat(2.0)).select(a, b)), [1.0, 2.0, 2.5, 2.5]);
+        assert_eq!(lanes(a.eq(F32x4::splat(3.0)).select(a * b, a - b)), [-1.5, -0.5, 7.5, 1.5]);
+        assert_eq!(lanes(a.min(b) + a.max(b) / F32x4::splat(2.0)), [2.25, 3.25, 4.0, 4.5]);
+    }
+}
@@ -0,0 +1,210 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Orderings of palettes and swatches.
+//!
+//! Like `Palette`, these take `Srgb<u8>` values. Each ordering comes as a
+//! key function, for `sort_by_key` and friends, and as a comparator.
+//! `sort_palette` computes each key once and sorts stably.
+
+use core::cmp::Ordering;
+
+use {Rgb, Srgb, ToHsv, Lab, ToLab};
+
+/// `(hue, luminance)`, with the hue in degrees. Grays, which have no hue, get
+/// a hue of `-1` so they come first, from dark to light.
+pub fn hue_key(c: &Srgb<u8>) -> (f64, f64) {
+    // the hue of the encoded values, as CSS takes it
+    let hue = if c.r == c.g && c.g == c.b { -1.0 } else { Rgb::<u8>::new(c.r, c.g, c.b).to_hsv::<f64>().h.0 };
+    (hue, luminance_key(c))
+}
+
+/// The relative luminance, as used by WCAG.
+#[inline]
+pub fn luminance_key(c: &Srgb<u8>) -> f64 {
+    c.relative_luminance()
+}
+
+/// The CIE L* lightness.
+#[inline]
+pub fn lab_lightness_key(c: &Srgb<u8>) -> f64 {
+    let lab: Lab<f64> = c.to_lab();
+    lab.l
+}
+
+/// The position of the color along a Hilbert curve through the RGB cube.
+/// Colors close on the curve are close in RGB, so this gives smoother runs
+/// than sorting by any single component.
+#[inline]
+pub fn hilbert_key(c: &Srgb<u8>) -> u32 {
+    hilbert_index([c.r as u32, c.g as u32, c.b as u32], 8)
+}
+
+/// The index of the point `x` along the 3D Hilbert curve of side
+/// `2^bits`, after Skilling, "Programming the Hilbert curve" (2004).
+fn hilbert_index(mut x: [u32; 3], bits: u32) -> u32 {
+    let m = 1 << (bits - 1);
+    // inverse undo
+    let mut q = m;
+    while q > 1 {
+        let p = q - 1;
+        for i in 0..3 {
+            if x[i] & q != 0 {
+                x[0] ^= p;
+            } else {
+                let t = (x[0] ^ x[i]) & p;
+                x[0] ^= t;
+                x[i] ^= t;
+            }
+        }
+        q >>= 1;
+    }
+    // Gray encode
+    x[1] ^= x[0];
+    x[2] ^= x[1];
+    let mut t = 0;
+    let mut q = m;
+    while q > 1 {
+        if x[2] & q != 0 {
+            t ^= q - 1;
+        }
+        q >>= 1;
+    }
+    // interleave the transposed bits, most significant first
+    let mut h = 0;
+    for b in (0..bits).rev() {
+        for v in &x {
+            h = (h << 1) | (((v ^ t) >> b) & 1);
+        }
+    }
+    h
+}
+
+pub fn by_hue(a: &Srgb<u8>, b: &Srgb<u8>) -> Ordering {
+    let (a, b) = (hue_key(a), hue_key(b));
+    a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1))
+}
+
+pub fn by_luminance(a: &Srgb<u8>, b: &Srgb<u8>) -> Ordering {
+    luminance_key(a).total_cmp(&luminance_key(b))
+}
+
+pub fn by_lab_lightness(a: &Srgb<u8>, b: &Srgb<u8>) -> Ordering {
+    lab_lightness_key(a).total_cmp(&lab_lightness_key(b))
+}
+
+pub fn by_hilbert(a: &Srgb<u8>, b: &Srgb<u8>) -> Ordering {
+    hilbert_key(a).cmp(&hilbert_key(b))
+}
+
+/// An ordering for `sort_palette`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SortOrder {
+    Hue,
+    Luminance,
+    LabLightness,
+    Hilbert,
+}
+
+/// An `f64` ordered by `total_cmp`.
+#[derive(Clone, Copy, PartialEq)]
+struct Key(f64);
+
+impl Eq for Key {}
+
+impl PartialOrd for Key {
+    #[inline]
+    fn partial_cmp(&self, other: &Key) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Key {
+    #[inline]
+    fn cmp(&self, other: &Key) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+/// Sorts `colors` in place. The sort is stable, so equal colors keep their
+/// order, and each key is computed only once, in a single buffer of keys.
+pub fn sort_palette(colors: &mut [Srgb<u8>], order: SortOrder) {
+    match order {
+        SortOrder::Hue => colors.sort_by_cached_key(|c| { let (h, l) = hue_key(c); (Key(h), Key(l)) }),
+        SortOrder::Luminance => colors.sort_by_cached_key(|c| Key(luminance_key(c))),
+        SortOrder::LabLightness => colors.sort_by_cached_key(|c| Key(lab_lightness_key(c))),
+        SortOrder::Hilbert => colors.sort_by_cached_key(hilbert_key),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use super::*;
+    use Srgb;
+
+    #[test]
+    fn test_hilbert() {
+        // the curve visits every cell once, moving to a neighbor each step
+        let bits = 3;
+        let n = 1 << bits;
+        let mut cells = vec![None; n * n * n];
+        for r in 0..n as u32 {
+            for g in 0..n as u32 {
+                for b in 0..n as u32 {
+                    let i = hilbert_index([r, g, b], bits) as usize;
+                    assert!(cells[i].is_none());
+                    cells[i] = Some([r as i32, g as i32, b as i32]);
+                }
+            }
+        }
+        let cells: Vec<[i32; 3]> = cells.into_iter().map(Option::unwrap).collect();
+        assert_eq!(cells[0], [0, 0, 0]);
+        for w in cells.windows(2) {
+            let d: i32 = (0..3).map(|i| (w[0][i] - w[1][i]).abs()).sum();
+            assert_eq!(d, 1, "{:?}", w);
+        }
+        assert_eq!(hilbert_key(&Srgb::new(0, 0, 0)), 0);
+    }
+
+    #[test]
+    fn test_sort_palette() {
+        let red = Srgb::new(255, 0, 0);
+        let green = Srgb::new(0, 255, 0);
+        let blue = Srgb::new(0, 0, 255);
+        let gray = Srgb::new(128, 128, 128);
+        let black = Srgb::new(0, 0, 0);
+
+        let mut colors = vec![blue, gray, red, black, green];
+        sort_palette(&mut colors, SortOrder::Hue);
+        assert_eq!(colors, vec![black, gray, red, green, blue]);
+
+        sort_palette(&mut colors, SortOrder::Luminance);
+        assert_eq!(colors, vec![black, blue, red, gray, green]);
+        assert!(colors.windows(2).all(|w| by_luminance(&w[0], &w[1]) != Ordering::Greater));
+
+        sort_palette(&mut colors, SortOrder::LabLightness);
+        assert_eq!(colors, vec![black, blue, red, gray, green]);
+
+        sort_palette(&mut colors, SortOrder::Hilbert);
+        assert_eq!(colors[0], black);
+        assert!(colors.windows(2).all(|w| by_hilbert(&w[0], &w[1]) == Ordering::Less));
+
+        let mut colors = [Srgb::new(0, 0, 1), Srgb::new(1, 0, 0), black];
+        colors.sort_by(by_lab_lightness);
+        assert_eq!(colors, [black, Srgb::new(0, 0, 1), Srgb::new(1, 0, 0)]);
+        assert_eq!(by_hue(&red, &blue), Ordering::Less);
+    }
+}
@@ -0,0 +1,326 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Spectral power distributions and their colors.
+//!
+//! An `Spd` holds samples of a spectrum at evenly spaced wavelengths, in
+//! nanometers, and is linearly interpolated between them. Its color is found
+//! by integrating it against the CIE 1931 2° standard observer from 380 nm
+//! to 780 nm in 1 nm steps, with the observer tabulated every 10 nm and
+//! interpolated likewise, which gives the chromaticities of the standard
+//! illuminants to within about `0.0005`.
+//!
+//! Spectra of light sources give XYZ directly. Spectra of surfaces are
+//! reflectances from `0` to `1`, and need an illuminant to be seen under.
+
+use alloc::vec::Vec;
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use math::*;
+
+use FloatChannel;
+use {Xyz, ToXyY, WhitePoint, Chromaticity};
+
+/// The range of wavelengths colors are integrated over, in nanometers.
+pub const VISIBLE_RANGE: (f64, f64) = (380.0, 780.0);
+
+/// The CIE 1931 2° color matching functions, x̄, ȳ and z̄, from 380 nm to
+/// 780 nm every 10 nm.
+const CIE_1931: [[f64; 3]; 41] = [
+    [0.001368, 0.000039, 0.006450], [0.004243, 0.000120, 0.020050],
+    [0.014310, 0.000396, 0.067850], [0.043510, 0.001210, 0.207400],
+    [0.134380, 0.004000, 0.645600], [0.283900, 0.011600, 1.385600],
+    [0.348280, 0.023000, 1.747060], [0.336200, 0.038000, 1.772110],
+    [0.290800, 0.060000, 1.669200], [0.195360, 0.090980, 1.287640],
+    [0.095640, 0.139020, 0.812950], [0.032010, 0.208020, 0.465180],
+    [0.004900, 0.323000, 0.272000], [0.009300, 0.503000, 0.158200],
+    [0.063270, 0.710000, 0.078250], [0.165500, 0.862000, 0.042160],
+    [0.290400, 0.954000, 0.020300], [0.433450, 0.994950, 0.008750],
+    [0.594500, 0.995000, 0.003900], [0.762100, 0.952000, 0.002100],
+    [0.916300, 0.870000, 0.001650], [1.026300, 0.757000, 0.001100],
+    [1.062200, 0.631000, 0.000800], [1.002600, 0.503000, 0.000340],
+    [0.854450, 0.381000, 0.000190], [0.642400, 0.265000, 0.000050],
+    [0.447900, 0.175000, 0.000020], [0.283500, 0.107000, 0.000000],
+    [0.164900, 0.061000, 0.000000], [0.087400, 0.032000, 0.000000],
+    [0.046770, 0.017000, 0.000000], [0.022700, 0.008210, 0.000000],
+    [0.011359, 0.004102, 0.000000], [0.005790, 0.002091, 0.000000],
+    [0.002899, 0.001047, 0.000000], [0.001440, 0.000520, 0.000000],
+    [0.000690, 0.000249, 0.000000], [0.000332, 0.000120, 0.000000],
+    [0.000166, 0.000060, 0.000000], [0.000083, 0.000030, 0.000000],
+    [0.000042, 0.000015, 0.000000],
+];
+
+/// CIE illuminant D65, from 380 nm to 780 nm every 10 nm.
+const D65: [f64; 41] = [
+    49.9755, 54.6482, 82.7549, 91.4860, 93.4318, 86.6823, 104.865, 117.008,
+    117.812, 114.861, 115.923, 108.811, 109.354, 107.802, 104.790, 107.689,
+    104.405, 104.046, 100.000, 96.3342, 95.7880, 88.6856, 90.0062, 89.5991,
+    87.6987, 83.2886, 83.6992, 80.0268, 80.2146, 82.2778, 78.2842, 69.7213,
+    71.6091, 74.3490, 61.6040, 69.8856, 75.0870, 63.5927, 46.4182, 66.8054,
+    63.3828,
+];
+
+/// CIE illuminant F2, cool white fluorescent, from 380 nm to 780 nm every
+/// 5 nm.
+const F2: [f64; 81] = [
+    1.18, 1.48, 1.84, 2.15, 3.44, 15.69, 3.85, 3.74, 4.19, 4.62, 5.06, 34.98,
+    11.81, 6.27, 6.63, 6.93, 7.19, 7.40, 7.54, 7.62, 7.65, 7.62, 7.62, 7.45,
+    7.28, 7.15, 7.05, 7.04, 7.16, 7.47, 8.04, 8.88, 10.01, 24.88, 16.64, 14.59,
+    16.16, 17.56, 18.62, 21.47, 22.79, 19.29, 18.66, 17.73, 16.54, 15.21, 13.80,
+    12.36, 10.95, 9.65, 8.40, 7.32, 6.31, 5.43, 4.68, 4.02, 3.45, 2.96, 2.55,
+    2.19, 1.89, 1.64, 1.53, 1.27, 1.10, 0.99, 0.88, 0.76, 0.68, 0.61, 0.56,
+    0.54, 0.51, 0.47, 0.47, 0.43, 0.46, 0.47, 0.40, 0.33, 0.27,
+];
+
+/// The components of CIE daylight, S0, S1 and S2, from 380 nm to 780 nm
+/// every 10 nm.
+const DAYLIGHT: [[f64; 3]; 41] = [
+    [63.4, 38.5, 3.0], [65.8, 35.0, 1.2], [94.8, 43.4, -1.1], [104.8, 46.3, -0.5],
+    [105.9, 43.9, -0.7], [96.8, 37.1, -1.2], [113.9, 36.7, -2.6], [125.6, 35.9, -2.9],
+    [125.5, 32.6, -2.8], [121.3, 27.9, -2.6], [121.3, 24.3, -2.6], [113.5, 20.1, -1.8],
+    [113.1, 16.2, -1.5], [110.8, 13.2, -1.3], [106.5, 8.6, -1.2], [108.8, 6.1, -1.0],
+    [105.3, 4.2, -0.5], [104.4, 1.9, -0.3], [100.0, 0.0, 0.0], [96.0, -1.6, 0.2],
+    [95.1, -3.5, 0.5], [89.1, -3.5, 2.1], [90.5, -5.8, 3.2], [90.3, -7.2, 4.1],
+    [88.4, -8.6, 4.7], [84.0, -9.5, 5.1], [85.1, -10.9, 6.7], [81.9, -10.7, 7.3],
+    [82.6, -12.0, 8.6], [84.9, -14.0, 9.8], [81.3, -13.6, 10.2], [71.9, -12.0, 8.3],
+    [74.3, -13.3, 9.6], [76.4, -12.9, 8.5], [63.3, -10.6, 7.0], [71.7, -11.6, 7.6],
+    [77.0, -12.2, 8.0], [65.2, -10.2, 6.7], [47.7, -7.8, 5.2], [68.6, -11.2, 7.4],
+    [65.0, -10.4, 6.8],
+];
+
+/// The values of the CIE 1931 2° color matching functions `[x̄, ȳ, z̄]` at
+/// `nm`, zero outside `VISIBLE_RANGE`.
+pub fn cie_1931(nm: f64) -> [f64; 3] {
+    if !(VISIBLE_RANGE.0..=VISIBLE_RANGE.1).contains(&nm) {
+        return [0.0; 3];
+    }
+    let pos = (nm - VISIBLE_RANGE.0) / 10.0;
+    let i = (pos.floor() as usize).min(CIE_1931.len() - 2);
+    let t = pos - i as f64;
+    let (a, b) = (CIE_1931[i], CIE_1931[i + 1]);
+    [a[0] + (b[0] - a[0]) * t, a[1] + (b[1] - a[1]) * t, a[2] + (b[2] - a[2]) * t]
+}
+
+/// A spectrum sampled at evenly spaced wavelengths.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Spd {
+    start: f64,
+    step: f64,
+    values: Vec<f64>,
+}
+
+impl Spd {
+    /// The spectrum with `values` at `start`, `start + step`, and so on, in
+    /// nanometers.
+    ///
+    /// # Panics
+    ///
+    /// If `step` is not positive or there are no values.
+    pub fn new(start: f64, step: f64, values: Vec<f64>) -> Spd {
+        assert!(step > 0.0, "wavelength step must be positive, got {}", step);
+        assert!(!values.is_empty(), "a spectrum needs at least one sample");
+        Spd { start, step, values }
+    }
+
+    /// Samples `f` across `VISIBLE_RANGE` every 5 nm.
+    pub fn from_fn<F: Fn(f64) -> f64>(f: F) -> Spd {
+        let values = (0..81).map(|i| f(VISIBLE_RANGE.0 + 5.0 * i as f64)).collect();
+        Spd::new(VISIBLE_RANGE.0, 5.0, values)
+    }
+
+    /// The radiance of a black body at `kelvin` by Planck's law, scaled to
+    /// `100` at 560 nm.
+    pub fn blackbody(kelvin: f64) -> Spd {
+        let planck = |nm: f64| {
+            let l = nm * 1e-9;
+            1.0 / (l.powi(5) * ((1.4388e-2 / (l * kelvin)).exp() - 1.0))
+        };
+        let norm = 100.0 / planck(560.0);
+        Spd::from_fn(|nm| planck(nm) * norm)
+    }
+
+    /// CIE standard illuminant D65, average daylight.
+    pub fn d65() -> Spd {
+        Spd::new(VISIBLE_RANGE.0, 10.0, D65.to_vec())
+    }
+
+    /// CIE daylight at `kelvin`, which is clamped to `4000..=25000`, scaled
+    /// to `100` at 560 nm.
+    pub fn daylight(kelvin: f64) -> Spd {
+        let t = kelvin.clamp(4000.0, 25000.0);
+        let (t2, t3) = (t * t, t * t * t);
+        let x = if t <= 7000.0 {
+            -4.6070e9 / t3 + 2.9678e6 / t2 + 0.09911e3 / t + 0.244063
+        } else {
+            -2.0064e9 / t3 + 1.9018e6 / t2 + 0.24748e3 / t + 0.237040
+        };
+        let y = -3.0 * x * x + 2.870 * x - 0.275;
+        let m = 0.0241 + 0.2562 * x - 0.7341 * y;
+        let m1 = (-1.3515 - 1.7703 * x + 5.9114 * y) / m;
+        let m2 = (0.0300 - 31.4424 * x + 30.0717 * y) / m;
+        let values = DAYLIGHT.iter().map(|s| s[0] + m1 * s[1] + m2 * s[2]).collect();
+        Spd::new(VISIBLE_RANGE.0, 10.0, values)
+    }
+
+    /// CIE standard illuminant A, incandescent light at 2856 K.
+    #[inline]
+    pub fn a() -> Spd {
+        Spd::blackbody(2856.0)
+    }
+
+    /// CIE illuminant F2, cool white fluorescent.
+    pub fn f2() -> Spd {
+        Spd::new(VISIBLE_RANGE.0, 5.0, F2.to_vec())
+    }
+
+    /// The wavelength of the first sample.
+    #[inline]
+    pub fn start(&self) -> f64 {
+        self.start
+    }
+
+    /// The spacing of the samples.
+    #[inline]
+    pub fn step(&self) -> f64 {
+        self.step
+    }
+
+    /// The wavelength of the last sample.
+    #[inline]
+    pub fn end(&self) -> f64 {
+        self.start + self.step * (self.values.len() - 1) as f64
+    }
+
+    #[inline]
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+
+    /// The value at `nm`, interpolated between samples and zero outside
+    /// them.
+    pub fn value_at(&self, nm: f64) -> f64 {
+        if nm < self.start || nm > self.end() {
+            return 0.0;
+        }
+        let pos = (nm - self.start) / self.step;
+        let i = pos.floor() as usize;
+        match self.values.get(i + 1) {
+            Some(&next) => self.values[i] + (next - self.values[i]) * (pos - i as f64),
+            None => self.values[i],
+        }
+    }
+
+    /// `sum(f(nm) * cmf(nm))` over `VISIBLE_RANGE` in 1 nm steps.
+    fn integrate<F: Fn(f64) -> f64>(f: F) -> [f64; 3] {
+        let mut sum = [0.0; 3];
+        let (start, end) = (VISIBLE_RANGE.0 as usize, VISIBLE_RANGE.1 as usize);
+        for nm in start..=end {
+            let nm = nm as f64;
+            let (v, cmf) = (f(nm), cie_1931(nm));
+            for (s, c) in sum.iter_mut().zip(cmf.iter()) {
+                *s += v * c;
+            }
+        }
+        sum
+    }
+
+    /// The color of the spectrum as a light source, scaled so that a flat
+    /// spectrum of `1` has `y == 1`.
+    pub fn to_xyz<U: FloatChannel>(&self) -> Xyz<U> {
+        let k = 1.0 / Spd::integrate(|_| 1.0)[1];
+        let [x, y, z] = Spd::integrate(|nm| self.value_at(nm));
+        Xyz::from_array([x * k, y * k, z * k])
+    }
+
+    /// The chromaticity of the spectrum as a light source.
+    #[inline]
+    pub fn chromaticity(&self) -> Chromaticity {
+        self.to_xyz::<f64>().to_xyy::<f64>().chromaticity()
+    }
+
+    /// The correlated color temperature of the spectrum as a light source,
+    /// and its tint, as by `Chromaticity::temperature_tint`.
+    #[inline]
+    pub fn temperature_tint(&self) -> (f64, f64) {
+        self.chromaticity().temperature_tint()
+    }
+
+    /// The white point of the spectrum as an illuminant.
+    #[inline]
+    pub fn white_point(&self) -> WhitePoint {
+        self.chromaticity().to_white_point()
+    }
+
+    /// The color of a surface with the spectrum as its reflectance, seen
+    /// under `illuminant`. A perfect white reflector gives the illuminant's
+    /// white point.
+    pub fn reflectance_to_xyz<U: FloatChannel>(&self, illuminant: &Spd) -> Xyz<U> {
+        let k = 1.0 / Spd::integrate(|nm| illuminant.value_at(nm))[1];
+        let [x, y, z] = Spd::integrate(|nm| self.value_at(nm) * illuminant.value_at(nm));
+        Xyz::from_array([x * k, y * k, z * k])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Spd, cie_1931};
+    use {Xyz, ToXyY, Chromaticity};
+
+    fn assert_chromaticity(spd: &Spd, expected: Chromaticity, tolerance: f64) {
+        let c = spd.chromaticity();
+        assert!((c.x - expected.x).abs() < tolerance && (c.y - expected.y).abs() < tolerance,
+                "{:?} != {:?}", c, expected);
+    }
+
+    #[test]
+    fn test_cmf() {
+        assert_eq!(cie_1931(555.0)[1], (0.99495 + 0.995) / 2.0);
+        assert_eq!(cie_1931(379.0), [0.0; 3]);
+        assert_eq!(cie_1931(780.0)[1], 0.000015);
+    }
+
+    #[test]
+    fn test_illuminants() {
+        assert_chromaticity(&Spd::d65(), Chromaticity::D65, 5e-4);
+        assert_chromaticity(&Spd::a(), Chromaticity::A, 5e-4);
+        assert_chromaticity(&Spd::daylight(6504.0), Chromaticity::D65, 5e-4);
+        assert_chromaticity(&Spd::f2(), Chromaticity::new(0.37208, 0.37529), 1e-3);
+        // a flat spectrum is the equal energy white
+        assert_chromaticity(&Spd::from_fn(|_| 1.0), Chromaticity::new(1.0 / 3.0, 1.0 / 3.0), 1e-3);
+        assert!((Spd::from_fn(|_| 1.0).to_xyz::<f64>().y - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_reflectance() {
+        let d65 = Spd::d65();
+        let white: Xyz<f64> = Spd::from_fn(|_| 1.0).reflectance_to_xyz(&d65);
+        let wp = d65.white_point();
+        assert!((white.x - wp.x).abs() < 1e-9 && (white.y - 1.0).abs() < 1e-9 && (white.z - wp.z).abs() < 1e-9);
+        let gray: Xyz<f64> = Spd::from_fn(|_| 0.5).reflectance_to_xyz(&d65);
+        assert!((gray.y - 0.5).abs() < 1e-9);
+        // a surface reflecting only long wavelengths is red
+        let red: Xyz<f64> = Spd::from_fn(|nm| if nm > 600.0 { 1.0 } else { 0.0 }).reflectance_to_xyz(&d65);
+        assert!(red.to_xyy::<f64>().x > 0.6);
+    }
+
+    #[test]
+    fn test_value_at() {
+        let spd = Spd::new(400.0, 10.0, vec![1.0, 3.0]);
+        assert_eq!(spd.end(), 410.0);
+        assert_eq!(spd.value_at(405.0), 2.0);
+        assert_eq!(spd.value_at(410.0), 3.0);
+        assert_eq!(spd.value_at(420.0), 0.0);
+    }
+}
@@ -13,12 +13,691 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
-pub struct Srgb<T> { pub r: T, pub g: T, pub b: T }
+//! http://en.wikipedia.org/wiki/SRGB
+//!
+//! `Srgb` holds gamma-encoded values, as stored in images and written in CSS,
+//! while `Rgb` holds linear light. Blending and lighting should be done in
+//! linear light: `Srgb::mix` decodes, mixes and re-encodes, and `to_linear`
+//! and `to_srgb` convert explicitly between the two.
+//!
+//! `Srgb<T>` is `Rgb<T, encoding::Srgb>`, so the two are distinct types and
+//! one is not passed where the other is expected. `Srgb` has no arithmetic
+//! operators, so encoded values are not added or scaled by accident, and
+//! `to_srgb` on an `Srgb` only casts the channels, so a color is never
+//! encoded twice. Encoded values from outside the crate, such as named
+//! colors, `const_srgb!` literals, CSS strings and colormaps, are `Srgb`,
+//! and so are the colors of `palette`, `quantize`, `dither` and `harmony`.
 
-impl<T> Srgb<T> {
+#[cfg(feature = "std")]
+use std::sync::OnceLock;
+#[cfg(feature = "srgb_lut16")]
+use alloc::vec::Vec;
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use math::*;
+
+use {Color, Channel, FloatChannel};
+use difference;
+use encoding;
+use ToLab;
+use {Rgb, ToRgb, Xyz, XyY, Lab, Luv, Lchuv, Oklab, Oklch, Hsluv, Hpluv, Jzazbz, ICtCp};
+
+/// A color in RGB, gamma-encoded with the sRGB transfer function.
+pub type Srgb<T> = Rgb<T, encoding::Srgb>;
+
+impl<T: Channel> Srgb<T> {
+    /// Decodes the color to linear light. This is the same as `to_rgb`.
+    /// `u8` channels, and `u16` ones with the `srgb_lut16` feature, are
+    /// decoded by table.
+    #[inline]
+    pub fn to_linear<U: Channel>(&self) -> Rgb<U> {
+        Rgb::new(Channel::from(self.r.srgb_to_linear()),
+                 Channel::from(self.g.srgb_to_linear()),
+                 Channel::from(self.b.srgb_to_linear()))
+    }
+
+    /// Encodes a linear light color. `u8` channels are encoded by a search
+    /// of a table with the `srgb_lut16` feature.
+    #[inline]
+    pub fn from_linear<C: ToRgb>(color: &C) -> Srgb<T> {
+        let rgb = color.to_rgb::<f64>();
+        Srgb::new(T::srgb_from_linear(rgb.r),
+                  T::srgb_from_linear(rgb.g),
+                  T::srgb_from_linear(rgb.b))
+    }
+}
+
+impl<T: Channel> Color<T> for Srgb<T> {
+    /// Clamps the components of the color to the range `(lo,hi)`.
+    #[inline]
+    fn clamp_s(self, lo: T, hi: T) -> Srgb<T> {
+        Srgb::new(self.r.clamp(lo, hi),
+                  self.g.clamp(lo, hi),
+                  self.b.clamp(lo, hi))
+    }
+
+    /// Clamps the components of the color component-wise between `lo` and `hi`.
+    #[inline]
+    fn clamp_c(self, lo: Srgb<T>, hi: Srgb<T>) -> Srgb<T> {
+        Srgb::new(self.r.clamp(lo.r, hi.r),
+                  self.g.clamp(lo.g, hi.g),
+                  self.b.clamp(lo.b, hi.b))
+    }
+
+    /// Inverts the encoded values.
+    #[inline]
+    fn inverse(self) -> Srgb<T> {
+        Srgb::new(self.r.invert_channel(),
+                  self.g.invert_channel(),
+                  self.b.invert_channel())
+    }
+
+    /// Mixes the colors in linear light.
+    #[inline]
+    fn mix(self, other: Self, value: T) -> Self {
+        let value = value.to_channel_f64();
+        Srgb::from_linear(&self.to_linear::<f64>().mix(other.to_linear(), value))
+    }
+}
+
+impl<T: Channel> ToRgb for Srgb<T> {
+    /// Decodes the color to linear light.
+    #[inline]
+    fn to_rgb<U: Channel>(&self) -> Rgb<U> {
+        self.to_linear()
+    }
+}
+
+pub trait ToSrgb {
+    fn to_srgb<U:Channel>(&self) -> Srgb<U>;
+}
+
+impl<T:Channel> ToSrgb for Srgb<T> {
+    #[inline]
+    fn to_srgb<U:Channel>(&self) -> Srgb<U> {
+        Srgb::new(self.r.to_channel(), self.g.to_channel(), self.b.to_channel())
+    }
+}
+
+impl<T:Channel> ToSrgb for Rgb<T> {
+    /// Encodes the linear light color.
+    #[inline]
+    fn to_srgb<U:Channel>(&self) -> Srgb<U> {
+        Srgb::from_linear(self)
+    }
+}
+
+impl<T:FloatChannel> ToSrgb for Xyz<T> {
+    #[inline]
+    fn to_srgb<U:Channel>(&self) -> Srgb<U> {
+        Srgb::from_linear(self)
+    }
+}
+
+impl<T:FloatChannel> ToSrgb for XyY<T> {
+    #[inline]
+    fn to_srgb<U:Channel>(&self) -> Srgb<U> {
+        Srgb::from_linear(self)
+    }
+}
+
+impl<T:FloatChannel> ToSrgb for Lab<T> {
+    #[inline]
+    fn to_srgb<U:Channel>(&self) -> Srgb<U> {
+        Srgb::from_linear(self)
+    }
+}
+
+impl<T:FloatChannel> ToSrgb for Luv<T> {
+    #[inline]
+    fn to_srgb<U:Channel>(&self) -> Srgb<U> {
+        Srgb::from_linear(self)
+    }
+}
+
+impl<T:FloatChannel> ToSrgb for Lchuv<T> {
+    #[inline]
+    fn to_srgb<U:Channel>(&self) -> Srgb<U> {
+        Srgb::from_linear(self)
+    }
+}
+
+impl<T:FloatChannel> ToSrgb for Oklab<T> {
+    #[inline]
+    fn to_srgb<U:Channel>(&self) -> Srgb<U> {
+        Srgb::from_linear(self)
+    }
+}
+
+impl<T:FloatChannel> ToSrgb for Oklch<T> {
     #[inline]
-    pub fn new(r: T, g: T, b: T) -> Srgb<T> {
-        Srgb { r: r, g: g, b: b }
+    fn to_srgb<U:Channel>(&self) -> Srgb<U> {
+        Srgb::from_linear(self)
+    }
+}
+
+impl<T:FloatChannel> ToSrgb for Hsluv<T> {
+    #[inline]
+    fn to_srgb<U:Channel>(&self) -> Srgb<U> {
+        Srgb::from_linear(self)
+    }
+}
+
+impl<T:FloatChannel> ToSrgb for Hpluv<T> {
+    #[inline]
+    fn to_srgb<U:Channel>(&self) -> Srgb<U> {
+        Srgb::from_linear(self)
+    }
+}
+
+impl<T:FloatChannel> ToSrgb for Jzazbz<T> {
+    #[inline]
+    fn to_srgb<U:Channel>(&self) -> Srgb<U> {
+        Srgb::from_linear(self)
+    }
+}
+
+impl<T:FloatChannel> ToSrgb for ICtCp<T> {
+    #[inline]
+    fn to_srgb<U:Channel>(&self) -> Srgb<U> {
+        Srgb::from_linear(self)
+    }
+}
+
+impl Srgb<u8> {
+    /// Looks up a CSS named color, ignoring ASCII case.
+    pub fn from_name(name: &str) -> Option<Srgb<u8>> {
+        let name = name.to_ascii_lowercase();
+        consts::NAMED_COLORS.binary_search_by(|&(n, _)| n.cmp(&name[..]))
+                            .ok()
+                            .map(|i| *consts::NAMED_COLORS[i].1)
+    }
+}
+
+impl<T: Channel> Srgb<T> {
+    /// Finds the CSS named color closest to this color by CIE76 color
+    /// difference.
+    pub fn nearest_name(&self) -> &'static str {
+        let target: Lab<f64> = self.to_lab();
+        let dist = |c: &Srgb<u8>| difference::cie76(&c.to_lab::<f64>(), &target);
+        consts::NAMED_COLORS.iter()
+                            .min_by(|a, b| dist(a.1).total_cmp(&dist(b.1)))
+                            .unwrap().0
+    }
+}
+
+/// Builds an `Srgb<u8>` from a `0xRRGGBB` literal, usable in `const` and
+/// `static` items.
+#[macro_export]
+macro_rules! const_srgb {
+    ( $v: expr ) => {
+        $crate::Srgb::new((($v >> 16) & 0xFF) as u8, (($v >> 8) & 0xFF) as u8, ($v & 0xFF) as u8)
+    };
+}
+
+/// CSS Color Module Level 4 named colors: https://www.w3.org/TR/css-color-4/#named-colors
+pub mod consts {
+    use Srgb;
+
+    pub static ALICEBLUE:               Srgb<u8> = Srgb::new(0xF0, 0xF8, 0xFF);
+    pub static ANTIQUEWHITE:            Srgb<u8> = Srgb::new(0xFA, 0xEB, 0xD7);
+    pub static AQUA:                    Srgb<u8> = Srgb::new(0x00, 0xFF, 0xFF);
+    pub static AQUAMARINE:              Srgb<u8> = Srgb::new(0x7F, 0xFF, 0xD4);
+    pub static AZURE:                   Srgb<u8> = Srgb::new(0xF0, 0xFF, 0xFF);
+    pub static BEIGE:                   Srgb<u8> = Srgb::new(0xF5, 0xF5, 0xDC);
+    pub static BISQUE:                  Srgb<u8> = Srgb::new(0xFF, 0xE4, 0xC4);
+    pub static BLACK:                   Srgb<u8> = Srgb::new(0x00, 0x00, 0x00);
+    pub static BLANCHEDALMOND:          Srgb<u8> = Srgb::new(0xFF, 0xEB, 0xCD);
+    pub static BLUE:                    Srgb<u8> = Srgb::new(0x00, 0x00, 0xFF);
+    pub static BLUEVIOLET:              Srgb<u8> = Srgb::new(0x8A, 0x2B, 0xE2);
+    pub static BROWN:                   Srgb<u8> = Srgb::new(0xA5, 0x2A, 0x2A);
+    pub static BURLYWOOD:               Srgb<u8> = Srgb::new(0xDE, 0xB8, 0x87);
+    pub static CADETBLUE:               Srgb<u8> = Srgb::new(0x5F, 0x9E, 0xA0);
+    pub static CHARTREUSE:              Srgb<u8> = Srgb::new(0x7F, 0xFF, 0x00);
+    pub static CHOCOLATE:               Srgb<u8> = Srgb::new(0xD2, 0x69, 0x1E);
+    pub static CORAL:                   Srgb<u8> = Srgb::new(0xFF, 0x7F, 0x50);
+    pub static CORNFLOWERBLUE:          Srgb<u8> = Srgb::new(0x64, 0x95, 0xED);
+    pub static CORNSILK:                Srgb<u8> = Srgb::new(0xFF, 0xF8, 0xDC);
+    pub static CRIMSON:                 Srgb<u8> = Srgb::new(0xDC, 0x14, 0x3C);
+    pub static CYAN:                    Srgb<u8> = Srgb::new(0x00, 0xFF, 0xFF);
+    pub static DARKBLUE:                Srgb<u8> = Srgb::new(0x00, 0x00, 0x8B);
+    pub static DARKCYAN:                Srgb<u8> = Srgb::new(0x00, 0x8B, 0x8B);
+    pub static DARKGOLDENROD:           Srgb<u8> = Srgb::new(0xB8, 0x86, 0x0B);
+    pub static DARKGRAY:                Srgb<u8> = Srgb::new(0xA9, 0xA9, 0xA9);
+    pub static DARKGREEN:               Srgb<u8> = Srgb::new(0x00, 0x64, 0x00);
+    pub static DARKGREY:                Srgb<u8> = Srgb::new(0xA9, 0xA9, 0xA9);
+    pub static DARKKHAKI:               Srgb<u8> = Srgb::new(0xBD, 0xB7, 0x6B);
+    pub static DARKMAGENTA:             Srgb<u8> = Srgb::new(0x8B, 0x00, 0x8B);
+    pub static DARKOLIVEGREEN:          Srgb<u8> = Srgb::new(0x55, 0x6B, 0x2F);
+    pub static DARKORANGE:              Srgb<u8> = Srgb::new(0xFF, 0x8C, 0x00);
+    pub static DARKORCHID:              Srgb<u8> = Srgb::new(0x99, 0x32, 0xCC);
+    pub static DARKRED:                 Srgb<u8> = Srgb::new(0x8B, 0x00, 0x00);
+    pub static DARKSALMON:              Srgb<u8> = Srgb::new(0xE9, 0x96, 0x7A);
+    pub static DARKSEAGREEN:            Srgb<u8> = Srgb::new(0x8F, 0xBC, 0x8F);
+    pub static DARKSLATEBLUE:           Srgb<u8> = Srgb::new(0x48, 0x3D, 0x8B);
+    pub static DARKSLATEGRAY:           Srgb<u8> = Srgb::new(0x2F, 0x4F, 0x4F);
+    pub static DARKSLATEGREY:           Srgb<u8> = Srgb::new(0x2F, 0x4F, 0x4F);
+    pub static DARKTURQUOISE:           Srgb<u8> = Srgb::new(0x00, 0xCE, 0xD1);
+    pub static DARKVIOLET:              Srgb<u8> = Srgb::new(0x94, 0x00, 0xD3);
+    pub static DEEPPINK:                Srgb<u8> = Srgb::new(0xFF, 0x14, 0x93);
+    pub static DEEPSKYBLUE:             Srgb<u8> = Srgb::new(0x00, 0xBF, 0xFF);
+    pub static DIMGRAY:                 Srgb<u8> = Srgb::new(0x69, 0x69, 0x69);
+    pub static DIMGREY:                 Srgb<u8> = Srgb::new(0x69, 0x69, 0x69);
+    pub static DODGERBLUE:              Srgb<u8> = Srgb::new(0x1E, 0x90, 0xFF);
+    pub static FIREBRICK:               Srgb<u8> = Srgb::new(0xB2, 0x22, 0x22);
+    pub static FLORALWHITE:             Srgb<u8> = Srgb::new(0xFF, 0xFA, 0xF0);
+    pub static FORESTGREEN:             Srgb<u8> = Srgb::new(0x22, 0x8B, 0x22);
+    pub static FUCHSIA:                 Srgb<u8> = Srgb::new(0xFF, 0x00, 0xFF);
+    pub static GAINSBORO:               Srgb<u8> = Srgb::new(0xDC, 0xDC, 0xDC);
+    pub static GHOSTWHITE:              Srgb<u8> = Srgb::new(0xF8, 0xF8, 0xFF);
+    pub static GOLD:                    Srgb<u8> = Srgb::new(0xFF, 0xD7, 0x00);
+    pub static GOLDENROD:               Srgb<u8> = Srgb::new(0xDA, 0xA5, 0x20);
+    pub static GRAY:                    Srgb<u8> = Srgb::new(0x80, 0x80, 0x80);
+    pub static GREEN:                   Srgb<u8> = Srgb::new(0x00, 0x80, 0x00);
+    pub static GREENYELLOW:             Srgb<u8> = Srgb::new(0xAD, 0xFF, 0x2F);
+    pub static GREY:                    Srgb<u8> = Srgb::new(0x80, 0x80, 0x80);
+    pub static HONEYDEW:                Srgb<u8> = Srgb::new(0xF0, 0xFF, 0xF0);
+    pub static HOTPINK:                 Srgb<u8> = Srgb::new(0xFF, 0x69, 0xB4);
+    pub static INDIANRED:               Srgb<u8> = Srgb::new(0xCD, 0x5C, 0x5C);
+    pub static INDIGO:                  Srgb<u8> = Srgb::new(0x4B, 0x00, 0x82);
+    pub static IVORY:                   Srgb<u8> = Srgb::new(0xFF, 0xFF, 0xF0);
+    pub static KHAKI:                   Srgb<u8> = Srgb::new(0xF0, 0xE6, 0x8C);
+    pub static LAVENDER:                Srgb<u8> = Srgb::new(0xE6, 0xE6, 0xFA);
+    pub static LAVENDERBLUSH:           Srgb<u8> = Srgb::new(0xFF, 0xF0, 0xF5);
+    pub static LAWNGREEN:               Srgb<u8> = Srgb::new(0x7C, 0xFC, 0x00);
+    pub static LEMONCHIFFON:            Srgb<u8> = Srgb::new(0xFF, 0xFA, 0xCD);
+    pub static LIGHTBLUE:               Srgb<u8> = Srgb::new(0xAD, 0xD8, 0xE6);
+    pub static LIGHTCORAL:              Srgb<u8> = Srgb::new(0xF0, 0x80, 0x80);
+    pub static LIGHTCYAN:               Srgb<u8> = Srgb::new(0xE0, 0xFF, 0xFF);
+    pub static LIGHTGOLDENRODYELLOW:    Srgb<u8> = Srgb::new(0xFA, 0xFA, 0xD2);
+    pub static LIGHTGRAY:               Srgb<u8> = Srgb::new(0xD3, 0xD3, 0xD3);
+    pub static LIGHTGREEN:              Srgb<u8> = Srgb::new(0x90, 0xEE, 0x90);
+    pub static LIGHTGREY:               Srgb<u8> = Srgb::new(0xD3, 0xD3, 0xD3);
+    pub static LIGHTPINK:               Srgb<u8> = Srgb::new(0xFF, 0xB6, 0xC1);
+    pub static LIGHTSALMON:             Srgb<u8> = Srgb::new(0xFF, 0xA0, 0x7A);
+    pub static LIGHTSEAGREEN:           Srgb<u8> = Srgb::new(0x20, 0xB2, 0xAA);
+    pub static LIGHTSKYBLUE:            Srgb<u8> = Srgb::new(0x87, 0xCE, 0xFA);
+    pub static LIGHTSLATEGRAY:          Srgb<u8> = Srgb::new(0x77, 0x88, 0x99);
+    pub static LIGHTSLATEGREY:          Srgb<u8> = Srgb::new(0x77, 0x88, 0x99);
+    pub static LIGHTSTEELBLUE:          Srgb<u8> = Srgb::new(0xB0, 0xC4, 0xDE);
+    pub static LIGHTYELLOW:             Srgb<u8> = Srgb::new(0xFF, 0xFF, 0xE0);
+    pub static LIME:                    Srgb<u8> = Srgb::new(0x00, 0xFF, 0x00);
+    pub static LIMEGREEN:               Srgb<u8> = Srgb::new(0x32, 0xCD, 0x32);
+    pub static LINEN:                   Srgb<u8> = Srgb::new(0xFA, 0xF0, 0xE6);
+    pub static MAGENTA:                 Srgb<u8> = Srgb::new(0xFF, 0x00, 0xFF);
+    pub static MAROON:                  Srgb<u8> = Srgb::new(0x80, 0x00, 0x00);
+    pub static MEDIUMAQUAMARINE:        Srgb<u8> = Srgb::new(0x66, 0xCD, 0xAA);
+    pub static MEDIUMBLUE:              Srgb<u8> = Srgb::new(0x00, 0x00, 0xCD);
+    pub static MEDIUMORCHID:            Srgb<u8> = Srgb::new(0xBA, 0x55, 0xD3);
+    pub static MEDIUMPURPLE:            Srgb<u8> = Srgb::new(0x93, 0x70, 0xDB);
+    pub static MEDIUMSEAGREEN:          Srgb<u8> = Srgb::new(0x3C, 0xB3, 0x71);
+    pub static MEDIUMSLATEBLUE:         Srgb<u8> = Srgb::new(0x7B, 0x68, 0xEE);
+    pub static MEDIUMSPRINGGREEN:       Srgb<u8> = Srgb::new(0x00, 0xFA, 0x9A);
+    pub static MEDIUMTURQUOISE:         Srgb<u8> = Srgb::new(0x48, 0xD1, 0xCC);
+    pub static MEDIUMVIOLETRED:         Srgb<u8> = Srgb::new(0xC7, 0x15, 0x85);
+    pub static MIDNIGHTBLUE:            Srgb<u8> = Srgb::new(0x19, 0x19, 0x70);
+    pub static MINTCREAM:               Srgb<u8> = Srgb::new(0xF5, 0xFF, 0xFA);
+    pub static MISTYROSE:               Srgb<u8> = Srgb::new(0xFF, 0xE4, 0xE1);
+    pub static MOCCASIN:                Srgb<u8> = Srgb::new(0xFF, 0xE4, 0xB5);
+    pub static NAVAJOWHITE:             Srgb<u8> = Srgb::new(0xFF, 0xDE, 0xAD);
+    pub static NAVY:                    Srgb<u8> = Srgb::new(0x00, 0x00, 0x80);
+    pub static OLDLACE:                 Srgb<u8> = Srgb::new(0xFD, 0xF5, 0xE6);
+    pub static OLIVE:                   Srgb<u8> = Srgb::new(0x80, 0x80, 0x00);
+    pub static OLIVEDRAB:               Srgb<u8> = Srgb::new(0x6B, 0x8E, 0x23);
+    pub static ORANGE:                  Srgb<u8> = Srgb::new(0xFF, 0xA5, 0x00);
+    pub static ORANGERED:               Srgb<u8> = Srgb::new(0xFF, 0x45, 0x00);
+    pub static ORCHID:                  Srgb<u8> = Srgb::new(0xDA, 0x70, 0xD6);
+    pub static PALEGOLDENROD:           Srgb<u8> = Srgb::new(0xEE, 0xE8, 0xAA);
+    pub static PALEGREEN:               Srgb<u8> = Srgb::new(0x98, 0xFB, 0x98);
+    pub static PALETURQUOISE:           Srgb<u8> = Srgb::new(0xAF, 0xEE, 0xEE);
+    pub static PALEVIOLETRED:           Srgb<u8> = Srgb::new(0xDB, 0x70, 0x93);
+    pub static PAPAYAWHIP:              Srgb<u8> = Srgb::new(0xFF, 0xEF, 0xD5);
+    pub static PEACHPUFF:               Srgb<u8> = Srgb::new(0xFF, 0xDA, 0xB9);
+    pub static PERU:                    Srgb<u8> = Srgb::new(0xCD, 0x85, 0x3F);
+    pub static PINK:                    Srgb<u8> = Srgb::new(0xFF, 0xC0, 0xCB);
+    pub static PLUM:                    Srgb<u8> = Srgb::new(0xDD, 0xA0, 0xDD);
+    pub static POWDERBLUE:              Srgb<u8> = Srgb::new(0xB0, 0xE0, 0xE6);
+    pub static PURPLE:                  Srgb<u8> = Srgb::new(0x80, 0x00, 0x80);
+    pub static REBECCAPURPLE:           Srgb<u8> = Srgb::new(0x66, 0x33, 0x99);
+    pub static RED:                     Srgb<u8> = Srgb::new(0xFF, 0x00, 0x00);
+    pub static ROSYBROWN:               Srgb<u8> = Srgb::new(0xBC, 0x8F, 0x8F);
+    pub static ROYALBLUE:               Srgb<u8> = Srgb::new(0x41, 0x69, 0xE1);
+    pub static SADDLEBROWN:             Srgb<u8> = Srgb::new(0x8B, 0x45, 0x13);
+    pub static SALMON:                  Srgb<u8> = Srgb::new(0xFA, 0x80, 0x72);
+    pub static SANDYBROWN:              Srgb<u8> = Srgb::new(0xFA, 0xA4, 0x60);
+    pub static SEAGREEN:                Srgb<u8> = Srgb::new(0x2E, 0x8B, 0x57);
+    pub static SEASHELL:                Srgb<u8> = Srgb::new(0xFF, 0xF5, 0xEE);
+    pub static SIENNA:                  Srgb<u8> = Srgb::new(0xA0, 0x52, 0x2D);
+    pub static SILVER:                  Srgb<u8> = Srgb::new(0xC0, 0xC0, 0xC0);
+    pub static SKYBLUE:                 Srgb<u8> = Srgb::new(0x87, 0xCE, 0xEB);
+    pub static SLATEBLUE:               Srgb<u8> = Srgb::new(0x6A, 0x5A, 0xCD);
+    pub static SLATEGRAY:               Srgb<u8> = Srgb::new(0x70, 0x80, 0x90);
+    pub static SLATEGREY:               Srgb<u8> = Srgb::new(0x70, 0x80, 0x90);
+    pub static SNOW:                    Srgb<u8> = Srgb::new(0xFF, 0xFA, 0xFA);
+    pub static SPRINGGREEN:             Srgb<u8> = Srgb::new(0x00, 0xFF, 0x7F);
+    pub static STEELBLUE:               Srgb<u8> = Srgb::new(0x46, 0x82, 0xB4);
+    pub static TAN:                     Srgb<u8> = Srgb::new(0xD2, 0xB4, 0x8C);
+    pub static TEAL:                    Srgb<u8> = Srgb::new(0x00, 0x80, 0x80);
+    pub static THISTLE:                 Srgb<u8> = Srgb::new(0xD8, 0xBF, 0xD8);
+    pub static TOMATO:                  Srgb<u8> = Srgb::new(0xFF, 0x63, 0x47);
+    pub static TURQUOISE:               Srgb<u8> = Srgb::new(0x40, 0xE0, 0xD0);
+    pub static VIOLET:                  Srgb<u8> = Srgb::new(0xEE, 0x82, 0xEE);
+    pub static WHEAT:                   Srgb<u8> = Srgb::new(0xF5, 0xDE, 0xB3);
+    pub static WHITE:                   Srgb<u8> = Srgb::new(0xFF, 0xFF, 0xFF);
+    pub static WHITESMOKE:              Srgb<u8> = Srgb::new(0xF5, 0xF5, 0xF5);
+    pub static YELLOW:                  Srgb<u8> = Srgb::new(0xFF, 0xFF, 0x00);
+    pub static YELLOWGREEN:             Srgb<u8> = Srgb::new(0x9A, 0xCD, 0x32);
+
+    /// Every named color, keyed by its lowercase CSS name and sorted by name.
+    pub static NAMED_COLORS: [(&str, &Srgb<u8>); 148] = [
+        ("aliceblue", &ALICEBLUE),
+        ("antiquewhite", &ANTIQUEWHITE),
+        ("aqua", &AQUA),
+        ("aquamarine", &AQUAMARINE),
+        ("azure", &AZURE),
+        ("beige", &BEIGE),
+        ("bisque", &BISQUE),
+        ("black", &BLACK),
+        ("blanchedalmond", &BLANCHEDALMOND),
+        ("blue", &BLUE),
+        ("blueviolet", &BLUEVIOLET),
+        ("brown", &BROWN),
+        ("burlywood", &BURLYWOOD),
+        ("cadetblue", &CADETBLUE),
+        ("chartreuse", &CHARTREUSE),
+        ("chocolate", &CHOCOLATE),
+        ("coral", &CORAL),
+        ("cornflowerblue", &CORNFLOWERBLUE),
+        ("cornsilk", &CORNSILK),
+        ("crimson", &CRIMSON),
+        ("cyan", &CYAN),
+        ("darkblue", &DARKBLUE),
+        ("darkcyan", &DARKCYAN),
+        ("darkgoldenrod", &DARKGOLDENROD),
+        ("darkgray", &DARKGRAY),
+        ("darkgreen", &DARKGREEN),
+        ("darkgrey", &DARKGREY),
+        ("darkkhaki", &DARKKHAKI),
+        ("darkmagenta", &DARKMAGENTA),
+        ("darkolivegreen", &DARKOLIVEGREEN),
+        ("darkorange", &DARKORANGE),
+        ("darkorchid", &DARKORCHID),
+        ("darkred", &DARKRED),
+        ("darksalmon", &DARKSALMON),
+        ("darkseagreen", &DARKSEAGREEN),
+        ("darkslateblue", &DARKSLATEBLUE),
+        ("darkslategray", &DARKSLATEGRAY),
+        ("darkslategrey", &DARKSLATEGREY),
+        ("darkturquoise", &DARKTURQUOISE),
+        ("darkviolet", &DARKVIOLET),
+        ("deeppink", &DEEPPINK),
+        ("deepskyblue", &DEEPSKYBLUE),
+        ("dimgray", &DIMGRAY),
+        ("dimgrey", &DIMGREY),
+        ("dodgerblue", &DODGERBLUE),
+        ("firebrick", &FIREBRICK),
+        ("floralwhite", &FLORALWHITE),
+        ("forestgreen", &FORESTGREEN),
+        ("fuchsia", &FUCHSIA),
+        ("gainsboro", &GAINSBORO),
+        ("ghostwhite", &GHOSTWHITE),
+        ("gold", &GOLD),
+        ("goldenrod", &GOLDENROD),
+        ("gray", &GRAY),
+        ("green", &GREEN),
+        ("greenyellow", &GREENYELLOW),
+        ("grey", &GREY),
+        ("honeydew", &HONEYDEW),
+        ("hotpink", &HOTPINK),
+        ("indianred", &INDIANRED),
+        ("indigo", &INDIGO),
+        ("ivory", &IVORY),
+        ("khaki", &KHAKI),
+        ("lavender", &LAVENDER),
+        ("lavenderblush", &LAVENDERBLUSH),
+        ("lawngreen", &LAWNGREEN),
+        ("lemonchiffon", &LEMONCHIFFON),
+        ("lightblue", &LIGHTBLUE),
+        ("lightcoral", &LIGHTCORAL),
+        ("lightcyan", &LIGHTCYAN),
+        ("lightgoldenrodyellow", &LIGHTGOLDENRODYELLOW),
+        ("lightgray", &LIGHTGRAY),
+        ("lightgreen", &LIGHTGREEN),
+        ("lightgrey", &LIGHTGREY),
+        ("lightpink", &LIGHTPINK),
+        ("lightsalmon", &LIGHTSALMON),
+        ("lightseagreen", &LIGHTSEAGREEN),
+        ("lightskyblue", &LIGHTSKYBLUE),
+        ("lightslategray", &LIGHTSLATEGRAY),
+        ("lightslategrey", &LIGHTSLATEGREY),
+        ("lightsteelblue", &LIGHTSTEELBLUE),
+        ("lightyellow", &LIGHTYELLOW),
+        ("lime", &LIME),
+        ("limegreen", &LIMEGREEN),
+        ("linen", &LINEN),
+        ("magenta", &MAGENTA),
+        ("maroon", &MAROON),
+        ("mediumaquamarine", &MEDIUMAQUAMARINE),
+        ("mediumblue", &MEDIUMBLUE),
+        ("mediumorchid", &MEDIUMORCHID),
+        ("mediumpurple", &MEDIUMPURPLE),
+        ("mediumseagreen", &MEDIUMSEAGREEN),
+        ("mediumslateblue", &MEDIUMSLATEBLUE),
+        ("mediumspringgreen", &MEDIUMSPRINGGREEN),
+        ("mediumturquoise", &MEDIUMTURQUOISE),
+        ("mediumvioletred", &MEDIUMVIOLETRED),
+        ("midnightblue", &MIDNIGHTBLUE),
+        ("mintcream", &MINTCREAM),
+        ("mistyrose", &MISTYROSE),
+        ("moccasin", &MOCCASIN),
+        ("navajowhite", &NAVAJOWHITE),
+        ("navy", &NAVY),
+        ("oldlace", &OLDLACE),
+        ("olive", &OLIVE),
+        ("olivedrab", &OLIVEDRAB),
+        ("orange", &ORANGE),
+        ("orangered", &ORANGERED),
+        ("orchid", &ORCHID),
+        ("palegoldenrod", &PALEGOLDENROD),
+        ("palegreen", &PALEGREEN),
+        ("paleturquoise", &PALETURQUOISE),
+        ("palevioletred", &PALEVIOLETRED),
+        ("papayawhip", &PAPAYAWHIP),
+        ("peachpuff", &PEACHPUFF),
+        ("peru", &PERU),
+        ("pink", &PINK),
+        ("plum", &PLUM),
+        ("powderblue", &POWDERBLUE),
+        ("purple", &PURPLE),
+        ("rebeccapurple", &REBECCAPURPLE),
+        ("red", &RED),
+        ("rosybrown", &ROSYBROWN),
+        ("royalblue", &ROYALBLUE),
+        ("saddlebrown", &SADDLEBROWN),
+        ("salmon", &SALMON),
+        ("sandybrown", &SANDYBROWN),
+        ("seagreen", &SEAGREEN),
+        ("seashell", &SEASHELL),
+        ("sienna", &SIENNA),
+        ("silver", &SILVER),
+        ("skyblue", &SKYBLUE),
+        ("slateblue", &SLATEBLUE),
+        ("slategray", &SLATEGRAY),
+        ("slategrey", &SLATEGREY),
+        ("snow", &SNOW),
+        ("springgreen", &SPRINGGREEN),
+        ("steelblue", &STEELBLUE),
+        ("tan", &TAN),
+        ("teal", &TEAL),
+        ("thistle", &THISTLE),
+        ("tomato", &TOMATO),
+        ("turquoise", &TURQUOISE),
+        ("violet", &VIOLET),
+        ("wheat", &WHEAT),
+        ("white", &WHITE),
+        ("whitesmoke", &WHITESMOKE),
+        ("yellow", &YELLOW),
+        ("yellowgreen", &YELLOWGREEN),
+    ];
+}
+
+/// `decode` of each `u8` value, computed on first use.
+#[cfg(feature = "std")]
+static DECODE_U8: OnceLock<[f64; 256]> = OnceLock::new();
+
+/// `decode` of each `u16` value, computed on first use.
+#[cfg(feature = "srgb_lut16")]
+static DECODE_U16: OnceLock<Vec<f32>> = OnceLock::new();
+
+/// The linear values at which `encode` to `u8` steps up: the least `f64`
+/// that encodes to `1`, to `2`, and so on to `255`. Computed on first use.
+#[cfg(feature = "srgb_lut16")]
+static ENCODE_U8: OnceLock<[f64; 255]> = OnceLock::new();
+
+/// `decode` of a `u8` channel, from a table. The results are those of
+/// `decode`.
+#[cfg(feature = "std")]
+#[inline]
+pub(crate) fn decode_u8(v: u8) -> f64 {
+    DECODE_U8.get_or_init(|| {
+        let mut table = [0.0; 256];
+        for (i, x) in table.iter_mut().enumerate() {
+            *x = decode(i as f64 / 255.0);
+        }
+        table
+    })[v as usize]
+}
+
+/// `decode` of a `u8` channel. Without `std` there is no `OnceLock` to hold
+/// the table, so it is computed each time.
+#[cfg(not(feature = "std"))]
+#[inline]
+pub(crate) fn decode_u8(v: u8) -> f64 {
+    decode(v as f64 / 255.0)
+}
+
+/// `decode` of a `u16` channel, from a table of `f32`, so to within `1e-7`.
+#[cfg(feature = "srgb_lut16")]
+#[inline]
+pub(crate) fn decode_u16(v: u16) -> f64 {
+    DECODE_U16.get_or_init(|| {
+        (0..=0xFFFF).map(|i| decode(i as f64 / 65535.0) as f32).collect()
+    })[v as usize] as f64
+}
+
+/// `encode` of linear light to a `u8` channel, by a binary search of the
+/// values at which it steps up. The results are those of `encode`.
+#[cfg(feature = "srgb_lut16")]
+#[inline]
+pub(crate) fn encode_u8(v: f64) -> u8 {
+    let steps = ENCODE_U8.get_or_init(|| {
+        let mut steps = [0.0; 255];
+        for (k, step) in steps.iter_mut().enumerate() {
+            // bisect the bits, which order like the values for positive floats
+            let (mut lo, mut hi) = (0u64, 2f64.to_bits());
+            while lo < hi {
+                let mid = lo + (hi - lo) / 2;
+                if encode(f64::from_bits(mid)).to_channel_u8() as usize > k { hi = mid } else { lo = mid + 1 }
+            }
+            *step = f64::from_bits(lo);
+        }
+        steps
+    });
+    // NaN becomes 0
+    steps.partition_point(|&step| step <= v) as u8
+}
+
+/// The sRGB electro-optical transfer function, taking an encoded value to
+/// linear light.
+#[inline]
+pub(crate) fn decode(v: f64) -> f64 {
+    if v <= 0.04045 { v / 12.92 } else { ((v + 0.055) / 1.055).powf(2.4) }
+}
+
+/// The inverse of `decode`, taking linear light to an encoded value.
+#[inline]
+pub(crate) fn encode(v: f64) -> f64 {
+    if v <= 0.0031308 { v * 12.92 } else { 1.055 * v.powf(1.0 / 2.4) - 0.055 }
+}
+
+#[cfg(test)]
+mod tests {
+    use {Color, Rgb, ToRgb, Srgb, ToSrgb};
+    use super::consts;
+
+    #[test]
+    fn test_const_srgb() {
+        const PALETTE: [Srgb<u8>; 2] = [const_srgb!(0x336699), Srgb::new(0xFF, 0xCC, 0x00)];
+        assert_eq!(PALETTE[0], Srgb::new(0x33, 0x66, 0x99));
+        assert_eq!(PALETTE[1], const_srgb!(0xFFCC00));
+    }
+
+    #[test]
+    fn test_named_colors() {
+        assert_eq!(Srgb::from_name("rebeccapurple"), Some(Srgb::<u8>::new(0x66, 0x33, 0x99)));
+        assert_eq!(Srgb::from_name("DarkSlateGrey"), Some(Srgb::<u8>::new(0x2F, 0x4F, 0x4F)));
+        assert_eq!(Srgb::from_name("notacolor"), None);
+        for &(name, c) in consts::NAMED_COLORS.iter() {
+            assert_eq!(Srgb::from_name(name), Some(*c));
+        }
+    }
+
+    #[test]
+    fn test_nearest_name() {
+        assert_eq!(Srgb::<u8>::new(0xFF, 0x00, 0x00).nearest_name(), "red");
+        assert_eq!(Srgb::<u8>::new(0xFE, 0x01, 0x02).nearest_name(), "red");
+        assert_eq!(Srgb::<f32>::new(0.4, 0.2, 0.6).nearest_name(), "rebeccapurple");
+        assert_eq!(Srgb::<u8>::new(0x00, 0x00, 0x00).nearest_name(), "black");
+    }
+
+    #[test]
+    fn test_transfer() {
+        assert_eq!(Srgb::<u8>::new(0x00, 0xFF, 0x00).to_linear::<f32>(), Rgb::new(0.0, 1.0, 0.0));
+        assert_eq!(Srgb::<u8>::new(0xBC, 0x80, 0x0D).to_rgb::<u8>(),     Rgb::new(0x80, 0x37, 0x01));
+        let mid = Rgb::<f64>::new(0.5, 0.5, 0.5).to_srgb::<f64>();
+        assert!((mid.r - 0.7354).abs() < 1e-4);
+        assert!((super::decode(mid.r) - 0.5).abs() < 1e-12);
+        assert_eq!(super::encode(0.002), 0.002 * 12.92);
+    }
+
+    #[test]
+    fn test_tables() {
+        for i in 0..=0xFFu8 {
+            assert_eq!(super::decode_u8(i), super::decode(i as f64 / 255.0));
+        }
+        assert_eq!(Srgb::<u8>::new(0x00, 0x80, 0xFF).to_linear::<f64>(),
+                   Srgb::<f64>::new(0.0, 0x80 as f64 / 255.0, 1.0).to_linear());
+    }
+
+    #[cfg(feature = "srgb_lut16")]
+    #[test]
+    fn test_tables_16() {
+        use Channel;
+
+        for i in (0..=0xFFFFu16).step_by(7) {
+            assert!((super::decode_u16(i) - super::decode(i as f64 / 65535.0)).abs() < 1e-7);
+        }
+        for i in 0..=0xFFFFu16 {
+            let v = i as f64 / 65535.0;
+            assert_eq!(super::encode_u8(v), super::encode(v).to_channel_u8(), "{}", v);
+        }
+        // on either side of every step
+        let steps = super::ENCODE_U8.get().unwrap();
+        for (k, &step) in (1..=0xFFu8).zip(steps.iter()) {
+            let below = f64::from_bits(step.to_bits() - 1);
+            assert_eq!((super::encode_u8(below), super::encode_u8(step)), (k - 1, k));
+            assert_eq!((super::encode(below).to_channel_u8(), super::encode(step).to_channel_u8()), (k - 1, k));
+        }
+        assert_eq!(super::encode_u8(-1.0), 0);
+        assert_eq!(super::encode_u8(f64::NAN), 0);
+        assert_eq!((super::encode_u8(1.0), super::encode_u8(2.0)), (super::encode(1.0).to_channel_u8(), 0xFF));
+    }
+
+    #[test]
+    fn test_srgb_mix() {
+        let mid = Srgb::<f64>::new(0.0, 0.0, 0.0).mix(Srgb::new(1.0, 1.0, 1.0), 0.5);
+        assert!((mid.r - 0.7354).abs() < 1e-4);
+        assert_eq!(Srgb::<f32>::new(0.0, 0.2, 1.0).mix(Srgb::new(1.0, 0.2, 0.0), 0.0).to_srgb::<u8>(),
+                   Srgb::new(0x00, 0x33, 0xFF));
     }
 }
@@ -0,0 +1,216 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! http://en.wikipedia.org/wiki/Color_temperature
+//!
+//! The Planckian locus is approximated with the cubic splines of Kim et al.,
+//! valid from 1667 K to 25000 K. Correlated color temperature is estimated
+//! with McCamy's formula, which is accurate to a few kelvin near the locus
+//! between roughly 2800 K and 6500 K and degrades further out.
+//!
+//! Tint is the signed distance from the locus along its normal in the CIE
+//! 1960 UCS, known as Duv. Positive tints lie above the locus, towards
+//! green, and negative ones below it, towards magenta. Together the two
+//! give the temperature and tint axes of a photo editor's white balance.
+
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use math::*;
+
+use {Channel, FloatChannel};
+use {Rgb, ToRgb, Srgb, ToXyY, Chromaticity, XyY};
+
+/// The range of temperatures, in kelvin, the locus approximation covers.
+pub const TEMPERATURE_RANGE: (f64, f64) = (1667.0, 25000.0);
+
+impl Chromaticity {
+    /// The chromaticity of a black body at `kelvin`, clamped to
+    /// `TEMPERATURE_RANGE`.
+    pub fn from_temperature(kelvin: f64) -> Chromaticity {
+        let t = kelvin.max(TEMPERATURE_RANGE.0).min(TEMPERATURE_RANGE.1);
+        let (t2, t3) = (t * t, t * t * t);
+        let x = if t <= 4000.0 {
+            -0.2661239e9 / t3 - 0.2343589e6 / t2 + 0.8776956e3 / t + 0.179910
+        } else {
+            -3.0258469e9 / t3 + 2.1070379e6 / t2 + 0.2226347e3 / t + 0.240390
+        };
+        let (x2, x3) = (x * x, x * x * x);
+        let y = if t <= 2222.0 {
+            -1.1063814 * x3 - 1.34811020 * x2 + 2.18555832 * x - 0.20219683
+        } else if t <= 4000.0 {
+            -0.9549476 * x3 - 1.37418593 * x2 + 2.09137015 * x - 0.16748867
+        } else {
+            3.0817580 * x3 - 5.87338670 * x2 + 3.75112997 * x - 0.37001483
+        };
+        Chromaticity::new(x, y)
+    }
+
+    /// Estimates the correlated color temperature in kelvin.
+    #[inline]
+    pub fn cct(&self) -> f64 {
+        let n = (self.x - 0.3320) / (0.1858 - self.y);
+        ((449.0 * n + 3525.0) * n + 6823.3) * n + 5520.33
+    }
+
+    /// The chromaticity `tint` (Duv) away from that of a black body at
+    /// `kelvin`, which is clamped to `TEMPERATURE_RANGE`.
+    pub fn from_temperature_tint(kelvin: f64, tint: f64) -> Chromaticity {
+        let t = kelvin.max(TEMPERATURE_RANGE.0).min(TEMPERATURE_RANGE.1);
+        let (u, v) = Chromaticity::from_temperature(t).to_uv();
+        let (nu, nv) = locus_normal(t);
+        Chromaticity::from_uv(u + tint * nu, v + tint * nv)
+    }
+
+    /// The `(kelvin, tint)` nearest to the chromaticity, with the
+    /// temperature clamped to `TEMPERATURE_RANGE`. Unlike `cct`, this is
+    /// accurate over the whole range, as it searches the locus rather than
+    /// fitting it.
+    pub fn temperature_tint(&self) -> (f64, f64) {
+        let (u, v) = self.to_uv();
+        let dist = |mired: f64| {
+            let (lu, lv) = Chromaticity::from_temperature(1e6 / mired).to_uv();
+            (u - lu) * (u - lu) + (v - lv) * (v - lv)
+        };
+        // the locus is close to uniform in mireds; scan coarsely, then
+        // narrow down around the nearest sample
+        let (lo, hi) = (1e6 / TEMPERATURE_RANGE.1, 1e6 / TEMPERATURE_RANGE.0);
+        let steps = 100;
+        let step = (hi - lo) / steps as f64;
+        let best = (0..=steps).map(|i| lo + step * i as f64)
+            .min_by(|a, b| dist(*a).total_cmp(&dist(*b)))
+            .unwrap();
+        let (mut a, mut b) = ((best - step).max(lo), (best + step).min(hi));
+        for _ in 0..60 {
+            let (m1, m2) = (a + (b - a) / 3.0, b - (b - a) / 3.0);
+            if dist(m1) < dist(m2) { b = m2 } else { a = m1 }
+        }
+        let t = 1e6 / ((a + b) / 2.0);
+        let (lu, lv) = Chromaticity::from_temperature(t).to_uv();
+        let (nu, nv) = locus_normal(t);
+        (t, (u - lu) * nu + (v - lv) * nv)
+    }
+
+    /// The CIE 1960 UCS coordinates.
+    #[inline]
+    fn to_uv(self) -> (f64, f64) {
+        let d = -2.0 * self.x + 12.0 * self.y + 3.0;
+        (4.0 * self.x / d, 6.0 * self.y / d)
+    }
+
+    #[inline]
+    fn from_uv(u: f64, v: f64) -> Chromaticity {
+        let d = 2.0 * u - 8.0 * v + 4.0;
+        Chromaticity::new(3.0 * u / d, 2.0 * v / d)
+    }
+}
+
+/// The unit normal to the Planckian locus at `kelvin` in the CIE 1960 UCS,
+/// pointing above the locus, towards green.
+fn locus_normal(kelvin: f64) -> (f64, f64) {
+    let (u0, v0) = Chromaticity::from_temperature(kelvin - 1.0).to_uv();
+    let (u1, v1) = Chromaticity::from_temperature(kelvin + 1.0).to_uv();
+    let (du, dv) = (u1 - u0, v1 - v0);
+    let len = (du * du + dv * dv).sqrt();
+    // u falls as the temperature rises, so (dv, -du) points up in v
+    (dv / len, -du / len)
+}
+
+impl<T: Channel> Rgb<T> {
+    /// The linear light color of a black body at `kelvin`, scaled so that
+    /// its largest component is `1`. Components outside the sRGB gamut are
+    /// clipped to `0`.
+    pub fn from_temperature(kelvin: f64) -> Rgb<T> {
+        let c = Chromaticity::from_temperature(kelvin);
+        let rgb = XyY::new(c.x, c.y, 1.0).to_rgb::<f64>();
+        let (r, g, b) = (rgb.r.max(0.0), rgb.g.max(0.0), rgb.b.max(0.0));
+        let max = r.max(g).max(b);
+        Rgb::new(Channel::from(r / max), Channel::from(g / max), Channel::from(b / max))
+    }
+
+    /// Estimates the correlated color temperature of the color in kelvin.
+    #[inline]
+    pub fn cct(&self) -> f64 {
+        self.to_xyy::<f64>().chromaticity().cct()
+    }
+}
+
+impl<T: Channel> Srgb<T> {
+    /// The color of a black body at `kelvin`, scaled so that its largest
+    /// component is `1`.
+    #[inline]
+    pub fn from_temperature(kelvin: f64) -> Srgb<T> {
+        Srgb::from_linear(&Rgb::<f64>::from_temperature(kelvin))
+    }
+
+    /// Estimates the correlated color temperature of the color in kelvin.
+    #[inline]
+    pub fn cct(&self) -> f64 {
+        self.to_xyy::<f64>().chromaticity().cct()
+    }
+}
+
+impl<T: FloatChannel> XyY<T> {
+    /// Estimates the correlated color temperature of the color in kelvin.
+    #[inline]
+    pub fn cct(&self) -> f64 {
+        self.chromaticity().cct()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {Rgb, Srgb, Chromaticity, WhitePoint};
+
+    #[test]
+    fn test_cct() {
+        assert!((Chromaticity::D65.cct() - 6504.0).abs() < 5.0, "{}", Chromaticity::D65.cct());
+        assert!((Chromaticity::A.cct() - 2856.0).abs() < 5.0, "{}", Chromaticity::A.cct());
+        assert!((Rgb::<f64>::new(1.0, 1.0, 1.0).cct() - WhitePoint::D65.chromaticity().cct()).abs() < 1.0);
+        for &t in &[2500.0, 3000.0, 4500.0, 6500.0, 9000.0] {
+            let cct = Chromaticity::from_temperature(t).cct();
+            assert!((cct - t).abs() / t < 0.01, "{} != {}", cct, t);
+        }
+    }
+
+    #[test]
+    fn test_from_temperature() {
+        let warm = Rgb::<f64>::from_temperature(2000.0);
+        assert!(warm.r == 1.0 && warm.g < 0.5 && warm.b < warm.g, "{:?}", warm);
+        let cool = Rgb::<f64>::from_temperature(15000.0);
+        assert!(cool.b == 1.0 && cool.r < cool.g, "{:?}", cool);
+        let white = Srgb::<f64>::from_temperature(6500.0);
+        assert!(white.r > 0.95 && white.g > 0.95 && white.b > 0.95, "{:?}", white);
+        assert_eq!(Chromaticity::from_temperature(100.0), Chromaticity::from_temperature(1667.0));
+    }
+
+    #[test]
+    fn test_temperature_tint() {
+        let (a, b) = (Chromaticity::from_temperature_tint(5000.0, 0.0), Chromaticity::from_temperature(5000.0));
+        assert!((a.x - b.x).abs() < 1e-12 && (a.y - b.y).abs() < 1e-12);
+        for &(t, tint) in &[(2000.0, 0.0), (3200.0, 0.01), (6500.0, -0.005), (12000.0, 0.02)] {
+            let (t2, tint2) = Chromaticity::from_temperature_tint(t, tint).temperature_tint();
+            assert!((t2 - t).abs() / t < 1e-3, "{} != {}", t2, t);
+            assert!((tint2 - tint).abs() < 1e-5, "{} != {}", tint2, tint);
+        }
+        // D65 sits a little above the locus, on the green side
+        let (t, tint) = Chromaticity::D65.temperature_tint();
+        assert!((t - 6504.0).abs() < 10.0, "{}", t);
+        assert!((tint - 0.0032).abs() < 3e-4, "{}", tint);
+        // positive tints are greener
+        let green = Chromaticity::from_temperature_tint(5000.0, 0.02);
+        let magenta = Chromaticity::from_temperature_tint(5000.0, -0.02);
+        assert!(green.y > magenta.y);
+    }
+}
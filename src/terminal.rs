@@ -0,0 +1,156 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! ANSI escape sequences for terminal colors.
+//!
+//! Colors are taken to be gamma-encoded, as terminals treat them. The
+//! nearest palette entries are found by Euclidean distance in sRGB, which is
+//! what terminal emulators and most tools use to downsample.
+
+use alloc::string::String;
+
+use {Rgb, ToRgb};
+
+/// Resets all attributes.
+pub const RESET: &str = "\x1b[0m";
+
+/// The default colors of the 16-color palette, as xterm defines them.
+pub static ANSI16: [Rgb<u8>; 16] = [
+    Rgb::new(0x00, 0x00, 0x00), Rgb::new(0xCD, 0x00, 0x00),
+    Rgb::new(0x00, 0xCD, 0x00), Rgb::new(0xCD, 0xCD, 0x00),
+    Rgb::new(0x00, 0x00, 0xEE), Rgb::new(0xCD, 0x00, 0xCD),
+    Rgb::new(0x00, 0xCD, 0xCD), Rgb::new(0xE5, 0xE5, 0xE5),
+    Rgb::new(0x7F, 0x7F, 0x7F), Rgb::new(0xFF, 0x00, 0x00),
+    Rgb::new(0x00, 0xFF, 0x00), Rgb::new(0xFF, 0xFF, 0x00),
+    Rgb::new(0x5C, 0x5C, 0xFF), Rgb::new(0xFF, 0x00, 0xFF),
+    Rgb::new(0x00, 0xFF, 0xFF), Rgb::new(0xFF, 0xFF, 0xFF),
+];
+
+/// The levels of each axis of the 6x6x6 color cube.
+const CUBE_LEVELS: [u8; 6] = [0x00, 0x5F, 0x87, 0xAF, 0xD7, 0xFF];
+
+#[inline]
+fn distance(a: Rgb<u8>, b: Rgb<u8>) -> u32 {
+    let d = |x: u8, y: u8| (x as i32 - y as i32).pow(2) as u32;
+    d(a.r, b.r) + d(a.g, b.g) + d(a.b, b.b)
+}
+
+/// The color of an entry in the xterm 256-color palette.
+pub fn xterm256_to_rgb(index: u8) -> Rgb<u8> {
+    match index {
+        0..=15 => ANSI16[index as usize],
+        16..=231 => {
+            let i = index - 16;
+            Rgb::new(CUBE_LEVELS[(i / 36) as usize], CUBE_LEVELS[(i / 6 % 6) as usize], CUBE_LEVELS[(i % 6) as usize])
+        }
+        _ => {
+            let v = 8 + 10 * (index - 232);
+            Rgb::new(v, v, v)
+        }
+    }
+}
+
+/// The nearest entry of the xterm 256-color palette, among the color cube
+/// and the gray ramp. The first 16 entries are skipped, as terminals let
+/// users redefine them.
+pub fn xterm256_index<C: ToRgb>(color: &C) -> u8 {
+    let c = color.to_rgb::<u8>();
+    let nearest_level = |v: u8| {
+        (0..6).min_by_key(|&i| (CUBE_LEVELS[i] as i32 - v as i32).abs()).unwrap() as u8
+    };
+    let cube = 16 + 36 * nearest_level(c.r) + 6 * nearest_level(c.g) + nearest_level(c.b);
+    let avg = (c.r as u32 + c.g as u32 + c.b as u32) / 3;
+    let gray = if avg < 8 { 232 } else { 232 + ((avg - 8 + 5) / 10).min(23) as u8 };
+    if distance(xterm256_to_rgb(gray), c) < distance(xterm256_to_rgb(cube), c) { gray } else { cube }
+}
+
+/// The nearest of the 16 basic colors, as an index from `0` to `15`.
+pub fn ansi16_index<C: ToRgb>(color: &C) -> u8 {
+    let c = color.to_rgb::<u8>();
+    (0..16).min_by_key(|&i| distance(ANSI16[i], c)).unwrap() as u8
+}
+
+/// The 24-bit foreground escape sequence for the color.
+pub fn truecolor_fg<C: ToRgb>(color: &C) -> String {
+    let c = color.to_rgb::<u8>();
+    format!("\x1b[38;2;{};{};{}m", c.r, c.g, c.b)
+}
+
+/// The 24-bit background escape sequence for the color.
+pub fn truecolor_bg<C: ToRgb>(color: &C) -> String {
+    let c = color.to_rgb::<u8>();
+    format!("\x1b[48;2;{};{};{}m", c.r, c.g, c.b)
+}
+
+/// The foreground escape sequence for the nearest 256-color entry.
+pub fn xterm256_fg<C: ToRgb>(color: &C) -> String {
+    format!("\x1b[38;5;{}m", xterm256_index(color))
+}
+
+/// The background escape sequence for the nearest 256-color entry.
+pub fn xterm256_bg<C: ToRgb>(color: &C) -> String {
+    format!("\x1b[48;5;{}m", xterm256_index(color))
+}
+
+/// The foreground escape sequence for the nearest basic color, using the
+/// `90`-`97` codes for the bright half.
+pub fn ansi16_fg<C: ToRgb>(color: &C) -> String {
+    let i = ansi16_index(color);
+    format!("\x1b[{}m", if i < 8 { 30 + i } else { 90 + i - 8 })
+}
+
+/// The background escape sequence for the nearest basic color, using the
+/// `100`-`107` codes for the bright half.
+pub fn ansi16_bg<C: ToRgb>(color: &C) -> String {
+    let i = ansi16_index(color);
+    format!("\x1b[{}m", if i < 8 { 40 + i } else { 100 + i - 8 })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Rgb;
+
+    #[test]
+    fn test_xterm256() {
+        assert_eq!(xterm256_to_rgb(16), Rgb::new(0, 0, 0));
+        assert_eq!(xterm256_to_rgb(196), Rgb::new(0xFF, 0, 0));
+        assert_eq!(xterm256_to_rgb(232), Rgb::new(8, 8, 8));
+        assert_eq!(xterm256_to_rgb(255), Rgb::new(0xEE, 0xEE, 0xEE));
+        for i in 16..=255u8 {
+            assert_eq!(xterm256_index(&xterm256_to_rgb(i)), i);
+        }
+        assert_eq!(xterm256_index(&0xFF0000_u32), 196);
+        assert_eq!(xterm256_index(&0x808080_u32), 244);
+        assert_eq!(xterm256_index(&0x336699_u32), 60);
+    }
+
+    #[test]
+    fn test_ansi16() {
+        assert_eq!(ansi16_index(&0xFF0000_u32), 9);
+        assert_eq!(ansi16_index(&0x800000_u32), 1);
+        assert_eq!(ansi16_index(&0x101010_u32), 0);
+        assert_eq!(ansi16_fg(&0xFF0000_u32), "\x1b[91m");
+        assert_eq!(ansi16_bg(&0x00CD00_u32), "\x1b[42m");
+    }
+
+    #[test]
+    fn test_escapes() {
+        assert_eq!(truecolor_fg(&0x336699_u32), "\x1b[38;2;51;102;153m");
+        assert_eq!(truecolor_bg(&Rgb::<f32>::new(1.0, 0.0, 0.0)), "\x1b[48;2;255;0;0m");
+        assert_eq!(xterm256_fg(&0xFF0000_u32), "\x1b[38;5;196m");
+        assert_eq!(xterm256_bg(&0x000000_u32), "\x1b[48;5;16m");
+    }
+}
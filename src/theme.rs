@@ -0,0 +1,236 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! UI color schemes derived from a single seed color, after Material
+//! Design's tonal palettes.
+//!
+//! A `TonalPalette` fixes a hue and chroma in Oklch and varies the
+//! lightness, given as a tone from `0` (black) to `100` (white). A `Theme`
+//! holds the palettes derived from the seed, and `Theme::light` and
+//! `Theme::dark` pick tones from them for each role in a `Scheme`, such that
+//! every `on_` color is readable on its partner. The results are
+//! gamma-encoded sRGB.
+
+use alloc::vec::Vec;
+
+use angle::Deg;
+
+use Channel;
+use {Srgb, ToSrgb, Oklch, ToOklch};
+use gamut::{map_to_srgb, GamutMapping};
+
+/// The tones of a palette most themes use, as in Material Design.
+pub const TONES: [f64; 13] = [0.0, 10.0, 20.0, 30.0, 40.0, 50.0, 60.0, 70.0, 80.0, 90.0, 95.0, 99.0, 100.0];
+
+/// Colors of a single Oklch hue and chroma at every lightness.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct TonalPalette {
+    /// The hue in degrees.
+    pub hue: f64,
+    /// The Oklch chroma. Tones near black and white cannot reach high
+    /// chromas within sRGB, and have it reduced.
+    pub chroma: f64,
+}
+
+impl TonalPalette {
+    #[inline]
+    pub fn new(hue: f64, chroma: f64) -> TonalPalette {
+        TonalPalette { hue, chroma }
+    }
+
+    /// The palette with the hue and chroma of `color`.
+    pub fn from_color<C: ToOklch>(color: &C) -> TonalPalette {
+        let lch = color.to_oklch::<f64>();
+        TonalPalette::new(lch.h.0, lch.c)
+    }
+
+    /// The color at `tone`, which is clamped to `[0, 100]`.
+    pub fn tone<U: Channel>(&self, tone: f64) -> Srgb<U> {
+        let l = tone.clamp(0.0, 100.0) / 100.0;
+        map_to_srgb(&Oklch::new(l, self.chroma, Deg(self.hue)), GamutMapping::Css)
+    }
+
+    /// The colors at each of `TONES`, from black to white.
+    pub fn ramp<U: Channel>(&self) -> Vec<Srgb<U>> {
+        TONES.iter().map(|&t| self.tone(t)).collect()
+    }
+}
+
+/// The tonal palettes of a theme.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Theme {
+    /// The seed's hue, with at least a moderate chroma.
+    pub primary: TonalPalette,
+    /// The seed's hue, muted.
+    pub secondary: TonalPalette,
+    /// A hue 60 degrees on from the seed's, for accents.
+    pub tertiary: TonalPalette,
+    /// Nearly gray, for surfaces and text.
+    pub neutral: TonalPalette,
+    /// Slightly more colorful than `neutral`, for outlines and variants.
+    pub neutral_variant: TonalPalette,
+    /// A red for errors.
+    pub error: TonalPalette,
+}
+
+impl Theme {
+    /// Derives the palettes from `seed`.
+    pub fn from_seed<C: ToOklch>(seed: &C) -> Theme {
+        let lch = seed.to_oklch::<f64>();
+        let hue = lch.h.0;
+        Theme {
+            primary: TonalPalette::new(hue, lch.c.max(0.1)),
+            secondary: TonalPalette::new(hue, (lch.c / 3.0).min(0.04)),
+            tertiary: TonalPalette::new((hue + 60.0) % 360.0, (lch.c / 2.0).clamp(0.04, 0.08)),
+            neutral: TonalPalette::new(hue, 0.01),
+            neutral_variant: TonalPalette::new(hue, 0.02),
+            error: TonalPalette::new(25.0, 0.19),
+        }
+    }
+
+    /// The scheme for a light background.
+    pub fn light(&self) -> Scheme {
+        self.scheme(&[40.0, 100.0, 90.0, 10.0, 99.0, 10.0, 90.0, 30.0, 50.0])
+    }
+
+    /// The scheme for a dark background.
+    pub fn dark(&self) -> Scheme {
+        self.scheme(&[80.0, 20.0, 30.0, 90.0, 10.0, 90.0, 30.0, 80.0, 60.0])
+    }
+
+    /// Picks the tones for each role: accent, on accent, container, on
+    /// container, surface, on surface, surface variant, on surface variant
+    /// and outline.
+    fn scheme(&self, t: &[f64; 9]) -> Scheme {
+        let roles = |p: &TonalPalette| Roles {
+            color: p.tone(t[0]),
+            on_color: p.tone(t[1]),
+            container: p.tone(t[2]),
+            on_container: p.tone(t[3]),
+        };
+        Scheme {
+            primary: roles(&self.primary),
+            secondary: roles(&self.secondary),
+            tertiary: roles(&self.tertiary),
+            error: roles(&self.error),
+            surface: self.neutral.tone(t[4]),
+            on_surface: self.neutral.tone(t[5]),
+            surface_variant: self.neutral_variant.tone(t[6]),
+            on_surface_variant: self.neutral_variant.tone(t[7]),
+            outline: self.neutral_variant.tone(t[8]),
+        }
+    }
+}
+
+/// An accent color, the color of content drawn on it, and a quieter
+/// container version of both.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Roles {
+    pub color: Srgb<u8>,
+    pub on_color: Srgb<u8>,
+    pub container: Srgb<u8>,
+    pub on_container: Srgb<u8>,
+}
+
+/// The colors of a UI, by role.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Scheme {
+    pub primary: Roles,
+    pub secondary: Roles,
+    pub tertiary: Roles,
+    pub error: Roles,
+    pub surface: Srgb<u8>,
+    pub on_surface: Srgb<u8>,
+    pub surface_variant: Srgb<u8>,
+    pub on_surface_variant: Srgb<u8>,
+    pub outline: Srgb<u8>,
+}
+
+/// An interaction state of a control.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum State {
+    Hover,
+    Focus,
+    Pressed,
+    Dragged,
+}
+
+impl State {
+    /// The opacity of the content color laid over the control.
+    pub fn opacity(self) -> f64 {
+        match self {
+            State::Hover => 0.08,
+            State::Focus | State::Pressed => 0.12,
+            State::Dragged => 0.16,
+        }
+    }
+}
+
+/// The color of a control of color `base` in `state`: its content color
+/// `on` laid over it at the state's opacity, blended in encoded sRGB as
+/// browsers composite.
+pub fn state_layer<U: Channel>(base: &Srgb<u8>, on: &Srgb<u8>, state: State) -> Srgb<U> {
+    let a = state.opacity();
+    let (base, on) = (base.to_srgb::<f64>(), on.to_srgb::<f64>());
+    let blend = |b: f64, o: f64| b + (o - b) * a;
+    Srgb::new(blend(base.r, on.r), blend(base.g, on.g), blend(base.b, on.b)).to_srgb()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use contrast::is_aa;
+    use {Srgb, ToOklch};
+
+    #[test]
+    fn test_tonal_palette() {
+        let p = TonalPalette::from_color(&Srgb::<u8>::new(0x67, 0x50, 0xA4));
+        let ramp: Vec<Srgb<f64>> = p.ramp();
+        assert_eq!(ramp.len(), TONES.len());
+        assert_eq!(p.tone::<u8>(0.0), Srgb::new(0, 0, 0));
+        assert_eq!(p.tone::<u8>(100.0), Srgb::new(255, 255, 255));
+        let l: Vec<f64> = ramp.iter().map(|c| c.to_oklch::<f64>().l).collect();
+        assert!(l.windows(2).all(|w| w[0] < w[1]));
+        let mid = p.tone::<f64>(50.0).to_oklch::<f64>();
+        assert!((mid.l - 0.5).abs() < 1e-3);
+        assert!((mid.h.0 - p.hue).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_schemes() {
+        let theme = Theme::from_seed(&Srgb::<u8>::new(0x00, 0x80, 0x60));
+        for scheme in &[theme.light(), theme.dark()] {
+            for roles in &[scheme.primary, scheme.secondary, scheme.tertiary, scheme.error] {
+                assert!(is_aa(&roles.on_color, &roles.color), "{:?}", roles);
+                assert!(is_aa(&roles.on_container, &roles.container), "{:?}", roles);
+            }
+            assert!(is_aa(&scheme.on_surface, &scheme.surface));
+            assert!(is_aa(&scheme.on_surface_variant, &scheme.surface_variant));
+        }
+        assert!(theme.light().surface.to_oklch::<f64>().l > theme.dark().surface.to_oklch::<f64>().l);
+        // a gray seed still gives a usable primary
+        assert!(Theme::from_seed(&Srgb::<u8>::new(0x80, 0x80, 0x80)).primary.chroma >= 0.1);
+    }
+
+    #[test]
+    fn test_state_layer() {
+        let base = Srgb::new(0, 0, 0);
+        let on = Srgb::new(255, 255, 255);
+        let hover: Srgb<f64> = state_layer(&base, &on, State::Hover);
+        assert!((hover.r - 0.08).abs() < 1e-9);
+        let pressed: Srgb<u8> = state_layer(&base, &on, State::Pressed);
+        assert!(pressed.r > state_layer::<u8>(&base, &on, State::Hover).r);
+    }
+}
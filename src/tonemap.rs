@@ -0,0 +1,129 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tone mapping of high dynamic range linear light into `[0, 1]`.
+//!
+//! The operators are applied to each channel separately, which desaturates
+//! bright colors towards white much like film does. The results are still
+//! linear; encode them with `Srgb::from_linear` for display.
+
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use math::*;
+
+use {Channel, Rgb};
+
+impl<T: Channel> Rgb<T> {
+    /// Scales a linear color by `2^stops`, as opening up the aperture by
+    /// that many stops would. Integer channels saturate at their maximum.
+    #[inline]
+    pub fn expose(&self, stops: f64) -> Rgb<T> {
+        let scale = stops.exp2();
+        let f = |c: T| Channel::from(c.to_channel_f64() * scale);
+        Rgb::new(f(self.r), f(self.g), f(self.b))
+    }
+}
+
+/// A tone mapping curve.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Operator {
+    /// `x / (1 + x)`. Never quite reaches white.
+    Reinhard,
+    /// Reinhard with the given input value mapped to white.
+    ReinhardExtended { white: f32 },
+    /// Narkowicz's fit of the ACES filmic curve.
+    Aces,
+    /// John Hable's filmic curve from Uncharted 2, with white at `11.2`.
+    Hable,
+}
+
+impl Operator {
+    /// Maps a single non-negative value.
+    pub fn apply(self, x: f32) -> f32 {
+        let x = x.max(0.0);
+        match self {
+            Operator::Reinhard => x / (1.0 + x),
+            Operator::ReinhardExtended { white } => (x * (1.0 + x / (white * white)) / (1.0 + x)).min(1.0),
+            Operator::Aces => {
+                let x = x * 0.6;
+                ((x * (2.51 * x + 0.03)) / (x * (2.43 * x + 0.59) + 0.14)).clamp(0.0, 1.0)
+            }
+            Operator::Hable => (hable(x * 2.0) / hable(HABLE_WHITE)).min(1.0),
+        }
+    }
+}
+
+const HABLE_WHITE: f32 = 11.2;
+
+#[inline]
+fn hable(x: f32) -> f32 {
+    let (a, b, c, d, e, f) = (0.15, 0.50, 0.10, 0.20, 0.02, 0.30);
+    ((x * (a * x + c * b) + d * e) / (x * (a * x + b) + d * f)) - e / f
+}
+
+/// Tone maps a color after scaling it by `2^exposure`, so an exposure of
+/// `1` doubles the brightness and `-1` halves it.
+#[inline]
+pub fn tonemap(color: Rgb<f32>, operator: Operator, exposure: f32) -> Rgb<f32> {
+    let scale = exposure.exp2();
+    Rgb::new(operator.apply(color.r * scale), operator.apply(color.g * scale), operator.apply(color.b * scale))
+}
+
+/// Tone maps every pixel of an image in place.
+pub fn tonemap_slice(pixels: &mut [Rgb<f32>], operator: Operator, exposure: f32) {
+    for p in pixels.iter_mut() {
+        *p = tonemap(*p, operator, exposure);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Rgb;
+
+    const OPERATORS: [Operator; 4] = [Operator::Reinhard, Operator::ReinhardExtended { white: 4.0 },
+                                      Operator::Aces, Operator::Hable];
+
+    #[test]
+    fn test_curves() {
+        for &op in OPERATORS.iter() {
+            assert!(op.apply(0.0).abs() < 1e-6, "{:?}", op);
+            assert_eq!(op.apply(-1.0), op.apply(0.0));
+            let mut last = op.apply(0.0);
+            for i in 1..200 {
+                let y = op.apply(i as f32 * 0.1);
+                assert!(y >= last && y <= 1.0, "{:?} at {}", op, i);
+                last = y;
+            }
+        }
+        assert_eq!(Operator::Reinhard.apply(1.0), 0.5);
+        assert!(Operator::Reinhard.apply(1000.0) < 1.0);
+        assert_eq!(Operator::ReinhardExtended { white: 4.0 }.apply(4.0), 1.0);
+        assert!((Operator::Hable.apply(HABLE_WHITE / 2.0) - 1.0).abs() < 1e-6);
+        assert_eq!(Operator::Aces.apply(100.0), 1.0);
+    }
+
+    #[test]
+    fn test_exposure() {
+        let c = Rgb::new(0.5f32, 2.0, 8.0);
+        assert_eq!(tonemap(c, Operator::Reinhard, 1.0), tonemap(Rgb::new(1.0, 4.0, 16.0), Operator::Reinhard, 0.0));
+        let mut pixels = [c, Rgb::new(0.0, 0.0, 0.0)];
+        tonemap_slice(&mut pixels, Operator::Aces, -1.0);
+        assert_eq!(pixels[0], tonemap(c, Operator::Aces, -1.0));
+        assert!(pixels[0].r < pixels[0].g && pixels[0].g < pixels[0].b);
+        assert_eq!(Rgb::new(0.25f32, 1.0, 4.0).expose(2.0), Rgb::new(1.0, 4.0, 16.0));
+        assert_eq!(Rgb::<u8>::new(0x40, 0x80, 0xC0).expose(-1.0), Rgb::new(0x20, 0x40, 0x60));
+    }
+}
@@ -0,0 +1,178 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Interoperability with `wgpu`: the vertex and texture formats that hold
+//! this crate's color types as they are laid out in memory.
+//!
+//! The formats come from `wgpu-types`, which `wgpu` re-exports, so they are
+//! `wgpu::VertexFormat` and `wgpu::TextureFormat`. A vertex struct with an
+//! `Rgba<u8>` field can declare its attribute with
+//! `Rgba::<u8>::vertex_attribute(offset, location)`.
+//!
+//! Vertex fetch does not decode sRGB, so `Srgb` and `Srgba` have no vertex
+//! format: convert them to linear first. `Srgba<u8>` textures are
+//! `Rgba8UnormSrgb`, which the GPU decodes when sampling. Formats with
+//! three 8 or 16-bit channels do not exist, so neither do formats for `Rgb`
+//! of those channels.
+
+use wgpu_types::{BufferAddress, ShaderLocation, TextureFormat, VertexAttribute, VertexFormat};
+#[cfg(feature = "half")]
+use half::f16;
+
+use {Rgb, Rg, Rgba, Srgba, Luma};
+use gpu::{RgbF32Std140, RgbaF32Std140};
+use packed::Rgb10a2;
+
+/// Colors that can be vertex attributes.
+pub trait VertexColor: Copy {
+    /// The format of the color as a vertex attribute.
+    const VERTEX_FORMAT: VertexFormat;
+
+    /// The attribute of a color `offset` bytes into the vertex, read at
+    /// `shader_location`.
+    #[inline]
+    fn vertex_attribute(offset: BufferAddress, shader_location: ShaderLocation) -> VertexAttribute {
+        VertexAttribute { format: Self::VERTEX_FORMAT, offset, shader_location }
+    }
+}
+
+/// Colors that can be the texels of a texture.
+pub trait TextureColor: Copy {
+    /// The format of a texture of the color.
+    const TEXTURE_FORMAT: TextureFormat;
+}
+
+macro_rules! vertex_formats {
+    ($($ty:ty => $format:ident;)*) => {
+        $(
+            impl VertexColor for $ty {
+                const VERTEX_FORMAT: VertexFormat = VertexFormat::$format;
+            }
+        )*
+    }
+}
+
+macro_rules! texture_formats {
+    ($($ty:ty => $format:ident;)*) => {
+        $(
+            impl TextureColor for $ty {
+                const TEXTURE_FORMAT: TextureFormat = TextureFormat::$format;
+            }
+        )*
+    }
+}
+
+vertex_formats! {
+    Luma<u8> => Unorm8;
+    Luma<i8> => Snorm8;
+    Luma<u16> => Unorm16;
+    Luma<i16> => Snorm16;
+    Luma<f32> => Float32;
+    Luma<f64> => Float64;
+    Rg<u8> => Unorm8x2;
+    Rg<i8> => Snorm8x2;
+    Rg<u16> => Unorm16x2;
+    Rg<i16> => Snorm16x2;
+    Rg<f32> => Float32x2;
+    Rg<f64> => Float64x2;
+    Rgb<f32> => Float32x3;
+    Rgb<f64> => Float64x3;
+    Rgba<u8> => Unorm8x4;
+    Rgba<i8> => Snorm8x4;
+    Rgba<u16> => Unorm16x4;
+    Rgba<i16> => Snorm16x4;
+    Rgba<f32> => Float32x4;
+    Rgba<f64> => Float64x4;
+    Rgb10a2 => Unorm10_10_10_2;
+    RgbF32Std140 => Float32x3;
+    RgbaF32Std140 => Float32x4;
+}
+
+texture_formats! {
+    Luma<u8> => R8Unorm;
+    Luma<i8> => R8Snorm;
+    Luma<u16> => R16Unorm;
+    Luma<i16> => R16Snorm;
+    Luma<f32> => R32Float;
+    Rg<u8> => Rg8Unorm;
+    Rg<i8> => Rg8Snorm;
+    Rg<u16> => Rg16Unorm;
+    Rg<i16> => Rg16Snorm;
+    Rg<f32> => Rg32Float;
+    Rgba<u8> => Rgba8Unorm;
+    Srgba<u8> => Rgba8UnormSrgb;
+    Rgba<i8> => Rgba8Snorm;
+    Rgba<u16> => Rgba16Unorm;
+    Rgba<i16> => Rgba16Snorm;
+    Rgba<f32> => Rgba32Float;
+    Rgb10a2 => Rgb10a2Unorm;
+    RgbaF32Std140 => Rgba32Float;
+}
+
+#[cfg(feature = "half")]
+vertex_formats! {
+    Luma<f16> => Float16;
+    Rg<f16> => Float16x2;
+    Rgba<f16> => Float16x4;
+}
+
+#[cfg(feature = "half")]
+texture_formats! {
+    Luma<f16> => R16Float;
+    Rg<f16> => Rg16Float;
+    Rgba<f16> => Rgba16Float;
+}
+
+#[cfg(test)]
+mod tests {
+    use core::mem::size_of;
+
+    use wgpu_types::{TextureFormat, VertexFormat};
+
+    use super::{TextureColor, VertexColor};
+    use {Rgb, Rg, Rgba, Srgba, Luma};
+    use gpu::RgbaF32Std140;
+    use packed::Rgb10a2;
+
+    /// The vertex formats match the sizes of the types.
+    fn check_vertex<C: VertexColor>() {
+        assert_eq!(C::VERTEX_FORMAT.size() as usize, size_of::<C>(), "{:?}", C::VERTEX_FORMAT);
+    }
+
+    /// The texture formats match the sizes of the types.
+    fn check_texture<C: TextureColor>() {
+        assert_eq!(C::TEXTURE_FORMAT.block_copy_size(None), Some(size_of::<C>() as u32), "{:?}", C::TEXTURE_FORMAT);
+    }
+
+    #[test]
+    fn test_formats() {
+        check_vertex::<Luma<u8>>();
+        check_vertex::<Rg<i16>>();
+        check_vertex::<Rgb<f32>>();
+        check_vertex::<Rgba<u8>>();
+        check_vertex::<Rgba<f64>>();
+        check_vertex::<Rgb10a2>();
+        check_vertex::<RgbaF32Std140>();
+        check_texture::<Luma<f32>>();
+        check_texture::<Rg<u8>>();
+        check_texture::<Rgba<u16>>();
+        check_texture::<Srgba<u8>>();
+        check_texture::<Rgb10a2>();
+
+        assert_eq!(Srgba::<u8>::TEXTURE_FORMAT, TextureFormat::Rgba8UnormSrgb);
+        let attribute = Rgba::<u8>::vertex_attribute(12, 1);
+        assert_eq!((attribute.format, attribute.offset, attribute.shader_location), (VertexFormat::Unorm8x4, 12, 1));
+    }
+}
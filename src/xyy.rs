@@ -0,0 +1,204 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! http://en.wikipedia.org/wiki/CIE_1931_color_space#CIE_xy_chromaticity_diagram_and_the_CIE_xyY_color_space
+
+use {Channel, FloatChannel};
+use {Rgb, ToRgb, Srgb, Xyz, ToXyz, WhitePoint, ColorError};
+
+/// A point `(x, y)` on the CIE 1931 chromaticity diagram.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Chromaticity { pub x: f64, pub y: f64 }
+
+impl Chromaticity {
+    /// Incandescent / tungsten.
+    pub const A:   Chromaticity = Chromaticity { x: 0.44757, y: 0.40745 };
+    /// Average daylight, deprecated in favour of D65.
+    pub const C:   Chromaticity = Chromaticity { x: 0.31006, y: 0.31616 };
+    /// Horizon light, used by ICC profile connection spaces.
+    pub const D50: Chromaticity = Chromaticity { x: 0.34567, y: 0.35850 };
+    /// Mid-morning / mid-afternoon daylight.
+    pub const D55: Chromaticity = Chromaticity { x: 0.33242, y: 0.34743 };
+    /// Noon daylight, the reference white of sRGB.
+    pub const D65: Chromaticity = Chromaticity { x: 0.31271, y: 0.32902 };
+    /// North sky daylight.
+    pub const D75: Chromaticity = Chromaticity { x: 0.29902, y: 0.31485 };
+    /// Equal energy.
+    pub const E:   Chromaticity = Chromaticity { x: 1.0 / 3.0, y: 1.0 / 3.0 };
+    /// Daylight fluorescent.
+    pub const F1:  Chromaticity = Chromaticity { x: 0.31310, y: 0.33727 };
+    /// Cool white fluorescent.
+    pub const F2:  Chromaticity = Chromaticity { x: 0.37208, y: 0.37529 };
+    /// White fluorescent.
+    pub const F3:  Chromaticity = Chromaticity { x: 0.40910, y: 0.39430 };
+    /// Warm white fluorescent.
+    pub const F4:  Chromaticity = Chromaticity { x: 0.44018, y: 0.40329 };
+    /// Daylight fluorescent.
+    pub const F5:  Chromaticity = Chromaticity { x: 0.31379, y: 0.34531 };
+    /// Lite white fluorescent.
+    pub const F6:  Chromaticity = Chromaticity { x: 0.37790, y: 0.38835 };
+    /// Broadband D65 simulator.
+    pub const F7:  Chromaticity = Chromaticity { x: 0.31292, y: 0.32933 };
+    /// Broadband D50 simulator.
+    pub const F8:  Chromaticity = Chromaticity { x: 0.34588, y: 0.35875 };
+    /// Broadband cool white deluxe.
+    pub const F9:  Chromaticity = Chromaticity { x: 0.37417, y: 0.37281 };
+    /// Narrow tri-band, 5000 K.
+    pub const F10: Chromaticity = Chromaticity { x: 0.34609, y: 0.35986 };
+    /// Narrow tri-band, 4000 K.
+    pub const F11: Chromaticity = Chromaticity { x: 0.38052, y: 0.37713 };
+    /// Narrow tri-band, 3000 K.
+    pub const F12: Chromaticity = Chromaticity { x: 0.43695, y: 0.40441 };
+
+    #[inline]
+    pub const fn new(x: f64, y: f64) -> Chromaticity {
+        Chromaticity { x, y }
+    }
+
+    /// The white point with this chromaticity, normalized so that `y == 1`.
+    #[inline]
+    pub fn to_white_point(&self) -> WhitePoint {
+        WhitePoint { x: self.x / self.y, y: 1.0, z: (1.0 - self.x - self.y) / self.y }
+    }
+}
+
+impl WhitePoint {
+    /// The chromaticity of the white point.
+    #[inline]
+    pub fn chromaticity(&self) -> Chromaticity {
+        let sum = self.x + self.y + self.z;
+        Chromaticity::new(self.x / sum, self.y / sum)
+    }
+}
+
+/// A color given by its chromaticity `(x, y)` and its luminance `Y`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[repr(C)]
+pub struct XyY<T> { pub x: T, pub y: T, pub luminance: T }
+
+impl<T> XyY<T> {
+    #[inline]
+    pub const fn new(x: T, y: T, luminance: T) -> XyY<T> {
+        XyY { x, y, luminance }
+    }
+}
+
+impl<T: FloatChannel> XyY<T> {
+    /// Splits an XYZ color into chromaticity and luminance. Black has no
+    /// chromaticity of its own and is given that of `white`. Fails for a
+    /// degenerate white.
+    pub fn from_xyz_with_white<C: ToXyz>(color: &C, white: WhitePoint) -> Result<XyY<T>, ColorError> {
+        white.check()?;
+        Ok(XyY::from_xyz_unchecked(color, white))
+    }
+
+    fn from_xyz_unchecked<C: ToXyz>(color: &C, white: WhitePoint) -> XyY<T> {
+        let xyz = color.to_xyz::<f64>();
+        let sum = xyz.x + xyz.y + xyz.z;
+        let (x, y) = if sum == 0.0 {
+            let c = white.chromaticity();
+            (c.x, c.y)
+        } else {
+            (xyz.x / sum, xyz.y / sum)
+        };
+        XyY::new(Channel::from(x), Channel::from(y), Channel::from(xyz.y))
+    }
+
+    /// The chromaticity of the color.
+    #[inline]
+    pub fn chromaticity(&self) -> Chromaticity {
+        Chromaticity::new(self.x.to_channel_f64(), self.y.to_channel_f64())
+    }
+}
+
+pub trait ToXyY {
+    fn to_xyy<U:FloatChannel>(&self) -> XyY<U>;
+}
+
+impl<T:FloatChannel> ToXyY for XyY<T> {
+    #[inline]
+    fn to_xyy<U:FloatChannel>(&self) -> XyY<U> {
+        XyY::new(self.x.to_channel(), self.y.to_channel(), self.luminance.to_channel())
+    }
+}
+
+impl<T:FloatChannel> ToXyY for Xyz<T> {
+    #[inline]
+    fn to_xyy<U:FloatChannel>(&self) -> XyY<U> {
+        XyY::from_xyz_unchecked(self, WhitePoint::D65)
+    }
+}
+
+impl<T:Channel> ToXyY for Rgb<T> {
+    #[inline]
+    fn to_xyy<U:FloatChannel>(&self) -> XyY<U> {
+        self.to_xyz::<f64>().to_xyy()
+    }
+}
+
+impl<T:Channel> ToXyY for Srgb<T> {
+    /// Decodes the sRGB transfer function before converting.
+    #[inline]
+    fn to_xyy<U:FloatChannel>(&self) -> XyY<U> {
+        self.to_xyz::<f64>().to_xyy()
+    }
+}
+
+impl<T:FloatChannel> ToXyz for XyY<T> {
+    fn to_xyz<U:FloatChannel>(&self) -> Xyz<U> {
+        let (x, y, lum) = (self.x.to_channel_f64(), self.y.to_channel_f64(), self.luminance.to_channel_f64());
+        if y == 0.0 {
+            return Xyz::from_array([0.0; 3]);
+        }
+        Xyz::from_array([x * lum / y, lum, (1.0 - x - y) * lum / y])
+    }
+}
+
+impl<T:FloatChannel> ToRgb for XyY<T> {
+    #[inline]
+    fn to_rgb<U:Channel>(&self) -> Rgb<U> {
+        self.to_xyz::<f64>().to_rgb()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {XyY, ToXyY, Chromaticity, Rgb, Xyz, ToXyz, WhitePoint};
+
+    #[test]
+    fn test_xyz_to_xyy() {
+        let white = Rgb::<f64>::new(1.0, 1.0, 1.0).to_xyy::<f64>();
+        assert!((white.x - 0.3127).abs() < 1e-4 && (white.y - 0.3290).abs() < 1e-4 && (white.luminance - 1.0).abs() < 1e-9);
+        let black = Xyz::<f64>::new(0.0, 0.0, 0.0).to_xyy::<f64>();
+        assert_eq!(black.chromaticity(), WhitePoint::D65.chromaticity());
+        assert_eq!(black.luminance, 0.0);
+        let xyz = Xyz::<f64>::new(0.2, 0.3, 0.4).to_xyy::<f64>().to_xyz::<f64>();
+        assert!((xyz.x - 0.2).abs() < 1e-9 && (xyz.y - 0.3).abs() < 1e-9 && (xyz.z - 0.4).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_illuminants() {
+        for &(c, w) in &[(Chromaticity::A, WhitePoint::A), (Chromaticity::D50, WhitePoint::D50),
+                         (Chromaticity::D65, WhitePoint::D65), (Chromaticity::E, WhitePoint::E)] {
+            let p = c.to_white_point();
+            assert!((p.x - w.x).abs() < 1e-3 && (p.z - w.z).abs() < 1e-3, "{:?} != {:?}", p, w);
+            let back = w.chromaticity();
+            assert!((back.x - c.x).abs() < 1e-4 && (back.y - c.y).abs() < 1e-4, "{:?} != {:?}", back, c);
+        }
+        assert_eq!(XyY::new(0.5, 0.25, 0.3).chromaticity(), Chromaticity::new(0.5, 0.25));
+    }
+}
@@ -0,0 +1,240 @@
+// Copyright 2013 The color-rs developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! http://en.wikipedia.org/wiki/CIE_1931_color_space
+//!
+//! `Rgb` values are treated as linear light with the sRGB (ITU-R BT.709)
+//! primaries when converting to and from XYZ.
+
+use {Channel, FloatChannel};
+use {Rgb, ToRgb, Srgb, Hsv, Hsl, ColorError};
+use matrix::{self, Matrix3};
+
+/// Chromaticities `(x, y)` of the sRGB red, green and blue primaries.
+pub const SRGB_PRIMARIES: [(f64, f64); 3] = [(0.64, 0.33), (0.30, 0.60), (0.15, 0.06)];
+
+/// The matrix taking linear sRGB to XYZ relative to D65, which nearly
+/// every conversion uses, and its inverse.
+pub(crate) const SRGB_TO_XYZ: Matrix3 = match matrix::rgb_to_xyz(SRGB_PRIMARIES, WhitePoint::D65.to_array()) {
+    Some(m) => m,
+    None => panic!("degenerate sRGB primaries"),
+};
+pub(crate) const XYZ_TO_SRGB: Matrix3 = match matrix::inverse(&SRGB_TO_XYZ) {
+    Some(m) => m,
+    None => panic!("degenerate sRGB primaries"),
+};
+
+/// The matrix taking linear sRGB to XYZ relative to `white`.
+fn srgb_to_xyz(white: WhitePoint) -> Result<Matrix3, ColorError> {
+    if white == WhitePoint::D65 {
+        return Ok(SRGB_TO_XYZ);
+    }
+    white.check()?;
+    matrix::rgb_to_xyz(SRGB_PRIMARIES, white.to_array()).ok_or(ColorError::Degenerate)
+}
+
+/// The tristimulus values of a reference white, normalized so that `y == 1`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct WhitePoint { pub x: f64, pub y: f64, pub z: f64 }
+
+impl WhitePoint {
+    /// Incandescent / tungsten.
+    pub const A:   WhitePoint = WhitePoint { x: 1.09850, y: 1.0, z: 0.35585 };
+    /// Horizon light, used by ICC profile connection spaces.
+    pub const D50: WhitePoint = WhitePoint { x: 0.96422, y: 1.0, z: 0.82521 };
+    /// Mid-morning / mid-afternoon daylight.
+    pub const D55: WhitePoint = WhitePoint { x: 0.95682, y: 1.0, z: 0.92149 };
+    /// Noon daylight, the reference white of sRGB.
+    pub const D65: WhitePoint = WhitePoint { x: 0.95047, y: 1.0, z: 1.08883 };
+    /// North sky daylight.
+    pub const D75: WhitePoint = WhitePoint { x: 0.94972, y: 1.0, z: 1.22638 };
+    /// Equal energy.
+    pub const E:   WhitePoint = WhitePoint { x: 1.0,     y: 1.0, z: 1.0 };
+
+    /// A white point with the given tristimulus values, which must all be
+    /// finite and positive.
+    pub fn new(x: f64, y: f64, z: f64) -> Result<WhitePoint, ColorError> {
+        let white = WhitePoint { x, y, z };
+        white.check()?;
+        Ok(white)
+    }
+
+    /// Fails for whites that no color space can be relative to: those with
+    /// a zero, negative or non-finite component.
+    #[inline]
+    pub(crate) fn check(&self) -> Result<(), ColorError> {
+        if self.to_array().iter().all(|&v| v.is_finite() && v > 0.0) {
+            Ok(())
+        } else {
+            Err(ColorError::Degenerate)
+        }
+    }
+
+    #[inline]
+    pub fn to_xyz<T: FloatChannel>(&self) -> Xyz<T> {
+        Xyz::new(Channel::from(self.x), Channel::from(self.y), Channel::from(self.z))
+    }
+
+    #[inline]
+    pub const fn to_array(&self) -> [f64; 3] {
+        [self.x, self.y, self.z]
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[repr(C)]
+pub struct Xyz<T> { pub x: T, pub y: T, pub z: T }
+
+impl<T> Xyz<T> {
+    #[inline]
+    pub const fn new(x: T, y: T, z: T) -> Xyz<T> {
+        Xyz { x, y, z }
+    }
+}
+
+impl<T: FloatChannel> Xyz<T> {
+    /// Converts a color from linear RGB with the sRGB primaries, using
+    /// `white` as the reference white of the RGB space. Fails for a
+    /// degenerate white.
+    pub fn from_rgb_with_white<C: ToRgb>(color: &C, white: WhitePoint) -> Result<Xyz<T>, ColorError> {
+        let rgb = color.to_rgb::<f64>();
+        Ok(Xyz::from_array(matrix::mul_vec(&srgb_to_xyz(white)?, [rgb.r, rgb.g, rgb.b])))
+    }
+
+    /// Converts the color to linear RGB with the sRGB primaries, using
+    /// `white` as the reference white of the RGB space. Fails for a
+    /// degenerate white.
+    pub fn to_rgb_with_white<U: Channel>(&self, white: WhitePoint) -> Result<Rgb<U>, ColorError> {
+        let m = if white == WhitePoint::D65 {
+            XYZ_TO_SRGB
+        } else {
+            matrix::inverse(&srgb_to_xyz(white)?).ok_or(ColorError::Degenerate)?
+        };
+        let rgb = matrix::mul_vec(&m, self.to_array());
+        Ok(Rgb::new(Channel::from(rgb[0]), Channel::from(rgb[1]), Channel::from(rgb[2])))
+    }
+
+    #[inline]
+    pub(crate) fn from_array(v: [f64; 3]) -> Xyz<T> {
+        Xyz::new(Channel::from(v[0]), Channel::from(v[1]), Channel::from(v[2]))
+    }
+
+    #[inline]
+    pub(crate) fn to_array(self) -> [f64; 3] {
+        [self.x.to_channel_f64(), self.y.to_channel_f64(), self.z.to_channel_f64()]
+    }
+}
+
+pub trait ToXyz {
+    fn to_xyz<U:FloatChannel>(&self) -> Xyz<U>;
+}
+
+impl<T:FloatChannel> ToXyz for Xyz<T> {
+    #[inline]
+    fn to_xyz<U:FloatChannel>(&self) -> Xyz<U> {
+        Xyz::new(self.x.to_channel(), self.y.to_channel(), self.z.to_channel())
+    }
+}
+
+impl<T:Channel> ToXyz for Rgb<T> {
+    /// Converts from linear sRGB relative to D65.
+    #[inline]
+    fn to_xyz<U:FloatChannel>(&self) -> Xyz<U> {
+        let rgb = self.to_rgb::<f64>();
+        Xyz::from_array(matrix::mul_vec(&SRGB_TO_XYZ, [rgb.r, rgb.g, rgb.b]))
+    }
+}
+
+impl<T:Channel> ToXyz for Srgb<T> {
+    /// Decodes the sRGB transfer function before converting.
+    #[inline]
+    fn to_xyz<U:FloatChannel>(&self) -> Xyz<U> {
+        self.to_linear::<f64>().to_xyz()
+    }
+}
+
+impl<T:Channel> ToXyz for Hsv<T> {
+    /// Reads the color as sRGB encoded, as CSS does, and decodes it before
+    /// converting.
+    #[inline]
+    fn to_xyz<U:FloatChannel>(&self) -> Xyz<U> {
+        let c = self.to_rgb::<f64>();
+        Srgb::new(c.r, c.g, c.b).to_xyz()
+    }
+}
+
+impl<T:Channel> ToXyz for Hsl<T> {
+    /// Reads the color as sRGB encoded, as CSS does, and decodes it before
+    /// converting.
+    #[inline]
+    fn to_xyz<U:FloatChannel>(&self) -> Xyz<U> {
+        let c = self.to_rgb::<f64>();
+        Srgb::new(c.r, c.g, c.b).to_xyz()
+    }
+}
+
+impl<T:FloatChannel> ToRgb for Xyz<T> {
+    /// Converts to linear sRGB relative to D65.
+    #[inline]
+    fn to_rgb<U:Channel>(&self) -> Rgb<U> {
+        let rgb = matrix::mul_vec(&XYZ_TO_SRGB, self.to_array());
+        Rgb::new(Channel::from(rgb[0]), Channel::from(rgb[1]), Channel::from(rgb[2]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {Rgb, ToRgb, Xyz, ToXyz, WhitePoint, ColorError};
+
+    fn assert_xyz_eq(a: Xyz<f64>, b: Xyz<f64>) {
+        assert!((a.x - b.x).abs() < 1e-4 && (a.y - b.y).abs() < 1e-4 && (a.z - b.z).abs() < 1e-4,
+                "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn test_rgb_to_xyz() {
+        assert_xyz_eq(Rgb::<f64>::new(1.0, 1.0, 1.0).to_xyz(), WhitePoint::D65.to_xyz());
+        assert_xyz_eq(Rgb::<f64>::new(0.0, 0.0, 0.0).to_xyz(), Xyz::new(0.0, 0.0, 0.0));
+        assert_xyz_eq(Rgb::<f64>::new(1.0, 0.0, 0.0).to_xyz(), Xyz::new(0.4124, 0.2126, 0.0193));
+        assert_xyz_eq(Rgb::<f64>::new(0.0, 1.0, 0.0).to_xyz(), Xyz::new(0.3576, 0.7152, 0.1192));
+        assert_xyz_eq(Rgb::<f64>::new(0.0, 0.0, 1.0).to_xyz(), Xyz::new(0.1804, 0.0722, 0.9503));
+    }
+
+    #[test]
+    fn test_white_point() {
+        let d50 = Xyz::<f64>::from_rgb_with_white(&Rgb::<f64>::new(1.0, 1.0, 1.0), WhitePoint::D50).unwrap();
+        assert_xyz_eq(d50, WhitePoint::D50.to_xyz());
+        assert_eq!(d50.to_rgb_with_white::<f32>(WhitePoint::D50), Ok(Rgb::<f32>::new(1.0, 1.0, 1.0)));
+
+        // degenerate whites are errors rather than NaN
+        let white = Rgb::<f64>::new(1.0, 1.0, 1.0);
+        assert_eq!(WhitePoint::new(0.95, 1.0, 1.09).map(|w| w.y), Ok(1.0));
+        assert_eq!(WhitePoint::new(0.95, 0.0, 1.09), Err(ColorError::Degenerate));
+        assert_eq!(WhitePoint::new(f64::NAN, 1.0, 1.09), Err(ColorError::Degenerate));
+        let zero = WhitePoint { x: 0.0, y: 0.0, z: 0.0 };
+        assert_eq!(Xyz::<f64>::from_rgb_with_white(&white, zero), Err(ColorError::Degenerate));
+        assert_eq!(d50.to_rgb_with_white::<f64>(zero), Err(ColorError::Degenerate));
+    }
+
+    #[test]
+    fn test_xyz_to_rgb() {
+        let white = WhitePoint::D65.to_xyz::<f64>().to_rgb::<f64>();
+        let red = Xyz::<f64>::new(0.4124564, 0.2126729, 0.0193339).to_rgb::<f64>();
+        assert_xyz_eq(Xyz::new(white.r, white.g, white.b), Xyz::new(1.0, 1.0, 1.0));
+        assert_xyz_eq(Xyz::new(red.r, red.g, red.b), Xyz::new(1.0, 0.0, 0.0));
+    }
+}
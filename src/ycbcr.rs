@@ -14,13 +14,181 @@
 // limitations under the License.
 
 //! http://en.wikipedia.org/wiki/YCbCr
+//!
+//! Components are stored as normalized channel values following the 8-bit
+//! code conventions: neutral chroma is `128 / 255`, and the limited (studio)
+//! range holds the usual `16..235` and `16..240` codes. Conversions work on
+//! gamma-encoded R'G'B', as video does.
 
+use {Channel, Rgb, ToRgb, Srgb};
+
+/// The luma coefficients of a YCbCr encoding.
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum YCbCrStandard {
+    /// ITU-R BT.601, used by SD video and JPEG.
+    Bt601,
+    /// ITU-R BT.709, used by HD video.
+    Bt709,
+}
+
+impl YCbCrStandard {
+    /// The `(Kr, Kb)` weights of red and blue in luma.
+    #[inline]
+    pub fn coefficients(self) -> (f64, f64) {
+        match self {
+            YCbCrStandard::Bt601 => (0.299, 0.114),
+            YCbCrStandard::Bt709 => (0.2126, 0.0722),
+        }
+    }
+}
+
+/// The range of codes used by a YCbCr encoding.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum YCbCrRange {
+    /// The whole channel range, as used by JPEG.
+    Full,
+    /// Studio swing: luma from 16 to 235 and chroma from 16 to 240, in
+    /// 8-bit terms.
+    Limited,
+}
+
+impl YCbCrRange {
+    /// Maps luma in `[0, 1]` and chroma in `[-0.5, 0.5]` to normalized codes.
+    #[inline]
+    fn encode(self, y: f64, cb: f64, cr: f64) -> [f64; 3] {
+        match self {
+            YCbCrRange::Full => [y, (128.0 + 255.0 * cb) / 255.0, (128.0 + 255.0 * cr) / 255.0],
+            YCbCrRange::Limited => [(16.0 + 219.0 * y) / 255.0,
+                                    (128.0 + 224.0 * cb) / 255.0,
+                                    (128.0 + 224.0 * cr) / 255.0],
+        }
+    }
+
+    #[inline]
+    fn decode(self, v: [f64; 3]) -> (f64, f64, f64) {
+        match self {
+            YCbCrRange::Full => (v[0], (v[1] * 255.0 - 128.0) / 255.0, (v[2] * 255.0 - 128.0) / 255.0),
+            YCbCrRange::Limited => ((v[0] * 255.0 - 16.0) / 219.0,
+                                    (v[1] * 255.0 - 128.0) / 224.0,
+                                    (v[2] * 255.0 - 128.0) / 224.0),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[repr(C)]
 pub struct YCbCr<T> { pub y: T, pub cb: T, pub cr: T }
 
 impl<T> YCbCr<T> {
     #[inline]
-    pub fn new(y: T, cb: T, cr: T) -> YCbCr<T> {
-        YCbCr { y: y, cb: cb, cr: cr }
+    pub const fn new(y: T, cb: T, cr: T) -> YCbCr<T> {
+        YCbCr { y, cb, cr }
+    }
+}
+
+impl<T: Channel> YCbCr<T> {
+    /// Encodes a color with the given standard and range.
+    pub fn from_rgb_with<C: ToRgb>(color: &C, standard: YCbCrStandard, range: YCbCrRange) -> YCbCr<T> {
+        let rgb = color.to_rgb::<f64>();
+        let (kr, kb) = standard.coefficients();
+        let y = kr * rgb.r + (1.0 - kr - kb) * rgb.g + kb * rgb.b;
+        let cb = (rgb.b - y) / (2.0 * (1.0 - kb));
+        let cr = (rgb.r - y) / (2.0 * (1.0 - kr));
+        let v = range.encode(y, cb, cr);
+        YCbCr::new(Channel::from(v[0]), Channel::from(v[1]), Channel::from(v[2]))
+    }
+
+    /// Decodes the color with the given standard and range. Values outside
+    /// the RGB cube are not clamped.
+    pub fn to_rgb_with<U: Channel>(&self, standard: YCbCrStandard, range: YCbCrRange) -> Rgb<U> {
+        let (kr, kb) = standard.coefficients();
+        let (y, cb, cr) = range.decode([self.y.to_channel_f64(), self.cb.to_channel_f64(), self.cr.to_channel_f64()]);
+        let r = y + 2.0 * (1.0 - kr) * cr;
+        let b = y + 2.0 * (1.0 - kb) * cb;
+        let g = (y - kr * r - kb * b) / (1.0 - kr - kb);
+        Rgb::new(Channel::from(r), Channel::from(g), Channel::from(b))
+    }
+}
+
+/// Conversion to YCbCr, using full-range BT.601 as JPEG does. Use
+/// `YCbCr::from_rgb_with` for other encodings.
+pub trait ToYCbCr {
+    fn to_ycbcr<U:Channel>(&self) -> YCbCr<U>;
+}
+
+impl<T:Channel> ToYCbCr for YCbCr<T> {
+    #[inline]
+    fn to_ycbcr<U:Channel>(&self) -> YCbCr<U> {
+        YCbCr::new(self.y.to_channel(), self.cb.to_channel(), self.cr.to_channel())
+    }
+}
+
+impl<T:Channel> ToYCbCr for Rgb<T> {
+    #[inline]
+    fn to_ycbcr<U:Channel>(&self) -> YCbCr<U> {
+        YCbCr::from_rgb_with(self, YCbCrStandard::Bt601, YCbCrRange::Full)
+    }
+}
+
+impl<T:Channel> ToYCbCr for Srgb<T> {
+    /// Encodes the gamma-encoded components as they are.
+    #[inline]
+    fn to_ycbcr<U:Channel>(&self) -> YCbCr<U> {
+        Rgb::<T>::new(self.r, self.g, self.b).to_ycbcr()
+    }
+}
+
+impl<T:Channel> ToRgb for YCbCr<T> {
+    /// Decodes full-range BT.601.
+    #[inline]
+    fn to_rgb<U:Channel>(&self) -> Rgb<U> {
+        self.to_rgb_with(YCbCrStandard::Bt601, YCbCrRange::Full)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {Rgb, ToRgb, YCbCr, ToYCbCr};
+    use ycbcr::{YCbCrStandard, YCbCrRange};
+
+    fn assert_close(a: YCbCr<f64>, b: (f64, f64, f64)) {
+        assert!((a.y * 255.0 - b.0).abs() < 0.01 && (a.cb * 255.0 - b.1).abs() < 0.01 && (a.cr * 255.0 - b.2).abs() < 0.01,
+                "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn test_jpeg() {
+        assert_close(Rgb::<u8>::new(0xFF, 0xFF, 0xFF).to_ycbcr(), (255.0, 128.0, 128.0));
+        assert_close(Rgb::<u8>::new(0x00, 0x00, 0x00).to_ycbcr(), (0.0, 128.0, 128.0));
+        assert_close(Rgb::<u8>::new(0xFF, 0x00, 0x00).to_ycbcr(), (76.245, 84.972, 255.5));
+        assert_close(Rgb::<u8>::new(0x00, 0x00, 0xFF).to_ycbcr(), (29.07, 255.5, 107.2644));
+        assert_eq!(YCbCr::<u8>::new(0xFF, 0x80, 0x80).to_rgb::<f32>(), Rgb::new(1.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn test_limited_range() {
+        let white = Rgb::<f64>::new(1.0, 1.0, 1.0);
+        let black = Rgb::<f64>::new(0.0, 0.0, 0.0);
+        for &standard in [YCbCrStandard::Bt601, YCbCrStandard::Bt709].iter() {
+            assert_close(YCbCr::from_rgb_with(&white, standard, YCbCrRange::Limited), (235.0, 128.0, 128.0));
+            assert_close(YCbCr::from_rgb_with(&black, standard, YCbCrRange::Limited), (16.0, 128.0, 128.0));
+        }
+        let red = Rgb::<f64>::new(1.0, 0.0, 0.0);
+        assert_close(YCbCr::from_rgb_with(&red, YCbCrStandard::Bt709, YCbCrRange::Limited), (62.5594, 102.3358, 240.0));
+        assert_close(YCbCr::from_rgb_with(&red, YCbCrStandard::Bt601, YCbCrRange::Limited), (81.481, 90.2032, 240.0));
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let c = Rgb::<f64>::new(0.2, 0.6, 0.9);
+        for &standard in [YCbCrStandard::Bt601, YCbCrStandard::Bt709].iter() {
+            for &range in [YCbCrRange::Full, YCbCrRange::Limited].iter() {
+                let rgb = YCbCr::<f64>::from_rgb_with(&c, standard, range).to_rgb_with::<f64>(standard, range);
+                assert!((rgb.r - c.r).abs() < 1e-9 && (rgb.g - c.g).abs() < 1e-9 && (rgb.b - c.b).abs() < 1e-9);
+            }
+        }
+        let limited = YCbCr::<u8>::new(235, 128, 128);
+        assert_eq!(limited.to_rgb_with::<u8>(YCbCrStandard::Bt709, YCbCrRange::Limited), Rgb::new(0xFF, 0xFF, 0xFF));
     }
 }